@@ -25,6 +25,11 @@ export interface BaseLeafData<TargetAddressType = unknown, CallData = unknown> {
 export interface SigningOptions {
     seed: string | Uint8Array;
     expiry: number | string | BigNumber;
+    /**
+     * Caps how many leaves the root may execute once verified (Solana only). When set and
+     * non-zero, `SignLimitedMerkleRoot` is signed instead of `SignMerkleRoot`.
+     */
+    maxExecutions?: number | string | BigNumber;
 }
 
 // We can use any here as it will be overridden by implementation
@@ -278,10 +283,35 @@ export const getOneSigTypedDataPrimaryTypes = (): Record<string, TypedDataField[
     return ONE_SIG_TYPED_DATA_PRIMARY_TYPES;
 };
 
+const ONE_SIG_LIMITED_TYPED_DATA_PRIMARY_TYPES: Record<string, TypedDataField[]> = {
+    SignLimitedMerkleRoot: [
+        { name: 'seed', type: 'bytes32' },
+        { name: 'merkleRoot', type: 'bytes32' },
+        { name: 'expiry', type: 'uint256' },
+        { name: 'maxExecutions', type: 'uint256' },
+    ],
+};
+
+export const getOneSigLimitedTypedDataPrimaryTypes = (): Record<string, TypedDataField[]> => {
+    return ONE_SIG_LIMITED_TYPED_DATA_PRIMARY_TYPES;
+};
+
 export const getSigningData = (
     tree: MerkleTree,
-    { seed, expiry }: SigningOptions,
+    { seed, expiry, maxExecutions }: SigningOptions,
 ): Parameters<TypedDataSigner['_signTypedData']> => {
+    if (maxExecutions !== undefined && !ethers.BigNumber.from(maxExecutions).isZero()) {
+        return [
+            getOneSigTypedDataDomain(),
+            getOneSigLimitedTypedDataPrimaryTypes(),
+            {
+                seed: seed,
+                expiry: expiry,
+                merkleRoot: tree.getHexRoot(),
+                maxExecutions: maxExecutions,
+            },
+        ];
+    }
     return [
         getOneSigTypedDataDomain(),
         getOneSigTypedDataPrimaryTypes(),
@@ -188,3 +188,32 @@ test('Signature Tests', async function () {
         }),
     ).toEqual('CANNOT_CONCAT_INPUT');
 });
+
+test('Limited merkle root signing', async function () {
+    const tree = makeOneSigTree([testLeafGen(getTestLeafs())]);
+    const signingOptions: SigningOptions = {
+        expiry: Math.floor(Date.now() / 1000) + 5 * 1000,
+        seed: `0x${testHelperRandomBytes().toString('hex')}`,
+    };
+    const unlimitedDigest = getDigestToSign(tree, signingOptions);
+
+    // A zero cap keeps the EVM-compatible `SignMerkleRoot` digest
+    expect(getDigestToSign(tree, { ...signingOptions, maxExecutions: 0 })).toEqual(unlimitedDigest);
+
+    const limitedDigest = getDigestToSign(tree, { ...signingOptions, maxExecutions: 2 });
+    expect(limitedDigest).not.toEqual(unlimitedDigest);
+    expect(limitedDigest).not.toEqual(
+        getDigestToSign(tree, { ...signingOptions, maxExecutions: 3 }),
+    );
+
+    const signer = ethers.Wallet.createRandom();
+    const signature = await signOneSigTree(
+        tree,
+        [signer],
+        { ...signingOptions, maxExecutions: 2 },
+        'signature',
+    );
+    expect(ethers.utils.recoverAddress(limitedDigest, signature.toHexString())).toEqual(
+        signer.address,
+    );
+});
@@ -40,9 +40,8 @@ describe('OneSig', () => {
     let executor1: SignerWithAddress;
     let executor2: SignerWithAddress;
 
-    // We use Wallet instead of SignerWithAddress because we need access to the private/public keypair
-    // This allows us to get the public key which is needed for Solana unit tests to verify
-    // merkle proofs and transaction proofs correctly
+    // We use Wallet instead of SignerWithAddress because we need access to the private/public keypair.
+    // Fixed keys shared with the Solana unit tests live in the onesig-test-vectors crate instead.
     let sortedWallets: Wallet[];
     const threshold = 2;
     const SEED = ethers.utils.keccak256(ethers.utils.randomBytes(32));
@@ -0,0 +1,79 @@
+import { expect } from 'chai';
+import type { Contract } from 'ethers';
+import { readFileSync } from 'fs';
+import { ethers } from 'hardhat';
+import { join } from 'path';
+
+// Vectors generated by the `onesig-test-vectors` crate of onesig-solana, which the Solana unit tests
+// verify as well. Regenerate them there rather than editing the file.
+const VECTORS_PATH = join(__dirname, '../../../onesig-solana/crates/onesig-test-vectors/vectors.json');
+
+interface SignedDigestVector {
+    digest: string;
+    signatures: string;
+}
+
+interface TestVectors {
+    signers: { privateKey: string; publicKey: string; address: string }[];
+    threshold: number;
+    seed: string;
+    expiry: number;
+    leaves: string[];
+    proofs: { proof: string[] }[];
+    merkleRoot: string;
+    merkleRootSignatures: SignedDigestVector;
+}
+
+const SIGNATURE_LENGTH = 65;
+
+describe('OneSig test vectors', () => {
+    const vectors: TestVectors = JSON.parse(readFileSync(VECTORS_PATH, 'utf8'));
+
+    async function setupOneSig(): Promise<Contract> {
+        const [executor] = await ethers.getSigners();
+        const OneSigFactory = await ethers.getContractFactory('OneSig');
+        const oneSig = await OneSigFactory.deploy(
+            1,
+            vectors.signers.map((s) => s.address),
+            vectors.threshold,
+            [executor.address],
+            true,
+            vectors.seed,
+        );
+        await oneSig.deployed();
+        return oneSig;
+    }
+
+    it('derives the signer addresses from their keys', () => {
+        for (const signer of vectors.signers) {
+            const address = ethers.utils.computeAddress('0x04' + signer.publicKey.slice(2));
+            expect(address.toLowerCase()).to.eq(signer.address.toLowerCase());
+            expect(new ethers.Wallet(signer.privateKey).address).to.eq(address);
+        }
+    });
+
+    it('proves every leaf against the merkle root', () => {
+        vectors.leaves.forEach((leaf, i) => {
+            const root = vectors.proofs[i].proof.reduce((node, sibling) => {
+                const [a, b] = node.toLowerCase() < sibling.toLowerCase() ? [node, sibling] : [sibling, node];
+                return ethers.utils.keccak256(ethers.utils.concat([a, b]));
+            }, leaf);
+            expect(root).to.eq(vectors.merkleRoot);
+        });
+    });
+
+    it('recovers the signers from the merkle root signatures', () => {
+        const { digest, signatures } = vectors.merkleRootSignatures;
+        const bytes = ethers.utils.arrayify(signatures);
+        vectors.signers.forEach((signer, i) => {
+            const signature = bytes.slice(i * SIGNATURE_LENGTH, (i + 1) * SIGNATURE_LENGTH);
+            const recovered = ethers.utils.recoverAddress(digest, signature);
+            expect(recovered.toLowerCase()).to.eq(signer.address.toLowerCase());
+        });
+    });
+
+    it('verifies the merkle root signatures on chain', async () => {
+        const oneSig = await setupOneSig();
+        await oneSig.verifyMerkleRoot(vectors.merkleRoot, vectors.expiry, vectors.merkleRootSignatures.signatures);
+    });
+});
@@ -0,0 +1,110 @@
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+
+/// Command line arguments: positionals, in order, and `--name value`, `--name=value` or `--flag`
+/// options, which commands take out as they read them so that leftovers can be rejected.
+#[derive(Debug, Default)]
+pub struct Args {
+    positionals: VecDeque<String>,
+    /// Each option as given, with its value unless it is a flag
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positionals.push_back(arg);
+                continue;
+            };
+            let option = match name.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => {
+                    let value = args.next_if(|value| !value.starts_with("--"));
+                    (name.to_string(), value)
+                },
+            };
+            parsed.options.push(option);
+        }
+        parsed
+    }
+
+    /// Takes the next positional argument.
+    pub fn positional(&mut self) -> Option<String> {
+        self.positionals.pop_front()
+    }
+
+    pub fn required_positional(&mut self, name: &str) -> Result<String> {
+        self.positional().ok_or_else(|| anyhow!("missing <{name}>"))
+    }
+
+    /// Takes every value of a repeatable option.
+    pub fn values(&mut self, name: &str) -> Result<Vec<String>> {
+        let (taken, options) = std::mem::take(&mut self.options)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(option, _)| option == name);
+        self.options = options;
+        taken
+            .into_iter()
+            .map(|(_, value)| value.ok_or_else(|| anyhow!("--{name} takes a value")))
+            .collect()
+    }
+
+    /// Takes an option given at most once.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>> {
+        let mut values = self.values(name)?;
+        if values.len() > 1 {
+            bail!("--{name} is given more than once");
+        }
+        Ok(values.pop())
+    }
+
+    pub fn required(&mut self, name: &str) -> Result<String> {
+        self.value(name)?.ok_or_else(|| anyhow!("missing --{name}"))
+    }
+
+    /// Takes an option given at most once, parsed.
+    pub fn parsed<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.value(name)?
+            .map(|value| {
+                value.parse().map_err(|error| anyhow!("invalid --{name} {value}: {error}"))
+            })
+            .transpose()
+    }
+
+    pub fn required_parsed<T>(&mut self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.parsed(name)?.ok_or_else(|| anyhow!("missing --{name}"))
+    }
+
+    /// Takes a flag, which is set if given.
+    pub fn flag(&mut self, name: &str) -> Result<bool> {
+        let count = self.options.iter().filter(|(option, _)| option == name).count();
+        if let Some((_, Some(value))) = self.options.iter().find(|(option, _)| option == name) {
+            bail!("--{name} takes no value, got {value}");
+        }
+        self.options.retain(|(option, _)| option != name);
+        Ok(count > 0)
+    }
+
+    /// Fails on the arguments no command took.
+    pub fn finish(self) -> Result<()> {
+        if let Some((name, _)) = self.options.first() {
+            bail!("unexpected option --{name}");
+        }
+        if let Some(positional) = self.positionals.front() {
+            bail!("unexpected argument {positional}");
+        }
+        Ok(())
+    }
+}
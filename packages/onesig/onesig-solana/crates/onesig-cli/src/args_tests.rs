@@ -0,0 +1,53 @@
+// Tests for the command line parser.
+#[cfg(test)]
+mod tests {
+    use crate::args::Args;
+
+    fn args(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_options_and_positionals() {
+        let mut args = args(&[
+            "set-config",
+            "add-signer",
+            "0x01",
+            "--state",
+            "abc",
+            "--signer=0x02",
+            "--signer",
+            "0x03",
+            "--executor-required",
+            "--threshold",
+            "2",
+        ]);
+        assert_eq!(args.positional().as_deref(), Some("set-config"));
+        assert_eq!(args.required("state").unwrap(), "abc");
+        assert_eq!(args.values("signer").unwrap(), ["0x02", "0x03"]);
+        assert!(args.flag("executor-required").unwrap());
+        assert!(!args.flag("help").unwrap());
+        assert_eq!(args.required_parsed::<u8>("threshold").unwrap(), 2);
+        assert_eq!(args.positional().as_deref(), Some("add-signer"));
+        assert_eq!(args.positional().as_deref(), Some("0x01"));
+        args.finish().unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut parsed = args(&["--state", "a", "--state", "b"]);
+        assert!(parsed.value("state").is_err());
+
+        let mut parsed = args(&["--threshold", "x", "--state"]);
+        assert!(parsed.parsed::<u8>("threshold").is_err());
+        assert!(parsed.value("state").is_err());
+        assert!(parsed.required("out").is_err());
+
+        let mut parsed = args(&["--help=yes"]);
+        assert!(parsed.flag("help").is_err());
+
+        // Whatever no command took is rejected
+        assert!(args(&["--unknown", "1"]).finish().is_err());
+        assert!(args(&["extra"]).finish().is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Result};
+use onesig::{Hash, MerkleRootRegistry, MerkleRootState};
+
+use crate::{args::Args, context::Context, parse};
+
+/// `close-root --state <pubkey> (--root <hex> | --all-expired)`: closes a `MerkleRootState` that
+/// expired or was signed with a former seed, refunding its rent to whoever verified it. With
+/// `--all-expired` every such root of the registry is closed.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    let root = args.value("root")?.map(|root| parse::hash(&root)).transpose()?;
+    let all_expired = args.flag("all-expired")?;
+    args.finish()?;
+
+    let seed = context.one_sig_account(&state)?.header.seed;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs().try_into()?;
+    let client = context.client(state);
+    let roots = match (root, all_expired) {
+        (Some(root), false) => {
+            let address = client.pda.merkle_root_state(&root).0;
+            let root = context
+                .anchor_account::<MerkleRootState>(&address)?
+                .ok_or_else(|| anyhow!("no MerkleRootState at {address}"))?;
+            if !closeable(&root, &seed, now) {
+                bail!("the root expires at {} and can only be closed after", root.expiry);
+            }
+            vec![root]
+        },
+        (None, true) => {
+            let registry = client.pda.merkle_root_registry().0;
+            let registry = context
+                .anchor_account::<MerkleRootRegistry>(&registry)?
+                .ok_or_else(|| anyhow!("no merkle root registry at {registry}"))?;
+            let mut roots = vec![];
+            for entry in registry.entries {
+                if let Some(root) = context.anchor_account::<MerkleRootState>(&entry.address)? {
+                    roots.push(root);
+                }
+            }
+            roots.retain(|root| closeable(root, &seed, now));
+            roots
+        },
+        _ => bail!("give either --root or --all-expired"),
+    };
+    if roots.is_empty() {
+        println!("no root to close");
+        return Ok(());
+    }
+
+    let payer = context.payer()?;
+    for root in roots {
+        let ix = client.close_merkle_root(&root.rent_payer, &root.merkle_root);
+        let confirmed = context.send(&payer, &[ix], &[])?;
+        println!(
+            "closed 0x{} in {}, rent refunded to {}",
+            hex::encode(root.merkle_root.0),
+            confirmed.signature,
+            root.rent_payer
+        );
+    }
+    Ok(())
+}
+
+/// Whether `close_merkle_root` accepts `root` at `now` for a OneSig whose seed is `seed`: once
+/// expired, or as soon as the seed rotated, since its leaves can never execute again.
+pub fn closeable(root: &MerkleRootState, seed: &Hash, now: i64) -> bool {
+    root.expiry < now || !root.verified_under(seed)
+}
@@ -0,0 +1,33 @@
+// Tests for telling which merkle roots `close_merkle_root` accepts.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{Hash, MerkleRootState, MERKLE_ROOT_STATE_VERSION};
+
+    use crate::commands::close_root::closeable;
+
+    fn root(seed: Hash, expiry: i64) -> MerkleRootState {
+        MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            seed_commitment: MerkleRootState::seed_commitment(&seed),
+            expiry,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions: None,
+        }
+    }
+
+    #[test]
+    fn test_closeable() {
+        let seed = Hash([2; 32]);
+        assert!(!closeable(&root(seed, 100), &seed, 99));
+        // The program closes roots strictly past their expiry
+        assert!(!closeable(&root(seed, 100), &seed, 100));
+        assert!(closeable(&root(seed, 100), &seed, 101));
+        // A root of a former seed is dead before it expires
+        assert!(closeable(&root(Hash([3; 32]), 100), &seed, 0));
+    }
+}
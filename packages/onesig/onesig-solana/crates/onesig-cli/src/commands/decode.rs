@@ -0,0 +1,134 @@
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Result};
+use onesig::{ExecutorState, MerkleRootRegistry, MerkleRootState, OneSigState};
+use onesig_sdk::{checksum_address, OneSigAccount, OneSigPda};
+
+use crate::{args::Args, commands::show, context::Context, parse};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// `decode <pubkey>`: fetches an account of the program and prints it, whichever of
+/// `OneSigState`, `MerkleRootState`, `MerkleRootRegistry` and `ExecutorState` it is.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let address = parse::pubkey(&args.required_positional("pubkey")?)?;
+    args.finish()?;
+
+    let data = context
+        .rpc()
+        .account_data(&address)?
+        .ok_or_else(|| anyhow!("no account at {address}"))?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs().try_into()?;
+    print!("{}", decode(context.program_id, address, &data, now)?);
+    Ok(())
+}
+
+/// Describes the account at `address` from its `data`, telling expiries relative to `now`.
+pub fn decode(program_id: Pubkey, address: Pubkey, data: &[u8], now: i64) -> Result<String> {
+    let mut out = String::new();
+    let mut line = |name: &str, value: String| writeln!(out, "{name:<22}{value}").unwrap();
+    match data.get(..8).unwrap_or(data) {
+        discriminator if discriminator == OneSigState::DISCRIMINATOR => {
+            let account = OneSigAccount::from_account_data(address, data)?;
+            line("account", "OneSigState".into());
+            return Ok(out + &show::describe(&account, &OneSigPda::new(program_id, address)));
+        },
+        discriminator if discriminator == MerkleRootState::DISCRIMINATOR => {
+            let root: MerkleRootState = deserialize(&address, data)?;
+            line("account", "MerkleRootState".into());
+            line("address", address.to_string());
+            line("merkle_root", format!("0x{}", hex::encode(root.merkle_root.0)));
+            line("seed_commitment", format!("0x{}", hex::encode(root.seed_commitment)));
+            line("expiry", expiry(root.expiry, now));
+            let remaining = root.remaining_executions.map(|remaining| remaining.to_string());
+            line("remaining_executions", remaining.unwrap_or_else(|| "unlimited".into()));
+            line("executions", root.executions.to_string());
+            line("rent_payer", root.rent_payer.to_string());
+            for (index, signer) in root.signed_by.iter().enumerate() {
+                line(if index == 0 { "signed_by" } else { "" }, checksum_address(signer));
+            }
+            line("version", root.version.to_string());
+        },
+        discriminator if discriminator == MerkleRootRegistry::DISCRIMINATOR => {
+            let registry: MerkleRootRegistry = deserialize(&address, data)?;
+            line("account", "MerkleRootRegistry".into());
+            line("address", address.to_string());
+            line("open_merkle_roots", registry.entries.len().to_string());
+            for entry in &registry.entries {
+                line("", format!("{} {}", entry.address, expiry(entry.expiry, now)));
+            }
+        },
+        discriminator if discriminator == ExecutorState::DISCRIMINATOR => {
+            let executor: ExecutorState = deserialize(&address, data)?;
+            line("account", "ExecutorState".into());
+            line("address", address.to_string());
+            line("executor", executor.executor.to_string());
+            line("rent_payer", executor.rent_payer.to_string());
+            if executor.target_programs.is_empty() {
+                line("target_programs", "any".into());
+            }
+            for (index, program) in executor.target_programs.iter().enumerate() {
+                line(if index == 0 { "target_programs" } else { "" }, program.to_string());
+            }
+        },
+        _ => bail!("{address} is not an account of the OneSig program"),
+    }
+    Ok(out)
+}
+
+fn deserialize<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|error| anyhow!("cannot decode {address}: {error}"))
+}
+
+/// `timestamp` in UTC, followed by how long ago it expired or how long until it does
+pub fn expiry(timestamp: i64, now: i64) -> String {
+    let relative = match now - timestamp {
+        elapsed if elapsed > 0 => format!("expired {} ago", duration(elapsed)),
+        elapsed => format!("in {}", duration(-elapsed)),
+    };
+    format!("{} ({relative})", utc(timestamp))
+}
+
+/// A unix timestamp as `YYYY-MM-DD hh:mm:ss UTC`
+pub fn utc(timestamp: i64) -> String {
+    let (days, seconds) =
+        (timestamp.div_euclid(SECONDS_PER_DAY), timestamp.rem_euclid(SECONDS_PER_DAY));
+    // Civil date of a day count since 1970-01-01, in eras of 400 years starting on March 1st
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// The two largest units of `seconds`, e.g. `2d 3h`
+fn duration(seconds: i64) -> String {
+    let units = [
+        (seconds / SECONDS_PER_DAY, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let first = units.iter().position(|(value, _)| *value > 0).unwrap_or(units.len() - 1);
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,96 @@
+// Tests for decoding the accounts of the program.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, AccountSerialize, Discriminator};
+    use bytemuck::Zeroable;
+    use onesig::{
+        Address, ExecutorState, Hash, MerkleRootEntry, MerkleRootRegistry, MerkleRootState,
+        OneSigState, ONE_SIG_STATE_VERSION,
+    };
+    use onesig_sdk::checksum_address;
+
+    use crate::commands::decode::{decode, expiry, utc};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn serialize(account: &impl AccountSerialize) -> Vec<u8> {
+        let mut data = vec![];
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn line(name: &str, value: impl ToString) -> String {
+        format!("{name:<22}{}", value.to_string())
+    }
+
+    #[test]
+    fn test_utc() {
+        assert_eq!(utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(utc(NOW), "2023-11-14 22:13:20 UTC");
+        assert_eq!(utc(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(utc(-1), "1969-12-31 23:59:59 UTC");
+        assert_eq!(expiry(NOW, NOW - 90_000), "2023-11-14 22:13:20 UTC (in 1d 1h)");
+        assert_eq!(expiry(NOW, NOW + 61), "2023-11-14 22:13:20 UTC (expired 1m 1s ago)");
+        assert_eq!(expiry(NOW, NOW), "2023-11-14 22:13:20 UTC (in 0s)");
+    }
+
+    #[test]
+    fn test_decode_merkle_root_state() {
+        let address = Pubkey::new_unique();
+        let signer = Address([0xaa; 20]);
+        let root = MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            seed_commitment: [2; 16],
+            expiry: NOW,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![signer],
+            bump: 255,
+            version: 1,
+            executions: 5,
+            remaining_executions: Some(3),
+        };
+        let out = decode(onesig::ID, address, &serialize(&root), NOW + 10).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], line("account", "MerkleRootState"));
+        assert_eq!(lines[2], line("merkle_root", format!("0x{}", "01".repeat(32))));
+        assert_eq!(lines[3], line("seed_commitment", format!("0x{}", "02".repeat(16))));
+        assert_eq!(lines[4], line("expiry", "2023-11-14 22:13:20 UTC (expired 10s ago)"));
+        assert_eq!(lines[5], line("remaining_executions", 3));
+        assert_eq!(lines[6], line("executions", 5));
+        assert_eq!(lines[8], line("signed_by", checksum_address(&signer)));
+    }
+
+    #[test]
+    fn test_decode() {
+        let address = Pubkey::new_unique();
+        let registry = MerkleRootRegistry {
+            entries: vec![MerkleRootEntry { address, expiry: NOW + 60 }],
+            bump: 255,
+        };
+        let out = decode(onesig::ID, address, &serialize(&registry), NOW).unwrap();
+        assert!(out.ends_with(&line("", format!("{address} {}\n", expiry(NOW + 60, NOW)))));
+
+        let executor = ExecutorState {
+            executor: Pubkey::new_unique(),
+            rent_payer: Pubkey::new_unique(),
+            bump: 255,
+            target_programs: vec![],
+        };
+        let out = decode(onesig::ID, address, &serialize(&executor), NOW).unwrap();
+        assert!(out.ends_with(&format!("{}\n", line("target_programs", "any"))));
+
+        let mut header = OneSigState::zeroed();
+        header.version = ONE_SIG_STATE_VERSION;
+        let mut data = OneSigState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&header));
+        let out = decode(onesig::ID, address, &data, NOW).unwrap();
+        assert!(out.starts_with(&format!(
+            "{}\n{}",
+            line("account", "OneSigState"),
+            line("state", address)
+        )));
+
+        assert!(decode(onesig::ID, address, &[0; 16], NOW).is_err());
+        assert!(decode(onesig::ID, address, &[], NOW).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use onesig::{TargetPolicy, VerifyMerkleRootParams};
+use onesig_sdk::{
+    Confirmed, ExecuteTransactionOptions, Leaf, OneSigAccount, ProposalBundle, SolanaLeaf,
+    TransactionSigner,
+};
+
+use crate::{
+    args::Args,
+    commands::{show, verify_root},
+    context::Context,
+};
+
+/// `execute --state <pubkey> --bundle <path> [--all]`: executes the leaf of a proposal bundle at
+/// the current nonce of the OneSig, and with `--all` the following ones until the bundle has no
+/// leaf left for it.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    let bundle = ProposalBundle::load(args.required("bundle")?)?;
+    let all = args.flag("all")?;
+    args.finish()?;
+
+    let mut executed = 0;
+    loop {
+        let account = context.one_sig_account(&state)?;
+        let Some((index, leaf)) = pending_leaf(&bundle, &state, account.header.nonce) else {
+            break;
+        };
+        let confirmed = execute_leaf(context, &account, &bundle, index, leaf)?;
+        println!("executed leaf {index} at nonce {} in {}", leaf.nonce, confirmed.signature);
+        executed += 1;
+        if !all {
+            break;
+        }
+    }
+    if executed == 0 {
+        let nonce = context.one_sig_account(&state)?.header.nonce;
+        bail!("the bundle has no leaf of {state} at nonce {nonce}");
+    }
+    println!();
+    show::print(context, &state)
+}
+
+/// The leaf of `state` at `nonce`, with its index in the bundle
+pub fn pending_leaf<'a>(
+    bundle: &'a ProposalBundle,
+    state: &Pubkey,
+    nonce: u64,
+) -> Option<(usize, &'a SolanaLeaf)> {
+    bundle.leaves.iter().enumerate().find_map(|(index, leaf)| match leaf {
+        Leaf::Solana(leaf) if leaf.one_sig_state == *state && leaf.nonce == nonce =>
+            Some((index, leaf)),
+        _ => None,
+    })
+}
+
+/// Submits `execute_transaction` of leaf `index` of `bundle`. The root is verified inline with
+/// the signatures of the bundle, unless a `MerkleRootState` already records it.
+pub fn execute_leaf(
+    context: &Context,
+    account: &OneSigAccount,
+    bundle: &ProposalBundle,
+    index: usize,
+    leaf: &SolanaLeaf,
+) -> Result<Confirmed> {
+    let client = context.client(account.address);
+    let (verification, options) = execution(context, account, bundle)?;
+    let payer = context.payer()?;
+    let ix = client.execute_leaf(
+        &payer.pubkey(),
+        &bundle.merkle_root,
+        leaf,
+        bundle.proofs[index].proof.clone(),
+        verification,
+        options,
+    );
+    context.send(&payer, &[ix], &[])
+}
+
+/// Submits `record_leaf_failure` of leaf `index` of `bundle`, whose execution by
+/// [`execute_leaf`] failed in simulation with `error_code`, the custom error of the program the
+/// leaf calls.
+pub fn record_failure(
+    context: &Context,
+    account: &OneSigAccount,
+    bundle: &ProposalBundle,
+    index: usize,
+    leaf: &SolanaLeaf,
+    error_code: u32,
+) -> Result<Confirmed> {
+    let client = context.client(account.address);
+    let (verification, options) = execution(context, account, bundle)?;
+    let payer = context.payer()?;
+    let ix = client.record_leaf_failure(
+        &payer.pubkey(),
+        &bundle.merkle_root,
+        leaf,
+        bundle.proofs[index].proof.clone(),
+        verification,
+        options,
+        error_code,
+    );
+    context.send(&payer, &[ix], &[])
+}
+
+/// The root verification and the optional accounts a leaf of `bundle` is executed with
+fn execution(
+    context: &Context,
+    account: &OneSigAccount,
+    bundle: &ProposalBundle,
+) -> Result<(Option<VerifyMerkleRootParams>, ExecuteTransactionOptions)> {
+    let client = context.client(account.address);
+    let merkle_root_state = client.pda.merkle_root_state(&bundle.merkle_root).0;
+    let verification = match context.rpc().account_data(&merkle_root_state)? {
+        Some(_) => None,
+        None => Some(verify_root::params(account, bundle)?),
+    };
+    let options = ExecuteTransactionOptions {
+        as_executor: account.header.executor_required(),
+        with_target_programs: account.header.target_policy() != TargetPolicy::None,
+        fee_payer: None,
+    };
+    Ok((verification, options))
+}
@@ -0,0 +1,65 @@
+// Tests for picking the leaf of a bundle a OneSig executes next.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::Hash;
+    use onesig_sdk::{
+        OneSigInstruction, ProposalMetadata, SolanaInstance, SolanaLeafGuards, TreeCoordinator,
+    };
+
+    use crate::commands::execute::pending_leaf;
+
+    fn instance(state: Pubkey, one_sig_id: u64) -> SolanaInstance {
+        SolanaInstance {
+            chain_id: "solana:test".into(),
+            one_sig_state: state,
+            one_sig_id,
+            nonce: 5,
+            cluster_genesis_hash: None,
+        }
+    }
+
+    fn instruction(data: u8) -> OneSigInstruction {
+        OneSigInstruction {
+            program_id: Pubkey::new_from_array([9; 32]),
+            accounts: vec![],
+            data: vec![data],
+            value: 0,
+        }
+    }
+
+    #[test]
+    fn test_pending_leaf() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut coordinator = TreeCoordinator::new();
+        let instance_a = coordinator.add_solana_instance(instance(a, 1));
+        let instance_b = coordinator.add_solana_instance(instance(b, 2));
+        coordinator
+            .push_solana(instance_a, instruction(0), SolanaLeafGuards::default())
+            .unwrap();
+        coordinator
+            .push_solana(instance_b, instruction(1), SolanaLeafGuards::default())
+            .unwrap();
+        coordinator
+            .push_solana(instance_a, instruction(2), SolanaLeafGuards::default())
+            .unwrap();
+        let bundle = coordinator
+            .bundle(ProposalMetadata {
+                title: "Batch".into(),
+                description: String::new(),
+                seed: Hash([0; 32]),
+                expiry: 0,
+                max_executions: 0,
+            })
+            .unwrap();
+
+        let (index, leaf) = pending_leaf(&bundle, &a, 6).unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(leaf.instruction.data, [2]);
+        assert_eq!(pending_leaf(&bundle, &b, 5).unwrap().0, 1);
+        // Executed, or beyond the batch
+        assert!(pending_leaf(&bundle, &b, 6).is_none());
+        assert!(pending_leaf(&bundle, &a, 4).is_none());
+        assert!(pending_leaf(&bundle, &Pubkey::new_unique(), 5).is_none());
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use onesig::InitOneSigParams;
+use onesig_sdk::TransactionSigner;
+
+use crate::{args::Args, commands::show, context::Context, keypair::Keypair, parse};
+
+/// `init --state-keypair <path> --one-sig-id <id> --seed <hex> --threshold <n> --signer <address>..
+/// [--executor <pubkey>..] [--executor-required] [--signers-capacity <n>]`: creates a OneSig
+/// account at the address of the state keypair, which signs its creation.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = Keypair::read(args.required("state-keypair")?)?;
+    let signers: Vec<_> = args
+        .values("signer")?
+        .iter()
+        .map(|signer| parse::address(signer))
+        .collect::<Result<_>>()?;
+    let executors: Vec<_> = args
+        .values("executor")?
+        .iter()
+        .map(|executor| parse::pubkey(executor))
+        .collect::<Result<_>>()?;
+    let params = InitOneSigParams {
+        one_sig_id: args.required_parsed("one-sig-id")?,
+        seed: parse::hash(&args.required("seed")?)?,
+        threshold: args.required_parsed("threshold")?,
+        signers_capacity: args.parsed("signers-capacity")?.unwrap_or(signers.len() as u16),
+        executor_required: args.flag("executor-required")?,
+        signers,
+        executors,
+    };
+    args.finish()?;
+
+    let payer = context.payer()?;
+    let client = context.client(state.pubkey());
+    let instruction = client.init_one_sig(&payer.pubkey(), params);
+    let confirmed = context.send(&payer, &[instruction], &[&state])?;
+    println!("initialized {} in {}\n", client.state(), confirmed.signature);
+    show::print(context, &client.state())
+}
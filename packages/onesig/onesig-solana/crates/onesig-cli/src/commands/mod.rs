@@ -0,0 +1,31 @@
+pub mod close_root;
+pub mod decode;
+pub mod execute;
+pub mod init;
+pub mod plan;
+pub mod propose;
+pub mod set_config;
+pub mod show;
+pub mod sign;
+pub mod snapshot;
+pub mod verify_proof;
+pub mod verify_root;
+
+#[cfg(test)]
+mod close_root_tests;
+#[cfg(test)]
+mod decode_tests;
+#[cfg(test)]
+mod execute_tests;
+#[cfg(test)]
+mod plan_tests;
+#[cfg(test)]
+mod propose_tests;
+#[cfg(test)]
+mod set_config_tests;
+#[cfg(test)]
+mod show_tests;
+#[cfg(test)]
+mod snapshot_tests;
+#[cfg(test)]
+mod verify_proof_tests;
@@ -0,0 +1,273 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::{
+    prelude::{Pubkey, Rent},
+    solana_program::instruction::Instruction,
+};
+use anyhow::{bail, Result};
+use onesig::{
+    Address, ExecutorState, SelfCpiTarget, SetConfigParams, SetTargetProgramsParams,
+    SpendingLimits, TargetPrograms,
+};
+use onesig_sdk::{checksum_address, OneSigAccount, OneSigClient, TransactionSigner};
+use serde::Deserialize;
+
+use crate::{
+    args::Args,
+    commands::{
+        propose,
+        set_config::{self, ProposalOptions},
+        show,
+    },
+    context::Context,
+    parse,
+};
+
+/// The configuration `plan` brings a OneSig account to, as it reads it from JSON or TOML. The
+/// optional settings are left as they are when left out.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ConfigFile {
+    pub signers: Vec<String>,
+    pub threshold: u8,
+    pub executor_required: Option<bool>,
+    pub target_programs: Option<TargetProgramsConfig>,
+    pub executor_reimbursement: Option<u64>,
+    /// Executors that must be registered. Executors cannot be listed on chain, so registered ones
+    /// missing from the file are reported rather than removed.
+    pub executors: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TargetProgramsConfig {
+    /// `none`, `allow` or `deny`
+    pub policy: String,
+    #[serde(default)]
+    pub programs: Vec<String>,
+}
+
+/// The configuration of a OneSig account as it is on chain
+pub struct Current {
+    pub account: OneSigAccount,
+    pub target_programs: Vec<Pubkey>,
+    pub executor_reimbursement: u64,
+    /// Executors of the file that are registered
+    pub executors: Vec<Pubkey>,
+}
+
+/// A leaf of the plan
+#[derive(Clone)]
+pub enum Change {
+    SetConfig(SetConfigParams),
+    AddExecutor(Pubkey),
+}
+
+/// `plan --state <pubkey> --file <path> [--dry-run] [--out <path>]`: prints the leaves that bring
+/// a OneSig account to the configuration of a file, in the order they can execute in. With
+/// `--dry-run` each is simulated after the ones before it; with `--out` they are written to a
+/// bundle, described by the options of `set-config`.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    let file: ConfigFile = propose::read_file(args.required("file")?)?;
+    let dry_run = args.flag("dry-run")?;
+    let out = args.value("out")?;
+    let options = ProposalOptions::parse(&mut args)?;
+    args.finish()?;
+
+    let current = fetch(context, &state, &file)?;
+    let changes = diff(&file, &current)?;
+    let unlisted =
+        usize::from(current.account.header.executors_len).saturating_sub(current.executors.len());
+    if unlisted > 0 && file.executors.is_some() {
+        println!("note: {unlisted} registered executors are not in the file and stay registered");
+    }
+    if changes.is_empty() {
+        println!("{state} already has this configuration");
+        return Ok(());
+    }
+
+    let client = context.client(state);
+    let instructions: Vec<Instruction> =
+        changes.iter().map(|change| instruction(&client, change)).collect();
+    let payer = if dry_run { Some(context.payer()?.pubkey()) } else { None };
+    for (index, change) in changes.iter().enumerate() {
+        let nonce = current.account.header.nonce + index as u64;
+        println!("nonce {nonce:<6}{}", describe(change));
+        if let Some(payer) = &payer {
+            // set_config is signed by a PDA, which simulations without signature checks accept
+            let preflight =
+                context.rpc().preflight(&context.program_id, payer, &instructions[..=index])?;
+            if let Some(error) = preflight.error {
+                bail!("simulation failed: {error:?}\n{}", preflight.logs.join("\n"));
+            }
+            println!("            simulated");
+        }
+    }
+
+    if let Some(out) = out {
+        let instructions = changes
+            .iter()
+            .zip(&instructions)
+            .map(|(change, ix)| client.resolve_instruction(ix, 0, value(change)).instruction)
+            .collect();
+        let title = format!("Configuration of {state}");
+        set_config::write_bundle(&current.account, instructions, title, options, &out)?;
+    }
+    Ok(())
+}
+
+fn fetch(context: &Context, state: &Pubkey, file: &ConfigFile) -> Result<Current> {
+    let account = context.one_sig_account(state)?;
+    let pda = context.client(*state).pda;
+    let target_programs = context
+        .anchor_account::<TargetPrograms>(&pda.target_programs().0)?
+        .map(|target_programs| target_programs.programs)
+        .unwrap_or_default();
+    let executor_reimbursement = context
+        .anchor_account::<SpendingLimits>(&pda.spending_limits().0)?
+        .map_or(0, |limits| limits.executor_reimbursement);
+    let mut executors = vec![];
+    for executor in file.executors.iter().flatten() {
+        let executor = parse::pubkey(executor)?;
+        let executor_state = pda.executor_state(&executor).0;
+        if context.anchor_account::<ExecutorState>(&executor_state)?.is_some() {
+            executors.push(executor);
+        }
+    }
+    Ok(Current { account, target_programs, executor_reimbursement, executors })
+}
+
+/// The fewest changes that bring `current` to the configuration of `file`. Signers are added
+/// before the threshold changes and removed after, so the threshold never exceeds the signers, and
+/// executors are added once leaves may call `add_executor`.
+pub fn diff(file: &ConfigFile, current: &Current) -> Result<Vec<Change>> {
+    let header = &current.account.header;
+    let signers: Vec<Address> = file
+        .signers
+        .iter()
+        .map(|signer| parse::address(signer))
+        .collect::<Result<_>>()?;
+    if signers.iter().collect::<BTreeSet<_>>().len() != signers.len() {
+        bail!("the file lists a signer twice");
+    }
+    if file.threshold == 0 || usize::from(file.threshold) > signers.len() {
+        bail!("the threshold must be between 1 and the {} signers", signers.len());
+    }
+
+    let mut changes = vec![];
+    let on_chain = &current.account.signers;
+    let added: Vec<Address> =
+        signers.iter().filter(|signer| !on_chain.contains(signer)).copied().collect();
+    let capacity = usize::from(header.signers_capacity);
+    if on_chain.len() + added.len() > capacity {
+        bail!(
+            "adding {} signers to the {} of {} takes resizing it beyond {capacity}",
+            added.len(),
+            on_chain.len(),
+            current.account.address
+        );
+    }
+    changes.extend(added.into_iter().map(SetConfigParams::AddSigner).map(Change::SetConfig));
+    if file.threshold != header.threshold {
+        changes.push(Change::SetConfig(SetConfigParams::SetThreshold(file.threshold)));
+    }
+    changes.extend(
+        on_chain
+            .iter()
+            .filter(|signer| !signers.contains(signer))
+            .map(|signer| Change::SetConfig(SetConfigParams::RemoveSigner(*signer))),
+    );
+
+    if let Some(executor_required) = file.executor_required {
+        if executor_required != header.executor_required() {
+            changes
+                .push(Change::SetConfig(SetConfigParams::SetExecutorRequired(executor_required)));
+        }
+    }
+    if let Some(target_programs) = &file.target_programs {
+        let policy = parse::target_policy(&target_programs.policy)?;
+        let programs: Vec<Pubkey> = target_programs
+            .programs
+            .iter()
+            .map(|program| parse::pubkey(program))
+            .collect::<Result<_>>()?;
+        let unchanged = policy == header.target_policy() &&
+            programs.iter().collect::<BTreeSet<_>>() ==
+                current.target_programs.iter().collect::<BTreeSet<_>>();
+        if !unchanged {
+            changes.push(Change::SetConfig(SetConfigParams::SetTargetPrograms(
+                SetTargetProgramsParams { policy, programs },
+            )));
+        }
+    }
+    if let Some(reimbursement) = file.executor_reimbursement {
+        if reimbursement != current.executor_reimbursement {
+            changes
+                .push(Change::SetConfig(SetConfigParams::SetExecutorReimbursement(reimbursement)));
+        }
+    }
+    let mut executors = vec![];
+    for executor in file.executors.iter().flatten() {
+        let executor = parse::pubkey(executor)?;
+        if !current.executors.contains(&executor) {
+            executors.push(Change::AddExecutor(executor));
+        }
+    }
+    // `add_executor` is called back into by the leaves, which accounts only allow once they opt in
+    let mut targets = header.self_cpi_targets();
+    if !executors.is_empty() && !targets.contains(&SelfCpiTarget::Executors) {
+        targets.push(SelfCpiTarget::Executors);
+        changes.push(Change::SetConfig(SetConfigParams::SetSelfCpiTargets(targets)));
+    }
+    changes.extend(executors);
+    Ok(changes)
+}
+
+fn instruction(client: &OneSigClient, change: &Change) -> Instruction {
+    match change {
+        Change::SetConfig(params) => client.set_config(params.clone()),
+        Change::AddExecutor(executor) => client.add_executor(executor),
+    }
+}
+
+/// Lamports the leaf of `change` spends: the rent of a new `ExecutorState`
+fn value(change: &Change) -> u64 {
+    match change {
+        Change::SetConfig(_) => 0,
+        Change::AddExecutor(_) => Rent::default().minimum_balance(ExecutorState::space(0)),
+    }
+}
+
+/// `change` as the arguments of `set-config` applying it
+pub fn describe(change: &Change) -> String {
+    let params = match change {
+        Change::AddExecutor(executor) => return format!("add-executor {executor}"),
+        Change::SetConfig(params) => params,
+    };
+    match params {
+        SetConfigParams::AddSigner(signer) => format!("add-signer {}", checksum_address(signer)),
+        SetConfigParams::RemoveSigner(signer) =>
+            format!("remove-signer {}", checksum_address(signer)),
+        SetConfigParams::SetThreshold(threshold) => format!("set-threshold {threshold}"),
+        SetConfigParams::SetSeed(seed) => format!("set-seed 0x{}", hex::encode(seed.0)),
+        SetConfigParams::AddExecutor(executor) => format!("add-executor {executor}"),
+        SetConfigParams::RemoveExecutor(executor) => format!("remove-executor {executor}"),
+        SetConfigParams::SetExecutorRequired(required) =>
+            format!("set-executor-required {required}"),
+        SetConfigParams::SetTargetPrograms(params) => {
+            let policy = show::target_policy(params.policy);
+            let programs = params.programs.iter().map(|program| format!(" {program}"));
+            format!("set-target-programs {policy}{}", programs.collect::<String>())
+        },
+        SetConfigParams::SetExecutorReimbursement(lamports) =>
+            format!("set-executor-reimbursement {lamports}"),
+        SetConfigParams::SetClusterGenesisHash(hash) =>
+            format!("set-cluster-genesis-hash 0x{}", hex::encode(hash.0)),
+        SetConfigParams::ResetNonce(seed) => format!("reset-nonce 0x{}", hex::encode(seed.0)),
+        SetConfigParams::SetSelfCpiTargets(targets) =>
+            format!("set-self-cpi-targets {}", show::self_cpi_targets(targets)),
+        SetConfigParams::SetExecutionRateLimit(limit) =>
+            format!("set-execution-rate-limit {} {}", limit.max_executions, limit.window),
+    }
+}
@@ -0,0 +1,116 @@
+// Tests for planning the leaves that bring a OneSig account to a configuration.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use bytemuck::Zeroable;
+    use onesig::{Address, OneSigState, SelfCpiTarget, ONE_SIG_STATE_VERSION};
+    use onesig_sdk::{checksum_address, OneSigAccount};
+    use serde_json::json;
+
+    use crate::commands::plan::{describe, diff, ConfigFile, Current};
+
+    fn current(signers: &[u8], threshold: u8) -> Current {
+        let mut header = OneSigState::zeroed();
+        header.signers_len = signers.len() as u16;
+        header.signers_capacity = 4;
+        header.threshold = threshold;
+        header.nonce = 7;
+        header.version = ONE_SIG_STATE_VERSION;
+        let signers = signers.iter().map(|byte| Address([*byte; 20])).collect();
+        Current {
+            account: OneSigAccount { address: Pubkey::new_unique(), header, signers },
+            target_programs: vec![],
+            executor_reimbursement: 0,
+            executors: vec![],
+        }
+    }
+
+    fn file(json: serde_json::Value) -> ConfigFile {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn signer(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 20]))
+    }
+
+    fn plan(file: &ConfigFile, current: &Current) -> Vec<String> {
+        diff(file, current).unwrap().iter().map(describe).collect()
+    }
+
+    #[test]
+    fn test_rotate_signers() {
+        let current = current(&[1, 2, 3], 2);
+        let file = file(json!({ "signers": [signer(2), signer(3), signer(4)], "threshold": 3 }));
+        // Added before the threshold rises, removed once it has
+        assert_eq!(
+            plan(&file, &current),
+            [
+                format!("add-signer {}", checksum_address(&Address([4; 20]))),
+                "set-threshold 3".into(),
+                format!("remove-signer {}", checksum_address(&Address([1; 20]))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let current = current(&[1, 2], 2);
+        let file = file(json!({
+            "signers": [signer(2), signer(1)],
+            "threshold": 2,
+            "executorRequired": false,
+            "targetPrograms": { "policy": "none" },
+            "executorReimbursement": 0,
+            "executors": [],
+        }));
+        assert!(plan(&file, &current).is_empty());
+    }
+
+    #[test]
+    fn test_settings() {
+        let mut current = current(&[1], 1);
+        let registered = Pubkey::new_unique();
+        let added = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        current.executors = vec![registered];
+        let file = file(json!({
+            "signers": [signer(1)],
+            "threshold": 1,
+            "executorRequired": true,
+            "targetPrograms": { "policy": "allow", "programs": [program.to_string()] },
+            "executorReimbursement": 5000,
+            "executors": [registered.to_string(), added.to_string()],
+        }));
+        assert_eq!(
+            plan(&file, &current),
+            [
+                "set-executor-required true".into(),
+                format!("set-target-programs allow {program}"),
+                "set-executor-reimbursement 5000".into(),
+                "set-self-cpi-targets executors".into(),
+                format!("add-executor {added}"),
+            ]
+        );
+
+        // Accounts already allowing `add_executor` are left as they are
+        current.account.header.self_cpi_targets = SelfCpiTarget::Executors.bit();
+        assert_eq!(plan(&file, &current).last(), Some(&format!("add-executor {added}")));
+        assert!(!plan(&file, &current).iter().any(|leaf| leaf.starts_with("set-self-cpi")));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let current = current(&[1, 2, 3], 2);
+        let fails = |json| diff(&file(json), &current).is_err();
+        assert!(fails(json!({ "signers": [signer(1)], "threshold": 2 })));
+        assert!(fails(json!({ "signers": [signer(1)], "threshold": 0 })));
+        assert!(fails(json!({ "signers": [signer(1), signer(1)], "threshold": 1 })));
+        // Beyond the capacity of 4 signers
+        let signers: Vec<String> = (1..=5).map(signer).collect();
+        assert!(fails(json!({ "signers": signers, "threshold": 1 })));
+        assert!(serde_json::from_value::<ConfigFile>(
+            json!({ "signers": [], "threshold": 1, "x": 1 })
+        )
+        .is_err());
+    }
+}
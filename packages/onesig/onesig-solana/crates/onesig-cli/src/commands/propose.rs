@@ -0,0 +1,273 @@
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+};
+use anyhow::{anyhow, bail, Context as _, Result};
+use onesig::Hash;
+use onesig_sdk::{
+    EvmCall, EvmInstance, Leaf, LeafMetadata, OneSigAccount, OneSigClient, ProposalBundle,
+    ProposalMetadata, SolanaInstance, SolanaLeafGuards, TokenOutflowLimit, TreeCoordinator,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{args::Args, context::Context, parse};
+
+/// Account of a Solana transaction standing for the signer PDA of the vault executing it
+const VAULT_SIGNER: &str = "vault";
+
+/// What a proposal executes, per OneSig instance, as `propose` reads it from JSON or TOML.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProposalFile {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Seed of the signed message; defaults to the one the Solana instances share
+    pub seed: Option<String>,
+    /// Unix time the root expires at, or else `expiresIn` seconds from now
+    pub expiry: Option<u64>,
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    pub max_executions: u32,
+    #[serde(default)]
+    pub solana: Vec<SolanaTarget>,
+    #[serde(default)]
+    pub evm: Vec<EvmTarget>,
+}
+
+/// A Solana OneSig, whose id, nonce and chain are read from its state account
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SolanaTarget {
+    /// Address of the state account, or its name in the profile
+    pub state: String,
+    /// CAIP-2 id of the cluster, required when the account is not bound to one
+    pub chain_id: Option<String>,
+    pub transactions: Vec<SolanaTransaction>,
+}
+
+/// An instruction executed by a leaf, with the guards it declares
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SolanaTransaction {
+    pub program_id: String,
+    #[serde(default)]
+    pub accounts: Vec<SolanaAccount>,
+    /// Hex instruction data
+    #[serde(default)]
+    pub data: String,
+    /// Lamports the instruction may spend
+    #[serde(default)]
+    pub value: u64,
+    #[serde(default)]
+    pub vault_index: u8,
+    #[serde(default)]
+    pub token_outflow_limits: Vec<TokenOutflowLimit>,
+    #[serde(default)]
+    pub guarded_accounts: Vec<String>,
+    /// Unix time before which executors hold the leaf. Signers do not sign it, unless
+    /// `enforceNotBefore` binds it into the leaf, for the program to refuse executing it earlier.
+    pub not_before: Option<u64>,
+    #[serde(default)]
+    pub enforce_not_before: bool,
+    /// Lamports paid from the bounty escrow to whoever executes the leaf while executors are not
+    /// required, bound into the leaf
+    pub bounty: Option<u64>,
+}
+
+/// An account of an instruction: a public key, or `vault` for the signer PDA of the vault, which
+/// is the only signer of executed instructions.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SolanaAccount {
+    pub pubkey: String,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+/// An EVM OneSig, which the proposal reads nothing from
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EvmTarget {
+    pub chain_id: String,
+    pub address: String,
+    pub one_sig_id: u64,
+    /// `nonce()` of the contract
+    pub nonce: u64,
+    /// Calls of each leaf
+    pub transactions: Vec<Vec<EvmCall>>,
+}
+
+/// `propose --file <path> --out <path>`: builds the tree of the transactions a proposal file
+/// describes, numbering the leaves of each instance from its current nonce, and writes the
+/// bundle the signers sign the digest of.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let mut file: ProposalFile = read_file(args.required("file")?)?;
+    let out = args.required("out")?;
+    args.finish()?;
+    // Instances may be named after the profile's, so one file serves every cluster
+    for target in &mut file.solana {
+        target.state = context.state(&target.state)?.to_string();
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let bundle = build(context.program_id, file, now, |state| context.one_sig_account(state))?;
+    bundle.save(&out)?;
+    println!("wrote {out}");
+    for (index, leaf) in bundle.leaves.iter().enumerate() {
+        let address = match leaf {
+            Leaf::Solana(leaf) => leaf.one_sig_state.to_string(),
+            Leaf::Evm(leaf) => format!("0x{}", hex::encode(leaf.one_sig_address)),
+        };
+        match bundle.not_before(index) {
+            Some(not_before) => {
+                println!("  {address} nonce {} not before {not_before}", leaf.nonce())
+            },
+            None => println!("  {address} nonce {}", leaf.nonce()),
+        }
+    }
+    println!("merkle root  0x{}", hex::encode(bundle.merkle_root.0));
+    println!("digest       0x{}", hex::encode(bundle.digest().0));
+    Ok(())
+}
+
+/// Reads a proposal or configuration file, as TOML if its extension says so and as JSON
+/// otherwise.
+pub fn read_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let file = if path.extension().is_some_and(|extension| extension == "toml") {
+        toml::from_str(&contents).map_err(anyhow::Error::from)
+    } else {
+        serde_json::from_str(&contents).map_err(anyhow::Error::from)
+    };
+    file.with_context(|| format!("invalid file {}", path.display()))
+}
+
+/// Bundles the transactions of `file`, reading the Solana instances with `fetch`.
+pub fn build(
+    program_id: Pubkey,
+    file: ProposalFile,
+    now: u64,
+    fetch: impl Fn(&Pubkey) -> Result<OneSigAccount>,
+) -> Result<ProposalBundle> {
+    let mut coordinator = TreeCoordinator::new();
+    let mut seeds = vec![];
+    // Of each leaf, in the order they are pushed
+    let mut leaf_metadata = vec![];
+    for target in file.solana {
+        let state = parse::pubkey(&target.state)?;
+        let header = fetch(&state)?.header;
+        seeds.push(header.seed);
+        let Some(chain_id) = target.chain_id.or_else(|| header.caip2_chain_id()) else {
+            bail!("{state} is not bound to a chain, give its chainId");
+        };
+        let instance = coordinator.add_solana_instance(SolanaInstance {
+            chain_id,
+            one_sig_state: state,
+            one_sig_id: header.one_sig_id,
+            nonce: header.nonce,
+            cluster_genesis_hash: header.cluster_genesis_hash(),
+        });
+        let client = OneSigClient::with_program_id(program_id, state);
+        for transaction in target.transactions {
+            let vault_index = transaction.vault_index;
+            let instruction = solana_instruction(&client, &transaction)?;
+            let guards = SolanaLeafGuards {
+                token_outflow_limits: transaction.token_outflow_limits,
+                guarded_accounts: transaction
+                    .guarded_accounts
+                    .iter()
+                    .map(|account| parse::pubkey(account))
+                    .collect::<Result<_>>()?,
+                vault_index,
+                not_before: match (transaction.not_before, transaction.enforce_not_before) {
+                    (Some(not_before), true) => Some(i64::try_from(not_before)?),
+                    (None, true) => bail!("enforceNotBefore needs a notBefore"),
+                    (_, false) => None,
+                },
+                bounty: transaction.bounty,
+            };
+            let resolved = client.resolve_instruction(&instruction, vault_index, transaction.value);
+            coordinator.push_solana(instance, resolved.instruction, guards)?;
+            leaf_metadata.push(LeafMetadata { not_before: transaction.not_before });
+        }
+    }
+    for target in file.evm {
+        let address = parse::evm_address(&target.address)?;
+        let instance = coordinator.add_evm_instance(EvmInstance {
+            chain_id: target.chain_id,
+            one_sig_address: address,
+            one_sig_id: target.one_sig_id,
+            nonce: target.nonce,
+        });
+        for calls in target.transactions {
+            coordinator.push_evm(instance, calls)?;
+            leaf_metadata.push(LeafMetadata::default());
+        }
+    }
+    if coordinator.leaves().is_empty() {
+        bail!("the proposal has no transactions");
+    }
+
+    let seed = match file.seed {
+        Some(seed) => parse::hash(&seed)?,
+        None => shared_seed(&seeds)?,
+    };
+    let expiry = match (file.expiry, file.expires_in) {
+        (Some(expiry), None) => expiry,
+        (None, Some(expires_in)) => now + expires_in,
+        _ => bail!("give either expiry or expiresIn"),
+    };
+    let metadata = ProposalMetadata {
+        title: file.title,
+        description: file.description,
+        seed,
+        expiry,
+        max_executions: file.max_executions,
+    };
+    let mut bundle = coordinator.bundle(metadata)?;
+    if leaf_metadata.iter().any(|metadata| metadata.not_before.is_some()) {
+        bundle.leaf_metadata = leaf_metadata;
+    }
+    Ok(bundle)
+}
+
+fn solana_instruction(
+    client: &OneSigClient,
+    transaction: &SolanaTransaction,
+) -> Result<Instruction> {
+    let vault_signer = client.pda.vault_signer(transaction.vault_index).0;
+    let accounts = transaction
+        .accounts
+        .iter()
+        .map(|account| {
+            let pubkey = match account.pubkey.as_str() {
+                VAULT_SIGNER => vault_signer,
+                pubkey => parse::pubkey(pubkey)?,
+            };
+            Ok(AccountMeta { pubkey, is_signer: false, is_writable: account.is_writable })
+        })
+        .collect::<Result<_>>()?;
+    let data = hex::decode(transaction.data.strip_prefix("0x").unwrap_or(&transaction.data))
+        .with_context(|| format!("invalid data of an instruction of {}", transaction.program_id))?;
+    Ok(Instruction { program_id: parse::pubkey(&transaction.program_id)?, accounts, data })
+}
+
+/// The seed of the Solana instances, which a single root can only be signed for if they share it
+fn shared_seed(seeds: &[Hash]) -> Result<Hash> {
+    let Some(seed) = seeds.first() else {
+        return Err(anyhow!("give the seed, as no Solana instance tells it"));
+    };
+    if seeds.iter().any(|other| other != seed) {
+        bail!("the Solana instances have different seeds, give the one to sign with");
+    }
+    Ok(*seed)
+}
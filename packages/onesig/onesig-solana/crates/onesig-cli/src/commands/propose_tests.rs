@@ -0,0 +1,208 @@
+// Tests for building proposal bundles from proposal files.
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anchor_lang::prelude::Pubkey;
+    use anyhow::Result;
+    use bytemuck::Zeroable;
+    use onesig::{Hash, OneSigState, ONE_SIG_STATE_VERSION};
+    use onesig_sdk::{Leaf, OneSigAccount, OneSigClient, ProposalBundle};
+    use serde_json::json;
+
+    use crate::commands::propose::{build, read_file, ProposalFile};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn account(address: Pubkey, nonce: u64, seed: Hash) -> OneSigAccount {
+        let mut header = OneSigState::zeroed();
+        header.one_sig_id = address.to_bytes()[0].into();
+        header.nonce = nonce;
+        header.seed = seed;
+        header.version = ONE_SIG_STATE_VERSION;
+        OneSigAccount { address, header, signers: vec![] }
+    }
+
+    fn file(json: serde_json::Value) -> ProposalFile {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn solana_file(states: &[Pubkey]) -> serde_json::Value {
+        let targets: Vec<_> = states
+            .iter()
+            .map(|state| {
+                json!({
+                    "state": state.to_string(),
+                    "chainId": "solana:test",
+                    "transactions": [
+                        {
+                            "programId": Pubkey::new_from_array([9; 32]).to_string(),
+                            "accounts": [
+                                { "pubkey": "vault", "isWritable": true },
+                                { "pubkey": Pubkey::new_from_array([8; 32]).to_string() },
+                            ],
+                            "data": "0x0102",
+                            "value": 5,
+                        },
+                        { "programId": Pubkey::new_from_array([9; 32]).to_string() },
+                    ],
+                })
+            })
+            .collect();
+        json!({ "title": "Rotate", "expiresIn": 60, "solana": targets })
+    }
+
+    fn build_at(
+        file: ProposalFile,
+        nonce: u64,
+        seed: impl Fn(&Pubkey) -> Hash,
+    ) -> Result<ProposalBundle> {
+        build(onesig::ID, file, NOW, |state| Ok(account(*state, nonce, seed(state))))
+    }
+
+    #[test]
+    fn test_build() {
+        let state = Pubkey::new_unique();
+        let mut json = solana_file(&[state]);
+        json["evm"] = json!([{
+            "chainId": "eip155:1",
+            "address": format!("0x{}", "11".repeat(20)),
+            "oneSigId": 2,
+            "nonce": 7,
+            "transactions": [[{ "to": format!("0x{}", "22".repeat(20)), "value": "1", "data": "0x" }]],
+        }]);
+        let bundle = build_at(file(json), 3, |_| Hash([4; 32])).unwrap();
+
+        assert_eq!(bundle.metadata.title, "Rotate");
+        assert_eq!(bundle.metadata.expiry, NOW + 60);
+        assert!(bundle.metadata.seed == Hash([4; 32]));
+        assert_eq!(bundle.targets.len(), 2);
+        let nonces: Vec<u64> = bundle.leaves.iter().map(Leaf::nonce).collect();
+        assert_eq!(nonces, [3, 4, 7]);
+
+        let Leaf::Solana(leaf) = &bundle.leaves[0] else { panic!("not a Solana leaf") };
+        let vault_signer = OneSigClient::new(state).pda.one_sig_signer().0;
+        let accounts = &leaf.instruction.accounts;
+        // The vault placeholder is the signer PDA, the only signer of the instruction
+        assert_eq!(
+            (accounts[0].pubkey, accounts[0].is_signer, accounts[0].is_writable),
+            (vault_signer, true, true)
+        );
+        assert_eq!(
+            (accounts[1].pubkey, accounts[1].is_signer, accounts[1].is_writable),
+            (Pubkey::new_from_array([8; 32]), false, false)
+        );
+        assert_eq!(leaf.instruction.data, [1, 2]);
+        assert_eq!(leaf.instruction.value, 5);
+    }
+
+    #[test]
+    fn test_not_before() {
+        let state = Pubkey::new_unique();
+        let seed = |_: &Pubkey| Hash([4; 32]);
+        let unscheduled = build_at(file(solana_file(&[state])), 0, seed).unwrap();
+        assert!(unscheduled.leaf_metadata.is_empty());
+
+        let mut json = solana_file(&[state]);
+        json["solana"][0]["transactions"][1]["notBefore"] = json!(NOW + 3600);
+        let scheduled = build_at(file(json.clone()), 0, seed).unwrap();
+        assert_eq!(scheduled.leaf_metadata.len(), 2);
+        assert_eq!(scheduled.not_before(0), None);
+        assert_eq!(scheduled.not_before(1), Some(NOW + 3600));
+        // Signers sign the same root either way
+        assert!(scheduled.merkle_root == unscheduled.merkle_root);
+
+        // Unless the leaf is bound to it
+        json["solana"][0]["transactions"][1]["enforceNotBefore"] = json!(true);
+        let enforced = build_at(file(json.clone()), 0, seed).unwrap();
+        let Leaf::Solana(leaf) = &enforced.leaves[1] else { panic!("not a Solana leaf") };
+        assert_eq!(leaf.not_before, Some((NOW + 3600) as i64));
+        assert_eq!(enforced.not_before(1), Some(NOW + 3600));
+        assert!(enforced.merkle_root != unscheduled.merkle_root);
+
+        json["solana"][0]["transactions"][0]["enforceNotBefore"] = json!(true);
+        assert!(build_at(file(json), 0, seed).is_err());
+    }
+
+    #[test]
+    fn test_bounty() {
+        let state = Pubkey::new_unique();
+        let seed = |_: &Pubkey| Hash([4; 32]);
+        let unrewarded = build_at(file(solana_file(&[state])), 0, seed).unwrap();
+
+        let mut json = solana_file(&[state]);
+        json["solana"][0]["transactions"][1]["bounty"] = json!(5_000);
+        let rewarded = build_at(file(json), 0, seed).unwrap();
+        let Leaf::Solana(leaf) = &rewarded.leaves[1] else { panic!("not a Solana leaf") };
+        assert_eq!(leaf.bounty, Some(5_000));
+        assert!(rewarded.merkle_root != unrewarded.merkle_root);
+    }
+
+    #[test]
+    fn test_seeds() {
+        let states = [Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32])];
+        // Instances with different seeds cannot share a root
+        let seed = |state: &Pubkey| Hash(state.to_bytes());
+        assert!(build_at(file(solana_file(&states)), 0, seed).is_err());
+
+        // unless the seed to sign with is given
+        let mut json = solana_file(&states);
+        json["seed"] = json!(format!("0x{}", "ab".repeat(32)));
+        let bundle = build_at(file(json), 0, seed).unwrap();
+        assert!(bundle.metadata.seed == Hash([0xab; 32]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let state = Pubkey::new_unique();
+        let seed = |_: &Pubkey| Hash([0; 32]);
+        assert!(build_at(file(json!({ "title": "Empty", "expiry": NOW })), 0, seed).is_err());
+
+        let mut json = solana_file(&[state]);
+        json["expiry"] = json!(NOW);
+        assert!(build_at(file(json), 0, seed).is_err());
+
+        let mut json = solana_file(&[state]);
+        json["solana"][0]["transactions"][0]["data"] = json!("0xzz");
+        assert!(build_at(file(json), 0, seed).is_err());
+
+        let mut json = solana_file(&[state]);
+        json["solana"][0]["transactions"][0]["unknown"] = json!(1);
+        assert!(serde_json::from_value::<ProposalFile>(json).is_err());
+    }
+
+    #[test]
+    fn test_read_toml() {
+        let state = Pubkey::new_unique();
+        let toml = format!(
+            r#"
+title = "Rotate"
+expiresIn = 60
+
+[[solana]]
+state = "{state}"
+chainId = "solana:test"
+
+[[solana.transactions]]
+programId = "{program}"
+data = "0x0102"
+value = 5
+accounts = [{{ pubkey = "vault", isWritable = true }}, {{ pubkey = "{account}" }}]
+
+[[solana.transactions]]
+programId = "{program}"
+"#,
+            program = Pubkey::new_from_array([9; 32]),
+            account = Pubkey::new_from_array([8; 32]),
+        );
+        let path = std::env::temp_dir().join(format!("onesig-proposal-{state}.toml"));
+        fs::write(&path, toml).unwrap();
+        let from_toml = read_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        let seed = |_: &Pubkey| Hash([4; 32]);
+        let from_toml = build_at(from_toml.unwrap(), 3, seed).unwrap();
+        let from_json = build_at(file(solana_file(&[state])), 3, seed).unwrap();
+        assert!(from_toml.merkle_root == from_json.merkle_root);
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anyhow::{anyhow, bail, Result};
+use onesig::{instruction, ExecutionRateLimit, Hash, SetConfigParams, SetTargetProgramsParams};
+use onesig_sdk::{
+    Leaf, OneSigAccount, OneSigInstruction, ProposalBundle, ProposalMetadata, ProposalTarget,
+    SolanaLeaf,
+};
+
+use crate::{
+    args::Args,
+    commands::{execute, show},
+    context::Context,
+    parse,
+};
+
+/// Time a proposal stays valid for unless `--expires-in` says otherwise, in seconds
+const DEFAULT_EXPIRES_IN: u64 = 24 * 60 * 60;
+
+/// `set-config <change> --state <pubkey> --out <path>` writes a proposal bundle of a single leaf
+/// applying `change` at the current nonce, for the signers to sign its digest; `set-config --state
+/// <pubkey> --bundle <path>` then executes it once the bundle holds enough signatures.
+///
+/// `set_config` is signed by the `one_sig_signer` PDA, so it only runs as a leaf of an approved
+/// root: the two steps are the whole lifecycle of one configuration change.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    match args.value("bundle")? {
+        Some(bundle) => {
+            args.finish()?;
+            execute(context, &state, &ProposalBundle::load(bundle)?)
+        },
+        None => propose(context, &state, args),
+    }
+}
+
+/// Parses `<change> <value>..`, e.g. `add-signer 0x..` or `set-target-programs allow <program>..`.
+/// `set-self-cpi-targets` takes `none`, or the targets to allow, and `set-execution-rate-limit` the
+/// number of leaves executed per window, then the window in seconds unless the number is 0.
+pub fn parse_change(args: &mut Args) -> Result<SetConfigParams> {
+    let change = args.required_positional("change")?;
+    let value = args.required_positional("value")?;
+    Ok(match change.as_str() {
+        "add-signer" => SetConfigParams::AddSigner(parse::address(&value)?),
+        "remove-signer" => SetConfigParams::RemoveSigner(parse::address(&value)?),
+        "set-threshold" => SetConfigParams::SetThreshold(value.parse()?),
+        "set-seed" => SetConfigParams::SetSeed(parse::hash(&value)?),
+        "set-executor-required" => SetConfigParams::SetExecutorRequired(parse::bool(&value)?),
+        "set-executor-reimbursement" => SetConfigParams::SetExecutorReimbursement(value.parse()?),
+        "set-cluster-genesis-hash" => SetConfigParams::SetClusterGenesisHash(genesis_hash(&value)?),
+        "reset-nonce" => SetConfigParams::ResetNonce(parse::hash(&value)?),
+        "set-target-programs" => {
+            let policy = parse::target_policy(&value)?;
+            let mut programs = vec![];
+            while let Some(program) = args.positional() {
+                programs.push(parse::pubkey(&program)?);
+            }
+            SetConfigParams::SetTargetPrograms(SetTargetProgramsParams { policy, programs })
+        },
+        "set-self-cpi-targets" => {
+            let mut targets = vec![];
+            if value != "none" {
+                targets.push(parse::self_cpi_target(&value)?);
+                while let Some(target) = args.positional() {
+                    targets.push(parse::self_cpi_target(&target)?);
+                }
+            }
+            SetConfigParams::SetSelfCpiTargets(targets)
+        },
+        "set-execution-rate-limit" => {
+            let max_executions = value.parse()?;
+            let window = match args.positional() {
+                Some(window) => window.parse()?,
+                None if max_executions == 0 => 0,
+                None => bail!("set-execution-rate-limit needs a window in seconds"),
+            };
+            SetConfigParams::SetExecutionRateLimit(ExecutionRateLimit { max_executions, window })
+        },
+        change => bail!("unknown change {change}"),
+    })
+}
+
+/// A genesis hash as `solana genesis-hash` prints it, or as hex
+fn genesis_hash(value: &str) -> Result<Hash> {
+    if value.starts_with("0x") {
+        return parse::hash(value);
+    }
+    Ok(Hash(
+        parse::pubkey(value)
+            .map_err(|_| anyhow!("invalid genesis hash {value}"))?
+            .to_bytes(),
+    ))
+}
+
+fn propose(context: &Context, state: &Pubkey, mut args: Args) -> Result<()> {
+    let change = parse_change(&mut args)?;
+    let out = args.required("out")?;
+    let options = ProposalOptions::parse(&mut args)?;
+    args.finish()?;
+
+    let account = context.one_sig_account(state)?;
+    let client = context.client(*state);
+    let instruction = client.resolve_instruction(&client.set_config(change), 0, 0).instruction;
+    let title = format!("set_config of {state} at nonce {}", account.header.nonce);
+    write_bundle(&account, vec![instruction], title, options, &out)?;
+    Ok(())
+}
+
+/// How `set-config` and `plan` describe the bundles they write: `--title`, `--description`,
+/// `--expires-in` and `--chain-id`, all optional.
+pub struct ProposalOptions {
+    pub title: Option<String>,
+    pub description: String,
+    pub expires_in: u64,
+    pub chain_id: Option<String>,
+}
+
+impl ProposalOptions {
+    pub fn parse(args: &mut Args) -> Result<Self> {
+        Ok(Self {
+            title: args.value("title")?,
+            description: args.value("description")?.unwrap_or_default(),
+            expires_in: args.parsed("expires-in")?.unwrap_or(DEFAULT_EXPIRES_IN),
+            chain_id: args.value("chain-id")?,
+        })
+    }
+}
+
+/// Writes a bundle to `out` executing `instructions` on `account` in turn from its current nonce,
+/// and prints the root and the digest to sign.
+pub fn write_bundle(
+    account: &OneSigAccount,
+    instructions: Vec<OneSigInstruction>,
+    default_title: String,
+    options: ProposalOptions,
+    out: &str,
+) -> Result<ProposalBundle> {
+    let state = account.address;
+    let header = &account.header;
+    let Some(chain_id) = options.chain_id.or_else(|| header.caip2_chain_id()) else {
+        bail!("{state} is not bound to a chain, pass its CAIP-2 id with --chain-id");
+    };
+    let leaves = (header.nonce..)
+        .zip(instructions)
+        .map(|(nonce, instruction)| {
+            Leaf::from(SolanaLeaf {
+                one_sig_state: state,
+                one_sig_id: header.one_sig_id,
+                nonce,
+                instruction,
+                token_outflow_limits: vec![],
+                guarded_accounts: vec![],
+                vault_index: 0,
+                cluster_genesis_hash: header.cluster_genesis_hash(),
+                group: None,
+                not_before: None,
+                bounty: None,
+            })
+        })
+        .collect();
+    let metadata = ProposalMetadata {
+        title: options.title.unwrap_or(default_title),
+        description: options.description,
+        seed: header.seed,
+        expiry: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + options.expires_in,
+        max_executions: 0,
+    };
+    let target =
+        ProposalTarget { chain_id, one_sig_id: header.one_sig_id, address: state.to_string() };
+    let bundle = ProposalBundle::new(metadata, vec![target], leaves)?;
+    bundle.save(out)?;
+    println!("wrote {out}");
+    println!("merkle root  0x{}", hex::encode(bundle.merkle_root.0));
+    println!("digest       0x{}", hex::encode(bundle.digest().0));
+    Ok(bundle)
+}
+
+fn execute(context: &Context, state: &Pubkey, bundle: &ProposalBundle) -> Result<()> {
+    let account = context.one_sig_account(state)?;
+    let nonce = account.header.nonce;
+    let (index, leaf) = execute::pending_leaf(bundle, state, nonce)
+        .ok_or_else(|| anyhow!("the bundle has no leaf of {state} at nonce {nonce}"))?;
+    let instruction = &leaf.instruction;
+    if instruction.program_id != context.program_id ||
+        !instruction.data.starts_with(instruction::SetConfig::DISCRIMINATOR)
+    {
+        bail!("leaf {index} of the bundle is not a set_config");
+    }
+    let confirmed = execute::execute_leaf(context, &account, bundle, index, leaf)?;
+    println!("executed leaf {index} in {}\n", confirmed.signature);
+    show::print(context, state)
+}
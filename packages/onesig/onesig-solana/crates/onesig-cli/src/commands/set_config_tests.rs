@@ -0,0 +1,89 @@
+// Tests for parsing the configuration changes of `set-config`.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{Address, ExecutionRateLimit, Hash, SelfCpiTarget, SetConfigParams, TargetPolicy};
+
+    use crate::{args::Args, commands::set_config::parse_change};
+
+    fn change(args: &[&str]) -> anyhow::Result<SetConfigParams> {
+        parse_change(&mut Args::parse(args.iter().map(|arg| arg.to_string())))
+    }
+
+    #[test]
+    fn test_parse_change() {
+        let signer = format!("0x{}", "ab".repeat(20));
+        assert!(matches!(
+            change(&["add-signer", &signer]).unwrap(),
+            SetConfigParams::AddSigner(address) if address == Address([0xab; 20])
+        ));
+        assert!(matches!(
+            change(&["set-threshold", "3"]).unwrap(),
+            SetConfigParams::SetThreshold(3)
+        ));
+        assert!(matches!(
+            change(&["set-executor-required", "false"]).unwrap(),
+            SetConfigParams::SetExecutorRequired(false)
+        ));
+        assert!(matches!(
+            change(&["reset-nonce", &format!("0x{}", "07".repeat(32))]).unwrap(),
+            SetConfigParams::ResetNonce(seed) if seed == Hash([7; 32])
+        ));
+        let programs = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let SetConfigParams::SetTargetPrograms(params) = change(&[
+            "set-target-programs",
+            "allow",
+            &programs[0].to_string(),
+            &programs[1].to_string(),
+        ])
+        .unwrap() else {
+            panic!("not a SetTargetPrograms");
+        };
+        assert_eq!(params.policy, TargetPolicy::Allow);
+        assert_eq!(params.programs, programs);
+
+        let SetConfigParams::SetSelfCpiTargets(targets) =
+            change(&["set-self-cpi-targets", "executors", "close-one-sig"]).unwrap()
+        else {
+            panic!("not a SetSelfCpiTargets");
+        };
+        assert_eq!(targets, [SelfCpiTarget::Executors, SelfCpiTarget::CloseOneSig]);
+        assert!(matches!(
+            change(&["set-self-cpi-targets", "none"]).unwrap(),
+            SetConfigParams::SetSelfCpiTargets(targets) if targets.is_empty()
+        ));
+
+        assert!(matches!(
+            change(&["set-execution-rate-limit", "10", "3600"]).unwrap(),
+            SetConfigParams::SetExecutionRateLimit(ExecutionRateLimit {
+                max_executions: 10,
+                window: 3_600
+            })
+        ));
+        assert!(matches!(
+            change(&["set-execution-rate-limit", "0"]).unwrap(),
+            SetConfigParams::SetExecutionRateLimit(ExecutionRateLimit { max_executions: 0, .. })
+        ));
+
+        // Genesis hashes in base58, as `solana genesis-hash` prints them, or in hex
+        let genesis = Pubkey::new_from_array([5; 32]).to_string();
+        for genesis in [genesis, format!("0x{}", "05".repeat(32))] {
+            assert!(matches!(
+                change(&["set-cluster-genesis-hash", &genesis]).unwrap(),
+                SetConfigParams::SetClusterGenesisHash(hash) if hash == Hash([5; 32])
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_change_errors() {
+        assert!(change(&[]).is_err());
+        assert!(change(&["add-signer"]).is_err());
+        assert!(change(&["add-signer", "0x01"]).is_err());
+        assert!(change(&["set-threshold", "300"]).is_err());
+        assert!(change(&["set-target-programs", "some"]).is_err());
+        assert!(change(&["set-self-cpi-targets", "execute"]).is_err());
+        assert!(change(&["set-execution-rate-limit", "10"]).is_err());
+        assert!(change(&["set-owner", "x"]).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use onesig::{ExecutionRateLimit, SelfCpiTarget, TargetPolicy};
+use onesig_sdk::{checksum_address, OneSigAccount, OneSigPda};
+
+use crate::{args::Args, context::Context};
+
+/// `show --state <pubkey>`: prints the configuration of a OneSig account and its PDAs.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    args.finish()?;
+    print(context, &state)
+}
+
+/// Fetches the OneSig account at `state` and prints it.
+pub fn print(context: &Context, state: &anchor_lang::prelude::Pubkey) -> Result<()> {
+    let account = context.one_sig_account(state)?;
+    print!("{}", describe(&account, &context.client(*state).pda));
+    Ok(())
+}
+
+pub fn describe(account: &OneSigAccount, pda: &OneSigPda) -> String {
+    let header = &account.header;
+    let mut out = String::new();
+    let mut line = |name: &str, value: String| writeln!(out, "{name:<22}{value}").unwrap();
+    line("state", account.address.to_string());
+    line("program", pda.program_id.to_string());
+    line("one_sig_id", header.one_sig_id.to_string());
+    line("nonce", header.nonce.to_string());
+    line("seed", format!("0x{}", hex::encode(header.seed.0)));
+    line("threshold", format!("{} of {} signers", header.threshold, account.signers.len()));
+    line("signers_capacity", header.signers_capacity.to_string());
+    for (index, signer) in account.signers.iter().enumerate() {
+        line(if index == 0 { "signers" } else { "" }, checksum_address(signer));
+    }
+    line("executor_required", header.executor_required().to_string());
+    line("executors", header.executors_len.to_string());
+    line("target_policy", target_policy(header.target_policy()).into());
+    line("self_cpi_targets", self_cpi_targets(&header.self_cpi_targets()));
+    line("execution_rate_limit", execution_rate_limit(&header.execution_rate_limit()));
+    line("chain", header.caip2_chain_id().unwrap_or_else(|| "unbound".into()));
+    line("open_merkle_roots", header.merkle_roots_len.to_string());
+    line("modules", header.modules_len.to_string());
+    line("one_sig_signer", pda.one_sig_signer().0.to_string());
+    line("merkle_root_registry", pda.merkle_root_registry().0.to_string());
+    line("spending_limits", pda.spending_limits().0.to_string());
+    line("target_programs", pda.target_programs().0.to_string());
+    line("event_authority", pda.event_authority().0.to_string());
+    out
+}
+
+/// `policy` as `set-config set-target-programs` takes it
+pub fn target_policy(policy: TargetPolicy) -> &'static str {
+    match policy {
+        TargetPolicy::None => "none",
+        TargetPolicy::Allow => "allow",
+        TargetPolicy::Deny => "deny",
+    }
+}
+
+/// `targets` as `set-config set-self-cpi-targets` takes them
+pub fn self_cpi_targets(targets: &[SelfCpiTarget]) -> String {
+    if targets.is_empty() {
+        return "none".into();
+    }
+    let names = targets.iter().map(|target| match target {
+        SelfCpiTarget::Executors => "executors",
+        SelfCpiTarget::Modules => "modules",
+        SelfCpiTarget::SpendingLimits => "spending-limits",
+        SelfCpiTarget::ResizeState => "resize-state",
+        SelfCpiTarget::CloseOneSig => "close-one-sig",
+        SelfCpiTarget::BountyEscrow => "bounty-escrow",
+    });
+    names.collect::<Vec<_>>().join(" ")
+}
+
+pub fn execution_rate_limit(limit: &ExecutionRateLimit) -> String {
+    match limit.max_executions {
+        0 => "unlimited".into(),
+        max_executions => format!("{max_executions} leaves per {}s", limit.window),
+    }
+}
@@ -0,0 +1,50 @@
+// Tests for printing the configuration of a OneSig account.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use bytemuck::Zeroable;
+    use onesig::{Address, Hash, OneSigState, SelfCpiTarget, ONE_SIG_STATE_VERSION};
+    use onesig_sdk::{checksum_address, OneSigAccount, OneSigPda};
+
+    use crate::commands::show::describe;
+
+    #[test]
+    fn test_describe() {
+        let mut header = OneSigState::zeroed();
+        header.one_sig_id = 9;
+        header.nonce = 4;
+        header.seed = Hash([0x11; 32]);
+        header.signers_len = 2;
+        header.signers_capacity = 3;
+        header.threshold = 2;
+        header.executors_len = 1;
+        header.executor_required = 1;
+        header.version = ONE_SIG_STATE_VERSION;
+        header.target_policy = 2;
+        header.self_cpi_targets = SelfCpiTarget::mask(&[SelfCpiTarget::Modules]);
+        header.cluster_genesis_hash = Hash([5; 32]);
+        header.max_executions = 10;
+        header.execution_window = 3_600;
+        let address = Pubkey::new_unique();
+        let account = OneSigAccount {
+            address,
+            header,
+            signers: vec![Address([0xaa; 20]), Address([0xbb; 20])],
+        };
+        let pda = OneSigPda::new(onesig::ID, address);
+
+        let lines: Vec<String> = describe(&account, &pda).lines().map(String::from).collect();
+        let line = |name: &str, value: String| format!("{name:<22}{value}");
+        assert_eq!(lines[0], line("state", address.to_string()));
+        assert_eq!(lines[3], line("nonce", "4".into()));
+        assert_eq!(lines[5], line("threshold", "2 of 2 signers".into()));
+        assert_eq!(lines[7], line("signers", checksum_address(&Address([0xaa; 20]))));
+        assert_eq!(lines[8], line("", checksum_address(&Address([0xbb; 20]))));
+        assert_eq!(lines[9], line("executor_required", "true".into()));
+        assert_eq!(lines[11], line("target_policy", "deny".into()));
+        assert_eq!(lines[12], line("self_cpi_targets", "modules".into()));
+        assert_eq!(lines[13], line("execution_rate_limit", "10 leaves per 3600s".into()));
+        assert_eq!(lines[14], line("chain", header.caip2_chain_id().unwrap()));
+        assert_eq!(lines[17], line("one_sig_signer", pda.one_sig_signer().0.to_string()));
+    }
+}
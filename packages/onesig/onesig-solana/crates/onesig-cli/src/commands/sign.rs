@@ -0,0 +1,35 @@
+use anyhow::{bail, Result};
+use onesig_sdk::{LedgerSigner, ProposalBundle, RootSigner, LEDGER_DEFAULT_DERIVATION_PATH};
+
+use crate::{
+    args::Args,
+    ledger::{self, LedgerHid},
+};
+
+/// `sign --bundle <path> --ledger [--derivation-path <path>] [--out <path>]`: signs the root of
+/// a proposal bundle with the Ethereum app of a Ledger, which shows the EIP-712 domain and
+/// message hashes to approve, and adds the signature to the bundle.
+pub fn run(mut args: Args) -> Result<()> {
+    let path = args.required("bundle")?;
+    let out = args.value("out")?.unwrap_or_else(|| path.clone());
+    let derivation_path = match args.value("derivation-path")? {
+        Some(derivation_path) => ledger::derivation_path(&derivation_path)?,
+        None => LEDGER_DEFAULT_DERIVATION_PATH.to_vec(),
+    };
+    if !args.flag("ledger")? {
+        bail!("pass --ledger to sign with the Ethereum app of a Ledger");
+    }
+    args.finish()?;
+
+    let mut bundle = ProposalBundle::load(&path)?;
+    let signer = LedgerSigner::new(LedgerHid::open()?, derivation_path)?;
+    let message = bundle.message();
+    println!("signing with 0x{}, approve on the Ledger:", hex::encode(signer.address().0));
+    println!("  domain hash   0x{}", hex::encode(message.domain_separator.0));
+    println!("  message hash  0x{}", hex::encode(message.struct_hash.0));
+    let signature = signer.sign(&message)?;
+    bundle.add_signature(&signature)?;
+    bundle.save(&out)?;
+    println!("wrote {out} with {} signatures", bundle.signatures.len());
+    Ok(())
+}
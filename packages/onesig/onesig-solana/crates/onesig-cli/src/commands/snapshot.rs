@@ -0,0 +1,339 @@
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anyhow::{anyhow, bail, Context as _, Result};
+use onesig::{
+    ExecutorState, Hash, InitOneSigParams, MerkleRootRegistry, MerkleRootState, SelfCpiTarget,
+    SetConfigParams, SetSpendingLimitParams, SetTargetProgramsParams, SpendingLimits, TargetPolicy,
+    TargetPrograms,
+};
+use onesig_sdk::{
+    checksum_address, OneSigAccount, OneSigClient, OneSigInstruction, TransactionSigner,
+    TRANSACTION_SIGNATURE_LEN,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::Args,
+    commands::{
+        set_config::{self, ProposalOptions},
+        show,
+    },
+    context::Context,
+    keypair::{self, Keypair},
+    parse,
+};
+
+/// Version of the snapshot format
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The configuration of a OneSig account and its open roots at a point in time, signed by the
+/// operator who exported it so an import can tell it was not altered on the way.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSnapshot {
+    pub snapshot: Snapshot,
+    /// Public key whose ed25519 signature of the JSON of `snapshot` is `signature`
+    pub signer: String,
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub version: u32,
+    pub program_id: String,
+    pub state: String,
+    pub exported_at: u64,
+    pub config: InstanceConfig,
+    pub merkle_roots: Vec<RootSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceConfig {
+    pub one_sig_id: u64,
+    pub nonce: u64,
+    pub seed: String,
+    pub threshold: u8,
+    pub signers: Vec<String>,
+    pub signers_capacity: u16,
+    pub executor_required: bool,
+    pub executors: Vec<String>,
+    pub target_policy: String,
+    pub target_programs: Vec<String>,
+    pub executor_reimbursement: u64,
+    /// Set only when the account overrides the cluster of the build
+    pub cluster_genesis_hash: Option<String>,
+    pub spending_limits: Vec<SpendingLimitConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingLimitConfig {
+    pub mint: String,
+    pub amount: u64,
+    pub window: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootSnapshot {
+    pub address: String,
+    pub merkle_root: String,
+    pub seed_commitment: String,
+    pub expiry: i64,
+    pub remaining_executions: Option<u32>,
+    pub signed_by: Vec<String>,
+    pub rent_payer: String,
+}
+
+impl SignedSnapshot {
+    pub fn sign(snapshot: Snapshot, keypair: &Keypair) -> Result<Self> {
+        let signature = keypair.sign(&serde_json::to_vec(&snapshot)?);
+        Ok(Self {
+            snapshot,
+            signer: keypair.pubkey().to_string(),
+            signature: format!("0x{}", hex::encode(signature)),
+        })
+    }
+
+    /// Checks the signature of the snapshot, returning its signer.
+    pub fn verify(&self) -> Result<Pubkey> {
+        let signer = parse::pubkey(&self.signer)?;
+        let signature = hex::decode(self.signature.strip_prefix("0x").unwrap_or(&self.signature))?;
+        let signature: [u8; TRANSACTION_SIGNATURE_LEN] =
+            signature.try_into().map_err(|_| anyhow!("invalid snapshot signature"))?;
+        if !keypair::verify(&signer, &serde_json::to_vec(&self.snapshot)?, &signature) {
+            bail!("the signature of the snapshot is not valid, was it modified?");
+        }
+        Ok(signer)
+    }
+}
+
+/// `export --state <pubkey> --out <path> [--executor <pubkey>...]`: writes the configuration of a
+/// OneSig account and its open roots to a snapshot signed with `--keypair`. Executors cannot be
+/// listed on chain, so the ones to record are given and checked to be registered.
+pub fn export(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    let out = args.required("out")?;
+    let executors: Vec<_> = args
+        .values("executor")?
+        .iter()
+        .map(|executor| parse::pubkey(executor))
+        .collect::<Result<_>>()?;
+    args.finish()?;
+
+    let account = context.one_sig_account(&state)?;
+    let pda = context.client(state).pda;
+    for executor in &executors {
+        if context
+            .anchor_account::<ExecutorState>(&pda.executor_state(executor).0)?
+            .is_none()
+        {
+            bail!("{executor} is not an executor of {state}");
+        }
+    }
+    if executors.len() != usize::from(account.header.executors_len) {
+        println!(
+            "note: {state} has {} executors, the snapshot records the {} given",
+            account.header.executors_len,
+            executors.len()
+        );
+    }
+    let target_programs = context
+        .anchor_account::<TargetPrograms>(&pda.target_programs().0)?
+        .map(|target_programs| target_programs.programs)
+        .unwrap_or_default();
+    let spending_limits = context.anchor_account::<SpendingLimits>(&pda.spending_limits().0)?;
+    let registry = context
+        .anchor_account::<MerkleRootRegistry>(&pda.merkle_root_registry().0)?
+        .map(|registry| registry.entries)
+        .unwrap_or_default();
+    let mut merkle_roots = vec![];
+    for entry in registry {
+        if let Some(root) = context.anchor_account::<MerkleRootState>(&entry.address)? {
+            merkle_roots.push(root_snapshot(&entry.address, &root));
+        }
+    }
+
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        program_id: context.program_id.to_string(),
+        state: state.to_string(),
+        exported_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        config: instance_config(&account, &executors, &target_programs, spending_limits.as_ref()),
+        merkle_roots,
+    };
+    let signed = SignedSnapshot::sign(snapshot, &context.payer()?)?;
+    fs::write(&out, serde_json::to_string_pretty(&signed)?)
+        .with_context(|| format!("cannot write {out}"))?;
+    println!("wrote {out}, signed by {}", signed.signer);
+    Ok(())
+}
+
+/// `import --snapshot <path> --state-keypair <path> [--trusted-signer <pubkey>] [--out <path>]`:
+/// creates a OneSig account like the one of a snapshot at the address of the state keypair. What
+/// `init_one_sig` does not set (target programs, reimbursement, chain, spending limits) is
+/// written to a bundle at `--out` for the signers to approve, described by the options of
+/// `set-config`.
+pub fn import(context: &Context, mut args: Args) -> Result<()> {
+    let path = args.required("snapshot")?;
+    let state = Keypair::read(args.required("state-keypair")?)?;
+    let trusted_signer =
+        args.value("trusted-signer")?.map(|signer| parse::pubkey(&signer)).transpose()?;
+    let out = args.value("out")?;
+    let options = ProposalOptions::parse(&mut args)?;
+    args.finish()?;
+
+    let json = fs::read_to_string(&path).with_context(|| format!("cannot read {path}"))?;
+    let signed: SignedSnapshot =
+        serde_json::from_str(&json).with_context(|| format!("invalid snapshot {path}"))?;
+    let signer = signed.verify()?;
+    match trusted_signer {
+        Some(trusted_signer) if trusted_signer != signer =>
+            bail!("the snapshot is signed by {signer}, not {trusted_signer}"),
+        Some(_) => {},
+        None => println!("snapshot signed by {signer}"),
+    }
+    let snapshot = signed.snapshot;
+    if snapshot.version != SNAPSHOT_VERSION {
+        bail!("unsupported snapshot version {}", snapshot.version);
+    }
+
+    let client = context.client(state.pubkey());
+    let (params, follow_ups) = restore(&client, &snapshot.config)?;
+    if !follow_ups.is_empty() && out.is_none() {
+        bail!("pass --out to write the bundle restoring the rest of the configuration");
+    }
+    let payer = context.payer()?;
+    let instruction = client.init_one_sig(&payer.pubkey(), params);
+    let confirmed = context.send(&payer, &[instruction], &[&state])?;
+    println!("restored {} as {} in {}", snapshot.state, client.state(), confirmed.signature);
+    if !snapshot.merkle_roots.is_empty() {
+        println!(
+            "note: the {} roots open on {} bind their leaves to it, propose them again",
+            snapshot.merkle_roots.len(),
+            snapshot.state
+        );
+    }
+    if let Some(out) = out.filter(|_| !follow_ups.is_empty()) {
+        let account = context.one_sig_account(&client.state())?;
+        let title = format!("Restore the configuration of {}", snapshot.state);
+        set_config::write_bundle(&account, follow_ups, title, options, &out)?;
+    }
+    println!();
+    show::print(context, &client.state())
+}
+
+/// The configuration of `account`, with the given executors, target programs and spending limits
+pub fn instance_config(
+    account: &OneSigAccount,
+    executors: &[Pubkey],
+    target_programs: &[Pubkey],
+    spending_limits: Option<&SpendingLimits>,
+) -> InstanceConfig {
+    let header = &account.header;
+    let hex = |hash: &Hash| format!("0x{}", hex::encode(hash.0));
+    InstanceConfig {
+        one_sig_id: header.one_sig_id,
+        nonce: header.nonce,
+        seed: hex(&header.seed),
+        threshold: header.threshold,
+        signers: account.signers.iter().map(checksum_address).collect(),
+        signers_capacity: header.signers_capacity,
+        executor_required: header.executor_required(),
+        executors: executors.iter().map(Pubkey::to_string).collect(),
+        target_policy: show::target_policy(header.target_policy()).into(),
+        target_programs: target_programs.iter().map(Pubkey::to_string).collect(),
+        executor_reimbursement: spending_limits.map_or(0, |limits| limits.executor_reimbursement),
+        cluster_genesis_hash: (header.cluster_genesis_hash != Hash([0; 32]))
+            .then(|| hex(&header.cluster_genesis_hash)),
+        spending_limits: spending_limits
+            .iter()
+            .flat_map(|limits| &limits.entries)
+            .map(|limit| SpendingLimitConfig {
+                mint: limit.mint.to_string(),
+                amount: limit.amount,
+                window: limit.window,
+            })
+            .collect(),
+    }
+}
+
+pub fn root_snapshot(address: &Pubkey, root: &MerkleRootState) -> RootSnapshot {
+    RootSnapshot {
+        address: address.to_string(),
+        merkle_root: format!("0x{}", hex::encode(root.merkle_root.0)),
+        seed_commitment: format!("0x{}", hex::encode(root.seed_commitment)),
+        expiry: root.expiry,
+        remaining_executions: root.remaining_executions,
+        signed_by: root.signed_by.iter().map(checksum_address).collect(),
+        rent_payer: root.rent_payer.to_string(),
+    }
+}
+
+/// `init_one_sig` parameters re-creating the account of `config` for `client`, and the
+/// instructions of the leaves restoring the rest of its configuration.
+pub fn restore(
+    client: &OneSigClient,
+    config: &InstanceConfig,
+) -> Result<(InitOneSigParams, Vec<OneSigInstruction>)> {
+    let pubkeys = |pubkeys: &[String]| -> Result<Vec<Pubkey>> {
+        pubkeys.iter().map(|pubkey| parse::pubkey(pubkey)).collect()
+    };
+    let params = InitOneSigParams {
+        one_sig_id: config.one_sig_id,
+        seed: parse::hash(&config.seed)?,
+        threshold: config.threshold,
+        signers: config
+            .signers
+            .iter()
+            .map(|signer| parse::address(signer))
+            .collect::<Result<_>>()?,
+        executors: pubkeys(&config.executors)?,
+        executor_required: config.executor_required,
+        signers_capacity: config.signers_capacity,
+    };
+
+    let mut follow_ups = vec![];
+    let set_config_leaf =
+        |params| client.resolve_instruction(&client.set_config(params), 0, 0).instruction;
+    let policy = parse::target_policy(&config.target_policy)?;
+    if policy != TargetPolicy::None || !config.target_programs.is_empty() {
+        let programs = pubkeys(&config.target_programs)?;
+        let params = SetTargetProgramsParams { policy, programs };
+        follow_ups.push(set_config_leaf(SetConfigParams::SetTargetPrograms(params)));
+    }
+    if config.executor_reimbursement > 0 {
+        let params = SetConfigParams::SetExecutorReimbursement(config.executor_reimbursement);
+        follow_ups.push(set_config_leaf(params));
+    }
+    if let Some(genesis_hash) = &config.cluster_genesis_hash {
+        let params = SetConfigParams::SetClusterGenesisHash(parse::hash(genesis_hash)?);
+        follow_ups.push(set_config_leaf(params));
+    }
+    // The limits are set by calling back into the program, which new accounts only allow once
+    // they opt in
+    if !config.spending_limits.is_empty() {
+        let targets = vec![SelfCpiTarget::SpendingLimits];
+        follow_ups.push(set_config_leaf(SetConfigParams::SetSelfCpiTargets(targets)));
+    }
+    // Each new entry grows the account by one limit, whose rent the leaf spends
+    let rent = Rent::default();
+    let entry_rent = rent.minimum_balance(SpendingLimits::space(1)) -
+        rent.minimum_balance(SpendingLimits::space(0));
+    for limit in &config.spending_limits {
+        let params = SetSpendingLimitParams {
+            mint: parse::pubkey(&limit.mint)?,
+            amount: limit.amount,
+            window: limit.window,
+        };
+        let instruction = client.set_spending_limit(params);
+        follow_ups.push(client.resolve_instruction(&instruction, 0, entry_rent).instruction);
+    }
+    Ok((params, follow_ups))
+}
@@ -0,0 +1,112 @@
+// Tests for signing snapshots and re-creating accounts from them.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, Discriminator};
+    use bytemuck::Zeroable;
+    use onesig::{
+        instruction, Address, Hash, OneSigState, SpendingLimit, SpendingLimits,
+        ONE_SIG_STATE_VERSION,
+    };
+    use onesig_sdk::{OneSigAccount, OneSigClient, TransactionSigner};
+
+    use crate::{
+        commands::snapshot::{
+            instance_config, restore, SignedSnapshot, Snapshot, SNAPSHOT_VERSION,
+        },
+        keypair::Keypair,
+    };
+
+    fn account() -> OneSigAccount {
+        let mut header = OneSigState::zeroed();
+        header.one_sig_id = 3;
+        header.nonce = 12;
+        header.seed = Hash([4; 32]);
+        header.threshold = 2;
+        header.signers_len = 2;
+        header.signers_capacity = 5;
+        header.executor_required = 1;
+        header.target_policy = 1;
+        header.version = ONE_SIG_STATE_VERSION;
+        let signers = vec![Address([0xaa; 20]), Address([0xbb; 20])];
+        OneSigAccount { address: Pubkey::new_unique(), header, signers }
+    }
+
+    fn snapshot(account: &OneSigAccount, executors: &[Pubkey]) -> Snapshot {
+        let limits = SpendingLimits {
+            entries: vec![SpendingLimit {
+                mint: Pubkey::default(),
+                amount: 1_000,
+                window: 3600,
+                window_start: 0,
+                spent: 10,
+            }],
+            bump: 255,
+            executor_reimbursement: 5_000,
+        };
+        let programs = [Pubkey::new_from_array([9; 32])];
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            program_id: onesig::ID.to_string(),
+            state: account.address.to_string(),
+            exported_at: 1_700_000_000,
+            config: instance_config(account, executors, &programs, Some(&limits)),
+            merkle_roots: vec![],
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        let keypair = Keypair::from_seed(&[1; 32]);
+        let signed = SignedSnapshot::sign(snapshot(&account(), &[]), &keypair).unwrap();
+        let json = serde_json::to_string_pretty(&signed).unwrap();
+        let signed: SignedSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(signed.verify().unwrap().to_string(), signed.signer);
+
+        let mut tampered = serde_json::from_str::<SignedSnapshot>(&json).unwrap();
+        tampered.snapshot.config.threshold = 1;
+        assert!(tampered.verify().is_err());
+        let mut tampered = serde_json::from_str::<SignedSnapshot>(&json).unwrap();
+        tampered.signer = Keypair::from_seed(&[2; 32]).pubkey().to_string();
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_restore() {
+        let account = account();
+        let executor = Pubkey::new_unique();
+        let snapshot = snapshot(&account, &[executor]);
+        let client = OneSigClient::new(Pubkey::new_unique());
+
+        let (params, follow_ups) = restore(&client, &snapshot.config).unwrap();
+        assert_eq!(params.one_sig_id, 3);
+        assert!(params.seed == Hash([4; 32]));
+        assert_eq!(params.threshold, 2);
+        assert!(params.signers == account.signers);
+        assert_eq!(params.signers_capacity, 5);
+        assert_eq!(params.executors, [executor]);
+        assert!(params.executor_required);
+
+        // Target programs, reimbursement, then the spending limit once leaves may set it, whose
+        // leaf pays its rent
+        let discriminators: Vec<&[u8]> =
+            follow_ups.iter().map(|instruction| &instruction.data[..8]).collect();
+        assert_eq!(
+            discriminators,
+            [
+                instruction::SetConfig::DISCRIMINATOR,
+                instruction::SetConfig::DISCRIMINATOR,
+                instruction::SetConfig::DISCRIMINATOR,
+                instruction::SetSpendingLimit::DISCRIMINATOR,
+            ]
+        );
+        assert_eq!(follow_ups[0].value, 0);
+        assert!(follow_ups[3].value > 0);
+
+        let mut bare = snapshot.config.clone();
+        bare.target_policy = "none".into();
+        bare.target_programs = vec![];
+        bare.executor_reimbursement = 0;
+        bare.spending_limits = vec![];
+        assert!(restore(&client, &bare).unwrap().1.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs;
+
+use anyhow::{bail, Context as _, Result};
+use onesig::Hash;
+use onesig_sdk::{merkle_root_message, Leaf, LeafProof};
+
+use crate::{args::Args, parse};
+
+/// `verify-proof --leaf <path> --root <hex> [--proof <hex>...] [--seed <hex> --expiry <unix>
+/// [--max-executions <n>]]`: recomputes the hash of the leaf a JSON file describes and checks its
+/// proof folds it into the root, without any network access. Given the seed and expiry, it also
+/// prints the EIP-712 hashes a Ledger shows when signing the root.
+pub fn run(mut args: Args) -> Result<()> {
+    let path = args.required("leaf")?;
+    let merkle_root = parse::hash(&args.required("root")?)?;
+    let proof = args
+        .values("proof")?
+        .iter()
+        .map(|hash| parse::hash(hash))
+        .collect::<Result<_>>()?;
+    let seed = args.value("seed")?.map(|seed| parse::hash(&seed)).transpose()?;
+    let expiry = args.parsed("expiry")?;
+    let max_executions = args.parsed("max-executions")?.unwrap_or(0);
+    args.finish()?;
+
+    let json = fs::read_to_string(&path).with_context(|| format!("cannot read {path}"))?;
+    let leaf: Leaf = serde_json::from_str(&json).with_context(|| format!("invalid leaf {path}"))?;
+    let proof = verify(&leaf, merkle_root, proof)?;
+    println!("leaf          0x{}", hex::encode(proof.leaf.0));
+    println!("merkle root   0x{}", hex::encode(proof.merkle_root.0));
+    println!("the proof of nonce {} of OneSig {} is valid", leaf.nonce(), leaf.one_sig_id());
+    match (seed, expiry) {
+        (Some(seed), Some(expiry)) => {
+            let message = merkle_root_message(&seed, &merkle_root, expiry, max_executions);
+            println!("domain hash   0x{}", hex::encode(message.domain_separator.0));
+            println!("message hash  0x{}", hex::encode(message.struct_hash.0));
+            println!("digest        0x{}", hex::encode(message.digest().0));
+        },
+        (None, None) => {},
+        _ => bail!("give both --seed and --expiry to print the hashes to sign"),
+    }
+    Ok(())
+}
+
+/// Checks `proof` folds the hash of `leaf` into `merkle_root`.
+pub fn verify(leaf: &Leaf, merkle_root: Hash, proof: Vec<Hash>) -> Result<LeafProof> {
+    let proof = LeafProof { merkle_root, leaf: leaf.hash(), proof };
+    if !proof.verify() {
+        bail!("the proof does not prove leaf 0x{} under the root", hex::encode(proof.leaf.0));
+    }
+    Ok(proof)
+}
@@ -0,0 +1,67 @@
+// Tests for checking the proof of a leaf described in JSON against a root.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::Hash;
+    use onesig_sdk::{
+        EvmCall, EvmInstance, Leaf, OneSigInstruction, ProposalMetadata, SolanaInstance,
+        SolanaLeafGuards, TreeCoordinator,
+    };
+
+    use crate::commands::verify_proof::verify;
+
+    #[test]
+    fn test_verify() {
+        let mut coordinator = TreeCoordinator::new();
+        let solana = coordinator.add_solana_instance(SolanaInstance {
+            chain_id: "solana:test".into(),
+            one_sig_state: Pubkey::new_unique(),
+            one_sig_id: 1,
+            nonce: 0,
+            cluster_genesis_hash: Some(Hash([3; 32])),
+        });
+        let evm = coordinator.add_evm_instance(EvmInstance {
+            chain_id: "eip155:1".into(),
+            one_sig_address: [0x11; 20],
+            one_sig_id: 2,
+            nonce: 0,
+        });
+        for data in 0..2 {
+            let instruction = OneSigInstruction {
+                program_id: Pubkey::new_from_array([9; 32]),
+                accounts: vec![],
+                data: vec![data],
+                value: 0,
+            };
+            coordinator
+                .push_solana(solana, instruction, SolanaLeafGuards::default())
+                .unwrap();
+        }
+        let call = EvmCall { to: [0x22; 20], value: 1, data: vec![] };
+        coordinator.push_evm(evm, vec![call]).unwrap();
+        let bundle = coordinator
+            .bundle(ProposalMetadata {
+                title: "Batch".into(),
+                description: String::new(),
+                seed: Hash([0; 32]),
+                expiry: 0,
+                max_executions: 0,
+            })
+            .unwrap();
+
+        for (leaf, proof) in bundle.leaves.iter().zip(&bundle.proofs) {
+            // As a signer would describe it in the leaf file
+            let json = serde_json::to_string(leaf).unwrap();
+            let leaf: Leaf = serde_json::from_str(&json).unwrap();
+            let verified = verify(&leaf, bundle.merkle_root, proof.proof.clone()).unwrap();
+            assert!(verified.leaf == leaf.hash());
+        }
+
+        let proof = bundle.proofs[0].proof.clone();
+        let Leaf::Solana(mut leaf) = bundle.leaves[0].clone() else { panic!("not a Solana leaf") };
+        assert!(verify(&Leaf::Solana(leaf.clone()), Hash([0; 32]), proof.clone()).is_err());
+        assert!(verify(&Leaf::Solana(leaf.clone()), bundle.merkle_root, vec![]).is_err());
+        leaf.cluster_genesis_hash = None;
+        assert!(verify(&Leaf::Solana(leaf), bundle.merkle_root, proof).is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{bail, Result};
+use onesig::VerifyMerkleRootParams;
+use onesig_sdk::{pack_signatures, OneSigAccount, ProposalBundle, TransactionSigner};
+
+use crate::{args::Args, context::Context};
+
+/// `verify-root --state <pubkey> --bundle <path>`: verifies the root of a proposal bundle with
+/// its signatures and records it in a `MerkleRootState`, so its leaves execute without carrying
+/// them.
+pub fn run(context: &Context, mut args: Args) -> Result<()> {
+    let state = context.state(&args.required("state")?)?;
+    let bundle = ProposalBundle::load(args.required("bundle")?)?;
+    args.finish()?;
+
+    let account = context.one_sig_account(&state)?;
+    let client = context.client(state);
+    let merkle_root_state = client.pda.merkle_root_state(&bundle.merkle_root).0;
+    if context.rpc().account_data(&merkle_root_state)?.is_some() {
+        bail!("the root is already verified in {merkle_root_state}");
+    }
+    let payer = context.payer()?;
+    let ix = client.verify_merkle_root(&payer.pubkey(), params(&account, &bundle)?);
+    let confirmed = context.send(&payer, &[ix], &[])?;
+    println!("verified 0x{} in {}", hex::encode(bundle.merkle_root.0), confirmed.signature);
+    println!("merkle_root_state     {merkle_root_state}");
+    Ok(())
+}
+
+/// `verify_merkle_root` parameters of `bundle`, with as many of its signatures as the threshold
+/// of `account` takes.
+pub fn params(account: &OneSigAccount, bundle: &ProposalBundle) -> Result<VerifyMerkleRootParams> {
+    Ok(VerifyMerkleRootParams {
+        merkle_root: bundle.merkle_root,
+        expiry: bundle.metadata.expiry.try_into()?,
+        max_executions: bundle.metadata.max_executions,
+        signatures: pack_signatures(
+            &bundle.digest(),
+            bundle.signatures.iter().cloned(),
+            &account.signers,
+            account.header.threshold,
+        )?,
+    })
+}
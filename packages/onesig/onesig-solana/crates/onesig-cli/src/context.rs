@@ -0,0 +1,221 @@
+use std::{collections::BTreeMap, env, error::Error, fmt, path::PathBuf, sync::OnceLock};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, AccountDeserialize};
+use anyhow::{anyhow, bail, Result};
+use onesig_sdk::{
+    tip_instruction, Commitment, ComputeBudgetOptions, Confirmed, JitoClient, Message,
+    OneSigAccount, OneSigClient, PreflightError, RpcClient, SubmitConfig, Submitter,
+    TransactionSigner,
+};
+
+use crate::{
+    args::Args,
+    keypair::Keypair,
+    parse,
+    profile::{expand_home, home, Profile},
+    transport::CurlTransport,
+};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+
+/// What every command needs from the global options: the node to talk to, the program, and the
+/// keypair paying for transactions.
+pub struct Context {
+    submitter: Submitter<CurlTransport>,
+    pub program_id: Pubkey,
+    keypair: PathBuf,
+    budget: ComputeBudgetOptions,
+    /// OneSig accounts named in the profile
+    instances: BTreeMap<String, Pubkey>,
+    jito: Option<Jito>,
+}
+
+/// A Jito block engine transactions are sent to in bundles, rather than to the RPC endpoint
+struct Jito {
+    client: JitoClient<CurlTransport>,
+    /// Lamports each transaction tips
+    tip: u64,
+    /// Fetched with the first transaction
+    tip_accounts: OnceLock<Vec<Pubkey>>,
+}
+
+impl Context {
+    /// Reads `--url` (or `ONESIG_RPC_URL`), `--commitment`, `--program-id`, `--keypair`, which
+    /// defaults to the Solana CLI's, and `--max-unit-price`, which prices transactions from
+    /// recent fees when set. Options left out are taken from the profile of `--profile` (or
+    /// `ONESIG_PROFILE`) when one is selected.
+    pub fn from_args(args: &mut Args) -> Result<Self> {
+        let config = args.value("config")?;
+        let profile = match args.value("profile")?.or_else(|| env::var("ONESIG_PROFILE").ok()) {
+            Some(name) => Profile::load(config, &name)?,
+            None => Profile::default(),
+        };
+        Self::with_profile(args, profile)
+    }
+
+    pub fn with_profile(args: &mut Args, profile: Profile) -> Result<Self> {
+        let url = match args.value("url")? {
+            Some(url) => url,
+            None => env::var("ONESIG_RPC_URL")
+                .ok()
+                .or(profile.url)
+                .unwrap_or_else(|| DEFAULT_URL.into()),
+        };
+        let commitment = match args.value("commitment")?.or(profile.commitment).as_deref() {
+            None | Some("confirmed") => Commitment::Confirmed,
+            Some("processed") => Commitment::Processed,
+            Some("finalized") => Commitment::Finalized,
+            Some(commitment) => bail!("unknown commitment {commitment}"),
+        };
+        let program_id = match args.value("program-id")?.or(profile.program_id) {
+            Some(program_id) => parse::pubkey(&program_id)?,
+            None => onesig::ID,
+        };
+        let keypair = match args.value("keypair")?.or(profile.keypair) {
+            Some(keypair) => expand_home(&keypair)?,
+            None =>
+                home().map_err(|_| anyhow!("missing --keypair"))?.join(".config/solana/id.json"),
+        };
+        let budget = match args.parsed("max-unit-price")?.or(profile.max_unit_price) {
+            Some(max_unit_price) => ComputeBudgetOptions::estimated(max_unit_price),
+            None => ComputeBudgetOptions::default(),
+        };
+        let instances = profile
+            .instances
+            .iter()
+            .map(|(name, state)| Ok((name.clone(), parse::pubkey(state)?)))
+            .collect::<Result<_>>()?;
+        let rpc = RpcClient::new(CurlTransport::new(url), commitment);
+        let config = SubmitConfig { commitment, ..SubmitConfig::default() };
+        Ok(Self {
+            submitter: Submitter::new(vec![rpc], config),
+            program_id,
+            keypair,
+            budget,
+            instances,
+            jito: None,
+        })
+    }
+
+    /// Sends transactions in bundles of the Jito block engine at `url`, each tipping `tip`
+    /// lamports, so they land without going through the public transaction queue. The RPC
+    /// endpoint still simulates them and tracks their confirmation.
+    pub fn with_jito(mut self, url: impl Into<String>, tip: u64) -> Self {
+        let client = JitoClient::new(CurlTransport::new(url));
+        self.jito = Some(Jito { client, tip, tip_accounts: OnceLock::new() });
+        self
+    }
+
+    /// Lamports each transaction tips, when they are sent through Jito
+    pub fn jito_tip(&self) -> Option<u64> {
+        self.jito.as_ref().map(|jito| jito.tip)
+    }
+
+    /// A OneSig account, by its name in the profile or its address.
+    pub fn state(&self, state: &str) -> Result<Pubkey> {
+        match self.instances.get(state) {
+            Some(state) => Ok(*state),
+            None => parse::pubkey(state),
+        }
+    }
+
+    /// OneSig accounts of the profile, by name
+    pub fn instances(&self) -> &BTreeMap<String, Pubkey> {
+        &self.instances
+    }
+
+    pub fn rpc(&self) -> &RpcClient<CurlTransport> {
+        &self.submitter.endpoints()[0]
+    }
+
+    pub fn payer(&self) -> Result<Keypair> {
+        Keypair::read(&self.keypair)
+    }
+
+    pub fn client(&self, state: Pubkey) -> OneSigClient {
+        OneSigClient::with_program_id(self.program_id, state)
+    }
+
+    pub fn one_sig_account(&self, state: &Pubkey) -> Result<OneSigAccount> {
+        self.rpc()
+            .one_sig_account(state)?
+            .ok_or_else(|| anyhow!("no OneSig account at {state}"))
+    }
+
+    /// Fetches and decodes the Anchor account at `address`, if it exists.
+    pub fn anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let Some(data) = self.rpc().account_data(address)? else {
+            return Ok(None);
+        };
+        let account = T::try_deserialize(&mut data.as_slice())
+            .map_err(|error| anyhow!("cannot decode {address}: {error}"))?;
+        Ok(Some(account))
+    }
+
+    /// Simulates `instructions`, failing with the decoded error if the program would reject them,
+    /// then signs and submits them and waits until they reach the commitment.
+    pub fn send(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        signers: &[&dyn TransactionSigner],
+    ) -> Result<Confirmed> {
+        let rpc = self.rpc();
+        let preflight = rpc.preflight(&self.program_id, &payer.pubkey(), instructions)?;
+        if let Some(error) = preflight.error {
+            return Err(SimulationFailed { error, logs: preflight.logs }.into());
+        }
+        let blockhash = rpc.latest_blockhash()?;
+        let mut instructions = instructions.to_vec();
+        if let Some(jito) = &self.jito {
+            // Tipping in the transaction itself, only a landed transaction pays the tip
+            let tip_account = jito.tip_account(&blockhash)?;
+            instructions.push(tip_instruction(&payer.pubkey(), &tip_account, jito.tip));
+        }
+        let instructions =
+            rpc.with_compute_budget(&payer.pubkey(), &instructions, &[], &self.budget)?;
+        let message = Message::new(&payer.pubkey(), &instructions, blockhash)?;
+        let transaction = message.sign(&[&[payer as &dyn TransactionSigner], signers].concat())?;
+        let confirmed = match &self.jito {
+            Some(jito) => self.submitter.submit_via(&transaction, |transaction| {
+                jito.client.send_bundle(&[transaction.to_vec()]).map(drop)
+            })?,
+            None => self.submitter.submit(&transaction)?,
+        };
+        Ok(confirmed)
+    }
+}
+
+impl Jito {
+    /// One of the tip accounts of the block engine, picked by `blockhash` so that transactions
+    /// spread their tips over them rather than all contending for the same account
+    fn tip_account(&self, blockhash: &[u8; 32]) -> Result<Pubkey> {
+        let accounts = match self.tip_accounts.get() {
+            Some(accounts) => accounts,
+            None => {
+                let accounts = self.client.tip_accounts()?;
+                self.tip_accounts.get_or_init(|| accounts)
+            },
+        };
+        if accounts.is_empty() {
+            bail!("the Jito block engine has no tip account");
+        }
+        Ok(accounts[blockhash[0] as usize % accounts.len()])
+    }
+}
+
+/// The error of [`Context::send`] when the simulation predicts the transaction fails, which
+/// callers keeping statistics downcast to.
+#[derive(Debug)]
+pub struct SimulationFailed {
+    pub error: PreflightError,
+    pub logs: Vec<String>,
+}
+
+impl fmt::Display for SimulationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "simulation failed: {:?}\n{}", self.error, self.logs.join("\n"))
+    }
+}
+
+impl Error for SimulationFailed {}
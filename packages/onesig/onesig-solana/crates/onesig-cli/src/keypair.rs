@@ -0,0 +1,61 @@
+use std::{fs, path::Path};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use onesig_sdk::{SdkError, TransactionSigner, TRANSACTION_SIGNATURE_LEN};
+
+/// An ed25519 keypair, as the Solana CLI writes them: a JSON array of the 32-byte secret seed
+/// followed by the 32-byte public key.
+pub struct Keypair(SigningKey);
+
+impl Keypair {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self(SigningKey::from_bytes(seed))
+    }
+
+    /// Parses a keypair file, checking its public key is the one of its seed.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let bytes: Vec<u8> = serde_json::from_str(json).context("invalid keypair")?;
+        let Ok(bytes) = <[u8; 64]>::try_from(bytes) else {
+            bail!("a keypair is 64 bytes");
+        };
+        let Ok(signing_key) = SigningKey::from_keypair_bytes(&bytes) else {
+            bail!("the public key of the keypair is not the one of its secret");
+        };
+        Ok(Self(signing_key))
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("cannot read keypair {}", path.display()))?;
+        Self::from_json(&json).with_context(|| format!("in keypair {}", path.display()))
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; TRANSACTION_SIGNATURE_LEN] {
+        self.0.sign(message).to_bytes()
+    }
+}
+
+/// Whether `signature` is a valid ed25519 signature of `message` by `pubkey`
+pub fn verify(
+    pubkey: &Pubkey,
+    message: &[u8],
+    signature: &[u8; TRANSACTION_SIGNATURE_LEN],
+) -> bool {
+    let Ok(verifying_key) = VerifyingKey::from_bytes(&pubkey.to_bytes()) else {
+        return false;
+    };
+    verifying_key.verify(message, &Signature::from_bytes(signature)).is_ok()
+}
+
+impl TransactionSigner for Keypair {
+    fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.0.verifying_key().to_bytes())
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<[u8; TRANSACTION_SIGNATURE_LEN], SdkError> {
+        Ok(self.sign(message))
+    }
+}
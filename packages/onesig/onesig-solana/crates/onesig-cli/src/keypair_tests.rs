@@ -0,0 +1,56 @@
+// Tests for ed25519 keypairs, against the vectors of RFC 8032.
+#[cfg(test)]
+mod tests {
+    use onesig_sdk::TransactionSigner;
+
+    use crate::keypair::{verify, Keypair};
+
+    fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        let vectors = [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                "",
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                "72",
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+        ];
+        for (seed, pubkey, message, signature) in vectors {
+            let keypair = Keypair::from_seed(&bytes(seed));
+            assert_eq!(keypair.pubkey().to_bytes(), bytes::<32>(pubkey));
+            let signature: [u8; 64] = bytes(signature);
+            let message = hex::decode(message).unwrap();
+            assert_eq!(keypair.sign_message(&message).unwrap(), signature);
+            assert!(verify(&keypair.pubkey(), &message, &signature));
+
+            let mut tampered = signature;
+            tampered[0] ^= 1;
+            assert!(!verify(&keypair.pubkey(), &message, &tampered));
+            assert!(!verify(&keypair.pubkey(), b"other message", &signature));
+        }
+    }
+
+    #[test]
+    fn test_from_json() {
+        let seed = [7u8; 32];
+        let pubkey = Keypair::from_seed(&seed).pubkey();
+        let file = [seed, pubkey.to_bytes()].concat();
+        let keypair = Keypair::from_json(&serde_json::to_string(&file).unwrap()).unwrap();
+        assert_eq!(keypair.pubkey(), pubkey);
+
+        // A public key that is not the one of the seed
+        let mismatched = [seed, [0; 32]].concat();
+        assert!(Keypair::from_json(&serde_json::to_string(&mismatched).unwrap()).is_err());
+        assert!(Keypair::from_json("[1, 2, 3]").is_err());
+    }
+}
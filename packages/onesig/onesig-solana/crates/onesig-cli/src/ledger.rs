@@ -0,0 +1,152 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, bail, Result};
+use onesig_sdk::{LedgerTransport, SdkError};
+
+/// USB vendor id of Ledger devices
+const LEDGER_VENDOR_ID: &str = "00002C97";
+/// Size of the HID reports APDUs are split into
+pub const HID_PACKET_LEN: usize = 64;
+const CHANNEL: u16 = 0x0101;
+const TAG_APDU: u8 = 0x05;
+/// Channel, tag and sequence number heading every packet
+const HEADER_LEN: usize = 5;
+
+/// [`LedgerTransport`] over the Linux `hidraw` interface of a Ledger plugged in over USB, which
+/// needs no HID library: APDUs are split into 64-byte reports as the Ledger HID protocol frames
+/// them. Other platforms have no `hidraw`, and fail to open it.
+pub struct LedgerHid {
+    device: File,
+}
+
+impl LedgerHid {
+    /// Opens the first Ledger found among the `hidraw` devices.
+    pub fn open() -> Result<Self> {
+        if !cfg!(target_os = "linux") {
+            bail!(
+                "signing with a Ledger is only supported on Linux, which exposes it through hidraw"
+            );
+        }
+        let path = find_device()?;
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(|error| anyhow!("cannot open {}: {error}", path.display()))?;
+        Ok(Self { device })
+    }
+}
+
+impl LedgerTransport for LedgerHid {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SdkError> {
+        let transport_error = |error: std::io::Error| SdkError::Signer(error.to_string());
+        let mut device = &self.device;
+        for packet in frame(apdu) {
+            // hidraw takes the report id first, 0 for devices without numbered reports
+            let mut report = vec![0];
+            report.extend_from_slice(&packet);
+            device.write_all(&report).map_err(transport_error)?;
+        }
+        let mut response = Response::default();
+        loop {
+            let mut packet = [0u8; HID_PACKET_LEN];
+            device.read_exact(&mut packet).map_err(transport_error)?;
+            if let Some(response) =
+                response.push(&packet).map_err(|error| SdkError::Signer(error.to_string()))?
+            {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+fn find_device() -> Result<PathBuf> {
+    let entries = fs::read_dir("/sys/class/hidraw")
+        .map_err(|error| anyhow!("cannot list HID devices: {error}"))?;
+    for entry in entries.flatten() {
+        let uevent = fs::read_to_string(entry.path().join("device/uevent")).unwrap_or_default();
+        // HID_ID=<bus>:<vendor>:<product>
+        let vendor = uevent
+            .lines()
+            .find_map(|line| line.strip_prefix("HID_ID="))
+            .and_then(|id| id.split(':').nth(1));
+        if vendor.is_some_and(|vendor| vendor.eq_ignore_ascii_case(LEDGER_VENDOR_ID)) {
+            return Ok(PathBuf::from("/dev").join(entry.file_name()));
+        }
+    }
+    bail!("no Ledger found, is it plugged in and unlocked, with the Ethereum app open?")
+}
+
+/// Splits `apdu` into HID packets, its length heading the data of the first one.
+pub fn frame(apdu: &[u8]) -> Vec<[u8; HID_PACKET_LEN]> {
+    let mut data = (apdu.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(apdu);
+    data.chunks(HID_PACKET_LEN - HEADER_LEN)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = [0u8; HID_PACKET_LEN];
+            packet[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+            packet[2] = TAG_APDU;
+            packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            packet[HEADER_LEN..][..chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// A response reassembled from its HID packets
+#[derive(Debug, Default)]
+pub struct Response {
+    len: Option<usize>,
+    data: Vec<u8>,
+    sequence: u16,
+}
+
+impl Response {
+    /// Adds the next packet, returning the whole response once it is complete.
+    pub fn push(&mut self, packet: &[u8; HID_PACKET_LEN]) -> Result<Option<Vec<u8>>> {
+        if packet[..2] != CHANNEL.to_be_bytes() || packet[2] != TAG_APDU {
+            bail!("unexpected Ledger packet");
+        }
+        if u16::from_be_bytes([packet[3], packet[4]]) != self.sequence {
+            bail!("Ledger packet out of sequence");
+        }
+        self.sequence += 1;
+        let mut chunk = &packet[HEADER_LEN..];
+        let len = match self.len {
+            Some(len) => len,
+            None => {
+                let len = u16::from_be_bytes([chunk[0], chunk[1]]) as usize;
+                chunk = &chunk[2..];
+                *self.len.insert(len)
+            },
+        };
+        let missing = len - self.data.len();
+        self.data.extend_from_slice(&chunk[..missing.min(chunk.len())]);
+        Ok((self.data.len() == len).then(|| std::mem::take(&mut self.data)))
+    }
+}
+
+/// Parses a BIP-32 derivation path, e.g. `m/44'/60'/0'/0/0`.
+pub fn derivation_path(path: &str) -> Result<Vec<u32>> {
+    let invalid = || anyhow!("invalid derivation path {path}");
+    let indexes = path.strip_prefix("m/").ok_or_else(invalid)?;
+    indexes
+        .split('/')
+        .map(|index| {
+            let (index, hardened) = match index.strip_suffix('\'').or(index.strip_suffix('h')) {
+                Some(index) => (index, 0x8000_0000),
+                None => (index, 0),
+            };
+            let index: u32 = index.parse().map_err(|_| invalid())?;
+            if index >= 0x8000_0000 {
+                return Err(invalid());
+            }
+            Ok(index | hardened)
+        })
+        .collect()
+}
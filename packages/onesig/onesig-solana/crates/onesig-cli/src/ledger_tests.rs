@@ -0,0 +1,46 @@
+// Tests for the HID framing of Ledger APDUs and derivation paths.
+#[cfg(test)]
+mod tests {
+    use crate::ledger::{derivation_path, frame, Response, HID_PACKET_LEN};
+
+    #[test]
+    fn test_frame() {
+        let apdu: Vec<u8> = (0..130).collect();
+        let packets = frame(&apdu);
+        // 2 length bytes and 130 bytes of APDU, 59 per packet
+        assert_eq!(packets.len(), 3);
+        assert_eq!(packets[0][..7], [0x01, 0x01, 0x05, 0, 0, 0, 130]);
+        assert_eq!(packets[0][7..], apdu[..57]);
+        assert_eq!(packets[2][..5], [0x01, 0x01, 0x05, 0, 2]);
+        assert_eq!(packets[2][5..19], apdu[116..]);
+        assert!(packets[2][19..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_response() {
+        // Responses are framed as requests are
+        let data: Vec<u8> = (0..100).map(|byte| byte as u8 ^ 0x5a).collect();
+        let packets = frame(&data);
+        let mut response = Response::default();
+        assert_eq!(response.push(&packets[0]).unwrap(), None);
+        assert_eq!(response.push(&packets[1]).unwrap(), Some(data));
+
+        let mut response = Response::default();
+        assert!(response.push(&packets[1]).is_err());
+        let mut other_channel = [0u8; HID_PACKET_LEN];
+        other_channel[2] = 0x05;
+        assert!(Response::default().push(&other_channel).is_err());
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        assert_eq!(
+            derivation_path("m/44'/60'/0'/0/1").unwrap(),
+            [0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 1]
+        );
+        assert_eq!(derivation_path("m/44h/60h").unwrap(), [0x8000_002c, 0x8000_003c]);
+        assert!(derivation_path("44'/60'").is_err());
+        assert!(derivation_path("m/x").is_err());
+        assert!(derivation_path("m/2147483648").is_err());
+    }
+}
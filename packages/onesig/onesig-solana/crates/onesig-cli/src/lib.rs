@@ -0,0 +1,20 @@
+//! The parts of `onesig-cli` that services running OneSig operations reuse: argument parsing,
+//! profiles, the keypair and `curl` transport the CLI sends transactions with, and its commands.
+
+pub mod args;
+pub mod commands;
+pub mod context;
+pub mod keypair;
+pub mod ledger;
+pub mod parse;
+pub mod profile;
+pub mod transport;
+
+#[cfg(test)]
+mod args_tests;
+#[cfg(test)]
+mod keypair_tests;
+#[cfg(test)]
+mod ledger_tests;
+#[cfg(test)]
+mod profile_tests;
@@ -0,0 +1,115 @@
+//! `onesig-cli`: administration of OneSig accounts from the command line.
+//!
+//! Commands derive the PDAs of an account, build its instructions with the SDK's
+//! [`OneSigClient`](onesig_sdk::OneSigClient), sign them with a Solana CLI keypair file and send
+//! them through `curl`, then print the resulting state. `propose` builds a proposal bundle from a
+//! file describing what to execute on each chain, `sign` adds the signature of a Ledger to it,
+//! and `execute` runs its leaves in nonce order. `verify-root` records the signed root of a bundle
+//! on chain and `close-root` reclaims the rent of the roots that can no longer execute. `decode`
+//! prints any account of the program. `export` and `import` save an account to a signed snapshot
+//! and re-create it from one. `sign` and `verify-proof` need no network, so signers can run them
+//! on an air-gapped machine.
+
+use std::{env, process::ExitCode};
+
+use anyhow::{bail, Result};
+use onesig_cli::{args::Args, commands, context::Context};
+
+const USAGE: &str = "\
+usage: onesig-cli <command> [options]
+
+commands:
+  close-root --state <pubkey> (--root <hex> | --all-expired)
+       closes expired roots, or roots of a former seed, refunding their rent
+  decode <pubkey>
+       prints a OneSigState, MerkleRootState, MerkleRootRegistry or ExecutorState account
+  execute --state <pubkey> --bundle <path> [--all]
+       executes the leaf at the current nonce, or with --all every pending one
+  export --state <pubkey> --out <path> [--executor <pubkey>...]
+       writes the configuration and open roots of the account to a snapshot signed by --keypair
+  import --snapshot <path> --state-keypair <path> [--trusted-signer <pubkey>] [--out <path>]
+       re-creates the account of a snapshot, writing the leaves restoring the rest of its
+       configuration to a bundle, with the options of set-config
+  init --state-keypair <path> --one-sig-id <id> --seed <hex> --threshold <n>
+       --signer <address>... [--executor <pubkey>...] [--executor-required]
+       [--signers-capacity <n>]
+  plan --state <pubkey> --file <path> [--dry-run] [--out <path>]
+       [--title <title>] [--description <text>] [--expires-in <seconds>] [--chain-id <caip2>]
+       prints the leaves bringing the account to the signers, threshold, executors and
+       policies of a JSON or TOML file, simulating them with --dry-run
+  propose --file <path> --out <path>
+       the file is JSON, or TOML if its name ends in .toml
+  show --state <pubkey>
+  set-config <change> <value>... --state <pubkey> --out <path>
+       [--title <title>] [--description <text>] [--expires-in <seconds>] [--chain-id <caip2>]
+       changes: add-signer <address>, remove-signer <address>, set-threshold <n>,
+                set-seed <hex>, set-executor-required <true|false>,
+                set-target-programs <none|allow|deny> [<program>...],
+                set-executor-reimbursement <lamports>, set-cluster-genesis-hash <hash>,
+                reset-nonce <new seed hex>,
+                set-self-cpi-targets <none|executors|modules|spending-limits|resize-state|
+                close-one-sig|bounty-escrow>...,
+                set-execution-rate-limit <leaves> <window seconds> (0 for unlimited)
+  set-config --state <pubkey> --bundle <path>
+  sign --bundle <path> --ledger [--derivation-path <path>] [--out <path>]
+       signs with the Ethereum app of a Ledger [default path: m/44'/60'/0'/0/0]
+       Linux only: the Ledger is reached through hidraw, other platforms fail
+  verify-proof --leaf <path> --root <hex> [--proof <hex>...]
+       [--seed <hex> --expiry <unix> [--max-executions <n>]]
+       offline: checks the proof of the leaf a JSON file describes, and prints the hashes to sign
+  verify-root --state <pubkey> --bundle <path>
+       records the signed root of a bundle in a MerkleRootState
+
+options:
+  --profile <name>         defaults of the options below and names of OneSig accounts, which
+                           --state takes in place of addresses, from the [profiles.<name>]
+                           table of the configuration file, or ONESIG_PROFILE
+  --config <path>          configuration file, or ONESIG_CONFIG
+                           [default: ~/.config/onesig/config.toml]
+  --url <url>              RPC endpoint, or ONESIG_RPC_URL [default: http://127.0.0.1:8899]
+  --commitment <level>     processed, confirmed or finalized [default: confirmed]
+  --keypair <path>         fee payer [default: ~/.config/solana/id.json]
+  --program-id <pubkey>    OneSig deployment [default: the program's id]
+  --max-unit-price <n>     price transactions from recent fees, up to n micro-lamports per unit
+";
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(args: impl IntoIterator<Item = String>) -> Result<()> {
+    let mut args = Args::parse(args);
+    let command = args.positional();
+    if args.flag("help")? || matches!(command.as_deref(), None | Some("help")) {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    // Commands that work offline, e.g. on an air-gapped signing machine
+    match command.as_deref() {
+        Some("sign") => return commands::sign::run(args),
+        Some("verify-proof") => return commands::verify_proof::run(args),
+        _ => {},
+    }
+    let context = Context::from_args(&mut args)?;
+    match command.as_deref() {
+        Some("close-root") => commands::close_root::run(&context, args),
+        Some("decode") => commands::decode::run(&context, args),
+        Some("execute") => commands::execute::run(&context, args),
+        Some("export") => commands::snapshot::export(&context, args),
+        Some("import") => commands::snapshot::import(&context, args),
+        Some("init") => commands::init::run(&context, args),
+        Some("plan") => commands::plan::run(&context, args),
+        Some("propose") => commands::propose::run(&context, args),
+        Some("show") => commands::show::run(&context, args),
+        Some("set-config") => commands::set_config::run(&context, args),
+        Some("verify-root") => commands::verify_root::run(&context, args),
+        Some(command) => bail!("unknown command {command}\n\n{USAGE}"),
+        None => unreachable!(),
+    }
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use onesig::{Address, Hash, SelfCpiTarget, TargetPolicy};
+use onesig_sdk::EVM_ADDRESS_LEN;
+
+/// A 0x-prefixed or bare hex EVM address
+pub fn address(address: &str) -> Result<Address> {
+    Ok(Address(hex_bytes(address).with_context(|| format!("invalid address {address}"))?))
+}
+
+/// The 0x-prefixed or bare hex address of an EVM contract
+pub fn evm_address(address: &str) -> Result<[u8; EVM_ADDRESS_LEN]> {
+    hex_bytes(address).with_context(|| format!("invalid address {address}"))
+}
+
+/// A 0x-prefixed or bare hex 32-byte hash
+pub fn hash(hash: &str) -> Result<Hash> {
+    Ok(Hash(hex_bytes(hash).with_context(|| format!("invalid hash {hash}"))?))
+}
+
+pub fn pubkey(pubkey: &str) -> Result<Pubkey> {
+    pubkey.parse().map_err(|_| anyhow!("invalid public key {pubkey}"))
+}
+
+fn hex_bytes<const N: usize>(value: &str) -> Result<[u8; N]> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value))?;
+    let len = bytes.len();
+    bytes.try_into().map_err(|_| anyhow!("expected {N} bytes, got {len}"))
+}
+
+pub fn bool(value: &str) -> Result<bool> {
+    value.parse().map_err(|_| anyhow!("expected true or false, got {value}"))
+}
+
+pub fn target_policy(policy: &str) -> Result<TargetPolicy> {
+    Ok(match policy {
+        "none" => TargetPolicy::None,
+        "allow" => TargetPolicy::Allow,
+        "deny" => TargetPolicy::Deny,
+        policy => bail!("unknown target policy {policy}, expected none, allow or deny"),
+    })
+}
+
+pub fn self_cpi_target(target: &str) -> Result<SelfCpiTarget> {
+    Ok(match target {
+        "executors" => SelfCpiTarget::Executors,
+        "modules" => SelfCpiTarget::Modules,
+        "spending-limits" => SelfCpiTarget::SpendingLimits,
+        "resize-state" => SelfCpiTarget::ResizeState,
+        "close-one-sig" => SelfCpiTarget::CloseOneSig,
+        "bounty-escrow" => SelfCpiTarget::BountyEscrow,
+        target => bail!(
+            "unknown self-CPI target {target}, expected executors, modules, spending-limits, \
+             resize-state, close-one-sig or bounty-escrow"
+        ),
+    })
+}
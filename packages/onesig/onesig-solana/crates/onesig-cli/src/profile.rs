@@ -0,0 +1,71 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use anyhow::{anyhow, Context as _, Result};
+use serde::Deserialize;
+
+/// Settings of a cluster, which `--profile <name>` selects from the `[profiles.<name>]` tables
+/// of the configuration file, e.g.
+///
+/// ```toml
+/// [profiles.devnet]
+/// url = "https://api.devnet.solana.com"
+/// max-unit-price = 10000
+///
+/// [profiles.devnet.instances]
+/// treasury = "<state pubkey>"
+/// ```
+///
+/// Keys are named after the global options they default; `instances` names OneSig accounts so
+/// that `--state` and proposal files can refer to them by name on every cluster.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub url: Option<String>,
+    pub commitment: Option<String>,
+    pub program_id: Option<String>,
+    pub keypair: Option<String>,
+    pub max_unit_price: Option<u64>,
+    #[serde(default)]
+    pub instances: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profile {
+    /// The profile `name` of the configuration file at `--config`, `ONESIG_CONFIG` or else
+    /// `~/.config/onesig/config.toml`.
+    pub fn load(path: Option<String>, name: &str) -> Result<Self> {
+        let path = match path.or_else(|| env::var("ONESIG_CONFIG").ok()) {
+            Some(path) => PathBuf::from(path),
+            None => home()?.join(".config/onesig/config.toml"),
+        };
+        let toml = fs::read_to_string(&path)
+            .with_context(|| format!("cannot read the configuration file {}", path.display()))?;
+        Self::from_toml(&toml, name).with_context(|| format!("in {}", path.display()))
+    }
+
+    pub fn from_toml(toml: &str, name: &str) -> Result<Self> {
+        let mut file: ConfigFile = toml::from_str(toml)?;
+        file.profiles.remove(name).ok_or_else(|| {
+            let names: Vec<_> = file.profiles.keys().map(String::as_str).collect();
+            anyhow!("no profile {name}, the profiles are: {}", names.join(", "))
+        })
+    }
+}
+
+pub fn home() -> Result<PathBuf> {
+    env::var("HOME").map(PathBuf::from).map_err(|_| anyhow!("HOME is not set"))
+}
+
+/// `path` with a leading `~/` expanded to the home directory
+pub fn expand_home(path: &str) -> Result<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(path) => Ok(home()?.join(path)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
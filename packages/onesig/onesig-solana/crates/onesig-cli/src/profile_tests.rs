@@ -0,0 +1,61 @@
+// Tests for reading profiles and applying them to the global options.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+
+    use crate::{args::Args, context::Context, profile::Profile};
+
+    fn config() -> String {
+        format!(
+            r#"
+[profiles.devnet]
+url = "https://api.devnet.solana.com"
+commitment = "finalized"
+program-id = "{program_id}"
+keypair = "/keys/devnet.json"
+max-unit-price = 10000
+
+[profiles.devnet.instances]
+treasury = "{treasury}"
+
+[profiles.mainnet]
+"#,
+            program_id = Pubkey::new_from_array([1; 32]),
+            treasury = Pubkey::new_from_array([2; 32]),
+        )
+    }
+
+    fn args(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let devnet = Profile::from_toml(&config(), "devnet").unwrap();
+        assert_eq!(devnet.url.as_deref(), Some("https://api.devnet.solana.com"));
+        assert_eq!(devnet.max_unit_price, Some(10000));
+        assert_eq!(devnet.instances.len(), 1);
+        assert_eq!(Profile::from_toml(&config(), "mainnet").unwrap(), Profile::default());
+
+        let error = Profile::from_toml(&config(), "eclipse").unwrap_err().to_string();
+        assert!(error.contains("devnet, mainnet"), "{error}");
+        assert!(Profile::from_toml("[profiles.devnet]\nrpc = \"x\"", "devnet").is_err());
+    }
+
+    #[test]
+    fn test_with_profile() {
+        let profile = Profile::from_toml(&config(), "devnet").unwrap();
+        let context = Context::with_profile(&mut args(&[]), profile.clone()).unwrap();
+        assert_eq!(context.program_id, Pubkey::new_from_array([1; 32]));
+        assert_eq!(context.state("treasury").unwrap(), Pubkey::new_from_array([2; 32]));
+        let address = Pubkey::new_unique();
+        assert_eq!(context.state(&address.to_string()).unwrap(), address);
+        assert!(context.state("unknown").is_err());
+
+        // Options given on the command line win over the profile
+        let program_id = Pubkey::new_unique();
+        let mut args = args(&["--program-id", &program_id.to_string()]);
+        let context = Context::with_profile(&mut args, profile).unwrap();
+        assert_eq!(context.program_id, program_id);
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use onesig_sdk::{RpcTransport, SdkError};
+
+/// [`RpcTransport`] posting requests with the `curl` binary, which every operator machine has,
+/// so the CLI needs no HTTP or TLS stack of its own.
+#[derive(Clone, Debug)]
+pub struct CurlTransport {
+    url: String,
+    headers: Vec<String>,
+}
+
+impl CurlTransport {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), headers: vec![] }
+    }
+
+    /// Adds a `name: value` header to the requests, e.g. the signature of a webhook payload.
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.headers.push(header.into());
+        self
+    }
+}
+
+impl RpcTransport for CurlTransport {
+    fn post(&self, body: &str) -> Result<String, SdkError> {
+        let transport_error = |error: std::io::Error| SdkError::Transport(error.to_string());
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail-with-body", "--request", "POST"])
+            .args(["--header", "content-type: application/json", "--data-binary", "@-"])
+            .args(self.headers.iter().flat_map(|header| ["--header", header]))
+            .arg(&self.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(transport_error)?;
+        // The body goes through stdin, as transactions can outgrow the command line
+        curl.stdin
+            .take()
+            .expect("piped stdin")
+            .write_all(body.as_bytes())
+            .map_err(transport_error)?;
+        let output = curl.wait_with_output().map_err(transport_error)?;
+        if !output.status.success() {
+            return Err(SdkError::Transport(format!(
+                "{}: {}",
+                self.url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8(output.stdout)
+            .map_err(|_| SdkError::Transport(format!("{}: response is not UTF-8", self.url)))
+    }
+}
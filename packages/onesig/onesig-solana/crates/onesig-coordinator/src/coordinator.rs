@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use onesig::Address;
+use onesig_sdk::{
+    checksum_address, normalize_recovery_id, pack_signatures, recover_signer, split_signatures,
+    ProposalBundle, SdkError,
+};
+use serde_json::{json, Value};
+
+use crate::http::{Request, Response};
+
+/// Collects the signatures of the proposals proposers upload, one signer set for all of them.
+///
+/// Signers are authenticated by their signatures: one is only accepted if it recovers to a
+/// signer of the set, from the digest of the proposal. Uploads, which anyone could otherwise make
+/// signers review, take the bearer token of the service when it has one. Proposals are kept as
+/// bundle files in a directory, so the service restarts with the signatures collected so far.
+pub struct Coordinator {
+    dir: PathBuf,
+    signers: Vec<Address>,
+    threshold: u8,
+    token: Option<String>,
+    /// By 0x-prefixed merkle root
+    proposals: BTreeMap<String, ProposalBundle>,
+}
+
+impl Coordinator {
+    /// Opens the proposals of `dir`, creating it if needed.
+    pub fn open(
+        dir: PathBuf,
+        signers: Vec<Address>,
+        threshold: u8,
+        token: Option<String>,
+    ) -> Result<Self> {
+        fs::create_dir_all(&dir).with_context(|| format!("cannot create {}", dir.display()))?;
+        let mut proposals = BTreeMap::new();
+        for entry in fs::read_dir(&dir).with_context(|| format!("cannot read {}", dir.display()))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                let bundle = ProposalBundle::load(&path)
+                    .with_context(|| format!("invalid bundle {}", path.display()))?;
+                proposals.insert(root_key(&bundle), bundle);
+            }
+        }
+        Ok(Self { dir, signers, threshold, token, proposals })
+    }
+
+    pub fn handle(&mut self, request: &Request) -> Response {
+        match (request.method.as_str(), request.segments().as_slice()) {
+            ("GET", ["proposals"]) =>
+                Response::ok(self.proposals.values().map(|bundle| self.summary(bundle)).collect()),
+            ("POST", ["proposals"]) => self.upload(request),
+            ("GET", ["proposals", root]) => self.with_proposal(root, |this, bundle| {
+                let mut summary = this.summary(bundle);
+                summary["bundle"] = serde_json::to_value(bundle).unwrap_or_default();
+                Response::ok(summary)
+            }),
+            ("GET", ["proposals", root, "digest"]) => self.with_proposal(root, |_, bundle| {
+                let message = bundle.message();
+                Response::ok(json!({
+                    "merkleRoot": root_key(bundle),
+                    "seed": hex_hash(&bundle.metadata.seed.0),
+                    "expiry": bundle.metadata.expiry,
+                    "maxExecutions": bundle.metadata.max_executions,
+                    "domainSeparator": hex_hash(&message.domain_separator.0),
+                    "structHash": hex_hash(&message.struct_hash.0),
+                    "digest": hex_hash(&bundle.digest().0),
+                }))
+            }),
+            ("POST", ["proposals", root, "signatures"]) => self.add_signature(root, &request.body),
+            ("GET", ["proposals", root, "signatures"]) =>
+                self.with_proposal(root, |this, bundle| {
+                    match pack_signatures(
+                        &bundle.digest(),
+                        bundle.signatures.iter().cloned(),
+                        &this.signers,
+                        this.threshold,
+                    ) {
+                        Ok(packed) => Response::ok(json!({
+                            "merkleRoot": root_key(bundle),
+                            "signatures": format!("0x{}", hex::encode(packed)),
+                        })),
+                        Err(error) => Response::error(409, error.to_string()),
+                    }
+                }),
+            (_, ["proposals", ..]) => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found, see /proposals"),
+        }
+    }
+
+    fn upload(&mut self, request: &Request) -> Response {
+        if let Some(token) = &self.token {
+            let bearer =
+                request.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+            if bearer != Some(token.as_str()) {
+                return Response::error(401, "uploads take the bearer token of the service");
+            }
+        }
+        let bundle = match std::str::from_utf8(&request.body)
+            .map_err(|error| SdkError::Json(error.to_string()))
+            .and_then(ProposalBundle::from_json)
+        {
+            Ok(bundle) => bundle,
+            Err(error) => return Response::error(400, error.to_string()),
+        };
+        if let Err(error) = bundle.verify() {
+            return Response::error(400, error.to_string());
+        }
+        let key = root_key(&bundle);
+        if self.proposals.contains_key(&key) {
+            return Response::error(409, format!("{key} was already uploaded"));
+        }
+        if let Err(error) = bundle.save(self.path(&key)) {
+            return Response::error(500, error.to_string());
+        }
+        let summary = self.summary(&bundle);
+        self.proposals.insert(key, bundle);
+        Response { status: 201, body: summary }
+    }
+
+    /// Adds the signature of `{"signature": "0x<65 bytes>"}` to a proposal.
+    fn add_signature(&mut self, root: &str, body: &[u8]) -> Response {
+        let Some(bundle) = self.proposals.get(&normalize_root(root)) else {
+            return Response::error(404, format!("no proposal {root}"));
+        };
+        let signature = serde_json::from_slice::<Value>(body)
+            .ok()
+            .and_then(|body| Some(body.get("signature")?.as_str()?.to_string()))
+            .and_then(|signature| hex::decode(signature.trim_start_matches("0x")).ok())
+            .and_then(|bytes| match split_signatures(&bytes).ok()?.as_slice() {
+                [signature] => normalize_recovery_id(signature).ok(),
+                _ => None,
+            });
+        let Some(signature) = signature else {
+            return Response::error(400, "expected {\"signature\": \"0x<65 bytes>\"}");
+        };
+        let Some(signer) = recover_signer(&bundle.digest(), &signature) else {
+            return Response::error(400, SdkError::FailedRecovery.to_string());
+        };
+        if !self.signers.contains(&signer) {
+            return Response::error(
+                403,
+                format!("{} is not a signer, or signed another digest", checksum_address(&signer)),
+            );
+        }
+
+        let mut bundle = bundle.clone();
+        match bundle.add_signature(&signature) {
+            Ok(_) => {},
+            Err(SdkError::DuplicateSigner) =>
+                return Response::error(409, format!("{} already signed", checksum_address(&signer))),
+            Err(error) => return Response::error(400, error.to_string()),
+        }
+        let key = root_key(&bundle);
+        if let Err(error) = bundle.save(self.path(&key)) {
+            return Response::error(500, error.to_string());
+        }
+        let summary = self.summary(&bundle);
+        self.proposals.insert(key, bundle);
+        Response::ok(summary)
+    }
+
+    fn with_proposal(
+        &self,
+        root: &str,
+        respond: impl FnOnce(&Self, &ProposalBundle) -> Response,
+    ) -> Response {
+        match self.proposals.get(&normalize_root(root)) {
+            Some(bundle) => respond(self, bundle),
+            None => Response::error(404, format!("no proposal {root}")),
+        }
+    }
+
+    /// What signers and proposers poll: the proposal, who signed it, and whether it is complete
+    fn summary(&self, bundle: &ProposalBundle) -> Value {
+        let signed_by: Vec<Address> = bundle
+            .signers()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|signer| self.signers.contains(signer))
+            .collect();
+        json!({
+            "merkleRoot": root_key(bundle),
+            "title": bundle.metadata.title,
+            "description": bundle.metadata.description,
+            "expiry": bundle.metadata.expiry,
+            "digest": hex_hash(&bundle.digest().0),
+            "signedBy": signed_by.iter().map(checksum_address).collect::<Vec<_>>(),
+            "threshold": self.threshold,
+            "complete": signed_by.len() >= usize::from(self.threshold),
+        })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+fn root_key(bundle: &ProposalBundle) -> String {
+    hex_hash(&bundle.merkle_root.0)
+}
+
+/// A root as URLs give it, with or without its 0x prefix, in any case
+fn normalize_root(root: &str) -> String {
+    format!("0x{}", root.trim_start_matches("0x").to_ascii_lowercase())
+}
+
+fn hex_hash(hash: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(hash))
+}
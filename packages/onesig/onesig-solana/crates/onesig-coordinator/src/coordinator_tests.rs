@@ -0,0 +1,154 @@
+// Tests for uploading proposals and collecting their signatures.
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anchor_lang::prelude::Pubkey;
+    use onesig::Hash;
+    use onesig_sdk::{
+        split_signatures, LocalSigner, OneSigInstruction, ProposalBundle, ProposalMetadata,
+        RootSigner, SolanaInstance, SolanaLeafGuards, TreeCoordinator,
+    };
+    use serde_json::json;
+
+    use crate::{coordinator::Coordinator, http::Request};
+
+    fn bundle() -> ProposalBundle {
+        let mut coordinator = TreeCoordinator::new();
+        let instance = coordinator.add_solana_instance(SolanaInstance {
+            chain_id: "solana:test".into(),
+            one_sig_state: Pubkey::new_from_array([7; 32]),
+            one_sig_id: 1,
+            nonce: 0,
+            cluster_genesis_hash: None,
+        });
+        let instruction = OneSigInstruction {
+            program_id: Pubkey::new_from_array([7; 32]),
+            accounts: vec![],
+            data: vec![1],
+            value: 0,
+        };
+        coordinator
+            .push_solana(instance, instruction, SolanaLeafGuards::default())
+            .unwrap();
+        coordinator
+            .bundle(ProposalMetadata {
+                title: "Raise the threshold".into(),
+                description: String::new(),
+                seed: Hash([0; 32]),
+                expiry: 2_000_000_000,
+                max_executions: 0,
+            })
+            .unwrap()
+    }
+
+    fn signer(key: u8) -> LocalSigner {
+        LocalSigner::from_bytes(&[key; 32]).unwrap()
+    }
+
+    fn request(method: &str, path: &str, body: &str, token: Option<&str>) -> Request {
+        Request {
+            method: method.into(),
+            path: path.into(),
+            headers: token
+                .map(|token| ("authorization".to_string(), format!("Bearer {token}")))
+                .into_iter()
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("onesig-coordinator-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn sign(bundle: &ProposalBundle, key: u8) -> String {
+        let signature = signer(key).sign(&bundle.message()).unwrap();
+        json!({ "signature": format!("0x{}", hex::encode(signature.0)) }).to_string()
+    }
+
+    #[test]
+    fn test_collects_signatures_up_to_the_threshold() {
+        let dir = dir("collect");
+        let signers = vec![signer(1).address(), signer(2).address(), signer(3).address()];
+        let mut coordinator =
+            Coordinator::open(dir.clone(), signers, 2, Some("secret".into())).unwrap();
+        let bundle = bundle();
+        let root = format!("0x{}", hex::encode(bundle.merkle_root.0));
+        let json = bundle.to_json().unwrap();
+
+        assert_eq!(coordinator.handle(&request("POST", "/proposals", &json, None)).status, 401);
+        assert_eq!(
+            coordinator.handle(&request("POST", "/proposals", &json, Some("secret"))).status,
+            201
+        );
+        assert_eq!(
+            coordinator.handle(&request("POST", "/proposals", &json, Some("secret"))).status,
+            409
+        );
+
+        let digest =
+            coordinator.handle(&request("GET", &format!("/proposals/{root}/digest"), "", None));
+        assert_eq!(digest.body["digest"], format!("0x{}", hex::encode(bundle.digest().0)));
+
+        let signatures = format!("/proposals/{root}/signatures");
+        assert_eq!(coordinator.handle(&request("GET", &signatures, "", None)).status, 409);
+        let response = coordinator.handle(&request("POST", &signatures, &sign(&bundle, 1), None));
+        assert_eq!((response.status, response.body["complete"].as_bool()), (200, Some(false)));
+        // A key outside the signer set, and a signer signing twice
+        assert_eq!(
+            coordinator
+                .handle(&request("POST", &signatures, &sign(&bundle, 9), None))
+                .status,
+            403
+        );
+        assert_eq!(
+            coordinator
+                .handle(&request("POST", &signatures, &sign(&bundle, 1), None))
+                .status,
+            409
+        );
+        assert_eq!(coordinator.handle(&request("POST", &signatures, "{}", None)).status, 400);
+        let response = coordinator.handle(&request("POST", &signatures, &sign(&bundle, 3), None));
+        assert_eq!(response.body["complete"], true);
+
+        let response = coordinator.handle(&request("GET", &signatures, "", None));
+        let packed = response.body["signatures"].as_str().unwrap();
+        let packed = split_signatures(&hex::decode(&packed[2..]).unwrap()).unwrap();
+        assert_eq!(packed.len(), 2);
+
+        // Restarted, the service has the signatures collected so far
+        let mut reopened =
+            Coordinator::open(dir.clone(), vec![signer(1).address()], 1, None).unwrap();
+        let listed = reopened.handle(&request("GET", "/proposals", "", None));
+        assert_eq!(listed.body.as_array().map(Vec::len), Some(1));
+        let proposal =
+            reopened.handle(&request("GET", &format!("/proposals/{}", &root[2..]), "", None));
+        assert_eq!(proposal.body["signedBy"].as_array().map(Vec::len), Some(1));
+        assert!(proposal.body["bundle"].is_object());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_invalid_requests() {
+        let dir = dir("invalid");
+        let mut coordinator =
+            Coordinator::open(dir.clone(), vec![signer(1).address()], 1, None).unwrap();
+        let mut tampered = bundle();
+        tampered.merkle_root = Hash([1; 32]);
+
+        let response =
+            coordinator.handle(&request("POST", "/proposals", &tampered.to_json().unwrap(), None));
+        assert_eq!(response.status, 400);
+        assert_eq!(coordinator.handle(&request("POST", "/proposals", "{", None)).status, 400);
+        let missing = format!("/proposals/0x{}", "00".repeat(32));
+        assert_eq!(coordinator.handle(&request("GET", &missing, "", None)).status, 404);
+        assert_eq!(coordinator.handle(&request("DELETE", "/proposals", "", None)).status, 405);
+        let response = coordinator.handle(&request("GET", "/", "", None));
+        assert_eq!(response.body, json!({ "error": "not found, see /proposals" }));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,50 @@
+// Tests for reading requests and writing responses.
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::json;
+
+    use crate::http::{Request, Response, MAX_BODY_LEN};
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /proposals/0xab/signatures?x=1 HTTP/1.1\r\nHost: localhost\r\n\
+                   Content-Length: 7\r\nAuthorization: Bearer t\r\n\r\n{\"a\":1}";
+        let request = Request::read(&mut Cursor::new(raw)).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.segments(), ["proposals", "0xab", "signatures"]);
+        assert_eq!(request.header("AUTHORIZATION"), Some("Bearer t"));
+        assert_eq!(request.body, b"{\"a\":1}");
+
+        let request = Request::read(&mut Cursor::new("GET / HTTP/1.1\r\n\r\n")).unwrap();
+        assert!(request.body.is_empty() && request.segments().is_empty());
+    }
+
+    #[test]
+    fn test_read_invalid_requests() {
+        let read = |raw: String| Request::read(&mut Cursor::new(raw));
+        assert!(read("\r\n".into()).is_err());
+        assert!(read("GET / HTTP/1.1\r\nHost".into()).is_err());
+        assert!(read("GET / HTTP/1.1\r\nContent-Length: x\r\n\r\n".into()).is_err());
+        assert!(read(format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_LEN + 1))
+            .is_err());
+        // A body shorter than announced
+        assert!(read("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nab".into()).is_err());
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = vec![];
+        Response::error(404, "no proposal").write_to(&mut out).unwrap();
+        let body = json!({ "error": "no proposal" }).to_string();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                body.len()
+            )
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! `onesig-coordinator`: an HTTP service collecting the signatures of proposals.
+//!
+//! A proposer uploads a proposal bundle; signers fetch its digest, sign it, and post their
+//! signatures, which the service checks against the signer set before adding them to the bundle.
+//! Once the threshold is met it serves the signatures packed in the order `verify_merkle_root`
+//! checks them, and the bundle carries them to the executors, so the collection no longer runs
+//! through chat threads.
+
+mod coordinator;
+mod http;
+
+#[cfg(test)]
+mod coordinator_tests;
+#[cfg(test)]
+mod http_tests;
+
+use std::{env, io::BufReader, net::TcpListener, path::PathBuf, process::ExitCode, time::Duration};
+
+use anyhow::{bail, Context as _, Result};
+use onesig_cli::{args::Args, parse};
+
+use crate::{
+    coordinator::Coordinator,
+    http::{Request, Response},
+};
+
+const USAGE: &str = "\
+usage: onesig-coordinator --dir <path> --signer <address>... --threshold <n>
+       [--listen <address>]
+
+  --dir <path>             directory keeping the proposals and their signatures
+  --signer <address>       signer whose signatures are accepted
+  --threshold <n>          signatures a proposal needs
+  --listen <address>       [default: 127.0.0.1:8081]
+
+  Uploads take `Authorization: Bearer <token>` when ONESIG_COORDINATOR_TOKEN is set.
+
+endpoints:
+  GET  /proposals                      every proposal, with who signed it
+  POST /proposals                      uploads a proposal bundle
+  GET  /proposals/<root>               a proposal and its bundle
+  GET  /proposals/<root>/digest        the EIP-712 digest to sign, and what it hashes
+  POST /proposals/<root>/signatures    adds {\"signature\": \"0x<65 bytes>\"}
+  GET  /proposals/<root>/signatures    the packed signatures, once the threshold is met
+";
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(args: impl IntoIterator<Item = String>) -> Result<()> {
+    let mut args = Args::parse(args);
+    if args.flag("help")? {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    let dir = PathBuf::from(args.required("dir")?);
+    let signers = args
+        .values("signer")?
+        .iter()
+        .map(|signer| parse::address(signer))
+        .collect::<Result<Vec<_>>>()?;
+    let threshold: u8 = args.required_parsed("threshold")?;
+    if threshold == 0 || usize::from(threshold) > signers.len() {
+        bail!("the threshold must be between 1 and the {} signers", signers.len());
+    }
+    let listen = args.value("listen")?.unwrap_or_else(|| "127.0.0.1:8081".into());
+    args.finish()?;
+
+    let token = env::var("ONESIG_COORDINATOR_TOKEN").ok();
+    let mut coordinator = Coordinator::open(dir, signers, threshold, token)?;
+    let listener =
+        TcpListener::bind(&listen).with_context(|| format!("cannot listen on {listen}"))?;
+    println!("collecting signatures on http://{listen}/proposals");
+    // One request at a time: signers post a handful of signatures per proposal
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("{error}");
+                continue;
+            },
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        let response = match Request::read(&mut BufReader::new(&stream)) {
+            Ok(request) => coordinator.handle(&request),
+            Err(error) => Response::error(400, error.to_string()),
+        };
+        if let Err(error) = response.write_to(&mut stream) {
+            eprintln!("{error}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use hex_literal::hex;
+pub use solana_keccak_hasher::HASH_BYTES;
+
+/// Length of an Ethereum address: the last 20 bytes of the keccak hash of a public key
+pub const ADDRESS_LEN: usize = 20;
+
+/// Size of raw signature (64 bytes + 1 recovery byte)
+pub const SIGNATURE_BYTES_LEN: usize = 65;
+
+/// Size of an uncompressed secp256k1 public key, without its prefix byte
+pub const SECP256K1_PUBLIC_KEY_LEN: usize = 64;
+
+/// The version of the Merkle tree leaf encoding
+pub const MERKLE_LEAF_ENCODING_VERSION: [u8; 1] = [1];
+
+/// EIP-191 prefix for EIP-712 style digests
+pub const EIP191_PREFIX_FOR_EIP712: [u8; 2] = [0x19, 0x01];
+
+/// keccak::hash(b"SignMerkleRoot(bytes32 seed,bytes32 merkleRoot,uint256 expiry)").as_ref()
+pub const SIGN_MERKLE_ROOT_TYPE_HASH: [u8; HASH_BYTES] =
+    hex!("642ed5d2b77bc7ccb98e10da4c02d7cd8231228da4222a9f88a80c15545074ed");
+
+/// keccak::hash(b"SignLimitedMerkleRoot(bytes32 seed,bytes32 merkleRoot,uint256 expiry,uint256
+/// maxExecutions)").as_ref()
+///
+/// Signed instead of `SignMerkleRoot` when a root is capped to a number of executions. Solana only.
+pub const SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH: [u8; HASH_BYTES] =
+    hex!("c9e26565503f2fb5ad0ae8a50b88758a231034b4557aae27e97df936bdceeb6a");
+
+/// keccak256("SignerExecutionAuthorization(bytes32 leafHash,bytes32 merkleRoot,bytes
+/// delegate,uint256 expiry)")
+pub const SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH: [u8; HASH_BYTES] =
+    hex!("2ed09255a17ecc5d8fd5128a11480e0f01d23b22eaeb02f4f4acae355e5c428a");
+
+/// Pre-calculated domain separator for EIP-712 signatures, hashed by following data:
+/// - EIP-191 prefix for EIP-712 style digests
+/// - EIP-712 domain separator type-hash
+/// - Contract name: "OneSig"
+/// - Contract version: "0.0.1"
+/// - Chain ID: 1 (Ethereum Mainnet)
+/// - Verifying contract address: 0xdEaD
+///
+/// Shared by every chain, as it is by the EVM deployments: signers sign a root once for all
+/// chains, and its leaves are kept apart by the OneSig account (and, on Solana, the cluster) they
+/// are bound to.
+pub const DOMAIN_SEPARATOR: [u8; HASH_BYTES] =
+    hex!("94c28989170eb4dc31359174b9115c116a8fafa67b5adacc570ca583eb96d657");
+
+/// `EIP191_PREFIX_FOR_EIP712 || DOMAIN_SEPARATOR`, the 34 bytes every digest of the OneSig domain
+/// hashes before its struct hash, combined at compile time
+pub const EIP712_DIGEST_PREFIX: [u8; 2 + HASH_BYTES] = {
+    let mut prefix = [0u8; 2 + HASH_BYTES];
+    prefix[0] = EIP191_PREFIX_FOR_EIP712[0];
+    prefix[1] = EIP191_PREFIX_FOR_EIP712[1];
+    let mut i = 0;
+    while i < HASH_BYTES {
+        prefix[2 + i] = DOMAIN_SEPARATOR[i];
+        i += 1;
+    }
+    prefix
+};
@@ -0,0 +1,133 @@
+//! The EIP-712 style digests signers sign, identical to the ones of the EVM implementation.
+//!
+//! Each message is laid out in a buffer on the stack and hashed as one slice: the keccak syscall
+//! charges for every slice it is given, and the constant parts of the digest are combined at
+//! compile time in [`EIP712_DIGEST_PREFIX`].
+
+use solana_keccak_hasher as keccak;
+
+use crate::{
+    constants::{
+        EIP712_DIGEST_PREFIX, HASH_BYTES, SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH,
+        SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH, SIGN_MERKLE_ROOT_TYPE_HASH,
+    },
+    types::Hash,
+};
+
+/// The digest signers sign to approve `merkle_root`.
+///
+/// A non-zero `max_executions` is bound into the digest through `SignLimitedMerkleRoot`;
+/// otherwise the digest is the `SignMerkleRoot` one shared with the EVM implementation.
+pub fn merkle_root_digest(
+    seed: &Hash,
+    merkle_root: &Hash,
+    expiry: u64,
+    max_executions: u32,
+) -> Hash {
+    eip712_digest(&merkle_root_struct_hash(seed, merkle_root, expiry, max_executions))
+}
+
+/// EIP-712 struct hash of the `SignMerkleRoot` (or `SignLimitedMerkleRoot`) message of
+/// [`merkle_root_digest`].
+pub fn merkle_root_struct_hash(
+    seed: &Hash,
+    merkle_root: &Hash,
+    expiry: u64,
+    max_executions: u32,
+) -> Hash {
+    if max_executions == 0 {
+        keccak::hash(&concat::<{ 4 * HASH_BYTES }>(&[
+            &SIGN_MERKLE_ROOT_TYPE_HASH,
+            seed.as_ref(),
+            merkle_root.as_ref(),
+            &uint256(expiry),
+        ]))
+    } else {
+        keccak::hash(&concat::<{ 5 * HASH_BYTES }>(&[
+            &SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH,
+            seed.as_ref(),
+            merkle_root.as_ref(),
+            &uint256(expiry),
+            &uint256(max_executions.into()),
+        ]))
+    }
+    .into()
+}
+
+/// Signer-as-executor authorization digest:
+/// ```text
+/// structHash            = keccak256(
+///     SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH || leafHash || merkleRoot ||
+///     keccak256(delegate) || expiry_padded
+/// )
+/// authorization_digest  = keccak256(0x1901 || DOMAIN_SEPARATOR || structHash)
+/// ```
+///
+/// `merkleRoot` pins the authorization to one operator-approved batch so the delegate cannot pick
+/// a different root that happens to contain the same leaf.
+pub fn signer_execution_authorization_digest(
+    leaf: &Hash,
+    merkle_root: &Hash,
+    delegate: &[u8],
+    expiry: u64,
+) -> Hash {
+    eip712_digest(&signer_execution_authorization_struct_hash(leaf, merkle_root, delegate, expiry))
+}
+
+/// EIP-712 struct hash of the `SignerExecutionAuthorization` message of
+/// [`signer_execution_authorization_digest`].
+pub fn signer_execution_authorization_struct_hash(
+    leaf: &Hash,
+    merkle_root: &Hash,
+    delegate: &[u8],
+    expiry: u64,
+) -> Hash {
+    let delegate_hash = keccak::hash(delegate);
+    keccak::hash(&concat::<{ 5 * HASH_BYTES }>(&[
+        &SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH,
+        leaf.as_ref(),
+        merkle_root.as_ref(),
+        delegate_hash.as_ref(),
+        &uint256(expiry),
+    ]))
+    .into()
+}
+
+/// keccak256 of the data of an executed instruction, as `TransactionExecuted` reports it
+pub fn ix_data_hash(ix_data: &[u8]) -> Hash {
+    keccak::hash(ix_data).into()
+}
+
+/// What a root verified on chain keeps of the seed it was verified under: the first 16 bytes of
+/// the seed's keccak hash, which tell a rotated seed apart as well as the seed itself.
+pub fn seed_commitment(seed: &Hash) -> [u8; 16] {
+    let mut commitment = [0u8; 16];
+    commitment.copy_from_slice(&keccak::hash(seed.as_ref()).as_ref()[..16]);
+    commitment
+}
+
+/// keccak256(0x1901 || DOMAIN_SEPARATOR || structHash), the digest of an EIP-712 message of the
+/// OneSig domain.
+pub fn eip712_digest(struct_hash: &Hash) -> Hash {
+    keccak::hash(&concat::<{ 2 + 2 * HASH_BYTES }>(&[&EIP712_DIGEST_PREFIX, struct_hash.as_ref()]))
+        .into()
+}
+
+/// `parts` laid out back to back; their lengths add up to `N`.
+fn concat<const N: usize>(parts: &[&[u8]]) -> [u8; N] {
+    let mut buffer = [0u8; N];
+    let mut offset = 0;
+    for part in parts {
+        buffer[offset..offset + part.len()].copy_from_slice(part);
+        offset += part.len();
+    }
+    debug_assert_eq!(offset, N);
+    buffer
+}
+
+// ABI encoding of a uint256: big-endian, left zero-padded to 32 bytes
+fn uint256(value: u64) -> [u8; 32] {
+    let mut encoded = [0u8; 32];
+    encoded[24..].copy_from_slice(&value.to_be_bytes());
+    encoded
+}
@@ -0,0 +1,74 @@
+// Tests for the digests laid out in single buffers, against the chains of hashes they replace.
+#[cfg(test)]
+mod tests {
+    use solana_keccak_hasher as keccak;
+
+    use crate::{
+        constants::{
+            DOMAIN_SEPARATOR, EIP191_PREFIX_FOR_EIP712, EIP712_DIGEST_PREFIX,
+            SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH, SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH,
+            SIGN_MERKLE_ROOT_TYPE_HASH,
+        },
+        digest::{merkle_root_digest, seed_commitment, signer_execution_authorization_digest},
+        types::Hash,
+    };
+
+    fn uint256(value: u64) -> [u8; 32] {
+        let mut encoded = [0u8; 32];
+        encoded[24..].copy_from_slice(&value.to_be_bytes());
+        encoded
+    }
+
+    fn eip712_digest(struct_hash: keccak::Hash) -> Hash {
+        keccak::hashv(&[&EIP191_PREFIX_FOR_EIP712, &DOMAIN_SEPARATOR, struct_hash.as_ref()]).into()
+    }
+
+    #[test]
+    fn test_digest_prefix() {
+        assert_eq!(EIP712_DIGEST_PREFIX[..2], EIP191_PREFIX_FOR_EIP712);
+        assert_eq!(EIP712_DIGEST_PREFIX[2..], DOMAIN_SEPARATOR);
+    }
+
+    #[test]
+    fn test_merkle_root_digest() {
+        let (seed, merkle_root) = (Hash([1u8; 32]), Hash([2u8; 32]));
+        let expected = eip712_digest(keccak::hashv(&[
+            &SIGN_MERKLE_ROOT_TYPE_HASH,
+            seed.as_ref(),
+            merkle_root.as_ref(),
+            &uint256(1_700_000_000),
+        ]));
+        assert!(merkle_root_digest(&seed, &merkle_root, 1_700_000_000, 0) == expected);
+
+        let expected = eip712_digest(keccak::hashv(&[
+            &SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH,
+            seed.as_ref(),
+            merkle_root.as_ref(),
+            &uint256(1_700_000_000),
+            &uint256(3),
+        ]));
+        assert!(merkle_root_digest(&seed, &merkle_root, 1_700_000_000, 3) == expected);
+    }
+
+    #[test]
+    fn test_signer_execution_authorization_digest() {
+        let (leaf, merkle_root, delegate) = (Hash([3u8; 32]), Hash([4u8; 32]), [5u8; 32]);
+        let expected = eip712_digest(keccak::hashv(&[
+            &SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH,
+            leaf.as_ref(),
+            merkle_root.as_ref(),
+            keccak::hash(&delegate).as_ref(),
+            &uint256(42),
+        ]));
+        assert!(
+            signer_execution_authorization_digest(&leaf, &merkle_root, &delegate, 42) == expected
+        );
+    }
+
+    #[test]
+    fn test_seed_commitment() {
+        let seed = Hash([6u8; 32]);
+        assert_eq!(seed_commitment(&seed), keccak::hash(&seed.0).to_bytes()[..16]);
+        assert_ne!(seed_commitment(&seed), seed_commitment(&Hash([7u8; 32])));
+    }
+}
@@ -0,0 +1,231 @@
+//! Leaf encoding and merkle proofs. Keys are taken as raw 32-byte arrays; the encodings match
+//! the Borsh serialization of the program's `OneSigInstruction`, `TokenOutflowLimit` and `Pubkey`
+//! lists.
+
+use alloc::vec::Vec;
+
+use solana_keccak_hasher as keccak;
+
+use crate::{constants::MERKLE_LEAF_ENCODING_VERSION, types::Hash};
+
+/// An account of the instruction a leaf executes.
+pub struct LeafAccountMeta<'a> {
+    pub pubkey: &'a [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Cap on the amount of a token a leaf's instruction may move out of the OneSig's token accounts.
+pub struct LeafTokenOutflowLimit<'a> {
+    pub mint: &'a [u8; 32],
+    pub max_amount: u64,
+}
+
+/// Position of a leaf in a group: the leaves at `len` consecutive nonces that are only executed
+/// together, within one transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafGroup {
+    /// Of the leaf among them, in nonce order
+    pub index: u8,
+    pub len: u8,
+}
+
+/// The instruction a leaf executes, for [`hash_leaf`] to hash without encoding it first
+pub struct LeafInstruction<'a, A> {
+    pub program_id: &'a [u8; 32],
+    /// Iterator of [`LeafAccountMeta`]s
+    pub accounts: A,
+    pub data: &'a [u8],
+    /// SOL the instruction may spend
+    pub value: u64,
+}
+
+/// Encodes the instruction a leaf executes: program id, accounts, data and the SOL `value` it may
+/// spend.
+pub fn encode_instruction<'a>(
+    program_id: &[u8; 32],
+    accounts: impl ExactSizeIterator<Item = LeafAccountMeta<'a>>,
+    data: &[u8],
+    value: u64,
+) -> Vec<u8> {
+    let mut encoded = encode_instruction_accounts(program_id, accounts, 12 + data.len());
+    encoded.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    encoded.extend_from_slice(data);
+    encoded.extend_from_slice(&value.to_le_bytes());
+    encoded
+}
+
+/// The program id and accounts of an encoded instruction, with room for `additional` bytes
+fn encode_instruction_accounts<'a>(
+    program_id: &[u8; 32],
+    accounts: impl ExactSizeIterator<Item = LeafAccountMeta<'a>>,
+    additional: usize,
+) -> Vec<u8> {
+    // 32 bytes of program id and 4 of vector length, then 34 bytes per account
+    let mut encoded = Vec::with_capacity(36 + accounts.len() * 34 + additional);
+    encoded.extend_from_slice(program_id);
+    encoded.extend_from_slice(&(accounts.len() as u32).to_le_bytes());
+    for account in accounts {
+        encoded.extend_from_slice(account.pubkey);
+        encoded.push(account.is_signer.into());
+        encoded.push(account.is_writable.into());
+    }
+    encoded
+}
+
+/// Encodes the leaf of `encoded_instruction` (see [`encode_instruction`]) for a OneSig account at
+/// `nonce`.
+///
+/// The extensions (token outflow limits, guarded accounts, vault index, cluster genesis hash,
+/// group, not-before timestamp, bounty) are appended in that order up to the last one that is set,
+/// so leaves without them keep the encoding shared with every other chain.
+#[allow(clippy::too_many_arguments)]
+pub fn encode_leaf<'a>(
+    one_sig_state: &[u8; 32],
+    one_sig_id: u64,
+    nonce: u64,
+    encoded_instruction: &[u8],
+    token_outflow_limits: impl ExactSizeIterator<Item = LeafTokenOutflowLimit<'a>>,
+    guarded_accounts: impl ExactSizeIterator<Item = &'a [u8; 32]>,
+    vault_index: u8,
+    cluster_genesis_hash: Option<&Hash>,
+    group: Option<LeafGroup>,
+    not_before: Option<i64>,
+    bounty: Option<u64>,
+) -> Hash {
+    hash_leaf_chunks(
+        one_sig_state,
+        one_sig_id,
+        nonce,
+        [encoded_instruction, &[], &[], &[]],
+        token_outflow_limits,
+        guarded_accounts,
+        vault_index,
+        cluster_genesis_hash,
+        group,
+        not_before,
+        bounty,
+    )
+}
+
+/// [`encode_leaf`] of `instruction`, hashed in place: its data, which can run to kilobytes, is
+/// passed to the hasher as is rather than copied into an encoded instruction first.
+#[allow(clippy::too_many_arguments)]
+pub fn hash_leaf<'a>(
+    one_sig_state: &[u8; 32],
+    one_sig_id: u64,
+    nonce: u64,
+    instruction: LeafInstruction<'a, impl ExactSizeIterator<Item = LeafAccountMeta<'a>>>,
+    token_outflow_limits: impl ExactSizeIterator<Item = LeafTokenOutflowLimit<'a>>,
+    guarded_accounts: impl ExactSizeIterator<Item = &'a [u8; 32]>,
+    vault_index: u8,
+    cluster_genesis_hash: Option<&Hash>,
+    group: Option<LeafGroup>,
+    not_before: Option<i64>,
+    bounty: Option<u64>,
+) -> Hash {
+    let accounts = encode_instruction_accounts(instruction.program_id, instruction.accounts, 0);
+    let data_len = (instruction.data.len() as u32).to_le_bytes();
+    let value = instruction.value.to_le_bytes();
+    hash_leaf_chunks(
+        one_sig_state,
+        one_sig_id,
+        nonce,
+        [&accounts, &data_len, instruction.data, &value],
+        token_outflow_limits,
+        guarded_accounts,
+        vault_index,
+        cluster_genesis_hash,
+        group,
+        not_before,
+        bounty,
+    )
+}
+
+/// Hashes a leaf whose encoded instruction is the concatenation of `instruction`. The hasher
+/// takes the pieces of the leaf as a fixed list of slices, empty ones changing nothing, so none
+/// is copied.
+#[allow(clippy::too_many_arguments)]
+fn hash_leaf_chunks<'a>(
+    one_sig_state: &[u8; 32],
+    one_sig_id: u64,
+    nonce: u64,
+    instruction: [&[u8]; 4],
+    token_outflow_limits: impl ExactSizeIterator<Item = LeafTokenOutflowLimit<'a>>,
+    guarded_accounts: impl ExactSizeIterator<Item = &'a [u8; 32]>,
+    vault_index: u8,
+    cluster_genesis_hash: Option<&Hash>,
+    group: Option<LeafGroup>,
+    not_before: Option<i64>,
+    bounty: Option<u64>,
+) -> Hash {
+    let limits_len = token_outflow_limits.len();
+    let mut encoded_limits = Vec::with_capacity(4 + limits_len * 40);
+    encoded_limits.extend_from_slice(&(limits_len as u32).to_le_bytes());
+    for limit in token_outflow_limits {
+        encoded_limits.extend_from_slice(limit.mint);
+        encoded_limits.extend_from_slice(&limit.max_amount.to_le_bytes());
+    }
+    let guarded_len = guarded_accounts.len();
+    let mut encoded_guarded_accounts = Vec::with_capacity(4 + guarded_len * 32);
+    encoded_guarded_accounts.extend_from_slice(&(guarded_len as u32).to_le_bytes());
+    for account in guarded_accounts {
+        encoded_guarded_accounts.extend_from_slice(account);
+    }
+
+    let one_sig_id_bytes = one_sig_id.to_be_bytes();
+    let nonce_bytes = nonce.to_be_bytes();
+    let vault_index_bytes = [vault_index];
+    let group_bytes = group.map_or([0; 2], |group| [group.index, group.len]);
+    let not_before_bytes = not_before.unwrap_or_default().to_le_bytes();
+    let bounty_bytes = bounty.unwrap_or_default().to_le_bytes();
+    let mut extensions: [(&[u8], bool); 7] = [
+        (&encoded_limits, limits_len != 0),
+        (&encoded_guarded_accounts, guarded_len != 0),
+        (&vault_index_bytes, vault_index != 0),
+        (cluster_genesis_hash.map_or(&[], |hash| hash.as_ref()), cluster_genesis_hash.is_some()),
+        (&group_bytes, group.is_some()),
+        (&not_before_bytes, not_before.is_some()),
+        (&bounty_bytes, bounty.is_some()),
+    ];
+    let extensions_len = extensions.iter().rposition(|(_, set)| *set).map_or(0, |i| i + 1);
+    for (encoded, _) in &mut extensions[extensions_len..] {
+        *encoded = &[];
+    }
+
+    let leaf_data: [&[u8]; 15] = [
+        MERKLE_LEAF_ENCODING_VERSION.as_ref(),
+        &one_sig_id_bytes,
+        one_sig_state,
+        &nonce_bytes,
+        instruction[0],
+        instruction[1],
+        instruction[2],
+        instruction[3],
+        extensions[0].0,
+        extensions[1].0,
+        extensions[2].0,
+        extensions[3].0,
+        extensions[4].0,
+        extensions[5].0,
+        extensions[6].0,
+    ];
+    keccak::hash(keccak::hashv(&leaf_data).as_ref()).into()
+}
+
+/// Folds `proof` into `leaf`, hashing each pair in sorted order, returning the merkle root the
+/// proof leads to.
+pub fn process_proof(leaf: &Hash, proof: &[Hash]) -> Hash {
+    proof.iter().fold(*leaf, |computed_hash, p| {
+        if computed_hash < *p {
+            keccak::hashv(&[computed_hash.as_ref(), p.as_ref()]).into()
+        } else {
+            keccak::hashv(&[p.as_ref(), computed_hash.as_ref()]).into()
+        }
+    })
+}
+
+/// Whether `proof` proves `leaf` is part of the tree of `merkle_root`.
+pub fn verify_proof(merkle_root: &Hash, proof: &[Hash], leaf: &Hash) -> bool {
+    process_proof(leaf, proof) == *merkle_root
+}
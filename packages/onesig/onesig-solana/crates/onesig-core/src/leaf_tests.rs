@@ -0,0 +1,160 @@
+// Tests for the leaf encoding and merkle proofs, against values of the EVM tests
+// (packages/onesig/onesig-evm/test/hardhat/one-sig.test.ts).
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use hex_literal::hex;
+    use solana_keccak_hasher as keccak;
+
+    use crate::{
+        leaf::{
+            encode_instruction, encode_leaf, hash_leaf, process_proof, verify_proof,
+            LeafAccountMeta, LeafGroup, LeafInstruction, LeafTokenOutflowLimit,
+        },
+        types::Hash,
+    };
+
+    #[test]
+    fn test_verify_proof() {
+        let leaf = Hash(hex!("fdcc354b818ad1de1e3be454bac1ca27100f245ac7ce99ef26d4d7746b05f4f7"));
+        let proof = [
+            Hash(hex!("c83f6f23f70097d27d2c5f3881632259151032aceddf5467ffc004e9bfc1b74e")),
+            Hash(hex!("63e335f61023ed89b955c0ee077806ffbdace5c6930e57967d183e10c225e7eb")),
+        ];
+        let merkle_root =
+            Hash(hex!("9c8e2403d4f1269e83ce128f2265631b22be6327cca2ede7776da9f8db2a9afc"));
+
+        assert!(verify_proof(&merkle_root, &proof, &leaf));
+        // The pairs are hashed in sorted order, so the proof order matters but not the sides
+        assert!(!verify_proof(&merkle_root, &[proof[1], proof[0]], &leaf));
+        assert!(process_proof(&leaf, &[]) == leaf);
+    }
+
+    #[test]
+    fn test_encode_instruction() {
+        let program_id = [1u8; 32];
+        let account = [2u8; 32];
+        let accounts = [LeafAccountMeta { pubkey: &account, is_signer: true, is_writable: false }];
+        let encoded = encode_instruction(&program_id, accounts.into_iter(), &[7, 8], 9);
+
+        let mut expected = vec![1u8; 32];
+        expected.extend_from_slice(&1u32.to_le_bytes());
+        expected.extend_from_slice(&account);
+        expected.extend_from_slice(&[1, 0]);
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&[7, 8]);
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_encode_leaf_extensions() {
+        let one_sig_state = [3u8; 32];
+        let instruction = [4u8; 10];
+        let leaf = |vault_index, genesis_hash: Option<&Hash>, group, not_before, bounty| {
+            encode_leaf(
+                &one_sig_state,
+                7,
+                3,
+                &instruction,
+                [].into_iter(),
+                [].into_iter(),
+                vault_index,
+                genesis_hash,
+                group,
+                not_before,
+                bounty,
+            )
+        };
+        let hash = |data: &[&[u8]]| -> Hash { keccak::hash(keccak::hashv(data).as_ref()).into() };
+        let (one_sig_id, nonce, empty) =
+            (7u64.to_be_bytes(), 3u64.to_be_bytes(), 0u32.to_le_bytes());
+        let base: [&[u8]; 5] = [&[1u8], &one_sig_id, &one_sig_state, &nonce, &instruction];
+
+        assert!(leaf(0, None, None, None, None) == hash(&base));
+        // Unset extensions before the last set one are encoded empty
+        let genesis_hash = Hash([9u8; 32]);
+        let mut extended = base.to_vec();
+        extended.extend([&empty[..], &empty, &[0u8], &genesis_hash.0]);
+        assert!(leaf(0, Some(&genesis_hash), None, None, None) == hash(&extended));
+        // An unset genesis hash is encoded empty, the group as its index and length
+        let group = LeafGroup { index: 1, len: 3 };
+        let mut grouped = base.to_vec();
+        grouped.extend([&empty[..], &empty, &[0u8], &[], &[1u8, 3]]);
+        assert!(leaf(0, None, Some(group), None, None) == hash(&grouped));
+        // The not-before timestamp as a little-endian i64, an unset group as zeroes
+        let not_before = 1_700_000_000i64.to_le_bytes();
+        let mut delayed = base.to_vec();
+        delayed.extend([&empty[..], &empty, &[0u8], &[], &[0u8, 0], &not_before]);
+        assert!(leaf(0, None, None, Some(1_700_000_000), None) == hash(&delayed));
+        // The bounty as a little-endian u64, an unset not-before timestamp as zeroes
+        let bounty = 5_000u64.to_le_bytes();
+        let mut rewarded = base.to_vec();
+        rewarded.extend([&empty[..], &empty, &[0u8], &[], &[0u8, 0], &[0u8; 8], &bounty]);
+        assert!(leaf(0, None, None, None, Some(5_000)) == hash(&rewarded));
+    }
+
+    #[test]
+    fn test_hash_leaf_matches_encode_leaf() {
+        let (one_sig_state, program_id, mint, guarded) =
+            ([3u8; 32], [1u8; 32], [5u8; 32], [6u8; 32]);
+        let keys = [[2u8; 32], [4u8; 32]];
+        let accounts = || {
+            keys.iter().enumerate().map(|(i, pubkey)| LeafAccountMeta {
+                pubkey,
+                is_signer: i == 0,
+                is_writable: true,
+            })
+        };
+        let data = vec![0xab; 1_000];
+        let genesis_hash = Hash([9u8; 32]);
+        let limits = || [LeafTokenOutflowLimit { mint: &mint, max_amount: 10 }].into_iter();
+
+        let group = Some(LeafGroup { index: 0, len: 2 });
+        for (with_limits, vault_index, genesis_hash, group, not_before, bounty) in [
+            (false, 0, None, None, None, None),
+            (true, 0, None, None, None, None),
+            (false, 2, Some(&genesis_hash), None, None, None),
+            (false, 0, None, group, None, None),
+            (false, 0, None, None, Some(-1), None),
+            (false, 0, None, None, None, Some(u64::MAX)),
+        ] {
+            let limits = limits().take(with_limits.into());
+            let encoded_instruction = encode_instruction(&program_id, accounts(), &data, 9);
+            let encoded = encode_leaf(
+                &one_sig_state,
+                7,
+                3,
+                &encoded_instruction,
+                limits.clone(),
+                [&guarded].into_iter(),
+                vault_index,
+                genesis_hash,
+                group,
+                not_before,
+                bounty,
+            );
+            let instruction = LeafInstruction {
+                program_id: &program_id,
+                accounts: accounts(),
+                data: &data,
+                value: 9,
+            };
+            let hashed = hash_leaf(
+                &one_sig_state,
+                7,
+                3,
+                instruction,
+                limits,
+                [&guarded].into_iter(),
+                vault_index,
+                genesis_hash,
+                group,
+                not_before,
+                bounty,
+            );
+            assert!(hashed == encoded);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Chain-agnostic OneSig encodings, shared by the Solana program and off-chain tooling so both
+//! build the exact same bytes: the leaf and instruction encodings, merkle proof folding, the
+//! EIP-712 digests signers sign, and the `Address` / `Hash` / `Signature` types.
+//!
+//! The crate is `no_std` (with `alloc`). Signer recovery needs the `recover` feature (default);
+//! the `anchor` feature derives Anchor serialization for the types, which the `borsh` feature
+//! derives for `Address` and `Hash` without Anchor, and off-chain builds need the
+//! `sha3` feature for keccak, which is a syscall on-chain. IDL generation (`idl-build`) needs
+//! `std`.
+#![cfg_attr(not(feature = "idl-build"), no_std)]
+
+extern crate alloc;
+
+pub mod constants;
+pub mod digest;
+pub mod leaf;
+#[cfg(feature = "recover")]
+pub mod signature;
+pub mod types;
+
+#[cfg(test)]
+mod digest_tests;
+#[cfg(test)]
+mod leaf_tests;
+
+pub use constants::*;
+pub use types::*;
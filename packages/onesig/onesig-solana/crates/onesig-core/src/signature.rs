@@ -0,0 +1,30 @@
+//! Recovery of the Ethereum address that signed a digest.
+
+use solana_secp256k1_recover::secp256k1_recover;
+
+use crate::{
+    constants::SIGNATURE_BYTES_LEN,
+    types::{Address, Hash, Secp256k1Pubkey, Signature},
+};
+
+/// Recovers the address that signed `digest`, or `None` if the signature is malformed.
+///
+/// Ethereum's `ecrecover` encodes the recovery id as v = recovery_id + 27, so v in 27..=30 is
+/// accepted and normalized back to the secp256k1 recovery id 0..=3.
+pub fn recover_address(digest: &Hash, signature: &Signature) -> Option<Address> {
+    recover_address_from_bytes(digest, &signature.0)
+}
+
+/// [`recover_address`] of a signature borrowed from a larger buffer, such as a chunk of
+/// concatenated signatures, so it need not be copied into a [`Signature`] first.
+pub fn recover_address_from_bytes(
+    digest: &Hash,
+    signature: &[u8; SIGNATURE_BYTES_LEN],
+) -> Option<Address> {
+    let (recovery_id, signature_r_s) = signature.split_last().unwrap();
+    let recovery_id = if (27..=30).contains(recovery_id) { recovery_id - 27 } else { *recovery_id };
+
+    let signer: Secp256k1Pubkey =
+        secp256k1_recover(digest.as_ref(), recovery_id, signature_r_s).ok()?.into();
+    Some(signer.into())
+}
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use core::array::TryFromSliceError;
+
+#[cfg(feature = "anchor")]
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, InitSpace};
+use bytemuck::{Pod, Zeroable};
+use solana_keccak_hasher::{self as keccak, Hash as SolanaHash};
+
+use crate::constants::{ADDRESS_LEN, HASH_BYTES, SECP256K1_PUBLIC_KEY_LEN, SIGNATURE_BYTES_LEN};
+
+/// Ethereum address of a signer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Pod, Zeroable)]
+#[cfg_attr(feature = "anchor", derive(InitSpace, AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(
+    all(feature = "borsh", not(feature = "anchor")),
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[repr(transparent)]
+pub struct Address(pub [u8; ADDRESS_LEN]);
+
+impl Address {
+    pub fn to_bytes(self) -> [u8; ADDRESS_LEN] {
+        self.0
+    }
+}
+
+impl From<Secp256k1Pubkey> for Address {
+    fn from(pubkey: Secp256k1Pubkey) -> Self {
+        let pubkey_bytes = pubkey.to_bytes();
+        let hash = keccak::hash(&pubkey_bytes);
+        let mut address_bytes = [0u8; ADDRESS_LEN];
+        address_bytes.copy_from_slice(&hash.to_bytes()[12..]);
+        Self(address_bytes)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Address {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: Vec<u8>) -> Result<Self, Self::Error> {
+        let array: &[u8; ADDRESS_LEN] = slice.as_slice().try_into()?;
+        Ok(Address(*array))
+    }
+}
+
+/// Uncompressed secp256k1 public key, without its prefix byte.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "anchor", derive(InitSpace, AnchorSerialize, AnchorDeserialize))]
+pub struct Secp256k1Pubkey(pub [u8; SECP256K1_PUBLIC_KEY_LEN]);
+
+impl Secp256k1Pubkey {
+    pub fn new(pubkey_vec: &[u8]) -> Self {
+        Self(
+            <[u8; SECP256K1_PUBLIC_KEY_LEN]>::try_from(<&[u8]>::clone(&pubkey_vec))
+                .expect("Slice must be the same length as a Pubkey"),
+        )
+    }
+
+    pub fn to_bytes(self) -> [u8; SECP256K1_PUBLIC_KEY_LEN] {
+        self.0
+    }
+}
+
+impl Default for Secp256k1Pubkey {
+    fn default() -> Self {
+        Self([0; SECP256K1_PUBLIC_KEY_LEN])
+    }
+}
+
+#[cfg(feature = "recover")]
+impl From<solana_secp256k1_recover::Secp256k1Pubkey> for Secp256k1Pubkey {
+    fn from(pubkey: solana_secp256k1_recover::Secp256k1Pubkey) -> Self {
+        Self(pubkey.0)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Secp256k1Pubkey {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: Vec<u8>) -> Result<Self, Self::Error> {
+        let array: &[u8; SECP256K1_PUBLIC_KEY_LEN] = slice.as_slice().try_into()?;
+        Ok(Secp256k1Pubkey(*array))
+    }
+}
+
+/// secp256k1 signature: r‖s‖v, 65 bytes.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "anchor", derive(InitSpace, AnchorSerialize, AnchorDeserialize))]
+pub struct Signature(pub [u8; SIGNATURE_BYTES_LEN]);
+
+impl Default for Signature {
+    fn default() -> Self {
+        Self([0u8; SIGNATURE_BYTES_LEN])
+    }
+}
+
+impl Signature {
+    pub fn to_bytes(self) -> [u8; SIGNATURE_BYTES_LEN] {
+        self.0
+    }
+
+    pub fn split_recovery_id(&self) -> (&u8, &[u8]) {
+        self.0.split_last().unwrap()
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let array: &[u8; SIGNATURE_BYTES_LEN] = slice.try_into()?;
+        Ok(Signature(*array))
+    }
+}
+
+impl TryFrom<Vec<u8>> for Signature {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: Vec<u8>) -> Result<Self, Self::Error> {
+        Signature::try_from(slice.as_slice())
+    }
+}
+
+/// 32-byte keccak hash: a leaf, a merkle root, a digest or a seed.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Pod, Zeroable)]
+#[cfg_attr(feature = "anchor", derive(InitSpace, AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(
+    all(feature = "borsh", not(feature = "anchor")),
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[repr(transparent)]
+pub struct Hash(pub [u8; HASH_BYTES]);
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl From<SolanaHash> for Hash {
+    fn from(hash: SolanaHash) -> Self {
+        Self(hash.to_bytes())
+    }
+}
+
+impl TryFrom<Vec<u8>> for Hash {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: Vec<u8>) -> Result<Self, Self::Error> {
+        let array: &[u8; HASH_BYTES] = slice.as_slice().try_into()?;
+        Ok(Hash(*array))
+    }
+}
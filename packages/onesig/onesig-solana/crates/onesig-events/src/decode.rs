@@ -0,0 +1,77 @@
+use std::fmt;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use borsh::BorshDeserialize;
+use solana_pubkey::Pubkey;
+
+use crate::events::OneSigEvent;
+
+/// Prefix of the data of an event CPI, the self-CPI `emit_cpi!` makes: Anchor's `EVENT_IX_TAG`,
+/// little-endian
+pub const EVENT_IX_TAG_LE: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// PDA seed of the event authority, which signs the event CPIs of a program
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Log line prefix of the events a program logs with `emit!`, base64-encoded
+pub const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Data of an event of the program that does not decode as that event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidEvent(pub &'static str);
+
+impl fmt::Display for InvalidEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} event", self.0)
+    }
+}
+
+impl std::error::Error for InvalidEvent {}
+
+/// The event authority of the program at `program_id`
+pub fn event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0
+}
+
+/// Whether an inner instruction invoking `program`, whose first account is `first_account`, is an
+/// event CPI of the program at `program_id`: a call into it signed by its event authority. Anyone
+/// can call the program with event CPI data, but only the program signs for its event authority.
+pub fn is_event_cpi(
+    program_id: &Pubkey,
+    event_authority: &Pubkey,
+    program: Option<Pubkey>,
+    first_account: Option<Pubkey>,
+) -> bool {
+    program == Some(*program_id) && first_account == Some(*event_authority)
+}
+
+/// Zeroes appended to an event emitted before events were versioned, more than any event spans
+/// once its fields are zeroed
+const UNVERSIONED_PADDING: [u8; 512] = [0; 512];
+
+/// Decodes an event of any `EVENT_VERSION`. The fields a later version added past the ones known
+/// here are skipped. An event emitted before events were versioned lacks its `event_version`, and
+/// the fields added since it was: they read as zeroes, which decode to zeroed keys and hashes, 0,
+/// `None`, empty lists and the first variant of enums, the `event_version` telling they are
+/// missing.
+pub fn decode_any_version<E: BorshDeserialize>(data: &[u8]) -> Option<E> {
+    E::deserialize(&mut &data[..])
+        .or_else(|_| E::deserialize(&mut [data, &UNVERSIONED_PADDING].concat().as_slice()))
+        .ok()
+}
+
+impl OneSigEvent {
+    /// Decodes the data of an event CPI: the event instruction tag, then the event. `None` if it is
+    /// not one; check the instruction [is an event CPI](is_event_cpi) of the program first.
+    pub fn from_event_cpi(data: &[u8]) -> Option<Result<Self, InvalidEvent>> {
+        Self::decode(data.strip_prefix(&EVENT_IX_TAG_LE)?)
+    }
+
+    /// Decodes a `Program data:` log line, as the program logs the events it emits with `emit!`.
+    /// `None` if it is not the line of an event of the program; the line being the program's is
+    /// told by the invocations the logs show around it.
+    pub fn from_log(line: &str) -> Option<Result<Self, InvalidEvent>> {
+        let data = BASE64_STANDARD.decode(line.strip_prefix(PROGRAM_DATA_LOG_PREFIX)?).ok()?;
+        Self::decode(&data)
+    }
+}
@@ -0,0 +1,320 @@
+//! The events of the program, encoded as it encodes them. Each carries its `event_version`, the
+//! [`EVENT_VERSION`] it was emitted at, after the fields of its first version: fields are only
+//! ever added after it.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    decode::{decode_any_version, InvalidEvent},
+    types::{Address, Hash, PreviousConfig, SetConfigParams},
+};
+
+/// The `EVENT_VERSION` of the program the events of this crate are the layouts of
+pub const EVENT_VERSION: u8 = 3;
+
+/// An event of the program
+pub trait Event: BorshSerialize + BorshDeserialize {
+    /// Prefix of its encoding, `sha256("event:<name>")[..8]` as Anchor derives it
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Its discriminator, then its fields, as the program emits it
+    fn data(&self) -> Vec<u8> {
+        let mut data = Self::DISCRIMINATOR.to_vec();
+        self.serialize(&mut data).expect("events serialize to a vector");
+        data
+    }
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct OneSigInitialized {
+    pub one_sig_account: Pubkey,
+    pub one_sig_id: u64,
+    pub seed: Hash,
+    pub threshold: u8,
+    pub signers: Vec<Address>,
+    pub executors: Vec<Pubkey>,
+    pub executor_required: bool,
+    /// CAIP-2 identifier of the chain leaves are bound to, if any
+    pub chain_id: Option<String>,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ConfigSet {
+    pub one_sig_account: Pubkey,
+    pub params: SetConfigParams,
+    /// What `params` replaced
+    pub previous: PreviousConfig,
+    pub event_version: u8,
+}
+
+/// `executor` can execute leaves from `effective_at`, the unix timestamp of the block it was
+/// registered in; 0 if emitted before version 2
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ExecutorAdded {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub event_version: u8,
+    pub effective_at: i64,
+}
+
+/// `executor` can no longer execute leaves from `effective_at`, the unix timestamp of the block it
+/// was deregistered in; 0 if emitted before version 2
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ExecutorRemoved {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub event_version: u8,
+    pub effective_at: i64,
+}
+
+/// Whether leaves can only be executed by registered executors changed, from `effective_at`
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ExecutorRequiredSet {
+    pub one_sig_account: Pubkey,
+    pub executor_required: bool,
+    pub effective_at: i64,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ModuleAdded {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ModuleRemoved {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ModuleTransactionExecuted {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub vault_index: u8,
+    pub chain_id: Option<String>,
+    pub one_sig_id: u64,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ExecutorTargetsSet {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub programs: Vec<Pubkey>,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct SpendingLimitSet {
+    pub one_sig_account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub window: i64,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct SpendingLimitRemoved {
+    pub one_sig_account: Pubkey,
+    pub mint: Pubkey,
+    pub event_version: u8,
+}
+
+/// `lamports` were paid back for the execution of a leaf by `executor`: to the `fee_payer` that
+/// sponsored it, if any, or else to `executor`; `None` if emitted before version 3
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct ExecutorReimbursed {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub lamports: u64,
+    pub event_version: u8,
+    pub fee_payer: Option<Pubkey>,
+}
+
+/// `lamports` were paid from the bounty escrow for the execution of the leaf at `nonce` by
+/// `executor`, the bounty the leaf committed to: to `recipient`, the fee payer that sponsored the
+/// execution, if any, or else `executor`
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct BountyPaid {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub recipient: Pubkey,
+    pub nonce: u64,
+    pub lamports: u64,
+    pub event_version: u8,
+}
+
+/// `lamports` were withdrawn from the bounty escrow to `recipient` by the multisig, or swept to it
+/// when the OneSig account was closed
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct BountyEscrowWithdrawn {
+    pub one_sig_account: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    pub event_version: u8,
+}
+
+/// The leaf `leaf`, at `nonce`, passes every check of `execute_transaction`, but the instruction it
+/// calls failed when `executor` simulated it, with `reported_error_code` according to the executor
+/// (unverified)
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct LeafFailureRecorded {
+    pub one_sig_account: Pubkey,
+    pub leaf: Hash,
+    pub nonce: u64,
+    pub reported_error_code: u32,
+    pub executor: Pubkey,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct OneSigClosed {
+    pub one_sig_account: Pubkey,
+    pub recipient: Pubkey,
+    pub event_version: u8,
+}
+
+/// The nonce moved other than by the execution of the leaf at it, which `TransactionExecuted`
+/// reports: no leaf past `previous_nonce` under `previous_seed` will execute, the next one is
+/// `nonce` under `seed`. Emitted by `ResetNonce`, `previous_nonce` being the one of the leaf that
+/// made the reset.
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct NonceSkipped {
+    pub one_sig_account: Pubkey,
+    pub previous_seed: Hash,
+    pub previous_nonce: u64,
+    pub seed: Hash,
+    pub nonce: u64,
+    pub event_version: u8,
+}
+
+/// Logged with `emit!` rather than emitted through an event CPI: `verify_merkle_root` carries up to
+/// `MAX_THRESHOLD` signatures, which leave no room in its transaction for the event authority.
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct MerkleRootVerified {
+    pub one_sig_account: Pubkey,
+    pub merkle_root: Hash,
+    pub expiry: i64,
+    /// Paid the rent of the `MerkleRootState`, and is refunded it on close
+    pub rent_payer: Pubkey,
+    pub event_version: u8,
+}
+
+/// `merkle_root` was re-verified by `extend_merkle_root`, moving its expiry from `previous_expiry`
+/// to `expiry`. Logged with `emit!` like `MerkleRootVerified`, for the same reason.
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct MerkleRootExtended {
+    pub one_sig_account: Pubkey,
+    pub merkle_root: Hash,
+    pub previous_expiry: i64,
+    pub expiry: i64,
+    pub event_version: u8,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct TransactionExecuted {
+    pub one_sig_account: Pubkey,
+    pub merkle_root: Hash,
+    pub nonce: u64,
+    /// CAIP-2 identifier of the chain leaves are bound to, if any
+    pub chain_id: Option<String>,
+    /// Instance identifier bound into the leaves, the `ONE_SIG_ID` of EVM deployments
+    pub one_sig_id: u64,
+    /// Who landed the leaf: the `executor` of `execute_transaction`, or the `delegate` of
+    /// `signer_execute_transaction`
+    pub executor: Pubkey,
+    /// Program the leaf called
+    pub program_id: Pubkey,
+    /// keccak256 of the instruction data the leaf called it with
+    pub ix_data_hash: Hash,
+    /// Lamports the leaf declared it may spend
+    pub value: u64,
+    /// Lamports the vault's signer actually lost to the call, before any executor reimbursement
+    pub lamports_spent: u64,
+    /// Leaves executed under a pre-verified root so far, this one included; `None` for a root
+    /// verified inline, of which nothing is recorded
+    pub root_executions: Option<u32>,
+    pub event_version: u8,
+}
+
+macro_rules! one_sig_events {
+    ($($event:ident = $discriminator:expr),* $(,)?) => {
+        $(
+            impl Event for $event {
+                const DISCRIMINATOR: [u8; 8] = $discriminator;
+            }
+        )*
+
+        /// An event of the OneSig program
+        #[derive(Clone)]
+        pub enum OneSigEvent {
+            $($event($event),)*
+        }
+
+        impl OneSigEvent {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$event(_) => stringify!($event),)*
+                }
+            }
+
+            /// The OneSig the event is about
+            pub fn one_sig_account(&self) -> &Pubkey {
+                match self {
+                    $(Self::$event(event) => &event.one_sig_account,)*
+                }
+            }
+
+            /// The `EVENT_VERSION` the event was emitted at, 0 if before events were versioned
+            pub fn event_version(&self) -> u8 {
+                match self {
+                    $(Self::$event(event) => event.event_version,)*
+                }
+            }
+
+            /// Decodes an event from its discriminator and data, `None` if the discriminator is
+            /// not of an event of the program.
+            pub fn decode(data: &[u8]) -> Option<Result<Self, InvalidEvent>> {
+                $(
+                    if let Some(event) = data.strip_prefix(&$event::DISCRIMINATOR) {
+                        return Some(
+                            decode_any_version(event)
+                                .map(Self::$event)
+                                .ok_or(InvalidEvent(stringify!($event))),
+                        );
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+
+one_sig_events!(
+    OneSigInitialized = [118, 126, 51, 209, 148, 242, 205, 61],
+    ConfigSet = [15, 104, 59, 16, 236, 241, 8, 6],
+    ExecutorAdded = [41, 10, 249, 60, 242, 196, 109, 62],
+    ExecutorRemoved = [13, 84, 95, 215, 219, 121, 202, 82],
+    ExecutorRequiredSet = [223, 67, 84, 155, 41, 220, 210, 92],
+    ModuleAdded = [231, 18, 84, 41, 172, 193, 105, 177],
+    ModuleRemoved = [72, 47, 43, 88, 229, 177, 175, 39],
+    ModuleTransactionExecuted = [141, 69, 218, 144, 111, 231, 142, 98],
+    ExecutorTargetsSet = [181, 145, 42, 4, 136, 3, 203, 153],
+    SpendingLimitSet = [84, 16, 56, 204, 246, 47, 205, 56],
+    SpendingLimitRemoved = [201, 122, 58, 180, 75, 103, 51, 139],
+    ExecutorReimbursed = [189, 49, 131, 144, 229, 112, 179, 76],
+    BountyPaid = [60, 72, 10, 171, 122, 59, 238, 148],
+    BountyEscrowWithdrawn = [79, 226, 119, 54, 245, 252, 150, 213],
+    LeafFailureRecorded = [57, 132, 42, 3, 227, 156, 176, 236],
+    OneSigClosed = [188, 186, 221, 184, 212, 56, 100, 181],
+    NonceSkipped = [134, 11, 242, 106, 42, 93, 66, 135],
+    MerkleRootVerified = [203, 219, 72, 49, 236, 244, 233, 66],
+    MerkleRootExtended = [34, 66, 117, 30, 148, 42, 15, 17],
+    TransactionExecuted = [211, 227, 168, 14, 32, 111, 189, 210],
+);
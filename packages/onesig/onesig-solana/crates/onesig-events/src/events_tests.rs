@@ -0,0 +1,219 @@
+// Tests for the events matching the program's encoding, and their decoding from event CPIs and
+// logs.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{event::EVENT_IX_TAG_LE as PROGRAM_EVENT_IX_TAG_LE, Discriminator};
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        event_authority, is_event_cpi, ConfigSet, Event, ExecutionRateLimit, ExecutorAdded,
+        ExecutorReimbursed, Hash, InvalidEvent, OneSigEvent, PreviousConfig, SelfCpiTarget,
+        SetConfigParams, SetTargetProgramsParams, TargetPolicy, TransactionExecuted,
+        EVENT_IX_TAG_LE, EVENT_VERSION,
+    };
+
+    const STATE: Pubkey = Pubkey::new_from_array([0x51; 32]);
+
+    fn executed() -> TransactionExecuted {
+        TransactionExecuted {
+            one_sig_account: STATE,
+            merkle_root: Hash([0xaa; 32]),
+            nonce: 3,
+            chain_id: Some("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".into()),
+            one_sig_id: 40_168,
+            executor: Pubkey::new_from_array([0xe0; 32]),
+            program_id: Pubkey::new_from_array([0xc0; 32]),
+            ix_data_hash: Hash([0xdd; 32]),
+            value: 1_000,
+            lamports_spent: 600,
+            root_executions: Some(7),
+            event_version: EVENT_VERSION,
+        }
+    }
+
+    #[test]
+    fn test_matches_program() {
+        assert_eq!(EVENT_VERSION, onesig::EVENT_VERSION);
+        assert_eq!(EVENT_IX_TAG_LE, PROGRAM_EVENT_IX_TAG_LE);
+        macro_rules! assert_discriminators {
+            ($($event:ident),*) => {
+                $(assert_eq!(crate::$event::DISCRIMINATOR, onesig::$event::DISCRIMINATOR);)*
+            };
+        }
+        assert_discriminators!(
+            OneSigInitialized,
+            ConfigSet,
+            ExecutorAdded,
+            ExecutorRemoved,
+            ExecutorRequiredSet,
+            ModuleAdded,
+            ModuleRemoved,
+            ModuleTransactionExecuted,
+            ExecutorTargetsSet,
+            SpendingLimitSet,
+            SpendingLimitRemoved,
+            ExecutorReimbursed,
+            BountyPaid,
+            BountyEscrowWithdrawn,
+            LeafFailureRecorded,
+            OneSigClosed,
+            NonceSkipped,
+            MerkleRootVerified,
+            MerkleRootExtended,
+            TransactionExecuted
+        );
+
+        let program = onesig::TransactionExecuted {
+            one_sig_account: STATE,
+            merkle_root: onesig::Hash([0xaa; 32]),
+            nonce: 3,
+            chain_id: Some("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".into()),
+            one_sig_id: 40_168,
+            executor: Pubkey::new_from_array([0xe0; 32]),
+            program_id: Pubkey::new_from_array([0xc0; 32]),
+            ix_data_hash: onesig::Hash([0xdd; 32]),
+            value: 1_000,
+            lamports_spent: 600,
+            root_executions: Some(7),
+            event_version: onesig::EVENT_VERSION,
+        };
+        assert_eq!(executed().data(), anchor_lang::Event::data(&program));
+
+        let programs = vec![Pubkey::new_from_array([0xc0; 32])];
+        let config_set = ConfigSet {
+            one_sig_account: STATE,
+            params: SetConfigParams::SetTargetPrograms(SetTargetProgramsParams {
+                policy: TargetPolicy::Allow,
+                programs: programs.clone(),
+            }),
+            previous: PreviousConfig::Nonce { seed: Hash([1; 32]), nonce: 9 },
+            event_version: EVENT_VERSION,
+        };
+        let program = onesig::ConfigSet {
+            one_sig_account: STATE,
+            params: onesig::SetConfigParams::SetTargetPrograms(onesig::SetTargetProgramsParams {
+                policy: onesig::TargetPolicy::Allow,
+                programs,
+            }),
+            previous: onesig::PreviousConfig::Nonce { seed: onesig::Hash([1; 32]), nonce: 9 },
+            event_version: onesig::EVENT_VERSION,
+        };
+        assert_eq!(config_set.data(), anchor_lang::Event::data(&program));
+
+        let config_set = ConfigSet {
+            params: SetConfigParams::SetSelfCpiTargets(vec![SelfCpiTarget::CloseOneSig]),
+            previous: PreviousConfig::SelfCpiTargets(vec![SelfCpiTarget::Executors]),
+            ..config_set
+        };
+        let program = onesig::ConfigSet {
+            params: onesig::SetConfigParams::SetSelfCpiTargets(vec![
+                onesig::SelfCpiTarget::CloseOneSig,
+            ]),
+            previous: onesig::PreviousConfig::SelfCpiTargets(vec![
+                onesig::SelfCpiTarget::Executors,
+            ]),
+            ..program
+        };
+        assert_eq!(config_set.data(), anchor_lang::Event::data(&program));
+
+        let config_set = ConfigSet {
+            params: SetConfigParams::SetExecutionRateLimit(ExecutionRateLimit {
+                max_executions: 10,
+                window: 3_600,
+            }),
+            previous: PreviousConfig::ExecutionRateLimit(ExecutionRateLimit {
+                max_executions: 0,
+                window: 0,
+            }),
+            ..config_set
+        };
+        let program = onesig::ConfigSet {
+            params: onesig::SetConfigParams::SetExecutionRateLimit(onesig::ExecutionRateLimit {
+                max_executions: 10,
+                window: 3_600,
+            }),
+            previous: onesig::PreviousConfig::ExecutionRateLimit(
+                onesig::ExecutionRateLimit::default(),
+            ),
+            ..program
+        };
+        assert_eq!(config_set.data(), anchor_lang::Event::data(&program));
+    }
+
+    #[test]
+    fn test_from_event_cpi_and_log() {
+        let data = [&EVENT_IX_TAG_LE[..], &executed().data()].concat();
+        let Some(Ok(OneSigEvent::TransactionExecuted(event))) = OneSigEvent::from_event_cpi(&data)
+        else {
+            panic!("not a TransactionExecuted");
+        };
+        assert_eq!((event.nonce, event.root_executions), (3, Some(7)));
+        assert!(OneSigEvent::from_event_cpi(&executed().data()).is_none());
+
+        let line = format!("Program data: {}", BASE64_STANDARD.encode(executed().data()));
+        let event = OneSigEvent::from_log(&line).unwrap().unwrap();
+        assert_eq!((event.name(), event.one_sig_account()), ("TransactionExecuted", &STATE));
+        assert!(OneSigEvent::from_log("Program log: Instruction: ExecuteTransaction").is_none());
+
+        // The tag of `chain_id`, after the account, the root and the nonce, is not one
+        let mut invalid = executed().data();
+        invalid[8 + 32 + 32 + 8] = 2;
+        assert_eq!(
+            OneSigEvent::decode(&invalid).unwrap().err(),
+            Some(InvalidEvent("TransactionExecuted"))
+        );
+
+        let program_id = Pubkey::new_from_array([0x0e; 32]);
+        let authority = event_authority(&program_id);
+        assert!(is_event_cpi(&program_id, &authority, Some(program_id), Some(authority)));
+        assert!(!is_event_cpi(&program_id, &authority, Some(program_id), Some(STATE)));
+    }
+
+    #[test]
+    fn test_event_versions() {
+        // Emitted before events were versioned, with only the account, the root and the nonce
+        let unversioned = [
+            &TransactionExecuted::DISCRIMINATOR[..],
+            STATE.as_ref(),
+            &[0xaa; 32],
+            &3u64.to_le_bytes(),
+        ]
+        .concat();
+        let event = OneSigEvent::decode(&unversioned).unwrap().unwrap();
+        assert_eq!(event.event_version(), 0);
+        let OneSigEvent::TransactionExecuted(event) = event else {
+            panic!("not a TransactionExecuted");
+        };
+        assert_eq!((event.nonce, event.chain_id, event.root_executions), (3, None, None));
+
+        // Emitted at version 1, before `effective_at`
+        let executor = Pubkey::new_from_array([0xe0; 32]);
+        let version_1 =
+            [&ExecutorAdded::DISCRIMINATOR[..], STATE.as_ref(), executor.as_ref(), &[1]].concat();
+        let Some(Ok(OneSigEvent::ExecutorAdded(event))) = OneSigEvent::decode(&version_1) else {
+            panic!("not an ExecutorAdded");
+        };
+        assert_eq!((event.executor, event.event_version, event.effective_at), (executor, 1, 0));
+
+        // Emitted at version 2, before `fee_payer`: the executor was reimbursed
+        let version_2 = [
+            &ExecutorReimbursed::DISCRIMINATOR[..],
+            STATE.as_ref(),
+            executor.as_ref(),
+            &5_000u64.to_le_bytes(),
+            &[2],
+        ]
+        .concat();
+        let Some(Ok(OneSigEvent::ExecutorReimbursed(event))) = OneSigEvent::decode(&version_2)
+        else {
+            panic!("not an ExecutorReimbursed");
+        };
+        assert_eq!((event.lamports, event.event_version, event.fee_payer), (5_000, 2, None));
+
+        // Emitted by a later version, with a field not known here
+        let later = [executed().data(), vec![0x2a; 8]].concat();
+        let event = OneSigEvent::decode(&later).unwrap().unwrap();
+        assert_eq!(event.event_version(), EVENT_VERSION);
+    }
+}
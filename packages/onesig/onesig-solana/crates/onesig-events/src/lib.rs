@@ -0,0 +1,17 @@
+//! The events of the OneSig program and their decoding, for indexers to depend on without the
+//! program crate and Anchor: the event types, their discriminators, and the unwrapping of the event
+//! CPIs and `Program data:` log lines that carry them.
+//!
+//! The types encode as the program's do, which the tests hold them to; events of any
+//! `EVENT_VERSION` decode, see [`decode_any_version`].
+
+pub mod decode;
+pub mod events;
+pub mod types;
+
+#[cfg(test)]
+mod events_tests;
+
+pub use decode::*;
+pub use events::*;
+pub use types::*;
@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+pub use onesig_core::types::{Address, Hash};
+use solana_pubkey::Pubkey;
+
+/// A configuration change, as `set_config` takes it
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub enum SetConfigParams {
+    AddSigner(Address),
+    RemoveSigner(Address),
+    SetThreshold(u8),
+    SetSeed(Hash),
+    // Rejected by the program, see `add_executor` and `remove_executor`
+    AddExecutor(Pubkey),
+    RemoveExecutor(Pubkey),
+    SetExecutorRequired(bool),
+    SetTargetPrograms(SetTargetProgramsParams),
+    SetExecutorReimbursement(u64),
+    // All zero to clear
+    SetClusterGenesisHash(Hash),
+    ResetNonce(Hash),
+    SetSelfCpiTargets(Vec<SelfCpiTarget>),
+    SetExecutionRateLimit(ExecutionRateLimit),
+}
+
+/// What a `SetConfigParams` replaced
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub enum PreviousConfig {
+    // `AddSigner` replaces nothing
+    None,
+    // Index the removed signer had in the signer list
+    SignerIndex(u16),
+    Threshold(u8),
+    Seed(Hash),
+    ExecutorRequired(bool),
+    TargetPrograms(SetTargetProgramsParams),
+    ExecutorReimbursement(u64),
+    // All zero when none was set
+    ClusterGenesisHash(Hash),
+    // Seed and nonce `ResetNonce` restarted from
+    Nonce { seed: Hash, nonce: u64 },
+    SelfCpiTargets(Vec<SelfCpiTarget>),
+    ExecutionRateLimit(ExecutionRateLimit),
+}
+
+/// Leaves executed per window of `window` seconds, unlimited when `max_executions` is 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ExecutionRateLimit {
+    pub max_executions: u32,
+    pub window: i64,
+}
+
+/// Restriction on the programs that executed leaves may call
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum TargetPolicy {
+    None,
+    // Only the listed programs can be called
+    Allow,
+    // Every program but the listed ones can be called
+    Deny,
+}
+
+/// Configuration instructions of the program, besides `set_config`, that executed leaves may call
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum SelfCpiTarget {
+    // `add_executor`, `remove_executor` and `set_executor_targets`
+    Executors,
+    // `add_module` and `remove_module`
+    Modules,
+    // `set_spending_limit` and `remove_spending_limit`
+    SpendingLimits,
+    ResizeState,
+    CloseOneSig,
+    // `withdraw_bounty_escrow`
+    BountyEscrow,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct SetTargetProgramsParams {
+    pub policy: TargetPolicy,
+    pub programs: Vec<Pubkey>,
+}
@@ -0,0 +1,372 @@
+use std::{
+    collections::BTreeMap,
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context as _, Result};
+use onesig::{ExecutorState, Hash, LeafFailure, MerkleRootState};
+use onesig_cli::{
+    commands::execute,
+    context::{Context, SimulationFailed},
+};
+use onesig_sdk::{
+    History, Leaf, OneSigAccount, PreflightError, ProposalBundle, SolanaLeaf, TransactionSigner,
+};
+
+use crate::{
+    fees::FeeRecord,
+    metrics::{error_code, Metrics},
+    notify::{notifications, Notifier},
+    progress::{leaf_key, Progress},
+    reclaim,
+};
+
+pub struct Config {
+    /// OneSig accounts to execute leaves on
+    pub states: Vec<Pubkey>,
+    /// Where bundles are read from and progress kept, unless the service only reclaims rent
+    pub execution: Option<Execution>,
+    /// Whether to close the roots the fee payer verified once they can no longer execute
+    pub reclaim_rent: bool,
+    /// Whether to record the leaves whose call fails with `record_leaf_failure`, and close their
+    /// markers once they execute
+    pub record_failures: bool,
+    /// Seconds between two passes, and the first wait before retrying a failed leaf
+    pub interval: u64,
+    /// Attempts after which a failing leaf is given up
+    pub max_attempts: u32,
+    /// Where the `ConfigSet` and `TransactionExecuted` events of the accounts are sent
+    pub notifier: Notifier,
+    /// Where the costs of the executed leaves are reported, if they are
+    pub fee_reports: Option<FeeReports>,
+}
+
+pub struct FeeReports {
+    /// Directory the CSV and JSON reports are written to
+    pub dir: PathBuf,
+    /// Seconds each report covers
+    pub interval: u64,
+}
+
+pub struct Execution {
+    /// Directory of the proposal bundles to execute
+    pub bundles: PathBuf,
+    pub progress: PathBuf,
+}
+
+/// Runs the leaves of the bundles of a directory whose roots are verified on chain, in the nonce
+/// order of each instance, and reclaims the rent of the roots it verified once they are dead.
+pub struct Executor<'a> {
+    context: &'a Context,
+    config: Config,
+    progress: Progress,
+    metrics: Arc<Metrics>,
+    /// Where the transactions of each account were walked to, for notifications
+    histories: BTreeMap<Pubkey, History>,
+}
+
+impl<'a> Executor<'a> {
+    pub fn new(context: &'a Context, config: Config, metrics: Arc<Metrics>) -> Result<Self> {
+        let progress = match &config.execution {
+            Some(execution) => Progress::load(&execution.progress)?,
+            None => Progress::default(),
+        };
+        Ok(Self { context, config, progress, metrics, histories: BTreeMap::new() })
+    }
+
+    /// Executes every leaf that is due on each instance, then reclaims rent. An instance that
+    /// fails is reported and left for the next pass without holding up the others.
+    pub fn tick(&mut self, now: u64) -> Result<()> {
+        let bundles = match &self.config.execution {
+            Some(execution) => load_bundles(&execution.bundles)?,
+            None => vec![],
+        };
+        for state in self.config.states.clone() {
+            if let Err(error) = self.run_instance(&bundles, &state, now) {
+                eprintln!("{state}: {error:#}");
+            }
+            if self.config.reclaim_rent {
+                if let Err(error) =
+                    reclaim::reclaim(self.context, &self.metrics, &state, now as i64)
+                {
+                    eprintln!("{state}: reclaiming rent: {error:#}");
+                }
+            }
+            if !self.config.notifier.is_empty() {
+                if let Err(error) = self.notify(&state, now) {
+                    eprintln!("{state}: notifying: {error:#}");
+                }
+            }
+        }
+        if let Err(error) = self.report_fees(now) {
+            eprintln!("reporting fees: {error:#}");
+        }
+        Ok(())
+    }
+
+    /// Writes the report of the fees of the period that ended, if one did. The records stay in
+    /// the progress file until their report is written.
+    fn report_fees(&mut self, now: u64) -> Result<()> {
+        let Some(reports) = &self.config.fee_reports else {
+            return Ok(());
+        };
+        if self.progress.fees_reported_at.is_none() {
+            self.progress.fees_reported_at = Some(now);
+            return self.save();
+        }
+        let Some(report) = self.progress.fee_report(now, reports.interval) else {
+            return Ok(());
+        };
+        report.save(&reports.dir)?;
+        println!(
+            "reported the fees of {} leaves from {} to {now}",
+            report.leaves.len(),
+            report.from
+        );
+        self.progress.fees_reported(&report);
+        self.save()
+    }
+
+    /// Sends the notifications of the transactions since the last pass. The first pass of an
+    /// account without a notified transaction only finds where its history ends, rather than
+    /// notifying all of it.
+    fn notify(&mut self, state: &Pubkey, now: u64) -> Result<()> {
+        let history = self.histories.entry(*state).or_insert_with(|| {
+            let mut history = History::new(*state);
+            history.last_signature = self.progress.notified.get(&state.to_string()).cloned();
+            history
+        });
+        let baseline = history.last_signature.is_none();
+        let walked = self.context.rpc().update_history(&self.context.program_id, history);
+        // What a walk cut short recorded is notified, and the next walk resumes after it
+        let records = mem::take(&mut history.records);
+        if !baseline {
+            for notification in notifications(state, &records) {
+                self.config.notifier.send(&notification, now);
+            }
+        }
+        if let Some(signature) = &history.last_signature {
+            self.progress.notified.insert(state.to_string(), signature.clone());
+            self.save()?;
+        }
+        Ok(walked?)
+    }
+
+    /// Records leaf `index` of `bundle` as stuck, its call having failed with `error_code`, unless
+    /// it already is.
+    fn record_failure(
+        &self,
+        account: &OneSigAccount,
+        bundle: &ProposalBundle,
+        index: usize,
+        leaf: &SolanaLeaf,
+        error_code: u32,
+    ) -> Result<()> {
+        let marker = self.context.client(account.address).pda.leaf_failure(&leaf.hash()).0;
+        if self.context.rpc().account_data(&marker)?.is_some() {
+            return Ok(());
+        }
+        let confirmed =
+            execute::record_failure(self.context, account, bundle, index, leaf, error_code)?;
+        println!(
+            "{}: recorded the failure of nonce {} in {}",
+            account.address, leaf.nonce, confirmed.signature
+        );
+        Ok(())
+    }
+
+    /// Closes the marker of `leaf`, if its failure was recorded, now that it executed.
+    fn close_failure(&self, state: &Pubkey, leaf: &SolanaLeaf) -> Result<()> {
+        let client = self.context.client(*state);
+        let leaf = leaf.hash();
+        let marker = client.pda.leaf_failure(&leaf).0;
+        let Some(failure) = self.context.anchor_account::<LeafFailure>(&marker)? else {
+            return Ok(());
+        };
+        let payer = self.context.payer()?;
+        let ix = client.close_leaf_failure(&failure.rent_payer, &leaf);
+        self.context.send(&payer, &[ix], &[])?;
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        match &self.config.execution {
+            Some(execution) => self.progress.save(&execution.progress),
+            None => Ok(()),
+        }
+    }
+
+    fn run_instance(&mut self, bundles: &[ProposalBundle], state: &Pubkey, now: u64) -> Result<()> {
+        if bundles.is_empty() {
+            return Ok(());
+        }
+        let mut account = self.context.one_sig_account(state)?;
+        let client = self.context.client(*state);
+        let mut usable = vec![];
+        for bundle in bundles {
+            let pending = bundle.leaves.iter().any(|leaf| {
+                matches!(leaf, Leaf::Solana(leaf)
+                    if leaf.one_sig_state == *state && leaf.nonce >= account.header.nonce)
+            });
+            if !pending {
+                continue;
+            }
+            let address = client.pda.merkle_root_state(&bundle.merkle_root).0;
+            let root = self.context.anchor_account::<MerkleRootState>(&address)?;
+            if root.is_some_and(|root| root_usable(&root, &account.header.seed, now as i64)) {
+                usable.push(bundle.merkle_root);
+            }
+        }
+
+        let verified = |root: &Hash| usable.contains(root);
+        let pending = pending_leaves(bundles, state, account.header.nonce, verified);
+        self.metrics.set_pending_leaves(state, pending);
+        if pending > 0 && account.header.executor_required() {
+            let payer = self.context.payer()?.pubkey();
+            let executor_state = client.pda.executor_state(&payer).0;
+            if self.context.anchor_account::<ExecutorState>(&executor_state)?.is_none() {
+                println!("{state}: skipped, {payer} is not one of its executors");
+                return Ok(());
+            }
+        }
+
+        // Whose costs the executed leaves are, when they are reported
+        let executor = match &self.config.fee_reports {
+            Some(_) => Some(self.context.payer()?.pubkey()),
+            None => None,
+        };
+        while let Some((bundle, index, leaf)) =
+            next_leaf(bundles, state, account.header.nonce, verified)
+        {
+            let key = leaf_key(&bundle.merkle_root, index);
+            if !self.progress.ready(&key, now, self.config.max_attempts) {
+                break;
+            }
+            // Later nonces cannot execute before it either
+            if let Some(not_before) = held_until(bundle, index, now) {
+                println!("{state}: holding {key} at nonce {} until {not_before}", leaf.nonce);
+                break;
+            }
+            let nonce = leaf.nonce;
+            let started = Instant::now();
+            match execute::execute_leaf(self.context, &account, bundle, index, leaf) {
+                Ok(confirmed) => {
+                    println!("{state}: executed {key} at nonce {nonce} in {}", confirmed.signature);
+                    self.metrics.executed(state, started.elapsed());
+                    let transaction =
+                        self.metrics.record_transaction(self.context, state, &confirmed.signature);
+                    if let (Some(transaction), Some(executor)) = (transaction, &executor) {
+                        let tip = self.context.jito_tip().unwrap_or(0);
+                        let leaf = key.clone();
+                        let record =
+                            FeeRecord::new(executor, state, leaf, nonce, now, tip, &transaction);
+                        self.progress.fees.push(record);
+                    }
+                    let signature = confirmed.signature;
+                    self.progress.executed(&key, state.to_string(), nonce, signature);
+                    self.save()?;
+                    if self.config.record_failures {
+                        if let Err(error) = self.close_failure(state, leaf) {
+                            eprintln!("{state}: closing the failure of {key}: {error:#}");
+                        }
+                    }
+                },
+                Err(error) => {
+                    eprintln!("{state}: {key} at nonce {nonce} failed: {error:#}");
+                    self.metrics.failed(state, error_code(&error));
+                    let call_error = call_error(&error, &self.context.program_id);
+                    if let (true, Some(code)) = (self.config.record_failures, call_error) {
+                        if let Err(error) = self.record_failure(&account, bundle, index, leaf, code)
+                        {
+                            eprintln!("{state}: recording the failure of {key}: {error:#}");
+                        }
+                    }
+                    let error = format!("{error:#}");
+                    let interval = self.config.interval;
+                    self.progress.failed(&key, state.to_string(), nonce, error, now, interval);
+                    return self.save();
+                },
+            }
+            account = self.context.one_sig_account(state)?;
+            let pending = pending_leaves(bundles, state, account.header.nonce, verified);
+            self.metrics.set_pending_leaves(state, pending);
+        }
+        Ok(())
+    }
+}
+
+/// The custom error of the program a leaf calls that executing it failed with in simulation,
+/// `None` when the OneSig program, or the runtime, refused the execution instead.
+pub fn call_error(error: &anyhow::Error, program_id: &Pubkey) -> Option<u32> {
+    match &error.downcast_ref::<SimulationFailed>()?.error {
+        PreflightError::Custom { program_id: Some(failed), code, .. } if failed != program_id =>
+            Some(*code),
+        _ => None,
+    }
+}
+
+/// The bundles of the `*.json` files of `dir`. Files that are not valid bundles are reported and
+/// left out, so a half-written file does not stop the others from executing.
+pub fn load_bundles(dir: &Path) -> Result<Vec<ProposalBundle>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("cannot read {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<_>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "json"));
+    paths.sort();
+    let mut bundles = vec![];
+    for path in paths {
+        match ProposalBundle::load(&path).and_then(|bundle| bundle.verify().map(|_| bundle)) {
+            Ok(bundle) => bundles.push(bundle),
+            Err(error) => eprintln!("{}: skipped, {error}", path.display()),
+        }
+    }
+    Ok(bundles)
+}
+
+/// Whether leaves of a verified root can still execute: signed with the current seed, not
+/// expired, and with executions left.
+pub fn root_usable(root: &MerkleRootState, seed: &Hash, now: i64) -> bool {
+    root.verified_under(seed) && root.expiry >= now && root.remaining_executions != Some(0)
+}
+
+/// The leaf of `state` at `nonce` in the first bundle with one whose root is `verified`, with its
+/// bundle and index
+pub fn next_leaf<'a>(
+    bundles: &'a [ProposalBundle],
+    state: &Pubkey,
+    nonce: u64,
+    verified: impl Fn(&Hash) -> bool,
+) -> Option<(&'a ProposalBundle, usize, &'a SolanaLeaf)> {
+    bundles
+        .iter()
+        .filter(|bundle| verified(&bundle.merkle_root))
+        .find_map(|bundle| {
+            let (index, leaf) = execute::pending_leaf(bundle, state, nonce)?;
+            Some((bundle, index, leaf))
+        })
+}
+
+/// When leaf `index` of `bundle` is scheduled after `now`, the Unix time it is held until
+pub fn held_until(bundle: &ProposalBundle, index: usize, now: u64) -> Option<u64> {
+    bundle.not_before(index).filter(|not_before| *not_before > now)
+}
+
+/// Leaves of `state` from `nonce` on in the bundles whose root is `verified`
+pub fn pending_leaves(
+    bundles: &[ProposalBundle],
+    state: &Pubkey,
+    nonce: u64,
+    verified: impl Fn(&Hash) -> bool,
+) -> u64 {
+    let leaves = bundles.iter().filter(|bundle| verified(&bundle.merkle_root)).flat_map(|bundle| {
+        bundle.leaves.iter().filter(|leaf| {
+            matches!(leaf, Leaf::Solana(leaf) if leaf.one_sig_state == *state && leaf.nonce >= nonce)
+        })
+    });
+    leaves.count() as u64
+}
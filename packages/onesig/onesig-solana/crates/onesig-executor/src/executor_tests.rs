@@ -0,0 +1,145 @@
+// Tests for choosing the bundle leaves the executor runs, and the failures it records.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{Hash, MerkleRootState, OneSigError, MERKLE_ROOT_STATE_VERSION};
+    use onesig_cli::context::SimulationFailed;
+    use onesig_sdk::{
+        LeafMetadata, OneSigInstruction, PreflightError, ProposalBundle, ProposalMetadata,
+        SolanaInstance, SolanaLeafGuards, TreeCoordinator,
+    };
+
+    use crate::executor::{
+        call_error, held_until, load_bundles, next_leaf, pending_leaves, root_usable,
+    };
+
+    fn bundle(state: Pubkey, nonce: u64, leaves: u8) -> ProposalBundle {
+        let mut coordinator = TreeCoordinator::new();
+        let instance = coordinator.add_solana_instance(SolanaInstance {
+            chain_id: "solana:test".into(),
+            one_sig_state: state,
+            one_sig_id: 1,
+            nonce,
+            cluster_genesis_hash: None,
+        });
+        for data in 0..leaves {
+            let instruction = OneSigInstruction {
+                program_id: Pubkey::new_from_array([9; 32]),
+                accounts: vec![],
+                data: vec![data],
+                value: 0,
+            };
+            coordinator
+                .push_solana(instance, instruction, SolanaLeafGuards::default())
+                .unwrap();
+        }
+        coordinator
+            .bundle(ProposalMetadata {
+                title: format!("Nonce {nonce}"),
+                description: String::new(),
+                seed: Hash([0; 32]),
+                expiry: 0,
+                max_executions: 0,
+            })
+            .unwrap()
+    }
+
+    fn root(seed: Hash, expiry: i64, remaining_executions: Option<u32>) -> MerkleRootState {
+        MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            seed_commitment: MerkleRootState::seed_commitment(&seed),
+            expiry,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions,
+        }
+    }
+
+    #[test]
+    fn test_root_usable() {
+        let seed = Hash([2; 32]);
+        assert!(root_usable(&root(seed, 100, None), &seed, 100));
+        assert!(root_usable(&root(seed, 100, Some(1)), &seed, 0));
+        assert!(!root_usable(&root(seed, 100, None), &seed, 101));
+        assert!(!root_usable(&root(seed, 100, Some(0)), &seed, 0));
+        assert!(!root_usable(&root(Hash([3; 32]), 100, None), &seed, 0));
+    }
+
+    #[test]
+    fn test_next_leaf_follows_the_nonce_across_bundles() {
+        let state = Pubkey::new_unique();
+        let bundles = [bundle(state, 0, 2), bundle(state, 2, 1)];
+        let all = |_: &Hash| true;
+
+        let (found, index, leaf) = next_leaf(&bundles, &state, 1, all).unwrap();
+        assert!(found.merkle_root == bundles[0].merkle_root);
+        assert_eq!((index, leaf.nonce), (1, 1));
+        let (found, _, leaf) = next_leaf(&bundles, &state, 2, all).unwrap();
+        assert!(found.merkle_root == bundles[1].merkle_root);
+        assert_eq!(leaf.nonce, 2);
+        assert!(next_leaf(&bundles, &state, 3, all).is_none());
+        assert!(next_leaf(&bundles, &Pubkey::new_unique(), 0, all).is_none());
+
+        // Leaves of roots not verified on chain wait
+        let first = bundles[0].merkle_root;
+        assert!(next_leaf(&bundles, &state, 0, |root: &Hash| *root != first).is_none());
+    }
+
+    #[test]
+    fn test_held_until() {
+        let mut scheduled = bundle(Pubkey::new_unique(), 0, 2);
+        assert_eq!(held_until(&scheduled, 0, 0), None);
+
+        scheduled.leaf_metadata =
+            vec![LeafMetadata::default(), LeafMetadata { not_before: Some(100) }];
+        assert_eq!(held_until(&scheduled, 0, 0), None);
+        assert_eq!(held_until(&scheduled, 1, 99), Some(100));
+        assert_eq!(held_until(&scheduled, 1, 100), None);
+    }
+
+    #[test]
+    fn test_pending_leaves() {
+        let state = Pubkey::new_unique();
+        let bundles =
+            [bundle(state, 0, 2), bundle(state, 2, 2), bundle(Pubkey::new_unique(), 0, 1)];
+        let first = bundles[0].merkle_root;
+
+        assert_eq!(pending_leaves(&bundles, &state, 1, |_: &Hash| true), 3);
+        assert_eq!(pending_leaves(&bundles, &state, 1, |root: &Hash| *root != first), 2);
+        assert_eq!(pending_leaves(&bundles, &state, 4, |_: &Hash| true), 0);
+    }
+
+    #[test]
+    fn test_call_error() {
+        let program_id = Pubkey::new_unique();
+        let code = |error: PreflightError| {
+            call_error(&SimulationFailed { error, logs: vec![] }.into(), &program_id)
+        };
+        let custom = |program_id| PreflightError::Custom { index: 0, program_id, code: 6 };
+        assert_eq!(code(custom(Some(Pubkey::new_unique()))), Some(6));
+
+        // Failures of the OneSig program are not ones of the call
+        assert_eq!(code(custom(Some(program_id))), None);
+        assert_eq!(code(custom(None)), None);
+        assert_eq!(code(PreflightError::OneSig(OneSigError::InvalidProof)), None);
+        assert_eq!(call_error(&anyhow::anyhow!("timed out"), &program_id), None);
+    }
+
+    #[test]
+    fn test_load_bundles_skips_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("onesig-bundles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut tampered = bundle(Pubkey::new_unique(), 0, 1);
+        tampered.merkle_root = Hash([7; 32]);
+        bundle(Pubkey::new_unique(), 0, 1).save(dir.join("a.json")).unwrap();
+        tampered.save(dir.join("b.json")).unwrap();
+        std::fs::write(dir.join("c.json"), "{").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a bundle").unwrap();
+
+        assert_eq!(load_bundles(&dir).unwrap().len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context as _, Result};
+use onesig_sdk::{OneSigEvent, TransactionEvents};
+use serde::{Deserialize, Serialize};
+
+/// Lamports each signature of a transaction pays; what a fee is beyond them is its priority fee.
+/// The executor signs its transactions alone.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// What executing a leaf cost its executor, kept until a report accounts for it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRecord {
+    /// Fee payer of the transaction
+    pub executor: String,
+    pub state: String,
+    /// See [`leaf_key`](crate::progress::leaf_key)
+    pub leaf: String,
+    pub nonce: u64,
+    pub signature: String,
+    /// Unix time the leaf executed at
+    pub executed_at: u64,
+    /// Lamports of the fee, signatures and priority fee together
+    pub fee: u64,
+    pub priority_fee: u64,
+    pub compute_units: u64,
+    /// Lamports tipped to land the transaction through Jito
+    #[serde(default)]
+    pub tip: u64,
+    /// Lamports the OneSig paid back to the executor, see `SetExecutorReimbursement`
+    pub reimbursed: u64,
+}
+
+impl FeeRecord {
+    /// The cost of the landed transaction of leaf `leaf` of `state` to `executor`, which tipped
+    /// `tip` lamports
+    pub fn new(
+        executor: &Pubkey,
+        state: &Pubkey,
+        leaf: String,
+        nonce: u64,
+        executed_at: u64,
+        tip: u64,
+        transaction: &TransactionEvents,
+    ) -> Self {
+        let fee = transaction.fee.unwrap_or(0);
+        let reimbursed = transaction
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                // Paid to whoever paid the fees: the executor, unless a fee payer sponsored it
+                OneSigEvent::ExecutorReimbursed(event)
+                    if event.fee_payer.unwrap_or(event.executor) == *executor =>
+                    Some(event.lamports),
+                _ => None,
+            })
+            .sum();
+        Self {
+            executor: executor.to_string(),
+            state: state.to_string(),
+            leaf,
+            nonce,
+            signature: transaction.signature.clone(),
+            executed_at,
+            fee,
+            priority_fee: fee.saturating_sub(LAMPORTS_PER_SIGNATURE),
+            compute_units: transaction.compute_units_consumed.unwrap_or(0),
+            tip,
+            reimbursed,
+        }
+    }
+}
+
+/// The costs of the leaves executed over a period, by executor and OneSig account, for the
+/// treasury to reimburse
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeReport {
+    /// Unix times the period starts and ends at
+    pub from: u64,
+    pub to: u64,
+    pub totals: Vec<FeeTotal>,
+    pub leaves: Vec<FeeRecord>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTotal {
+    pub executor: String,
+    pub state: String,
+    pub leaves: u64,
+    pub fees: u64,
+    pub priority_fees: u64,
+    pub compute_units: u64,
+    pub tips: u64,
+    pub reimbursed: u64,
+}
+
+impl FeeReport {
+    pub fn new(from: u64, to: u64, leaves: Vec<FeeRecord>) -> Self {
+        let mut totals: BTreeMap<(&str, &str), FeeTotal> = BTreeMap::new();
+        for leaf in &leaves {
+            let total = totals.entry((&leaf.executor, &leaf.state)).or_insert_with(|| FeeTotal {
+                executor: leaf.executor.clone(),
+                state: leaf.state.clone(),
+                ..FeeTotal::default()
+            });
+            total.leaves += 1;
+            total.fees += leaf.fee;
+            total.priority_fees += leaf.priority_fee;
+            total.compute_units += leaf.compute_units;
+            total.tips += leaf.tip;
+            total.reimbursed += leaf.reimbursed;
+        }
+        let totals = totals.into_values().collect();
+        Self { from, to, totals, leaves }
+    }
+
+    /// The totals as CSV, one line per executor and OneSig account
+    pub fn csv(&self) -> String {
+        let mut out = String::from(
+            "executor,state,leaves,fees,priority_fees,compute_units,tips,reimbursed\n",
+        );
+        for total in &self.totals {
+            let FeeTotal {
+                executor,
+                state,
+                leaves,
+                fees,
+                priority_fees,
+                compute_units,
+                tips,
+                reimbursed,
+            } = total;
+            let _ = writeln!(
+                out,
+                "{executor},{state},{leaves},{fees},{priority_fees},{compute_units},{tips},\
+                 {reimbursed}"
+            );
+        }
+        out
+    }
+
+    /// Writes `fees-<from>-<to>.csv` and `fees-<from>-<to>.json` to `dir`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let name = format!("fees-{}-{}", self.from, self.to);
+        let csv = dir.join(format!("{name}.csv"));
+        fs::write(&csv, self.csv()).with_context(|| format!("cannot write {}", csv.display()))?;
+        let json = dir.join(format!("{name}.json"));
+        fs::write(&json, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("cannot write {}", json.display()))
+    }
+}
@@ -0,0 +1,98 @@
+// Tests for the fee records of executed leaves and the reports summing them.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{ExecutorReimbursed, EVENT_VERSION};
+    use onesig_sdk::{OneSigEvent, TransactionEvents};
+
+    use crate::{
+        fees::{FeeRecord, FeeReport},
+        progress::Progress,
+    };
+
+    fn transaction(fee: u64, reimbursed_to: &[(Pubkey, u64)]) -> TransactionEvents {
+        let events = reimbursed_to
+            .iter()
+            .map(|(executor, lamports)| {
+                OneSigEvent::ExecutorReimbursed(ExecutorReimbursed {
+                    one_sig_account: Pubkey::default(),
+                    executor: *executor,
+                    lamports: *lamports,
+                    event_version: EVENT_VERSION,
+                    fee_payer: None,
+                })
+            })
+            .collect();
+        TransactionEvents {
+            signature: "5ig".into(),
+            slot: 1,
+            block_time: None,
+            err: None,
+            fee: Some(fee),
+            compute_units_consumed: Some(40_000),
+            events,
+        }
+    }
+
+    fn record(executor: &Pubkey, state: &Pubkey, fee: u64) -> FeeRecord {
+        FeeRecord::new(executor, state, "leaf".into(), 0, 100, 1_000, &transaction(fee, &[]))
+    }
+
+    #[test]
+    fn test_record() {
+        let (executor, other, state) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transaction = transaction(7_500, &[(executor, 10_000), (other, 3)]);
+        let record = FeeRecord::new(&executor, &state, "leaf".into(), 4, 100, 0, &transaction);
+
+        assert_eq!((record.fee, record.priority_fee, record.compute_units), (7_500, 2_500, 40_000));
+        // Only what is paid back to the executor counts
+        assert_eq!(record.reimbursed, 10_000);
+        assert_eq!((record.nonce, record.executed_at, record.signature.as_str()), (4, 100, "5ig"));
+    }
+
+    #[test]
+    fn test_report() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let state = Pubkey::new_unique();
+        let leaves = vec![
+            record(&first, &state, 5_000),
+            record(&second, &state, 6_000),
+            record(&first, &state, 8_000),
+        ];
+        let report = FeeReport::new(0, 86_400, leaves);
+
+        assert_eq!(report.totals.len(), 2);
+        let total = report.totals.iter().find(|total| total.executor == first.to_string()).unwrap();
+        assert_eq!((total.leaves, total.fees, total.priority_fees), (2, 13_000, 3_000));
+        assert_eq!((total.compute_units, total.tips), (80_000, 2_000));
+
+        let csv = report.csv();
+        assert!(csv.starts_with(
+            "executor,state,leaves,fees,priority_fees,compute_units,tips,reimbursed\n"
+        ));
+        assert!(csv.contains(&format!("{first},{state},2,13000,3000,80000,2000,0\n")));
+        assert_eq!(csv.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_reports_are_periodic() {
+        let (executor, state) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut progress = Progress::default();
+        // Not started
+        assert_eq!(progress.fee_report(u64::MAX, 10), None);
+
+        progress.fees_reported_at = Some(100);
+        progress.fees.push(record(&executor, &state, 5_000));
+        assert_eq!(progress.fee_report(109, 10), None);
+        let report = progress.fee_report(110, 10).unwrap();
+        assert_eq!((report.from, report.to, report.leaves.len()), (100, 110, 1));
+
+        // A leaf executed while the report was written waits for the next one
+        progress.fees.push(record(&executor, &state, 6_000));
+        progress.fees_reported(&report);
+        assert_eq!(progress.fees_reported_at, Some(110));
+        assert_eq!(progress.fees.len(), 1);
+        assert_eq!(progress.fees[0].fee, 6_000);
+    }
+}
@@ -0,0 +1,206 @@
+//! `onesig-executor`: a service executing the leaves of signed proposal bundles.
+//!
+//! It watches a directory of bundles and, on each of the OneSig accounts it is given, executes
+//! the leaves whose roots were verified on chain with `onesig-cli verify-root`, one transaction
+//! per leaf in nonce order. A leaf the bundle schedules with a `notBefore` time is held until then,
+//! and the later leaves of its instance with it. Accounts that require an executor are skipped
+//! unless the fee payer is registered as one. Failed leaves are retried with a growing wait up to a
+//! number of attempts, and what was executed or attempted is kept in a progress file, so a restart
+//! resumes where the service stopped. With `--reclaim-rent` it also keeps the rent of the roots the
+//! fee payer verified from lingering: once expired, or signed with a former seed, they are closed
+//! in batches. With `--metrics` it serves Prometheus metrics of what it does: pending leaves,
+//! execution latency, failures by error code, and the compute units and fees it spends. With
+//! webhooks, Slack or Telegram configured, it notifies the `ConfigSet` and `TransactionExecuted`
+//! events of the accounts as it finds them in their transaction history. With `--fee-reports` it
+//! accounts for the fees, priority fees and reimbursements of each leaf it executes, and writes
+//! periodic CSV and JSON reports of them by executor and OneSig account. With `--jito-url` its
+//! transactions go to a Jito block engine in tipped bundles, for executions that cannot wait out
+//! congestion, such as emergency pauses. With `--record-failures`, a leaf whose call fails in
+//! simulation is recorded on chain with `record_leaf_failure`, for monitors to see it stuck, and
+//! its marker closed once it executes.
+
+mod executor;
+mod fees;
+mod metrics;
+mod notify;
+mod progress;
+mod reclaim;
+
+#[cfg(test)]
+mod executor_tests;
+#[cfg(test)]
+mod fees_tests;
+#[cfg(test)]
+mod metrics_tests;
+#[cfg(test)]
+mod notify_tests;
+#[cfg(test)]
+mod progress_tests;
+#[cfg(test)]
+mod reclaim_tests;
+
+use std::{
+    env,
+    path::PathBuf,
+    process::ExitCode,
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Result};
+use onesig_cli::{args::Args, context::Context};
+use onesig_sdk::MIN_JITO_TIP;
+
+use crate::{
+    executor::{Config, Execution, Executor, FeeReports},
+    metrics::Metrics,
+    notify::{Notifier, Telegram},
+};
+
+/// Lamports each transaction tips when sent through Jito, unless `--jito-tip` says otherwise
+const DEFAULT_JITO_TIP: u64 = 10_000;
+
+const USAGE: &str = "\
+usage: onesig-executor --state <name|pubkey>... [--bundles <dir> --progress <path>]
+       [--reclaim-rent] [--record-failures] [--interval <seconds>] [--max-attempts <n>]
+       [--once] [--metrics <address>] [--webhook <url>...] [--slack-webhook <url>...]
+       [--telegram-chat <id>] [--fee-reports <dir> [--report-interval <seconds>]]
+       [--jito-url <url> [--jito-tip <lamports>]]
+
+  --state <name|pubkey>    OneSig account to execute leaves on, by address or profile name
+  --bundles <dir>          directory of the proposal bundles to execute, as *.json files
+  --progress <path>        file recording executed and failed leaves, and the last notified
+                           transactions, across restarts
+  --interval <seconds>     wait between two passes, and before the first retry [default: 10]
+  --max-attempts <n>       attempts after which a failing leaf is given up [default: 5]
+  --reclaim-rent           closes the dead roots whose rent the fee payer paid, refunding it
+  --record-failures        records on chain the leaves whose call fails in simulation, paying
+                           the rent of each marker until the leaf executes
+  --once                   makes a single pass and exits
+  --metrics <address>      serves Prometheus metrics at http://<address>/metrics,
+                           e.g. 0.0.0.0:9464
+  --webhook <url>          posts the JSON of each ConfigSet and TransactionExecuted event,
+                           signed with ONESIG_WEBHOOK_SECRET when set
+  --slack-webhook <url>    posts each event to a Slack incoming webhook
+  --telegram-chat <id>     sends each event to a Telegram chat, as the bot of
+                           ONESIG_TELEGRAM_TOKEN
+  --fee-reports <dir>      writes CSV and JSON reports of the fees the executed leaves cost, by
+                           executor and OneSig account
+  --report-interval <seconds>
+                           period each fee report covers [default: 86400]
+  --jito-url <url>         sends transactions in bundles of a Jito block engine, e.g.
+                           https://mainnet.block-engine.jito.wtf/api/v1/bundles, so they land
+                           past congestion of the public transaction queue
+  --jito-tip <lamports>    tip of each transaction sent through Jito [default: 10000]
+
+  --profile, --config, --url, --commitment, --keypair, --program-id and --max-unit-price are
+  those of onesig-cli; the fee payer executes the leaves.
+";
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(args: impl IntoIterator<Item = String>) -> Result<()> {
+    let mut args = Args::parse(args);
+    if args.flag("help")? {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    let mut context = Context::from_args(&mut args)?;
+    if let Some(url) = args.value("jito-url")? {
+        let tip = args.parsed("jito-tip")?.unwrap_or(DEFAULT_JITO_TIP);
+        if tip < MIN_JITO_TIP {
+            bail!("the block engine takes tips of at least {MIN_JITO_TIP} lamports");
+        }
+        context = context.with_jito(url, tip);
+    }
+    let states = args
+        .values("state")?
+        .iter()
+        .map(|state| context.state(state))
+        .collect::<Result<Vec<_>>>()?;
+    if states.is_empty() {
+        bail!("give at least one --state\n\n{USAGE}");
+    }
+    let execution = match (args.value("bundles")?, args.value("progress")?) {
+        (Some(bundles), Some(progress)) =>
+            Some(Execution { bundles: PathBuf::from(bundles), progress: PathBuf::from(progress) }),
+        (None, None) => None,
+        _ => bail!("--bundles and --progress go together"),
+    };
+    let fee_reports = match args.value("fee-reports")? {
+        Some(dir) => Some(FeeReports {
+            dir: PathBuf::from(dir),
+            interval: args.parsed("report-interval")?.unwrap_or(24 * 60 * 60),
+        }),
+        None => None,
+    };
+    if fee_reports.is_some() && execution.is_none() {
+        bail!("--fee-reports needs --bundles and --progress, which keeps its records");
+    }
+    let reclaim_rent = args.flag("reclaim-rent")?;
+    let record_failures = args.flag("record-failures")?;
+    if record_failures && execution.is_none() {
+        bail!("--record-failures needs --bundles and --progress, the leaves it executes");
+    }
+    let notifier = notifier(&mut args)?;
+    if execution.is_none() && !reclaim_rent && notifier.is_empty() {
+        bail!("give --bundles and --progress, --reclaim-rent, or notification targets\n\n{USAGE}");
+    }
+    let config = Config {
+        states,
+        execution,
+        reclaim_rent,
+        record_failures,
+        interval: args.parsed("interval")?.unwrap_or(10),
+        max_attempts: args.parsed("max-attempts")?.unwrap_or(5),
+        notifier,
+        fee_reports,
+    };
+    let once = args.flag("once")?;
+    let metrics_address = args.value("metrics")?;
+    args.finish()?;
+
+    let interval = Duration::from_secs(config.interval);
+    let metrics = Arc::new(Metrics::default());
+    if let Some(address) = metrics_address {
+        metrics::serve(&address, metrics.clone())?;
+    }
+    let mut executor = Executor::new(&context, config, metrics)?;
+    loop {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        executor.tick(now)?;
+        if once {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Reads the notification targets. Secrets come from the environment rather than the command
+/// line, which other users of the machine can list.
+fn notifier(args: &mut Args) -> Result<Notifier> {
+    let telegram = match args.value("telegram-chat")? {
+        Some(chat_id) => {
+            let Ok(token) = env::var("ONESIG_TELEGRAM_TOKEN") else {
+                bail!("--telegram-chat needs the bot token in ONESIG_TELEGRAM_TOKEN");
+            };
+            Some(Telegram { token, chat_id })
+        },
+        None => None,
+    };
+    Ok(Notifier {
+        webhooks: args.values("webhook")?,
+        secret: env::var("ONESIG_WEBHOOK_SECRET").ok().map(String::into_bytes),
+        slack: args.values("slack-webhook")?,
+        telegram,
+    })
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context as _, Result};
+use onesig_cli::context::{Context, SimulationFailed};
+use onesig_sdk::{PreflightError, SdkError, TransactionEvents};
+use serde_json::Value;
+
+/// Upper bounds of the buckets of the execution latency histogram, in seconds
+const LATENCY_BUCKETS: [f64; 8] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+/// What the service reports to Prometheus, by OneSig account
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    pending_leaves: BTreeMap<String, u64>,
+    executed: BTreeMap<String, u64>,
+    latency: BTreeMap<String, Histogram>,
+    /// By account and error code
+    failures: BTreeMap<(String, String), u64>,
+    compute_units: BTreeMap<String, u64>,
+    fees: BTreeMap<String, u64>,
+    roots_closed: BTreeMap<String, u64>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    /// Observations at most each bound of [`LATENCY_BUCKETS`], not cumulated
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Metrics {
+    /// Leaves of verified roots that wait for their nonce on `state`
+    pub fn set_pending_leaves(&self, state: &Pubkey, pending: u64) {
+        self.inner().pending_leaves.insert(state.to_string(), pending);
+    }
+
+    /// Records a leaf executed in `latency`, from submission to confirmation.
+    pub fn executed(&self, state: &Pubkey, latency: Duration) {
+        let mut inner = self.inner();
+        *inner.executed.entry(state.to_string()).or_default() += 1;
+        let histogram = inner.latency.entry(state.to_string()).or_default();
+        let seconds = latency.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            histogram.buckets[bucket] += 1;
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    pub fn failed(&self, state: &Pubkey, code: String) {
+        *self.inner().failures.entry((state.to_string(), code)).or_default() += 1;
+    }
+
+    /// Records the cost of a landed transaction of the service, as `getTransaction` reports it.
+    pub fn spent(&self, state: &Pubkey, compute_units: Option<u64>, fee: Option<u64>) {
+        let mut inner = self.inner();
+        *inner.compute_units.entry(state.to_string()).or_default() += compute_units.unwrap_or(0);
+        *inner.fees.entry(state.to_string()).or_default() += fee.unwrap_or(0);
+    }
+
+    /// Fetches a landed transaction of the service to record its cost, and returns it. The cost
+    /// is left out when the node cannot serve the transaction yet, rather than failing what the
+    /// service did.
+    pub fn record_transaction(
+        &self,
+        context: &Context,
+        state: &Pubkey,
+        signature: &str,
+    ) -> Option<TransactionEvents> {
+        match context.rpc().transaction_events(&context.program_id, signature) {
+            Ok(Some(transaction)) => {
+                self.spent(state, transaction.compute_units_consumed, transaction.fee);
+                Some(transaction)
+            },
+            Ok(None) => {
+                eprintln!("{state}: {signature} not found, its cost is left out");
+                None
+            },
+            Err(error) => {
+                eprintln!("{state}: cannot fetch {signature}: {error}");
+                None
+            },
+        }
+    }
+
+    pub fn roots_closed(&self, state: &Pubkey, closed: u64) {
+        *self.inner().roots_closed.entry(state.to_string()).or_default() += closed;
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // The metrics stay consistent field by field, so a panic while holding the lock is moot
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let inner = self.inner();
+        let mut out = String::new();
+        family(
+            &mut out,
+            "onesig_executor_pending_leaves",
+            "gauge",
+            "Leaves of verified roots waiting to execute",
+            &inner.pending_leaves,
+        );
+        family(
+            &mut out,
+            "onesig_executor_executed_leaves_total",
+            "counter",
+            "Leaves executed",
+            &inner.executed,
+        );
+
+        let name = "onesig_executor_execution_seconds";
+        let _ = writeln!(out, "# HELP {name} Time from submitting a leaf to its confirmation");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (state, histogram) in &inner.latency {
+            let mut cumulated = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                cumulated += count;
+                let _ =
+                    writeln!(out, "{name}_bucket{{state=\"{state}\",le=\"{bound}\"}} {cumulated}");
+            }
+            let count = histogram.count;
+            let _ = writeln!(out, "{name}_bucket{{state=\"{state}\",le=\"+Inf\"}} {count}");
+            let _ = writeln!(out, "{name}_sum{{state=\"{state}\"}} {}", histogram.sum);
+            let _ = writeln!(out, "{name}_count{{state=\"{state}\"}} {count}");
+        }
+
+        let name = "onesig_executor_failures_total";
+        let _ = writeln!(out, "# HELP {name} Failed attempts to execute a leaf, by error code");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        for ((state, code), count) in &inner.failures {
+            let _ = writeln!(out, "{name}{{state=\"{state}\",code=\"{}\"}} {count}", escape(code));
+        }
+
+        family(
+            &mut out,
+            "onesig_executor_compute_units_total",
+            "counter",
+            "Compute units consumed by the transactions of the service",
+            &inner.compute_units,
+        );
+        family(
+            &mut out,
+            "onesig_executor_fees_lamports_total",
+            "counter",
+            "Lamports spent on the fees of the transactions of the service",
+            &inner.fees,
+        );
+        family(
+            &mut out,
+            "onesig_executor_roots_closed_total",
+            "counter",
+            "Merkle roots closed to reclaim their rent",
+            &inner.roots_closed,
+        );
+        out
+    }
+}
+
+/// Writes a metric labelled by OneSig account.
+fn family(out: &mut String, name: &str, kind: &str, help: &str, values: &BTreeMap<String, u64>) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (state, value) in values {
+        let _ = writeln!(out, "{name}{{state=\"{state}\"}} {value}");
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// A short, stable code for why executing a leaf failed, to count failures by: the name of the
+/// OneSig error or of the runtime error, the code of another program's custom error, or the
+/// variant of the SDK error.
+pub fn error_code(error: &anyhow::Error) -> String {
+    if let Some(failed) = error.downcast_ref::<SimulationFailed>() {
+        return match &failed.error {
+            PreflightError::OneSig(error) => variant(&format!("{error:?}")),
+            PreflightError::Custom { code, .. } => code.to_string(),
+            PreflightError::Instruction { error, .. } | PreflightError::Transaction(error) =>
+                match error {
+                    Value::String(name) => name.clone(),
+                    Value::Object(error) => error.keys().next().cloned().unwrap_or_default(),
+                    error => error.to_string(),
+                },
+        };
+    }
+    match error.downcast_ref::<SdkError>() {
+        Some(error) => variant(&format!("{error:?}")),
+        None => "Other".into(),
+    }
+}
+
+/// The name of the enum variant a `Debug` representation starts with
+fn variant(debug: &str) -> String {
+    debug.split(['(', ' ', '{']).next().unwrap_or_default().to_string()
+}
+
+/// Serves [`Metrics::render`] at `GET /metrics` of `address`, from a thread of its own.
+pub fn serve(address: &str, metrics: Arc<Metrics>) -> Result<()> {
+    let listener =
+        TcpListener::bind(address).with_context(|| format!("cannot listen on {address}"))?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(error) = respond(stream, &metrics) {
+                eprintln!("metrics: {error}");
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split(' ').nth(1).unwrap_or_default();
+    let (status, body) = match path.split('?').next() {
+        Some("/metrics") => ("200 OK", metrics.render()),
+        _ => ("404 Not Found", String::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
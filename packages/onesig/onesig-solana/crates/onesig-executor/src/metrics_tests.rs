@@ -0,0 +1,76 @@
+// Tests for the Prometheus exposition of the metrics and the codes failures are counted by.
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anchor_lang::prelude::Pubkey;
+    use anyhow::anyhow;
+    use onesig::OneSigError;
+    use onesig_cli::context::SimulationFailed;
+    use onesig_sdk::{PreflightError, SdkError};
+    use serde_json::json;
+
+    use crate::metrics::{error_code, Metrics};
+
+    fn simulation_failed(error: PreflightError) -> anyhow::Error {
+        SimulationFailed { error, logs: vec![] }.into()
+    }
+
+    #[test]
+    fn test_error_code() {
+        let code = |error: PreflightError| error_code(&simulation_failed(error));
+        assert_eq!(
+            code(PreflightError::OneSig(OneSigError::ExpiredMerkleRoot)),
+            "ExpiredMerkleRoot"
+        );
+        assert_eq!(code(PreflightError::Custom { index: 1, program_id: None, code: 42 }), "42");
+        let error = json!({ "InsufficientFundsForRent": { "account_index": 2 } });
+        assert_eq!(
+            code(PreflightError::Instruction { index: 0, error }),
+            "InsufficientFundsForRent"
+        );
+        assert_eq!(
+            code(PreflightError::Transaction(json!("BlockhashNotFound"))),
+            "BlockhashNotFound"
+        );
+
+        let error: anyhow::Error = SdkError::TransactionNotFound("sig".into()).into();
+        assert_eq!(error_code(&error), "TransactionNotFound");
+        // Context added on the way up does not hide the cause
+        assert_eq!(error_code(&error.context("executing")), "TransactionNotFound");
+        assert_eq!(error_code(&anyhow!("connection refused")), "Other");
+    }
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        let state = Pubkey::new_unique();
+        metrics.set_pending_leaves(&state, 3);
+        metrics.executed(&state, Duration::from_millis(1_500));
+        metrics.executed(&state, Duration::from_secs(90));
+        metrics.failed(&state, "InvalidProof".into());
+        metrics.failed(&state, "InvalidProof".into());
+        metrics.spent(&state, Some(40_000), Some(5_000));
+        metrics.spent(&state, Some(2_000), None);
+
+        let rendered = metrics.render();
+        let line = |line: String| assert!(rendered.contains(&line), "{line} in\n{rendered}");
+        line(format!("onesig_executor_pending_leaves{{state=\"{state}\"}} 3\n"));
+        line(format!("onesig_executor_executed_leaves_total{{state=\"{state}\"}} 2\n"));
+        line(format!("onesig_executor_execution_seconds_bucket{{state=\"{state}\",le=\"1\"}} 0\n"));
+        line(format!("onesig_executor_execution_seconds_bucket{{state=\"{state}\",le=\"2\"}} 1\n"));
+        line(format!(
+            "onesig_executor_execution_seconds_bucket{{state=\"{state}\",le=\"60\"}} 1\n"
+        ));
+        line(format!(
+            "onesig_executor_execution_seconds_bucket{{state=\"{state}\",le=\"+Inf\"}} 2\n"
+        ));
+        line(format!("onesig_executor_execution_seconds_sum{{state=\"{state}\"}} 91.5\n"));
+        line(format!(
+            "onesig_executor_failures_total{{state=\"{state}\",code=\"InvalidProof\"}} 2\n"
+        ));
+        line(format!("onesig_executor_compute_units_total{{state=\"{state}\"}} 42000\n"));
+        line(format!("onesig_executor_fees_lamports_total{{state=\"{state}\"}} 5000\n"));
+        line("# TYPE onesig_executor_execution_seconds histogram\n".into());
+    }
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::Pubkey;
+use hmac::{Hmac, Mac};
+use onesig_cli::{
+    commands::plan::{self, Change},
+    transport::CurlTransport,
+};
+use onesig_sdk::{Activity, ConfigChange, Execution, Record, RpcTransport};
+use serde::Serialize;
+use serde_json::json;
+use sha2::Sha256;
+
+/// Where notifications are delivered
+#[derive(Default)]
+pub struct Notifier {
+    /// Receive the JSON of each [`Notification`]
+    pub webhooks: Vec<String>,
+    /// Key of the HMAC-SHA256 signing webhook payloads, if they are signed
+    pub secret: Option<Vec<u8>>,
+    /// Slack incoming webhooks, which receive a line of text
+    pub slack: Vec<String>,
+    pub telegram: Option<Telegram>,
+}
+
+pub struct Telegram {
+    pub token: String,
+    pub chat_id: String,
+}
+
+/// An event of a watched OneSig, as webhooks receive it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub one_sig_account: String,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all_fields = "camelCase")]
+pub enum Event {
+    /// `change` as the arguments of `onesig-cli set-config` making it
+    ConfigSet {
+        change: String,
+    },
+    TransactionExecuted {
+        merkle_root: String,
+        nonce: u64,
+    },
+}
+
+impl Notification {
+    /// A line for chat channels
+    pub fn text(&self) -> String {
+        let what = match &self.event {
+            Event::ConfigSet { change } => format!("configuration changed: {change}"),
+            Event::TransactionExecuted { merkle_root, nonce } =>
+                format!("executed nonce {nonce} of root {merkle_root}"),
+        };
+        format!("OneSig {}: {what} ({})", self.one_sig_account, self.signature)
+    }
+}
+
+/// The notifications of the `ConfigSet` and `TransactionExecuted` events among `records`
+pub fn notifications(state: &Pubkey, records: &[Record<Activity>]) -> Vec<Notification> {
+    records
+        .iter()
+        .filter_map(|record| {
+            let event = match &record.activity {
+                Activity::Config(ConfigChange::Set { params, .. }) =>
+                    Event::ConfigSet { change: plan::describe(&Change::SetConfig(params.clone())) },
+                Activity::Execution(Execution::Leaf { merkle_root, nonce, .. }) =>
+                    Event::TransactionExecuted {
+                        merkle_root: format!("0x{}", hex::encode(merkle_root.0)),
+                        nonce: *nonce,
+                    },
+                _ => return None,
+            };
+            Some(Notification {
+                one_sig_account: state.to_string(),
+                signature: record.signature.clone(),
+                slot: record.slot,
+                block_time: record.block_time,
+                event,
+            })
+        })
+        .collect()
+}
+
+/// Hex HMAC-SHA256 of `message` under `secret`
+pub fn signature(secret: &[u8], message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+impl Notifier {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty() && self.slack.is_empty() && self.telegram.is_none()
+    }
+
+    /// Delivers `notification` everywhere, once. Webhooks get `x-onesig-timestamp` and, with a
+    /// secret, `x-onesig-signature: sha256=<hex>` over `<timestamp>.<body>`, so receivers can
+    /// reject forged or replayed payloads. Failed deliveries are reported and dropped.
+    pub fn send(&self, notification: &Notification, now: u64) {
+        let body = serde_json::to_string(notification).expect("notifications serialize to JSON");
+        for url in &self.webhooks {
+            let mut transport =
+                CurlTransport::new(url.as_str()).header(format!("x-onesig-timestamp: {now}"));
+            if let Some(secret) = &self.secret {
+                let signature = signature(secret, &format!("{now}.{body}"));
+                transport = transport.header(format!("x-onesig-signature: sha256={signature}"));
+            }
+            deliver(&transport, &body, None);
+        }
+        let text = notification.text();
+        for url in &self.slack {
+            deliver(&CurlTransport::new(url.as_str()), &json!({ "text": text }).to_string(), None);
+        }
+        if let Some(Telegram { token, chat_id }) = &self.telegram {
+            let url = format!("https://api.telegram.org/bot{token}/sendMessage");
+            let body = json!({ "chat_id": chat_id, "text": text }).to_string();
+            deliver(&CurlTransport::new(url), &body, Some(token));
+        }
+    }
+}
+
+/// Posts `body`, keeping `secret`, e.g. a token in the URL, out of the reported errors.
+fn deliver(transport: &CurlTransport, body: &str, secret: Option<&str>) {
+    if let Err(error) = transport.post(body) {
+        let mut error = error.to_string();
+        if let Some(secret) = secret {
+            error = error.replace(secret, "***");
+        }
+        eprintln!("notification not delivered: {error}");
+    }
+}
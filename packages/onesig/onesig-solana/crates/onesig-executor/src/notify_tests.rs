@@ -0,0 +1,86 @@
+// Tests for the notifications of OneSig events and the signature of webhook payloads.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{Hash, PreviousConfig, SetConfigParams};
+    use onesig_sdk::{Activity, ConfigChange, Execution, Record};
+    use serde_json::json;
+
+    use crate::notify::{notifications, signature, Event};
+
+    fn record(slot: u64, activity: Activity) -> Record<Activity> {
+        Record { signature: format!("sig{slot}"), slot, block_time: Some(1_700_000_000), activity }
+    }
+
+    /// Execution of leaf `nonce` of root `0x0101..`
+    fn leaf(nonce: u64) -> Execution {
+        Execution::Leaf {
+            merkle_root: Hash([1; 32]),
+            nonce,
+            executor: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            ix_data_hash: Hash([2; 32]),
+            value: 0,
+            lamports_spent: 0,
+            root_executions: None,
+        }
+    }
+
+    #[test]
+    fn test_notifications() {
+        let state = Pubkey::new_unique();
+        let records = [
+            record(
+                1,
+                Activity::Config(ConfigChange::Set {
+                    params: SetConfigParams::SetThreshold(2),
+                    previous: PreviousConfig::Threshold(1),
+                }),
+            ),
+            record(
+                2,
+                Activity::Config(ConfigChange::ExecutorAdded {
+                    executor: Pubkey::new_unique(),
+                    effective_at: 1_700_000_000,
+                }),
+            ),
+            record(3, Activity::Execution(leaf(4))),
+            record(
+                4,
+                Activity::Execution(Execution::Module {
+                    module: Pubkey::new_unique(),
+                    vault_index: 0,
+                }),
+            ),
+        ];
+
+        let notifications = notifications(&state, &records);
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].event, Event::ConfigSet { change: "set-threshold 2".into() });
+        assert_eq!(
+            serde_json::to_value(&notifications[1]).unwrap(),
+            json!({
+                "oneSigAccount": state.to_string(),
+                "signature": "sig3",
+                "slot": 3,
+                "blockTime": 1_700_000_000,
+                "event": "TransactionExecuted",
+                "merkleRoot": format!("0x{}", "01".repeat(32)),
+                "nonce": 4,
+            })
+        );
+        assert_eq!(
+            notifications[0].text(),
+            format!("OneSig {state}: configuration changed: set-threshold 2 (sig1)")
+        );
+    }
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            signature(b"Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context as _, Result};
+use onesig::Hash;
+use serde::{Deserialize, Serialize};
+
+use crate::fees::{FeeRecord, FeeReport};
+
+/// Longest wait between two attempts of a leaf, in seconds
+const MAX_BACKOFF: u64 = 60 * 60;
+
+/// What the executor did with each leaf it attempted, persisted across restarts so failing leaves
+/// keep backing off and give up after the configured number of attempts.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// By [`leaf_key`]
+    pub leaves: BTreeMap<String, LeafProgress>,
+    /// Newest transaction of each OneSig whose events were notified, by address
+    #[serde(default)]
+    pub notified: BTreeMap<String, String>,
+    /// Costs of the leaves executed since the last fee report
+    #[serde(default)]
+    pub fees: Vec<FeeRecord>,
+    /// Unix time the last fee report ends at, or the fee reports started at
+    #[serde(default)]
+    pub fees_reported_at: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafProgress {
+    pub state: String,
+    pub nonce: u64,
+    pub attempts: u32,
+    /// Signature of the transaction that executed the leaf
+    pub executed: Option<String>,
+    pub last_error: Option<String>,
+    /// Unix time before which the leaf is not attempted again
+    pub retry_at: u64,
+}
+
+/// Key of leaf `index` of the bundle of `merkle_root`
+pub fn leaf_key(merkle_root: &Hash, index: usize) -> String {
+    format!("0x{}:{index}", hex::encode(merkle_root.0))
+}
+
+impl Progress {
+    /// Reads the progress file, starting afresh when there is none yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("invalid progress file {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("cannot read {}", path.display())),
+        }
+    }
+
+    /// Writes the progress file through a temporary one, so a crash never leaves it truncated.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("cannot write {}", temporary.display()))?;
+        fs::rename(&temporary, path).with_context(|| format!("cannot write {}", path.display()))
+    }
+
+    /// Whether the leaf of `key` is due for an attempt at `now`
+    pub fn ready(&self, key: &str, now: u64, max_attempts: u32) -> bool {
+        self.leaves.get(key).is_none_or(|leaf| {
+            leaf.executed.is_none() && leaf.attempts < max_attempts && leaf.retry_at <= now
+        })
+    }
+
+    pub fn executed(&mut self, key: &str, state: String, nonce: u64, signature: String) {
+        let leaf = self.entry(key, state, nonce);
+        leaf.attempts += 1;
+        leaf.executed = Some(signature);
+        leaf.last_error = None;
+    }
+
+    /// Records a failed attempt, doubling the wait before the next one from `interval` seconds.
+    pub fn failed(
+        &mut self,
+        key: &str,
+        state: String,
+        nonce: u64,
+        error: String,
+        now: u64,
+        interval: u64,
+    ) {
+        let leaf = self.entry(key, state, nonce);
+        leaf.attempts += 1;
+        leaf.last_error = Some(error);
+        let backoff = interval.saturating_mul(1 << (leaf.attempts - 1).min(16));
+        leaf.retry_at = now + backoff.min(MAX_BACKOFF);
+    }
+
+    /// The report of the fees recorded since the last one, once `interval` seconds passed since it
+    pub fn fee_report(&self, now: u64, interval: u64) -> Option<FeeReport> {
+        let from = self.fees_reported_at?;
+        let due = now >= from.saturating_add(interval);
+        due.then(|| FeeReport::new(from, now, self.fees.clone()))
+    }
+
+    /// Takes the records of a saved report out, starting the period of the next one.
+    pub fn fees_reported(&mut self, report: &FeeReport) {
+        self.fees.drain(..report.leaves.len());
+        self.fees_reported_at = Some(report.to);
+    }
+
+    fn entry(&mut self, key: &str, state: String, nonce: u64) -> &mut LeafProgress {
+        self.leaves.entry(key.to_string()).or_insert_with(|| LeafProgress {
+            state,
+            nonce,
+            ..LeafProgress::default()
+        })
+    }
+}
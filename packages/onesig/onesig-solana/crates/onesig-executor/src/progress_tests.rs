@@ -0,0 +1,59 @@
+// Tests for the retry schedule of leaves and the progress file.
+#[cfg(test)]
+mod tests {
+    use onesig::Hash;
+
+    use crate::progress::{leaf_key, Progress};
+
+    fn key() -> String {
+        leaf_key(&Hash([0xab; 32]), 3)
+    }
+
+    #[test]
+    fn test_leaf_key() {
+        assert_eq!(key(), format!("0x{}:3", "ab".repeat(32)));
+    }
+
+    #[test]
+    fn test_failed_leaves_back_off() {
+        let mut progress = Progress::default();
+        assert!(progress.ready(&key(), 0, 3));
+
+        progress.failed(&key(), "state".into(), 7, "blockhash expired".into(), 100, 10);
+        assert!(!progress.ready(&key(), 109, 3));
+        assert!(progress.ready(&key(), 110, 3));
+        progress.failed(&key(), "state".into(), 7, "blockhash expired".into(), 110, 10);
+        assert_eq!(progress.leaves[&key()].retry_at, 130);
+        progress.failed(&key(), "state".into(), 7, "blockhash expired".into(), 130, 10);
+        // Given up after the last attempt
+        assert!(!progress.ready(&key(), u64::MAX, 3));
+
+        // The wait stays under an hour
+        progress.failed(&key(), "state".into(), 7, "blockhash expired".into(), 0, 3_000);
+        assert_eq!(progress.leaves[&key()].retry_at, 3_600);
+    }
+
+    #[test]
+    fn test_executed_leaves_are_not_retried() {
+        let mut progress = Progress::default();
+        progress.failed(&key(), "state".into(), 7, "rpc unavailable".into(), 0, 10);
+        progress.executed(&key(), "state".into(), 7, "5ig".into());
+
+        let leaf = &progress.leaves[&key()];
+        assert_eq!((leaf.attempts, leaf.last_error.as_deref()), (2, None));
+        assert!(!progress.ready(&key(), u64::MAX, 5));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("onesig-progress-{}.json", std::process::id()));
+        assert_eq!(Progress::load(&path).unwrap(), Progress::default());
+
+        let mut progress = Progress::default();
+        progress.executed(&key(), "state".into(), 7, "5ig".into());
+        progress.save(&path).unwrap();
+        assert_eq!(Progress::load(&path).unwrap(), progress);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use onesig::{Hash, MerkleRootRegistry, MerkleRootState};
+use onesig_cli::{commands::close_root::closeable, context::Context};
+use onesig_sdk::TransactionSigner;
+
+use crate::metrics::Metrics;
+
+/// Roots closed per transaction. Each adds one account and its instruction to the message, which
+/// keeps eight well under the packet size with the compute budget instructions.
+pub const CLOSE_BATCH: usize = 8;
+
+/// Closes the roots of `state` that can no longer execute and were verified by the fee payer,
+/// [`CLOSE_BATCH`] per transaction, refunding their rent to it. Returns the number closed.
+pub fn reclaim(context: &Context, metrics: &Metrics, state: &Pubkey, now: i64) -> Result<usize> {
+    let client = context.client(*state);
+    let registry = client.pda.merkle_root_registry().0;
+    let Some(registry) = context.anchor_account::<MerkleRootRegistry>(&registry)? else {
+        return Ok(0);
+    };
+    let mut roots = vec![];
+    for entry in registry.entries {
+        if let Some(root) = context.anchor_account::<MerkleRootState>(&entry.address)? {
+            roots.push(root);
+        }
+    }
+    let payer = context.payer()?;
+    let seed = context.one_sig_account(state)?.header.seed;
+    let reclaimable = reclaimable(&roots, &payer.pubkey(), &seed, now);
+
+    for batch in reclaimable.chunks(CLOSE_BATCH) {
+        let instructions: Vec<_> = batch
+            .iter()
+            .map(|root| client.close_merkle_root(&payer.pubkey(), root))
+            .collect();
+        let confirmed = context.send(&payer, &instructions, &[])?;
+        println!("{state}: closed {} roots in {}", batch.len(), confirmed.signature);
+        metrics.roots_closed(state, batch.len() as u64);
+        let _ = metrics.record_transaction(context, state, &confirmed.signature);
+    }
+    Ok(reclaimable.len())
+}
+
+/// The roots of `roots` whose rent returns to `rent_payer` and that `close_merkle_root` accepts
+pub fn reclaimable(
+    roots: &[MerkleRootState],
+    rent_payer: &Pubkey,
+    seed: &Hash,
+    now: i64,
+) -> Vec<Hash> {
+    roots
+        .iter()
+        .filter(|root| root.rent_payer == *rent_payer && closeable(root, seed, now))
+        .map(|root| root.merkle_root)
+        .collect()
+}
@@ -0,0 +1,38 @@
+// Tests for picking the roots whose rent the keeper reclaims.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{Hash, MerkleRootState, MERKLE_ROOT_STATE_VERSION};
+
+    use crate::reclaim::reclaimable;
+
+    fn root(id: u8, seed: Hash, expiry: i64, rent_payer: Pubkey) -> MerkleRootState {
+        MerkleRootState {
+            merkle_root: Hash([id; 32]),
+            seed_commitment: MerkleRootState::seed_commitment(&seed),
+            expiry,
+            rent_payer,
+            signed_by: vec![],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions: None,
+        }
+    }
+
+    #[test]
+    fn test_reclaimable() {
+        let (keeper, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let seed = Hash([2; 32]);
+        let roots = [
+            root(1, seed, 100, keeper),
+            root(2, seed, 50, keeper),
+            root(3, seed, 50, other),
+            root(4, Hash([3; 32]), 200, keeper),
+        ];
+
+        let reclaimable = reclaimable(&roots, &keeper, &seed, 100);
+        // Live roots and the rent of other payers are left alone
+        assert!(reclaimable == [Hash([2; 32]), Hash([4; 32])]);
+    }
+}
@@ -0,0 +1,264 @@
+//! In-process harness running the OneSig program in LiteSVM: instances are created, roots
+//! verified and leaves executed in microseconds, without a validator or a bank, so property tests
+//! and scenario suites can run thousands of transactions.
+//!
+//! LiteSVM runs the SBF binary of the program, `target/deploy/onesig.so` as `anchor build` leaves
+//! it, or the one at `ONESIG_PROGRAM_SO`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anchor_lang::{
+    prelude::{Clock, Pubkey},
+    solana_program::instruction::Instruction,
+    AccountDeserialize,
+};
+use litesvm::{
+    types::{FailedTransactionMetadata, TransactionMetadata},
+    LiteSVM,
+};
+use onesig::{InitOneSigParams, MerkleRootState, OneSigError, VerifyMerkleRootParams};
+use onesig_core::Hash;
+use onesig_sdk::{
+    pack_signatures, ExecuteTransactionOptions, LocalSigner, OneSigAccount, OneSigClient,
+    OneSigTree, RootSigner, SolanaLeaf,
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+#[cfg(test)]
+mod svm_tests;
+
+/// Variable overriding the path of the program binary
+pub const PROGRAM_SO_VAR: &str = "ONESIG_PROGRAM_SO";
+
+/// Lamports the payer starts with
+pub const PAYER_LAMPORTS: u64 = 1_000_000_000_000;
+
+pub type SvmResult = Result<TransactionMetadata, FailedTransactionMetadata>;
+
+/// Path of the program binary: `ONESIG_PROGRAM_SO`, or else where `anchor build` writes it
+pub fn program_path() -> PathBuf {
+    std::env::var_os(PROGRAM_SO_VAR).map(PathBuf::from).unwrap_or_else(|| {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/onesig.so")
+    })
+}
+
+/// A OneSig account created in the SVM
+#[derive(Clone, Copy)]
+pub struct Instance {
+    pub client: OneSigClient,
+    pub one_sig_id: u64,
+    pub seed: Hash,
+    pub threshold: u8,
+}
+
+impl Instance {
+    /// Leaf at `nonce` executing `instruction` through vault 0, spending up to `value` lamports
+    pub fn leaf(&self, nonce: u64, instruction: &Instruction, value: u64) -> SolanaLeaf {
+        SolanaLeaf {
+            one_sig_state: self.client.state(),
+            one_sig_id: self.one_sig_id,
+            nonce,
+            instruction: self.client.resolve_instruction(instruction, 0, value).instruction,
+            token_outflow_limits: vec![],
+            guarded_accounts: vec![],
+            vault_index: 0,
+            cluster_genesis_hash: None,
+            group: None,
+            not_before: None,
+            bounty: None,
+        }
+    }
+
+    /// `tree` signed by `signers` under the seed of the instance, packed for its threshold
+    pub fn sign(
+        &self,
+        signers: &[LocalSigner],
+        tree: &OneSigTree,
+        expiry: u64,
+        max_executions: u32,
+    ) -> VerifyMerkleRootParams {
+        let message = tree.message(&self.seed, expiry, max_executions);
+        let signatures = signers.iter().map(|signer| signer.sign(&message).unwrap());
+        let addresses: Vec<_> = signers.iter().map(RootSigner::address).collect();
+        VerifyMerkleRootParams {
+            merkle_root: tree.root(),
+            expiry: expiry as i64,
+            max_executions,
+            signatures: pack_signatures(&message.digest(), signatures, &addresses, self.threshold)
+                .unwrap(),
+        }
+    }
+}
+
+/// The program loaded in a LiteSVM, with a funded payer that pays for and executes everything,
+/// and the signers of the test vectors.
+pub struct OneSigSvm {
+    pub svm: LiteSVM,
+    pub payer: Keypair,
+    pub signers: Vec<LocalSigner>,
+}
+
+impl OneSigSvm {
+    /// Loads the program binary at [`program_path`].
+    pub fn load() -> io::Result<Self> {
+        Self::new(&program_path())
+    }
+
+    /// Loads the program binary at `program`.
+    pub fn new(program: &Path) -> io::Result<Self> {
+        let binary = fs::read(program).map_err(|error| {
+            io::Error::new(error.kind(), format!("cannot read {}: {error}", program.display()))
+        })?;
+        let mut svm = LiteSVM::new();
+        svm.add_program(onesig::ID, &binary)
+            .map_err(|error| io::Error::other(format!("cannot load the program: {error:?}")))?;
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), PAYER_LAMPORTS)
+            .map_err(|error| io::Error::other(format!("cannot fund the payer: {:?}", error.err)))?;
+        let signers = onesig_test_vectors::load()
+            .signers
+            .iter()
+            .map(|signer| LocalSigner::from_bytes(&signer.private_key).unwrap())
+            .collect();
+        Ok(Self { svm, payer, signers })
+    }
+
+    /// The test vector signers at their threshold, without executors
+    pub fn init_params(&self, one_sig_id: u64, seed: Hash) -> InitOneSigParams {
+        InitOneSigParams {
+            one_sig_id,
+            seed,
+            threshold: onesig_test_vectors::THRESHOLD,
+            signers: self.signers.iter().map(RootSigner::address).collect(),
+            executors: vec![],
+            executor_required: false,
+            signers_capacity: self.signers.len() as u16,
+        }
+    }
+
+    /// Creates a OneSig account at a new address.
+    pub fn create_instance(
+        &mut self,
+        params: InitOneSigParams,
+    ) -> Result<Instance, FailedTransactionMetadata> {
+        let state = Keypair::new();
+        let instance = Instance {
+            client: OneSigClient::new(state.pubkey()),
+            one_sig_id: params.one_sig_id,
+            seed: params.seed,
+            threshold: params.threshold,
+        };
+        let instruction = instance.client.init_one_sig(&self.payer.pubkey(), params);
+        self.process(&[instruction], &[&state])?;
+        Ok(instance)
+    }
+
+    /// Sends `instructions` in a transaction of their own, signed by the payer and `signers`. The
+    /// blockhash expires after each, so sending the same instructions twice is not a duplicate.
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> SvmResult {
+        let signers: Vec<&Keypair> =
+            std::iter::once(&self.payer).chain(signers.iter().copied()).collect();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(transaction);
+        self.svm.expire_blockhash();
+        result
+    }
+
+    pub fn state(&self, instance: &Instance) -> OneSigAccount {
+        let address = instance.client.state();
+        let account = self.svm.get_account(&address).unwrap();
+        OneSigAccount::from_account_data(address, &account.data).unwrap()
+    }
+
+    /// The `MerkleRootState` of `merkle_root`, if verified and not closed
+    pub fn merkle_root_state(
+        &self,
+        instance: &Instance,
+        merkle_root: &Hash,
+    ) -> Option<MerkleRootState> {
+        let address = instance.client.pda.merkle_root_state(merkle_root).0;
+        let account = self.svm.get_account(&address)?;
+        Some(MerkleRootState::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or(0)
+    }
+
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.svm.airdrop(address, lamports).unwrap();
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// Moves the clock to `unix_timestamp`.
+    pub fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Moves the clock to just past `expiry`, e.g. to close the roots expiring then.
+    pub fn warp_past(&mut self, expiry: u64) {
+        self.set_time(expiry as i64 + 1);
+    }
+
+    pub fn verify_merkle_root(
+        &mut self,
+        instance: &Instance,
+        params: VerifyMerkleRootParams,
+    ) -> SvmResult {
+        let instruction = instance.client.verify_merkle_root(&self.payer.pubkey(), params);
+        self.process(&[instruction], &[])
+    }
+
+    /// Executes leaf `index` of `tree`, verifying the root inline with `merkle_root_verification`
+    /// or else through its `MerkleRootState`.
+    pub fn execute(
+        &mut self,
+        instance: &Instance,
+        tree: &OneSigTree,
+        index: usize,
+        leaf: &SolanaLeaf,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+    ) -> SvmResult {
+        let instruction = instance.client.execute_leaf(
+            &self.payer.pubkey(),
+            &tree.root(),
+            leaf,
+            tree.proof(index).unwrap(),
+            merkle_root_verification,
+            ExecuteTransactionOptions::default(),
+        );
+        self.process(&[instruction], &[])
+    }
+
+    pub fn close_merkle_root(&mut self, instance: &Instance, merkle_root: &Hash) -> SvmResult {
+        let instruction = instance.client.close_merkle_root(&self.payer.pubkey(), merkle_root);
+        self.process(&[instruction], &[])
+    }
+}
+
+/// Asserts that `result` is the failure of the program with `expected`.
+pub fn assert_error(result: SvmResult, expected: OneSigError) {
+    match result.expect_err("the transaction succeeded").err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) =>
+            assert_eq!(code, u32::from(expected), "expected {expected:?}"),
+        error => panic!("expected {expected:?}, got {error:?}"),
+    }
+}
@@ -0,0 +1,88 @@
+// Tests for the LiteSVM harness, which skip when the program binary has not been built.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use onesig::{OneSigError, SetConfigParams};
+    use onesig_core::Hash;
+    use onesig_sdk::OneSigTree;
+    use onesig_test_vectors::EXPIRY;
+    use solana_system_interface::instruction::transfer;
+
+    use crate::{assert_error, program_path, OneSigSvm};
+
+    fn svm() -> Option<OneSigSvm> {
+        match OneSigSvm::load() {
+            Ok(svm) => Some(svm),
+            Err(error) => {
+                eprintln!("skipped, run `anchor build` first: {error}");
+                None
+            },
+        }
+    }
+
+    #[test]
+    fn test_program_path() {
+        assert!(program_path().ends_with("target/deploy/onesig.so"));
+    }
+
+    #[test]
+    fn test_instances_are_independent() {
+        let Some(mut svm) = svm() else { return };
+        let first = svm.create_instance(svm.init_params(1, Hash([1u8; 32]))).unwrap();
+        let second = svm.create_instance(svm.init_params(2, Hash([2u8; 32]))).unwrap();
+
+        let leaf = first.leaf(0, &first.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let verification = first.sign(&svm.signers, &tree, EXPIRY, 0);
+        svm.execute(&first, &tree, 0, &leaf, Some(verification)).unwrap();
+
+        assert_eq!(svm.state(&first).header.threshold, 1);
+        assert_eq!(svm.state(&second).header.threshold, first.threshold);
+        assert_eq!((svm.state(&first).header.nonce, svm.state(&second).header.nonce), (1, 0));
+    }
+
+    #[test]
+    fn test_execute_every_leaf_of_a_tree() {
+        let Some(mut svm) = svm() else { return };
+        let instance = svm.create_instance(svm.init_params(1, Hash([1u8; 32]))).unwrap();
+        let vault = instance.client.pda.one_sig_signer().0;
+        let recipient = Pubkey::new_unique();
+        svm.airdrop(&vault, 1_000_000_000);
+        svm.airdrop(&recipient, 1_000_000_000);
+
+        let leaves: Vec<_> = (0..64)
+            .map(|nonce| instance.leaf(nonce, &transfer(&vault, &recipient, 1_000), 1_000))
+            .collect();
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        svm.verify_merkle_root(&instance, instance.sign(&svm.signers, &tree, EXPIRY, 0))
+            .unwrap();
+        for (index, leaf) in leaves.iter().enumerate() {
+            svm.execute(&instance, &tree, index, leaf, None).unwrap();
+        }
+
+        assert_eq!(svm.state(&instance).header.nonce, 64);
+        assert_eq!(svm.lamports(&recipient), 1_000_000_000 + 64 * 1_000);
+        // Leaves run in nonce order only
+        assert_error(svm.execute(&instance, &tree, 0, &leaves[0], None), OneSigError::InvalidProof);
+    }
+
+    #[test]
+    fn test_warp_past_expiry() {
+        let Some(mut svm) = svm() else { return };
+        let instance = svm.create_instance(svm.init_params(1, Hash([1u8; 32]))).unwrap();
+        let leaf =
+            instance.leaf(0, &instance.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let expiry = svm.now() as u64 + 3_600;
+        svm.verify_merkle_root(&instance, instance.sign(&svm.signers, &tree, expiry, 0))
+            .unwrap();
+
+        let result = svm.close_merkle_root(&instance, &tree.root());
+        assert_error(result, OneSigError::MerkleRootNotCloseable);
+
+        svm.warp_past(expiry);
+        assert_error(svm.execute(&instance, &tree, 0, &leaf, None), OneSigError::ExpiredMerkleRoot);
+        svm.close_merkle_root(&instance, &tree.root()).unwrap();
+        assert!(svm.merkle_root_state(&instance, &tree.root()).is_none());
+    }
+}
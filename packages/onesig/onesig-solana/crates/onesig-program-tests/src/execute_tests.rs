@@ -0,0 +1,506 @@
+// Tests for executing leaves, with the root verified inline or beforehand, alone or in groups, and
+// for the guards around the executed instruction.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::{Pubkey, Rent},
+        solana_program::instruction::{AccountMeta, Instruction},
+        AccountDeserialize, Discriminator,
+    };
+    use onesig::{
+        instruction::{
+            CloseMerkleRoot, ExecuteModuleTransaction, ExecuteTransaction, ExtendMerkleRoot,
+            InitOneSig, MigrateState, RecordLeafFailure, SignerExecuteTransaction,
+            VerifyMerkleRoot,
+        },
+        ExecutionRateLimit, ExecutorState, InitOneSigParams, LeafFailure, OneSigError,
+        SelfCpiTarget, SetConfigParams, TransactionCheck,
+    };
+    use onesig_sdk::{ExecuteTransactionOptions, OneSigTree, SolanaLeaf, TransactionGroup};
+    use onesig_test_vectors::EXPIRY;
+    use solana_sdk::{signature::Keypair, signer::Signer};
+    use solana_system_interface::instruction::transfer;
+
+    use crate::{assert_error, Harness};
+
+    const LAMPORTS: u64 = 1_000_000_000;
+
+    /// A funded vault and recipient, and a leaf at nonce 0 moving `amount` from the one to the
+    /// other, spending up to `value`
+    async fn transfer_leaf(harness: &mut Harness, amount: u64, value: u64) -> (SolanaLeaf, Pubkey) {
+        let vault = harness.client.pda.one_sig_signer().0;
+        let recipient = Pubkey::new_unique();
+        harness.fund(vault, LAMPORTS);
+        harness.fund(recipient, LAMPORTS);
+        (harness.leaf(0, &transfer(&vault, &recipient, amount), value), recipient)
+    }
+
+    #[tokio::test]
+    async fn test_execute_verified_inline() {
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        harness
+            .execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0)))
+            .await
+            .unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 1_000);
+        assert_eq!(harness.state().await.header.nonce, 1);
+        // Nothing is recorded for a root verified inline
+        assert!(harness.merkle_root_state(&tree.root()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_verified() {
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        // Executing needs the root verified first
+        let result = harness.execute(&tree, 0, &leaf, None).await;
+        assert!(result.is_err());
+
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 1_000);
+        assert_eq!(harness.state().await.header.nonce, 1);
+
+        // The nonce moved on, so the leaf no longer proves
+        assert_error(harness.execute(&tree, 0, &leaf, None).await, OneSigError::InvalidProof);
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_down_limited_root() {
+        let mut harness = Harness::start().await;
+        let (leaf, _) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        // A capped root is only tracked once verified
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 1))).await;
+        assert_error(result, OneSigError::ExecutionLimitNotTracked);
+
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 1)).await.unwrap();
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+        let root_state = harness.merkle_root_state(&tree.root()).await.unwrap();
+        assert_eq!(root_state.remaining_executions, Some(0));
+        assert_eq!(root_state.executions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_check_transaction() {
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        let check = harness.check(&tree, 0, &leaf, None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::MerkleRoot));
+        assert_eq!(check.error_code, u32::from(OneSigError::MissingMerkleRootState));
+
+        let check = harness.check(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        assert_eq!((check.failed, check.error_code, check.nonce), (None, 0, 0));
+        // Nothing was executed
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS);
+
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        assert_eq!(harness.check(&tree, 0, &leaf, None).await.failed, None);
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+
+        // The nonce moved on, so the leaf no longer proves
+        let check = harness.check(&tree, 0, &leaf, None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::Proof));
+        assert_eq!(check.error_code, u32::from(OneSigError::InvalidProof));
+        assert_eq!(check.nonce, 1);
+
+        harness.set_time(EXPIRY as i64 + 1).await;
+        let check = harness.check(&tree, 0, &leaf, None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::Expiry));
+        assert_eq!(check.error_code, u32::from(OneSigError::ExpiredMerkleRoot));
+    }
+
+    #[tokio::test]
+    async fn test_record_leaf_failure() {
+        let mut harness = Harness::start().await;
+        // The vault holds less than the leaf moves, so its call fails
+        let (leaf, recipient) = transfer_leaf(&mut harness, 2 * LAMPORTS, 2 * LAMPORTS).await;
+        let vault = harness.client.pda.one_sig_signer().0;
+        let next = harness.leaf(1, &transfer(&vault, &recipient, 0), 0);
+        let tree = OneSigTree::new([leaf.clone().into(), next.clone().into()]).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        assert!(harness.execute(&tree, 0, &leaf, None).await.is_err());
+
+        let payer = harness.payer();
+        let record = |harness: &Harness, index: usize, leaf: &SolanaLeaf| {
+            let proof = tree.proof(index).unwrap();
+            let options = ExecuteTransactionOptions::default();
+            harness
+                .client
+                .record_leaf_failure(&payer, &tree.root(), leaf, proof, None, options, 1)
+        };
+        // Only a leaf passing every check can be recorded, not one of a later nonce
+        let result = harness.process(&[record(&harness, 1, &next)], &[]).await;
+        assert_error(result, OneSigError::InvalidProof);
+
+        harness.process(&[record(&harness, 0, &leaf)], &[]).await.unwrap();
+        let marker = harness.client.pda.leaf_failure(&leaf.hash()).0;
+        let failure = LeafFailure::try_deserialize(&mut &harness.data(marker).await[..]).unwrap();
+        assert!(failure.one_sig_state == harness.client.state() && failure.leaf == leaf.hash());
+        assert_eq!((failure.nonce, failure.reported_error_code, failure.rent_payer), (0, 1, payer));
+        // Nothing was executed
+        assert_eq!(harness.state().await.header.nonce, 0);
+
+        // The marker stays while the leaf can execute, which it still does
+        let close = harness.client.close_leaf_failure(&payer, &leaf.hash());
+        let result = harness.process(&[close.clone()], &[]).await;
+        assert_error(result, OneSigError::LeafFailureNotCloseable);
+        harness.fund(vault, 3 * LAMPORTS);
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+        harness.process(&[close], &[]).await.unwrap();
+        assert_eq!(harness.lamports(marker).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_close_leaf_failure_after_close_one_sig() {
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 2 * LAMPORTS, 2 * LAMPORTS).await;
+        harness.allow_self_cpi(&[SelfCpiTarget::CloseOneSig]).await;
+        let close_one_sig = harness.leaf(0, &harness.client.close_one_sig(&recipient, &[]), 0);
+        let tree = OneSigTree::new([leaf.clone().into(), close_one_sig.clone().into()]).unwrap();
+        // Verified inline, so no merkle root account keeps the instance from closing
+        let verification = harness.sign(&tree, EXPIRY, 0);
+
+        let payer = harness.payer();
+        let proof = tree.proof(0).unwrap();
+        let options = ExecuteTransactionOptions::default();
+        let record = harness.client.record_leaf_failure(
+            &payer,
+            &tree.root(),
+            &leaf,
+            proof,
+            Some(verification.clone()),
+            options,
+            1,
+        );
+        harness.process(&[record], &[]).await.unwrap();
+        let marker = harness.client.pda.leaf_failure(&leaf.hash()).0;
+
+        harness.fund(harness.client.pda.one_sig_signer().0, 0);
+        harness.execute(&tree, 1, &close_one_sig, Some(verification)).await.unwrap();
+        let state = harness.client.state();
+        assert!(harness.context.banks_client.get_account(state).await.unwrap().is_none());
+
+        // No leaf of a closed instance executes again, so its marker is outdated, and its rent,
+        // above the fee, is refunded
+        let balance = harness.lamports(payer).await;
+        let close = harness.client.close_leaf_failure(&payer, &leaf.hash());
+        harness.process(&[close], &[]).await.unwrap();
+        assert_eq!(harness.lamports(marker).await, 0);
+        assert!(harness.lamports(payer).await > balance);
+    }
+
+    #[tokio::test]
+    async fn test_execute_group() {
+        let mut harness = Harness::start().await;
+        let (first, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let vault = harness.client.pda.one_sig_signer().0;
+        let second = harness.leaf(1, &transfer(&vault, &recipient, 2_000), 2_000);
+        let leaves = [first, second].map(|leaf| SolanaLeaf {
+            group: Some(TransactionGroup { index: leaf.nonce as u8, len: 2 }),
+            ..leaf
+        });
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // A grouped leaf does not execute without the rest of its group
+        let result = harness.execute(&tree, 0, &leaves[0], None).await;
+        assert_error(result, OneSigError::IncompleteTransactionGroup);
+        assert_eq!(harness.state().await.header.nonce, 0);
+
+        let executor = harness.payer();
+        let instructions: Vec<_> = leaves
+            .iter()
+            .enumerate()
+            .map(|(index, leaf)| {
+                harness.client.execute_leaf(
+                    &executor,
+                    &tree.root(),
+                    leaf,
+                    tree.proof(index).unwrap(),
+                    None,
+                    ExecuteTransactionOptions::default(),
+                )
+            })
+            .collect();
+        harness.process(&instructions, &[]).await.unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 3_000);
+        assert_eq!(harness.state().await.header.nonce, 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_not_before() {
+        const NOT_BEFORE: i64 = 2_000_000_000;
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let leaf = SolanaLeaf { not_before: Some(NOT_BEFORE), ..leaf };
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        harness.set_time(NOT_BEFORE - 1).await;
+        let check = harness.check(&tree, 0, &leaf, None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::NotBefore));
+        assert_eq!(check.error_code, u32::from(OneSigError::ExecutionTooEarly));
+        let result = harness.execute(&tree, 0, &leaf, None).await;
+        assert_error(result, OneSigError::ExecutionTooEarly);
+        assert_eq!(harness.state().await.header.nonce, 0);
+
+        harness.set_time(NOT_BEFORE).await;
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_sponsored_execution() {
+        // The fee of a transaction signed by the sponsor and the executor
+        const REIMBURSEMENT: u64 = 10_000;
+        let mut harness = Harness::new().await;
+        let executor = Keypair::new();
+        let params = InitOneSigParams {
+            executors: vec![executor.pubkey()],
+            executor_required: true,
+            ..harness.init_params()
+        };
+        harness.init_one_sig(params).await.unwrap();
+        let reimbursement = SetConfigParams::SetExecutorReimbursement(REIMBURSEMENT);
+        let reimburse = harness.client.set_config(reimbursement);
+        let vault = harness.client.pda.one_sig_signer().0;
+        harness.fund(vault, LAMPORTS);
+        let leaves = [harness.leaf(0, &reimburse, 0), harness.leaf(1, &reimburse, 0)];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // The payer sponsors `executor`, which only signs
+        let sponsor = harness.payer();
+        let [first, second] = [0, 1].map(|index| {
+            harness.client.execute_leaf(
+                &executor.pubkey(),
+                &tree.root(),
+                &leaves[index],
+                tree.proof(index).unwrap(),
+                None,
+                ExecuteTransactionOptions {
+                    as_executor: true,
+                    fee_payer: Some(sponsor),
+                    ..Default::default()
+                },
+            )
+        });
+        harness.process(&[first], &[&executor]).await.unwrap();
+        let (sponsor_balance, vault_balance) =
+            (harness.lamports(sponsor).await, harness.lamports(vault).await);
+        harness.process(&[second], &[&executor]).await.unwrap();
+        assert_eq!(harness.lamports(executor.pubkey()).await, 0);
+        assert_eq!(harness.lamports(vault).await, vault_balance - REIMBURSEMENT);
+        // Paid back the fee it paid
+        assert_eq!(harness.lamports(sponsor).await, sponsor_balance);
+
+        // Sponsoring does not make an executor of an unregistered signer
+        let stranger = Keypair::new();
+        let leaf = harness.leaf(2, &reimburse, 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        let instruction = harness.client.execute_leaf(
+            &stranger.pubkey(),
+            &tree.root(),
+            &leaf,
+            tree.proof(0).unwrap(),
+            None,
+            ExecuteTransactionOptions { fee_payer: Some(sponsor), ..Default::default() },
+        );
+        let result = harness.process(&[instruction], &[&stranger]).await;
+        assert_error(result, OneSigError::ExecutorRequired);
+    }
+
+    #[tokio::test]
+    async fn test_bounty() {
+        const BOUNTY: u64 = 50_000;
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let escrow = harness.client.pda.bounty_escrow().0;
+        harness.fund(escrow, LAMPORTS);
+        let vault = harness.client.pda.one_sig_signer().0;
+        let next = harness.leaf(1, &transfer(&vault, &recipient, 1_000), 1_000);
+        let leaves = [
+            SolanaLeaf { bounty: Some(BOUNTY), ..leaf },
+            SolanaLeaf { bounty: Some(LAMPORTS), ..next.clone() },
+            SolanaLeaf { bounty: Some(LAMPORTS - BOUNTY - 1), ..next.clone() },
+            SolanaLeaf { bounty: Some(LAMPORTS - BOUNTY), ..next },
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        let executor = harness.payer();
+        let balance = harness.lamports(executor).await;
+        harness.execute(&tree, 0, &leaves[0], None).await.unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 1_000);
+        assert_eq!(harness.lamports(escrow).await, LAMPORTS - BOUNTY);
+        // Paid the bounty on top of the fee of its single signature
+        assert_eq!(harness.lamports(executor).await, balance - 5_000 + BOUNTY);
+
+        // A bounty the escrow cannot pay, or that would leave it short of rent, holds the leaf
+        // back...
+        for index in [1, 2] {
+            let result = harness.execute(&tree, index, &leaves[index], None).await;
+            assert_error(result, OneSigError::InsufficientBountyEscrow);
+        }
+        assert_eq!(harness.state().await.header.nonce, 1);
+        // ...but one emptying it is paid
+        harness.execute(&tree, 3, &leaves[3], None).await.unwrap();
+        assert_eq!(harness.lamports(escrow).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_bounty_escrow() {
+        let mut harness = Harness::start().await;
+        let escrow = harness.client.pda.bounty_escrow().0;
+        let recipient = Pubkey::new_unique();
+        harness.fund(escrow, LAMPORTS);
+        harness.fund(recipient, LAMPORTS);
+        harness.allow_self_cpi(&[SelfCpiTarget::BountyEscrow]).await;
+        let withdraw = |lamports| harness.client.withdraw_bounty_escrow(&recipient, lamports);
+        let leaves = [
+            harness.leaf(0, &withdraw(LAMPORTS - 1), 0),
+            harness.leaf(0, &withdraw(1_000), 0),
+            harness.leaf(1, &withdraw(LAMPORTS - 1_000), 0),
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // The escrow is left rent-exempt, or emptied
+        let result = harness.execute(&tree, 0, &leaves[0], None).await;
+        assert_error(result, OneSigError::InsufficientBountyEscrow);
+        for index in [1, 2] {
+            harness.execute(&tree, index, &leaves[index], None).await.unwrap();
+        }
+        assert_eq!(harness.lamports(escrow).await, 0);
+        assert_eq!(harness.lamports(recipient).await, 2 * LAMPORTS);
+    }
+
+    #[tokio::test]
+    async fn test_balance_guard() {
+        let mut harness = Harness::start().await;
+        let (leaf, recipient) = transfer_leaf(&mut harness, 1_000, 999).await;
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        assert_error(result, OneSigError::ExcessiveBalanceDeduction);
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS);
+        assert_eq!(harness.state().await.header.nonce, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reentrancy_guard() {
+        let mut harness = Harness::start().await;
+        // Whatever they would execute, and with every self-CPI target allowed, leaves calling
+        // back into the execute paths, the merkle root and initialization instructions, or
+        // `record_leaf_failure` are refused
+        harness.allow_self_cpi(&SelfCpiTarget::ALL).await;
+        let state = AccountMeta::new(harness.client.state(), false);
+        let discriminators = [
+            ExecuteTransaction::DISCRIMINATOR,
+            SignerExecuteTransaction::DISCRIMINATOR,
+            ExecuteModuleTransaction::DISCRIMINATOR,
+            VerifyMerkleRoot::DISCRIMINATOR,
+            ExtendMerkleRoot::DISCRIMINATOR,
+            CloseMerkleRoot::DISCRIMINATOR,
+            InitOneSig::DISCRIMINATOR,
+            MigrateState::DISCRIMINATOR,
+            RecordLeafFailure::DISCRIMINATOR,
+        ];
+        let leaves = discriminators.map(|discriminator| {
+            let reentrant = Instruction {
+                program_id: onesig::ID,
+                accounts: vec![state.clone()],
+                data: discriminator.to_vec(),
+            };
+            harness.leaf(0, &reentrant, 0)
+        });
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        let verification = harness.sign(&tree, EXPIRY, 0);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let result = harness.execute(&tree, index, leaf, Some(verification.clone())).await;
+            assert_error(result, OneSigError::Reentrancy);
+        }
+        assert_eq!(harness.state().await.header.nonce, 0);
+    }
+
+    #[tokio::test]
+    async fn test_self_cpi_policy() {
+        let mut harness = Harness::start().await;
+        assert_eq!(harness.state().await.header.self_cpi_targets(), SelfCpiTarget::DEFAULT);
+        let executors = SetConfigParams::SetSelfCpiTargets(vec![SelfCpiTarget::Executors]);
+        let allow = harness.client.set_config(executors);
+        let add_executor = harness.client.add_executor(&Pubkey::new_unique());
+        let rent = Rent::default().minimum_balance(ExecutorState::space(0));
+        harness.fund(harness.client.pda.one_sig_signer().0, LAMPORTS);
+        let leaves = [
+            harness.leaf(0, &add_executor, rent),
+            harness.leaf(0, &allow, 0),
+            harness.leaf(1, &add_executor, rent),
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // Only `set_config` can be called back into by default
+        let check = harness.check(&tree, 0, &leaves[0], None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::TargetProgram));
+        assert_eq!(check.error_code, u32::from(OneSigError::Reentrancy));
+        let result = harness.execute(&tree, 0, &leaves[0], None).await;
+        assert_error(result, OneSigError::Reentrancy);
+        assert_eq!(harness.state().await.header.nonce, 0);
+
+        // ...until the multisig allows more
+        harness.execute(&tree, 1, &leaves[1], None).await.unwrap();
+        assert_eq!(harness.state().await.header.self_cpi_targets(), [SelfCpiTarget::Executors]);
+        harness.execute(&tree, 2, &leaves[2], None).await.unwrap();
+        assert_eq!(harness.state().await.header.executors_len, 1);
+    }
+
+    #[tokio::test]
+    async fn test_execution_rate_limit() {
+        const NOW: i64 = 2_000_000_000;
+        const WINDOW: i64 = 3_600;
+        let mut harness = Harness::start().await;
+        let (_, recipient) = transfer_leaf(&mut harness, 1_000, 1_000).await;
+        let limit = ExecutionRateLimit { max_executions: 2, window: WINDOW };
+        let set_limit = harness.client.set_config(SetConfigParams::SetExecutionRateLimit(limit));
+        let pay = transfer(&harness.client.pda.one_sig_signer().0, &recipient, 1_000);
+        let leaves = [
+            harness.leaf(0, &set_limit, 0),
+            harness.leaf(1, &pay, 1_000),
+            harness.leaf(2, &pay, 1_000),
+            harness.leaf(3, &pay, 1_000),
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        harness.set_time(NOW).await;
+        for (index, leaf) in leaves[..3].iter().enumerate() {
+            harness.execute(&tree, index, leaf, None).await.unwrap();
+        }
+        assert_eq!(harness.state().await.header.execution_rate_limit(), limit);
+
+        // However many leaves the root holds, the window takes no more
+        harness.set_time(NOW + WINDOW - 1).await;
+        let check = harness.check(&tree, 3, &leaves[3], None).await;
+        assert_eq!(check.failed, Some(TransactionCheck::ExecutionRate));
+        assert_eq!(check.error_code, u32::from(OneSigError::ExecutionRateLimited));
+        let result = harness.execute(&tree, 3, &leaves[3], None).await;
+        assert_error(result, OneSigError::ExecutionRateLimited);
+        assert_eq!(harness.state().await.header.nonce, 3);
+
+        harness.set_time(NOW + WINDOW).await;
+        harness.execute(&tree, 3, &leaves[3], None).await.unwrap();
+        assert_eq!(harness.lamports(recipient).await, LAMPORTS + 3_000);
+    }
+}
@@ -0,0 +1,405 @@
+//! The IDL of the program against the snapshot committed at `idl/onesig.json`, the IDL of the
+//! last release: clients are generated from the IDL, so what they encode must keep decoding, under
+//! the names of [`RENAMES`] where the program renamed something since, unless [`MIGRATIONS`]
+//! lists the change as one clients were migrated for.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anchor_lang_idl::{
+    build::IdlBuilder,
+    types::{Idl, IdlInstructionAccount, IdlInstructionAccountItem, IdlTypeDefTy},
+};
+use anyhow::{Context as _, Result};
+use serde_json::Value;
+
+/// Variable accepting the IDL the program builds as the new snapshot
+pub const UPDATE_SNAPSHOT_VAR: &str = "UPDATE_IDL_SNAPSHOT";
+
+/// Something the program renamed since the snapshot, `from` being its name there and `to` its name
+/// now. Renaming an instruction, account or event changes its discriminator unless the old one is
+/// kept, e.g. with `#[instruction(discriminator = ...)]`, which the check holds it to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rename {
+    Instruction {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// An account of instruction `instruction`, as named now
+    InstructionAccount {
+        instruction: &'static str,
+        from: &'static str,
+        to: &'static str,
+    },
+    /// An account or defined type, wherever it is referenced
+    Type {
+        from: &'static str,
+        to: &'static str,
+    },
+    Event {
+        from: &'static str,
+        to: &'static str,
+    },
+}
+
+/// The renames clients were migrated for since the snapshot was taken; emptied whenever the
+/// snapshot is updated.
+pub const RENAMES: &[Rename] = &[];
+
+/// Breaking changes clients were migrated for, as [`breaking_changes`] reports them, and why
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    pub changes: &'static [&'static str],
+    pub reason: &'static str,
+}
+
+/// The breaking changes clients were migrated for since the snapshot was taken; emptied, like
+/// [`RENAMES`], whenever the snapshot is updated.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        changes: &[
+            "account 2 of instruction `init_one_sig` is `merkle_root_registry`, not \
+             `system_program`",
+            "instruction `init_one_sig` requires the new account `spending_limits`",
+            "instruction `init_one_sig` requires the new account `target_programs`",
+            "instruction `init_one_sig` requires the new account `system_program`",
+            "instruction `init_one_sig` requires the new account `event_authority`",
+            "instruction `init_one_sig` requires the new account `program`",
+        ],
+        reason: "the registry, spending limits and target programs PDAs of a OneSig account are \
+                 created with it, and its event is emitted by self-CPI",
+    },
+    Migration {
+        changes: &[
+            "instruction `set_config` requires the new account `event_authority`",
+            "instruction `set_config` requires the new account `program`",
+        ],
+        reason: "configuration events are emitted by self-CPI",
+    },
+    Migration {
+        changes: &[
+            "account `one_sig_state` of instruction `verify_merkle_root` changed whether it is \
+             writable, a signer or optional",
+            "account 3 of instruction `verify_merkle_root` is `merkle_root_registry`, not \
+             `system_program`",
+            "instruction `verify_merkle_root` requires the new account `system_program`",
+            "account `one_sig_state` of instruction `close_merkle_root` changed whether it is \
+             writable, a signer or optional",
+            "instruction `close_merkle_root` requires the new account `merkle_root_registry`",
+            "instruction `close_merkle_root` requires the new account `system_program`",
+        ],
+        reason: "verified roots are recorded in the merkle root registry and counted in the state \
+                 until they are closed",
+    },
+    Migration {
+        changes: &[
+            "account `executor` of instruction `execute_transaction` changed whether it is \
+             writable, a signer or optional",
+            "account `one_sig_signer` of instruction `execute_transaction` changed whether it is \
+             writable, a signer or optional",
+            "account 3 of instruction `execute_transaction` is `spending_limits`, not \
+             `merkle_root_state`",
+            "instruction `execute_transaction` requires the new account `system_program`",
+            "instruction `execute_transaction` requires the new account `event_authority`",
+            "instruction `execute_transaction` requires the new account `program`",
+            "account 3 of instruction `signer_execute_transaction` is `spending_limits`, not \
+             `merkle_root_state`",
+            "instruction `signer_execute_transaction` requires the new account `event_authority`",
+            "instruction `signer_execute_transaction` requires the new account `program`",
+        ],
+        reason: "executions are charged to the spending limits, `one_sig_signer` pays the \
+                 executor reimbursement to `executor`, and execution events are emitted by \
+                 self-CPI",
+    },
+    Migration {
+        changes: &[
+            "account `OneSigState` changed its discriminator",
+            "type `OneSigState` changed its layout",
+            "type `Multisig` was removed",
+            "type `Executors` was removed",
+            "type `Address` changed its layout",
+            "type `Hash` changed its layout",
+        ],
+        reason: "the state is zero-copy, its signers stored inline with room to grow and its \
+                 executors in PDAs of their own; existing accounts are converted by \
+                 `migrate_state`. `Address` and `Hash` are `repr(transparent)` for it, their Borsh \
+                 encoding unchanged",
+    },
+    Migration {
+        changes: &["type `MerkleRootState` changed its layout"],
+        reason: "roots commit to the seed instead of copying it, count their executions and are \
+                 sized to their signatures",
+    },
+    Migration {
+        changes: &[
+            "type `InitOneSigParams` changed its layout",
+            "type `VerifyMerkleRootParams` changed its layout",
+            "type `OneSigTransaction` changed its layout",
+        ],
+        reason: "the parameters gained the signer capacity of the state, the execution limit of a \
+                 root, and the policies of a leaf: its outflow limits, guarded accounts, vault, \
+                 group, activation time and bounty",
+    },
+    Migration {
+        changes: &[
+            "type `OneSigInitialized` changed its layout",
+            "type `ConfigSet` changed its layout",
+            "type `TransactionExecuted` changed its layout",
+        ],
+        reason: "events are versioned by `event_version` and carry what indexers otherwise had to \
+                 read from the accounts; the events crate decodes every version",
+    },
+];
+
+pub fn snapshot_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("idl/onesig.json")
+}
+
+/// Builds the IDL of the program as `anchor idl build` does, in a target directory of its own so
+/// it does not wait on the build running the tests.
+pub fn generate() -> Result<Idl> {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target = manifest.join("../../target/idl");
+    IdlBuilder::new()
+        .program_path(manifest.join("../../programs/onesig"))
+        .skip_lint(true)
+        .cargo_args(vec!["--target-dir".into(), target.display().to_string()])
+        .build()
+        .context("cannot build the IDL of the program")
+}
+
+/// The committed snapshot, if any
+pub fn load_snapshot() -> Result<Option<Idl>> {
+    let path = snapshot_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json =
+        fs::read_to_string(&path).with_context(|| format!("cannot read {}", path.display()))?;
+    let idl =
+        serde_json::from_str(&json).with_context(|| format!("cannot parse {}", path.display()))?;
+    Ok(Some(idl))
+}
+
+/// Writes `idl` as the snapshot, formatted as `anchor idl build` writes it.
+pub fn save_snapshot(idl: &Idl) -> Result<()> {
+    let path = snapshot_path();
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, serde_json::to_string_pretty(idl)? + "\n")
+        .with_context(|| format!("cannot write {}", path.display()))
+}
+
+/// Whether `a` and `b` are the same IDL, docs and all
+pub fn same(a: &Idl, b: &Idl) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// What clients generated from `snapshot` would get wrong against `current`, once `renames` are
+/// applied: removed or re-encoded instructions, accounts and events, moved accounts, changed
+/// arguments and types, and renumbered errors. Additions are compatible, as long as new accounts
+/// of an instruction are optional and come last, and new enum variants come last.
+pub fn breaking_changes(snapshot: &Idl, current: &Idl, renames: &[Rename]) -> Vec<String> {
+    let mut changes = vec![];
+    if snapshot.address != current.address {
+        changes.push(format!("program address changed to {}", current.address));
+    }
+
+    for old in &snapshot.instructions {
+        let name = instruction_name(renames, &old.name);
+        let Some(new) = current.instructions.iter().find(|new| new.name == name) else {
+            changes.push(format!("instruction `{}` was removed", old.name));
+            continue;
+        };
+        if new.discriminator != old.discriminator {
+            changes.push(format!("instruction `{name}` changed its discriminator"));
+        }
+        let old_args: Vec<_> = old
+            .args
+            .iter()
+            .map(|arg| (&arg.name, renamed_value(renames, &arg.ty)))
+            .collect();
+        let new_args: Vec<_> = new.args.iter().map(|arg| (&arg.name, to_value(&arg.ty))).collect();
+        if old_args != new_args {
+            changes.push(format!("instruction `{name}` changed its arguments"));
+        }
+
+        let (old_accounts, new_accounts) = (flatten(&old.accounts), flatten(&new.accounts));
+        for (index, old_account) in old_accounts.iter().enumerate() {
+            let account = account_name(renames, name, &old_account.name);
+            match new_accounts.get(index) {
+                None => changes.push(format!("instruction `{name}` lost account `{account}`")),
+                Some(new_account) if new_account.name != account => changes.push(format!(
+                    "account {index} of instruction `{name}` is `{}`, not `{account}`",
+                    new_account.name
+                )),
+                Some(new_account)
+                    if (new_account.writable, new_account.signer, new_account.optional) !=
+                        (old_account.writable, old_account.signer, old_account.optional) =>
+                    changes.push(format!(
+                        "account `{account}` of instruction `{name}` changed whether it is \
+                         writable, a signer or optional"
+                    )),
+                Some(_) => {},
+            }
+        }
+        for new_account in new_accounts.iter().skip(old_accounts.len()) {
+            if !new_account.optional {
+                changes.push(format!(
+                    "instruction `{name}` requires the new account `{}`",
+                    new_account.name
+                ));
+            }
+        }
+    }
+
+    for old in &snapshot.accounts {
+        let name = type_name(renames, &old.name);
+        match current.accounts.iter().find(|new| new.name == name) {
+            None => changes.push(format!("account `{}` was removed", old.name)),
+            Some(new) if new.discriminator != old.discriminator =>
+                changes.push(format!("account `{name}` changed its discriminator")),
+            Some(_) => {},
+        }
+    }
+
+    for old in &snapshot.events {
+        let name = event_name(renames, &old.name);
+        match current.events.iter().find(|new| new.name == name) {
+            None => changes.push(format!("event `{}` was removed", old.name)),
+            Some(new) if new.discriminator != old.discriminator =>
+                changes.push(format!("event `{name}` changed its discriminator")),
+            Some(_) => {},
+        }
+    }
+
+    for old in &snapshot.types {
+        let name = type_name(renames, &old.name);
+        let Some(new) = current.types.iter().find(|new| new.name == name) else {
+            changes.push(format!("type `{}` was removed", old.name));
+            continue;
+        };
+        let compatible = old.serialization == new.serialization &&
+            old.repr == new.repr &&
+            match (&old.ty, &new.ty) {
+                (IdlTypeDefTy::Enum { variants: old }, IdlTypeDefTy::Enum { variants: new }) => {
+                    let old: Vec<_> =
+                        old.iter().map(|variant| renamed_value(renames, variant)).collect();
+                    let new: Vec<_> = new.iter().map(to_value).collect();
+                    new.starts_with(&old)
+                },
+                (old, new) => renamed_value(renames, old) == to_value(new),
+            };
+        if !compatible {
+            changes.push(format!("type `{name}` changed its layout"));
+        }
+    }
+
+    for old in &snapshot.errors {
+        if let Some(new) = current.errors.iter().find(|new| new.name == old.name) {
+            if new.code != old.code {
+                changes.push(format!("error `{}` changed its code to {}", old.name, new.code));
+            }
+        }
+    }
+    changes
+}
+
+/// The `changes` not listed by `migrations`, and the changes `migrations` lists that are not made
+pub fn unmigrated(
+    changes: &[String],
+    migrations: &[Migration],
+) -> (Vec<String>, Vec<&'static str>) {
+    let migrated: Vec<_> = migrations.iter().flat_map(|migration| migration.changes).collect();
+    let unmigrated = changes
+        .iter()
+        .filter(|change| !migrated.contains(&&change.as_str()))
+        .cloned()
+        .collect();
+    let stale = migrated
+        .into_iter()
+        .filter(|migrated| !changes.iter().any(|change| change == *migrated))
+        .copied()
+        .collect();
+    (unmigrated, stale)
+}
+
+/// The accounts of an instruction in the order they are passed, composite accounts expanded
+fn flatten(items: &[IdlInstructionAccountItem]) -> Vec<&IdlInstructionAccount> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            IdlInstructionAccountItem::Single(account) => vec![account],
+            IdlInstructionAccountItem::Composite(accounts) => flatten(&accounts.accounts),
+        })
+        .collect()
+}
+
+fn instruction_name<'a>(renames: &[Rename], name: &'a str) -> &'a str {
+    renames
+        .iter()
+        .find_map(|rename| match *rename {
+            Rename::Instruction { from, to } if from == name => Some(to),
+            _ => None,
+        })
+        .unwrap_or(name)
+}
+
+fn account_name<'a>(renames: &[Rename], instruction: &str, name: &'a str) -> &'a str {
+    renames
+        .iter()
+        .find_map(|rename| match *rename {
+            Rename::InstructionAccount { instruction: renamed, from, to }
+                if renamed == instruction && from == name =>
+                Some(to),
+            _ => None,
+        })
+        .unwrap_or(name)
+}
+
+fn type_name<'a>(renames: &[Rename], name: &'a str) -> &'a str {
+    renames
+        .iter()
+        .find_map(|rename| match *rename {
+            Rename::Type { from, to } if from == name => Some(to),
+            _ => None,
+        })
+        .unwrap_or(name)
+}
+
+fn event_name<'a>(renames: &[Rename], name: &'a str) -> &'a str {
+    renames
+        .iter()
+        .find_map(|rename| match *rename {
+            Rename::Event { from, to } if from == name => Some(to),
+            _ => None,
+        })
+        .unwrap_or(name)
+}
+
+fn to_value(value: &impl serde::Serialize) -> Value {
+    serde_json::to_value(value).expect("IDL types serialize to JSON")
+}
+
+/// `value` as JSON, its references to defined types renamed
+fn renamed_value(renames: &[Rename], value: &impl serde::Serialize) -> Value {
+    fn rename(renames: &[Rename], value: Value) -> Value {
+        match value {
+            Value::Object(mut object) => {
+                if let Some(Value::Object(defined)) = object.get_mut("defined") {
+                    if let Some(Value::String(name)) = defined.get_mut("name") {
+                        *name = type_name(renames, name).to_owned();
+                    }
+                }
+                Value::Object(
+                    object.into_iter().map(|(key, value)| (key, rename(renames, value))).collect(),
+                )
+            },
+            Value::Array(values) =>
+                Value::Array(values.into_iter().map(|value| rename(renames, value)).collect()),
+            value => value,
+        }
+    }
+    rename(renames, to_value(value))
+}
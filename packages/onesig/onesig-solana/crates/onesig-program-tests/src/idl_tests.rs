@@ -0,0 +1,252 @@
+// Tests for the compatibility of the program's IDL with its committed snapshot.
+#[cfg(test)]
+mod tests {
+    use anchor_lang_idl::types::Idl;
+    use serde_json::{json, Value};
+
+    use crate::idl::{
+        breaking_changes, generate, load_snapshot, same, save_snapshot, snapshot_path, unmigrated,
+        Migration, Rename, MIGRATIONS, RENAMES, UPDATE_SNAPSHOT_VAR,
+    };
+
+    fn snapshot() -> Value {
+        json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "program", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "execute",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "executor", "writable": true, "signer": true },
+                    { "name": "roots", "accounts": [{ "name": "state" }, { "name": "root" }] },
+                ],
+                "args": [{ "name": "params", "type": { "defined": { "name": "Params" } } }],
+            }],
+            "accounts": [{ "name": "State", "discriminator": [8, 7, 6, 5, 4, 3, 2, 1] }],
+            "events": [{ "name": "Executed", "discriminator": [0, 1, 0, 1, 0, 1, 0, 1] }],
+            "errors": [{ "code": 6000, "name": "Expired" }],
+            "types": [
+                {
+                    "name": "Params",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "nonce", "type": "u64" },
+                            { "name": "mode", "type": { "defined": { "name": "Mode" } } },
+                        ],
+                    },
+                },
+                {
+                    "name": "Mode",
+                    "type": { "kind": "enum", "variants": [{ "name": "Once" }, { "name": "Always" }] },
+                },
+            ],
+        })
+    }
+
+    fn idl(json: Value) -> Idl {
+        serde_json::from_value(json).unwrap()
+    }
+
+    /// The snapshot, changed by `change`
+    fn changed(change: impl FnOnce(&mut Value)) -> Idl {
+        let mut json = snapshot();
+        change(&mut json);
+        idl(json)
+    }
+
+    fn changes(current: &Idl, renames: &[Rename]) -> Vec<String> {
+        breaking_changes(&idl(snapshot()), current, renames)
+    }
+
+    #[test]
+    fn test_program_idl_matches_snapshot() {
+        let current = generate().unwrap();
+        let update = std::env::var_os(UPDATE_SNAPSHOT_VAR).is_some();
+        let Some(snapshot) = load_snapshot().unwrap() else {
+            assert!(
+                update,
+                "no IDL snapshot at {}, run with {UPDATE_SNAPSHOT_VAR}=1 to take it",
+                snapshot_path().display()
+            );
+            return save_snapshot(&current).unwrap();
+        };
+        let changes = breaking_changes(&snapshot, &current, RENAMES);
+        if update {
+            for change in &changes {
+                eprintln!("accepting a breaking change: {change}");
+            }
+            if !RENAMES.is_empty() || !MIGRATIONS.is_empty() {
+                eprintln!("empty `RENAMES` and `MIGRATIONS`, which the new snapshot includes");
+            }
+            return save_snapshot(&current).unwrap();
+        }
+        let (unmigrated, stale) = unmigrated(&changes, MIGRATIONS);
+        assert!(
+            unmigrated.is_empty(),
+            "breaking changes to the IDL:\n{}\nname renames clients were migrated for in \
+             `RENAMES` and other changes in `MIGRATIONS`, or run with {UPDATE_SNAPSHOT_VAR}=1 to \
+             accept the changes",
+            unmigrated.join("\n")
+        );
+        assert!(stale.is_empty(), "`MIGRATIONS` lists changes not made:\n{}", stale.join("\n"));
+        // Until the next release, the snapshot is the IDL clients were migrated from
+        if RENAMES.is_empty() && MIGRATIONS.is_empty() {
+            assert!(
+                same(&snapshot, &current),
+                "the IDL changed compatibly, run with {UPDATE_SNAPSHOT_VAR}=1 to update its \
+                 snapshot"
+            );
+        }
+    }
+
+    #[test]
+    fn test_migrations_explain_changes() {
+        let current = changed(|json| json["events"][0]["discriminator"] = json!([0; 8]));
+        let changes = changes(&current, &[]);
+        let migrations = [Migration {
+            changes: &[
+                "event `Executed` changed its discriminator",
+                "event `Executed` was removed",
+            ],
+            reason: "test",
+        }];
+        let (left, stale) = unmigrated(&changes, &migrations);
+        assert!(left.is_empty());
+        assert_eq!(stale, ["event `Executed` was removed"]);
+
+        let (left, stale) = unmigrated(&changes, &[]);
+        assert_eq!(left, ["event `Executed` changed its discriminator"]);
+        assert!(stale.is_empty());
+    }
+
+    #[test]
+    fn test_additions_are_compatible() {
+        let current = changed(|json| {
+            json["instructions"][0]["accounts"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({ "name": "extra", "optional": true }));
+            json["instructions"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({ "name": "close", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9], "accounts": [], "args": [] }));
+            json["types"][1]["type"]["variants"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({ "name": "Never" }));
+            json["errors"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({ "code": 6001, "name": "Closed" }));
+            json["instructions"][0]["docs"] = json!(["Executes a leaf."]);
+        });
+
+        assert!(changes(&current, &[]).is_empty());
+        assert!(!same(&idl(snapshot()), &current));
+    }
+
+    #[test]
+    fn test_removals_are_breaking() {
+        let current = changed(|json| {
+            json["instructions"] = json!([]);
+            json["accounts"] = json!([]);
+            json["events"] = json!([]);
+            json["types"].as_array_mut().unwrap().pop();
+        });
+
+        assert_eq!(
+            changes(&current, &[]),
+            [
+                "instruction `execute` was removed",
+                "account `State` was removed",
+                "event `Executed` was removed",
+                "type `Mode` was removed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_changes_are_breaking() {
+        let current = changed(|json| {
+            let instruction = &mut json["instructions"][0];
+            instruction["discriminator"] = json!([0, 0, 0, 0, 0, 0, 0, 0]);
+            instruction["args"][0]["type"] = json!("u64");
+            instruction["accounts"][1]["accounts"] =
+                json!([{ "name": "root" }, { "name": "state" }]);
+            instruction["accounts"][0]["writable"] = json!(false);
+            instruction["accounts"].as_array_mut().unwrap().push(json!({ "name": "vault" }));
+        });
+
+        assert_eq!(
+            changes(&current, &[]),
+            [
+                "instruction `execute` changed its discriminator",
+                "instruction `execute` changed its arguments",
+                "account `executor` of instruction `execute` changed whether it is writable, a \
+                 signer or optional",
+                "account 1 of instruction `execute` is `root`, not `state`",
+                "account 2 of instruction `execute` is `state`, not `root`",
+                "instruction `execute` requires the new account `vault`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_changes_are_breaking() {
+        let current = changed(|json| {
+            json["types"][0]["type"]["fields"].as_array_mut().unwrap().reverse();
+            json["types"][1]["type"]["variants"].as_array_mut().unwrap().reverse();
+            json["accounts"][0]["discriminator"] = json!([0, 0, 0, 0, 0, 0, 0, 0]);
+            json["errors"][0]["code"] = json!(6001);
+        });
+
+        assert_eq!(
+            changes(&current, &[]),
+            [
+                "account `State` changed its discriminator",
+                "type `Params` changed its layout",
+                "type `Mode` changed its layout",
+                "error `Expired` changed its code to 6001",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_renames_keeping_the_encoding_are_compatible() {
+        let current = changed(|json| {
+            json["instructions"][0]["name"] = json!("execute_leaf");
+            json["instructions"][0]["accounts"][1]["accounts"][0]["name"] = json!("one_sig_state");
+            json["accounts"][0]["name"] = json!("OneSigState");
+            json["events"][0]["name"] = json!("LeafExecuted");
+            json["types"][1]["name"] = json!("ExecutionMode");
+            json["types"][0]["type"]["fields"][1]["type"] =
+                json!({ "defined": { "name": "ExecutionMode" } });
+        });
+        let renames = [
+            Rename::Instruction { from: "execute", to: "execute_leaf" },
+            Rename::InstructionAccount {
+                instruction: "execute_leaf",
+                from: "state",
+                to: "one_sig_state",
+            },
+            Rename::Type { from: "State", to: "OneSigState" },
+            Rename::Type { from: "Mode", to: "ExecutionMode" },
+            Rename::Event { from: "Executed", to: "LeafExecuted" },
+        ];
+
+        assert!(changes(&current, &renames).is_empty());
+        // Unless named, they are removals
+        assert_eq!(changes(&current, &[]).len(), 5);
+
+        // A renamed instruction still has to keep its discriminator
+        let current = changed(|json| {
+            json["instructions"][0]["name"] = json!("execute_leaf");
+            json["instructions"][0]["discriminator"] = json!([0, 0, 0, 0, 0, 0, 0, 0]);
+        });
+        assert_eq!(
+            changes(&current, &renames[..1]),
+            ["instruction `execute_leaf` changed its discriminator"]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Integration tests of the OneSig program: its instructions run end to end in a
+//! `solana-program-test` bank, built with `onesig-sdk`, covering the flows only the TypeScript
+//! tests exercised so far.
+//!
+//! The program is loaded natively from the `onesig` crate of this workspace, so the tests need no
+//! SBF build: `cargo test -p onesig-program-tests`. The [IDL](idl) is checked against its committed
+//! snapshot there too.
+
+use std::mem::offset_of;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Clock, ProgramResult, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, AccountDeserialize, AnchorDeserialize,
+};
+use onesig::{
+    InitOneSigParams, MerkleRootState, OneSigError, OneSigState, SelfCpiTarget,
+    TransactionCheckResult, VerifyMerkleRootParams,
+};
+use onesig_core::Hash;
+use onesig_sdk::{
+    pack_signatures, ExecuteTransactionOptions, LocalSigner, OneSigAccount, OneSigClient,
+    OneSigTree, RootSigner, SolanaLeaf,
+};
+use onesig_test_vectors::THRESHOLD;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::AccountSharedData,
+    instruction::InstructionError,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+pub mod idl;
+pub mod mocks;
+
+#[cfg(test)]
+mod execute_tests;
+#[cfg(test)]
+mod idl_tests;
+#[cfg(test)]
+mod lifecycle_tests;
+#[cfg(test)]
+mod migrate_tests;
+#[cfg(test)]
+mod mocks_tests;
+
+/// `one_sig_id` of the accounts the harness initializes
+pub const ONE_SIG_ID: u64 = 1;
+
+/// Seed of the accounts the harness initializes
+pub const SEED: Hash = Hash([7u8; 32]);
+
+/// Anchor's entrypoint ties the lifetime of the account slice to the one of the accounts, which
+/// `processor!` does not; the slice is leaked, the bank only living as long as a test.
+fn process_instruction<'a, 'b, 'c, 'd>(
+    program_id: &'a Pubkey,
+    accounts: &'b [AccountInfo<'c>],
+    data: &'d [u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    onesig::entry(program_id, accounts, data)
+}
+
+/// A bank running the program, and the OneSig account under test, signed for by the signers of
+/// the test vectors. The bank's payer pays for and executes everything.
+pub struct Harness {
+    pub context: ProgramTestContext,
+    pub client: OneSigClient,
+    pub signers: Vec<LocalSigner>,
+}
+
+impl Harness {
+    /// A bank with the program and the [mock target programs](mocks) loaded, and no OneSig
+    /// account yet
+    pub async fn new() -> Self {
+        let mut program = ProgramTest::new("onesig", onesig::ID, processor!(process_instruction));
+        program.prefer_bpf(false);
+        program.add_program("counter", mocks::COUNTER_ID, processor!(mocks::counter));
+        program.add_program("failing", mocks::FAILING_ID, processor!(mocks::failing));
+        program.add_program("reentrant", mocks::REENTRANT_ID, processor!(mocks::reentrant));
+        program.add_program("drainer", mocks::DRAINER_ID, processor!(mocks::drainer));
+        let context = program.start_with_context().await;
+        let signers = onesig_test_vectors::load()
+            .signers
+            .iter()
+            .map(|signer| LocalSigner::from_bytes(&signer.private_key).unwrap())
+            .collect();
+        Self { context, client: OneSigClient::new(Pubkey::default()), signers }
+    }
+
+    /// A bank with the program loaded and a OneSig account initialized with
+    /// [`Self::init_params`]
+    pub async fn start() -> Self {
+        let mut harness = Self::new().await;
+        let params = harness.init_params();
+        harness.init_one_sig(params).await.unwrap();
+        harness
+    }
+
+    /// The test vector signers at their threshold, without executors
+    pub fn init_params(&self) -> InitOneSigParams {
+        InitOneSigParams {
+            one_sig_id: ONE_SIG_ID,
+            seed: SEED,
+            threshold: THRESHOLD,
+            signers: self.signers.iter().map(RootSigner::address).collect(),
+            executors: vec![],
+            executor_required: false,
+            signers_capacity: self.signers.len() as u16,
+        }
+    }
+
+    /// Initializes a OneSig account at a new address, which the client then addresses.
+    pub async fn init_one_sig(&mut self, params: InitOneSigParams) -> Result<(), BanksClientError> {
+        let state = Keypair::new();
+        self.client = OneSigClient::new(state.pubkey());
+        let payer = self.payer();
+        self.process(&[self.client.init_one_sig(&payer, params)], &[&state]).await
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Sends `instructions` in a transaction of their own, signed by the payer and `signers`. Each
+    /// transaction gets a new blockhash, so sending the same instructions twice is not a duplicate.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let payer = &self.context.payer;
+        let signers: Vec<&Keypair> =
+            std::iter::once(payer).chain(signers.iter().copied()).collect();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    pub async fn state(&mut self) -> OneSigAccount {
+        let address = self.client.state();
+        let account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        OneSigAccount::from_account_data(address, &account.data).unwrap()
+    }
+
+    /// The `MerkleRootState` of `merkle_root`, if verified and not closed
+    pub async fn merkle_root_state(&mut self, merkle_root: &Hash) -> Option<MerkleRootState> {
+        let address = self.client.pda.merkle_root_state(merkle_root).0;
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        Some(MerkleRootState::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    /// Sets the lamports of the system account `address`, creating it if needed.
+    pub fn fund(&mut self, address: Pubkey, lamports: u64) {
+        self.set_account(address, lamports, 0, &system_program::ID);
+    }
+
+    /// Replaces the account at `address` with one of `space` zeroed bytes owned by `owner`.
+    pub fn set_account(&mut self, address: Pubkey, lamports: u64, space: usize, owner: &Pubkey) {
+        self.context
+            .set_account(&address, &AccountSharedData::new(lamports, space, owner));
+    }
+
+    pub async fn data(&mut self, address: Pubkey) -> Vec<u8> {
+        self.context.banks_client.get_account(address).await.unwrap().unwrap().data
+    }
+
+    /// Lets executed leaves call the instructions of `targets`, as a `SetSelfCpiTargets` leaf
+    /// would, without using up a nonce.
+    pub async fn allow_self_cpi(&mut self, targets: &[SelfCpiTarget]) {
+        let address = self.client.state();
+        let mut account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let offset = OneSigState::HEADER_OFFSET + offset_of!(OneSigState, self_cpi_targets);
+        account.data[offset] = SelfCpiTarget::mask(targets);
+        self.context.set_account(&address, &account.into());
+    }
+
+    /// Moves the clock to `unix_timestamp`.
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Leaf at `nonce` executing `instruction` through vault 0, spending up to `value` lamports
+    pub fn leaf(&self, nonce: u64, instruction: &Instruction, value: u64) -> SolanaLeaf {
+        SolanaLeaf {
+            one_sig_state: self.client.state(),
+            one_sig_id: ONE_SIG_ID,
+            nonce,
+            instruction: self.client.resolve_instruction(instruction, 0, value).instruction,
+            token_outflow_limits: vec![],
+            guarded_accounts: vec![],
+            vault_index: 0,
+            cluster_genesis_hash: None,
+            group: None,
+            not_before: None,
+            bounty: None,
+        }
+    }
+
+    /// `tree` signed by `THRESHOLD` of the signers, under the seed of the account
+    pub fn sign(
+        &self,
+        tree: &OneSigTree,
+        expiry: u64,
+        max_executions: u32,
+    ) -> VerifyMerkleRootParams {
+        let message = tree.message(&SEED, expiry, max_executions);
+        let signatures = self.signers.iter().map(|signer| signer.sign(&message).unwrap());
+        let addresses: Vec<_> = self.signers.iter().map(RootSigner::address).collect();
+        VerifyMerkleRootParams {
+            merkle_root: tree.root(),
+            expiry: expiry as i64,
+            max_executions,
+            signatures: pack_signatures(&message.digest(), signatures, &addresses, THRESHOLD)
+                .unwrap(),
+        }
+    }
+
+    pub async fn verify_merkle_root(
+        &mut self,
+        params: VerifyMerkleRootParams,
+    ) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        self.process(&[self.client.verify_merkle_root(&payer, params)], &[]).await
+    }
+
+    /// Executes leaf `index` of `tree`, verifying the root inline with `merkle_root_verification`
+    /// or else through its `MerkleRootState`.
+    pub async fn execute(
+        &mut self,
+        tree: &OneSigTree,
+        index: usize,
+        leaf: &SolanaLeaf,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+    ) -> Result<(), BanksClientError> {
+        let executor = self.payer();
+        let instruction = self.client.execute_leaf(
+            &executor,
+            &tree.root(),
+            leaf,
+            tree.proof(index).unwrap(),
+            merkle_root_verification,
+            ExecuteTransactionOptions::default(),
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    /// What `check_transaction` reports of leaf `index` of `tree`, in a simulation of it with the
+    /// arguments [`Self::execute`] would execute it with
+    pub async fn check(
+        &mut self,
+        tree: &OneSigTree,
+        index: usize,
+        leaf: &SolanaLeaf,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+    ) -> TransactionCheckResult {
+        let executor = self.payer();
+        let instruction = self.client.check_leaf(
+            &executor,
+            &tree.root(),
+            leaf,
+            tree.proof(index).unwrap(),
+            merkle_root_verification,
+            ExecuteTransactionOptions::default(),
+        );
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&executor),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        TransactionCheckResult::try_from_slice(&return_data.data).unwrap()
+    }
+
+    pub async fn close_merkle_root(&mut self, merkle_root: &Hash) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        self.process(&[self.client.close_merkle_root(&payer, merkle_root)], &[]).await
+    }
+}
+
+/// Asserts that `result` is the failure of the program with `expected`.
+pub fn assert_error(result: Result<(), BanksClientError>, expected: OneSigError) {
+    assert_instruction_error(result, InstructionError::Custom(expected.into()));
+}
+
+/// Asserts that `result` failed with `expected`, whichever program raised it.
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    match result.expect_err("the transaction succeeded").unwrap() {
+        TransactionError::InstructionError(_, error) => assert_eq!(error, expected),
+        error => panic!("expected {expected:?}, got {error:?}"),
+    }
+}
@@ -0,0 +1,338 @@
+// Tests for initializing a OneSig account, configuring it through a leaf, and verifying and closing
+// merkle roots.
+#[cfg(test)]
+mod tests {
+    use std::mem::offset_of;
+
+    use anchor_lang::{
+        solana_program::instruction::Instruction, AccountDeserialize, AccountSerialize,
+        AnchorDeserialize, InstructionData,
+    };
+    use onesig::{
+        instruction::Version, MerkleRootRegistry, MerkleRootState, OneSigError, OneSigState,
+        SelfCpiTarget, SetConfigParams, VerifyMerkleRootParams, MERKLE_ROOT_STATE_VERSION, VERSION,
+    };
+    use onesig_core::Hash;
+    use onesig_sdk::{pack_signatures, LocalSigner, OneSigTree, RootSigner, SolanaLeaf};
+    use onesig_test_vectors::{EXPIRY, THRESHOLD};
+    use solana_sdk::{pubkey::Pubkey, signer::Signer, transaction::Transaction};
+    use solana_system_interface::instruction::transfer;
+
+    use crate::{assert_error, Harness, ONE_SIG_ID, SEED};
+
+    #[tokio::test]
+    async fn test_init_one_sig() {
+        let mut harness = Harness::start().await;
+        let state = harness.state().await;
+
+        assert_eq!(state.header.one_sig_id, ONE_SIG_ID);
+        assert_eq!((state.header.nonce, state.header.threshold), (0, THRESHOLD));
+        assert!(state.header.seed == SEED);
+        let signers: Vec<_> = harness.signers.iter().map(RootSigner::address).collect();
+        assert_eq!(state.signers, signers);
+    }
+
+    #[tokio::test]
+    async fn test_version_without_accounts() {
+        let mut harness = Harness::new().await;
+        let instruction = Instruction::new_with_bytes(onesig::ID, &Version {}.data(), vec![]);
+        let blockhash = harness.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&harness.payer()),
+            &[&harness.context.payer],
+            blockhash,
+        );
+
+        let simulation =
+            harness.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        assert_eq!(String::try_from_slice(&return_data.data).unwrap(), VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_init_rejects_threshold_above_signers() {
+        let mut harness = Harness::new().await;
+        let params = harness.init_params();
+        let threshold = params.signers.len() as u8 + 1;
+
+        let result = harness.init_one_sig(onesig::InitOneSigParams { threshold, ..params }).await;
+        assert_error(result, OneSigError::ThresholdExceedsSigners);
+    }
+
+    #[tokio::test]
+    async fn test_set_config() {
+        let mut harness = Harness::start().await;
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        let verification = harness.sign(&tree, EXPIRY, 0);
+        harness.execute(&tree, 0, &leaf, Some(verification)).await.unwrap();
+
+        let state = harness.state().await;
+        assert_eq!((state.header.nonce, state.header.threshold), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_set_config_rejects_executor_params() {
+        let mut harness = Harness::start().await;
+        // The variants keep their Borsh tags, so the ones after them keep theirs
+        let instruction = harness.client.set_config(SetConfigParams::SetExecutorRequired(true));
+        assert_eq!(instruction.data[8..], [6, 1]);
+
+        let params = SetConfigParams::AddExecutor(harness.payer());
+        let leaf = harness.leaf(0, &harness.client.set_config(params), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        assert_error(result, OneSigError::ExecutorConfigMoved);
+    }
+
+    #[tokio::test]
+    async fn test_verify_and_close_merkle_root() {
+        let mut harness = Harness::start().await;
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.into()]).unwrap();
+        let root = tree.root();
+
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        let root_state = harness.merkle_root_state(&root).await.unwrap();
+        assert!(root_state.merkle_root == root && root_state.verified_under(&SEED));
+        assert_eq!(root_state.expiry, EXPIRY as i64);
+        assert_eq!(root_state.signed_by.len(), THRESHOLD as usize);
+        assert_eq!(
+            (root_state.rent_payer, root_state.version),
+            (harness.payer(), MERKLE_ROOT_STATE_VERSION)
+        );
+        assert_eq!(root_state.remaining_executions, None);
+        // Sized to the signers it records
+        let address = harness.client.pda.merkle_root_state(&root).0;
+        assert_eq!(harness.data(address).await.len(), MerkleRootState::space(THRESHOLD as usize));
+
+        // Live roots stay
+        assert_error(harness.close_merkle_root(&root).await, OneSigError::MerkleRootNotCloseable);
+
+        harness.set_time(EXPIRY as i64 + 1).await;
+        let payer_before = harness.lamports(harness.payer()).await;
+        harness.close_merkle_root(&root).await.unwrap();
+        assert!(harness.merkle_root_state(&root).await.is_none());
+        // The rent outweighs the fee of the transaction
+        assert!(harness.lamports(harness.payer()).await > payer_before);
+    }
+
+    #[tokio::test]
+    async fn test_verify_records_every_signer() {
+        let mut harness = Harness::new().await;
+        harness.signers.push(LocalSigner::from_bytes(&[0x42; 32]).unwrap());
+        let params = harness.init_params();
+        harness.init_one_sig(params).await.unwrap();
+        let mut signers: Vec<_> = harness.signers.iter().map(RootSigner::address).collect();
+        signers.sort();
+        // The signer a root recording only the first `THRESHOLD` signers would hold
+        let removed = SetConfigParams::RemoveSigner(signers[0]);
+        let leaves = [
+            harness.leaf(0, &harness.client.set_config(removed), 0),
+            harness.leaf(1, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0),
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        let message = tree.message(&SEED, EXPIRY, 0);
+        let signatures = harness.signers.iter().map(|signer| signer.sign(&message).unwrap());
+        let signatures =
+            pack_signatures(&message.digest(), signatures, &signers, signers.len() as u8).unwrap();
+        let params = VerifyMerkleRootParams {
+            merkle_root: tree.root(),
+            expiry: EXPIRY as i64,
+            max_executions: 0,
+            signatures,
+        };
+
+        harness.verify_merkle_root(params).await.unwrap();
+        let root_state = harness.merkle_root_state(&tree.root()).await.unwrap();
+        assert_eq!(root_state.signed_by, signers);
+        let address = harness.client.pda.merkle_root_state(&tree.root()).0;
+        assert_eq!(harness.data(address).await.len(), MerkleRootState::space(signers.len()));
+
+        // Enough of its signers remain once one is removed, so the root keeps executing
+        for (index, leaf) in leaves.iter().enumerate() {
+            harness.execute(&tree, index, leaf, None).await.unwrap();
+        }
+        assert_eq!(harness.state().await.header.threshold, 1);
+    }
+
+    #[tokio::test]
+    async fn test_close_merkle_root_outside_registry() {
+        let mut harness = Harness::start().await;
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.into()]).unwrap();
+        let root = tree.root();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // Drop the entry of the root from the registry
+        let address = harness.client.pda.merkle_root_registry().0;
+        let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let mut registry =
+            MerkleRootRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+        registry.entries.clear();
+        account.data = vec![0; MerkleRootRegistry::space(0)];
+        registry.try_serialize(&mut account.data.as_mut_slice()).unwrap();
+        let lamports = account.lamports;
+        harness.context.set_account(&address, &account.into());
+
+        // The registry is left as is rather than losing another entry's space
+        harness.set_time(EXPIRY as i64 + 1).await;
+        harness.close_merkle_root(&root).await.unwrap();
+        assert_eq!(harness.data(address).await.len(), MerkleRootRegistry::space(0));
+        assert_eq!(harness.lamports(address).await, lamports);
+    }
+
+    #[tokio::test]
+    async fn test_close_merkle_root_rejects_uncounted_root() {
+        let mut harness = Harness::start().await;
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.into()]).unwrap();
+        let root = tree.root();
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+
+        // A registered root the count does not include
+        let address = harness.client.state();
+        let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let len = OneSigState::HEADER_OFFSET + offset_of!(OneSigState, merkle_roots_len);
+        account.data[len..len + 2].copy_from_slice(&0u16.to_le_bytes());
+        harness.context.set_account(&address, &account.into());
+
+        harness.set_time(EXPIRY as i64 + 1).await;
+        let result = harness.close_merkle_root(&root).await;
+        assert_error(result, OneSigError::InvalidMerkleRootsLen);
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_expired_root() {
+        let mut harness = Harness::start().await;
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.into()]).unwrap();
+
+        harness.set_time(EXPIRY as i64 + 1).await;
+        let result = harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await;
+        assert_error(result, OneSigError::ExpiredMerkleRoot);
+    }
+
+    #[tokio::test]
+    async fn test_reset_nonce() {
+        let mut harness = Harness::start().await;
+        let seed = Hash([8u8; 32]);
+        let threshold =
+            harness.leaf(0, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let reset =
+            harness.leaf(1, &harness.client.set_config(SetConfigParams::ResetNonce(seed)), 0);
+        let tree = OneSigTree::new([threshold.clone().into(), reset.clone().into()]).unwrap();
+
+        harness.verify_merkle_root(harness.sign(&tree, EXPIRY, 0)).await.unwrap();
+        harness.execute(&tree, 0, &threshold, None).await.unwrap();
+        harness.execute(&tree, 1, &reset, None).await.unwrap();
+        let state = harness.state().await;
+        assert_eq!(state.header.nonce, 0);
+        assert!(state.header.seed == seed);
+
+        // The first leaf is at the nonce again, but its root was signed under the previous seed
+        let result = harness.execute(&tree, 0, &threshold, None).await;
+        assert_error(result, OneSigError::SeedMismatch);
+    }
+
+    #[tokio::test]
+    async fn test_reset_nonce_requires_new_seed() {
+        let mut harness = Harness::start().await;
+        let leaf =
+            harness.leaf(0, &harness.client.set_config(SetConfigParams::ResetNonce(SEED)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        assert_error(result, OneSigError::NonceNamespaceUnchanged);
+    }
+
+    #[tokio::test]
+    async fn test_set_seed_at_nonce_zero() {
+        let mut harness = Harness::start().await;
+        let seed = Hash([8u8; 32]);
+        let leaf = harness.leaf(0, &harness.client.set_config(SetConfigParams::SetSeed(seed)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+
+        // Changing the seed at nonce 0 leaves the state `ResetNonce` would, but still uses the
+        // nonce up
+        harness
+            .execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0)))
+            .await
+            .unwrap();
+        let state = harness.state().await;
+        assert_eq!(state.header.nonce, 1);
+        assert!(state.header.seed == seed);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_overflow() {
+        let mut harness = Harness::start().await;
+        let address = harness.client.state();
+        let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let nonce = OneSigState::HEADER_OFFSET + offset_of!(OneSigState, nonce);
+        account.data[nonce..nonce + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        harness.context.set_account(&address, &account.into());
+
+        // The last nonce cannot be advanced past...
+        let leaf =
+            harness.leaf(u64::MAX, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        assert_error(result, OneSigError::NonceOverflow);
+        assert_eq!(harness.state().await.header.nonce, u64::MAX);
+
+        // ...but a leaf at it can still start a new namespace
+        let reset = SetConfigParams::ResetNonce(Hash([8u8; 32]));
+        let leaf = harness.leaf(u64::MAX, &harness.client.set_config(reset), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        harness
+            .execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0)))
+            .await
+            .unwrap();
+        assert_eq!(harness.state().await.header.nonce, 0);
+    }
+
+    #[tokio::test]
+    async fn test_close_one_sig_requires_used_vaults() {
+        const LAMPORTS: u64 = 1_000_000_000;
+        let mut harness = Harness::start().await;
+        let vault = harness.client.pda.vault_signer(3).0;
+        let recipient = Pubkey::new_unique();
+        harness.fund(vault, LAMPORTS);
+        harness.fund(recipient, LAMPORTS);
+        harness.allow_self_cpi(&[SelfCpiTarget::CloseOneSig]).await;
+        let pay = transfer(&vault, &recipient, 1_000);
+        let leaves = [
+            SolanaLeaf {
+                instruction: harness.client.resolve_instruction(&pay, 3, 1_000).instruction,
+                vault_index: 3,
+                ..harness.leaf(0, &pay, 1_000)
+            },
+            harness.leaf(1, &harness.client.close_one_sig(&recipient, &[]), 0),
+            harness.leaf(1, &harness.client.close_one_sig(&recipient, &[3]), 0),
+        ];
+        let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+        let verification = harness.sign(&tree, EXPIRY, 0);
+        harness.execute(&tree, 0, &leaves[0], Some(verification.clone())).await.unwrap();
+        assert_eq!(harness.state().await.header.used_vaults().collect::<Vec<_>>(), [3]);
+
+        // Vault 3 has signed, so its signer PDA must be passed, and drained
+        let result = harness.execute(&tree, 1, &leaves[1], Some(verification.clone())).await;
+        assert_error(result, OneSigError::VaultSignersMissing);
+        let result = harness.execute(&tree, 2, &leaves[2], Some(verification.clone())).await;
+        assert_error(result, OneSigError::NonZeroSignerBalance);
+
+        // The bounty escrow is swept with the rest
+        harness.fund(vault, 0);
+        harness.fund(harness.client.pda.bounty_escrow().0, LAMPORTS);
+        let balance = harness.lamports(recipient).await;
+        harness.execute(&tree, 2, &leaves[2], Some(verification)).await.unwrap();
+        let state = harness.client.state();
+        assert!(harness.context.banks_client.get_account(state).await.unwrap().is_none());
+        assert_eq!(harness.lamports(harness.client.pda.bounty_escrow().0).await, 0);
+        assert!(harness.lamports(recipient).await > balance + LAMPORTS);
+    }
+}
@@ -0,0 +1,311 @@
+// Tests for `migrate_state` converting the accounts written by the first release of the program,
+// from their raw data as it left them.
+#[cfg(test)]
+mod tests {
+    use std::mem::offset_of;
+
+    use anchor_lang::{
+        error::ErrorCode,
+        prelude::{Pubkey, Rent},
+        AccountDeserialize, AnchorSerialize, Discriminator,
+    };
+    use onesig::{
+        Address, ExecutorState, MerkleRootRegistry, MerkleRootState, OneSigError, OneSigState,
+        SelfCpiTarget, SetConfigParams, SpendingLimits, TargetPrograms,
+        BASELINE_ONE_SIG_STATE_DISCRIMINATOR, MERKLE_ROOT_STATE_VERSION, ONE_SIG_SEED,
+        ONE_SIG_STATE_VERSION,
+    };
+    use onesig_core::Hash;
+    use onesig_sdk::{OneSigClient, OneSigTree, RootSigner};
+    use onesig_test_vectors::{EXPIRY, THRESHOLD};
+    use solana_program_test::BanksClientError;
+    use solana_sdk::{account::Account, instruction::InstructionError};
+
+    use crate::{assert_error, assert_instruction_error, Harness, ONE_SIG_ID, SEED};
+
+    /// Size of the `OneSigState` accounts of the first release, `8 + INIT_SPACE`
+    const BASELINE_SPACE: usize = 9331;
+
+    /// Size of the `MerkleRootState` accounts of the first release, `8 + INIT_SPACE`
+    const BASELINE_MERKLE_ROOT_SPACE: usize = 517;
+
+    /// Nonce the accounts of the first release had reached
+    const NONCE: u64 = 5;
+
+    /// `OneSigState` of the first release, its `Multisig` and `Executors` fields inlined
+    #[derive(AnchorSerialize)]
+    struct BaselineOneSigState {
+        one_sig_id: u64,
+        seed: Hash,
+        bump: u8,
+        nonce: u64,
+        signers: Vec<Address>,
+        threshold: u8,
+        executors: Vec<Pubkey>,
+        executor_required: bool,
+    }
+
+    /// `MerkleRootState` of the first release
+    #[derive(AnchorSerialize)]
+    struct BaselineMerkleRootState {
+        merkle_root: Hash,
+        seed: Hash,
+        expiry: i64,
+        rent_payer: Pubkey,
+        signed_by: Vec<Address>,
+        bump: u8,
+    }
+
+    /// Writes the account the first release left for the test vector signers and `executors` at a
+    /// new address, which the client then addresses.
+    fn set_baseline_one_sig(harness: &mut Harness, executors: &[Pubkey]) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let state = BaselineOneSigState {
+            one_sig_id: ONE_SIG_ID,
+            seed: SEED,
+            bump: Pubkey::find_program_address(&[ONE_SIG_SEED, address.as_ref()], &onesig::ID).1,
+            nonce: NONCE,
+            signers: harness.signers.iter().map(RootSigner::address).collect(),
+            threshold: THRESHOLD,
+            executors: executors.to_vec(),
+            executor_required: false,
+        };
+        let mut data = BASELINE_ONE_SIG_STATE_DISCRIMINATOR.to_vec();
+        state.serialize(&mut data).unwrap();
+        data.resize(BASELINE_SPACE, 0);
+        let account = Account {
+            lamports: Rent::default().minimum_balance(BASELINE_SPACE),
+            data,
+            owner: onesig::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        harness.context.set_account(&address, &account.into());
+        harness.client = OneSigClient::new(address);
+        address
+    }
+
+    /// Writes the `MerkleRootState` the first release left for `tree`, verified by the test vector
+    /// signers at their threshold.
+    fn set_baseline_merkle_root(harness: &mut Harness, tree: &OneSigTree, rent_payer: Pubkey) {
+        let merkle_root = tree.root();
+        let (address, bump) = harness.client.pda.merkle_root_state(&merkle_root);
+        let root = BaselineMerkleRootState {
+            merkle_root,
+            seed: SEED,
+            expiry: EXPIRY as i64,
+            rent_payer,
+            signed_by: harness
+                .signers
+                .iter()
+                .take(THRESHOLD as usize)
+                .map(RootSigner::address)
+                .collect(),
+            bump,
+        };
+        let mut data = MerkleRootState::DISCRIMINATOR.to_vec();
+        root.serialize(&mut data).unwrap();
+        data.resize(BASELINE_MERKLE_ROOT_SPACE, 0);
+        let account = Account {
+            lamports: Rent::default().minimum_balance(BASELINE_MERKLE_ROOT_SPACE),
+            data,
+            owner: onesig::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        harness.context.set_account(&address, &account.into());
+    }
+
+    async fn migrate(
+        harness: &mut Harness,
+        executors: &[Pubkey],
+        merkle_roots: &[Hash],
+    ) -> Result<(), BanksClientError> {
+        let payer = harness.payer();
+        let instruction = harness.client.migrate_state(&payer, executors, merkle_roots);
+        harness.process(&[instruction], &[]).await
+    }
+
+    #[tokio::test]
+    async fn test_migrate_baseline_one_sig_state() {
+        let mut harness = Harness::new().await;
+        let executors = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let address = set_baseline_one_sig(&mut harness, &executors);
+
+        // Not mistaken for an account of the current layout
+        let leaf =
+            harness.leaf(NONCE, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let result = harness.execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0))).await;
+        let mismatch = ErrorCode::AccountDiscriminatorMismatch;
+        assert_instruction_error(result, InstructionError::Custom(mismatch.into()));
+
+        migrate(&mut harness, &executors, &[]).await.unwrap();
+        let state = harness.state().await;
+        assert_eq!((state.header.one_sig_id, state.header.nonce), (ONE_SIG_ID, NONCE));
+        assert!(state.header.seed == SEED);
+        assert_eq!(state.header.threshold, THRESHOLD);
+        let signers: Vec<_> = harness.signers.iter().map(RootSigner::address).collect();
+        assert_eq!(state.signers, signers);
+        assert_eq!((state.header.signers_capacity, state.header.executors_len), (20, 2));
+        assert_eq!(state.header.version, ONE_SIG_STATE_VERSION);
+        assert_eq!(state.header.self_cpi_targets(), SelfCpiTarget::DEFAULT);
+        assert_eq!(harness.data(address).await.len(), OneSigState::space(20));
+
+        // The PDAs `init_one_sig` creates now exist, with one `ExecutorState` per executor
+        let registry = harness.data(harness.client.pda.merkle_root_registry().0).await;
+        let registry = MerkleRootRegistry::try_deserialize(&mut registry.as_slice()).unwrap();
+        assert!(registry.entries.is_empty());
+        let limits = harness.data(harness.client.pda.spending_limits().0).await;
+        let limits = SpendingLimits::try_deserialize(&mut limits.as_slice()).unwrap();
+        assert!(limits.entries.is_empty() && limits.executor_reimbursement == 0);
+        let targets = harness.data(harness.client.pda.target_programs().0).await;
+        let targets = TargetPrograms::try_deserialize(&mut targets.as_slice()).unwrap();
+        assert!(targets.programs.is_empty());
+        for executor in executors {
+            let record = harness.data(harness.client.pda.executor_state(&executor).0).await;
+            let record = ExecutorState::try_deserialize(&mut record.as_slice()).unwrap();
+            assert_eq!(record.executor, executor);
+        }
+
+        // The multisig carries on from its nonce
+        harness
+            .execute(&tree, 0, &leaf, Some(harness.sign(&tree, EXPIRY, 0)))
+            .await
+            .unwrap();
+        let state = harness.state().await;
+        assert_eq!((state.header.nonce, state.header.threshold), (NONCE + 1, 1));
+
+        // Migrating again changes nothing
+        migrate(&mut harness, &[], &[]).await.unwrap();
+        assert_eq!(harness.state().await.header.nonce, NONCE + 1);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_baseline_requires_executor_states() {
+        let mut harness = Harness::new().await;
+        let executors = [Pubkey::new_unique(), Pubkey::new_unique()];
+        set_baseline_one_sig(&mut harness, &executors);
+
+        // All of them, in the order the account lists them
+        let result = migrate(&mut harness, &executors[..1], &[]).await;
+        assert_error(result, OneSigError::InvalidExecutorsLen);
+        let result = migrate(&mut harness, &[executors[1], executors[0]], &[]).await;
+        assert_error(result, OneSigError::InvalidExecutor);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_rejects_unrecognised_state() {
+        let mut harness = Harness::new().await;
+        let address = set_baseline_one_sig(&mut harness, &[]);
+        let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+
+        // Any other size, as the first release never resized its accounts
+        let mut resized = account.clone();
+        resized.data.truncate(BASELINE_SPACE - 1);
+        harness.context.set_account(&address, &resized.into());
+        assert_error(migrate(&mut harness, &[], &[]).await, OneSigError::InvalidMigrationAccount);
+
+        // Another bump than the one of its `one_sig_signer` PDA, at offset 8 + 8 + 32
+        account.data[48] = account.data[48].wrapping_add(1);
+        harness.context.set_account(&address, &account.clone().into());
+        assert_error(migrate(&mut harness, &[], &[]).await, OneSigError::InvalidMigrationAccount);
+
+        // More signers than the first release allowed, at offset 8 + 8 + 32 + 1 + 8
+        account.data[48] = account.data[48].wrapping_sub(1);
+        account.data[57..61].copy_from_slice(&21u32.to_le_bytes());
+        harness.context.set_account(&address, &account.clone().into());
+        assert_error(migrate(&mut harness, &[], &[]).await, OneSigError::InvalidMigrationAccount);
+
+        // Nor any other account of the program
+        harness
+            .context
+            .set_account(&address, &Account { data: vec![0; 128], ..account }.into());
+        assert_error(migrate(&mut harness, &[], &[]).await, OneSigError::InvalidMigrationAccount);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_rejects_current_state_at_another_version() {
+        let mut harness = Harness::start().await;
+        let address = harness.client.state();
+        let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let version = OneSigState::HEADER_OFFSET + offset_of!(OneSigState, version);
+        account.data[version] = ONE_SIG_STATE_VERSION - 1;
+        harness.context.set_account(&address, &account.into());
+
+        // Only the layout of the first release is converted
+        assert_error(migrate(&mut harness, &[], &[]).await, OneSigError::StateVersionMismatch);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_baseline_merkle_root() {
+        let mut harness = Harness::new().await;
+        set_baseline_one_sig(&mut harness, &[]);
+        let leaf =
+            harness.leaf(NONCE, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.clone().into()]).unwrap();
+        let root = tree.root();
+        let rent_payer = Pubkey::new_unique();
+        set_baseline_merkle_root(&mut harness, &tree, rent_payer);
+
+        // Converted along with its OneSig account
+        migrate(&mut harness, &[], &[root]).await.unwrap();
+        let root_state = harness.merkle_root_state(&root).await.unwrap();
+        assert!(root_state.merkle_root == root && root_state.verified_under(&SEED));
+        assert_eq!((root_state.expiry, root_state.rent_payer), (EXPIRY as i64, rent_payer));
+        assert_eq!(root_state.version, MERKLE_ROOT_STATE_VERSION);
+        assert_eq!((root_state.executions, root_state.remaining_executions), (0, None));
+        let signed_by: Vec<_> = harness
+            .signers
+            .iter()
+            .take(THRESHOLD as usize)
+            .map(RootSigner::address)
+            .collect();
+        assert_eq!(root_state.signed_by, signed_by);
+        let address = harness.client.pda.merkle_root_state(&root).0;
+        assert_eq!(harness.data(address).await.len(), MerkleRootState::space(THRESHOLD as usize));
+        // Registered and counted like a root verified now
+        let registry = harness.data(harness.client.pda.merkle_root_registry().0).await;
+        let registry = MerkleRootRegistry::try_deserialize(&mut registry.as_slice()).unwrap();
+        assert_eq!(registry.entries.len(), 1);
+        assert_eq!(
+            (registry.entries[0].address, registry.entries[0].expiry),
+            (address, EXPIRY as i64)
+        );
+        assert_eq!(harness.state().await.header.merkle_roots_len, 1);
+
+        // Its leaves execute without signatures, as before the upgrade
+        harness.execute(&tree, 0, &leaf, None).await.unwrap();
+        assert_eq!(harness.state().await.header.nonce, NONCE + 1);
+
+        // Converting it again changes nothing
+        migrate(&mut harness, &[], &[root]).await.unwrap();
+        assert_eq!(harness.merkle_root_state(&root).await.unwrap().executions, 1);
+        assert_eq!(harness.state().await.header.merkle_roots_len, 1);
+
+        // Closing it uncounts it
+        harness.set_time(EXPIRY as i64 + 1).await;
+        harness.close_merkle_root(&root).await.unwrap();
+        assert_eq!(harness.state().await.header.merkle_roots_len, 0);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_rejects_foreign_merkle_root() {
+        let mut harness = Harness::new().await;
+        set_baseline_one_sig(&mut harness, &[]);
+        migrate(&mut harness, &[], &[]).await.unwrap();
+        let leaf =
+            harness.leaf(NONCE, &harness.client.set_config(SetConfigParams::SetThreshold(1)), 0);
+        let tree = OneSigTree::new([leaf.into()]).unwrap();
+        set_baseline_merkle_root(&mut harness, &tree, harness.payer());
+
+        // A root of another OneSig account
+        let address = harness.client.pda.merkle_root_state(&tree.root()).0;
+        let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let other = Hash([9u8; 32]);
+        let other_address = harness.client.pda.merkle_root_state(&other).0;
+        harness.context.set_account(&other_address, &account.into());
+        let result = migrate(&mut harness, &[], &[other]).await;
+        assert_error(result, OneSigError::InvalidMigrationAccount);
+    }
+}
@@ -0,0 +1,92 @@
+//! Target programs of the leaves of the integration tests, loaded natively next to the OneSig
+//! program, so `execute_instruction`'s guards face calls that really change state, fail, re-enter
+//! or drain.
+
+use anchor_lang::{
+    prelude::{AccountInfo, ProgramError, ProgramResult, Pubkey},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+use solana_system_interface::instruction::transfer;
+
+/// Counts its calls in the first 8 bytes of its only account, which it owns
+pub const COUNTER_ID: Pubkey = Pubkey::new_from_array([0xc0; 32]);
+
+/// Fails every call with [`FAILING_ERROR`]
+pub const FAILING_ID: Pubkey = Pubkey::new_from_array([0xfa; 32]);
+
+/// Calls the OneSig program with its own instruction data and accounts, the OneSig program first
+pub const REENTRANT_ID: Pubkey = Pubkey::new_from_array([0x5e; 32]);
+
+/// Moves the little-endian `u64` of its data from its first account to its second: directly if it
+/// owns the first, or else through the system program, with the signature the first passes on
+pub const DRAINER_ID: Pubkey = Pubkey::new_from_array([0xd5; 32]);
+
+pub const FAILING_ERROR: u32 = 0xfa11;
+
+pub fn counter(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let counter = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let mut data = counter.try_borrow_mut_data()?;
+    let count = data.get_mut(..8).ok_or(ProgramError::AccountDataTooSmall)?;
+    let next = u64::from_le_bytes(count.try_into().unwrap()) + 1;
+    count.copy_from_slice(&next.to_le_bytes());
+    Ok(())
+}
+
+pub fn failing(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Err(ProgramError::Custom(FAILING_ERROR))
+}
+
+pub fn reentrant(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction = Instruction {
+        program_id: onesig::ID,
+        accounts: accounts
+            .iter()
+            .skip(1)
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke(&instruction, accounts)
+}
+
+pub fn drainer(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [from, to, ..] = accounts else { return Err(ProgramError::NotEnoughAccountKeys) };
+    let amount = u64::from_le_bytes(data.try_into().map_err(|_| ProgramError::InvalidArgument)?);
+    if from.owner == program_id {
+        **from.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    } else {
+        invoke(&transfer(from.key, to.key, amount), accounts)
+    }
+}
+
+/// `counter` of the counter account `counter`
+pub fn count(counter: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: COUNTER_ID,
+        accounts: vec![AccountMeta::new(*counter, false)],
+        data: vec![],
+    }
+}
+
+/// `drainer` moving `amount` from `from` to `to`; the system program is passed along for the
+/// accounts the drainer does not own.
+pub fn drain(from: &Pubkey, to: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: DRAINER_ID,
+        accounts: vec![
+            AccountMeta::new(*from, false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data: amount.to_le_bytes().to_vec(),
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use onesig_core::{Address, Hash, Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    digest::{merkle_root_message, Eip712Message},
+    encoding::{hex, hex_list},
+    error::SdkError,
+    leaf::Leaf,
+    signatures::{normalize_recovery_id, recover_signer},
+    tree::OneSigTree,
+};
+
+/// Version of the bundle format this SDK reads and writes
+pub const PROPOSAL_BUNDLE_VERSION: u32 = 1;
+
+/// Everything a proposal goes through between the proposer, the signers and the executors, as one
+/// JSON file: the leaves, their tree, what signers approve, and the signatures collected so far.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalBundle {
+    pub version: u32,
+    pub metadata: ProposalMetadata,
+    /// OneSig instances the leaves execute on
+    pub targets: Vec<ProposalTarget>,
+    pub leaves: Vec<Leaf>,
+    #[serde(with = "hex")]
+    pub merkle_root: Hash,
+    /// Proof of each leaf, in the order of `leaves`
+    pub proofs: Vec<ProposalProof>,
+    /// Signatures of the root collected so far, one per signer
+    #[serde(with = "hex_list")]
+    pub signatures: Vec<Signature>,
+    /// What executors are told about each leaf, in the order of `leaves`; empty when no leaf has
+    /// any. Signers do not sign it, so it is advice to executors rather than a guard.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub leaf_metadata: Vec<LeafMetadata>,
+}
+
+/// What signers approve besides the root, and why.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalMetadata {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(with = "hex")]
+    pub seed: Hash,
+    pub expiry: u64,
+    /// Caps how many leaves the root executes on Solana; 0 leaves it uncapped
+    #[serde(default)]
+    pub max_executions: u32,
+}
+
+/// A OneSig instance, by the CAIP-2 id of its chain and its address: the base58 state account of a
+/// Solana OneSig, the 0x-prefixed contract address of an EVM one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalTarget {
+    pub chain_id: String,
+    pub one_sig_id: u64,
+    pub address: String,
+}
+
+/// Off-chain instructions to the executors of a leaf
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafMetadata {
+    /// Unix time before which executors hold the leaf, e.g. so changes on several chains land
+    /// together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalProof {
+    #[serde(with = "hex_list")]
+    pub proof: Vec<Hash>,
+}
+
+impl ProposalBundle {
+    /// Bundles `leaves` with their tree, ready to collect signatures.
+    pub fn new(
+        metadata: ProposalMetadata,
+        targets: Vec<ProposalTarget>,
+        leaves: Vec<Leaf>,
+    ) -> Result<Self, SdkError> {
+        let tree = OneSigTree::new(leaves.iter().cloned())?;
+        let proofs = (0..leaves.len())
+            .map(|index| Ok(ProposalProof { proof: tree.proof(index)? }))
+            .collect::<Result<_, SdkError>>()?;
+        let bundle = Self {
+            version: PROPOSAL_BUNDLE_VERSION,
+            metadata,
+            targets,
+            leaves,
+            merkle_root: tree.root(),
+            proofs,
+            signatures: vec![],
+            leaf_metadata: vec![],
+        };
+        bundle.verify()?;
+        Ok(bundle)
+    }
+
+    /// Reads and [verifies](Self::verify) a bundle.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SdkError> {
+        let json = fs::read_to_string(path).map_err(|error| SdkError::Io(error.to_string()))?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SdkError> {
+        fs::write(path, self.to_json()?).map_err(|error| SdkError::Io(error.to_string()))
+    }
+
+    /// Parses and [verifies](Self::verify) a bundle.
+    pub fn from_json(json: &str) -> Result<Self, SdkError> {
+        let bundle: Self =
+            serde_json::from_str(json).map_err(|error| SdkError::Json(error.to_string()))?;
+        bundle.verify()?;
+        Ok(bundle)
+    }
+
+    pub fn to_json(&self) -> Result<String, SdkError> {
+        serde_json::to_string_pretty(self).map_err(|error| SdkError::Json(error.to_string()))
+    }
+
+    /// Rebuilds the tree of the leaves, so a tampered leaf, root or proof is caught before anyone
+    /// signs or executes, checks every leaf executes on a listed target, and returns the signers
+    /// of the collected signatures.
+    pub fn verify(&self) -> Result<Vec<Address>, SdkError> {
+        if self.version != PROPOSAL_BUNDLE_VERSION {
+            return Err(SdkError::UnsupportedBundleVersion(self.version));
+        }
+        let tree = self.tree()?;
+        if tree.root() != self.merkle_root {
+            return Err(SdkError::BundleRootMismatch);
+        }
+        if self.proofs.len() != self.leaves.len() {
+            return Err(SdkError::BundleProofMismatch(self.proofs.len().min(self.leaves.len())));
+        }
+        for (index, proof) in self.proofs.iter().enumerate() {
+            if tree.proof(index)? != proof.proof {
+                return Err(SdkError::BundleProofMismatch(index));
+            }
+        }
+        if !self.leaf_metadata.is_empty() && self.leaf_metadata.len() != self.leaves.len() {
+            return Err(SdkError::BundleLeafMetadataMismatch(self.leaf_metadata.len()));
+        }
+        for (index, leaf) in self.leaves.iter().enumerate() {
+            let (one_sig_id, address) = leaf.target();
+            let targeted = self.targets.iter().any(|target| {
+                target.one_sig_id == one_sig_id && target.address.eq_ignore_ascii_case(&address)
+            });
+            if !targeted {
+                return Err(SdkError::BundleUnknownTarget(index));
+            }
+        }
+        self.signers()
+    }
+
+    pub fn tree(&self) -> Result<OneSigTree, SdkError> {
+        OneSigTree::new(self.leaves.iter().cloned())
+    }
+
+    /// Unix time before which executors hold leaf `index`, if it is scheduled: by its metadata, or
+    /// by the not-before timestamp a Solana leaf signs, the later of the two
+    pub fn not_before(&self, index: usize) -> Option<u64> {
+        let signed = match self.leaves.get(index)? {
+            Leaf::Solana(leaf) => leaf.not_before.map(|not_before| not_before.max(0) as u64),
+            Leaf::Evm(_) => None,
+        };
+        let held = self.leaf_metadata.get(index).and_then(|metadata| metadata.not_before);
+        held.max(signed)
+    }
+
+    /// EIP-712 message signers sign to approve the bundle, e.g. with a [`RootSigner`]
+    ///
+    /// [`RootSigner`]: crate::signer::RootSigner
+    pub fn message(&self) -> Eip712Message {
+        let ProposalMetadata { seed, expiry, max_executions, .. } = &self.metadata;
+        merkle_root_message(seed, &self.merkle_root, *expiry, *max_executions)
+    }
+
+    /// Digest of [`Self::message`]
+    pub fn digest(&self) -> Hash {
+        self.message().digest()
+    }
+
+    /// Adds a signature of [`Self::digest`], returning its signer.
+    pub fn add_signature(&mut self, signature: &Signature) -> Result<Address, SdkError> {
+        let signature = normalize_recovery_id(signature)?;
+        let signer = recover_signer(&self.digest(), &signature).ok_or(SdkError::FailedRecovery)?;
+        if self.signers()?.contains(&signer) {
+            return Err(SdkError::DuplicateSigner);
+        }
+        self.signatures.push(signature);
+        Ok(signer)
+    }
+
+    /// Signers of the collected signatures
+    pub fn signers(&self) -> Result<Vec<Address>, SdkError> {
+        let digest = self.digest();
+        let mut seen = HashSet::new();
+        self.signatures
+            .iter()
+            .map(|signature| {
+                let signer = recover_signer(&digest, signature).ok_or(SdkError::FailedRecovery)?;
+                if !seen.insert(signer) {
+                    return Err(SdkError::DuplicateSigner);
+                }
+                Ok(signer)
+            })
+            .collect()
+    }
+}
+
+impl Leaf {
+    /// The OneSig id and address of the instance the leaf executes on, as [`ProposalTarget`]s
+    /// write them.
+    fn target(&self) -> (u64, String) {
+        match self {
+            Leaf::Solana(leaf) => (leaf.one_sig_id, leaf.one_sig_state.to_string()),
+            Leaf::Evm(leaf) => (leaf.one_sig_id, hex::encode(&leaf.one_sig_address)),
+        }
+    }
+}
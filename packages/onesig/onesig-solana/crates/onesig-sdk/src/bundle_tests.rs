@@ -0,0 +1,214 @@
+// Tests for the proposal bundle format: JSON round trips, integrity checks and signature
+// collection.
+#[cfg(test)]
+mod tests {
+    use onesig_core::{Hash, SIGNATURE_BYTES_LEN};
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        bundle::{
+            LeafMetadata, ProposalBundle, ProposalMetadata, ProposalTarget, PROPOSAL_BUNDLE_VERSION,
+        },
+        error::SdkError,
+        leaf::{
+            EvmCall, EvmLeaf, Leaf, OneSigAccountMeta, OneSigInstruction, SolanaLeaf,
+            TokenOutflowLimit,
+        },
+        signer::{LocalSigner, RootSigner},
+    };
+
+    const ONE_SIG_STATE: Pubkey = Pubkey::new_from_array([0x51; 32]);
+    const ONE_SIG_ADDRESS: [u8; 20] = [0xe1; 20];
+
+    fn solana_leaf(nonce: u64) -> Leaf {
+        SolanaLeaf {
+            one_sig_state: ONE_SIG_STATE,
+            one_sig_id: 1,
+            nonce,
+            instruction: OneSigInstruction {
+                program_id: Pubkey::new_from_array([0x77; 32]),
+                accounts: vec![OneSigAccountMeta {
+                    pubkey: Pubkey::new_from_array([0x78; 32]),
+                    is_signer: false,
+                    is_writable: true,
+                }],
+                data: vec![1, 2, 3],
+                value: 5,
+            },
+            token_outflow_limits: vec![TokenOutflowLimit {
+                mint: Pubkey::new_from_array([0x79; 32]),
+                max_amount: u64::MAX,
+            }],
+            guarded_accounts: vec![Pubkey::new_from_array([0x7a; 32])],
+            vault_index: 2,
+            cluster_genesis_hash: Some(Hash([0x7b; 32])),
+            group: None,
+            not_before: None,
+            bounty: None,
+        }
+        .into()
+    }
+
+    fn evm_leaf(nonce: u64) -> Leaf {
+        EvmLeaf {
+            one_sig_address: ONE_SIG_ADDRESS,
+            one_sig_id: 2,
+            nonce,
+            calls: vec![EvmCall { to: [0x22; 20], value: u128::MAX, data: vec![0xab] }],
+        }
+        .into()
+    }
+
+    fn bundle() -> ProposalBundle {
+        ProposalBundle::new(
+            ProposalMetadata {
+                title: "Rotate the executors".into(),
+                description: String::new(),
+                seed: Hash([0x5e; 32]),
+                expiry: 4_102_444_800,
+                max_executions: 0,
+            },
+            vec![
+                ProposalTarget {
+                    chain_id: "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".into(),
+                    one_sig_id: 1,
+                    address: ONE_SIG_STATE.to_string(),
+                },
+                ProposalTarget {
+                    chain_id: "eip155:1".into(),
+                    one_sig_id: 2,
+                    address: format!("0x{}", "E1".repeat(20)),
+                },
+            ],
+            vec![solana_leaf(0), evm_leaf(0), solana_leaf(1)],
+        )
+        .unwrap()
+    }
+
+    fn signers() -> Vec<LocalSigner> {
+        onesig_test_vectors::load()
+            .signers
+            .iter()
+            .map(|signer| LocalSigner::from_bytes(&signer.private_key).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut bundle = bundle();
+        let message = bundle.tree().unwrap().message(&bundle.metadata.seed, 4_102_444_800, 0);
+        for signer in signers() {
+            bundle.add_signature(&signer.sign(&message).unwrap()).unwrap();
+        }
+
+        let json = bundle.to_json().unwrap();
+        let parsed = ProposalBundle::from_json(&json).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), json);
+        assert!(parsed.merkle_root == bundle.tree().unwrap().root()); // `Hash` is not `Debug`
+        assert_eq!(parsed.verify().unwrap().len(), signers().len());
+
+        // Amounts beyond JSON's safe integers survive the round trip
+        let Leaf::Evm(leaf) = &parsed.leaves[1] else { panic!("an EVM leaf") };
+        assert_eq!(leaf.calls[0].value, u128::MAX);
+        assert!(json.contains(&format!("\"{}\"", u128::MAX)));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let bundle = bundle();
+        let path = std::env::temp_dir().join(format!("onesig-bundle-{}.json", std::process::id()));
+        bundle.save(&path).unwrap();
+        let loaded = ProposalBundle::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().to_json(), bundle.to_json());
+
+        assert!(matches!(ProposalBundle::load(&path), Err(SdkError::Io(_))));
+    }
+
+    #[test]
+    fn test_integrity_checks() {
+        let mut tampered = bundle();
+        tampered.version = PROPOSAL_BUNDLE_VERSION + 1;
+        assert_eq!(
+            tampered.verify(),
+            Err(SdkError::UnsupportedBundleVersion(PROPOSAL_BUNDLE_VERSION + 1))
+        );
+
+        let mut tampered = bundle();
+        tampered.leaves[0] = solana_leaf(7);
+        assert_eq!(tampered.verify(), Err(SdkError::BundleRootMismatch));
+
+        let mut tampered = bundle();
+        tampered.proofs.swap(0, 2);
+        assert_eq!(tampered.verify(), Err(SdkError::BundleProofMismatch(0)));
+
+        let mut tampered = bundle();
+        tampered.proofs.pop();
+        assert_eq!(tampered.verify(), Err(SdkError::BundleProofMismatch(2)));
+
+        let mut tampered = bundle();
+        tampered.leaf_metadata = vec![LeafMetadata::default()];
+        assert_eq!(tampered.verify(), Err(SdkError::BundleLeafMetadataMismatch(1)));
+
+        let mut tampered = bundle();
+        tampered.targets.remove(1);
+        assert_eq!(tampered.verify(), Err(SdkError::BundleUnknownTarget(1)));
+
+        // Tampering is caught on load too
+        let json = tampered.to_json().unwrap();
+        assert!(matches!(ProposalBundle::from_json(&json), Err(SdkError::BundleUnknownTarget(1))));
+        assert!(matches!(ProposalBundle::from_json("{}"), Err(SdkError::Json(_))));
+    }
+
+    #[test]
+    fn test_leaf_metadata() {
+        let mut bundle = bundle();
+        assert!(!bundle.to_json().unwrap().contains("leafMetadata"));
+        assert_eq!(bundle.not_before(0), None);
+
+        let scheduled = LeafMetadata { not_before: Some(1_700_003_600) };
+        bundle.leaf_metadata = vec![LeafMetadata::default(), scheduled.clone(), scheduled];
+        let parsed = ProposalBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.leaf_metadata, bundle.leaf_metadata);
+        assert_eq!(parsed.not_before(0), None);
+        assert_eq!(parsed.not_before(2), Some(1_700_003_600));
+        assert_eq!(parsed.not_before(3), None);
+    }
+
+    #[test]
+    fn test_signed_not_before() {
+        let Leaf::Solana(leaf) = solana_leaf(1) else { unreachable!() };
+        let signed = SolanaLeaf { not_before: Some(1_700_007_200), ..leaf };
+        let base = bundle();
+        let leaves = vec![solana_leaf(0), evm_leaf(0), signed.into()];
+        let mut bundle = ProposalBundle::new(base.metadata, base.targets, leaves).unwrap();
+        assert_eq!(bundle.not_before(2), Some(1_700_007_200));
+
+        // The later of the signed timestamp and the one of the metadata holds the leaf
+        let scheduled = |not_before| LeafMetadata { not_before: Some(not_before) };
+        bundle.leaf_metadata =
+            vec![LeafMetadata::default(), LeafMetadata::default(), scheduled(1_700_003_600)];
+        assert_eq!(bundle.not_before(2), Some(1_700_007_200));
+        bundle.leaf_metadata[2] = scheduled(1_700_010_800);
+        assert_eq!(bundle.not_before(2), Some(1_700_010_800));
+    }
+
+    #[test]
+    fn test_add_signature() {
+        let mut bundle = bundle();
+        let message = bundle.tree().unwrap().message(&bundle.metadata.seed, 4_102_444_800, 0);
+        let signer = &signers()[0];
+
+        // Raw 0/1 recovery ids are normalized
+        let mut signature = signer.sign(&message).unwrap();
+        signature.0[SIGNATURE_BYTES_LEN - 1] -= 27;
+        assert_eq!(bundle.add_signature(&signature), Ok(signer.address()));
+        assert_eq!(
+            bundle.signatures[0].0[SIGNATURE_BYTES_LEN - 1],
+            signature.0[SIGNATURE_BYTES_LEN - 1] + 27
+        );
+
+        assert_eq!(bundle.add_signature(&signature), Err(SdkError::DuplicateSigner));
+        assert_eq!(bundle.signers(), Ok(vec![signer.address()]));
+    }
+}
@@ -0,0 +1,521 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        sysvar,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use onesig::{
+    accounts, instruction, ExecuteTransactionParams, InitOneSigParams, OneSigTransaction,
+    RecordLeafFailureParams, SetConfigParams, SetSpendingLimitParams, VerifyMerkleRootParams,
+};
+use onesig_core::Hash;
+
+use crate::{
+    leaf::{OneSigAccountMeta, OneSigInstruction, SolanaLeaf, TokenOutflowLimit, TransactionGroup},
+    pda::OneSigPda,
+};
+
+/// Optional accounts of `execute_transaction`, which depend on the configuration of the OneSig
+/// account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecuteTransactionOptions {
+    /// Pass the `ExecutorState` PDA of the executor, required when `executor_required` is set
+    pub as_executor: bool,
+    /// Pass the `TargetPrograms` PDA, required when a target policy is set
+    pub with_target_programs: bool,
+    /// Signer sponsoring the transaction in place of the executor, paid back the executor
+    /// reimbursement
+    pub fee_payer: Option<Pubkey>,
+}
+
+/// An instruction to execute from a leaf, in the two shapes the program sees it in.
+#[derive(Clone)]
+pub struct ResolvedInstruction {
+    /// `remaining_accounts` of `execute_transaction`: the program id, then the accounts of the
+    /// instruction
+    pub remaining_accounts: Vec<AccountMeta>,
+    /// The instruction as `build_instruction` rebuilds it from those accounts, which the leaf
+    /// encodes
+    pub instruction: OneSigInstruction,
+}
+
+/// Builds the instructions of a OneSig account, deriving its PDAs and the event CPI accounts.
+///
+/// Instructions are returned unsigned; sending them is left to the caller's RPC client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OneSigClient {
+    pub pda: OneSigPda,
+}
+
+impl OneSigClient {
+    /// Client of the OneSig account `state` of the program this crate was built for.
+    pub fn new(state: Pubkey) -> Self {
+        Self::with_program_id(onesig::ID, state)
+    }
+
+    /// Client of a deployment of the program at another address.
+    pub fn with_program_id(program_id: Pubkey, state: Pubkey) -> Self {
+        Self { pda: OneSigPda::new(program_id, state) }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.pda.program_id
+    }
+
+    pub fn state(&self) -> Pubkey {
+        self.pda.state
+    }
+
+    /// `init_one_sig`, creating the state account, which must sign, and the `ExecutorState` of
+    /// each executor.
+    pub fn init_one_sig(&self, payer: &Pubkey, params: InitOneSigParams) -> Instruction {
+        let mut accounts = accounts::InitOneSig {
+            payer: *payer,
+            state: self.state(),
+            merkle_root_registry: self.pda.merkle_root_registry().0,
+            spending_limits: self.pda.spending_limits().0,
+            target_programs: self.pda.target_programs().0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            params
+                .executors
+                .iter()
+                .map(|executor| AccountMeta::new(self.pda.executor_state(executor).0, false)),
+        );
+        self.instruction(accounts, instruction::InitOneSig { params })
+    }
+
+    /// `set_config`, signed by the `one_sig_signer` PDA: the instruction of a leaf, executed by
+    /// `execute_transaction`.
+    pub fn set_config(&self, params: SetConfigParams) -> Instruction {
+        let target_programs = matches!(params, SetConfigParams::SetTargetPrograms(_))
+            .then(|| self.pda.target_programs().0);
+        let spending_limits = matches!(params, SetConfigParams::SetExecutorReimbursement(_))
+            .then(|| self.pda.spending_limits().0);
+        let accounts = accounts::SetConfig {
+            one_sig_signer: self.pda.one_sig_signer().0,
+            state: self.state(),
+            target_programs,
+            spending_limits,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::SetConfig { params })
+    }
+
+    /// `add_executor` as a leaf: the `one_sig_signer` PDA authorizes it and pays the rent of the
+    /// `ExecutorState`, which the `value` the leaf is resolved with must cover.
+    pub fn add_executor(&self, executor: &Pubkey) -> Instruction {
+        let one_sig_signer = self.pda.one_sig_signer().0;
+        let accounts = accounts::AddExecutor {
+            one_sig_signer,
+            payer: one_sig_signer,
+            state: self.state(),
+            executor_state: self.pda.executor_state(executor).0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::AddExecutor { executor: *executor })
+    }
+
+    /// `set_spending_limit` as a leaf, the `one_sig_signer` PDA paying the rent of a new entry
+    /// from the `value` of the leaf.
+    pub fn set_spending_limit(&self, params: SetSpendingLimitParams) -> Instruction {
+        let one_sig_signer = self.pda.one_sig_signer().0;
+        let accounts = accounts::SetSpendingLimit {
+            one_sig_signer,
+            payer: one_sig_signer,
+            state: self.state(),
+            spending_limits: self.pda.spending_limits().0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::SetSpendingLimit { params })
+    }
+
+    /// `verify_merkle_root`, recording the root in its `MerkleRootState`.
+    pub fn verify_merkle_root(
+        &self,
+        payer: &Pubkey,
+        params: VerifyMerkleRootParams,
+    ) -> Instruction {
+        let accounts = accounts::VerifyMerkleRoot {
+            payer: *payer,
+            merkle_root_state: self.pda.merkle_root_state(&params.merkle_root).0,
+            one_sig_state: self.state(),
+            merkle_root_registry: self.pda.merkle_root_registry().0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::VerifyMerkleRoot { params })
+    }
+
+    /// `execute_transaction` of a leaf of `merkle_root`, verified inline when `params` carry the
+    /// signatures or else by its `MerkleRootState`.
+    ///
+    /// `remaining_accounts` are the ones of the executed instruction, its program id first (see
+    /// [`Self::resolve_instruction`]); the signer flags are cleared, as the vault signer PDA signs
+    /// through the program.
+    pub fn execute_transaction(
+        &self,
+        executor: &Pubkey,
+        merkle_root: &Hash,
+        params: ExecuteTransactionParams,
+        options: ExecuteTransactionOptions,
+        remaining_accounts: impl IntoIterator<Item = AccountMeta>,
+    ) -> Instruction {
+        let merkle_root_state = params
+            .merkle_root_verification
+            .is_none()
+            .then(|| self.pda.merkle_root_state(merkle_root).0);
+        let mut accounts = accounts::ExecuteTransaction {
+            executor: *executor,
+            one_sig_signer: self.pda.vault_signer(params.transaction.vault_index).0,
+            one_sig_state: self.state(),
+            spending_limits: self.pda.spending_limits().0,
+            merkle_root_state,
+            target_programs: options.with_target_programs.then(|| self.pda.target_programs().0),
+            executor_state: options.as_executor.then(|| self.pda.executor_state(executor).0),
+            instructions_sysvar: params
+                .transaction
+                .group
+                .is_some()
+                .then_some(sysvar::instructions::ID),
+            fee_payer: options.fee_payer,
+            bounty_escrow: params.transaction.bounty.is_some().then(|| self.pda.bounty_escrow().0),
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            remaining_accounts
+                .into_iter()
+                .map(|account| AccountMeta { is_signer: false, ..account }),
+        );
+        self.instruction(accounts, instruction::ExecuteTransaction { params })
+    }
+
+    /// `execute_transaction` of `leaf`, with its `proof` under `merkle_root`: the transaction and
+    /// the remaining accounts are the ones the leaf encodes.
+    pub fn execute_leaf(
+        &self,
+        executor: &Pubkey,
+        merkle_root: &Hash,
+        leaf: &SolanaLeaf,
+        proof: Vec<Hash>,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+        options: ExecuteTransactionOptions,
+    ) -> Instruction {
+        let (transaction, remaining_accounts) = leaf_transaction(leaf, proof);
+        self.execute_transaction(
+            executor,
+            merkle_root,
+            ExecuteTransactionParams { transaction, merkle_root_verification },
+            options,
+            remaining_accounts,
+        )
+    }
+
+    /// `check_transaction` of the `execute_transaction` built from the same arguments; simulated,
+    /// its return data is a `TransactionCheckResult`. Nothing signs it, `executor` included.
+    pub fn check_transaction(
+        &self,
+        executor: &Pubkey,
+        merkle_root: &Hash,
+        params: ExecuteTransactionParams,
+        options: ExecuteTransactionOptions,
+        remaining_accounts: impl IntoIterator<Item = AccountMeta>,
+    ) -> Instruction {
+        let merkle_root_state = params
+            .merkle_root_verification
+            .is_none()
+            .then(|| self.pda.merkle_root_state(merkle_root).0);
+        let mut accounts = accounts::CheckTransaction {
+            executor: *executor,
+            one_sig_signer: self.pda.vault_signer(params.transaction.vault_index).0,
+            one_sig_state: self.state(),
+            merkle_root_state,
+            target_programs: options.with_target_programs.then(|| self.pda.target_programs().0),
+            executor_state: options.as_executor.then(|| self.pda.executor_state(executor).0),
+        }
+        .to_account_metas(None);
+        // The leaf encodes which accounts are writable, so they are passed as executed
+        accounts.extend(
+            remaining_accounts
+                .into_iter()
+                .map(|account| AccountMeta { is_signer: false, ..account }),
+        );
+        self.instruction(accounts, instruction::CheckTransaction { params })
+    }
+
+    /// `check_transaction` of `leaf`, as [`Self::execute_leaf`] builds its execution.
+    pub fn check_leaf(
+        &self,
+        executor: &Pubkey,
+        merkle_root: &Hash,
+        leaf: &SolanaLeaf,
+        proof: Vec<Hash>,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+        options: ExecuteTransactionOptions,
+    ) -> Instruction {
+        let (transaction, remaining_accounts) = leaf_transaction(leaf, proof);
+        self.check_transaction(
+            executor,
+            merkle_root,
+            ExecuteTransactionParams { transaction, merkle_root_verification },
+            options,
+            remaining_accounts,
+        )
+    }
+
+    /// `record_leaf_failure` of `leaf`, whose `execute_leaf` from the same arguments failed with
+    /// `reported_error_code` in simulation, which the program records unverified. `executor` signs,
+    /// and pays the rent of the marker.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_leaf_failure(
+        &self,
+        executor: &Pubkey,
+        merkle_root: &Hash,
+        leaf: &SolanaLeaf,
+        proof: Vec<Hash>,
+        merkle_root_verification: Option<VerifyMerkleRootParams>,
+        options: ExecuteTransactionOptions,
+        reported_error_code: u32,
+    ) -> Instruction {
+        let (transaction, remaining_accounts) = leaf_transaction(leaf, proof);
+        let leaf = leaf.hash();
+        let merkle_root_state = merkle_root_verification
+            .is_none()
+            .then(|| self.pda.merkle_root_state(merkle_root).0);
+        let mut accounts = accounts::RecordLeafFailure {
+            executor: *executor,
+            one_sig_signer: self.pda.vault_signer(transaction.vault_index).0,
+            one_sig_state: self.state(),
+            merkle_root_state,
+            target_programs: options.with_target_programs.then(|| self.pda.target_programs().0),
+            executor_state: options.as_executor.then(|| self.pda.executor_state(executor).0),
+            leaf_failure: self.pda.leaf_failure(&leaf).0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            remaining_accounts
+                .into_iter()
+                .map(|account| AccountMeta { is_signer: false, ..account }),
+        );
+        let execution = ExecuteTransactionParams { transaction, merkle_root_verification };
+        let params = RecordLeafFailureParams { execution, leaf, reported_error_code };
+        self.instruction(accounts, instruction::RecordLeafFailure { params })
+    }
+
+    /// `close_leaf_failure` of the marker of `leaf`, refunding its rent to the executor that
+    /// recorded it.
+    pub fn close_leaf_failure(&self, rent_payer: &Pubkey, leaf: &Hash) -> Instruction {
+        let accounts = accounts::CloseLeafFailure {
+            rent_payer: *rent_payer,
+            leaf_failure: self.pda.leaf_failure(leaf).0,
+            one_sig_state: self.state(),
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::CloseLeafFailure {})
+    }
+
+    /// Resolves `instruction` for execution by the signer PDA of vault `vault_index`, spending at
+    /// most `value` lamports.
+    ///
+    /// As `build_instruction` does on-chain, the vault signer is the only signer of the
+    /// instruction: its flag is set wherever it appears, and cleared on every other account.
+    pub fn resolve_instruction(
+        &self,
+        instruction: &Instruction,
+        vault_index: u8,
+        value: u64,
+    ) -> ResolvedInstruction {
+        let vault_signer = self.pda.vault_signer(vault_index).0;
+        let accounts: Vec<OneSigAccountMeta> = instruction
+            .accounts
+            .iter()
+            .map(|account| OneSigAccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.pubkey == vault_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+        let remaining_accounts =
+            std::iter::once(AccountMeta::new_readonly(instruction.program_id, false))
+                .chain(accounts.iter().map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                }))
+                .collect();
+        ResolvedInstruction {
+            remaining_accounts,
+            instruction: OneSigInstruction {
+                program_id: instruction.program_id,
+                accounts,
+                data: instruction.data.clone(),
+                value,
+            },
+        }
+    }
+
+    /// `close_merkle_root`, refunding the rent of the `MerkleRootState` to whoever paid it.
+    pub fn close_merkle_root(&self, rent_payer: &Pubkey, merkle_root: &Hash) -> Instruction {
+        let accounts = accounts::CloseMerkleRoot {
+            rent_payer: *rent_payer,
+            merkle_root_state: self.pda.merkle_root_state(merkle_root).0,
+            one_sig_state: self.state(),
+            merkle_root_registry: self.pda.merkle_root_registry().0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::CloseMerkleRoot {})
+    }
+
+    /// `withdraw_bounty_escrow` as a leaf, moving `lamports` from the bounty escrow to `recipient`.
+    pub fn withdraw_bounty_escrow(&self, recipient: &Pubkey, lamports: u64) -> Instruction {
+        let accounts = accounts::WithdrawBountyEscrow {
+            one_sig_signer: self.pda.one_sig_signer().0,
+            recipient: *recipient,
+            state: self.state(),
+            bounty_escrow: self.pda.bounty_escrow().0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        self.instruction(accounts, instruction::WithdrawBountyEscrow { lamports })
+    }
+
+    /// `close_one_sig` as a leaf, sending the rent of the accounts it closes, and what is left in
+    /// the bounty escrow, to `recipient`.
+    /// `used_vaults` are the vaults other than vault 0 whose signer PDA has signed for the
+    /// account, in ascending order, as `OneSigState::used_vaults` lists them.
+    pub fn close_one_sig(&self, recipient: &Pubkey, used_vaults: &[u8]) -> Instruction {
+        let mut accounts = accounts::CloseOneSig {
+            one_sig_signer: self.pda.one_sig_signer().0,
+            recipient: *recipient,
+            state: self.state(),
+            merkle_root_registry: self.pda.merkle_root_registry().0,
+            spending_limits: self.pda.spending_limits().0,
+            target_programs: self.pda.target_programs().0,
+            bounty_escrow: self.pda.bounty_escrow().0,
+            system_program: system_program::ID,
+            event_authority: self.pda.event_authority().0,
+            program: self.program_id(),
+        }
+        .to_account_metas(None);
+        accounts.extend(used_vaults.iter().map(|vault_index| {
+            AccountMeta::new_readonly(self.pda.vault_signer(*vault_index).0, false)
+        }));
+        self.instruction(accounts, instruction::CloseOneSig {})
+    }
+
+    /// `migrate_state`, converting the state account from the layout of the first release, with
+    /// `executors` its executors in the order it lists them, then the roots of `merkle_roots`.
+    /// `executors` is empty for an account already in the current layout.
+    pub fn migrate_state(
+        &self,
+        payer: &Pubkey,
+        executors: &[Pubkey],
+        merkle_roots: &[Hash],
+    ) -> Instruction {
+        let mut accounts = accounts::MigrateState {
+            payer: *payer,
+            state: self.state(),
+            merkle_root_registry: self.pda.merkle_root_registry().0,
+            spending_limits: self.pda.spending_limits().0,
+            target_programs: self.pda.target_programs().0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            executors
+                .iter()
+                .map(|executor| AccountMeta::new(self.pda.executor_state(executor).0, false)),
+        );
+        accounts.extend(
+            merkle_roots
+                .iter()
+                .map(|root| AccountMeta::new(self.pda.merkle_root_state(root).0, false)),
+        );
+        self.instruction(accounts, instruction::MigrateState {})
+    }
+
+    /// Accounts every `execute_transaction` of vault `vault_index` passes whatever the leaf, then
+    /// the programs of `targets`, to hold in a lookup table of the instance. The program itself is
+    /// left out: invoked programs cannot be loaded from tables.
+    pub fn lookup_table_addresses(&self, vault_index: u8, targets: &[Pubkey]) -> Vec<Pubkey> {
+        let mut addresses = vec![
+            self.state(),
+            self.pda.vault_signer(vault_index).0,
+            self.pda.spending_limits().0,
+            self.pda.target_programs().0,
+            self.pda.event_authority().0,
+            system_program::ID,
+        ];
+        for target in targets {
+            if !addresses.contains(target) {
+                addresses.push(*target);
+            }
+        }
+        addresses
+    }
+
+    fn instruction(&self, accounts: Vec<AccountMeta>, data: impl InstructionData) -> Instruction {
+        Instruction { program_id: self.program_id(), accounts, data: data.data() }
+    }
+}
+
+/// The transaction executing `leaf` with its `proof`, and the remaining accounts it is executed
+/// over: the program the leaf calls, then the accounts of its instruction.
+fn leaf_transaction(leaf: &SolanaLeaf, proof: Vec<Hash>) -> (OneSigTransaction, Vec<AccountMeta>) {
+    let instruction = &leaf.instruction;
+    let transaction = OneSigTransaction {
+        ix_data: instruction.data.clone(),
+        value: instruction.value,
+        token_outflow_limits: leaf.token_outflow_limits.iter().copied().map(Into::into).collect(),
+        guarded_accounts: leaf.guarded_accounts.clone(),
+        vault_index: leaf.vault_index,
+        group: leaf.group.map(Into::into),
+        not_before: leaf.not_before,
+        bounty: leaf.bounty,
+        proof,
+    };
+    let remaining_accounts =
+        std::iter::once(AccountMeta::new_readonly(instruction.program_id, false))
+            .chain(instruction.accounts.iter().map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            }))
+            .collect();
+    (transaction, remaining_accounts)
+}
+
+impl From<TokenOutflowLimit> for onesig::TokenOutflowLimit {
+    fn from(limit: TokenOutflowLimit) -> Self {
+        Self { mint: limit.mint, max_amount: limit.max_amount }
+    }
+}
+
+impl From<TransactionGroup> for onesig::TransactionGroup {
+    fn from(group: TransactionGroup) -> Self {
+        Self { index: group.index, len: group.len }
+    }
+}
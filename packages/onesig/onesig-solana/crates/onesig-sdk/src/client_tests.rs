@@ -0,0 +1,341 @@
+// Tests for the account lists and data of the instructions the client builds.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+        system_program, Discriminator, InstructionData,
+    };
+    use onesig::{
+        instruction, ExecuteTransactionParams, InitOneSigParams, OneSigTransaction,
+        SetConfigParams, SetSpendingLimitParams, VerifyMerkleRootParams,
+    };
+    use onesig_core::Hash;
+
+    use crate::{
+        client::{ExecuteTransactionOptions, OneSigClient},
+        leaf::{SolanaLeaf, TokenOutflowLimit},
+        pda::OneSigPda,
+    };
+
+    fn client() -> OneSigClient {
+        OneSigClient::new(Pubkey::new_unique())
+    }
+
+    fn execute_params(vault_index: u8, verified: bool) -> ExecuteTransactionParams {
+        ExecuteTransactionParams {
+            transaction: OneSigTransaction {
+                ix_data: vec![],
+                value: 0,
+                token_outflow_limits: vec![],
+                guarded_accounts: vec![],
+                vault_index,
+                group: None,
+                not_before: None,
+                bounty: None,
+                proof: vec![],
+            },
+            merkle_root_verification: (!verified).then(|| VerifyMerkleRootParams {
+                merkle_root: Hash([1; 32]),
+                expiry: 0,
+                max_executions: 0,
+                signatures: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_vault_signers() {
+        let pda = OneSigPda::new(onesig::ID, Pubkey::new_unique());
+
+        assert_eq!(pda.vault_signer(0), pda.one_sig_signer());
+        assert_eq!(
+            pda.vault_signer(2).0,
+            Pubkey::find_program_address(
+                &[onesig::ONE_SIG_SEED, pda.state.as_ref(), &[2]],
+                &onesig::ID
+            )
+            .0
+        );
+        assert_eq!(
+            pda.event_authority().0,
+            Pubkey::find_program_address(&[b"__event_authority"], &onesig::ID).0
+        );
+    }
+
+    #[test]
+    fn test_init_one_sig() {
+        let client = client();
+        let payer = Pubkey::new_unique();
+        let executors = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let ix = client.init_one_sig(
+            &payer,
+            InitOneSigParams {
+                one_sig_id: 1,
+                seed: Hash([0; 32]),
+                threshold: 1,
+                signers: vec![],
+                executors: executors.clone(),
+                executor_required: true,
+                signers_capacity: 1,
+            },
+        );
+
+        assert_eq!(ix.program_id, onesig::ID);
+        assert!(ix.data.starts_with(instruction::InitOneSig::DISCRIMINATOR));
+        assert_eq!(ix.accounts[0], AccountMeta::new(payer, true));
+        assert_eq!(ix.accounts[1], AccountMeta::new(client.state(), true));
+        assert_eq!(
+            ix.accounts[6],
+            AccountMeta::new_readonly(client.pda.event_authority().0, false)
+        );
+        assert_eq!(ix.accounts[7], AccountMeta::new_readonly(onesig::ID, false));
+        let executor_states: Vec<_> = executors
+            .iter()
+            .map(|executor| AccountMeta::new(client.pda.executor_state(executor).0, false))
+            .collect();
+        assert_eq!(ix.accounts[8..], executor_states);
+    }
+
+    #[test]
+    fn test_set_config_passes_the_accounts_of_its_params() {
+        let client = client();
+        let signer = AccountMeta::new_readonly(client.pda.one_sig_signer().0, true);
+        // Absent optional accounts are passed as the program id
+        let absent = AccountMeta::new_readonly(onesig::ID, false);
+
+        let ix = client.set_config(SetConfigParams::SetThreshold(2));
+        assert_eq!(ix.accounts[0], signer);
+        assert_eq!(ix.accounts[2..4], [absent.clone(), absent.clone()]);
+
+        let ix = client.set_config(SetConfigParams::SetExecutorReimbursement(5_000));
+        assert_eq!(
+            ix.accounts[2..4],
+            [absent, AccountMeta::new(client.pda.spending_limits().0, false)]
+        );
+    }
+
+    #[test]
+    fn test_add_executor() {
+        let client = client();
+        let executor = Pubkey::new_unique();
+        let signer = client.pda.one_sig_signer().0;
+
+        let ix = client.add_executor(&executor);
+        assert_eq!(ix.data, instruction::AddExecutor { executor }.data());
+        assert_eq!(
+            ix.accounts[..2],
+            [AccountMeta::new_readonly(signer, true), AccountMeta::new(signer, true)]
+        );
+        assert_eq!(ix.accounts[3], AccountMeta::new(client.pda.executor_state(&executor).0, false));
+
+        // Resolved as a leaf, the PDA pays from the value of the leaf
+        let resolved = client.resolve_instruction(&ix, 0, 1_000_000);
+        assert!(
+            resolved.instruction.accounts[1].is_signer &&
+                resolved.instruction.accounts[1].is_writable
+        );
+    }
+
+    #[test]
+    fn test_set_spending_limit() {
+        let client = client();
+        let signer = client.pda.one_sig_signer().0;
+        let params = SetSpendingLimitParams { mint: Pubkey::default(), amount: 5, window: 60 };
+
+        let ix = client.set_spending_limit(params.clone());
+        assert_eq!(ix.data, instruction::SetSpendingLimit { params }.data());
+        assert_eq!(
+            ix.accounts[..4],
+            [
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(signer, true),
+                AccountMeta::new_readonly(client.state(), false),
+                AccountMeta::new(client.pda.spending_limits().0, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_transaction() {
+        let client = client();
+        let executor = Pubkey::new_unique();
+        let merkle_root = Hash([1; 32]);
+        let target = Pubkey::new_unique();
+        let remaining = [
+            AccountMeta::new_readonly(target, false),
+            AccountMeta::new(client.pda.vault_signer(3).0, true),
+        ];
+
+        let ix = client.execute_transaction(
+            &executor,
+            &merkle_root,
+            execute_params(3, true),
+            ExecuteTransactionOptions { as_executor: true, ..Default::default() },
+            remaining,
+        );
+        assert_eq!(ix.accounts[0], AccountMeta::new(executor, true));
+        assert_eq!(ix.accounts[1], AccountMeta::new(client.pda.vault_signer(3).0, false));
+        assert_eq!(
+            ix.accounts[4],
+            AccountMeta::new(client.pda.merkle_root_state(&merkle_root).0, false)
+        );
+        assert_eq!(ix.accounts[5], AccountMeta::new_readonly(onesig::ID, false));
+        assert_eq!(
+            ix.accounts[6],
+            AccountMeta::new_readonly(client.pda.executor_state(&executor).0, false)
+        );
+        // No instructions sysvar, fee payer or bounty escrow
+        assert!(ix.accounts[7..10].iter().all(|account| account.pubkey == onesig::ID));
+        assert_eq!(ix.accounts[10], AccountMeta::new_readonly(system_program::ID, false));
+        assert_eq!(
+            ix.accounts[13..],
+            [
+                AccountMeta::new_readonly(target, false),
+                AccountMeta::new(client.pda.vault_signer(3).0, false)
+            ]
+        );
+
+        // An inline verification needs no `MerkleRootState`
+        let ix = client.execute_transaction(
+            &executor,
+            &merkle_root,
+            execute_params(0, false),
+            ExecuteTransactionOptions::default(),
+            [],
+        );
+        assert_eq!(ix.accounts[4], AccountMeta::new_readonly(onesig::ID, false));
+        assert_eq!(ix.accounts.len(), 13);
+
+        // A leaf with a bounty is paid it from the bounty escrow
+        let mut params = execute_params(0, true);
+        params.transaction.bounty = Some(5_000);
+        let ix = client.execute_transaction(
+            &executor,
+            &merkle_root,
+            params,
+            ExecuteTransactionOptions::default(),
+            [],
+        );
+        assert_eq!(ix.accounts[9], AccountMeta::new(client.pda.bounty_escrow().0, false));
+    }
+
+    #[test]
+    fn test_execute_leaf() {
+        let client = client();
+        let executor = Pubkey::new_unique();
+        let target = Pubkey::new_unique();
+        let set_config = client.set_config(SetConfigParams::SetThreshold(2));
+        let resolved = client.resolve_instruction(&set_config, 0, 0);
+        let leaf = SolanaLeaf {
+            one_sig_state: client.state(),
+            one_sig_id: 1,
+            nonce: 0,
+            instruction: resolved.instruction,
+            token_outflow_limits: vec![TokenOutflowLimit { mint: target, max_amount: 5 }],
+            guarded_accounts: vec![target],
+            vault_index: 0,
+            cluster_genesis_hash: None,
+            group: None,
+            not_before: None,
+            bounty: None,
+        };
+        let proof = vec![Hash([2; 32])];
+
+        let ix = client.execute_leaf(
+            &executor,
+            &Hash([1; 32]),
+            &leaf,
+            proof.clone(),
+            None,
+            ExecuteTransactionOptions::default(),
+        );
+        let expected = client.execute_transaction(
+            &executor,
+            &Hash([1; 32]),
+            ExecuteTransactionParams {
+                transaction: OneSigTransaction {
+                    ix_data: set_config.data,
+                    value: 0,
+                    token_outflow_limits: vec![onesig::TokenOutflowLimit {
+                        mint: target,
+                        max_amount: 5,
+                    }],
+                    guarded_accounts: vec![target],
+                    vault_index: 0,
+                    group: None,
+                    not_before: None,
+                    bounty: None,
+                    proof,
+                },
+                merkle_root_verification: None,
+            },
+            ExecuteTransactionOptions::default(),
+            resolved.remaining_accounts,
+        );
+        assert_eq!(ix, expected);
+    }
+
+    #[test]
+    fn test_resolve_instruction() {
+        let client = client();
+        let vault_signer = client.pda.vault_signer(1).0;
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction {
+            program_id: system_program::ID,
+            accounts: vec![
+                AccountMeta::new(vault_signer, false),
+                AccountMeta::new(source, true),
+                AccountMeta::new_readonly(destination, false),
+            ],
+            data: vec![2, 0, 0, 0],
+        };
+
+        let resolved = client.resolve_instruction(&instruction, 1, 10);
+        assert_eq!(
+            resolved.remaining_accounts,
+            [
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(vault_signer, true),
+                AccountMeta::new(source, false),
+                AccountMeta::new_readonly(destination, false),
+            ]
+        );
+        assert_eq!(resolved.instruction.program_id, system_program::ID);
+        assert_eq!(resolved.instruction.data, instruction.data);
+        assert_eq!(resolved.instruction.value, 10);
+        // The leaf accounts are the remaining accounts after the program id
+        for (account, meta) in
+            resolved.instruction.accounts.iter().zip(&resolved.remaining_accounts[1..])
+        {
+            assert_eq!(
+                (account.pubkey, account.is_signer, account.is_writable),
+                (meta.pubkey, meta.is_signer, meta.is_writable)
+            );
+        }
+
+        // Through vault 0, the vault 1 signer is just another account
+        let resolved = client.resolve_instruction(&instruction, 0, 10);
+        assert_eq!(resolved.remaining_accounts[1], AccountMeta::new(vault_signer, false));
+    }
+
+    #[test]
+    fn test_close_merkle_root() {
+        let client = client();
+        let rent_payer = Pubkey::new_unique();
+        let merkle_root = Hash([1; 32]);
+
+        let ix = client.close_merkle_root(&rent_payer, &merkle_root);
+        assert_eq!(ix.data, instruction::CloseMerkleRoot::DISCRIMINATOR);
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new(rent_payer, false),
+                AccountMeta::new(client.pda.merkle_root_state(&merkle_root).0, false),
+                AccountMeta::new(client.state(), false),
+                AccountMeta::new(client.pda.merkle_root_registry().0, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// Compute units a transaction may request at most
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Accounts `getRecentPrioritizationFees` takes at most
+pub const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
+// Discriminants of the program's instructions
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// How many compute units a transaction requests
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComputeUnitLimit {
+    /// The runtime's default, 200k units per instruction
+    #[default]
+    Default,
+    Fixed(u32),
+    /// The units a simulation consumes, plus `margin_percent` of them
+    Simulated {
+        margin_percent: u32,
+    },
+}
+
+/// What a transaction pays per compute unit, in micro-lamports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComputeUnitPrice {
+    /// No priority fee
+    #[default]
+    Default,
+    Fixed(u64),
+    /// The `percentile` of the fees recently paid to lock the transaction's writable accounts,
+    /// at most `max`
+    Percentile {
+        percentile: u8,
+        max: u64,
+    },
+}
+
+/// The compute budget instructions to attach to a transaction. The default attaches none.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudgetOptions {
+    pub unit_limit: ComputeUnitLimit,
+    pub unit_price: ComputeUnitPrice,
+}
+
+impl ComputeBudgetOptions {
+    /// Requests the simulated units plus a tenth, and pays the median recent fee, capped at
+    /// `max_unit_price`.
+    pub fn estimated(max_unit_price: u64) -> Self {
+        Self {
+            unit_limit: ComputeUnitLimit::Simulated { margin_percent: 10 },
+            unit_price: ComputeUnitPrice::Percentile { percentile: 50, max: max_unit_price },
+        }
+    }
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend(units.to_le_bytes());
+    Instruction { program_id: COMPUTE_BUDGET_PROGRAM_ID, accounts: vec![], data }
+}
+
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_PRICE];
+    data.extend(micro_lamports.to_le_bytes());
+    Instruction { program_id: COMPUTE_BUDGET_PROGRAM_ID, accounts: vec![], data }
+}
+
+/// `units_consumed` plus `margin_percent` of them, within what a transaction may request
+pub fn compute_unit_limit(units_consumed: u64, margin_percent: u32) -> u32 {
+    let units = units_consumed.saturating_mul(100 + margin_percent as u64) / 100;
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// The nearest-rank `percentile` of `fees`, 0 without any
+pub fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    fees.sort_unstable();
+    let rank = (fees.len() * percentile.min(100) as usize).div_ceil(100);
+    fees.get(rank.saturating_sub(1)).copied().unwrap_or_default()
+}
+
+/// Accounts `instructions` paid by `payer` write, whose recent fees price their transaction
+pub fn writable_accounts(payer: &Pubkey, instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts = vec![*payer];
+    for account in instructions.iter().flat_map(|instruction| &instruction.accounts) {
+        if account.is_writable && !accounts.contains(&account.pubkey) {
+            accounts.push(account.pubkey);
+        }
+    }
+    accounts.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+    accounts
+}
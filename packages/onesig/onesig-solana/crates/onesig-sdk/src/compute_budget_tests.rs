@@ -0,0 +1,69 @@
+// Tests for the compute budget instructions and the estimation of limits and prices.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+    };
+
+    use crate::compute_budget::{
+        compute_unit_limit, fee_percentile, set_compute_unit_limit, set_compute_unit_price,
+        writable_accounts, COMPUTE_BUDGET_PROGRAM_ID, MAX_COMPUTE_UNIT_LIMIT,
+        MAX_PRIORITIZATION_FEE_ACCOUNTS,
+    };
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    #[test]
+    fn test_instructions() {
+        let limit = set_compute_unit_limit(300_000);
+        assert_eq!(limit.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert!(limit.accounts.is_empty());
+        assert_eq!(limit.data, [2, 0xe0, 0x93, 0x04, 0x00]);
+
+        let price = set_compute_unit_price(1_000_000);
+        assert_eq!(price.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(price.data, [3, 0x40, 0x42, 0x0f, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        assert_eq!(compute_unit_limit(100_000, 10), 110_000);
+        assert_eq!(compute_unit_limit(100_000, 0), 100_000);
+        assert_eq!(compute_unit_limit(1_300_000, 10), MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(compute_unit_limit(u64::MAX, 10), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn test_fee_percentile() {
+        let fees = vec![40, 10, 30, 20, 0];
+        assert_eq!(fee_percentile(fees.clone(), 0), 0);
+        assert_eq!(fee_percentile(fees.clone(), 50), 20);
+        assert_eq!(fee_percentile(fees.clone(), 75), 30);
+        assert_eq!(fee_percentile(fees.clone(), 100), 40);
+        assert_eq!(fee_percentile(fees, 200), 40);
+        assert_eq!(fee_percentile(vec![], 50), 0);
+    }
+
+    #[test]
+    fn test_writable_accounts() {
+        let instruction = |accounts| Instruction { program_id: key(0x90), accounts, data: vec![] };
+        let instructions = [
+            instruction(vec![
+                AccountMeta::new(key(2), false),
+                AccountMeta::new_readonly(key(3), false),
+                AccountMeta::new(key(1), true),
+            ]),
+            instruction(vec![AccountMeta::new(key(2), false), AccountMeta::new(key(4), false)]),
+        ];
+        assert_eq!(writable_accounts(&key(1), &instructions), [key(1), key(2), key(4)]);
+
+        let many: Vec<AccountMeta> = (0..=200).map(|i| AccountMeta::new(key(i), false)).collect();
+        assert_eq!(
+            writable_accounts(&key(0xff), &[instruction(many)]).len(),
+            MAX_PRIORITIZATION_FEE_ACCOUNTS
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use onesig_core::Hash;
+use serde::{Deserialize, Serialize};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    bundle::{ProposalBundle, ProposalMetadata, ProposalTarget},
+    encoding::hex,
+    error::SdkError,
+    leaf::{
+        EvmCall, EvmLeaf, Leaf, OneSigInstruction, SolanaLeaf, TokenOutflowLimit, TransactionGroup,
+        EVM_ADDRESS_LEN,
+    },
+    proof::LeafProof,
+    tree::OneSigTree,
+};
+
+/// A Solana OneSig a proposal executes on, from the nonce it is at
+#[derive(Clone, PartialEq, Eq)]
+pub struct SolanaInstance {
+    /// CAIP-2 id of the cluster
+    pub chain_id: String,
+    pub one_sig_state: Pubkey,
+    pub one_sig_id: u64,
+    /// Nonce of the next leaf the OneSig executes
+    pub nonce: u64,
+    /// Genesis hash the OneSig binds leaves to, see `OneSigState::cluster_genesis_hash`
+    pub cluster_genesis_hash: Option<Hash>,
+}
+
+/// An EVM OneSig a proposal executes on, from the nonce it is at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmInstance {
+    /// CAIP-2 id of the chain, e.g. `eip155:1`
+    pub chain_id: String,
+    pub one_sig_address: [u8; EVM_ADDRESS_LEN],
+    pub one_sig_id: u64,
+    /// `nonce()` of the contract
+    pub nonce: u64,
+}
+
+/// The guards a Solana leaf declares besides its instruction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolanaLeafGuards {
+    pub token_outflow_limits: Vec<TokenOutflowLimit>,
+    pub guarded_accounts: Vec<Pubkey>,
+    pub vault_index: u8,
+    /// Unix time before which the program refuses to execute the leaf, if any
+    pub not_before: Option<i64>,
+    /// Lamports paid from the bounty escrow to whoever executes the leaf permissionlessly, if any
+    pub bounty: Option<u64>,
+}
+
+/// Handle of an instance added to a [`TreeCoordinator`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolanaInstanceId(usize);
+
+/// Handle of an instance added to a [`TreeCoordinator`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvmInstanceId(usize);
+
+/// The leaves of one instance with their proofs, in nonce order: what its executor needs.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceProofs {
+    pub target: ProposalTarget,
+    pub leaves: Vec<Leaf>,
+    /// Proof of each leaf, in the order of `leaves`
+    pub proofs: Vec<LeafProof>,
+}
+
+/// Assembles a single tree of leaves for OneSigs on several chains.
+///
+/// Leaves are added per instance and take its nonces in order, from the one the instance is at,
+/// so each chain executes its leaves in the order they were added.
+#[derive(Clone, Default)]
+pub struct TreeCoordinator {
+    solana: Vec<SolanaInstance>,
+    evm: Vec<EvmInstance>,
+    leaves: Vec<Leaf>,
+    /// Instance of each leaf
+    instances: Vec<InstanceId>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InstanceId {
+    Solana(SolanaInstanceId),
+    Evm(EvmInstanceId),
+}
+
+impl TreeCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_solana_instance(&mut self, instance: SolanaInstance) -> SolanaInstanceId {
+        self.solana.push(instance);
+        SolanaInstanceId(self.solana.len() - 1)
+    }
+
+    pub fn add_evm_instance(&mut self, instance: EvmInstance) -> EvmInstanceId {
+        self.evm.push(instance);
+        EvmInstanceId(self.evm.len() - 1)
+    }
+
+    /// Adds a leaf executing `instruction` on the Solana instance, returning its nonce.
+    pub fn push_solana(
+        &mut self,
+        instance: SolanaInstanceId,
+        instruction: OneSigInstruction,
+        guards: SolanaLeafGuards,
+    ) -> Result<u64, SdkError> {
+        self.push_solana_leaf(instance, instruction, guards, None)
+    }
+
+    /// Adds a leaf per instruction on the Solana instance, at consecutive nonces, grouped so that
+    /// the program only executes them all in one transaction. Returns the nonce of the first.
+    pub fn push_solana_group(
+        &mut self,
+        instance: SolanaInstanceId,
+        leaves: Vec<(OneSigInstruction, SolanaLeafGuards)>,
+    ) -> Result<u64, SdkError> {
+        let len = u8::try_from(leaves.len())
+            .ok()
+            .filter(|len| *len > 0)
+            .ok_or(SdkError::TransactionGroupSize(leaves.len()))?;
+        let first =
+            self.solana.get(instance.0).ok_or(SdkError::InstanceNotFound(instance.0))?.nonce;
+        for (index, (instruction, guards)) in (0..len).zip(leaves) {
+            let group = TransactionGroup { index, len };
+            self.push_solana_leaf(instance, instruction, guards, Some(group))?;
+        }
+        Ok(first)
+    }
+
+    fn push_solana_leaf(
+        &mut self,
+        instance: SolanaInstanceId,
+        instruction: OneSigInstruction,
+        guards: SolanaLeafGuards,
+        group: Option<TransactionGroup>,
+    ) -> Result<u64, SdkError> {
+        let id = instance;
+        let instance = self.solana.get_mut(id.0).ok_or(SdkError::InstanceNotFound(id.0))?;
+        let nonce = instance.nonce;
+        instance.nonce += 1;
+        self.instances.push(InstanceId::Solana(id));
+        self.leaves.push(Leaf::Solana(SolanaLeaf {
+            one_sig_state: instance.one_sig_state,
+            one_sig_id: instance.one_sig_id,
+            nonce,
+            instruction,
+            token_outflow_limits: guards.token_outflow_limits,
+            guarded_accounts: guards.guarded_accounts,
+            vault_index: guards.vault_index,
+            cluster_genesis_hash: instance.cluster_genesis_hash,
+            group,
+            not_before: guards.not_before,
+            bounty: guards.bounty,
+        }));
+        Ok(nonce)
+    }
+
+    /// Adds a leaf executing `calls` on the EVM instance, returning its nonce.
+    pub fn push_evm(
+        &mut self,
+        instance: EvmInstanceId,
+        calls: Vec<EvmCall>,
+    ) -> Result<u64, SdkError> {
+        let id = instance;
+        let instance = self.evm.get_mut(id.0).ok_or(SdkError::InstanceNotFound(id.0))?;
+        let nonce = instance.nonce;
+        instance.nonce += 1;
+        self.instances.push(InstanceId::Evm(id));
+        self.leaves.push(Leaf::Evm(EvmLeaf {
+            one_sig_address: instance.one_sig_address,
+            one_sig_id: instance.one_sig_id,
+            nonce,
+            calls,
+        }));
+        Ok(nonce)
+    }
+
+    /// Leaves added so far, in the order they were added
+    pub fn leaves(&self) -> &[Leaf] {
+        &self.leaves
+    }
+
+    /// Instances as proposal targets, the Solana ones first
+    pub fn targets(&self) -> Vec<ProposalTarget> {
+        let solana = self.solana.iter().map(|instance| ProposalTarget {
+            chain_id: instance.chain_id.clone(),
+            one_sig_id: instance.one_sig_id,
+            address: instance.one_sig_state.to_string(),
+        });
+        let evm = self.evm.iter().map(|instance| ProposalTarget {
+            chain_id: instance.chain_id.clone(),
+            one_sig_id: instance.one_sig_id,
+            address: hex::encode(&instance.one_sig_address),
+        });
+        solana.chain(evm).collect()
+    }
+
+    pub fn tree(&self) -> Result<OneSigTree, SdkError> {
+        OneSigTree::new(self.leaves.iter().cloned())
+    }
+
+    /// The leaves of each instance with their proofs, in the order of [`Self::targets`]
+    pub fn instance_proofs(&self) -> Result<Vec<InstanceProofs>, SdkError> {
+        let tree = self.tree()?;
+        let ids = (0..self.solana.len())
+            .map(|index| InstanceId::Solana(SolanaInstanceId(index)))
+            .chain((0..self.evm.len()).map(|index| InstanceId::Evm(EvmInstanceId(index))));
+        ids.zip(self.targets())
+            .map(|(id, target)| {
+                let mut leaves = vec![];
+                let mut proofs = vec![];
+                for (index, leaf) in self.leaves.iter().enumerate() {
+                    if self.instances[index] == id {
+                        leaves.push(leaf.clone());
+                        proofs.push(tree.leaf_proof(index)?);
+                    }
+                }
+                Ok(InstanceProofs { target, leaves, proofs })
+            })
+            .collect()
+    }
+
+    /// Bundles the leaves for every instance, ready to collect signatures.
+    pub fn bundle(&self, metadata: ProposalMetadata) -> Result<ProposalBundle, SdkError> {
+        ProposalBundle::new(metadata, self.targets(), self.leaves.clone())
+    }
+}
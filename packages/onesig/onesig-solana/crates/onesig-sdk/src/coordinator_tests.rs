@@ -0,0 +1,190 @@
+// Tests for assembling a tree across Solana and EVM OneSigs.
+#[cfg(test)]
+mod tests {
+    use onesig_core::Hash;
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        bundle::ProposalMetadata,
+        coordinator::{
+            EvmInstance, InstanceProofs, SolanaInstance, SolanaLeafGuards, TreeCoordinator,
+        },
+        error::SdkError,
+        leaf::{EvmCall, EvmLeaf, Leaf, OneSigInstruction, TokenOutflowLimit, TransactionGroup},
+    };
+
+    const STATE: Pubkey = Pubkey::new_from_array([0x51; 32]);
+
+    fn solana_instance() -> SolanaInstance {
+        SolanaInstance {
+            chain_id: "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".into(),
+            one_sig_state: STATE,
+            one_sig_id: 30_168,
+            nonce: 7,
+            cluster_genesis_hash: Some(Hash([0x9e; 32])),
+        }
+    }
+
+    fn evm_instance() -> EvmInstance {
+        EvmInstance {
+            chain_id: "eip155:1".into(),
+            one_sig_address: [0xab; 20],
+            one_sig_id: 30_101,
+            nonce: 2,
+        }
+    }
+
+    fn instruction(data: u8) -> OneSigInstruction {
+        OneSigInstruction {
+            program_id: Pubkey::new_from_array([0x0f; 32]),
+            accounts: vec![],
+            data: vec![data],
+            value: 0,
+        }
+    }
+
+    fn call(data: u8) -> EvmCall {
+        EvmCall { to: [0xcd; 20], value: 1, data: vec![data] }
+    }
+
+    fn coordinator() -> TreeCoordinator {
+        let mut coordinator = TreeCoordinator::new();
+        let evm = coordinator.add_evm_instance(evm_instance());
+        let solana = coordinator.add_solana_instance(solana_instance());
+        let guards = SolanaLeafGuards {
+            token_outflow_limits: vec![TokenOutflowLimit {
+                mint: Pubkey::default(),
+                max_amount: 5,
+            }],
+            guarded_accounts: vec![Pubkey::new_from_array([0x6a; 32])],
+            vault_index: 1,
+            not_before: None,
+            bounty: None,
+        };
+        assert_eq!(coordinator.push_solana(solana, instruction(1), guards), Ok(7));
+        assert_eq!(coordinator.push_evm(evm, vec![call(1), call(2)]), Ok(2));
+        assert_eq!(coordinator.push_solana(solana, instruction(2), Default::default()), Ok(8));
+        coordinator
+    }
+
+    #[test]
+    fn test_leaves() {
+        let coordinator = coordinator();
+        let leaves = coordinator.leaves();
+        assert_eq!(leaves.len(), 3);
+
+        let Leaf::Solana(first) = &leaves[0] else { panic!("not a Solana leaf") };
+        assert_eq!((first.one_sig_state, first.one_sig_id, first.nonce), (STATE, 30_168, 7));
+        assert_eq!(first.instruction, instruction(1));
+        assert_eq!(first.vault_index, 1);
+        assert!(first.cluster_genesis_hash == Some(Hash([0x9e; 32])));
+
+        // EVM leaves hash as `OneSig.encodeLeaf` does
+        let evm = EvmLeaf {
+            one_sig_address: [0xab; 20],
+            one_sig_id: 30_101,
+            nonce: 2,
+            calls: vec![call(1), call(2)],
+        };
+        assert!(leaves[1].hash() == evm.hash());
+    }
+
+    #[test]
+    fn test_solana_group() {
+        let mut coordinator = TreeCoordinator::new();
+        let solana = coordinator.add_solana_instance(solana_instance());
+        coordinator.push_solana(solana, instruction(1), Default::default()).unwrap();
+        let leaves =
+            vec![(instruction(2), Default::default()), (instruction(3), Default::default())];
+        assert_eq!(coordinator.push_solana_group(solana, leaves), Ok(8));
+        assert_eq!(coordinator.push_solana(solana, instruction(4), Default::default()), Ok(10));
+
+        let groups: Vec<_> = coordinator
+            .leaves()
+            .iter()
+            .map(|leaf| match leaf {
+                Leaf::Solana(leaf) => leaf.group,
+                Leaf::Evm(_) => panic!("not a Solana leaf"),
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                None,
+                Some(TransactionGroup { index: 0, len: 2 }),
+                Some(TransactionGroup { index: 1, len: 2 }),
+                None
+            ]
+        );
+
+        assert_eq!(
+            coordinator.push_solana_group(solana, vec![]),
+            Err(SdkError::TransactionGroupSize(0))
+        );
+        let leaves = vec![(instruction(5), Default::default()); 256];
+        assert_eq!(
+            coordinator.push_solana_group(solana, leaves),
+            Err(SdkError::TransactionGroupSize(256))
+        );
+        assert_eq!(coordinator.leaves().len(), 4);
+    }
+
+    #[test]
+    fn test_instance_proofs() {
+        let coordinator = coordinator();
+        let root = coordinator.tree().unwrap().root();
+        let instance_proofs = coordinator.instance_proofs().unwrap();
+        assert_eq!(instance_proofs.len(), 2);
+
+        // Solana instances come first, whatever the order they were added in
+        let solana = &instance_proofs[0];
+        assert_eq!(solana.target.address, STATE.to_string());
+        assert_eq!(solana.leaves.iter().map(Leaf::nonce).collect::<Vec<_>>(), [7, 8]);
+        let evm = &instance_proofs[1];
+        assert_eq!(evm.target.chain_id, "eip155:1");
+        assert_eq!(evm.target.address, format!("0x{}", "ab".repeat(20)));
+        assert_eq!(evm.leaves.iter().map(Leaf::nonce).collect::<Vec<_>>(), [2]);
+
+        for InstanceProofs { leaves, proofs, .. } in &instance_proofs {
+            for (leaf, proof) in leaves.iter().zip(proofs) {
+                assert!(proof.merkle_root == root);
+                assert!(proof.leaf == leaf.hash());
+                assert!(proof.verify());
+            }
+        }
+
+        let json = serde_json::to_string(solana).unwrap();
+        assert!(serde_json::from_str::<InstanceProofs>(&json).unwrap() == *solana);
+    }
+
+    #[test]
+    fn test_bundle() {
+        let coordinator = coordinator();
+        let metadata = ProposalMetadata {
+            title: "Cross-chain".into(),
+            description: String::new(),
+            seed: Hash([0x5e; 32]),
+            expiry: 1_700_000_000,
+            max_executions: 0,
+        };
+        let bundle = coordinator.bundle(metadata).unwrap();
+        assert_eq!(bundle.targets, coordinator.targets());
+        assert!(bundle.merkle_root == coordinator.tree().unwrap().root());
+        assert_eq!(bundle.verify(), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_instance_not_found() {
+        let mut other = TreeCoordinator::new();
+        other.add_solana_instance(solana_instance());
+        let second = other.add_solana_instance(solana_instance());
+
+        let mut coordinator = TreeCoordinator::new();
+        coordinator.add_solana_instance(solana_instance());
+        assert_eq!(
+            coordinator.push_solana(second, instruction(1), Default::default()),
+            Err(SdkError::InstanceNotFound(1))
+        );
+        assert!(coordinator.leaves().is_empty());
+    }
+}
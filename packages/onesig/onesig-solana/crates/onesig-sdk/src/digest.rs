@@ -0,0 +1,101 @@
+#[cfg(feature = "client")]
+use onesig::{merkle::MerkleValidator, state::Multisig};
+#[cfg(feature = "client")]
+use onesig_core::Address;
+use onesig_core::{
+    digest::{merkle_root_struct_hash, signer_execution_authorization_struct_hash},
+    Hash, DOMAIN_SEPARATOR, EIP191_PREFIX_FOR_EIP712,
+};
+use solana_keccak_hasher as keccak;
+use solana_pubkey::Pubkey;
+
+#[cfg(feature = "client")]
+use crate::error::SdkError;
+
+/// An EIP-712 message: signers sign `keccak256(0x1901 ‖ domain_separator ‖ struct_hash)`.
+///
+/// Software signers only need the digest, but hardware wallets hash it themselves from the two
+/// halves, which they can show for review.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Eip712Message {
+    pub domain_separator: Hash,
+    pub struct_hash: Hash,
+}
+
+impl Eip712Message {
+    pub fn digest(&self) -> Hash {
+        keccak::hashv(&[
+            &EIP191_PREFIX_FOR_EIP712,
+            self.domain_separator.as_ref(),
+            self.struct_hash.as_ref(),
+        ])
+        .into()
+    }
+}
+
+/// The `SignMerkleRoot` message approving `merkle_root`, or the `SignLimitedMerkleRoot` one
+/// when `max_executions` is non-zero: exactly what `verify_merkle_root` recovers signers from.
+pub fn merkle_root_message(
+    seed: &Hash,
+    merkle_root: &Hash,
+    expiry: u64,
+    max_executions: u32,
+) -> Eip712Message {
+    Eip712Message {
+        domain_separator: Hash(DOMAIN_SEPARATOR),
+        struct_hash: merkle_root_struct_hash(seed, merkle_root, expiry, max_executions),
+    }
+}
+
+/// The `SignerExecutionAuthorization` message letting `delegate` execute `leaf` of `merkle_root`
+/// through `signer_execute_transaction` until `expiry`.
+pub fn signer_execution_authorization_message(
+    leaf: &Hash,
+    merkle_root: &Hash,
+    delegate: &Pubkey,
+    expiry: u64,
+) -> Eip712Message {
+    Eip712Message {
+        domain_separator: Hash(DOMAIN_SEPARATOR),
+        struct_hash: signer_execution_authorization_struct_hash(
+            leaf,
+            merkle_root,
+            delegate.as_ref(),
+            expiry,
+        ),
+    }
+}
+
+/// Checks `signatures` of `merkle_root` as `verify_merkle_root` does at `now`, returning the
+/// signers they recover to, so a root can be checked before it is submitted.
+#[cfg(feature = "client")]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_merkle_root_signatures(
+    seed: &Hash,
+    signers: &[Address],
+    threshold: u8,
+    merkle_root: &Hash,
+    expiry: i64,
+    max_executions: u32,
+    signatures: &[u8],
+    now: i64,
+) -> Result<Vec<Address>, SdkError> {
+    MerkleValidator::verify_merkle_root(
+        seed,
+        &Multisig { signers, threshold },
+        merkle_root,
+        expiry,
+        max_executions,
+        signatures,
+        now,
+    )
+    .map_err(|error| SdkError::Rejected(error_name(error)))
+}
+
+#[cfg(feature = "client")]
+fn error_name(error: anchor_lang::error::Error) -> String {
+    match error {
+        anchor_lang::error::Error::AnchorError(error) => error.error_name,
+        anchor_lang::error::Error::ProgramError(error) => error.program_error.to_string(),
+    }
+}
@@ -0,0 +1,115 @@
+// Tests for the EIP-712 messages and merkle root verification, against the shared test vectors.
+#[cfg(test)]
+mod tests {
+    use onesig_core::Hash;
+    #[cfg(feature = "client")]
+    use onesig_core::{Address, Secp256k1Pubkey};
+    #[cfg(feature = "client")]
+    use onesig_test_vectors::TestVectors;
+    use onesig_test_vectors::MAX_EXECUTIONS;
+    use solana_pubkey::Pubkey;
+
+    use crate::digest::{merkle_root_message, signer_execution_authorization_message};
+    #[cfg(feature = "client")]
+    use crate::{digest::verify_merkle_root_signatures, error::SdkError};
+
+    fn hash(bytes: &[u8]) -> Hash {
+        Hash::try_from(bytes.to_vec()).unwrap()
+    }
+
+    #[cfg(feature = "client")]
+    fn signers(vectors: &TestVectors) -> Vec<Address> {
+        vectors
+            .signers
+            .iter()
+            .map(|signer| Secp256k1Pubkey::new(&signer.public_key).into())
+            .collect()
+    }
+
+    #[cfg(feature = "client")]
+    fn verify(
+        vectors: &TestVectors,
+        max_executions: u32,
+        signatures: &[u8],
+        now: i64,
+    ) -> Result<Vec<Address>, SdkError> {
+        verify_merkle_root_signatures(
+            &hash(&vectors.seed),
+            &signers(vectors),
+            vectors.threshold,
+            &hash(&vectors.merkle_root),
+            vectors.expiry as i64,
+            max_executions,
+            signatures,
+            now,
+        )
+    }
+
+    #[test]
+    fn test_merkle_root_message_matches_vectors() {
+        let vectors = onesig_test_vectors::load();
+        let seed = hash(&vectors.seed);
+        let root = hash(&vectors.merkle_root);
+        assert_eq!(
+            merkle_root_message(&seed, &root, vectors.expiry, 0).digest().0.to_vec(),
+            vectors.merkle_root_signatures.digest
+        );
+        assert_eq!(
+            merkle_root_message(&seed, &root, vectors.expiry, MAX_EXECUTIONS)
+                .digest()
+                .0
+                .to_vec(),
+            vectors.limited_merkle_root_signatures.digest
+        );
+    }
+
+    #[test]
+    fn test_signer_execution_authorization_message_matches_vectors() {
+        let vectors = onesig_test_vectors::load();
+        let authorization = &vectors.signer_execution_authorization;
+        let message = signer_execution_authorization_message(
+            &hash(&vectors.leaves[authorization.leaf_index]),
+            &hash(&vectors.merkle_root),
+            &Pubkey::try_from(authorization.delegate.as_slice()).unwrap(),
+            authorization.expiry,
+        );
+        assert_eq!(message.digest().0.to_vec(), authorization.digest);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_verify_merkle_root_signatures() {
+        let vectors = onesig_test_vectors::load();
+        let mut signers = signers(&vectors);
+        signers.sort();
+
+        let mut proved =
+            verify(&vectors, 0, &vectors.merkle_root_signatures.signatures, 0).unwrap();
+        proved.sort();
+        assert_eq!(proved, signers);
+
+        let signatures = &vectors.limited_merkle_root_signatures.signatures;
+        let mut proved = verify(&vectors, MAX_EXECUTIONS, signatures, 0).unwrap();
+        proved.sort();
+        assert_eq!(proved, signers);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_verify_merkle_root_signatures_rejected() {
+        let vectors = onesig_test_vectors::load();
+        let signatures = &vectors.merkle_root_signatures.signatures;
+
+        let expired = verify(&vectors, 0, signatures, vectors.expiry as i64 + 1);
+        assert!(matches!(expired, Err(SdkError::Rejected(name)) if name == "ExpiredMerkleRoot"));
+
+        // Signatures over the unlimited root do not approve a limited one
+        let limited = verify(&vectors, MAX_EXECUTIONS, signatures, 0);
+        assert!(matches!(limited, Err(SdkError::Rejected(_))));
+
+        let one_signature = verify(&vectors, 0, &signatures[..signatures.len() / 2], 0);
+        assert!(
+            matches!(one_signature, Err(SdkError::Rejected(name)) if name == "InsufficientSignatures")
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Serde field encodings of the SDK's JSON formats, matching the TypeScript tooling: bytes as
+//! 0x-prefixed hex, Solana keys as base58, and amounts too large for JSON numbers as decimal
+//! strings.
+
+pub(crate) mod hex {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes.as_ref()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<Vec<u8>>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        decode(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    pub fn encode(bytes: &[u8]) -> String {
+        format!("0x{}", ::hex::encode(bytes))
+    }
+
+    /// Decodes hex, with or without its 0x prefix, into bytes of the expected length.
+    pub fn decode<T: TryFrom<Vec<u8>>>(hex: &str) -> Result<T, String> {
+        let bytes = ::hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+            .map_err(|error| format!("invalid hex {hex:?}: {error}"))?;
+        let len = bytes.len();
+        T::try_from(bytes).map_err(|_| format!("unexpected length {len} of {hex:?}"))
+    }
+}
+
+pub(crate) mod hex_list {
+    use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        list: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(list.len()))?;
+        for bytes in list {
+            seq.serialize_element(&super::hex::encode(bytes.as_ref()))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<Vec<u8>>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex| super::hex::decode(hex).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+pub(crate) mod hex_option {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        bytes: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&super::hex::encode(bytes.as_ref())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<Vec<u8>>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| super::hex::decode(&hex).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+pub(crate) mod base58 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod base58_list {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkeys: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(pubkeys.iter().map(Pubkey::to_string))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Pubkey>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|pubkey| pubkey.parse().map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+pub(crate) mod decimal {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
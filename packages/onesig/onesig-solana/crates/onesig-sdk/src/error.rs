@@ -0,0 +1,103 @@
+use solana_pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SdkError {
+    #[error("a tree needs at least one leaf")]
+    EmptyTree,
+    #[error("two leaves share OneSig id {one_sig_id} and nonce {nonce}")]
+    DuplicateNonce { one_sig_id: u64, nonce: u64 },
+    #[error("the tree has no leaf {0}")]
+    LeafNotFound(usize),
+    #[error("invalid secp256k1 private key")]
+    InvalidPrivateKey,
+    #[error("signer failed: {0}")]
+    Signer(String),
+    #[error("Ledger returned status {0:#06x}")]
+    LedgerStatus(u16),
+    #[error("the signature does not recover to the signer's address")]
+    SignerMismatch,
+    /// The program would reject the input, with this error
+    #[error("rejected by the program: {0}")]
+    Rejected(String),
+    #[error("invalid signature recovery id {0}")]
+    InvalidRecoveryId(u8),
+    #[error("no address recovers from the signature")]
+    FailedRecovery,
+    #[error("{found} distinct signers signed, the threshold is {threshold}")]
+    InsufficientSignatures { found: usize, threshold: u8 },
+    #[error("{0} bytes is not a whole number of signatures")]
+    SignatureLength(usize),
+    #[error("two signatures recover to the same signer")]
+    DuplicateSigner,
+    #[error("the signed message is not of the expected root")]
+    SignedMessageMismatch,
+    #[error("{0} signatures cannot be verified by the program")]
+    UnsupportedSignatureType(String),
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("invalid JSON: {0}")]
+    Json(String),
+    #[error("{0}")]
+    Hex(String),
+    #[error("{0} bytes is not a whole number of hashes")]
+    HashesLength(usize),
+    #[error("unsupported proposal bundle version {0}")]
+    UnsupportedBundleVersion(u32),
+    #[error("the bundle's merkle root is not the root of its leaves")]
+    BundleRootMismatch,
+    #[error("the bundle's proof {0} does not match its tree")]
+    BundleProofMismatch(usize),
+    #[error("the bundle has metadata for {0} leaves rather than for each of them")]
+    BundleLeafMetadataMismatch(usize),
+    #[error("leaf {0} of the bundle executes on none of its targets")]
+    BundleUnknownTarget(usize),
+    #[error("no instance {0} was added to the coordinator")]
+    InstanceNotFound(usize),
+    #[error("a transaction cannot reference {0} accounts")]
+    TooManyAccounts(usize),
+    /// Over the 1232 bytes of a network packet, see `transaction::PACKET_DATA_SIZE`
+    #[error("the transaction is {0} bytes, over the 1232 bytes of a packet")]
+    TransactionTooLarge(usize),
+    #[error("no signer for {0}")]
+    MissingTransactionSigner(Pubkey),
+    #[error("RPC transport failed: {0}")]
+    Transport(String),
+    #[error("RPC error {code}: {message}")]
+    Rpc { code: i64, message: String, data: Option<serde_json::Value> },
+    #[error("invalid RPC response: {0}")]
+    InvalidRpcResponse(String),
+    /// A simulation the SDK relies on failed, with the error and logs the node reports
+    #[error("simulation failed: {err}")]
+    SimulationFailed { err: serde_json::Value, logs: Vec<String> },
+    #[error("{0} is not an address lookup table")]
+    InvalidLookupTable(Pubkey),
+    #[error("a lookup table cannot hold {0} addresses")]
+    LookupTableFull(usize),
+    #[error("{0} is not an initialized nonce account")]
+    InvalidNonceAccount(Pubkey),
+    #[error("{0} is not a OneSigState account of this program version")]
+    InvalidOneSigState(Pubkey),
+    #[error("{0} is not a valid {1} account")]
+    InvalidAccount(Pubkey, &'static str),
+    #[error("not a signed wire transaction")]
+    InvalidTransaction,
+    /// The transaction landed, and failed
+    #[error("transaction {signature} failed: {err}")]
+    TransactionFailed { signature: String, err: serde_json::Value },
+    #[error("a Jito bundle holds 1 to 5 transactions, not {0}")]
+    JitoBundleSize(usize),
+    #[error("transaction {signature} was not confirmed in time")]
+    SubmissionTimeout { signature: String },
+    #[error("invalid {0} event")]
+    InvalidEvent(&'static str),
+    #[error("transaction {0} not found")]
+    TransactionNotFound(String),
+    #[error("invalid IDL: {0}")]
+    InvalidIdl(String),
+    /// The data of an instruction does not decode with the IDL of its program
+    #[error("invalid {program} instruction: {reason}")]
+    InvalidInstructionData { program: String, reason: String },
+    #[error("a group holds 1 to 255 leaves, not {0}")]
+    TransactionGroupSize(usize),
+}
@@ -0,0 +1,322 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, AnchorDeserialize, Discriminator};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::{
+    error::SdkError,
+    pda::EVENT_AUTHORITY_SEED,
+    rpc::{field, Commitment, RpcClient, RpcTransport},
+};
+
+macro_rules! one_sig_events {
+    ($($event:ident),* $(,)?) => {
+        /// An event of the OneSig program
+        pub enum OneSigEvent {
+            $($event(onesig::$event),)*
+        }
+
+        impl OneSigEvent {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$event(_) => stringify!($event),)*
+                }
+            }
+
+            /// The OneSig the event is about
+            pub fn one_sig_account(&self) -> &Pubkey {
+                match self {
+                    $(Self::$event(event) => &event.one_sig_account,)*
+                }
+            }
+
+            /// The `EVENT_VERSION` the event was emitted at, 0 if before events were versioned
+            pub fn event_version(&self) -> u8 {
+                match self {
+                    $(Self::$event(event) => event.event_version,)*
+                }
+            }
+
+            /// Decodes an event from its discriminator and data, `None` if the discriminator is
+            /// not of an event of the program.
+            fn decode(data: &[u8]) -> Option<Result<Self, SdkError>> {
+                $(
+                    if let Some(event) = data.strip_prefix(onesig::$event::DISCRIMINATOR) {
+                        return Some(
+                            decode_any_version(event)
+                                .map(Self::$event)
+                                .ok_or(SdkError::InvalidEvent(stringify!($event))),
+                        );
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+
+one_sig_events!(
+    OneSigInitialized,
+    ConfigSet,
+    ExecutorAdded,
+    ExecutorRemoved,
+    ExecutorRequiredSet,
+    ModuleAdded,
+    ModuleRemoved,
+    ModuleTransactionExecuted,
+    ExecutorTargetsSet,
+    SpendingLimitSet,
+    SpendingLimitRemoved,
+    ExecutorReimbursed,
+    BountyPaid,
+    BountyEscrowWithdrawn,
+    LeafFailureRecorded,
+    OneSigClosed,
+    MerkleRootVerified,
+    MerkleRootExtended,
+    TransactionExecuted,
+    NonceSkipped,
+);
+
+impl OneSigEvent {
+    /// Decodes the data of an event CPI, the self-CPI `emit_cpi!` makes: the event instruction tag,
+    /// then the event. `None` if it is not one.
+    pub fn from_event_cpi(data: &[u8]) -> Option<Result<Self, SdkError>> {
+        Self::decode(data.strip_prefix(EVENT_IX_TAG_LE)?)
+    }
+
+    /// Decodes the data of an event the program logged with `emit!`, as a `Program data:` log
+    /// line carries it. `None` if it is not one.
+    pub fn from_log_data(data: &[u8]) -> Option<Result<Self, SdkError>> {
+        Self::decode(data)
+    }
+}
+
+/// Zeroes appended to an event emitted before events were versioned, more than any event spans
+/// once its fields are zeroed
+const UNVERSIONED_PADDING: [u8; 512] = [0; 512];
+
+/// Decodes an event of any `EVENT_VERSION`. The fields a later version added past the ones the SDK
+/// knows are skipped. An event emitted before events were versioned lacks its `event_version`, and
+/// the fields added since it was: they read as zeroes, which decode to zeroed keys and hashes, 0,
+/// `None`, empty lists and the first variant of enums, the `event_version` telling they are
+/// missing.
+fn decode_any_version<E: AnchorDeserialize>(data: &[u8]) -> Option<E> {
+    E::deserialize(&mut &data[..])
+        .or_else(|_| E::deserialize(&mut [data, &UNVERSIONED_PADDING].concat().as_slice()))
+        .ok()
+}
+
+/// Events a transaction emitted, and where it landed
+pub struct TransactionEvents {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Why the transaction failed; a failed transaction emits no event
+    pub err: Option<Value>,
+    /// Lamports the fee payer paid, if the node reports it
+    pub fee: Option<u64>,
+    /// Compute units the transaction consumed, if the node reports them
+    pub compute_units_consumed: Option<u64>,
+    pub events: Vec<OneSigEvent>,
+}
+
+impl TransactionEvents {
+    /// Decodes the events of the OneSig program at `program_id` in a `getTransaction` result, in
+    /// `json` encoding, from the inner instructions its event authority signs and the events it
+    /// logged.
+    pub fn from_transaction(
+        program_id: &Pubkey,
+        signature: &str,
+        transaction: &Value,
+    ) -> Result<Self, SdkError> {
+        let err: Option<Value> = field(transaction, "/meta/err")?;
+        let mut events = vec![];
+        if err.is_none() {
+            let mut keys: Vec<String> = field(transaction, "/transaction/message/accountKeys")?;
+            for loaded in ["/meta/loadedAddresses/writable", "/meta/loadedAddresses/readonly"] {
+                let loaded: Option<Vec<String>> = field(transaction, loaded).ok().flatten();
+                keys.extend(loaded.into_iter().flatten());
+            }
+            let key = |index: &Value| -> Option<Pubkey> {
+                keys.get(usize::try_from(index.as_u64()?).ok()?)?.parse().ok()
+            };
+            let event_authority =
+                Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0;
+            let inner: Option<Vec<Value>> = field(transaction, "/meta/innerInstructions").ok();
+            let event_cpis = inner
+                .iter()
+                .flatten()
+                .filter_map(|inner| inner.get("instructions")?.as_array())
+                .flatten()
+                .map(|instruction| {
+                    let program = instruction.get("programIdIndex").and_then(key);
+                    let first_account = instruction.pointer("/accounts/0").and_then(key);
+                    if !is_event_cpi(program_id, &event_authority, program, first_account) {
+                        return Ok(None);
+                    }
+                    let data: String = field(instruction, "/data")?;
+                    let data = bs58::decode(&data)
+                        .into_vec()
+                        .map_err(|error| SdkError::InvalidRpcResponse(error.to_string()))?;
+                    OneSigEvent::from_event_cpi(&data).transpose()
+                });
+            let logs: Option<Vec<String>> = field(transaction, "/meta/logMessages").ok().flatten();
+            events = interleave_logged_events(program_id, logs.iter().flatten(), event_cpis)?;
+        }
+        Ok(Self {
+            signature: signature.into(),
+            slot: field(transaction, "/slot")?,
+            block_time: field(transaction, "/blockTime").ok().flatten(),
+            err,
+            fee: field(transaction, "/meta/fee").ok(),
+            compute_units_consumed: field(transaction, "/meta/computeUnitsConsumed").ok(),
+            events,
+        })
+    }
+}
+
+/// Whether an inner instruction is an event CPI of the program at `program_id`: a call into the
+/// program signed by its event authority, which no one else can sign
+pub(crate) fn is_event_cpi(
+    program_id: &Pubkey,
+    event_authority: &Pubkey,
+    program: Option<Pubkey>,
+    first_account: Option<Pubkey>,
+) -> bool {
+    program == Some(*program_id) && first_account == Some(*event_authority)
+}
+
+/// The events of a transaction, in the order they were emitted: `event_cpis` are its inner
+/// instructions, in order, decoded as the event CPIs of the program at `program_id` (`None` for the
+/// ones that are not), and `logs` the log lines that tell which of them the program's events
+/// logged with `emit!` came before. Each invocation the logs show past the top level is the next
+/// inner instruction; the ones past truncated logs come last.
+pub(crate) fn interleave_logged_events(
+    program_id: &Pubkey,
+    logs: impl IntoIterator<Item = impl AsRef<str>>,
+    event_cpis: impl IntoIterator<Item = Result<Option<OneSigEvent>, SdkError>>,
+) -> Result<Vec<OneSigEvent>, SdkError> {
+    let program = program_id.to_string();
+    let mut event_cpis = event_cpis.into_iter();
+    let mut events = vec![];
+    // Whether each program on the invocation stack is this one; only the program running logs
+    // `Program data:` lines, and no program can log a line the runtime starts with `Program `
+    let mut frames: Vec<bool> = vec![];
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if frames.last() == Some(&true) {
+                let data = data
+                    .split(' ')
+                    .map(|part| BASE64_STANDARD.decode(part))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| SdkError::InvalidRpcResponse(error.to_string()))?
+                    .concat();
+                if let Some(event) = OneSigEvent::from_log_data(&data) {
+                    events.push(event?);
+                }
+            }
+            continue;
+        }
+        let mut words = log.strip_prefix("Program ").unwrap_or_default().split(' ');
+        match (words.next(), words.next()) {
+            (Some(id), Some("invoke")) => {
+                if !frames.is_empty() {
+                    events.extend(event_cpis.next().transpose()?.flatten());
+                }
+                frames.push(id == program);
+            },
+            (Some(_), Some("success" | "failed:")) => {
+                frames.pop();
+            },
+            _ => {},
+        }
+    }
+    for event in event_cpis {
+        events.extend(event?);
+    }
+    Ok(events)
+}
+
+/// A websocket connection to the pubsub endpoint of a node, e.g. over `tungstenite`
+pub trait PubsubTransport {
+    fn send(&mut self, message: &str) -> Result<(), SdkError>;
+
+    /// The next text message, `None` once the connection closed
+    fn receive(&mut self) -> Result<Option<String>, SdkError>;
+}
+
+/// `logsSubscribe` request to the transactions mentioning `program_id`
+pub fn logs_subscribe_request(program_id: &Pubkey, commitment: Commitment) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "logsSubscribe",
+        "params": [{ "mentions": [program_id.to_string()] }, { "commitment": commitment.as_str() }],
+    })
+    .to_string()
+}
+
+/// Signature of the transaction a `logsNotification` is about, if it succeeded; `None` for other
+/// messages, such as the confirmation of the subscription.
+pub fn logs_notification_signature(message: &str) -> Option<String> {
+    let message: Value = serde_json::from_str(message).ok()?;
+    if message.get("method")?.as_str()? != "logsNotification" {
+        return None;
+    }
+    let value = message.pointer("/params/result/value")?;
+    if !value.get("err").is_none_or(Value::is_null) {
+        return None;
+    }
+    Some(value.get("signature")?.as_str()?.into())
+}
+
+/// Events of the transactions of a OneSig program as they land.
+///
+/// Events are mostly emitted through self-CPIs, which logs do not carry: each transaction the logs
+/// subscription notifies is fetched, and its events decoded. Transactions emitting none, failed
+/// ones included, are skipped.
+pub struct EventSubscription<'a, S, T> {
+    socket: S,
+    client: &'a RpcClient<T>,
+    program_id: Pubkey,
+}
+
+impl<'a, S: PubsubTransport, T: RpcTransport> EventSubscription<'a, S, T> {
+    /// Subscribes over `socket` to the logs of `program_id`, fetching transactions with `client`,
+    /// at its commitment.
+    pub fn subscribe(
+        mut socket: S,
+        client: &'a RpcClient<T>,
+        program_id: Pubkey,
+    ) -> Result<Self, SdkError> {
+        socket.send(&logs_subscribe_request(&program_id, client.commitment()))?;
+        Ok(Self { socket, client, program_id })
+    }
+
+    pub fn socket(&self) -> &S {
+        &self.socket
+    }
+}
+
+impl<S: PubsubTransport, T: RpcTransport> Iterator for EventSubscription<'_, S, T> {
+    type Item = Result<TransactionEvents, SdkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let message = match self.socket.receive() {
+                Ok(message) => message?,
+                Err(error) => return Some(Err(error)),
+            };
+            let Some(signature) = logs_notification_signature(&message) else {
+                continue;
+            };
+            match self.client.transaction_events(&self.program_id, &signature) {
+                Ok(Some(events)) if events.events.is_empty() => {},
+                Ok(Some(events)) => return Some(Ok(events)),
+                Ok(None) => return Some(Err(SdkError::TransactionNotFound(signature))),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
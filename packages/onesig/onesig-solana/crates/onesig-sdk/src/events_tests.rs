@@ -0,0 +1,329 @@
+// Tests for decoding event CPIs and logged events from transactions, and the event subscription.
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque};
+
+    use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, Discriminator, Event};
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use onesig::{
+        ConfigSet, MerkleRootVerified, PreviousConfig, SetConfigParams, TransactionExecuted,
+        EVENT_VERSION,
+    };
+    use onesig_core::Hash;
+    use serde_json::{json, Value};
+
+    use crate::{
+        error::SdkError,
+        events::{
+            logs_notification_signature, logs_subscribe_request, EventSubscription, OneSigEvent,
+            PubsubTransport, TransactionEvents,
+        },
+        pda::EVENT_AUTHORITY_SEED,
+        rpc::{Commitment, RpcClient, RpcTransport},
+    };
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0e; 32]);
+    const STATE: Pubkey = Pubkey::new_from_array([0x51; 32]);
+
+    fn event_authority() -> Pubkey {
+        Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID).0
+    }
+
+    fn executed() -> TransactionExecuted {
+        TransactionExecuted {
+            one_sig_account: STATE,
+            merkle_root: Hash([0xaa; 32]),
+            nonce: 3,
+            chain_id: Some("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp".into()),
+            one_sig_id: 40_168,
+            executor: Pubkey::new_from_array([0xe0; 32]),
+            program_id: Pubkey::new_from_array([0xc0; 32]),
+            ix_data_hash: Hash([0xdd; 32]),
+            value: 1_000,
+            lamports_spent: 600,
+            root_executions: Some(7),
+            event_version: EVENT_VERSION,
+        }
+    }
+
+    fn event_cpi(event: &impl Event) -> String {
+        bs58::encode([EVENT_IX_TAG_LE, &event.data()].concat()).into_string()
+    }
+
+    fn inner(program_id_index: u8, accounts: &[u8], data: &str) -> Value {
+        json!({ "programIdIndex": program_id_index, "accounts": accounts, "data": data, "stackHeight": 2 })
+    }
+
+    /// A `getTransaction` result: the program, the payer and another program are static keys, the
+    /// event authority is loaded from a table
+    fn transaction(err: Value, inner_instructions: Vec<Value>) -> Value {
+        json!({
+            "slot": 77,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": {
+                    "accountKeys": [
+                        Pubkey::new_from_array([0x10; 32]).to_string(),
+                        PROGRAM_ID.to_string(),
+                        Pubkey::new_from_array([0x90; 32]).to_string(),
+                    ],
+                },
+            },
+            "meta": {
+                "err": err,
+                "fee": 5_000,
+                "computeUnitsConsumed": 41_000,
+                "innerInstructions": [{ "index": 0, "instructions": inner_instructions }],
+                "loadedAddresses": { "writable": [], "readonly": [event_authority().to_string()] },
+            },
+        })
+    }
+
+    #[test]
+    fn test_from_event_cpi() {
+        let data = [EVENT_IX_TAG_LE, &executed().data()].concat();
+        let Some(Ok(OneSigEvent::TransactionExecuted(event))) = OneSigEvent::from_event_cpi(&data)
+        else {
+            panic!("not a TransactionExecuted");
+        };
+        assert!(event.merkle_root == executed().merkle_root);
+        assert_eq!(event.nonce, 3);
+        assert_eq!(event.chain_id, executed().chain_id);
+        assert_eq!(event.one_sig_id, 40_168);
+        assert_eq!(event.executor, executed().executor);
+        assert_eq!(event.program_id, executed().program_id);
+        assert!(event.ix_data_hash == executed().ix_data_hash);
+        assert_eq!((event.value, event.lamports_spent), (1_000, 600));
+        assert_eq!(event.root_executions, Some(7));
+
+        // Not an event CPI, an unknown event, an invalid one
+        assert!(OneSigEvent::from_event_cpi(&executed().data()).is_none());
+        assert!(OneSigEvent::from_event_cpi(&[EVENT_IX_TAG_LE, &[0; 16]].concat()).is_none());
+        let mut invalid = data.clone();
+        // The tag of `chain_id`, after the tags, the account, the root and the nonce
+        invalid[8 + 8 + 32 + 32 + 8] = 2;
+        assert!(matches!(
+            OneSigEvent::from_event_cpi(&invalid),
+            Some(Err(SdkError::InvalidEvent("TransactionExecuted")))
+        ));
+    }
+
+    #[test]
+    fn test_event_versions() {
+        // Emitted before events were versioned, with only the account, the root and the nonce
+        let unversioned =
+            [TransactionExecuted::DISCRIMINATOR, STATE.as_ref(), &[0xaa; 32], &3u64.to_le_bytes()]
+                .concat();
+        let Some(Ok(event)) = OneSigEvent::from_log_data(&unversioned) else {
+            panic!("not an event");
+        };
+        assert_eq!(event.event_version(), 0);
+        let OneSigEvent::TransactionExecuted(event) = event else {
+            panic!("not a TransactionExecuted");
+        };
+        assert_eq!((event.one_sig_account, event.nonce), (STATE, 3));
+        assert_eq!((event.chain_id, event.executor), (None, Pubkey::default()));
+        assert_eq!((event.lamports_spent, event.root_executions), (0, None));
+
+        // Emitted by a later version, with a field the SDK does not know yet
+        let later = [&executed().data()[..], &[0x2a; 8]].concat();
+        let Some(Ok(OneSigEvent::TransactionExecuted(event))) = OneSigEvent::from_log_data(&later)
+        else {
+            panic!("not a TransactionExecuted");
+        };
+        assert_eq!(event.event_version, EVENT_VERSION);
+        assert_eq!(event.root_executions, Some(7));
+    }
+
+    #[test]
+    fn test_from_transaction() {
+        let config_set = ConfigSet {
+            one_sig_account: STATE,
+            params: SetConfigParams::SetThreshold(2),
+            previous: PreviousConfig::Threshold(3),
+            event_version: EVENT_VERSION,
+        };
+        let transaction = transaction(
+            Value::Null,
+            vec![
+                // The leaf's instruction, by another program
+                inner(2, &[0], &event_cpi(&executed())),
+                // A call into the program not signed by its event authority
+                inner(1, &[0], &event_cpi(&executed())),
+                inner(1, &[3], &event_cpi(&config_set)),
+                inner(1, &[3], &event_cpi(&executed())),
+            ],
+        );
+
+        let events = TransactionEvents::from_transaction(&PROGRAM_ID, "sig", &transaction).unwrap();
+        assert_eq!(events.signature, "sig");
+        assert_eq!(events.slot, 77);
+        assert_eq!(events.block_time, Some(1_700_000_000));
+        assert_eq!(events.err, None);
+        assert_eq!((events.fee, events.compute_units_consumed), (Some(5_000), Some(41_000)));
+        let names: Vec<&str> = events.events.iter().map(OneSigEvent::name).collect();
+        assert_eq!(names, ["ConfigSet", "TransactionExecuted"]);
+        assert!(matches!(
+            &events.events[0],
+            OneSigEvent::ConfigSet(ConfigSet {
+                params: SetConfigParams::SetThreshold(2),
+                previous: PreviousConfig::Threshold(3),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_logged_events() {
+        let verified = MerkleRootVerified {
+            one_sig_account: STATE,
+            merkle_root: Hash([0xaa; 32]),
+            expiry: 1_800_000_000,
+            rent_payer: Pubkey::new_from_array([0x10; 32]),
+            event_version: EVENT_VERSION,
+        };
+        let program = PROGRAM_ID.to_string();
+        let other = Pubkey::new_from_array([0x90; 32]).to_string();
+        let logged = format!("Program data: {}", BASE64_STANDARD.encode(verified.data()));
+        let mut transaction = transaction(
+            Value::Null,
+            vec![inner(2, &[0], "1"), inner(1, &[3], &event_cpi(&executed()))],
+        );
+        // `verify_merkle_root`, then `execute_transaction` calling a program logging the same data
+        transaction["meta"]["logMessages"] = json!([
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: VerifyMerkleRoot",
+            logged,
+            format!("Program {program} consumed 40000 of 200000 compute units"),
+            format!("Program {program} success"),
+            format!("Program {program} invoke [1]"),
+            format!("Program {other} invoke [2]"),
+            logged,
+            format!("Program {other} success"),
+            format!("Program {program} invoke [2]"),
+            format!("Program {program} success"),
+            format!("Program {program} success"),
+        ]);
+
+        let events = TransactionEvents::from_transaction(&PROGRAM_ID, "sig", &transaction).unwrap();
+        let names: Vec<&str> = events.events.iter().map(OneSigEvent::name).collect();
+        assert_eq!(names, ["MerkleRootVerified", "TransactionExecuted"]);
+        assert!(matches!(
+            &events.events[0],
+            OneSigEvent::MerkleRootVerified(MerkleRootVerified { expiry: 1_800_000_000, .. })
+        ));
+
+        // Truncated logs still yield the event CPIs past them
+        transaction["meta"]["logMessages"] = json!([format!("Program {program} invoke [1]")]);
+        let events = TransactionEvents::from_transaction(&PROGRAM_ID, "sig", &transaction).unwrap();
+        let names: Vec<&str> = events.events.iter().map(OneSigEvent::name).collect();
+        assert_eq!(names, ["TransactionExecuted"]);
+    }
+
+    #[test]
+    fn test_failed_transaction() {
+        let err = json!({ "InstructionError": [0, { "Custom": 6009 }] });
+        let transaction = transaction(err.clone(), vec![inner(1, &[3], &event_cpi(&executed()))]);
+
+        let events = TransactionEvents::from_transaction(&PROGRAM_ID, "sig", &transaction).unwrap();
+        assert_eq!(events.err, Some(err));
+        assert!(events.events.is_empty());
+    }
+
+    /// Replays `responses` in order
+    struct MockTransport(RefCell<VecDeque<Value>>);
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            let request: Value = serde_json::from_str(body).unwrap();
+            assert_eq!(request["method"], "getTransaction");
+            let response =
+                self.0.borrow_mut().pop_front().ok_or(SdkError::Transport("closed".into()))?;
+            Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": response }).to_string())
+        }
+    }
+
+    /// Yields `messages`, recording what is sent
+    struct MockSocket {
+        sent: Vec<String>,
+        messages: VecDeque<Value>,
+    }
+
+    impl PubsubTransport for MockSocket {
+        fn send(&mut self, message: &str) -> Result<(), SdkError> {
+            self.sent.push(message.into());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> Result<Option<String>, SdkError> {
+            Ok(self.messages.pop_front().map(|message| message.to_string()))
+        }
+    }
+
+    fn notification(signature: &str, err: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "logsNotification",
+            "params": {
+                "result": {
+                    "context": { "slot": 77 },
+                    "value": { "signature": signature, "err": err, "logs": [] },
+                },
+                "subscription": 9,
+            },
+        })
+    }
+
+    #[test]
+    fn test_logs_notification_signature() {
+        let succeeded = notification("sig", Value::Null);
+        assert_eq!(logs_notification_signature(&succeeded.to_string()), Some("sig".into()));
+
+        let failed = notification("sig", json!({ "InstructionError": [0, "Foo"] }));
+        assert_eq!(logs_notification_signature(&failed.to_string()), None);
+        let confirmation = json!({ "jsonrpc": "2.0", "id": 1, "result": 9 });
+        assert_eq!(logs_notification_signature(&confirmation.to_string()), None);
+        assert_eq!(logs_notification_signature("not json"), None);
+    }
+
+    #[test]
+    fn test_subscription() {
+        let client = RpcClient::new(
+            MockTransport(RefCell::new(VecDeque::from([
+                transaction(Value::Null, vec![]),
+                transaction(Value::Null, vec![inner(1, &[3], &event_cpi(&executed()))]),
+            ]))),
+            Commitment::Processed,
+        );
+        let socket = MockSocket {
+            sent: vec![],
+            messages: VecDeque::from([
+                json!({ "jsonrpc": "2.0", "id": 1, "result": 9 }),
+                notification("quiet", Value::Null),
+                notification("failed", json!("AccountInUse")),
+                notification("executed", Value::Null),
+            ]),
+        };
+
+        let mut subscription = EventSubscription::subscribe(socket, &client, PROGRAM_ID).unwrap();
+        assert_eq!(
+            subscription.socket().sent,
+            [logs_subscribe_request(&PROGRAM_ID, Commitment::Processed)]
+        );
+        // The transaction without events is skipped, the failed one not fetched
+        let events = subscription.next().unwrap().unwrap();
+        assert_eq!(events.signature, "executed");
+        assert_eq!(events.events.len(), 1);
+        assert!(subscription.next().is_none());
+    }
+
+    #[test]
+    fn test_transaction_events() {
+        let client = RpcClient::new(
+            MockTransport(RefCell::new(VecDeque::from([Value::Null]))),
+            Commitment::default(),
+        );
+        assert!(matches!(client.transaction_events(&PROGRAM_ID, "unknown"), Ok(None)));
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use crate::error::SdkError;
+#[cfg(feature = "tokio")]
+use crate::rpc::AsyncRpcTransport;
+#[cfg(feature = "blocking")]
+use crate::rpc::RpcTransport;
+
+/// How long a request may take by default, sending a transaction included
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// [`RpcTransport`] posting JSON over HTTP with `ureq`, behind the `blocking` feature.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug)]
+pub struct HttpTransport {
+    agent: ureq::Agent,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+#[cfg(feature = "blocking")]
+impl HttpTransport {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_timeout(url, DEFAULT_HTTP_TIMEOUT)
+    }
+
+    pub fn with_timeout(url: impl Into<String>, timeout: Duration) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        Self { agent, url: url.into(), headers: vec![] }
+    }
+
+    /// Adds a header to the requests, e.g. the API key of an RPC provider.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl RpcTransport for HttpTransport {
+    fn post(&self, body: &str) -> Result<String, SdkError> {
+        let request = self.headers.iter().fold(
+            self.agent.post(&self.url).set("content-type", "application/json"),
+            |request, (name, value)| request.set(name, value),
+        );
+        let response = match request.send_string(body) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                return Err(status_error(&self.url, status, &body));
+            },
+            Err(error) => return Err(SdkError::Transport(format!("{}: {error}", self.url))),
+        };
+        response
+            .into_string()
+            .map_err(|error| SdkError::Transport(format!("{}: {error}", self.url)))
+    }
+}
+
+/// [`AsyncRpcTransport`] posting JSON over HTTP with `reqwest`, behind the `tokio` feature: its
+/// requests run on the tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug)]
+pub struct AsyncHttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncHttpTransport {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_timeout(url, DEFAULT_HTTP_TIMEOUT)
+    }
+
+    pub fn with_timeout(url: impl Into<String>, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("the TLS backend and system configuration load");
+        Self::with_client(client, url)
+    }
+
+    /// Posts with `client`, sharing its connection pool and configuration.
+    pub fn with_client(client: reqwest::Client, url: impl Into<String>) -> Self {
+        Self { client, url: url.into(), headers: vec![] }
+    }
+
+    /// Adds a header to the requests, e.g. the API key of an RPC provider.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncRpcTransport for AsyncHttpTransport {
+    async fn post(&self, body: String) -> Result<String, SdkError> {
+        let transport_error =
+            |error: reqwest::Error| SdkError::Transport(format!("{}: {error}", self.url));
+        let request = self.headers.iter().fold(
+            self.client
+                .post(&self.url)
+                .header("content-type", "application/json")
+                .body(body),
+            |request, (name, value)| request.header(name, value),
+        );
+        let response = request.send().await.map_err(transport_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(transport_error)?;
+        if !status.is_success() {
+            return Err(status_error(&self.url, status.as_u16(), &body));
+        }
+        Ok(body)
+    }
+}
+
+/// The error of a request answered with a non-2xx `status`, with the body the node explains it
+/// with, if any
+fn status_error(url: &str, status: u16, body: &str) -> SdkError {
+    match body.trim() {
+        "" => SdkError::Transport(format!("{url}: HTTP {status}")),
+        body => SdkError::Transport(format!("{url}: HTTP {status}: {body}")),
+    }
+}
@@ -0,0 +1,110 @@
+// Tests for the HTTP transports, against a local server answering a single request.
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use crate::error::SdkError;
+
+    /// A request the server received
+    struct Request {
+        headers: Vec<String>,
+        body: String,
+    }
+
+    /// Serves one request on a local port with `status` and `body`, returning its URL and the
+    /// request
+    fn serve(status: &'static str, body: &'static str) -> (String, JoinHandle<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut headers = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end() {
+                    "" => break,
+                    line => headers.push(line.to_lowercase()),
+                }
+            }
+            let len = headers
+                .iter()
+                .find_map(|header| header.strip_prefix("content-length: "))
+                .map_or(0, |len| len.parse().unwrap());
+            let mut request = vec![0; len];
+            reader.read_exact(&mut request).unwrap();
+            write!(
+                &stream,
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+                 connection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            Request { headers, body: String::from_utf8(request).unwrap() }
+        });
+        (url, server)
+    }
+
+    const REQUEST: &str = r#"{"jsonrpc":"2.0","id":1,"method":"getSlot","params":[]}"#;
+    const RESPONSE: &str = r#"{"jsonrpc":"2.0","id":1,"result":42}"#;
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_http_transport_posts_json() {
+        use crate::{http::HttpTransport, rpc::RpcTransport};
+
+        let (url, server) = serve("200 OK", RESPONSE);
+        let transport = HttpTransport::new(url).header("x-api-key", "secret");
+        assert_eq!(transport.post(REQUEST).unwrap(), RESPONSE);
+
+        let request = server.join().unwrap();
+        assert!(request.headers[0].starts_with("post / "));
+        assert!(request.headers.contains(&"content-type: application/json".to_owned()));
+        assert!(request.headers.contains(&"x-api-key: secret".to_owned()));
+        assert_eq!(request.body, REQUEST);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_http_transport_fails_on_error_status() {
+        use crate::{http::HttpTransport, rpc::RpcTransport};
+
+        let (url, server) = serve("429 Too Many Requests", "rate limited");
+        let error = HttpTransport::new(url.clone()).post(REQUEST).unwrap_err();
+        assert_eq!(error, SdkError::Transport(format!("{url}: HTTP 429: rate limited")));
+        server.join().unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_http_transport_posts_json() {
+        use crate::{http::AsyncHttpTransport, rpc::AsyncRpcTransport};
+
+        let (url, server) = serve("200 OK", RESPONSE);
+        let transport = AsyncHttpTransport::new(url).header("x-api-key", "secret");
+        assert_eq!(transport.post(REQUEST.to_owned()).await.unwrap(), RESPONSE);
+
+        let request = server.join().unwrap();
+        assert!(request.headers[0].starts_with("post / "));
+        assert!(request.headers.contains(&"content-type: application/json".to_owned()));
+        assert!(request.headers.contains(&"x-api-key: secret".to_owned()));
+        assert_eq!(request.body, REQUEST);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_http_transport_fails_on_error_status() {
+        use crate::{http::AsyncHttpTransport, rpc::AsyncRpcTransport};
+
+        let (url, server) = serve("503 Service Unavailable", "");
+        let error =
+            AsyncHttpTransport::new(url.clone()).post(REQUEST.to_owned()).await.unwrap_err();
+        assert_eq!(error, SdkError::Transport(format!("{url}: HTTP 503")));
+        server.join().unwrap();
+    }
+}
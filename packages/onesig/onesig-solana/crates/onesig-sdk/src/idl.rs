@@ -0,0 +1,373 @@
+use std::{collections::HashMap, fmt};
+
+use anchor_lang_idl_spec::{
+    Idl, IdlArrayLen, IdlDefinedFields, IdlInstructionAccountItem, IdlSerialization, IdlType,
+    IdlTypeDefTy,
+};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    encoding::hex,
+    error::SdkError,
+    leaf::{OneSigInstruction, SolanaLeaf},
+};
+
+/// Types nest at most this deep, so a recursive IDL cannot exhaust the stack
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A value decoded from instruction data, in the shape its IDL type declares
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedValue {
+    Bool(bool),
+    Int(i128),
+    Uint(u128),
+    Float(f64),
+    String(String),
+    /// `bytes`, and vectors and arrays of `u8`
+    Bytes(Vec<u8>),
+    Pubkey(Pubkey),
+    Option(Option<Box<DecodedValue>>),
+    List(Vec<DecodedValue>),
+    Struct(DecodedFields),
+    Variant {
+        name: String,
+        fields: DecodedFields,
+    },
+}
+
+/// Fields of a struct or an enum variant, in declaration order
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedFields {
+    Unit,
+    Named(Vec<(String, DecodedValue)>),
+    Tuple(Vec<DecodedValue>),
+}
+
+/// An account of a decoded instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedAccount {
+    /// `None` past the accounts the IDL names, e.g. for remaining accounts
+    pub name: Option<String>,
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An instruction decoded with the IDL of its program, for reviewers to read what they approve
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedInstruction {
+    /// Name of the program, from its IDL
+    pub program: String,
+    pub name: String,
+    pub accounts: Vec<DecodedAccount>,
+    pub args: Vec<(String, DecodedValue)>,
+}
+
+/// Decodes the instructions of leaves with the Anchor IDLs of the programs they call.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionDecoder {
+    idls: HashMap<Pubkey, Idl>,
+}
+
+impl InstructionDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the IDL of the program at its `address`, replacing any previous one.
+    pub fn add_idl(&mut self, idl: Idl) -> Result<(), SdkError> {
+        let program_id = idl
+            .address
+            .parse()
+            .map_err(|_| SdkError::InvalidIdl(format!("invalid address {}", idl.address)))?;
+        self.idls.insert(program_id, idl);
+        Ok(())
+    }
+
+    /// Registers an IDL in the JSON `anchor build` writes.
+    pub fn add_idl_json(&mut self, json: &str) -> Result<(), SdkError> {
+        self.add_idl(
+            serde_json::from_str(json).map_err(|error| SdkError::InvalidIdl(error.to_string()))?,
+        )
+    }
+
+    pub fn idl(&self, program_id: &Pubkey) -> Option<&Idl> {
+        self.idls.get(program_id)
+    }
+
+    /// Decodes `instruction`, `None` if the IDL of its program is not registered.
+    pub fn decode(
+        &self,
+        instruction: &OneSigInstruction,
+    ) -> Result<Option<DecodedInstruction>, SdkError> {
+        let Some(idl) = self.idls.get(&instruction.program_id) else {
+            return Ok(None);
+        };
+        let invalid = |reason: String| SdkError::InvalidInstructionData {
+            program: idl.metadata.name.clone(),
+            reason,
+        };
+        let idl_instruction = idl
+            .instructions
+            .iter()
+            .find(|candidate| instruction.data.starts_with(&candidate.discriminator))
+            .ok_or_else(|| invalid("unknown discriminator".into()))?;
+
+        let mut reader =
+            Reader { idl, data: &instruction.data[idl_instruction.discriminator.len()..] };
+        let args = idl_instruction
+            .args
+            .iter()
+            .map(|arg| Ok((arg.name.clone(), reader.read(&arg.ty, 0)?)))
+            .collect::<Result<_, String>>()
+            .map_err(invalid)?;
+        if !reader.data.is_empty() {
+            return Err(invalid(format!("{} trailing bytes", reader.data.len())));
+        }
+
+        let mut names = vec![];
+        account_names(&idl_instruction.accounts, "", &mut names);
+        let mut names = names.into_iter();
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|account| DecodedAccount {
+                name: names.next(),
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+
+        Ok(Some(DecodedInstruction {
+            program: idl.metadata.name.clone(),
+            name: idl_instruction.name.clone(),
+            accounts,
+            args,
+        }))
+    }
+
+    /// Decodes the instruction `leaf` executes.
+    pub fn decode_leaf(&self, leaf: &SolanaLeaf) -> Result<Option<DecodedInstruction>, SdkError> {
+        self.decode(&leaf.instruction)
+    }
+}
+
+/// Names of the accounts of an instruction in order, those of composite accounts under their
+/// struct's name
+fn account_names(items: &[IdlInstructionAccountItem], prefix: &str, names: &mut Vec<String>) {
+    for item in items {
+        match item {
+            IdlInstructionAccountItem::Single(account) =>
+                names.push(format!("{prefix}{}", account.name)),
+            IdlInstructionAccountItem::Composite(accounts) =>
+                account_names(&accounts.accounts, &format!("{prefix}{}.", accounts.name), names),
+        }
+    }
+}
+
+/// Borsh decoding of instruction data as the types of an IDL declare it
+struct Reader<'a> {
+    idl: &'a Idl,
+    data: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take_slice(N)?.try_into().unwrap())
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&[u8], String> {
+        if self.data.len() < len {
+            return Err("unexpected end of data".into());
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    /// Length prefix of a string or vector, which cannot exceed what is left to read
+    fn len(&mut self) -> Result<usize, String> {
+        let len = u32::from_le_bytes(self.take()?) as usize;
+        if len > self.data.len() {
+            return Err(format!("length {len} exceeds the data"));
+        }
+        Ok(len)
+    }
+
+    fn read(&mut self, ty: &IdlType, depth: usize) -> Result<DecodedValue, String> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err("types nest too deep".into());
+        }
+        Ok(match ty {
+            IdlType::Bool => match self.take::<1>()? {
+                [0] => DecodedValue::Bool(false),
+                [1] => DecodedValue::Bool(true),
+                [byte] => return Err(format!("invalid bool {byte}")),
+            },
+            IdlType::U8 => DecodedValue::Uint(u8::from_le_bytes(self.take()?).into()),
+            IdlType::U16 => DecodedValue::Uint(u16::from_le_bytes(self.take()?).into()),
+            IdlType::U32 => DecodedValue::Uint(u32::from_le_bytes(self.take()?).into()),
+            IdlType::U64 => DecodedValue::Uint(u64::from_le_bytes(self.take()?).into()),
+            IdlType::U128 => DecodedValue::Uint(u128::from_le_bytes(self.take()?)),
+            IdlType::I8 => DecodedValue::Int(i8::from_le_bytes(self.take()?).into()),
+            IdlType::I16 => DecodedValue::Int(i16::from_le_bytes(self.take()?).into()),
+            IdlType::I32 => DecodedValue::Int(i32::from_le_bytes(self.take()?).into()),
+            IdlType::I64 => DecodedValue::Int(i64::from_le_bytes(self.take()?).into()),
+            IdlType::I128 => DecodedValue::Int(i128::from_le_bytes(self.take()?)),
+            IdlType::F32 => DecodedValue::Float(f32::from_le_bytes(self.take()?).into()),
+            IdlType::F64 => DecodedValue::Float(f64::from_le_bytes(self.take()?)),
+            IdlType::String => {
+                let len = self.len()?;
+                let string = std::str::from_utf8(self.take_slice(len)?)
+                    .map_err(|error| format!("invalid string: {error}"))?;
+                DecodedValue::String(string.into())
+            },
+            IdlType::Bytes | IdlType::Vec(_) if is_bytes(ty) => {
+                let len = self.len()?;
+                DecodedValue::Bytes(self.take_slice(len)?.to_vec())
+            },
+            IdlType::Pubkey => DecodedValue::Pubkey(Pubkey::new_from_array(self.take()?)),
+            IdlType::Option(inner) => match self.take::<1>()? {
+                [0] => DecodedValue::Option(None),
+                [1] => DecodedValue::Option(Some(Box::new(self.read(inner, depth + 1)?))),
+                [tag] => return Err(format!("invalid option tag {tag}")),
+            },
+            IdlType::Vec(inner) => {
+                let len = self.len()?;
+                DecodedValue::List(
+                    (0..len).map(|_| self.read(inner, depth + 1)).collect::<Result<_, _>>()?,
+                )
+            },
+            IdlType::Array(inner, IdlArrayLen::Value(len)) if **inner == IdlType::U8 =>
+                DecodedValue::Bytes(self.take_slice(*len)?.to_vec()),
+            IdlType::Array(inner, IdlArrayLen::Value(len)) => DecodedValue::List(
+                (0..*len).map(|_| self.read(inner, depth + 1)).collect::<Result<_, _>>()?,
+            ),
+            IdlType::Defined { name, generics } if generics.is_empty() =>
+                self.read_defined(name, depth + 1)?,
+            ty => return Err(format!("unsupported type {ty:?}")),
+        })
+    }
+
+    fn read_defined(&mut self, name: &str, depth: usize) -> Result<DecodedValue, String> {
+        let type_def = self
+            .idl
+            .types
+            .iter()
+            .find(|type_def| type_def.name == name)
+            .ok_or_else(|| format!("undefined type {name}"))?;
+        if type_def.serialization != IdlSerialization::Borsh || !type_def.generics.is_empty() {
+            return Err(format!("unsupported type {name}"));
+        }
+        Ok(match &type_def.ty {
+            IdlTypeDefTy::Struct { fields } =>
+                DecodedValue::Struct(self.read_fields(fields.as_ref(), depth)?),
+            IdlTypeDefTy::Enum { variants } => {
+                let [index] = self.take::<1>()?;
+                let variant = variants
+                    .get(index as usize)
+                    .ok_or_else(|| format!("invalid variant {index} of {name}"))?;
+                DecodedValue::Variant {
+                    name: variant.name.clone(),
+                    fields: self.read_fields(variant.fields.as_ref(), depth)?,
+                }
+            },
+            IdlTypeDefTy::Type { alias } => self.read(alias, depth)?,
+        })
+    }
+
+    fn read_fields(
+        &mut self,
+        fields: Option<&IdlDefinedFields>,
+        depth: usize,
+    ) -> Result<DecodedFields, String> {
+        Ok(match fields {
+            None => DecodedFields::Unit,
+            Some(IdlDefinedFields::Named(fields)) => DecodedFields::Named(
+                fields
+                    .iter()
+                    .map(|field| Ok((field.name.clone(), self.read(&field.ty, depth)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Some(IdlDefinedFields::Tuple(types)) => DecodedFields::Tuple(
+                types.iter().map(|ty| self.read(ty, depth)).collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+fn is_bytes(ty: &IdlType) -> bool {
+    match ty {
+        IdlType::Bytes => true,
+        IdlType::Vec(inner) => **inner == IdlType::U8,
+        _ => false,
+    }
+}
+
+impl fmt::Display for DecodedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodedValue::Bool(value) => write!(f, "{value}"),
+            DecodedValue::Int(value) => write!(f, "{value}"),
+            DecodedValue::Uint(value) => write!(f, "{value}"),
+            DecodedValue::Float(value) => write!(f, "{value}"),
+            DecodedValue::String(value) => write!(f, "{value:?}"),
+            DecodedValue::Bytes(value) => write!(f, "{}", hex::encode(value)),
+            DecodedValue::Pubkey(value) => write!(f, "{value}"),
+            DecodedValue::Option(None) => write!(f, "None"),
+            DecodedValue::Option(Some(value)) => write!(f, "Some({value})"),
+            DecodedValue::List(values) => {
+                write!(f, "[")?;
+                write_list(f, values.iter().map(|value| (None, value)))?;
+                write!(f, "]")
+            },
+            DecodedValue::Struct(fields) => write!(f, "{fields}"),
+            DecodedValue::Variant { name, fields: DecodedFields::Unit } => write!(f, "{name}"),
+            DecodedValue::Variant { name, fields } => write!(f, "{name} {fields}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodedFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodedFields::Unit => write!(f, "{{}}"),
+            DecodedFields::Named(fields) => {
+                write!(f, "{{ ")?;
+                write_list(f, fields.iter().map(|(name, value)| (Some(name.as_str()), value)))?;
+                write!(f, " }}")
+            },
+            DecodedFields::Tuple(values) => {
+                write!(f, "(")?;
+                write_list(f, values.iter().map(|value| (None, value)))?;
+                write!(f, ")")
+            },
+        }
+    }
+}
+
+/// `program::name(arg: value, ..)`, without the accounts
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::{}(", self.program, self.name)?;
+        write_list(f, self.args.iter().map(|(name, value)| (Some(name.as_str()), value)))?;
+        write!(f, ")")
+    }
+}
+
+fn write_list<'a>(
+    f: &mut fmt::Formatter,
+    items: impl Iterator<Item = (Option<&'a str>, &'a DecodedValue)>,
+) -> fmt::Result {
+    for (i, (name, value)) in items.enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        match name {
+            Some(name) => write!(f, "{name}: {value}")?,
+            None => write!(f, "{value}")?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+// Tests for decoding the instructions of leaves with Anchor IDLs.
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        error::SdkError,
+        idl::{DecodedFields, DecodedValue, InstructionDecoder},
+        leaf::{OneSigAccountMeta, OneSigInstruction, SolanaLeaf},
+    };
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0f; 32]);
+    const POOL: Pubkey = Pubkey::new_from_array([0x90; 32]);
+    const DISCRIMINATOR: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn decoder() -> InstructionDecoder {
+        let idl = json!({
+            "address": PROGRAM_ID.to_string(),
+            "metadata": { "name": "swap", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "swap",
+                "discriminator": DISCRIMINATOR,
+                "accounts": [
+                    { "name": "vault", "writable": true, "signer": true },
+                    { "name": "pools", "accounts": [{ "name": "source" }, { "name": "destination" }] },
+                ],
+                "args": [
+                    { "name": "amount", "type": "u64" },
+                    { "name": "memo", "type": { "option": "string" } },
+                    { "name": "route", "type": { "vec": { "defined": { "name": "Hop" } } } },
+                    { "name": "mode", "type": { "defined": { "name": "Mode" } } },
+                    { "name": "salt", "type": { "array": ["u8", 4] } },
+                ],
+            }],
+            "types": [
+                {
+                    "name": "Hop",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [{ "name": "pool", "type": "pubkey" }, { "name": "fee_bps", "type": "u16" }],
+                    },
+                },
+                {
+                    "name": "Mode",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [
+                            { "name": "Exact" },
+                            { "name": "Slippage", "fields": [{ "name": "min_out", "type": "u64" }] },
+                            { "name": "Offset", "fields": ["i8", "bool"] },
+                        ],
+                    },
+                },
+            ],
+        });
+        let mut decoder = InstructionDecoder::new();
+        decoder.add_idl_json(&idl.to_string()).unwrap();
+        decoder
+    }
+
+    /// `swap(5, Some("hi"), [Hop { POOL, 30 }], Slippage { 4 }, 0xdeadbeef)`
+    fn swap_data() -> Vec<u8> {
+        let mut data = DISCRIMINATOR.to_vec();
+        data.extend(5u64.to_le_bytes());
+        data.extend([1, 2, 0, 0, 0, b'h', b'i']);
+        data.extend(1u32.to_le_bytes());
+        data.extend(POOL.to_bytes());
+        data.extend(30u16.to_le_bytes());
+        data.push(1);
+        data.extend(4u64.to_le_bytes());
+        data.extend([0xde, 0xad, 0xbe, 0xef]);
+        data
+    }
+
+    fn instruction(data: Vec<u8>, accounts: usize) -> OneSigInstruction {
+        OneSigInstruction {
+            program_id: PROGRAM_ID,
+            accounts: (0..accounts)
+                .map(|i| OneSigAccountMeta {
+                    pubkey: Pubkey::new_from_array([i as u8; 32]),
+                    is_signer: i == 0,
+                    is_writable: i == 0,
+                })
+                .collect(),
+            data,
+            value: 0,
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let decoded = decoder().decode(&instruction(swap_data(), 4)).unwrap().unwrap();
+        assert_eq!((decoded.program.as_str(), decoded.name.as_str()), ("swap", "swap"));
+        let names: Vec<_> =
+            decoded.accounts.iter().map(|account| account.name.as_deref()).collect();
+        // The last account is a remaining account
+        assert_eq!(names, [Some("vault"), Some("pools.source"), Some("pools.destination"), None]);
+        assert!(decoded.accounts[0].is_signer && decoded.accounts[0].is_writable);
+
+        assert_eq!(decoded.args[0], ("amount".into(), DecodedValue::Uint(5)));
+        assert_eq!(
+            decoded.args[3].1,
+            DecodedValue::Variant {
+                name: "Slippage".into(),
+                fields: DecodedFields::Named(vec![("min_out".into(), DecodedValue::Uint(4))]),
+            }
+        );
+        assert_eq!(
+            decoded.to_string(),
+            format!(
+                "swap::swap(amount: 5, memo: Some(\"hi\"), route: [{{ pool: {POOL}, fee_bps: 30 }}], \
+                 mode: Slippage {{ min_out: 4 }}, salt: 0xdeadbeef)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_variants() {
+        let decoder = decoder();
+        let mut data = DISCRIMINATOR.to_vec();
+        data.extend(5u64.to_le_bytes());
+        data.extend([0, 0, 0, 0, 0]);
+        let mut exact = data.clone();
+        exact.extend([0, 1, 2, 3, 4]);
+        let mut offset = data;
+        offset.extend([2, 0xff, 1, 1, 2, 3, 4]);
+
+        let decoded = decoder.decode(&instruction(exact, 1)).unwrap().unwrap();
+        assert_eq!(
+            decoded.to_string(),
+            "swap::swap(amount: 5, memo: None, route: [], mode: Exact, salt: 0x01020304)"
+        );
+        let decoded = decoder.decode(&instruction(offset, 1)).unwrap().unwrap();
+        assert_eq!(
+            decoded.args[3].1,
+            DecodedValue::Variant {
+                name: "Offset".into(),
+                fields: DecodedFields::Tuple(vec![DecodedValue::Int(-1), DecodedValue::Bool(true)]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let decoder = decoder();
+        let invalid = |reason: &str| {
+            Err(SdkError::InvalidInstructionData { program: "swap".into(), reason: reason.into() })
+        };
+
+        let mut unknown = swap_data();
+        unknown[0] = 0;
+        assert_eq!(decoder.decode(&instruction(unknown, 0)), invalid("unknown discriminator"));
+        let mut trailing = swap_data();
+        trailing.push(0);
+        assert_eq!(decoder.decode(&instruction(trailing, 0)), invalid("1 trailing bytes"));
+        let truncated = swap_data()[..20].to_vec();
+        assert_eq!(decoder.decode(&instruction(truncated, 0)), invalid("unexpected end of data"));
+        let mut mode = swap_data();
+        mode[61] = 3;
+        assert_eq!(decoder.decode(&instruction(mode, 0)), invalid("invalid variant 3 of Mode"));
+    }
+
+    #[test]
+    fn test_decode_leaf() {
+        let decoder = decoder();
+        let mut leaf = SolanaLeaf {
+            one_sig_state: Pubkey::new_from_array([0x51; 32]),
+            one_sig_id: 1,
+            nonce: 0,
+            instruction: instruction(swap_data(), 3),
+            token_outflow_limits: vec![],
+            guarded_accounts: vec![],
+            vault_index: 0,
+            cluster_genesis_hash: None,
+            group: None,
+            not_before: None,
+            bounty: None,
+        };
+        assert_eq!(decoder.decode_leaf(&leaf).unwrap().unwrap().name, "swap");
+
+        // Programs without an IDL are left undecoded
+        leaf.instruction.program_id = POOL;
+        assert_eq!(decoder.decode_leaf(&leaf), Ok(None));
+    }
+
+    #[test]
+    fn test_add_idl_json() {
+        let mut decoder = InstructionDecoder::new();
+        assert!(matches!(decoder.add_idl_json("{}"), Err(SdkError::InvalidIdl(_))));
+        let idl = json!({
+            "address": "not a key",
+            "metadata": { "name": "swap", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [],
+        });
+        assert_eq!(
+            decoder.add_idl_json(&idl.to_string()),
+            Err(SdkError::InvalidIdl("invalid address not a key".into()))
+        );
+    }
+}
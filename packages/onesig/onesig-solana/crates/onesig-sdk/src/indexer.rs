@@ -0,0 +1,300 @@
+use anchor_lang::prelude::Pubkey;
+use onesig::{Address, Hash, PreviousConfig, SetConfigParams};
+
+use crate::events::{OneSigEvent, TransactionEvents};
+
+/// A change to the configuration of a OneSig
+#[derive(Clone)]
+pub enum ConfigChange {
+    Initialized {
+        one_sig_id: u64,
+        seed: Hash,
+        threshold: u8,
+        signers: Vec<Address>,
+        executors: Vec<Pubkey>,
+        executor_required: bool,
+        chain_id: Option<String>,
+    },
+    /// `params` set, replacing `previous`
+    Set {
+        params: SetConfigParams,
+        previous: PreviousConfig,
+    },
+    /// `executor` registered, executing leaves from `effective_at`; 0 if the event predates it
+    ExecutorAdded {
+        executor: Pubkey,
+        effective_at: i64,
+    },
+    /// `executor` deregistered, no longer executing leaves from `effective_at`; 0 if the event
+    /// predates it
+    ExecutorRemoved {
+        executor: Pubkey,
+        effective_at: i64,
+    },
+    ExecutorTargetsSet {
+        executor: Pubkey,
+        programs: Vec<Pubkey>,
+    },
+    ModuleAdded(Pubkey),
+    ModuleRemoved(Pubkey),
+    SpendingLimitSet {
+        mint: Pubkey,
+        amount: u64,
+        window: i64,
+    },
+    SpendingLimitRemoved {
+        mint: Pubkey,
+    },
+    Closed {
+        recipient: Pubkey,
+    },
+}
+
+/// A transaction a OneSig executed
+#[derive(Clone)]
+pub enum Execution {
+    /// A leaf of a signed merkle root, landed by `executor`, calling `program_id` with the data of
+    /// hash `ix_data_hash`
+    Leaf {
+        merkle_root: Hash,
+        nonce: u64,
+        executor: Pubkey,
+        program_id: Pubkey,
+        ix_data_hash: Hash,
+        /// Lamports the leaf declared it may spend
+        value: u64,
+        /// Lamports the vault actually spent
+        lamports_spent: u64,
+        /// Leaves executed under the root so far, this one included, if it was pre-verified
+        root_executions: Option<u32>,
+    },
+    /// A transaction of a module, from the vault at `vault_index`
+    Module { module: Pubkey, vault_index: u8 },
+}
+
+/// What an event did to a OneSig
+#[derive(Clone)]
+pub enum Activity {
+    Config(ConfigChange),
+    Execution(Execution),
+    /// The executor of a leaf was paid back from the vault, or the `fee_payer` that sponsored it
+    ExecutorReimbursed {
+        executor: Pubkey,
+        lamports: u64,
+        fee_payer: Option<Pubkey>,
+    },
+    /// Whoever landed the leaf at `nonce` was paid its bounty from the bounty escrow: `executor`,
+    /// or the fee payer that sponsored it as `recipient`
+    BountyPaid {
+        executor: Pubkey,
+        recipient: Pubkey,
+        nonce: u64,
+        lamports: u64,
+    },
+    /// The multisig withdrew `lamports` from the bounty escrow to `recipient`, or swept them to it
+    /// with the close of the OneSig
+    BountyEscrowWithdrawn {
+        recipient: Pubkey,
+        lamports: u64,
+    },
+    /// `executor` recorded the leaf `leaf`, at `nonce`, as stuck: its call failed in simulation,
+    /// with `reported_error_code` according to the executor (unverified)
+    LeafFailureRecorded {
+        leaf: Hash,
+        nonce: u64,
+        reported_error_code: u32,
+        executor: Pubkey,
+    },
+    /// The nonce restarted at `nonce` under `seed` without a leaf executing at it, from
+    /// `previous_nonce` under `previous_seed`
+    NonceSkipped {
+        previous_seed: Hash,
+        previous_nonce: u64,
+        seed: Hash,
+        nonce: u64,
+    },
+    /// Whether leaves can only be executed by registered executors changed, from `effective_at`.
+    /// The `SetExecutorRequired` making the change is among the configuration changes.
+    ExecutorRequiredSet {
+        executor_required: bool,
+        effective_at: i64,
+    },
+    /// A merkle root was verified into its `MerkleRootState`, whose rent `rent_payer` paid
+    MerkleRootVerified {
+        merkle_root: Hash,
+        expiry: i64,
+        rent_payer: Pubkey,
+    },
+    /// A verified merkle root was re-verified to expire at `expiry` rather than `previous_expiry`
+    MerkleRootExtended {
+        merkle_root: Hash,
+        previous_expiry: i64,
+        expiry: i64,
+    },
+}
+
+impl From<OneSigEvent> for Activity {
+    fn from(event: OneSigEvent) -> Self {
+        use ConfigChange as Config;
+        match event {
+            OneSigEvent::OneSigInitialized(event) => Self::Config(Config::Initialized {
+                one_sig_id: event.one_sig_id,
+                seed: event.seed,
+                threshold: event.threshold,
+                signers: event.signers,
+                executors: event.executors,
+                executor_required: event.executor_required,
+                chain_id: event.chain_id,
+            }),
+            OneSigEvent::ConfigSet(event) =>
+                Self::Config(Config::Set { params: event.params, previous: event.previous }),
+            OneSigEvent::ExecutorAdded(event) => Self::Config(Config::ExecutorAdded {
+                executor: event.executor,
+                effective_at: event.effective_at,
+            }),
+            OneSigEvent::ExecutorRemoved(event) => Self::Config(Config::ExecutorRemoved {
+                executor: event.executor,
+                effective_at: event.effective_at,
+            }),
+            OneSigEvent::ExecutorRequiredSet(event) => Self::ExecutorRequiredSet {
+                executor_required: event.executor_required,
+                effective_at: event.effective_at,
+            },
+            OneSigEvent::ExecutorTargetsSet(event) => Self::Config(Config::ExecutorTargetsSet {
+                executor: event.executor,
+                programs: event.programs,
+            }),
+            OneSigEvent::ModuleAdded(event) => Self::Config(Config::ModuleAdded(event.module)),
+            OneSigEvent::ModuleRemoved(event) => Self::Config(Config::ModuleRemoved(event.module)),
+            OneSigEvent::SpendingLimitSet(event) => Self::Config(Config::SpendingLimitSet {
+                mint: event.mint,
+                amount: event.amount,
+                window: event.window,
+            }),
+            OneSigEvent::SpendingLimitRemoved(event) =>
+                Self::Config(Config::SpendingLimitRemoved { mint: event.mint }),
+            OneSigEvent::OneSigClosed(event) =>
+                Self::Config(Config::Closed { recipient: event.recipient }),
+            OneSigEvent::TransactionExecuted(event) => Self::Execution(Execution::Leaf {
+                merkle_root: event.merkle_root,
+                nonce: event.nonce,
+                executor: event.executor,
+                program_id: event.program_id,
+                ix_data_hash: event.ix_data_hash,
+                value: event.value,
+                lamports_spent: event.lamports_spent,
+                root_executions: event.root_executions,
+            }),
+            OneSigEvent::ModuleTransactionExecuted(event) => Self::Execution(Execution::Module {
+                module: event.module,
+                vault_index: event.vault_index,
+            }),
+            OneSigEvent::ExecutorReimbursed(event) => Self::ExecutorReimbursed {
+                executor: event.executor,
+                lamports: event.lamports,
+                fee_payer: event.fee_payer,
+            },
+            OneSigEvent::BountyPaid(event) => Self::BountyPaid {
+                executor: event.executor,
+                recipient: event.recipient,
+                nonce: event.nonce,
+                lamports: event.lamports,
+            },
+            OneSigEvent::BountyEscrowWithdrawn(event) =>
+                Self::BountyEscrowWithdrawn { recipient: event.recipient, lamports: event.lamports },
+            OneSigEvent::LeafFailureRecorded(event) => Self::LeafFailureRecorded {
+                leaf: event.leaf,
+                nonce: event.nonce,
+                reported_error_code: event.reported_error_code,
+                executor: event.executor,
+            },
+            OneSigEvent::NonceSkipped(event) => Self::NonceSkipped {
+                previous_seed: event.previous_seed,
+                previous_nonce: event.previous_nonce,
+                seed: event.seed,
+                nonce: event.nonce,
+            },
+            OneSigEvent::MerkleRootVerified(event) => Self::MerkleRootVerified {
+                merkle_root: event.merkle_root,
+                expiry: event.expiry,
+                rent_payer: event.rent_payer,
+            },
+            OneSigEvent::MerkleRootExtended(event) => Self::MerkleRootExtended {
+                merkle_root: event.merkle_root,
+                previous_expiry: event.previous_expiry,
+                expiry: event.expiry,
+            },
+        }
+    }
+}
+
+/// An activity, and the transaction it happened in
+#[derive(Clone)]
+pub struct Record<A> {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub activity: A,
+}
+
+/// Everything that happened to a OneSig, oldest first, as its transaction history tells.
+///
+/// [`RpcClient::update_history`](crate::rpc::RpcClient::update_history) walks the history from
+/// where the previous walk stopped, so a history is built once and then kept up to date.
+#[derive(Clone)]
+pub struct History {
+    pub one_sig_account: Pubkey,
+    pub records: Vec<Record<Activity>>,
+    /// The newest transaction walked, the ones before it are in `records`
+    pub last_signature: Option<String>,
+}
+
+impl History {
+    /// History of the OneSig at `one_sig_account`, not walked yet
+    pub fn new(one_sig_account: Pubkey) -> Self {
+        Self { one_sig_account, records: vec![], last_signature: None }
+    }
+
+    /// Records the events about this OneSig of the transaction following the ones walked.
+    pub fn push(&mut self, events: TransactionEvents) {
+        let TransactionEvents { signature, slot, block_time, events, .. } = events;
+        for event in events {
+            if *event.one_sig_account() == self.one_sig_account {
+                self.records.push(Record {
+                    signature: signature.clone(),
+                    slot,
+                    block_time,
+                    activity: event.into(),
+                });
+            }
+        }
+        self.last_signature = Some(signature);
+    }
+
+    /// Changes to the configuration, in the order they were made
+    pub fn config_changes(&self) -> impl Iterator<Item = Record<&ConfigChange>> {
+        self.records.iter().filter_map(|record| match &record.activity {
+            Activity::Config(change) => Some(record.with(change)),
+            _ => None,
+        })
+    }
+
+    /// Executed transactions, in the order they were executed
+    pub fn executions(&self) -> impl Iterator<Item = Record<&Execution>> {
+        self.records.iter().filter_map(|record| match &record.activity {
+            Activity::Execution(execution) => Some(record.with(execution)),
+            _ => None,
+        })
+    }
+}
+
+impl<A> Record<A> {
+    fn with<B>(&self, activity: B) -> Record<B> {
+        Record {
+            signature: self.signature.clone(),
+            slot: self.slot,
+            block_time: self.block_time,
+            activity,
+        }
+    }
+}
@@ -0,0 +1,326 @@
+// Tests for replaying the history of a OneSig from its transactions.
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, Event};
+    use onesig::{
+        ConfigSet, ExecutorAdded, ExecutorReimbursed, ExecutorRequiredSet, NonceSkipped,
+        PreviousConfig, SetConfigParams, SpendingLimitSet, TransactionExecuted, EVENT_VERSION,
+    };
+    use onesig_core::Hash;
+    use serde_json::{json, Value};
+
+    use crate::{
+        error::SdkError,
+        events::{OneSigEvent, TransactionEvents},
+        indexer::{Activity, ConfigChange, Execution, History},
+        pda::EVENT_AUTHORITY_SEED,
+        rpc::{Commitment, RpcClient, RpcTransport, SIGNATURES_PAGE_LIMIT},
+    };
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0e; 32]);
+    const STATE: Pubkey = Pubkey::new_from_array([0x51; 32]);
+    const OTHER_STATE: Pubkey = Pubkey::new_from_array([0x52; 32]);
+
+    fn executed(one_sig_account: Pubkey, nonce: u64) -> TransactionExecuted {
+        TransactionExecuted {
+            one_sig_account,
+            merkle_root: Hash([0xaa; 32]),
+            nonce,
+            chain_id: None,
+            one_sig_id: 1,
+            executor: Pubkey::new_from_array([0xe0; 32]),
+            program_id: Pubkey::new_from_array([0xc0; 32]),
+            ix_data_hash: Hash([0xdd; 32]),
+            value: 0,
+            lamports_spent: 0,
+            root_executions: None,
+            event_version: EVENT_VERSION,
+        }
+    }
+
+    fn threshold_set(threshold: u8) -> ConfigSet {
+        ConfigSet {
+            one_sig_account: STATE,
+            params: SetConfigParams::SetThreshold(threshold),
+            previous: PreviousConfig::Threshold(1),
+            event_version: EVENT_VERSION,
+        }
+    }
+
+    fn transaction_events(
+        signature: &str,
+        slot: u64,
+        events: Vec<OneSigEvent>,
+    ) -> TransactionEvents {
+        TransactionEvents {
+            signature: signature.into(),
+            slot,
+            block_time: None,
+            err: None,
+            fee: None,
+            compute_units_consumed: None,
+            events,
+        }
+    }
+
+    #[test]
+    fn test_push() {
+        let mut history = History::new(STATE);
+        history.push(transaction_events(
+            "config",
+            10,
+            vec![
+                OneSigEvent::ConfigSet(threshold_set(2)),
+                OneSigEvent::SpendingLimitSet(SpendingLimitSet {
+                    one_sig_account: STATE,
+                    mint: Pubkey::default(),
+                    amount: 5,
+                    window: 3600,
+                    event_version: EVENT_VERSION,
+                }),
+            ],
+        ));
+        history.push(transaction_events(
+            "execute",
+            11,
+            vec![
+                OneSigEvent::TransactionExecuted(executed(STATE, 0)),
+                // Another instance of the program, in the same transaction
+                OneSigEvent::TransactionExecuted(executed(OTHER_STATE, 0)),
+                OneSigEvent::ExecutorReimbursed(ExecutorReimbursed {
+                    one_sig_account: STATE,
+                    executor: Pubkey::new_from_array([0xe1; 32]),
+                    lamports: 5000,
+                    event_version: EVENT_VERSION,
+                    fee_payer: None,
+                }),
+            ],
+        ));
+        history.push(transaction_events(
+            "reset",
+            12,
+            vec![OneSigEvent::NonceSkipped(NonceSkipped {
+                one_sig_account: STATE,
+                previous_seed: Hash([1; 32]),
+                previous_nonce: 1,
+                seed: Hash([2; 32]),
+                nonce: 0,
+                event_version: EVENT_VERSION,
+            })],
+        ));
+        history.push(transaction_events("unrelated", 13, vec![]));
+
+        assert_eq!(history.records.len(), 5);
+        assert_eq!(history.last_signature.as_deref(), Some("unrelated"));
+        assert!(matches!(
+            history.records[3].activity,
+            Activity::ExecutorReimbursed { lamports: 5000, .. }
+        ));
+        // Nonce trackers see the reset apart from the executions
+        assert!(matches!(
+            history.records[4].activity,
+            Activity::NonceSkipped { previous_nonce: 1, nonce: 0, .. }
+        ));
+
+        let changes: Vec<_> = history.config_changes().collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!((changes[0].signature.as_str(), changes[0].slot), ("config", 10));
+        assert!(matches!(
+            changes[0].activity,
+            ConfigChange::Set {
+                params: SetConfigParams::SetThreshold(2),
+                previous: PreviousConfig::Threshold(1),
+            }
+        ));
+        assert!(matches!(
+            changes[1].activity,
+            ConfigChange::SpendingLimitSet { amount: 5, window: 3600, .. }
+        ));
+
+        let executions: Vec<_> = history.executions().collect();
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].signature, "execute");
+        assert!(matches!(executions[0].activity, Execution::Leaf { nonce: 0, .. }));
+    }
+
+    #[test]
+    fn test_executor_lifecycle() {
+        let executor = Pubkey::new_from_array([0xe2; 32]);
+        let mut history = History::new(STATE);
+        history.push(transaction_events(
+            "executors",
+            10,
+            vec![
+                OneSigEvent::ExecutorAdded(ExecutorAdded {
+                    one_sig_account: STATE,
+                    executor,
+                    event_version: EVENT_VERSION,
+                    effective_at: 1_700_000_000,
+                }),
+                OneSigEvent::ConfigSet(ConfigSet {
+                    one_sig_account: STATE,
+                    params: SetConfigParams::SetExecutorRequired(true),
+                    previous: PreviousConfig::ExecutorRequired(false),
+                    event_version: EVENT_VERSION,
+                }),
+                OneSigEvent::ExecutorRequiredSet(ExecutorRequiredSet {
+                    one_sig_account: STATE,
+                    executor_required: true,
+                    effective_at: 1_700_000_000,
+                    event_version: EVENT_VERSION,
+                }),
+            ],
+        ));
+
+        assert!(matches!(
+            history.records[0].activity,
+            Activity::Config(ConfigChange::ExecutorAdded { executor: added, effective_at })
+                if added == executor && effective_at == 1_700_000_000
+        ));
+        // Relayers see the requirement apart from the configuration changes
+        assert!(matches!(
+            history.records[2].activity,
+            Activity::ExecutorRequiredSet { executor_required: true, effective_at: 1_700_000_000 }
+        ));
+        assert_eq!(history.config_changes().count(), 2);
+    }
+
+    /// Replays `responses` in order, recording the requests
+    struct MockTransport {
+        requests: RefCell<Vec<Value>>,
+        responses: RefCell<Vec<Value>>,
+    }
+
+    impl MockTransport {
+        fn new(responses: impl IntoIterator<Item = Value>) -> Self {
+            let mut responses: Vec<Value> = responses.into_iter().collect();
+            responses.reverse();
+            Self { requests: RefCell::default(), responses: RefCell::new(responses) }
+        }
+    }
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            self.requests.borrow_mut().push(serde_json::from_str(body).unwrap());
+            let response = self.responses.borrow_mut().pop();
+            response
+                .map(|result| json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string())
+                .ok_or(SdkError::Transport("closed".into()))
+        }
+    }
+
+    fn signature_info(signature: &str, slot: u64, err: Value) -> Value {
+        json!({ "signature": signature, "slot": slot, "blockTime": null, "err": err })
+    }
+
+    /// A `getTransaction` result emitting `events` through the event authority
+    fn transaction(slot: u64, events: &[Vec<u8>]) -> Value {
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID).0;
+        let instructions: Vec<Value> = events
+            .iter()
+            .map(|event| {
+                let data = [EVENT_IX_TAG_LE, event].concat();
+                json!({
+                    "programIdIndex": 1,
+                    "accounts": [2],
+                    "data": bs58::encode(data).into_string(),
+                })
+            })
+            .collect();
+        json!({
+            "slot": slot,
+            "blockTime": null,
+            "transaction": {
+                "message": {
+                    "accountKeys": [
+                        Pubkey::new_from_array([0x10; 32]).to_string(),
+                        PROGRAM_ID.to_string(),
+                        event_authority.to_string(),
+                    ],
+                },
+            },
+            "meta": { "err": null, "innerInstructions": [{ "index": 0, "instructions": instructions }] },
+        })
+    }
+
+    #[test]
+    fn test_update_history() {
+        let client = RpcClient::new(
+            MockTransport::new([
+                // Newest first
+                json!([
+                    signature_info("execute", 12, Value::Null),
+                    signature_info("failed", 11, json!({ "InstructionError": [0, "Foo"] })),
+                    signature_info("config", 10, Value::Null),
+                ]),
+                transaction(10, &[threshold_set(2).data()]),
+                transaction(12, &[executed(STATE, 0).data(), executed(STATE, 1).data()]),
+                json!([]),
+            ]),
+            Commitment::Processed,
+        );
+
+        let mut history = History::new(STATE);
+        client.update_history(&PROGRAM_ID, &mut history).unwrap();
+        let requests = client.transport().requests.borrow().clone();
+        assert_eq!(requests[0]["method"], "getSignaturesForAddress");
+        assert_eq!(
+            requests[0]["params"],
+            json!([
+                STATE.to_string(),
+                { "limit": 1000, "before": null, "until": null, "commitment": "confirmed" },
+            ])
+        );
+        // The failed transaction is not fetched
+        assert_eq!(requests[1]["params"][0], "config");
+        assert_eq!(requests[2]["params"][0], "execute");
+        assert_eq!(requests.len(), 3);
+
+        let slots: Vec<u64> = history.records.iter().map(|record| record.slot).collect();
+        assert_eq!(slots, [10, 12, 12]);
+        assert_eq!(history.config_changes().count(), 1);
+        assert_eq!(history.executions().count(), 2);
+        assert_eq!(history.last_signature.as_deref(), Some("execute"));
+
+        // The next walk starts after the last transaction walked
+        client.update_history(&PROGRAM_ID, &mut history).unwrap();
+        assert_eq!(client.transport().requests.borrow()[3]["params"][1]["until"], "execute");
+        assert_eq!(history.records.len(), 3);
+    }
+
+    #[test]
+    fn test_signatures_pages() {
+        let page: Vec<Value> = (0..SIGNATURES_PAGE_LIMIT as u64)
+            .map(|slot| signature_info(&format!("sig{slot}"), 100 - slot / 10, Value::Null))
+            .collect();
+        let client = RpcClient::new(
+            MockTransport::new([json!(page), json!([signature_info("oldest", 0, Value::Null)])]),
+            Commitment::default(),
+        );
+
+        let signatures = client.signatures_for_address(&STATE, Some("newest")).unwrap();
+        assert_eq!(signatures.len(), SIGNATURES_PAGE_LIMIT + 1);
+        assert_eq!(signatures.last().unwrap().signature, "oldest");
+        let requests = client.transport().requests.borrow().clone();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1]["params"][1]["before"], "sig999");
+        assert_eq!(requests[1]["params"][1]["until"], "newest");
+    }
+
+    #[test]
+    fn test_update_history_not_found() {
+        let client = RpcClient::new(
+            MockTransport::new([json!([signature_info("pruned", 10, Value::Null)]), Value::Null]),
+            Commitment::default(),
+        );
+
+        let mut history = History::new(STATE);
+        assert_eq!(
+            client.update_history(&PROGRAM_ID, &mut history).err(),
+            Some(SdkError::TransactionNotFound("pruned".into()))
+        );
+        assert_eq!(history.last_signature, None);
+    }
+}
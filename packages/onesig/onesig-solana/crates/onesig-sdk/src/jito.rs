@@ -0,0 +1,90 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    system_program,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::json;
+
+use crate::{
+    error::SdkError,
+    rpc::{field, RpcCall, RpcTransport},
+};
+
+/// Lamports the block engine takes at least as the tip of a bundle
+pub const MIN_JITO_TIP: u64 = 1_000;
+
+/// Transactions a bundle holds at most
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Discriminant of the system program's `Transfer`, a little-endian `u32`
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// Sends bundles to a Jito block engine, e.g. at
+/// `https://mainnet.block-engine.jito.wtf/api/v1/bundles`, which lands them atomically through the
+/// validators running the Jito client rather than through the public transaction queue.
+///
+/// The block engine only forwards bundles that tip one of its [tip
+/// accounts](Self::tip_accounts): put a [`tip_instruction`] in the last transaction of a bundle,
+/// so the tip is only paid if the bundle lands.
+pub struct JitoClient<T> {
+    transport: T,
+}
+
+impl<T: RpcTransport> JitoClient<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    fn call<R>(&self, call: RpcCall<R>) -> Result<R, SdkError> {
+        call.response(&self.transport.post(&call.body())?)
+    }
+
+    /// Accounts the block engine takes tips at
+    pub fn tip_accounts(&self) -> Result<Vec<Pubkey>, SdkError> {
+        self.call(RpcCall::new("getTipAccounts", json!([]), |result| {
+            let accounts: Vec<String> = field(&result, "")?;
+            accounts
+                .iter()
+                .map(|account| {
+                    account.parse().map_err(|_| {
+                        SdkError::InvalidRpcResponse(format!("invalid tip account {account}"))
+                    })
+                })
+                .collect()
+        }))
+    }
+
+    /// Sends signed wire transactions as a bundle, executing in order and all or none, returning
+    /// its id. The block engine does not report bundles that lose the auction: track the
+    /// signatures of the transactions to know whether they landed.
+    pub fn send_bundle(&self, transactions: &[Vec<u8>]) -> Result<String, SdkError> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(SdkError::JitoBundleSize(transactions.len()));
+        }
+        let transactions: Vec<String> = transactions
+            .iter()
+            .map(|transaction| BASE64_STANDARD.encode(transaction))
+            .collect();
+        self.call(RpcCall::new(
+            "sendBundle",
+            json!([transactions, { "encoding": "base64" }]),
+            |result| field(&result, ""),
+        ))
+    }
+}
+
+/// Transfers the `lamports` of the tip from `payer` to `tip_account`.
+pub fn tip_instruction(payer: &Pubkey, tip_account: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+    data.extend(lamports.to_le_bytes());
+    Instruction {
+        program_id: system_program::ID,
+        accounts: vec![AccountMeta::new(*payer, true), AccountMeta::new(*tip_account, false)],
+        data,
+    }
+}
@@ -0,0 +1,82 @@
+// Tests for the block engine requests of Jito bundles and their tips.
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+        system_program,
+    };
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde_json::{json, Value};
+
+    use crate::{
+        error::SdkError,
+        jito::{tip_instruction, JitoClient, MAX_BUNDLE_TRANSACTIONS},
+        rpc::RpcTransport,
+    };
+
+    /// Answers every request with `response`, recording the requests
+    struct MockTransport {
+        requests: RefCell<Vec<Value>>,
+        response: Value,
+    }
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            self.requests.borrow_mut().push(serde_json::from_str(body).unwrap());
+            Ok(self.response.to_string())
+        }
+    }
+
+    fn client(result: Value) -> JitoClient<MockTransport> {
+        JitoClient::new(MockTransport {
+            requests: RefCell::default(),
+            response: json!({ "jsonrpc": "2.0", "id": 1, "result": result }),
+        })
+    }
+
+    #[test]
+    fn test_tip_instruction() {
+        let (payer, tip_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![2, 0, 0, 0];
+        data.extend(10_000u64.to_le_bytes());
+        assert_eq!(
+            tip_instruction(&payer, &tip_account, 10_000),
+            Instruction {
+                program_id: system_program::ID,
+                accounts: vec![AccountMeta::new(payer, true), AccountMeta::new(tip_account, false)],
+                data,
+            }
+        );
+    }
+
+    #[test]
+    fn test_tip_accounts() {
+        let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let jito = client(json!(accounts.map(|account| account.to_string())));
+        assert_eq!(jito.tip_accounts(), Ok(accounts.to_vec()));
+        assert_eq!(jito.transport().requests.borrow()[0]["method"], "getTipAccounts");
+
+        let invalid = client(json!(["not a key"])).tip_accounts();
+        assert!(matches!(invalid, Err(SdkError::InvalidRpcResponse(_))));
+    }
+
+    #[test]
+    fn test_send_bundle() {
+        let jito = client(json!("bundle-id"));
+        let transactions = vec![vec![1, 2, 3], vec![4]];
+        assert_eq!(jito.send_bundle(&transactions), Ok("bundle-id".into()));
+
+        let request = jito.transport().requests.borrow()[0].clone();
+        assert_eq!(request["method"], "sendBundle");
+        let encoded = [BASE64_STANDARD.encode([1, 2, 3]), BASE64_STANDARD.encode([4])];
+        assert_eq!(request["params"], json!([encoded, { "encoding": "base64" }]));
+
+        assert_eq!(jito.send_bundle(&[]), Err(SdkError::JitoBundleSize(0)));
+        let too_many = vec![vec![0]; MAX_BUNDLE_TRANSACTIONS + 1];
+        assert_eq!(jito.send_bundle(&too_many), Err(SdkError::JitoBundleSize(6)));
+        assert_eq!(jito.transport().requests.borrow().len(), 1);
+    }
+}
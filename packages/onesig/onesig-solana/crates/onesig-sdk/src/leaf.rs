@@ -0,0 +1,231 @@
+use onesig_core::{
+    leaf::{encode_instruction, encode_leaf, LeafAccountMeta, LeafGroup, LeafTokenOutflowLimit},
+    Hash, MERKLE_LEAF_ENCODING_VERSION,
+};
+use serde::{Deserialize, Serialize};
+use solana_keccak_hasher as keccak;
+use solana_pubkey::Pubkey;
+
+use crate::encoding::{base58, base58_list, decimal, hex, hex_option};
+
+/// Length of an EVM address
+pub const EVM_ADDRESS_LEN: usize = 20;
+
+const WORD_LEN: usize = 32;
+
+/// The instruction a Solana leaf executes, with the lamports it may spend: the program's
+/// `OneSigInstruction`, without its Anchor dependencies.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OneSigInstruction {
+    #[serde(with = "base58")]
+    pub program_id: Pubkey,
+    pub accounts: Vec<OneSigAccountMeta>,
+    #[serde(with = "hex")]
+    pub data: Vec<u8>,
+    pub value: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OneSigAccountMeta {
+    #[serde(with = "base58")]
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Cap on the amount of `mint` a leaf may move out of the OneSig's token accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOutflowLimit {
+    #[serde(with = "base58")]
+    pub mint: Pubkey,
+    pub max_amount: u64,
+}
+
+/// Position of a leaf among the `len` leaves at consecutive nonces that only execute together, in
+/// one Solana transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionGroup {
+    pub index: u8,
+    pub len: u8,
+}
+
+/// A transaction of a Solana OneSig: one instruction at `nonce`, with the guards it declares.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolanaLeaf {
+    #[serde(with = "base58")]
+    pub one_sig_state: Pubkey,
+    pub one_sig_id: u64,
+    pub nonce: u64,
+    pub instruction: OneSigInstruction,
+    pub token_outflow_limits: Vec<TokenOutflowLimit>,
+    #[serde(with = "base58_list")]
+    pub guarded_accounts: Vec<Pubkey>,
+    pub vault_index: u8,
+    /// Genesis hash the OneSig binds leaves to, see `OneSigState::cluster_genesis_hash`
+    #[serde(with = "hex_option", default)]
+    pub cluster_genesis_hash: Option<Hash>,
+    /// Group of leaves the leaf executes with, if any
+    #[serde(default)]
+    pub group: Option<TransactionGroup>,
+    /// Unix time before which the program refuses to execute the leaf, if any
+    #[serde(default)]
+    pub not_before: Option<i64>,
+    /// Lamports paid from the bounty escrow to whoever executes the leaf while
+    /// `executor_required` is not set, if any
+    #[serde(default)]
+    pub bounty: Option<u64>,
+}
+
+impl SolanaLeaf {
+    /// Leaf hash, as `execute_transaction` encodes it
+    pub fn hash(&self) -> Hash {
+        let instruction = &self.instruction;
+        let encoded_instruction = encode_instruction(
+            instruction.program_id.as_array(),
+            instruction.accounts.iter().map(|account| LeafAccountMeta {
+                pubkey: account.pubkey.as_array(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            }),
+            &instruction.data,
+            instruction.value,
+        );
+        encode_leaf(
+            self.one_sig_state.as_array(),
+            self.one_sig_id,
+            self.nonce,
+            &encoded_instruction,
+            self.token_outflow_limits.iter().map(|limit| LeafTokenOutflowLimit {
+                mint: limit.mint.as_array(),
+                max_amount: limit.max_amount,
+            }),
+            self.guarded_accounts.iter().map(Pubkey::as_array),
+            self.vault_index,
+            self.cluster_genesis_hash.as_ref(),
+            self.group.map(|group| LeafGroup { index: group.index, len: group.len }),
+            self.not_before,
+            self.bounty,
+        )
+    }
+}
+
+/// A call of an EVM OneSig transaction, the `Call` struct of `OneSig.sol`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmCall {
+    #[serde(with = "hex")]
+    pub to: [u8; EVM_ADDRESS_LEN],
+    /// Wei sent with the call. `uint256` on-chain, but no amount of ether exceeds a `u128`.
+    #[serde(with = "decimal")]
+    pub value: u128,
+    #[serde(with = "hex")]
+    pub data: Vec<u8>,
+}
+
+/// A transaction of an EVM OneSig: calls executed together at `nonce`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmLeaf {
+    #[serde(with = "hex")]
+    pub one_sig_address: [u8; EVM_ADDRESS_LEN],
+    pub one_sig_id: u64,
+    pub nonce: u64,
+    pub calls: Vec<EvmCall>,
+}
+
+impl EvmLeaf {
+    /// Leaf hash, as `OneSig.encodeLeaf` computes it
+    pub fn hash(&self) -> Hash {
+        let leaf = keccak::hashv(&[
+            &MERKLE_LEAF_ENCODING_VERSION,
+            &self.one_sig_id.to_be_bytes(),
+            &address_word(&self.one_sig_address),
+            &self.nonce.to_be_bytes(),
+            &encode_evm_calls(&self.calls),
+        ]);
+        keccak::hash(leaf.as_ref()).into()
+    }
+}
+
+/// A leaf of a OneSig tree, on any chain.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "chain", rename_all = "lowercase")]
+pub enum Leaf {
+    Solana(SolanaLeaf),
+    Evm(EvmLeaf),
+}
+
+impl Leaf {
+    pub fn one_sig_id(&self) -> u64 {
+        match self {
+            Leaf::Solana(leaf) => leaf.one_sig_id,
+            Leaf::Evm(leaf) => leaf.one_sig_id,
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Leaf::Solana(leaf) => leaf.nonce,
+            Leaf::Evm(leaf) => leaf.nonce,
+        }
+    }
+
+    pub fn hash(&self) -> Hash {
+        match self {
+            Leaf::Solana(leaf) => leaf.hash(),
+            Leaf::Evm(leaf) => leaf.hash(),
+        }
+    }
+}
+
+impl From<SolanaLeaf> for Leaf {
+    fn from(leaf: SolanaLeaf) -> Self {
+        Leaf::Solana(leaf)
+    }
+}
+
+impl From<EvmLeaf> for Leaf {
+    fn from(leaf: EvmLeaf) -> Self {
+        Leaf::Evm(leaf)
+    }
+}
+
+/// ABI encoding of a `Call[]`, `abi.encode(calls)`.
+pub(crate) fn encode_evm_calls(calls: &[EvmCall]) -> Vec<u8> {
+    let padded_len = |len: usize| len.div_ceil(WORD_LEN) * WORD_LEN;
+    let mut encoded = Vec::new();
+    // Offset of the array, its length, then the offset of each call from the end of the length
+    encoded.extend(uint_word(WORD_LEN as u128));
+    encoded.extend(uint_word(calls.len() as u128));
+    let mut offset = calls.len() * WORD_LEN;
+    for call in calls {
+        encoded.extend(uint_word(offset as u128));
+        // `to`, `value`, the offset of `data` and its length, then `data` itself
+        offset += 4 * WORD_LEN + padded_len(call.data.len());
+    }
+    for call in calls {
+        encoded.extend(address_word(&call.to));
+        encoded.extend(uint_word(call.value));
+        encoded.extend(uint_word(3 * WORD_LEN as u128));
+        encoded.extend(uint_word(call.data.len() as u128));
+        encoded.extend(&call.data);
+        encoded.resize(encoded.len() + padded_len(call.data.len()) - call.data.len(), 0);
+    }
+    encoded
+}
+
+fn uint_word(value: u128) -> [u8; WORD_LEN] {
+    let mut word = [0; WORD_LEN];
+    word[WORD_LEN - 16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn address_word(address: &[u8; EVM_ADDRESS_LEN]) -> [u8; WORD_LEN] {
+    let mut word = [0; WORD_LEN];
+    word[WORD_LEN - EVM_ADDRESS_LEN..].copy_from_slice(address);
+    word
+}
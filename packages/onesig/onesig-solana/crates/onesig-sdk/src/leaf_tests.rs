@@ -0,0 +1,113 @@
+// Tests that leaves hash like the program and `OneSig.sol` encode them.
+#[cfg(test)]
+mod tests {
+    use onesig_core::Hash;
+    use solana_keccak_hasher as keccak;
+    use solana_pubkey::Pubkey;
+
+    use crate::leaf::{
+        encode_evm_calls, EvmCall, EvmLeaf, OneSigAccountMeta, OneSigInstruction, SolanaLeaf,
+        TokenOutflowLimit,
+    };
+
+    fn word(last_bytes: &[u8]) -> Vec<u8> {
+        let mut word = vec![0; 32 - last_bytes.len()];
+        word.extend_from_slice(last_bytes);
+        word
+    }
+
+    #[test]
+    fn test_solana_leaf_matches_vectors() {
+        let pubkey = |bytes: &[u8]| Pubkey::try_from(bytes).unwrap();
+        for vector in onesig_test_vectors::load().solana_leaves {
+            let leaf = SolanaLeaf {
+                one_sig_state: pubkey(&vector.one_sig_state),
+                one_sig_id: vector.one_sig_id,
+                nonce: vector.nonce,
+                instruction: OneSigInstruction {
+                    program_id: pubkey(&vector.program_id),
+                    accounts: vector
+                        .accounts
+                        .iter()
+                        .map(|account| OneSigAccountMeta {
+                            pubkey: pubkey(&account.pubkey),
+                            is_signer: account.is_signer,
+                            is_writable: account.is_writable,
+                        })
+                        .collect(),
+                    data: vector.data,
+                    value: vector.value,
+                },
+                token_outflow_limits: vector
+                    .token_outflow_limits
+                    .iter()
+                    .map(|limit| TokenOutflowLimit {
+                        mint: pubkey(&limit.mint),
+                        max_amount: limit.max_amount,
+                    })
+                    .collect(),
+                guarded_accounts: vector
+                    .guarded_accounts
+                    .iter()
+                    .map(|account| pubkey(account))
+                    .collect(),
+                vault_index: vector.vault_index,
+                cluster_genesis_hash: (!vector.cluster_genesis_hash.is_empty())
+                    .then(|| vector.cluster_genesis_hash.clone().try_into().unwrap()),
+                group: None,
+                not_before: None,
+                bounty: None,
+            };
+            assert_eq!(leaf.hash().0.to_vec(), vector.leaf);
+        }
+    }
+
+    #[test]
+    fn test_encode_evm_calls() {
+        let calls = [
+            EvmCall { to: [0x11; 20], value: 1, data: vec![0xab, 0xcd] },
+            EvmCall { to: [0x22; 20], value: 0, data: vec![] },
+        ];
+
+        let expected = [
+            word(&[0x20]),
+            word(&[2]),
+            // The first call takes 4 words plus one of data
+            word(&[0x40]),
+            word(&[0x40 + 5 * 32]),
+            word(&[0x11; 20]),
+            word(&[1]),
+            word(&[0x60]),
+            word(&[2]),
+            [vec![0xab, 0xcd], vec![0; 30]].concat(),
+            word(&[0x22; 20]),
+            word(&[]),
+            word(&[0x60]),
+            word(&[]),
+        ]
+        .concat();
+        assert_eq!(encode_evm_calls(&calls), expected);
+        assert_eq!(encode_evm_calls(&[]), [word(&[0x20]), word(&[])].concat());
+    }
+
+    #[test]
+    fn test_evm_leaf_hash() {
+        let leaf = EvmLeaf {
+            one_sig_address: [0x33; 20],
+            one_sig_id: 101,
+            nonce: 7,
+            calls: vec![EvmCall { to: [0x11; 20], value: 1, data: vec![0xab, 0xcd] }],
+        };
+
+        let inner = keccak::hashv(&[
+            &[1],
+            &101u64.to_be_bytes(),
+            &word(&[0x33; 20]),
+            &7u64.to_be_bytes(),
+            &encode_evm_calls(&leaf.calls),
+        ]);
+        let expected: Hash = keccak::hash(inner.as_ref()).into();
+        // `Hash` is not `Debug`
+        assert!(leaf.hash() == expected);
+    }
+}
@@ -0,0 +1,186 @@
+//! Off-chain OneSig SDK.
+//!
+//! [`OneSigTree`] builds the merkle tree of a batch of Solana and EVM leaves the same way as
+//! `makeOneSigTree` of `@layerzerolabs/onesig-core`, hashing Solana leaves with the program's own
+//! encoding, so roots, proofs and the EIP-712 digest to sign are interchangeable with the ones of
+//! the TypeScript tooling. [`LeafProof`] carries a proof to executors, as the TypeScript tooling's
+//! hex JSON or as compact bytes.
+//!
+//! [`OneSigClient`] builds the program's instructions, with their PDAs and event CPI accounts, for
+//! integrators that do not go through the TypeScript client. It is behind the default `client`
+//! feature; without it the crate does not depend on the program nor Anchor, and builds for
+//! `wasm32-unknown-unknown`, so browser signer UIs can reuse the leaf encoding, trees and digests.
+//! [`RpcClient`] and [`AsyncRpcClient`] sign and send those instructions over any HTTP client,
+//! e.g. the `HttpTransport` of the `blocking` feature or the `AsyncHttpTransport` of the `tokio`
+//! feature, after a [`Preflight`] simulation has decoded why the program would reject them, in
+//! legacy transactions or in [`MessageV0`]s loading the accounts of an instance from a
+//! [`LookupTable`], with a compute budget sized by simulation and priced from recent fees per
+//! [`ComputeBudgetOptions`]. Transactions advancing a [`NonceAccount`] stay valid past the
+//! blockhash window, so air-gapped operators can sign them offline. A [`Submitter`] broadcasts
+//! them through several endpoints and tracks them until confirmed, or lands them in bundles of a
+//! Jito block engine with a [`JitoClient`]. [`OneSigAccount`] decodes the
+//! state account, with its signers, for tools showing or acting on the current configuration.
+//!
+//! [`TransactionEvents`] decodes the events the program emits through self-CPIs, and an
+//! [`EventSubscription`] yields them as transactions land, for monitoring tools. A [`History`]
+//! replays them from the transaction history of an instance, as typed config changes and
+//! executions. Geyser plugins and Yellowstone gRPC consumers, which stream raw accounts and
+//! transactions, decode them with [`AccountUpdate`] and [`events_from_inner_instructions`], as the
+//! `onesig-yellowstone` crate does.
+//!
+//! [`merkle_root_message`] and [`verify_merkle_root_signatures`] build and check the EIP-712
+//! messages approving roots exactly as the program does, for signers on any stack, and
+//! [`pack_signatures`] packs the collected signatures in the order the program checks them.
+//! Signatures collected with ethers or through a Safe are imported with
+//! [`import_ethers_signatures`] and [`import_safe_confirmations`].
+//! Behind the default `idl` feature, an [`InstructionDecoder`] decodes the instruction of each
+//! leaf with the Anchor IDL of the program it calls, so signers review what they approve.
+//!
+//! [`RootSigner`] signs roots with a local key, a Ledger, or any remote signer, so the code
+//! collecting signatures does not depend on where the keys are.
+//!
+//! [`ProposalBundle`] carries a proposal, from its leaves to its signatures, as a single JSON file
+//! passed between the proposer, the signers and the executors. A [`TreeCoordinator`] assembles
+//! the leaves of a proposal spanning Solana and EVM OneSigs, numbering each instance's leaves from
+//! its current nonce, and hands the executor of each instance the proofs of its own leaves.
+
+pub mod bundle;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "client")]
+pub mod compute_budget;
+pub mod coordinator;
+pub mod digest;
+mod encoding;
+pub mod error;
+#[cfg(feature = "client")]
+pub mod events;
+#[cfg(any(feature = "blocking", feature = "tokio"))]
+pub mod http;
+#[cfg(feature = "idl")]
+pub mod idl;
+#[cfg(feature = "client")]
+pub mod indexer;
+#[cfg(feature = "client")]
+pub mod jito;
+pub mod leaf;
+#[cfg(feature = "client")]
+pub mod lookup_table;
+#[cfg(feature = "client")]
+pub mod nonce;
+#[cfg(feature = "client")]
+pub mod pda;
+#[cfg(feature = "client")]
+pub mod preflight;
+pub mod proof;
+#[cfg(feature = "client")]
+pub mod rpc;
+pub mod signature_import;
+pub mod signatures;
+pub mod signer;
+#[cfg(feature = "client")]
+pub mod state;
+#[cfg(feature = "client")]
+pub mod stream;
+#[cfg(feature = "client")]
+pub mod submission;
+#[cfg(feature = "client")]
+pub mod transaction;
+pub mod tree;
+
+#[cfg(test)]
+mod bundle_tests;
+#[cfg(all(test, feature = "client"))]
+mod client_tests;
+#[cfg(all(test, feature = "client"))]
+mod compute_budget_tests;
+#[cfg(test)]
+mod coordinator_tests;
+#[cfg(test)]
+mod digest_tests;
+#[cfg(all(test, feature = "client"))]
+mod events_tests;
+#[cfg(all(test, any(feature = "blocking", feature = "tokio")))]
+mod http_tests;
+#[cfg(all(test, feature = "idl"))]
+mod idl_tests;
+#[cfg(all(test, feature = "client"))]
+mod indexer_tests;
+#[cfg(all(test, feature = "client"))]
+mod jito_tests;
+#[cfg(test)]
+mod leaf_tests;
+#[cfg(all(test, feature = "client"))]
+mod lookup_table_tests;
+#[cfg(all(test, feature = "client"))]
+mod merkle_tests;
+#[cfg(all(test, feature = "client"))]
+mod nonce_tests;
+#[cfg(all(test, feature = "client"))]
+mod preflight_tests;
+#[cfg(test)]
+mod proof_tests;
+#[cfg(all(test, feature = "client"))]
+mod rpc_tests;
+#[cfg(test)]
+mod signature_import_tests;
+#[cfg(test)]
+mod signatures_tests;
+#[cfg(test)]
+mod signer_tests;
+#[cfg(all(test, feature = "client"))]
+mod state_tests;
+#[cfg(all(test, feature = "client"))]
+mod stream_tests;
+#[cfg(all(test, feature = "client"))]
+mod submission_tests;
+#[cfg(all(test, feature = "client"))]
+mod transaction_size_tests;
+#[cfg(all(test, feature = "client"))]
+mod transaction_tests;
+#[cfg(test)]
+mod tree_tests;
+
+pub use bundle::*;
+#[cfg(feature = "client")]
+pub use client::*;
+#[cfg(feature = "client")]
+pub use compute_budget::*;
+pub use coordinator::*;
+pub use digest::*;
+pub use error::*;
+#[cfg(feature = "client")]
+pub use events::*;
+#[cfg(any(feature = "blocking", feature = "tokio"))]
+pub use http::*;
+#[cfg(feature = "idl")]
+pub use idl::*;
+#[cfg(feature = "client")]
+pub use indexer::*;
+#[cfg(feature = "client")]
+pub use jito::*;
+pub use leaf::*;
+#[cfg(feature = "client")]
+pub use lookup_table::*;
+#[cfg(feature = "client")]
+pub use nonce::*;
+pub use onesig_core::Hash;
+#[cfg(feature = "client")]
+pub use pda::*;
+#[cfg(feature = "client")]
+pub use preflight::*;
+pub use proof::*;
+#[cfg(feature = "client")]
+pub use rpc::*;
+pub use signature_import::*;
+pub use signatures::*;
+pub use signer::*;
+#[cfg(feature = "client")]
+pub use state::*;
+#[cfg(feature = "client")]
+pub use stream::*;
+#[cfg(feature = "client")]
+pub use submission::*;
+#[cfg(feature = "client")]
+pub use transaction::*;
+pub use tree::*;
@@ -0,0 +1,136 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    system_program,
+};
+
+use crate::error::SdkError;
+
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("AddressLookupTab1e1111111111111111111111111");
+
+/// Size of the metadata leading a lookup table account, before its addresses
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Addresses a lookup table holds at most
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// Addresses one extension adds at most, so its transaction stays under the size limit
+pub const LOOKUP_TABLE_MAX_EXTEND: usize = 30;
+
+// Discriminants of the program's bincode-encoded instructions and account state
+const CREATE_LOOKUP_TABLE: u32 = 0;
+const EXTEND_LOOKUP_TABLE: u32 = 2;
+const LOOKUP_TABLE_STATE: u32 = 1;
+
+/// An address lookup table, as v0 messages load accounts from it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable {
+    pub address: Pubkey,
+    /// `None` once frozen
+    pub authority: Option<Pubkey>,
+    pub addresses: Vec<Pubkey>,
+}
+
+impl LookupTable {
+    /// Decodes the data of the lookup table account at `address`.
+    pub fn from_account_data(address: Pubkey, data: &[u8]) -> Result<Self, SdkError> {
+        let invalid = || SdkError::InvalidLookupTable(address);
+        if data.len() < LOOKUP_TABLE_META_SIZE ||
+            (data.len() - LOOKUP_TABLE_META_SIZE) % 32 != 0 ||
+            data[..4] != LOOKUP_TABLE_STATE.to_le_bytes()
+        {
+            return Err(invalid());
+        }
+        // State tag, deactivation slot, last extended slot and its start index, then the authority
+        let authority = match data[21] {
+            0 => None,
+            1 => Some(Pubkey::try_from(&data[22..54]).map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+        let addresses = data[LOOKUP_TABLE_META_SIZE..]
+            .chunks_exact(32)
+            .map(|address| Pubkey::try_from(address).map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { address, authority, addresses })
+    }
+}
+
+/// Address of the table `authority` creates at `recent_slot`, with its bump
+pub fn lookup_table_address(authority: &Pubkey, recent_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), &recent_slot.to_le_bytes()],
+        &ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+    )
+}
+
+/// Creates a table of `authority`, paid by `payer`, returning it with its address.
+///
+/// `recent_slot` must be a slot the cluster still has in its slot hashes, e.g. the current one.
+pub fn create_lookup_table(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    let (address, bump) = lookup_table_address(authority, recent_slot);
+    let mut data = CREATE_LOOKUP_TABLE.to_le_bytes().to_vec();
+    data.extend(recent_slot.to_le_bytes());
+    data.push(bump);
+    let instruction = Instruction {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(address, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    };
+    (instruction, address)
+}
+
+/// Appends `addresses` to `lookup_table`, signed by its authority; `payer` tops up its rent, which
+/// can be left out if the table already holds enough.
+pub fn extend_lookup_table(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    payer: Option<&Pubkey>,
+    addresses: &[Pubkey],
+) -> Instruction {
+    let mut data = EXTEND_LOOKUP_TABLE.to_le_bytes().to_vec();
+    data.extend((addresses.len() as u64).to_le_bytes());
+    for address in addresses {
+        data.extend_from_slice(address.as_ref());
+    }
+    let mut accounts =
+        vec![AccountMeta::new(*lookup_table, false), AccountMeta::new_readonly(*authority, true)];
+    if let Some(payer) = payer {
+        accounts.push(AccountMeta::new(*payer, true));
+        accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    }
+    Instruction { program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID, accounts, data }
+}
+
+/// Extends `lookup_table` with those of `addresses` it does not hold yet, in as many instructions
+/// as fit a transaction each.
+pub fn extend_lookup_table_missing(
+    lookup_table: &LookupTable,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<Vec<Instruction>, SdkError> {
+    let mut missing: Vec<Pubkey> = vec![];
+    for address in addresses {
+        if !lookup_table.addresses.contains(address) && !missing.contains(address) {
+            missing.push(*address);
+        }
+    }
+    let len = lookup_table.addresses.len() + missing.len();
+    if len > LOOKUP_TABLE_MAX_ADDRESSES {
+        return Err(SdkError::LookupTableFull(len));
+    }
+    Ok(missing
+        .chunks(LOOKUP_TABLE_MAX_EXTEND)
+        .map(|chunk| extend_lookup_table(&lookup_table.address, authority, Some(payer), chunk))
+        .collect())
+}
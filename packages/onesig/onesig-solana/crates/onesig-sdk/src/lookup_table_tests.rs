@@ -0,0 +1,158 @@
+// Tests for the address lookup table instructions and account decoding.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, solana_program::instruction::AccountMeta, system_program};
+
+    use crate::{
+        client::OneSigClient,
+        error::SdkError,
+        lookup_table::{
+            create_lookup_table, extend_lookup_table, extend_lookup_table_missing,
+            lookup_table_address, LookupTable, ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+            LOOKUP_TABLE_MAX_EXTEND, LOOKUP_TABLE_META_SIZE,
+        },
+    };
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    /// Account data of an active table
+    fn account_data(authority: Option<&Pubkey>, addresses: &[Pubkey]) -> Vec<u8> {
+        let mut data = vec![1, 0, 0, 0];
+        data.extend(u64::MAX.to_le_bytes());
+        data.extend(7u64.to_le_bytes());
+        data.push(0);
+        match authority {
+            Some(authority) => {
+                data.push(1);
+                data.extend_from_slice(authority.as_ref());
+            },
+            None => data.extend([0; 33]),
+        }
+        data.extend([0, 0]);
+        assert_eq!(data.len(), LOOKUP_TABLE_META_SIZE);
+        for address in addresses {
+            data.extend_from_slice(address.as_ref());
+        }
+        data
+    }
+
+    #[test]
+    fn test_create_lookup_table() {
+        let (instruction, address) = create_lookup_table(&key(1), &key(2), 300);
+        let (expected, bump) = lookup_table_address(&key(1), 300);
+        assert_eq!(address, expected);
+        assert_eq!(
+            address,
+            Pubkey::find_program_address(
+                &[key(1).as_ref(), &300u64.to_le_bytes()],
+                &ADDRESS_LOOKUP_TABLE_PROGRAM_ID
+            )
+            .0
+        );
+        assert_eq!(instruction.program_id, ADDRESS_LOOKUP_TABLE_PROGRAM_ID);
+        assert_eq!(
+            instruction.accounts,
+            [
+                AccountMeta::new(address, false),
+                AccountMeta::new_readonly(key(1), false),
+                AccountMeta::new(key(2), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ]
+        );
+        let mut data = vec![0, 0, 0, 0];
+        data.extend(300u64.to_le_bytes());
+        data.push(bump);
+        assert_eq!(instruction.data, data);
+    }
+
+    #[test]
+    fn test_extend_lookup_table() {
+        let instruction = extend_lookup_table(&key(9), &key(1), Some(&key(2)), &[key(3), key(4)]);
+        assert_eq!(
+            instruction.accounts,
+            [
+                AccountMeta::new(key(9), false),
+                AccountMeta::new_readonly(key(1), true),
+                AccountMeta::new(key(2), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ]
+        );
+        let mut data = vec![2, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(key(3).as_ref());
+        data.extend_from_slice(key(4).as_ref());
+        assert_eq!(instruction.data, data);
+
+        let instruction = extend_lookup_table(&key(9), &key(1), None, &[]);
+        assert_eq!(instruction.accounts.len(), 2);
+    }
+
+    #[test]
+    fn test_extend_lookup_table_missing() {
+        let table =
+            LookupTable { address: key(9), authority: Some(key(1)), addresses: vec![key(3)] };
+        let addresses: Vec<Pubkey> = (0..40).map(|i| key(i % 35)).collect();
+
+        let instructions =
+            extend_lookup_table_missing(&table, &key(1), &key(2), &addresses).unwrap();
+        // 35 distinct addresses, one already in the table
+        assert_eq!(instructions.len(), 2);
+        let count = |data: &[u8]| u64::from_le_bytes(data[4..12].try_into().unwrap()) as usize;
+        assert_eq!(count(&instructions[0].data), LOOKUP_TABLE_MAX_EXTEND);
+        assert_eq!(count(&instructions[1].data), 34 - LOOKUP_TABLE_MAX_EXTEND);
+        assert!(!instructions[0].data.windows(32).any(|window| window == key(3).as_ref()));
+
+        let full = LookupTable { addresses: vec![key(0); 250], ..table };
+        assert_eq!(
+            extend_lookup_table_missing(&full, &key(1), &key(2), &addresses),
+            Err(SdkError::LookupTableFull(284))
+        );
+    }
+
+    #[test]
+    fn test_from_account_data() {
+        let data = account_data(Some(&key(1)), &[key(3), key(4)]);
+        assert_eq!(
+            LookupTable::from_account_data(key(9), &data),
+            Ok(LookupTable {
+                address: key(9),
+                authority: Some(key(1)),
+                addresses: vec![key(3), key(4)]
+            })
+        );
+        let frozen = LookupTable::from_account_data(key(9), &account_data(None, &[])).unwrap();
+        assert_eq!(frozen.authority, None);
+        assert!(frozen.addresses.is_empty());
+
+        let invalid = Err(SdkError::InvalidLookupTable(key(9)));
+        assert_eq!(
+            LookupTable::from_account_data(key(9), &data[..LOOKUP_TABLE_META_SIZE - 1]),
+            invalid
+        );
+        assert_eq!(LookupTable::from_account_data(key(9), &data[..data.len() - 1]), invalid);
+        let mut uninitialized = data.clone();
+        uninitialized[0] = 0;
+        assert_eq!(LookupTable::from_account_data(key(9), &uninitialized), invalid);
+    }
+
+    #[test]
+    fn test_lookup_table_addresses() {
+        let client = OneSigClient::with_program_id(key(0xee), key(0x51));
+        let addresses =
+            client.lookup_table_addresses(1, &[key(0x70), system_program::ID, key(0x70)]);
+        assert_eq!(
+            addresses,
+            [
+                key(0x51),
+                client.pda.vault_signer(1).0,
+                client.pda.spending_limits().0,
+                client.pda.target_programs().0,
+                client.pda.event_authority().0,
+                system_program::ID,
+                key(0x70),
+            ]
+        );
+        assert!(!addresses.contains(&client.program_id()));
+    }
+}
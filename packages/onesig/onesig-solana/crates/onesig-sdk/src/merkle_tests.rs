@@ -0,0 +1,215 @@
+// Property tests that trees of generated leaves prove every leaf to the program's merkle
+// validation, and nothing else: not a mutated leaf, nor a mutated or borrowed proof.
+#[cfg(test)]
+mod tests {
+    use onesig::validation::merkle::MerkleValidator;
+    use onesig_core::Hash;
+    use proptest::{collection::vec, option, prelude::*};
+    use solana_pubkey::Pubkey;
+
+    use crate::{
+        leaf::{
+            OneSigAccountMeta, OneSigInstruction, SolanaLeaf, TokenOutflowLimit, TransactionGroup,
+        },
+        tree::OneSigTree,
+    };
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    fn instruction() -> impl Strategy<Value = OneSigInstruction> {
+        let account = (pubkey(), any::<bool>(), any::<bool>()).prop_map(
+            |(pubkey, is_signer, is_writable)| OneSigAccountMeta { pubkey, is_signer, is_writable },
+        );
+        (pubkey(), vec(account, 0..8), vec(any::<u8>(), 0..64), any::<u64>()).prop_map(
+            |(program_id, accounts, data, value)| OneSigInstruction {
+                program_id,
+                accounts,
+                data,
+                value,
+            },
+        )
+    }
+
+    /// What a leaf executes, with its guards: all of it but its account and nonce
+    type LeafBody = (
+        OneSigInstruction,
+        Vec<TokenOutflowLimit>,
+        Vec<Pubkey>,
+        u8,
+        Option<[u8; 32]>,
+        Option<TransactionGroup>,
+        Option<i64>,
+        Option<u64>,
+    );
+
+    /// Leaves of one OneSig account at consecutive nonces, as a proposal numbers them. Generated
+    /// as parts, leaves and hashes not being `Debug`.
+    #[derive(Clone, Debug)]
+    struct Proposal {
+        one_sig_state: Pubkey,
+        one_sig_id: u64,
+        first_nonce: u64,
+        bodies: Vec<LeafBody>,
+    }
+
+    impl Proposal {
+        fn leaves(&self) -> Vec<SolanaLeaf> {
+            self.bodies
+                .iter()
+                .cloned()
+                .zip(self.first_nonce..)
+                .map(
+                    |(
+                        (
+                            instruction,
+                            limits,
+                            guarded_accounts,
+                            vault_index,
+                            genesis_hash,
+                            group,
+                            not_before,
+                            bounty,
+                        ),
+                        nonce,
+                    )| {
+                        SolanaLeaf {
+                            one_sig_state: self.one_sig_state,
+                            one_sig_id: self.one_sig_id,
+                            nonce,
+                            instruction,
+                            token_outflow_limits: limits,
+                            guarded_accounts,
+                            vault_index,
+                            cluster_genesis_hash: genesis_hash.map(Hash),
+                            group,
+                            not_before,
+                            bounty,
+                        }
+                    },
+                )
+                .collect()
+        }
+    }
+
+    fn proposals() -> impl Strategy<Value = Proposal> {
+        let limit = (pubkey(), any::<u64>())
+            .prop_map(|(mint, max_amount)| TokenOutflowLimit { mint, max_amount });
+        let body = (
+            instruction(),
+            vec(limit, 0..3),
+            vec(pubkey(), 0..3),
+            any::<u8>(),
+            option::of(any::<[u8; 32]>()),
+            option::of(
+                (any::<u8>(), any::<u8>()).prop_map(|(index, len)| TransactionGroup { index, len }),
+            ),
+            option::of(any::<i64>()),
+            option::of(any::<u64>()),
+        );
+        (pubkey(), any::<u64>(), 0..u64::MAX / 2, vec(body, 1..24)).prop_map(
+            |(one_sig_state, one_sig_id, first_nonce, bodies)| Proposal {
+                one_sig_state,
+                one_sig_id,
+                first_nonce,
+                bodies,
+            },
+        )
+    }
+
+    /// The leaf hash as `execute_transaction` encodes it, from the program's own types
+    fn encode_leaf(leaf: &SolanaLeaf) -> Hash {
+        let instruction = &leaf.instruction;
+        let instruction = onesig::OneSigInstruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|account| onesig::OneSigAccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+            value: instruction.value,
+        };
+        let limits: Vec<onesig::TokenOutflowLimit> =
+            leaf.token_outflow_limits.iter().copied().map(Into::into).collect();
+        MerkleValidator::encode_leaf(
+            &leaf.one_sig_state,
+            leaf.one_sig_id,
+            leaf.nonce,
+            &instruction,
+            &limits,
+            &leaf.guarded_accounts,
+            leaf.vault_index,
+            leaf.cluster_genesis_hash.as_ref(),
+            leaf.group.map(onesig::TransactionGroup::from).as_ref(),
+            leaf.not_before,
+            leaf.bounty,
+        )
+        .unwrap()
+    }
+
+    fn proves(root: &Hash, proof: &[Hash], leaf: &Hash) -> bool {
+        MerkleValidator::verify_merkle_proof(root, proof, leaf).is_ok()
+    }
+
+    proptest! {
+        #[test]
+        fn test_every_leaf_proves(proposal in proposals()) {
+            let leaves = proposal.leaves();
+            let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let hash = encode_leaf(leaf);
+                prop_assert!(hash == leaf.hash());
+                prop_assert!(proves(&tree.root(), &tree.proof(index).unwrap(), &hash));
+            }
+        }
+
+        #[test]
+        fn test_mutated_leaf_does_not_prove(
+            proposal in proposals(),
+            index in any::<prop::sample::Index>(),
+            mutation in 0..4u8,
+        ) {
+            let leaves = proposal.leaves();
+            let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+            let index = index.index(leaves.len());
+            let mut leaf = leaves[index].clone();
+            match mutation {
+                0 => leaf.nonce ^= 1,
+                1 => leaf.instruction.value ^= 1,
+                2 => leaf.instruction.data.push(0),
+                _ => leaf.vault_index ^= 1,
+            }
+            prop_assert!(!proves(&tree.root(), &tree.proof(index).unwrap(), &encode_leaf(&leaf)));
+        }
+
+        #[test]
+        fn test_mutated_proof_does_not_prove(
+            proposal in proposals(),
+            index in any::<prop::sample::Index>(),
+            position in any::<prop::sample::Index>(),
+            bit in 0..256usize,
+        ) {
+            let leaves = proposal.leaves();
+            prop_assume!(leaves.len() > 1);
+            let tree = OneSigTree::new(leaves.iter().cloned().map(Into::into)).unwrap();
+            let index = index.index(leaves.len());
+            let hash = encode_leaf(&leaves[index]);
+            let proof = tree.proof(index).unwrap();
+
+            let mut flipped = proof.clone();
+            flipped[position.index(proof.len())].0[bit / 8] ^= 1 << (bit % 8);
+            prop_assert!(!proves(&tree.root(), &flipped, &hash));
+            prop_assert!(!proves(&tree.root(), &proof[..proof.len() - 1], &hash));
+
+            // Nor does the proof of another leaf
+            let other = (index + 1) % leaves.len();
+            prop_assert!(!proves(&tree.root(), &tree.proof(other).unwrap(), &hash));
+        }
+    }
+}
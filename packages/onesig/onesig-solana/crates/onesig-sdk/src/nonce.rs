@@ -0,0 +1,57 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_instruction},
+};
+
+use crate::error::SdkError;
+
+/// Size of a nonce account
+pub const NONCE_ACCOUNT_SIZE: usize = 80;
+
+// Tags of the bincode-encoded `Versions::Current` and `State::Initialized`
+const NONCE_VERSION_CURRENT: u32 = 1;
+const NONCE_STATE_INITIALIZED: u32 = 1;
+
+/// A durable nonce account, whose nonce stands in for the recent blockhash of transactions that
+/// must stay valid for longer, such as the ones signed offline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceAccount {
+    pub address: Pubkey,
+    /// Signs the advance of the nonce
+    pub authority: Pubkey,
+    /// The nonce, valid until a transaction advances it
+    pub durable_nonce: [u8; 32],
+    pub lamports_per_signature: u64,
+}
+
+impl NonceAccount {
+    /// Decodes the data of the nonce account at `address`. Nonces of legacy accounts cannot be
+    /// used until the accounts are upgraded, so they are rejected.
+    pub fn from_account_data(address: Pubkey, data: &[u8]) -> Result<Self, SdkError> {
+        let invalid = || SdkError::InvalidNonceAccount(address);
+        if data.len() != NONCE_ACCOUNT_SIZE ||
+            data[..4] != NONCE_VERSION_CURRENT.to_le_bytes() ||
+            data[4..8] != NONCE_STATE_INITIALIZED.to_le_bytes()
+        {
+            return Err(invalid());
+        }
+        Ok(Self {
+            address,
+            authority: Pubkey::try_from(&data[8..40]).map_err(|_| invalid())?,
+            durable_nonce: data[40..72].try_into().map_err(|_| invalid())?,
+            lamports_per_signature: u64::from_le_bytes(
+                data[72..].try_into().map_err(|_| invalid())?,
+            ),
+        })
+    }
+
+    /// Advances the nonce, the instruction durable transactions start with
+    pub fn advance(&self) -> Instruction {
+        system_instruction::advance_nonce_account(&self.address, &self.authority)
+    }
+
+    /// `instructions` after the advance of the nonce
+    pub fn with_advance(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        [&[self.advance()], instructions].concat()
+    }
+}
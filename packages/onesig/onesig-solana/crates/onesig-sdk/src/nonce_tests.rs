@@ -0,0 +1,101 @@
+// Tests for durable nonce accounts and the transactions advancing them.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::{
+            instruction::{AccountMeta, Instruction},
+            system_instruction,
+        },
+        system_program,
+    };
+
+    use crate::{
+        error::SdkError,
+        lookup_table::LookupTable,
+        nonce::{NonceAccount, NONCE_ACCOUNT_SIZE},
+        transaction::{Message, MessageV0},
+    };
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn nonce() -> NonceAccount {
+        NonceAccount {
+            address: key(0x4e),
+            authority: key(0x10),
+            durable_nonce: [0xdd; 32],
+            lamports_per_signature: 5_000,
+        }
+    }
+
+    fn account_data(version: u32, state: u32) -> Vec<u8> {
+        let mut data = version.to_le_bytes().to_vec();
+        data.extend(state.to_le_bytes());
+        data.extend_from_slice(key(0x10).as_ref());
+        data.extend([0xdd; 32]);
+        data.extend(5_000u64.to_le_bytes());
+        data
+    }
+
+    fn instruction() -> Instruction {
+        Instruction {
+            program_id: key(0x90),
+            accounts: vec![AccountMeta::new(key(0x30), false)],
+            data: vec![1],
+        }
+    }
+
+    #[test]
+    fn test_from_account_data() {
+        let data = account_data(1, 1);
+        assert_eq!(data.len(), NONCE_ACCOUNT_SIZE);
+        assert_eq!(NonceAccount::from_account_data(key(0x4e), &data), Ok(nonce()));
+
+        let invalid = Err(SdkError::InvalidNonceAccount(key(0x4e)));
+        // Legacy, uninitialized, truncated
+        assert_eq!(NonceAccount::from_account_data(key(0x4e), &account_data(0, 1)), invalid);
+        assert_eq!(NonceAccount::from_account_data(key(0x4e), &account_data(1, 0)), invalid);
+        assert_eq!(NonceAccount::from_account_data(key(0x4e), &data[..79]), invalid);
+    }
+
+    #[test]
+    fn test_advance() {
+        let advance = nonce().advance();
+        assert_eq!(advance, system_instruction::advance_nonce_account(&key(0x4e), &key(0x10)));
+        assert_eq!(advance.program_id, system_program::ID);
+        assert_eq!(advance.data, [4, 0, 0, 0]);
+        assert_eq!(nonce().with_advance(&[instruction()]), [advance, instruction()]);
+    }
+
+    #[test]
+    fn test_message_with_nonce() {
+        let message = Message::new_with_nonce(&key(0x20), &[instruction()], &nonce()).unwrap();
+        let expected =
+            Message::new(&key(0x20), &[nonce().advance(), instruction()], [0xdd; 32]).unwrap();
+        assert!(message == expected);
+        // The authority signs the advance
+        assert_eq!(message.signers(), [key(0x20), key(0x10)]);
+    }
+
+    #[test]
+    fn test_message_v0_with_nonce() {
+        let table = LookupTable {
+            address: key(0xa1),
+            authority: None,
+            addresses: vec![key(0x4e), key(0x30)],
+        };
+        let message =
+            MessageV0::new_with_nonce(&key(0x20), &[instruction()], &[table], &nonce()).unwrap();
+
+        assert_eq!(message.recent_blockhash, [0xdd; 32]);
+        // The nonce account stays static, the other account is loaded
+        assert!(message.account_keys.contains(&key(0x4e)));
+        assert!(!message.account_keys.contains(&key(0x30)));
+        assert_eq!(message.address_table_lookups[0].writable_indexes, [1]);
+        let advance = &message.instructions[0];
+        assert_eq!(message.account_keys[advance.program_id_index as usize], system_program::ID);
+        assert_eq!(message.account_keys[advance.accounts[0] as usize], key(0x4e));
+    }
+}
@@ -0,0 +1,83 @@
+use onesig::{
+    BOUNTY_ESCROW_SEED, EXECUTOR_SEED, LEAF_FAILURE_SEED, MERKLE_ROOT_REGISTRY_SEED,
+    MERKLE_ROOT_SEED, MODULE_AUTHORITY_SEED, MODULE_SEED, ONE_SIG_SEED, SPENDING_LIMITS_SEED,
+    TARGET_PROGRAMS_SEED,
+};
+use onesig_core::Hash;
+use solana_pubkey::Pubkey;
+
+/// Seed of the event authority PDA `#[event_cpi]` instructions take
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// PDAs of a OneSig account, with their bumps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OneSigPda {
+    pub program_id: Pubkey,
+    pub state: Pubkey,
+}
+
+impl OneSigPda {
+    pub fn new(program_id: Pubkey, state: Pubkey) -> Self {
+        Self { program_id, state }
+    }
+
+    pub fn one_sig_signer(&self) -> (Pubkey, u8) {
+        self.find(&[ONE_SIG_SEED, self.state.as_ref()])
+    }
+
+    /// Signer PDA of vault `vault_index`; vault 0 is `one_sig_signer`.
+    pub fn vault_signer(&self, vault_index: u8) -> (Pubkey, u8) {
+        if vault_index == 0 {
+            return self.one_sig_signer();
+        }
+        self.find(&[ONE_SIG_SEED, self.state.as_ref(), &[vault_index]])
+    }
+
+    pub fn merkle_root_state(&self, merkle_root: &Hash) -> (Pubkey, u8) {
+        self.find(&[MERKLE_ROOT_SEED, self.state.as_ref(), merkle_root.as_ref()])
+    }
+
+    pub fn merkle_root_registry(&self) -> (Pubkey, u8) {
+        self.find(&[MERKLE_ROOT_REGISTRY_SEED, self.state.as_ref()])
+    }
+
+    pub fn spending_limits(&self) -> (Pubkey, u8) {
+        self.find(&[SPENDING_LIMITS_SEED, self.state.as_ref()])
+    }
+
+    pub fn target_programs(&self) -> (Pubkey, u8) {
+        self.find(&[TARGET_PROGRAMS_SEED, self.state.as_ref()])
+    }
+
+    /// System account the bounties of executed leaves are paid from, funded by transfers to it.
+    pub fn bounty_escrow(&self) -> (Pubkey, u8) {
+        self.find(&[BOUNTY_ESCROW_SEED, self.state.as_ref()])
+    }
+
+    /// `LeafFailure` marker of the leaf hashing to `leaf`, once `record_leaf_failure` recorded it.
+    pub fn leaf_failure(&self, leaf: &Hash) -> (Pubkey, u8) {
+        self.find(&[LEAF_FAILURE_SEED, self.state.as_ref(), leaf.as_ref()])
+    }
+
+    pub fn executor_state(&self, executor: &Pubkey) -> (Pubkey, u8) {
+        self.find(&[EXECUTOR_SEED, self.state.as_ref(), executor.as_ref()])
+    }
+
+    pub fn module_state(&self, module: &Pubkey) -> (Pubkey, u8) {
+        self.find(&[MODULE_SEED, self.state.as_ref(), module.as_ref()])
+    }
+
+    /// PDA of the `module` program it signs `execute_module_transaction` with.
+    pub fn module_authority(&self, module: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MODULE_AUTHORITY_SEED, self.state.as_ref()], module)
+    }
+
+    /// Event authority of the program, shared by every OneSig account.
+    pub fn event_authority(&self) -> (Pubkey, u8) {
+        self.find(&[EVENT_AUTHORITY_SEED])
+    }
+
+    fn find(&self, seeds: &[&[u8]]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(seeds, &self.program_id)
+    }
+}
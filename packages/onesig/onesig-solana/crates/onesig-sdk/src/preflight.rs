@@ -0,0 +1,163 @@
+use anchor_lang::{error::ERROR_CODE_OFFSET, prelude::Pubkey};
+use onesig::OneSigError;
+use serde_json::Value;
+
+/// [`OneSigError`]s in declaration order, so at their code minus [`ERROR_CODE_OFFSET`], as Anchor
+/// does not generate the conversion from codes back to errors. `test_one_sig_errors` checks it
+/// against the program's source.
+const ONE_SIG_ERRORS: [OneSigError; 71] = [
+    OneSigError::InvalidSignersLen,
+    OneSigError::DuplicateSigners,
+    OneSigError::InvalidThreshold,
+    OneSigError::ThresholdExceedsSigners,
+    OneSigError::MissingSigner,
+    OneSigError::SignatureDataSizeMismatch,
+    OneSigError::InsufficientSignatures,
+    OneSigError::InvalidSignatureFormat,
+    OneSigError::FailedSignatureRecovery,
+    OneSigError::InvalidProof,
+    OneSigError::ExpiredMerkleRoot,
+    OneSigError::ExcessiveBalanceDeduction,
+    OneSigError::InvalidSignerOwner,
+    OneSigError::NonEmptySignerData,
+    OneSigError::MissingMerkleRootState,
+    OneSigError::MerkleRootNotCloseable,
+    OneSigError::Reentrancy,
+    OneSigError::InvalidRentPayer,
+    OneSigError::SeedMismatch,
+    OneSigError::InvalidExecutorsLen,
+    OneSigError::DuplicateExecutor,
+    OneSigError::ExecutorNotFound,
+    OneSigError::ExecutorRequired,
+    OneSigError::InvalidSigner,
+    OneSigError::InvalidExecutor,
+    OneSigError::ExpiredSignerExecutionProof,
+    OneSigError::SignerExecutionProofUnauthorized,
+    OneSigError::MissingProgramId,
+    OneSigError::NonceMutatedDuringExecution,
+    OneSigError::InvalidStateSize,
+    OneSigError::TooManyMerkleRoots,
+    OneSigError::MerkleRootsRemaining,
+    OneSigError::ExecutorsRemaining,
+    OneSigError::NonZeroSignerBalance,
+    OneSigError::StateVersionMismatch,
+    OneSigError::InvalidMigrationAccount,
+    OneSigError::MerkleRootExhausted,
+    OneSigError::ExecutionLimitNotTracked,
+    OneSigError::ExpiryNotExtended,
+    OneSigError::ExcessiveTokenOutflow,
+    OneSigError::SpendingLimitExceeded,
+    OneSigError::InvalidSpendingLimit,
+    OneSigError::SpendingLimitNotFound,
+    OneSigError::MissingTargetPrograms,
+    OneSigError::TooManyTargetPrograms,
+    OneSigError::TargetProgramNotAllowed,
+    OneSigError::ExecutorTargetNotAllowed,
+    OneSigError::InvalidVaultSigner,
+    OneSigError::InvalidTokenProgram,
+    OneSigError::MissingSpendingLimits,
+    OneSigError::InvalidModule,
+    OneSigError::ModulesRemaining,
+    OneSigError::ModuleCannotCallOneSig,
+    OneSigError::NonceOverflow,
+    OneSigError::NonceNamespaceUnchanged,
+    OneSigError::ProofTooDeep,
+    OneSigError::MissingPayer,
+    OneSigError::InvalidTransactionGroup,
+    OneSigError::MissingInstructionsSysvar,
+    OneSigError::IncompleteTransactionGroup,
+    OneSigError::ExecutionTooEarly,
+    OneSigError::MissingBountyEscrow,
+    OneSigError::InvalidBountyEscrow,
+    OneSigError::InsufficientBountyEscrow,
+    OneSigError::InvalidExecutionRateLimit,
+    OneSigError::ExecutionRateLimited,
+    OneSigError::ExecutorConfigMoved,
+    OneSigError::InvalidMerkleRootsLen,
+    OneSigError::VaultSignersMissing,
+    OneSigError::LeafMismatch,
+    OneSigError::LeafFailureNotCloseable,
+];
+
+/// The [`OneSigError`] of a custom program error code
+pub fn one_sig_error(code: u32) -> Option<OneSigError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    ONE_SIG_ERRORS.get(index as usize).copied()
+}
+
+/// What simulating a transaction predicts, before it is broadcast
+#[derive(Clone, Debug)]
+pub struct Preflight {
+    /// Compute units the transaction consumed, to size its compute budget
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    /// Why the transaction would fail, `None` if it would succeed
+    pub error: Option<PreflightError>,
+}
+
+impl Preflight {
+    /// Decodes the result of `simulateTransaction`, attributing custom errors to the program that
+    /// raised them, which for `execute_transaction` may be the leaf's target rather than OneSig.
+    pub(crate) fn new(
+        program_id: &Pubkey,
+        err: Option<Value>,
+        logs: Vec<String>,
+        units_consumed: Option<u64>,
+    ) -> Self {
+        let error = err.map(|err| PreflightError::new(program_id, err, &logs));
+        Self { units_consumed, logs, error }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Why a simulated transaction fails
+#[derive(Clone, Debug)]
+pub enum PreflightError {
+    /// The OneSig program rejects it, e.g. with `InvalidProof` or `ExpiredMerkleRoot`
+    OneSig(OneSigError),
+    /// Instruction `index` fails with a custom error of another program, e.g. the target of an
+    /// executed leaf, or with an Anchor framework error. `program_id` is the program that failed,
+    /// if the logs tell.
+    Custom { index: u8, program_id: Option<Pubkey>, code: u32 },
+    /// Instruction `index` fails with a runtime error, as the node reports it
+    Instruction { index: u8, error: Value },
+    /// The transaction fails before its instructions run, e.g. with `AccountNotFound`
+    Transaction(Value),
+}
+
+impl PreflightError {
+    fn new(program_id: &Pubkey, err: Value, logs: &[String]) -> Self {
+        let Some([index, error]) = err
+            .get("InstructionError")
+            .and_then(Value::as_array)
+            .and_then(|error| <&[Value; 2]>::try_from(error.as_slice()).ok())
+        else {
+            return Self::Transaction(err);
+        };
+        let Some(index) = index.as_u64().and_then(|index| u8::try_from(index).ok()) else {
+            return Self::Transaction(err);
+        };
+        let Some(code) = error.get("Custom").and_then(Value::as_u64) else {
+            return Self::Instruction { index, error: error.clone() };
+        };
+        let code = code as u32;
+        let failed = failed_program(logs);
+        match (failed, one_sig_error(code)) {
+            (Some(failed), Some(error)) if failed == *program_id => Self::OneSig(error),
+            _ => Self::Custom { index, program_id: failed, code },
+        }
+    }
+}
+
+/// The program that failed first, so the innermost one of a chain of CPIs: every caller then logs
+/// its own failure with the same error.
+fn failed_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().find_map(|log| {
+        let rest = log.strip_prefix("Program ")?;
+        let (program_id, rest) = rest.split_once(' ')?;
+        rest.starts_with("failed").then(|| program_id.parse().ok())?
+    })
+}
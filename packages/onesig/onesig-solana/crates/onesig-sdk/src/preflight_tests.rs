@@ -0,0 +1,183 @@
+// Tests for decoding simulated transactions' errors, and the preflight over the RPC clients.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+    };
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde_json::{json, Value};
+
+    use crate::{
+        error::SdkError,
+        preflight::{one_sig_error, Preflight, PreflightError},
+        rpc::{Commitment, RpcClient, RpcTransport},
+        transaction::{Message, TRANSACTION_SIGNATURE_LEN},
+    };
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0e; 32]);
+    const TARGET_ID: Pubkey = Pubkey::new_from_array([0x7a; 32]);
+
+    /// Answers every request with `response`, recording the last request
+    struct MockTransport {
+        response: Value,
+        request: std::cell::RefCell<Value>,
+    }
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            *self.request.borrow_mut() = serde_json::from_str(body).unwrap();
+            Ok(self.response.to_string())
+        }
+    }
+
+    fn client(value: Value) -> RpcClient<MockTransport> {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": value },
+        });
+        RpcClient::new(
+            MockTransport { response, request: Default::default() },
+            Commitment::default(),
+        )
+    }
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn failure(program_id: &Pubkey, code: u32) -> String {
+        format!("Program {program_id} failed: custom program error: {code:#x}")
+    }
+
+    fn name(error: &Option<PreflightError>) -> Option<String> {
+        match error {
+            Some(PreflightError::OneSig(error)) => Some(error.name()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_one_sig_errors() {
+        let source = include_str!("../../../programs/onesig/src/errors.rs");
+        let variants: Vec<&str> = source
+            .lines()
+            .filter_map(|line| line.trim().strip_suffix(','))
+            .filter(|line| line.chars().all(char::is_alphanumeric))
+            .collect();
+        let decoded: Vec<String> =
+            (6000..).map_while(one_sig_error).map(|error| error.name()).collect();
+        assert_eq!(decoded, variants);
+        assert!(one_sig_error(5999).is_none());
+        assert!(one_sig_error(0).is_none());
+    }
+
+    #[test]
+    fn test_decode_one_sig_error() {
+        let err = json!({ "InstructionError": [1, { "Custom": 6010 }] });
+        let logs = logs(&[
+            &format!("Program {PROGRAM_ID} invoke [1]"),
+            "Program log: AnchorError occurred. Error Code: ExpiredMerkleRoot. Error Number: 6010.",
+            &failure(&PROGRAM_ID, 6010),
+        ]);
+        let preflight = Preflight::new(&PROGRAM_ID, Some(err), logs, Some(4_200));
+        assert!(!preflight.is_ok());
+        assert_eq!(name(&preflight.error).as_deref(), Some("ExpiredMerkleRoot"));
+        assert_eq!(preflight.units_consumed, Some(4_200));
+    }
+
+    #[test]
+    fn test_decode_target_error() {
+        // The leaf's target fails with a code in OneSig's range; OneSig relays it
+        let err = json!({ "InstructionError": [0, { "Custom": 6009 }] });
+        let logs = logs(&[
+            &format!("Program {PROGRAM_ID} invoke [1]"),
+            &format!("Program {TARGET_ID} invoke [2]"),
+            &failure(&TARGET_ID, 6009),
+            &failure(&PROGRAM_ID, 6009),
+        ]);
+        let preflight = Preflight::new(&PROGRAM_ID, Some(err.clone()), logs, None);
+        assert!(matches!(
+            preflight.error,
+            Some(PreflightError::Custom { index: 0, program_id: Some(TARGET_ID), code: 6009 })
+        ));
+
+        // Without logs, the failing program is unknown
+        let preflight = Preflight::new(&PROGRAM_ID, Some(err), vec![], None);
+        assert!(matches!(
+            preflight.error,
+            Some(PreflightError::Custom { index: 0, program_id: None, code: 6009 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_other_errors() {
+        let err = json!({ "InstructionError": [2, "InsufficientFunds"] });
+        let preflight = Preflight::new(&PROGRAM_ID, Some(err), vec![], None);
+        assert!(matches!(
+            preflight.error,
+            Some(PreflightError::Instruction { index: 2, error }) if error == "InsufficientFunds"
+        ));
+
+        let preflight = Preflight::new(&PROGRAM_ID, Some(json!("AccountNotFound")), vec![], None);
+        assert!(matches!(
+            preflight.error,
+            Some(PreflightError::Transaction(error)) if error == "AccountNotFound"
+        ));
+
+        assert!(Preflight::new(&PROGRAM_ID, None, vec![], Some(1)).is_ok());
+    }
+
+    #[test]
+    fn test_preflight() {
+        let payer = Pubkey::new_from_array([0x10; 32]);
+        let instruction = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![AccountMeta::new(payer, true)],
+            data: vec![7],
+        };
+        let client = client(json!({
+            "err": { "InstructionError": [0, { "Custom": 6022 }] },
+            "logs": [failure(&PROGRAM_ID, 6022)],
+            "unitsConsumed": 12_345,
+            "accounts": null,
+        }));
+
+        let preflight = client
+            .preflight(&PROGRAM_ID, &payer, std::slice::from_ref(&instruction))
+            .unwrap();
+        assert_eq!(name(&preflight.error).as_deref(), Some("ExecutorRequired"));
+        assert_eq!(preflight.units_consumed, Some(12_345));
+        assert_eq!(preflight.logs, [failure(&PROGRAM_ID, 6022)]);
+
+        // Unsigned, and the node replaces the blockhash
+        let request = client.transport().request.borrow().clone();
+        assert_eq!(request["method"], "simulateTransaction");
+        assert_eq!(
+            request["params"][1],
+            json!({
+                "encoding": "base64",
+                "sigVerify": false,
+                "replaceRecentBlockhash": true,
+                "commitment": "confirmed",
+            })
+        );
+        let transaction = BASE64_STANDARD.decode(request["params"][0].as_str().unwrap()).unwrap();
+        let message = Message::new(&payer, &[instruction], [0; 32]).unwrap().serialize();
+        assert_eq!(transaction[0], 1);
+        assert_eq!(transaction[1..1 + TRANSACTION_SIGNATURE_LEN], [0; TRANSACTION_SIGNATURE_LEN]);
+        assert_eq!(transaction[1 + TRANSACTION_SIGNATURE_LEN..], message);
+    }
+
+    #[test]
+    fn test_preflight_success() {
+        let payer = Pubkey::new_from_array([0x10; 32]);
+        let client = client(json!({ "err": null, "logs": null, "unitsConsumed": 900 }));
+
+        let preflight = client.preflight(&PROGRAM_ID, &payer, &[]).unwrap();
+        assert!(preflight.is_ok());
+        assert!(preflight.logs.is_empty());
+        assert_eq!(preflight.units_consumed, Some(900));
+    }
+}
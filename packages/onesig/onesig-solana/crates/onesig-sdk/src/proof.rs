@@ -0,0 +1,85 @@
+use onesig_core::{leaf::verify_proof, Hash, HASH_BYTES};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encoding::{hex, hex_list},
+    error::SdkError,
+};
+
+/// A leaf with its proof and the root it proves it under, the unit an executor needs.
+///
+/// Serializes to JSON with 0x-prefixed hex hashes, the format `getHexRoot` and `getHexProof` of
+/// the TypeScript tree builder produce, and to a compact binary form: the root, the leaf, then
+/// the proof, 32 bytes each.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafProof {
+    #[serde(with = "hex")]
+    pub merkle_root: Hash,
+    #[serde(with = "hex")]
+    pub leaf: Hash,
+    #[serde(with = "hex_list")]
+    pub proof: Vec<Hash>,
+}
+
+impl LeafProof {
+    /// Whether `proof` folds `leaf` into `merkle_root`, as the program checks it
+    pub fn verify(&self) -> bool {
+        verify_proof(&self.merkle_root, &self.proof, &self.leaf)
+    }
+
+    pub fn to_json(&self) -> Result<String, SdkError> {
+        serde_json::to_string(self).map_err(|error| SdkError::Json(error.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SdkError> {
+        serde_json::from_str(json).map_err(|error| SdkError::Json(error.to_string()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.merkle_root, self.leaf]
+            .iter()
+            .chain(&self.proof)
+            .flat_map(|hash| hash.0)
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SdkError> {
+        match &proof_from_bytes(bytes)?[..] {
+            [merkle_root, leaf, proof @ ..] =>
+                Ok(Self { merkle_root: *merkle_root, leaf: *leaf, proof: proof.to_vec() }),
+            _ => Err(SdkError::HashesLength(bytes.len())),
+        }
+    }
+}
+
+/// 0x-prefixed hex of a root or leaf, as `getHexRoot` writes it
+pub fn hash_to_hex(hash: &Hash) -> String {
+    hex::encode(hash.as_ref())
+}
+
+/// Parses a root or leaf in hex, with or without its 0x prefix.
+pub fn hash_from_hex(hex: &str) -> Result<Hash, SdkError> {
+    hex::decode(hex).map_err(SdkError::Hex)
+}
+
+/// A proof as `getHexProof` returns it
+pub fn proof_to_hex(proof: &[Hash]) -> Vec<String> {
+    proof.iter().map(hash_to_hex).collect()
+}
+
+pub fn proof_from_hex(proof: &[impl AsRef<str>]) -> Result<Vec<Hash>, SdkError> {
+    proof.iter().map(|hex| hash_from_hex(hex.as_ref())).collect()
+}
+
+/// A proof as its hashes back to back
+pub fn proof_to_bytes(proof: &[Hash]) -> Vec<u8> {
+    proof.iter().flat_map(|hash| hash.0).collect()
+}
+
+pub fn proof_from_bytes(bytes: &[u8]) -> Result<Vec<Hash>, SdkError> {
+    if bytes.len() % HASH_BYTES != 0 {
+        return Err(SdkError::HashesLength(bytes.len()));
+    }
+    Ok(bytes.chunks(HASH_BYTES).map(|chunk| Hash(chunk.try_into().unwrap())).collect())
+}
@@ -0,0 +1,99 @@
+// Tests for the hex JSON and binary encodings of proofs, against the proofs of the shared test
+// vectors, which are in the TypeScript tooling's format.
+#[cfg(test)]
+mod tests {
+    use onesig_core::Hash;
+
+    use crate::{
+        error::SdkError,
+        proof::{
+            hash_from_hex, hash_to_hex, proof_from_bytes, proof_from_hex, proof_to_bytes,
+            proof_to_hex, LeafProof,
+        },
+        tree::OneSigTree,
+    };
+
+    fn vector_proof() -> LeafProof {
+        let vectors = onesig_test_vectors::load();
+        LeafProof {
+            merkle_root: vectors.merkle_root.try_into().unwrap(),
+            leaf: vectors.leaves[0].clone().try_into().unwrap(),
+            proof: vectors.proofs[0]
+                .proof
+                .iter()
+                .map(|hash| hash.clone().try_into().unwrap())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_hex_matches_vectors() {
+        let vectors: serde_json::Value =
+            serde_json::from_str(onesig_test_vectors::VECTORS_JSON).unwrap();
+        let root = vectors["merkleRoot"].as_str().unwrap();
+        let proof: Vec<&str> = vectors["proofs"][0]["proof"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hash| hash.as_str().unwrap())
+            .collect();
+
+        let leaf_proof = vector_proof();
+        assert!(leaf_proof.verify());
+        assert_eq!(hash_to_hex(&leaf_proof.merkle_root), root);
+        assert!(hash_from_hex(root).unwrap() == leaf_proof.merkle_root); // `Hash` is not `Debug`
+        assert_eq!(proof_to_hex(&leaf_proof.proof), proof);
+        assert!(proof_from_hex(&proof).unwrap() == leaf_proof.proof);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let leaf_proof = vector_proof();
+        let json = leaf_proof.to_json().unwrap();
+        assert!(
+            json.contains(&format!("\"merkleRoot\":\"{}\"", hash_to_hex(&leaf_proof.merkle_root)))
+        );
+        assert!(LeafProof::from_json(&json).unwrap() == leaf_proof);
+
+        // Unprefixed and uppercase hex parse too
+        let upper = hash_to_hex(&leaf_proof.leaf)[2..].to_uppercase();
+        assert!(hash_from_hex(&upper).unwrap() == leaf_proof.leaf);
+
+        assert!(matches!(hash_from_hex("0x1234"), Err(SdkError::Hex(_))));
+        assert!(matches!(hash_from_hex(&"zz".repeat(32)), Err(SdkError::Hex(_))));
+        assert!(matches!(
+            LeafProof::from_json("{\"merkleRoot\":\"0x12\"}"),
+            Err(SdkError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let leaf_proof = vector_proof();
+        let bytes = leaf_proof.to_bytes();
+        assert_eq!(bytes.len(), 32 * (2 + leaf_proof.proof.len()));
+        assert!(LeafProof::from_bytes(&bytes).unwrap() == leaf_proof);
+
+        let proof = proof_to_bytes(&leaf_proof.proof);
+        assert_eq!(proof, bytes[64..]);
+        assert!(proof_from_bytes(&proof).unwrap() == leaf_proof.proof);
+
+        assert_eq!(
+            proof_from_bytes(&proof[1..]).err(),
+            Some(SdkError::HashesLength(proof.len() - 1))
+        );
+        assert_eq!(LeafProof::from_bytes(&bytes[..32]).err(), Some(SdkError::HashesLength(32)));
+    }
+
+    #[test]
+    fn test_tree_leaf_proofs() {
+        let hashes: Vec<Hash> = (0..5u8).map(|i| Hash([i; 32])).collect();
+        let tree = OneSigTree::from_hashes(hashes.clone()).unwrap();
+        for (index, leaf) in hashes.iter().enumerate() {
+            let leaf_proof = tree.leaf_proof(index).unwrap();
+            assert!(leaf_proof.leaf == *leaf);
+            assert!(leaf_proof.verify());
+            assert!(LeafProof::from_bytes(&leaf_proof.to_bytes()).unwrap() == leaf_proof);
+        }
+    }
+}
@@ -0,0 +1,833 @@
+use std::future::Future;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    compute_budget::{
+        compute_unit_limit, fee_percentile, set_compute_unit_limit, set_compute_unit_price,
+        writable_accounts, ComputeBudgetOptions, ComputeUnitLimit, ComputeUnitPrice,
+        MAX_COMPUTE_UNIT_LIMIT,
+    },
+    error::SdkError,
+    events::TransactionEvents,
+    indexer::History,
+    lookup_table::LookupTable,
+    nonce::NonceAccount,
+    preflight::Preflight,
+    state::OneSigAccount,
+    transaction::{Message, MessageV0, TransactionSigner},
+};
+
+/// Posts JSON-RPC requests to a Solana node and returns the response body, e.g. over `ureq` or a
+/// test double.
+pub trait RpcTransport {
+    fn post(&self, body: &str) -> Result<String, SdkError>;
+}
+
+/// [`RpcTransport`] of async HTTP clients, e.g. `reqwest` under tokio.
+pub trait AsyncRpcTransport {
+    fn post(&self, body: String) -> impl Future<Output = Result<String, SdkError>> + Send;
+}
+
+/// Commitment requests are made at, from the lowest
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+/// Status of a sent transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureStatus {
+    pub slot: u64,
+    /// `processed`, `confirmed` or `finalized`
+    pub confirmation_status: Option<String>,
+    /// Why the transaction failed, as the node reports it
+    pub err: Option<Value>,
+}
+
+impl SignatureStatus {
+    /// The commitment the transaction reached, if the node tells
+    pub fn commitment(&self) -> Option<Commitment> {
+        match self.confirmation_status.as_deref()? {
+            "processed" => Some(Commitment::Processed),
+            "confirmed" => Some(Commitment::Confirmed),
+            "finalized" => Some(Commitment::Finalized),
+            _ => None,
+        }
+    }
+}
+
+/// Signatures `getSignaturesForAddress` returns at most per page
+pub const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// A transaction of the history of an address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Why the transaction failed, as the node reports it
+    pub err: Option<Value>,
+}
+
+/// A JSON-RPC call and how to read its result, shared by the blocking and async clients so both
+/// build the same requests and fail the same way.
+pub(crate) struct RpcCall<T> {
+    method: &'static str,
+    params: Value,
+    parse: fn(Value) -> Result<T, SdkError>,
+}
+
+impl<T> RpcCall<T> {
+    pub(crate) fn new(
+        method: &'static str,
+        params: Value,
+        parse: fn(Value) -> Result<T, SdkError>,
+    ) -> Self {
+        Self { method, params, parse }
+    }
+
+    pub(crate) fn body(&self) -> String {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": self.method, "params": self.params })
+            .to_string()
+    }
+
+    pub(crate) fn response(&self, body: &str) -> Result<T, SdkError> {
+        #[derive(Deserialize)]
+        struct Response {
+            // A `null` result, e.g. of a transaction not found, is still a result
+            #[serde(default, deserialize_with = "present")]
+            result: Option<Value>,
+            error: Option<RpcError>,
+        }
+        #[derive(Deserialize)]
+        struct RpcError {
+            code: i64,
+            message: String,
+            data: Option<Value>,
+        }
+
+        fn present<'de, D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Value>, D::Error> {
+            Value::deserialize(deserializer).map(Some)
+        }
+
+        let response: Response = serde_json::from_str(body)
+            .map_err(|error| SdkError::InvalidRpcResponse(error.to_string()))?;
+        match response {
+            Response { error: Some(error), .. } =>
+                Err(SdkError::Rpc { code: error.code, message: error.message, data: error.data }),
+            Response { result: Some(result), .. } => (self.parse)(result),
+            _ => Err(SdkError::InvalidRpcResponse("neither a result nor an error".into())),
+        }
+    }
+}
+
+/// Result of `simulateTransaction`, decoded into a [`Preflight`] by the clients, which know the
+/// program
+pub(crate) struct Simulation {
+    err: Option<Value>,
+    logs: Vec<String>,
+    units_consumed: Option<u64>,
+}
+
+impl Simulation {
+    fn preflight(self, program_id: &Pubkey) -> Preflight {
+        Preflight::new(program_id, self.err, self.logs, self.units_consumed)
+    }
+
+    /// Units consumed by a simulation that must succeed
+    fn units_consumed(self) -> Result<u64, SdkError> {
+        if let Some(err) = self.err {
+            return Err(SdkError::SimulationFailed { err, logs: self.logs });
+        }
+        self.units_consumed
+            .ok_or_else(|| SdkError::InvalidRpcResponse("missing unitsConsumed".into()))
+    }
+}
+
+/// The unsigned transaction simulating `instructions` under the highest compute unit limit, in a v0
+/// message if it loads from `lookup_tables`.
+fn budget_simulation(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[LookupTable],
+) -> Result<Vec<u8>, SdkError> {
+    let instructions = [&[set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT)], instructions].concat();
+    if lookup_tables.is_empty() {
+        return Ok(Message::new(payer, &instructions, [0; 32])?.unsigned());
+    }
+    Ok(MessageV0::new(payer, &instructions, lookup_tables, [0; 32])?.unsigned())
+}
+
+/// `instructions` after the compute budget instructions of `options`, given the units a
+/// simulation consumed and the recent fees, fetched as `options` need them
+fn with_budget(
+    options: &ComputeBudgetOptions,
+    units_consumed: Option<u64>,
+    fees: Option<Vec<u64>>,
+    instructions: &[Instruction],
+) -> Vec<Instruction> {
+    let mut budget = vec![];
+    match (options.unit_limit, units_consumed) {
+        (ComputeUnitLimit::Fixed(units), _) => budget.push(set_compute_unit_limit(units)),
+        (ComputeUnitLimit::Simulated { margin_percent }, Some(units)) =>
+            budget.push(set_compute_unit_limit(compute_unit_limit(units, margin_percent))),
+        _ => {},
+    }
+    let price = match (options.unit_price, fees) {
+        (ComputeUnitPrice::Fixed(price), _) => price,
+        (ComputeUnitPrice::Percentile { percentile, max }, Some(fees)) =>
+            fee_percentile(fees, percentile).min(max),
+        _ => 0,
+    };
+    if price > 0 {
+        budget.push(set_compute_unit_price(price));
+    }
+    budget.extend_from_slice(instructions);
+    budget
+}
+
+pub(crate) fn field<T: for<'de> Deserialize<'de>>(
+    value: &Value,
+    pointer: &str,
+) -> Result<T, SdkError> {
+    let field = value
+        .pointer(pointer)
+        .ok_or_else(|| SdkError::InvalidRpcResponse(format!("missing {pointer}")))?;
+    T::deserialize(field).map_err(|error| SdkError::InvalidRpcResponse(error.to_string()))
+}
+
+mod calls {
+    use super::*;
+
+    pub fn latest_blockhash(commitment: Commitment) -> RpcCall<[u8; 32]> {
+        RpcCall::new(
+            "getLatestBlockhash",
+            json!([{ "commitment": commitment.as_str() }]),
+            |result| {
+                let blockhash: String = field(&result, "/value/blockhash")?;
+                let mut bytes = [0u8; 32];
+                match bs58::decode(&blockhash).onto(&mut bytes) {
+                    Ok(32) => Ok(bytes),
+                    _ =>
+                        Err(SdkError::InvalidRpcResponse(format!("invalid blockhash {blockhash}"))),
+                }
+            },
+        )
+    }
+
+    pub fn account_data(pubkey: &Pubkey, commitment: Commitment) -> RpcCall<Option<Vec<u8>>> {
+        RpcCall::new(
+            "getAccountInfo",
+            json!([pubkey.to_string(), { "encoding": "base64", "commitment": commitment.as_str() }]),
+            |result| {
+                if result.pointer("/value").is_none_or(Value::is_null) {
+                    return Ok(None);
+                }
+                let data: String = field(&result, "/value/data/0")?;
+                let data = BASE64_STANDARD
+                    .decode(data)
+                    .map_err(|error| SdkError::InvalidRpcResponse(error.to_string()))?;
+                Ok(Some(data))
+            },
+        )
+    }
+
+    /// Sends without preflight, nor the node retrying on its own: the caller rebroadcasts.
+    pub fn rebroadcast_transaction(transaction: &[u8]) -> RpcCall<String> {
+        RpcCall::new(
+            "sendTransaction",
+            json!([
+                BASE64_STANDARD.encode(transaction),
+                { "encoding": "base64", "skipPreflight": true, "maxRetries": 0 },
+            ]),
+            |result| field(&result, ""),
+        )
+    }
+
+    pub fn slot(commitment: Commitment) -> RpcCall<u64> {
+        RpcCall::new("getSlot", json!([{ "commitment": commitment.as_str() }]), |result| {
+            field(&result, "")
+        })
+    }
+
+    pub fn send_transaction(transaction: &[u8], commitment: Commitment) -> RpcCall<String> {
+        RpcCall::new(
+            "sendTransaction",
+            json!([
+                BASE64_STANDARD.encode(transaction),
+                { "encoding": "base64", "preflightCommitment": commitment.as_str() },
+            ]),
+            |result| field(&result, ""),
+        )
+    }
+
+    /// Simulates without checking signatures and against the latest blockhash, so the transaction
+    /// needs neither signing nor a blockhash of its own.
+    pub fn simulate_transaction(transaction: &[u8], commitment: Commitment) -> RpcCall<Simulation> {
+        RpcCall::new(
+            "simulateTransaction",
+            json!([
+                BASE64_STANDARD.encode(transaction),
+                {
+                    "encoding": "base64",
+                    "sigVerify": false,
+                    "replaceRecentBlockhash": true,
+                    "commitment": commitment.as_str(),
+                },
+            ]),
+            |result| {
+                Ok(Simulation {
+                    err: field(&result, "/value/err")?,
+                    logs: field::<Option<Vec<String>>>(&result, "/value/logs")?.unwrap_or_default(),
+                    units_consumed: field(&result, "/value/unitsConsumed").ok().flatten(),
+                })
+            },
+        )
+    }
+
+    /// Fees recently paid per compute unit to lock `accounts`, one per slot
+    pub fn recent_prioritization_fees(accounts: &[Pubkey]) -> RpcCall<Vec<u64>> {
+        let accounts: Vec<String> = accounts.iter().map(Pubkey::to_string).collect();
+        RpcCall::new("getRecentPrioritizationFees", json!([accounts]), |result| {
+            let fees: Vec<Value> = field(&result, "")?;
+            fees.iter().map(|fee| field(fee, "/prioritizationFee")).collect()
+        })
+    }
+
+    /// A landed transaction, `None` if the node does not know it. Transactions are only served
+    /// from confirmed blocks.
+    pub fn transaction(signature: &str, commitment: Commitment) -> RpcCall<Option<Value>> {
+        let commitment = commitment.max(Commitment::Confirmed);
+        RpcCall::new(
+            "getTransaction",
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "maxSupportedTransactionVersion": 0,
+                    "commitment": commitment.as_str(),
+                },
+            ]),
+            |result| Ok((!result.is_null()).then_some(result)),
+        )
+    }
+
+    /// A page of the transactions mentioning `address`, newest first, older than `before` and
+    /// newer than `until`. Like transactions, the history is only served from confirmed blocks.
+    pub fn signatures_for_address(
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+        commitment: Commitment,
+    ) -> RpcCall<Vec<SignatureInfo>> {
+        let commitment = commitment.max(Commitment::Confirmed);
+        RpcCall::new(
+            "getSignaturesForAddress",
+            json!([
+                address.to_string(),
+                {
+                    "limit": SIGNATURES_PAGE_LIMIT,
+                    "before": before,
+                    "until": until,
+                    "commitment": commitment.as_str(),
+                },
+            ]),
+            |result| {
+                let signatures: Vec<Value> = field(&result, "")?;
+                signatures
+                    .iter()
+                    .map(|info| {
+                        Ok(SignatureInfo {
+                            signature: field(info, "/signature")?,
+                            slot: field(info, "/slot")?,
+                            block_time: field(info, "/blockTime").ok().flatten(),
+                            err: field::<Option<Value>>(info, "/err")?,
+                        })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    pub fn signature_status(signature: &str) -> RpcCall<Option<SignatureStatus>> {
+        RpcCall::new(
+            "getSignatureStatuses",
+            json!([[signature], { "searchTransactionHistory": true }]),
+            |result| {
+                let status: Value = field(&result, "/value/0")?;
+                if status.is_null() {
+                    return Ok(None);
+                }
+                Ok(Some(SignatureStatus {
+                    slot: field(&status, "/slot")?,
+                    confirmation_status: field(&status, "/confirmationStatus").ok(),
+                    err: field::<Option<Value>>(&status, "/err")?,
+                }))
+            },
+        )
+    }
+}
+
+/// Blocking RPC client submitting OneSig instructions, for CLI tools and test harnesses.
+pub struct RpcClient<T> {
+    transport: T,
+    commitment: Commitment,
+}
+
+/// Async RPC client submitting OneSig instructions, for services; the futures are `Send` as long as
+/// the transport's are, so they run under tokio or any other executor.
+pub struct AsyncRpcClient<T> {
+    transport: T,
+    commitment: Commitment,
+}
+
+impl<T: RpcTransport> RpcClient<T> {
+    pub fn new(transport: T, commitment: Commitment) -> Self {
+        Self { transport, commitment }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+
+    fn call<R>(&self, call: RpcCall<R>) -> Result<R, SdkError> {
+        call.response(&self.transport.post(&call.body())?)
+    }
+
+    pub fn latest_blockhash(&self) -> Result<[u8; 32], SdkError> {
+        self.call(calls::latest_blockhash(self.commitment))
+    }
+
+    /// Data of an account, or `None` if it does not exist
+    pub fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, SdkError> {
+        self.call(calls::account_data(pubkey, self.commitment))
+    }
+
+    /// Current slot, e.g. to create a lookup table at
+    pub fn slot(&self) -> Result<u64, SdkError> {
+        self.call(calls::slot(self.commitment))
+    }
+
+    /// The lookup table at `address`, or `None` if it does not exist
+    pub fn lookup_table(&self, address: &Pubkey) -> Result<Option<LookupTable>, SdkError> {
+        self.account_data(address)?
+            .map(|data| LookupTable::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// The nonce account at `address`, or `None` if it does not exist, to sign durable
+    /// transactions with its current nonce
+    pub fn nonce_account(&self, address: &Pubkey) -> Result<Option<NonceAccount>, SdkError> {
+        self.account_data(address)?
+            .map(|data| NonceAccount::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// The `OneSigState` account at `address`, or `None` if it does not exist
+    pub fn one_sig_account(&self, address: &Pubkey) -> Result<Option<OneSigAccount>, SdkError> {
+        self.account_data(address)?
+            .map(|data| OneSigAccount::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// Sends a signed wire transaction, returning its signature.
+    pub fn send_transaction(&self, transaction: &[u8]) -> Result<String, SdkError> {
+        self.call(calls::send_transaction(transaction, self.commitment))
+    }
+
+    /// Sends a signed wire transaction already checked by a preflight, e.g. again to another node
+    pub fn rebroadcast_transaction(&self, transaction: &[u8]) -> Result<String, SdkError> {
+        self.call(calls::rebroadcast_transaction(transaction))
+    }
+
+    /// Events the OneSig program at `program_id` emitted in a landed transaction, `None` if the
+    /// node does not know it
+    pub fn transaction_events(
+        &self,
+        program_id: &Pubkey,
+        signature: &str,
+    ) -> Result<Option<TransactionEvents>, SdkError> {
+        let transaction = self.call(calls::transaction(signature, self.commitment))?;
+        transaction
+            .map(|transaction| {
+                TransactionEvents::from_transaction(program_id, signature, &transaction)
+            })
+            .transpose()
+    }
+
+    /// Transactions mentioning `address` newer than `until`, or all of them, newest first
+    pub fn signatures_for_address(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+    ) -> Result<Vec<SignatureInfo>, SdkError> {
+        let mut signatures: Vec<SignatureInfo> = vec![];
+        loop {
+            let before = signatures.last().map(|info| info.signature.as_str());
+            let page =
+                self.call(calls::signatures_for_address(address, before, until, self.commitment))?;
+            let last_page = page.len() < SIGNATURES_PAGE_LIMIT;
+            signatures.extend(page);
+            if last_page {
+                return Ok(signatures);
+            }
+        }
+    }
+
+    /// Walks the transactions of the OneSig of `history` since the last walk, recording the
+    /// events the OneSig program at `program_id` emitted about it. A walk cut short by an error
+    /// resumes from the last transaction recorded.
+    pub fn update_history(
+        &self,
+        program_id: &Pubkey,
+        history: &mut History,
+    ) -> Result<(), SdkError> {
+        let signatures = self
+            .signatures_for_address(&history.one_sig_account, history.last_signature.as_deref())?;
+        for info in signatures.into_iter().rev() {
+            let events = match info.err {
+                // Failed transactions emit no event
+                Some(err) => TransactionEvents {
+                    signature: info.signature,
+                    slot: info.slot,
+                    block_time: info.block_time,
+                    err: Some(err),
+                    fee: None,
+                    compute_units_consumed: None,
+                    events: vec![],
+                },
+                None => self
+                    .transaction_events(program_id, &info.signature)?
+                    .ok_or(SdkError::TransactionNotFound(info.signature))?,
+            };
+            history.push(events);
+        }
+        Ok(())
+    }
+
+    pub fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>, SdkError> {
+        self.call(calls::signature_status(signature))
+    }
+
+    /// Simulates `instructions` paid by `payer`, decoding errors of the OneSig program at
+    /// `program_id`, without signing nor sending anything.
+    pub fn preflight(
+        &self,
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<Preflight, SdkError> {
+        let transaction = Message::new(payer, instructions, [0; 32])?.unsigned();
+        let simulation = self.call(calls::simulate_transaction(&transaction, self.commitment))?;
+        Ok(simulation.preflight(program_id))
+    }
+
+    /// `instructions` after the compute budget instructions of `options`, simulating them, in a
+    /// transaction loading from `lookup_tables`, and fetching recent fees as `options` need.
+    pub fn with_compute_budget(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        options: &ComputeBudgetOptions,
+    ) -> Result<Vec<Instruction>, SdkError> {
+        let units_consumed = match options.unit_limit {
+            ComputeUnitLimit::Simulated { .. } => {
+                let transaction = budget_simulation(payer, instructions, lookup_tables)?;
+                let call = calls::simulate_transaction(&transaction, self.commitment);
+                Some(self.call(call)?.units_consumed()?)
+            },
+            _ => None,
+        };
+        let fees = match options.unit_price {
+            ComputeUnitPrice::Percentile { .. } => {
+                let accounts = writable_accounts(payer, instructions);
+                Some(self.call(calls::recent_prioritization_fees(&accounts))?)
+            },
+            _ => None,
+        };
+        Ok(with_budget(options, units_consumed, fees, instructions))
+    }
+
+    /// Signs `instructions` paid by `payer` with a recent blockhash and sends them, after the
+    /// compute budget instructions of `budget`, returning the transaction signature.
+    pub fn send_instructions(
+        &self,
+        payer: &dyn TransactionSigner,
+        instructions: &[Instruction],
+        signers: &[&dyn TransactionSigner],
+        budget: &ComputeBudgetOptions,
+    ) -> Result<String, SdkError> {
+        let instructions = self.with_compute_budget(&payer.pubkey(), instructions, &[], budget)?;
+        let message = Message::new(&payer.pubkey(), &instructions, self.latest_blockhash()?)?;
+        self.send_transaction(&message.sign(&[&[payer], signers].concat())?)
+    }
+
+    /// As [`Self::send_instructions`], in a v0 transaction loading accounts from `lookup_tables`.
+    pub fn send_instructions_v0(
+        &self,
+        payer: &dyn TransactionSigner,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        signers: &[&dyn TransactionSigner],
+        budget: &ComputeBudgetOptions,
+    ) -> Result<String, SdkError> {
+        let payer_key = payer.pubkey();
+        let instructions =
+            self.with_compute_budget(&payer_key, instructions, lookup_tables, budget)?;
+        let blockhash = self.latest_blockhash()?;
+        let message = MessageV0::new(&payer_key, &instructions, lookup_tables, blockhash)?;
+        self.send_transaction(&message.sign(&[&[payer], signers].concat())?)
+    }
+}
+
+impl<T: AsyncRpcTransport> AsyncRpcClient<T> {
+    pub fn new(transport: T, commitment: Commitment) -> Self {
+        Self { transport, commitment }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+
+    async fn call<R>(&self, call: RpcCall<R>) -> Result<R, SdkError> {
+        call.response(&self.transport.post(call.body()).await?)
+    }
+
+    pub async fn latest_blockhash(&self) -> Result<[u8; 32], SdkError> {
+        self.call(calls::latest_blockhash(self.commitment)).await
+    }
+
+    /// Data of an account, or `None` if it does not exist
+    pub async fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, SdkError> {
+        self.call(calls::account_data(pubkey, self.commitment)).await
+    }
+
+    /// Current slot, e.g. to create a lookup table at
+    pub async fn slot(&self) -> Result<u64, SdkError> {
+        self.call(calls::slot(self.commitment)).await
+    }
+
+    /// The lookup table at `address`, or `None` if it does not exist
+    pub async fn lookup_table(&self, address: &Pubkey) -> Result<Option<LookupTable>, SdkError> {
+        self.account_data(address)
+            .await?
+            .map(|data| LookupTable::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// The nonce account at `address`, or `None` if it does not exist, to sign durable
+    /// transactions with its current nonce
+    pub async fn nonce_account(&self, address: &Pubkey) -> Result<Option<NonceAccount>, SdkError> {
+        self.account_data(address)
+            .await?
+            .map(|data| NonceAccount::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// The `OneSigState` account at `address`, or `None` if it does not exist
+    pub async fn one_sig_account(
+        &self,
+        address: &Pubkey,
+    ) -> Result<Option<OneSigAccount>, SdkError> {
+        self.account_data(address)
+            .await?
+            .map(|data| OneSigAccount::from_account_data(*address, &data))
+            .transpose()
+    }
+
+    /// Sends a signed wire transaction, returning its signature.
+    pub async fn send_transaction(&self, transaction: &[u8]) -> Result<String, SdkError> {
+        self.call(calls::send_transaction(transaction, self.commitment)).await
+    }
+
+    /// Sends a signed wire transaction already checked by a preflight, e.g. again to another node
+    pub async fn rebroadcast_transaction(&self, transaction: &[u8]) -> Result<String, SdkError> {
+        self.call(calls::rebroadcast_transaction(transaction)).await
+    }
+
+    /// Events the OneSig program at `program_id` emitted in a landed transaction, `None` if the
+    /// node does not know it
+    pub async fn transaction_events(
+        &self,
+        program_id: &Pubkey,
+        signature: &str,
+    ) -> Result<Option<TransactionEvents>, SdkError> {
+        let transaction = self.call(calls::transaction(signature, self.commitment)).await?;
+        transaction
+            .map(|transaction| {
+                TransactionEvents::from_transaction(program_id, signature, &transaction)
+            })
+            .transpose()
+    }
+
+    /// Transactions mentioning `address` newer than `until`, or all of them, newest first
+    pub async fn signatures_for_address(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+    ) -> Result<Vec<SignatureInfo>, SdkError> {
+        let mut signatures: Vec<SignatureInfo> = vec![];
+        loop {
+            let before = signatures.last().map(|info| info.signature.as_str());
+            let page = self
+                .call(calls::signatures_for_address(address, before, until, self.commitment))
+                .await?;
+            let last_page = page.len() < SIGNATURES_PAGE_LIMIT;
+            signatures.extend(page);
+            if last_page {
+                return Ok(signatures);
+            }
+        }
+    }
+
+    /// Walks the transactions of the OneSig of `history` since the last walk, recording the
+    /// events the OneSig program at `program_id` emitted about it. A walk cut short by an error
+    /// resumes from the last transaction recorded.
+    pub async fn update_history(
+        &self,
+        program_id: &Pubkey,
+        history: &mut History,
+    ) -> Result<(), SdkError> {
+        let signatures = self
+            .signatures_for_address(&history.one_sig_account, history.last_signature.as_deref())
+            .await?;
+        for info in signatures.into_iter().rev() {
+            let events = match info.err {
+                // Failed transactions emit no event
+                Some(err) => TransactionEvents {
+                    signature: info.signature,
+                    slot: info.slot,
+                    block_time: info.block_time,
+                    err: Some(err),
+                    fee: None,
+                    compute_units_consumed: None,
+                    events: vec![],
+                },
+                None => self
+                    .transaction_events(program_id, &info.signature)
+                    .await?
+                    .ok_or(SdkError::TransactionNotFound(info.signature))?,
+            };
+            history.push(events);
+        }
+        Ok(())
+    }
+
+    pub async fn signature_status(
+        &self,
+        signature: &str,
+    ) -> Result<Option<SignatureStatus>, SdkError> {
+        self.call(calls::signature_status(signature)).await
+    }
+
+    /// Simulates `instructions` paid by `payer`, decoding errors of the OneSig program at
+    /// `program_id`, without signing nor sending anything.
+    pub async fn preflight(
+        &self,
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<Preflight, SdkError> {
+        let transaction = Message::new(payer, instructions, [0; 32])?.unsigned();
+        let simulation =
+            self.call(calls::simulate_transaction(&transaction, self.commitment)).await?;
+        Ok(simulation.preflight(program_id))
+    }
+
+    /// `instructions` after the compute budget instructions of `options`, simulating them, in a
+    /// transaction loading from `lookup_tables`, and fetching recent fees as `options` need.
+    pub async fn with_compute_budget(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        options: &ComputeBudgetOptions,
+    ) -> Result<Vec<Instruction>, SdkError> {
+        let units_consumed = match options.unit_limit {
+            ComputeUnitLimit::Simulated { .. } => {
+                let transaction = budget_simulation(payer, instructions, lookup_tables)?;
+                let call = calls::simulate_transaction(&transaction, self.commitment);
+                Some(self.call(call).await?.units_consumed()?)
+            },
+            _ => None,
+        };
+        let fees = match options.unit_price {
+            ComputeUnitPrice::Percentile { .. } => {
+                let accounts = writable_accounts(payer, instructions);
+                Some(self.call(calls::recent_prioritization_fees(&accounts)).await?)
+            },
+            _ => None,
+        };
+        Ok(with_budget(options, units_consumed, fees, instructions))
+    }
+
+    /// Signs `instructions` paid by `payer` with a recent blockhash and sends them, after the
+    /// compute budget instructions of `budget`, returning the transaction signature.
+    pub async fn send_instructions(
+        &self,
+        payer: &(dyn TransactionSigner + Sync),
+        instructions: &[Instruction],
+        signers: &[&(dyn TransactionSigner + Sync)],
+        budget: &ComputeBudgetOptions,
+    ) -> Result<String, SdkError> {
+        let payer_key = payer.pubkey();
+        let instructions = self.with_compute_budget(&payer_key, instructions, &[], budget).await?;
+        let blockhash = self.latest_blockhash().await?;
+        let message = Message::new(&payer_key, &instructions, blockhash)?;
+        let signers: Vec<&dyn TransactionSigner> = std::iter::once(payer as &dyn TransactionSigner)
+            .chain(signers.iter().map(|signer| *signer as &dyn TransactionSigner))
+            .collect();
+        let transaction = message.sign(&signers)?;
+        self.send_transaction(&transaction).await
+    }
+
+    /// As [`Self::send_instructions`], in a v0 transaction loading accounts from `lookup_tables`.
+    pub async fn send_instructions_v0(
+        &self,
+        payer: &(dyn TransactionSigner + Sync),
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        signers: &[&(dyn TransactionSigner + Sync)],
+        budget: &ComputeBudgetOptions,
+    ) -> Result<String, SdkError> {
+        let payer_key = payer.pubkey();
+        let instructions = self
+            .with_compute_budget(&payer_key, instructions, lookup_tables, budget)
+            .await?;
+        let blockhash = self.latest_blockhash().await?;
+        let message = MessageV0::new(&payer_key, &instructions, lookup_tables, blockhash)?;
+        let signers: Vec<&dyn TransactionSigner> = std::iter::once(payer as &dyn TransactionSigner)
+            .chain(signers.iter().map(|signer| *signer as &dyn TransactionSigner))
+            .collect();
+        let transaction = message.sign(&signers)?;
+        self.send_transaction(&transaction).await
+    }
+}
@@ -0,0 +1,484 @@
+// Tests for the blocking and async RPC clients, against a transport replaying canned responses.
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        future::{ready, Future},
+        pin::pin,
+        sync::Mutex,
+        task::{Context, Poll, Waker},
+    };
+
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+    };
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde_json::{json, Value};
+
+    use crate::{
+        compute_budget::{
+            set_compute_unit_limit, set_compute_unit_price, ComputeBudgetOptions, ComputeUnitLimit,
+            ComputeUnitPrice, MAX_COMPUTE_UNIT_LIMIT,
+        },
+        error::SdkError,
+        lookup_table::LookupTable,
+        nonce::NonceAccount,
+        rpc::{
+            AsyncRpcClient, AsyncRpcTransport, Commitment, RpcClient, RpcTransport, SignatureStatus,
+        },
+        transaction::{Message, MessageV0, TransactionSigner, TRANSACTION_SIGNATURE_LEN},
+    };
+
+    const BLOCKHASH: [u8; 32] = [0xbb; 32];
+
+    /// Replays `responses` in order, recording the requests
+    struct MockTransport {
+        requests: RefCell<Vec<Value>>,
+        responses: RefCell<Vec<Value>>,
+    }
+
+    impl MockTransport {
+        fn new(responses: impl IntoIterator<Item = Value>) -> Self {
+            let mut responses: Vec<Value> = responses.into_iter().collect();
+            responses.reverse();
+            Self { requests: RefCell::default(), responses: RefCell::new(responses) }
+        }
+
+        fn requests(&self) -> Vec<Value> {
+            self.requests.borrow().clone()
+        }
+    }
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            self.requests.borrow_mut().push(serde_json::from_str(body).unwrap());
+            let response = self.responses.borrow_mut().pop();
+            response
+                .map(|response| response.to_string())
+                .ok_or(SdkError::Transport("closed".into()))
+        }
+    }
+
+    /// [`MockTransport`] behind the async interface, `Sync` as async clients' transports are
+    struct AsyncMockTransport(Mutex<MockTransport>);
+
+    impl AsyncRpcTransport for AsyncMockTransport {
+        fn post(&self, body: String) -> impl Future<Output = Result<String, SdkError>> + Send {
+            ready(self.0.lock().unwrap().post(&body))
+        }
+    }
+
+    // The mock's futures are ready at once
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the mock transport does not suspend"),
+        }
+    }
+
+    struct FixedSigner(Pubkey);
+
+    impl TransactionSigner for FixedSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.0
+        }
+
+        fn sign_message(
+            &self,
+            _message: &[u8],
+        ) -> Result<[u8; TRANSACTION_SIGNATURE_LEN], SdkError> {
+            Ok([0x5a; TRANSACTION_SIGNATURE_LEN])
+        }
+    }
+
+    fn result(result: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "result": result })
+    }
+
+    fn blockhash_response() -> Value {
+        result(json!({
+            "context": { "slot": 1 },
+            "value": { "blockhash": bs58::encode(BLOCKHASH).into_string(), "lastValidBlockHeight": 9 },
+        }))
+    }
+
+    fn instruction(signer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_from_array([0x90; 32]),
+            accounts: vec![AccountMeta::new(*signer, true)],
+            data: vec![7],
+        }
+    }
+
+    #[test]
+    fn test_latest_blockhash() {
+        let client =
+            RpcClient::new(MockTransport::new([blockhash_response()]), Commitment::Finalized);
+        assert_eq!(client.latest_blockhash(), Ok(BLOCKHASH));
+        assert_eq!(
+            client.transport().requests(),
+            [json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getLatestBlockhash",
+                "params": [{ "commitment": "finalized" }],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_account_data() {
+        let account = result(json!({
+            "context": { "slot": 1 },
+            "value": { "data": [BASE64_STANDARD.encode([1, 2, 3]), "base64"], "lamports": 5 },
+        }));
+        let missing = result(json!({ "context": { "slot": 1 }, "value": null }));
+        let client = RpcClient::new(MockTransport::new([account, missing]), Commitment::default());
+        let pubkey = Pubkey::new_from_array([0x11; 32]);
+
+        assert_eq!(client.account_data(&pubkey), Ok(Some(vec![1, 2, 3])));
+        assert_eq!(client.account_data(&pubkey), Ok(None));
+        assert_eq!(
+            client.transport().requests()[0]["params"],
+            json!([pubkey.to_string(), { "encoding": "base64", "commitment": "confirmed" }])
+        );
+    }
+
+    #[test]
+    fn test_signature_status() {
+        let status = result(json!({
+            "context": { "slot": 1 },
+            "value": [{ "slot": 42, "confirmations": null, "err": null, "confirmationStatus": "finalized" }],
+        }));
+        let failed = result(json!({
+            "context": { "slot": 1 },
+            "value": [{ "slot": 43, "err": { "InstructionError": [0, { "Custom": 6001 }] } }],
+        }));
+        let unknown = result(json!({ "context": { "slot": 1 }, "value": [null] }));
+        let client =
+            RpcClient::new(MockTransport::new([status, failed, unknown]), Commitment::default());
+
+        assert_eq!(
+            client.signature_status("sig"),
+            Ok(Some(SignatureStatus {
+                slot: 42,
+                confirmation_status: Some("finalized".into()),
+                err: None,
+            }))
+        );
+        let failed = client.signature_status("sig").unwrap().unwrap();
+        assert_eq!(failed.err, Some(json!({ "InstructionError": [0, { "Custom": 6001 }] })));
+        assert_eq!(client.signature_status("sig"), Ok(None));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32002, "message": "Transaction simulation failed", "data": { "logs": [] } },
+        });
+        let client = RpcClient::new(
+            MockTransport::new([error, json!("not a response"), result(json!({ "value": {} }))]),
+            Commitment::default(),
+        );
+
+        assert_eq!(
+            client.send_transaction(&[1]),
+            Err(SdkError::Rpc {
+                code: -32002,
+                message: "Transaction simulation failed".into(),
+                data: Some(json!({ "logs": [] })),
+            })
+        );
+        assert!(matches!(client.latest_blockhash(), Err(SdkError::InvalidRpcResponse(_))));
+        assert!(matches!(client.latest_blockhash(), Err(SdkError::InvalidRpcResponse(_))));
+        assert_eq!(client.latest_blockhash(), Err(SdkError::Transport("closed".into())));
+    }
+
+    #[test]
+    fn test_send_instructions() {
+        let payer = FixedSigner(Pubkey::new_from_array([0x10; 32]));
+        let signer = FixedSigner(Pubkey::new_from_array([0x20; 32]));
+        let client = RpcClient::new(
+            MockTransport::new([blockhash_response(), result(json!("5ig"))]),
+            Commitment::Processed,
+        );
+
+        let signature = client.send_instructions(
+            &payer,
+            &[instruction(&signer.0)],
+            &[&signer],
+            &ComputeBudgetOptions::default(),
+        );
+        assert_eq!(signature, Ok("5ig".into()));
+
+        let requests = client.transport().requests();
+        assert_eq!(requests[1]["method"], "sendTransaction");
+        assert_eq!(
+            requests[1]["params"][1],
+            json!({ "encoding": "base64", "preflightCommitment": "processed" })
+        );
+        let message = Message::new(&payer.0, &[instruction(&signer.0)], BLOCKHASH).unwrap();
+        let transaction = message.sign(&[&payer, &signer]).unwrap();
+        assert_eq!(requests[1]["params"][0], BASE64_STANDARD.encode(transaction));
+    }
+
+    #[test]
+    fn test_lookup_table() {
+        let address = Pubkey::new_from_array([0xa1; 32]);
+        let entry = Pubkey::new_from_array([0x30; 32]);
+        let mut data = vec![1, 0, 0, 0];
+        data.extend([0; 52]);
+        data.extend_from_slice(entry.as_ref());
+        let account = result(json!({
+            "context": { "slot": 1 },
+            "value": { "data": [BASE64_STANDARD.encode(&data), "base64"] },
+        }));
+        let missing = result(json!({ "context": { "slot": 1 }, "value": null }));
+        let client = RpcClient::new(
+            MockTransport::new([result(json!(300)), account, missing]),
+            Commitment::default(),
+        );
+
+        assert_eq!(client.slot(), Ok(300));
+        assert_eq!(
+            client.lookup_table(&address),
+            Ok(Some(LookupTable { address, authority: None, addresses: vec![entry] }))
+        );
+        assert_eq!(client.lookup_table(&address), Ok(None));
+        assert_eq!(client.transport().requests()[0]["method"], "getSlot");
+    }
+
+    #[test]
+    fn test_nonce_account() {
+        let address = Pubkey::new_from_array([0x4e; 32]);
+        let authority = Pubkey::new_from_array([0x10; 32]);
+        let mut data = vec![1, 0, 0, 0, 1, 0, 0, 0];
+        data.extend_from_slice(authority.as_ref());
+        data.extend([0xdd; 32]);
+        data.extend(5_000u64.to_le_bytes());
+        let account = result(json!({
+            "context": { "slot": 1 },
+            "value": { "data": [BASE64_STANDARD.encode(&data), "base64"] },
+        }));
+        let client = RpcClient::new(MockTransport::new([account]), Commitment::default());
+
+        assert_eq!(
+            client.nonce_account(&address),
+            Ok(Some(NonceAccount {
+                address,
+                authority,
+                durable_nonce: [0xdd; 32],
+                lamports_per_signature: 5_000,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_send_instructions_v0() {
+        let payer = FixedSigner(Pubkey::new_from_array([0x10; 32]));
+        let program_id = Pubkey::new_from_array([0x90; 32]);
+        let table = LookupTable {
+            address: Pubkey::new_from_array([0xa1; 32]),
+            authority: None,
+            addresses: vec![program_id, Pubkey::new_from_array([0x30; 32])],
+        };
+        let instruction = Instruction {
+            program_id,
+            accounts: vec![AccountMeta::new(table.addresses[1], false)],
+            data: vec![],
+        };
+        let client = RpcClient::new(
+            MockTransport::new([blockhash_response(), result(json!("5ig"))]),
+            Commitment::default(),
+        );
+
+        let signature = client.send_instructions_v0(
+            &payer,
+            std::slice::from_ref(&instruction),
+            std::slice::from_ref(&table),
+            &[],
+            &ComputeBudgetOptions::default(),
+        );
+        assert_eq!(signature, Ok("5ig".into()));
+        let message = MessageV0::new(&payer.0, &[instruction], &[table], BLOCKHASH).unwrap();
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(
+            client.transport().requests()[1]["params"][0],
+            BASE64_STANDARD.encode(message.sign(&[&payer]).unwrap())
+        );
+    }
+
+    fn simulation_response(units_consumed: u64) -> Value {
+        result(json!({
+            "context": { "slot": 1 },
+            "value": { "err": null, "logs": [], "unitsConsumed": units_consumed },
+        }))
+    }
+
+    fn fees_response(fees: &[u64]) -> Value {
+        let fees: Vec<Value> = fees
+            .iter()
+            .enumerate()
+            .map(|(slot, fee)| json!({ "slot": slot, "prioritizationFee": fee }))
+            .collect();
+        result(json!(fees))
+    }
+
+    #[test]
+    fn test_send_instructions_with_budget() {
+        let payer = FixedSigner(Pubkey::new_from_array([0x10; 32]));
+        let signer = FixedSigner(Pubkey::new_from_array([0x20; 32]));
+        let client = RpcClient::new(
+            MockTransport::new([
+                simulation_response(50_000),
+                fees_response(&[0, 300, 100, 200]),
+                blockhash_response(),
+                result(json!("5ig")),
+            ]),
+            Commitment::default(),
+        );
+        let budget = ComputeBudgetOptions {
+            unit_limit: ComputeUnitLimit::Simulated { margin_percent: 20 },
+            unit_price: ComputeUnitPrice::Percentile { percentile: 75, max: 150 },
+        };
+
+        let signature =
+            client.send_instructions(&payer, &[instruction(&signer.0)], &[&signer], &budget);
+        assert_eq!(signature, Ok("5ig".into()));
+
+        let requests = client.transport().requests();
+        // Simulated under the highest limit, skipping signatures
+        assert_eq!(requests[0]["method"], "simulateTransaction");
+        let simulated = Message::new(
+            &payer.0,
+            &[set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT), instruction(&signer.0)],
+            [0; 32],
+        )
+        .unwrap();
+        assert_eq!(requests[0]["params"][0], BASE64_STANDARD.encode(simulated.unsigned()));
+        // Priced from the fees of the accounts it writes, the 75th percentile 200 capped at 150
+        assert_eq!(requests[1]["method"], "getRecentPrioritizationFees");
+        assert_eq!(requests[1]["params"], json!([[payer.0.to_string(), signer.0.to_string()]]));
+        let message = Message::new(
+            &payer.0,
+            &[set_compute_unit_limit(60_000), set_compute_unit_price(150), instruction(&signer.0)],
+            BLOCKHASH,
+        )
+        .unwrap();
+        assert_eq!(
+            requests[3]["params"][0],
+            BASE64_STANDARD.encode(message.sign(&[&payer, &signer]).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_compute_budget_simulation_failure() {
+        let failed = result(json!({
+            "context": { "slot": 1 },
+            "value": {
+                "err": { "InstructionError": [1, { "Custom": 6009 }] },
+                "logs": ["Program log: failed"],
+                "unitsConsumed": 900,
+            },
+        }));
+        let client = RpcClient::new(MockTransport::new([failed]), Commitment::default());
+        let payer = Pubkey::new_from_array([0x10; 32]);
+        let budget = ComputeBudgetOptions::estimated(1_000);
+
+        assert_eq!(
+            client.with_compute_budget(&payer, &[instruction(&payer)], &[], &budget),
+            Err(SdkError::SimulationFailed {
+                err: json!({ "InstructionError": [1, { "Custom": 6009 }] }),
+                logs: vec!["Program log: failed".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_fixed_compute_budget() {
+        // Fixed budgets need no request
+        let client = RpcClient::new(MockTransport::new([]), Commitment::default());
+        let payer = Pubkey::new_from_array([0x10; 32]);
+        let budget = ComputeBudgetOptions {
+            unit_limit: ComputeUnitLimit::Fixed(80_000),
+            unit_price: ComputeUnitPrice::Fixed(5),
+        };
+
+        let instructions = client
+            .with_compute_budget(&payer, &[instruction(&payer)], &[], &budget)
+            .unwrap();
+        assert_eq!(
+            instructions,
+            [set_compute_unit_limit(80_000), set_compute_unit_price(5), instruction(&payer)]
+        );
+        let instructions = client
+            .with_compute_budget(&payer, &[instruction(&payer)], &[], &Default::default())
+            .unwrap();
+        assert_eq!(instructions, [instruction(&payer)]);
+        assert!(client.transport().requests().is_empty());
+    }
+
+    #[test]
+    fn test_async_compute_budget() {
+        let payer = Pubkey::new_from_array([0x10; 32]);
+        let responses = || [simulation_response(1_000), fees_response(&[])];
+        let client = AsyncRpcClient::new(
+            AsyncMockTransport(Mutex::new(MockTransport::new(responses()))),
+            Commitment::default(),
+        );
+        let budget = ComputeBudgetOptions::estimated(1_000);
+
+        let instructions =
+            block_on(client.with_compute_budget(&payer, &[instruction(&payer)], &[], &budget));
+        // No recent fee, no price
+        assert_eq!(instructions, Ok(vec![set_compute_unit_limit(1_100), instruction(&payer)]));
+
+        let blocking = RpcClient::new(MockTransport::new(responses()), Commitment::default());
+        blocking
+            .with_compute_budget(&payer, &[instruction(&payer)], &[], &budget)
+            .unwrap();
+        assert_eq!(
+            client.transport().0.lock().unwrap().requests(),
+            blocking.transport().requests()
+        );
+    }
+
+    #[test]
+    fn test_async_client() {
+        let payer = FixedSigner(Pubkey::new_from_array([0x10; 32]));
+        let transport =
+            MockTransport::new([blockhash_response(), blockhash_response(), result(json!("5ig"))]);
+        let client =
+            AsyncRpcClient::new(AsyncMockTransport(Mutex::new(transport)), Commitment::default());
+
+        assert_eq!(block_on(client.latest_blockhash()), Ok(BLOCKHASH));
+        let signature = block_on(client.send_instructions(
+            &payer,
+            &[instruction(&payer.0)],
+            &[],
+            &ComputeBudgetOptions::default(),
+        ));
+        assert_eq!(signature, Ok("5ig".into()));
+        assert_eq!(block_on(client.latest_blockhash()), Err(SdkError::Transport("closed".into())));
+
+        // Both clients send the same requests
+        let requests = client.transport().0.lock().unwrap().requests();
+        let blocking = RpcClient::new(
+            MockTransport::new([blockhash_response(), result(json!("5ig"))]),
+            Commitment::default(),
+        );
+        blocking
+            .send_instructions(
+                &payer,
+                &[instruction(&payer.0)],
+                &[],
+                &ComputeBudgetOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(requests[1..3], blocking.transport().requests());
+    }
+}
@@ -0,0 +1,172 @@
+use onesig_core::{Address, Hash, Signature, SIGNATURE_BYTES_LEN};
+use serde_json::Value;
+
+use crate::{
+    digest::merkle_root_message,
+    encoding::hex,
+    error::SdkError,
+    signatures::{normalize_recovery_id, recover_signer},
+};
+
+/// Length of an EIP-2098 compact signature, r‖yParityAndS
+const COMPACT_SIGNATURE_LEN: usize = 64;
+
+/// A signature imported from other tooling, with the signer it recovers to
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ImportedSignature {
+    pub signer: Address,
+    /// r‖s‖v, v normalized to 27/28 as [`pack_signatures`](crate::pack_signatures) takes it
+    pub signature: Signature,
+}
+
+/// Imports signatures of `digest` exported from ethers: one object or an array of them, each with
+/// the `signature` of `signTypedData`, and optionally the `address` that signed it and the signed
+/// `message` (or `value`) with its `domain` and `types`.
+///
+/// Signatures may be 65-byte hex, 64-byte EIP-2098 compact hex, or split into `r`, `s` and `v`
+/// (or `yParity`) as ethers' `Signature` serializes. A signer or message that does not match
+/// `digest` fails the import, so signatures of another root are caught before being packed.
+pub fn import_ethers_signatures(
+    json: &str,
+    digest: &Hash,
+) -> Result<Vec<ImportedSignature>, SdkError> {
+    let json: Value =
+        serde_json::from_str(json).map_err(|error| SdkError::Json(error.to_string()))?;
+    let entries = match json {
+        Value::Array(entries) => entries,
+        entry => vec![entry],
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            if let Some(message) = entry.get("message").or_else(|| entry.get("value")) {
+                if signed_digest(message)? != *digest {
+                    return Err(SdkError::SignedMessageMismatch);
+                }
+            }
+            let signature = entry
+                .get("signature")
+                .ok_or_else(|| SdkError::Json("missing signature".into()))?;
+            let signer = entry.get("address").or_else(|| entry.get("signer"));
+            import(digest, parse_signature(signature)?, signer)
+        })
+        .collect()
+}
+
+/// Imports the confirmations of a message or multisig transaction exported from the Safe
+/// transaction service, or a bare array of them: `owner`, `signature` and `signatureType` each.
+///
+/// Only `EOA` confirmations are signatures `verify_merkle_root` can recover, and only if the owner
+/// signed `digest` itself; contract, `ETH_SIGN` and approved-hash confirmations are rejected.
+pub fn import_safe_confirmations(
+    json: &str,
+    digest: &Hash,
+) -> Result<Vec<ImportedSignature>, SdkError> {
+    let json: Value =
+        serde_json::from_str(json).map_err(|error| SdkError::Json(error.to_string()))?;
+    let confirmations = match &json {
+        Value::Array(confirmations) => confirmations,
+        export => export
+            .get("confirmations")
+            .and_then(Value::as_array)
+            .ok_or_else(|| SdkError::Json("missing confirmations".into()))?,
+    };
+    confirmations
+        .iter()
+        .map(|confirmation| {
+            let signature_type = string(confirmation, "signatureType")?;
+            if signature_type != "EOA" {
+                return Err(SdkError::UnsupportedSignatureType(signature_type.into()));
+            }
+            let signature =
+                hex::decode::<[u8; SIGNATURE_BYTES_LEN]>(string(confirmation, "signature")?)
+                    .map_err(SdkError::Hex)?;
+            import(digest, Signature(signature), confirmation.get("owner"))
+        })
+        .collect()
+}
+
+fn import(
+    digest: &Hash,
+    signature: Signature,
+    signer: Option<&Value>,
+) -> Result<ImportedSignature, SdkError> {
+    let signature = normalize_recovery_id(&signature)?;
+    let recovered = recover_signer(digest, &signature).ok_or(SdkError::FailedRecovery)?;
+    if let Some(signer) = signer {
+        let signer = signer.as_str().ok_or_else(|| SdkError::Json("invalid signer".into()))?;
+        if hex::decode::<Address>(signer).map_err(SdkError::Hex)? != recovered {
+            return Err(SdkError::SignerMismatch);
+        }
+    }
+    Ok(ImportedSignature { signer: recovered, signature })
+}
+
+/// Digest of a signed `SignMerkleRoot` or `SignLimitedMerkleRoot` message
+fn signed_digest(message: &Value) -> Result<Hash, SdkError> {
+    let hash = |name: &str| hex::decode::<[u8; 32]>(string(message, name)?).map_err(SdkError::Hex);
+    let max_executions = match message.get("maxExecutions") {
+        Some(max_executions) => uint(max_executions)?,
+        None => 0,
+    };
+    let expiry = message.get("expiry").ok_or_else(|| SdkError::Json("missing expiry".into()))?;
+    Ok(merkle_root_message(
+        &Hash(hash("seed")?),
+        &Hash(hash("merkleRoot")?),
+        uint(expiry)?,
+        max_executions
+            .try_into()
+            .map_err(|_| SdkError::Json("invalid maxExecutions".into()))?,
+    )
+    .digest())
+}
+
+/// A hex signature, full or compact, or one split into its parts
+fn parse_signature(signature: &Value) -> Result<Signature, SdkError> {
+    let mut bytes = [0u8; SIGNATURE_BYTES_LEN];
+    if let Some(signature) = signature.as_str() {
+        let decoded: Vec<u8> = hex::decode(signature).map_err(SdkError::Hex)?;
+        match decoded.len() {
+            SIGNATURE_BYTES_LEN => bytes.copy_from_slice(&decoded),
+            COMPACT_SIGNATURE_LEN => {
+                // The top bit of s is the y parity
+                bytes[..64].copy_from_slice(&decoded);
+                bytes[32] &= 0x7f;
+                bytes[64] = 27 + (decoded[32] >> 7);
+            },
+            len => return Err(SdkError::SignatureLength(len)),
+        }
+        return Ok(Signature(bytes));
+    }
+    bytes[..32]
+        .copy_from_slice(&hex::decode::<[u8; 32]>(string(signature, "r")?).map_err(SdkError::Hex)?);
+    bytes[32..64]
+        .copy_from_slice(&hex::decode::<[u8; 32]>(string(signature, "s")?).map_err(SdkError::Hex)?);
+    let v = match (signature.get("v"), signature.get("yParity")) {
+        (Some(v), _) if !v.is_null() => uint(v)?,
+        (_, Some(y_parity)) => uint(y_parity)?,
+        _ => return Err(SdkError::Json("missing v".into())),
+    };
+    bytes[64] = v.try_into().map_err(|_| SdkError::Json(format!("invalid v {v}")))?;
+    Ok(Signature(bytes))
+}
+
+fn string<'a>(value: &'a Value, name: &str) -> Result<&'a str, SdkError> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| SdkError::Json(format!("missing {name}")))
+}
+
+/// An integer as JSON numbers, decimal strings or 0x-prefixed hex strings write it
+fn uint(value: &Value) -> Result<u64, SdkError> {
+    let invalid = || SdkError::Json(format!("invalid integer {value}"));
+    match value {
+        Value::Number(number) => number.as_u64().ok_or_else(invalid),
+        Value::String(string) => match string.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).map_err(|_| invalid()),
+            None => string.parse().map_err(|_| invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
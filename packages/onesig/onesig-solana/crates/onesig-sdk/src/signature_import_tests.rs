@@ -0,0 +1,168 @@
+// Tests for importing signatures exported by ethers and the Safe transaction service.
+#[cfg(test)]
+mod tests {
+    use onesig_core::{Hash, Signature};
+    use serde_json::{json, Value};
+
+    use crate::{
+        digest::{merkle_root_message, Eip712Message},
+        encoding::hex,
+        error::SdkError,
+        signature_import::{
+            import_ethers_signatures, import_safe_confirmations, ImportedSignature,
+        },
+        signatures::pack_signatures,
+        signer::{LocalSigner, RootSigner},
+    };
+
+    const SEED: Hash = Hash([0x5e; 32]);
+    const ROOT: Hash = Hash([0x0a; 32]);
+    const EXPIRY: u64 = 1_700_000_000;
+
+    fn message() -> Eip712Message {
+        merkle_root_message(&SEED, &ROOT, EXPIRY, 0)
+    }
+
+    fn signer(key: u8) -> LocalSigner {
+        LocalSigner::from_bytes(&[key; 32]).unwrap()
+    }
+
+    fn signed(key: u8) -> (String, Signature) {
+        let signer = signer(key);
+        (hex::encode(&signer.address().0), signer.sign(&message()).unwrap())
+    }
+
+    /// The typed data `signTypedData` signs, with its signature and signer
+    fn ethers_export(key: u8, signature: Value) -> Value {
+        json!({
+            "domain": { "name": "OneSig", "version": "0.0.1", "chainId": 1 },
+            "types": {
+                "SignMerkleRoot": [
+                    { "name": "seed", "type": "bytes32" },
+                    { "name": "merkleRoot", "type": "bytes32" },
+                    { "name": "expiry", "type": "uint256" },
+                ],
+            },
+            "message": {
+                "seed": hex::encode(&SEED.0),
+                "merkleRoot": hex::encode(&ROOT.0),
+                "expiry": EXPIRY.to_string(),
+            },
+            "address": signed(key).0,
+            "signature": signature,
+        })
+    }
+
+    #[test]
+    fn test_import_ethers_signatures() {
+        let digest = message().digest();
+        let (address, signature) = signed(1);
+        let mut raw = signature;
+        raw.0[64] -= 27;
+        // EIP-2098: s with the y parity in its top bit
+        let mut compact = signature.0[..64].to_vec();
+        compact[32] |= (signature.0[64] - 27) << 7;
+
+        for exported in [
+            json!(hex::encode(&signature.0)),
+            json!(hex::encode(&raw.0)),
+            json!(hex::encode(&compact)),
+            json!({
+                "r": hex::encode(&signature.0[..32]),
+                "s": hex::encode(&signature.0[32..64]),
+                "v": signature.0[64],
+            }),
+            json!({
+                "r": hex::encode(&signature.0[..32]),
+                "s": hex::encode(&signature.0[32..64]),
+                "v": null,
+                "yParity": signature.0[64] - 27,
+            }),
+        ] {
+            let imported =
+                import_ethers_signatures(&ethers_export(1, exported).to_string(), &digest).unwrap();
+            assert_eq!(imported.len(), 1);
+            assert_eq!(hex::encode(&imported[0].signer.0), address);
+            assert!(imported[0].signature == signature);
+        }
+
+        // An array of bare signatures
+        let export = json!([{ "signature": hex::encode(&signed(2).1.0) }, { "signature": hex::encode(&signature.0) }]);
+        let imported = import_ethers_signatures(&export.to_string(), &digest).unwrap();
+        assert_eq!(imported[0].signer, signer(2).address());
+        assert_eq!(imported[1].signer, signer(1).address());
+    }
+
+    #[test]
+    fn test_import_ethers_mismatch() {
+        let digest = message().digest();
+        let signature = json!(hex::encode(&signed(1).1 .0));
+
+        let mut other_root = ethers_export(1, signature.clone());
+        other_root["message"]["merkleRoot"] = json!(hex::encode(&[0x0b; 32]));
+        assert!(matches!(
+            import_ethers_signatures(&other_root.to_string(), &digest),
+            Err(SdkError::SignedMessageMismatch)
+        ));
+
+        let mut other_signer = ethers_export(1, signature);
+        other_signer["address"] = json!(signed(2).0);
+        assert!(matches!(
+            import_ethers_signatures(&other_signer.to_string(), &digest),
+            Err(SdkError::SignerMismatch)
+        ));
+    }
+
+    fn confirmation(key: u8, signature_type: &str) -> Value {
+        let (owner, signature) = signed(key);
+        json!({
+            "owner": owner,
+            "submissionDate": "2024-01-01T00:00:00Z",
+            "signature": hex::encode(&signature.0),
+            "signatureType": signature_type,
+        })
+    }
+
+    #[test]
+    fn test_import_safe_confirmations() {
+        let digest = message().digest();
+        let export = json!({
+            "messageHash": hex::encode(&digest.0),
+            "confirmations": [confirmation(1, "EOA"), confirmation(2, "EOA")],
+        });
+        let imported = import_safe_confirmations(&export.to_string(), &digest).unwrap();
+        let signers: Vec<_> = imported.iter().map(|imported| imported.signer).collect();
+        assert_eq!(signers, [signer(1).address(), signer(2).address()]);
+
+        // Packed as `verify_merkle_root` expects them
+        let packed = pack_signatures(
+            &digest,
+            imported.iter().map(|ImportedSignature { signature, .. }| *signature),
+            &signers,
+            2,
+        )
+        .unwrap();
+        assert_eq!(packed.len(), 130);
+
+        let bare = json!([confirmation(1, "EOA")]);
+        assert_eq!(import_safe_confirmations(&bare.to_string(), &digest).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_import_safe_unsupported() {
+        let digest = message().digest();
+        let export =
+            json!({ "confirmations": [confirmation(1, "EOA"), confirmation(2, "ETH_SIGN")] });
+        assert!(matches!(
+            import_safe_confirmations(&export.to_string(), &digest),
+            Err(SdkError::UnsupportedSignatureType(signature_type)) if signature_type == "ETH_SIGN"
+        ));
+
+        let mut other_owner = confirmation(1, "EOA");
+        other_owner["owner"] = json!(signed(2).0);
+        assert!(matches!(
+            import_safe_confirmations(&json!([other_owner]).to_string(), &digest),
+            Err(SdkError::SignerMismatch)
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use k256::ecdsa::{RecoveryId, VerifyingKey};
+use onesig_core::{Address, Hash, Secp256k1Pubkey, Signature, SIGNATURE_BYTES_LEN};
+use solana_keccak_hasher as keccak;
+
+use crate::error::SdkError;
+
+/// Offset of the Ethereum recovery id `v` in an r‖s‖v signature.
+const RECOVERY_ID_OFFSET: usize = SIGNATURE_BYTES_LEN - 1;
+
+/// Recovers the address that signed `digest`, as `onesig_core::signature::recover_address` does
+/// but with `k256` alone, whose dependencies build for wasm32.
+pub fn recover_signer(digest: &Hash, signature: &Signature) -> Option<Address> {
+    let (recovery_id, signature_r_s) = signature.split_recovery_id();
+    let recovery_id = if (27..=30).contains(recovery_id) { recovery_id - 27 } else { *recovery_id };
+    let recovery_id = RecoveryId::from_byte(recovery_id)?;
+    let signature = k256::ecdsa::Signature::from_slice(signature_r_s).ok()?;
+    let key = VerifyingKey::recover_from_prehash(digest.as_ref(), &signature, recovery_id).ok()?;
+    Some(Secp256k1Pubkey::new(&key.to_encoded_point(false).as_bytes()[1..]).into())
+}
+
+/// EIP-55 form of `address`: 0x-prefixed hex whose letters are uppercased where the matching
+/// nibble of the keccak256 of the lowercase hex is 8 or more, as wallets and explorers show it.
+pub fn checksum_address(address: &Address) -> String {
+    let lowercase = hex::encode(address.0);
+    let hash = keccak::hash(lowercase.as_bytes()).to_bytes();
+    let checksummed: String = lowercase
+        .chars()
+        .enumerate()
+        .map(|(index, char)| {
+            let nibble = (hash[index / 2] >> if index % 2 == 0 { 4 } else { 0 }) & 0xf;
+            if nibble >= 8 {
+                char.to_ascii_uppercase()
+            } else {
+                char
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// Rewrites a raw secp256k1 recovery id (0/1) as Ethereum's `v` (27/28), which both the program
+/// and the EVM contract accept.
+pub fn normalize_recovery_id(signature: &Signature) -> Result<Signature, SdkError> {
+    let mut normalized = *signature;
+    normalized.0[RECOVERY_ID_OFFSET] = match signature.0[RECOVERY_ID_OFFSET] {
+        v @ (0 | 1) => v + 27,
+        v @ (27 | 28) => v,
+        v => return Err(SdkError::InvalidRecoveryId(v)),
+    };
+    Ok(normalized)
+}
+
+/// Packs collected signatures of `digest` into the blob `verify_merkle_root` expects: recovery ids
+/// normalized, one signature per signer, sorted by ascending signer address, trimmed to
+/// `threshold`.
+///
+/// Signatures of keys that are not in `signers` are dropped, so signatures collected before a
+/// signer rotation can be passed as is.
+pub fn pack_signatures(
+    digest: &Hash,
+    signatures: impl IntoIterator<Item = Signature>,
+    signers: &[Address],
+    threshold: u8,
+) -> Result<Vec<u8>, SdkError> {
+    let mut by_signer = BTreeMap::new();
+    for signature in signatures {
+        let signature = normalize_recovery_id(&signature)?;
+        let signer = recover_signer(digest, &signature).ok_or(SdkError::FailedRecovery)?;
+        if signers.contains(&signer) {
+            by_signer.entry(signer).or_insert(signature);
+        }
+    }
+    if by_signer.len() < threshold as usize {
+        return Err(SdkError::InsufficientSignatures { found: by_signer.len(), threshold });
+    }
+    Ok(by_signer
+        .into_values()
+        .take(threshold as usize)
+        .flat_map(Signature::to_bytes)
+        .collect())
+}
+
+/// Splits a concatenated signature blob, e.g. one produced by the TypeScript tooling.
+pub fn split_signatures(signatures: &[u8]) -> Result<Vec<Signature>, SdkError> {
+    if signatures.len() % SIGNATURE_BYTES_LEN != 0 {
+        return Err(SdkError::SignatureLength(signatures.len()));
+    }
+    Ok(signatures
+        .chunks(SIGNATURE_BYTES_LEN)
+        .map(|chunk| Signature::try_from(chunk).unwrap())
+        .collect())
+}
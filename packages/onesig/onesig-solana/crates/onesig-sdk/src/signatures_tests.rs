@@ -0,0 +1,164 @@
+// Tests for the packing of collected signatures, against the signatures of the shared test vectors.
+#[cfg(test)]
+mod tests {
+    use onesig_core::{Address, Hash, Secp256k1Pubkey, Signature, SIGNATURE_BYTES_LEN};
+    use onesig_test_vectors::TestVectors;
+
+    use crate::{
+        digest::merkle_root_message,
+        error::SdkError,
+        signatures::{
+            checksum_address, normalize_recovery_id, pack_signatures, recover_signer,
+            split_signatures,
+        },
+        signer::{LocalSigner, RootSigner},
+    };
+
+    fn hash(bytes: &[u8]) -> Hash {
+        Hash::try_from(bytes.to_vec()).unwrap()
+    }
+
+    fn signers(vectors: &TestVectors) -> Vec<Address> {
+        vectors
+            .signers
+            .iter()
+            .map(|signer| Secp256k1Pubkey::new(&signer.public_key).into())
+            .collect()
+    }
+
+    // The vector signatures with raw 0/1 recovery ids, in reverse order
+    fn raw_signatures(vectors: &TestVectors) -> Vec<Signature> {
+        let mut signatures = split_signatures(&vectors.merkle_root_signatures.signatures).unwrap();
+        for signature in &mut signatures {
+            signature.0[SIGNATURE_BYTES_LEN - 1] -= 27;
+        }
+        signatures.reverse();
+        signatures
+    }
+
+    #[test]
+    fn test_recover_signer() {
+        let vectors = onesig_test_vectors::load();
+        let digest = hash(&vectors.merkle_root_signatures.digest);
+        let signatures = split_signatures(&vectors.merkle_root_signatures.signatures).unwrap();
+        let recovered: Vec<Address> = signatures
+            .iter()
+            .map(|signature| recover_signer(&digest, signature).unwrap())
+            .collect();
+        assert_eq!(recovered, signers(&vectors));
+
+        // The raw recovery id recovers too, and an invalid one does not
+        let mut signature = signatures[0];
+        signature.0[SIGNATURE_BYTES_LEN - 1] -= 27;
+        assert_eq!(recover_signer(&digest, &signature), Some(recovered[0]));
+        signature.0[SIGNATURE_BYTES_LEN - 1] = 4;
+        assert_eq!(recover_signer(&digest, &signature), None);
+    }
+
+    #[test]
+    fn test_normalize_recovery_id() {
+        let mut signature = Signature([0x11; SIGNATURE_BYTES_LEN]);
+        for (v, normalized) in [(0, 27), (1, 28), (27, 27), (28, 28)] {
+            signature.0[SIGNATURE_BYTES_LEN - 1] = v;
+            let signature = normalize_recovery_id(&signature).unwrap();
+            assert_eq!(signature.0[SIGNATURE_BYTES_LEN - 1], normalized);
+        }
+
+        signature.0[SIGNATURE_BYTES_LEN - 1] = 2;
+        assert!(matches!(normalize_recovery_id(&signature), Err(SdkError::InvalidRecoveryId(2))));
+    }
+
+    #[test]
+    fn test_pack_signatures() {
+        let vectors = onesig_test_vectors::load();
+        let digest = hash(&vectors.merkle_root_signatures.digest);
+        let signers = signers(&vectors);
+
+        // A duplicate and a signature of a key that is not a signer are dropped
+        let stranger = LocalSigner::from_bytes(&[0x42; 32]).unwrap();
+        let message = merkle_root_message(
+            &hash(&vectors.seed),
+            &hash(&vectors.merkle_root),
+            vectors.expiry,
+            0,
+        );
+        let mut signatures = raw_signatures(&vectors);
+        signatures.push(signatures[0]);
+        signatures.push(stranger.sign(&message).unwrap());
+
+        let packed = pack_signatures(&digest, signatures, &signers, vectors.threshold).unwrap();
+        assert_eq!(packed.len(), vectors.threshold as usize * SIGNATURE_BYTES_LEN);
+        #[cfg(feature = "client")]
+        crate::digest::verify_merkle_root_signatures(
+            &hash(&vectors.seed),
+            &signers,
+            vectors.threshold,
+            &hash(&vectors.merkle_root),
+            vectors.expiry as i64,
+            0,
+            &packed,
+            0,
+        )
+        .unwrap();
+
+        let mut sorted = signers.clone();
+        sorted.sort();
+        let packed_order: Vec<Address> = split_signatures(&packed)
+            .unwrap()
+            .iter()
+            .map(|signature| recover_signer(&digest, signature).unwrap())
+            .collect();
+        assert_eq!(packed_order, sorted);
+    }
+
+    #[test]
+    fn test_pack_signatures_trims_to_threshold() {
+        let vectors = onesig_test_vectors::load();
+        let digest = hash(&vectors.merkle_root_signatures.digest);
+        let signers = signers(&vectors);
+
+        let packed = pack_signatures(&digest, raw_signatures(&vectors), &signers, 1).unwrap();
+        let [signature] = split_signatures(&packed).unwrap()[..] else { panic!("one signature") };
+        let recovered = recover_signer(&digest, &signature).unwrap();
+        assert_eq!(recovered, *signers.iter().min().unwrap());
+    }
+
+    #[test]
+    fn test_pack_signatures_insufficient() {
+        let vectors = onesig_test_vectors::load();
+        let digest = hash(&vectors.merkle_root_signatures.digest);
+        let signers = signers(&vectors);
+        let threshold = vectors.threshold + 1;
+
+        let result = pack_signatures(&digest, raw_signatures(&vectors), &signers, threshold);
+        assert_eq!(
+            result,
+            Err(SdkError::InsufficientSignatures { found: signers.len(), threshold })
+        );
+    }
+
+    #[test]
+    fn test_split_signatures() {
+        let vectors = onesig_test_vectors::load();
+        let signatures = &vectors.merkle_root_signatures.signatures;
+        let split = split_signatures(signatures).unwrap();
+        assert_eq!(split.len(), vectors.signers.len());
+        assert_eq!(split[0].to_bytes()[..], signatures[..SIGNATURE_BYTES_LEN]);
+
+        assert!(matches!(split_signatures(&signatures[1..]), Err(SdkError::SignatureLength(_))));
+    }
+
+    #[test]
+    fn test_checksum_address() {
+        // Vectors of EIP-55
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = Address::try_from(hex::decode(&expected[2..]).unwrap()).unwrap();
+            assert_eq!(checksum_address(&address), expected);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use k256::ecdsa::SigningKey;
+use onesig_core::{
+    Address, Secp256k1Pubkey, Signature, SECP256K1_PUBLIC_KEY_LEN, SIGNATURE_BYTES_LEN,
+};
+
+use crate::{digest::Eip712Message, error::SdkError, signatures::recover_signer};
+
+/// A secp256k1 key approving merkle roots, wherever it is kept.
+pub trait RootSigner {
+    /// Address the signatures recover to, as registered in the OneSig signer set
+    fn address(&self) -> Address;
+
+    /// Signs `message`, returning r‖s‖v with v = 27 + recovery id as Ethereum signers do.
+    fn sign(&self, message: &Eip712Message) -> Result<Signature, SdkError>;
+}
+
+/// Signer holding its private key in memory.
+pub struct LocalSigner {
+    key: SigningKey,
+    address: Address,
+}
+
+impl LocalSigner {
+    pub fn from_bytes(private_key: &[u8]) -> Result<Self, SdkError> {
+        let key = SigningKey::from_slice(private_key).map_err(|_| SdkError::InvalidPrivateKey)?;
+        let point = key.verifying_key().to_encoded_point(false);
+        let address = Secp256k1Pubkey::new(&point.as_bytes()[1..]).into();
+        Ok(Self { key, address })
+    }
+}
+
+impl RootSigner for LocalSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, message: &Eip712Message) -> Result<Signature, SdkError> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(message.digest().as_ref())
+            .map_err(|error| SdkError::Signer(error.to_string()))?;
+        let mut bytes = [0u8; SIGNATURE_BYTES_LEN];
+        bytes[..64].copy_from_slice(&signature.to_bytes());
+        bytes[64] = 27 + recovery_id.to_byte();
+        Ok(Signature(bytes))
+    }
+}
+
+/// `m/44'/60'/0'/0/0`, the first Ethereum account of a Ledger
+pub const LEDGER_DEFAULT_DERIVATION_PATH: [u32; 5] = [44 | HARDENED, 60 | HARDENED, HARDENED, 0, 0];
+
+/// Exchanges APDUs with a Ledger device, e.g. over HID.
+pub trait LedgerTransport {
+    /// Sends `apdu` and returns the response, status word included.
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SdkError>;
+}
+
+/// Signer backed by the Ethereum app of a Ledger, which shows the domain and message hashes of
+/// every root before signing it.
+pub struct LedgerSigner<T> {
+    transport: T,
+    derivation_path: Vec<u32>,
+    address: Address,
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    /// Connects to the key at `derivation_path`, reading its address from the device.
+    pub fn new(transport: T, derivation_path: Vec<u32>) -> Result<Self, SdkError> {
+        let apdu = ledger_apdu(INS_GET_PUBLIC_KEY, &encode_derivation_path(&derivation_path));
+        let response = exchange(&transport, &apdu)?;
+        // Length of the public key, then the uncompressed public key with its prefix byte
+        let public_key = response
+            .get(2..2 + SECP256K1_PUBLIC_KEY_LEN)
+            .filter(|_| response[0] as usize == SECP256K1_PUBLIC_KEY_LEN + 1)
+            .ok_or_else(|| SdkError::Signer("malformed Ledger public key".to_string()))?;
+        let address = Secp256k1Pubkey::new(public_key).into();
+        Ok(Self { transport, derivation_path, address })
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T: LedgerTransport> RootSigner for LedgerSigner<T> {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, message: &Eip712Message) -> Result<Signature, SdkError> {
+        let mut data = encode_derivation_path(&self.derivation_path);
+        data.extend_from_slice(message.domain_separator.as_ref());
+        data.extend_from_slice(message.struct_hash.as_ref());
+        let response = exchange(&self.transport, &ledger_apdu(INS_SIGN_EIP712, &data))?;
+        // The app answers v‖r‖s
+        let (v, r_s) = response
+            .split_first()
+            .filter(|(_, r_s)| r_s.len() == 64)
+            .ok_or_else(|| SdkError::Signer("malformed Ledger signature".to_string()))?;
+        let mut bytes = [0u8; SIGNATURE_BYTES_LEN];
+        bytes[..64].copy_from_slice(r_s);
+        bytes[64] = *v;
+        check_signature(&self.address, message, Signature(bytes))
+    }
+}
+
+/// Signer delegating to a remote service (a KMS, a custody API, another process), called with
+/// each message to sign. Its signatures are checked against its address before being returned.
+pub struct RemoteSigner<F> {
+    address: Address,
+    sign: F,
+}
+
+impl<F> RemoteSigner<F>
+where
+    F: Fn(&Eip712Message) -> Result<Signature, SdkError>,
+{
+    pub fn new(address: Address, sign: F) -> Self {
+        Self { address, sign }
+    }
+}
+
+impl<F> RootSigner for RemoteSigner<F>
+where
+    F: Fn(&Eip712Message) -> Result<Signature, SdkError>,
+{
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, message: &Eip712Message) -> Result<Signature, SdkError> {
+        check_signature(&self.address, message, (self.sign)(message)?)
+    }
+}
+
+const HARDENED: u32 = 0x8000_0000;
+const CLA_ETHEREUM: u8 = 0xe0;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN_EIP712: u8 = 0x0c;
+const STATUS_OK: u16 = 0x9000;
+
+fn encode_derivation_path(derivation_path: &[u32]) -> Vec<u8> {
+    let mut encoded = vec![derivation_path.len() as u8];
+    for index in derivation_path {
+        encoded.extend_from_slice(&index.to_be_bytes());
+    }
+    encoded
+}
+
+fn ledger_apdu(instruction: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = vec![CLA_ETHEREUM, instruction, 0, 0, data.len() as u8];
+    apdu.extend_from_slice(data);
+    apdu
+}
+
+// Sends `apdu`, returning the response without its status word
+fn exchange(transport: &impl LedgerTransport, apdu: &[u8]) -> Result<Vec<u8>, SdkError> {
+    let mut response = transport.exchange(apdu)?;
+    let status = response
+        .len()
+        .checked_sub(2)
+        .map(|len| u16::from_be_bytes([response[len], response[len + 1]]))
+        .ok_or_else(|| SdkError::Signer("empty Ledger response".to_string()))?;
+    if status != STATUS_OK {
+        return Err(SdkError::LedgerStatus(status));
+    }
+    response.truncate(response.len() - 2);
+    Ok(response)
+}
+
+fn check_signature(
+    address: &Address,
+    message: &Eip712Message,
+    signature: Signature,
+) -> Result<Signature, SdkError> {
+    match recover_signer(&message.digest(), &signature) {
+        Some(recovered) if recovered == *address => Ok(signature),
+        _ => Err(SdkError::SignerMismatch),
+    }
+}
@@ -0,0 +1,156 @@
+// Tests for the signer backends, against the keys and signatures of the shared test vectors.
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use onesig_core::{
+        leaf::process_proof, Address, Hash, Signature, DOMAIN_SEPARATOR, SIGNATURE_BYTES_LEN,
+    };
+    use onesig_test_vectors::TestVectors;
+
+    use crate::{
+        digest::{self, Eip712Message},
+        error::SdkError,
+        signer::{
+            LedgerSigner, LedgerTransport, LocalSigner, RemoteSigner, RootSigner,
+            LEDGER_DEFAULT_DERIVATION_PATH,
+        },
+    };
+
+    // The `SignMerkleRoot` message the vectors' signers signed
+    fn merkle_root_message(vectors: &TestVectors) -> Eip712Message {
+        digest::merkle_root_message(
+            &Hash::try_from(vectors.seed.clone()).unwrap(),
+            &Hash::try_from(vectors.merkle_root.clone()).unwrap(),
+            vectors.expiry,
+            0,
+        )
+    }
+
+    fn vector_signature(vectors: &TestVectors, index: usize) -> Signature {
+        let signatures = &vectors.merkle_root_signatures.signatures;
+        Signature::try_from(&signatures[index * SIGNATURE_BYTES_LEN..][..SIGNATURE_BYTES_LEN])
+            .unwrap()
+    }
+
+    // A Ledger answering with a local key, recording the APDUs it receives
+    struct MockLedger {
+        signer: LocalSigner,
+        public_key: Vec<u8>,
+        status: u16,
+        apdus: RefCell<Vec<Vec<u8>>>,
+    }
+
+    impl LedgerTransport for MockLedger {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SdkError> {
+            self.apdus.borrow_mut().push(apdu.to_vec());
+            let mut response = match apdu[1] {
+                0x02 => [&[65, 0x04][..], &self.public_key].concat(),
+                _ => {
+                    let message = Eip712Message {
+                        domain_separator: Hash(apdu[apdu.len() - 64..][..32].try_into().unwrap()),
+                        struct_hash: Hash(apdu[apdu.len() - 32..].try_into().unwrap()),
+                    };
+                    let signature = self.signer.sign(&message)?.0;
+                    [&signature[64..], &signature[..64]].concat()
+                },
+            };
+            response.extend_from_slice(&self.status.to_be_bytes());
+            Ok(response)
+        }
+    }
+
+    fn mock_ledger(vectors: &TestVectors, status: u16) -> MockLedger {
+        MockLedger {
+            signer: LocalSigner::from_bytes(&vectors.signers[0].private_key).unwrap(),
+            public_key: vectors.signers[0].public_key.clone(),
+            status,
+            apdus: RefCell::new(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_message_digest() {
+        let vectors = onesig_test_vectors::load();
+        assert_eq!(
+            merkle_root_message(&vectors).digest().0.to_vec(),
+            vectors.merkle_root_signatures.digest
+        );
+    }
+
+    #[test]
+    fn test_local_signer() {
+        let vectors = onesig_test_vectors::load();
+        let message = merkle_root_message(&vectors);
+        for (index, vector) in vectors.signers.iter().enumerate() {
+            let signer = LocalSigner::from_bytes(&vector.private_key).unwrap();
+            assert_eq!(signer.address().0.to_vec(), vector.address);
+            // RFC 6979 signatures are deterministic
+            assert!(signer.sign(&message).unwrap() == vector_signature(&vectors, index));
+        }
+        assert_eq!(LocalSigner::from_bytes(&[0; 32]).err(), Some(SdkError::InvalidPrivateKey));
+    }
+
+    #[test]
+    fn test_ledger_signer() {
+        let vectors = onesig_test_vectors::load();
+        let message = merkle_root_message(&vectors);
+        let signer = LedgerSigner::new(
+            mock_ledger(&vectors, 0x9000),
+            LEDGER_DEFAULT_DERIVATION_PATH.to_vec(),
+        )
+        .unwrap();
+
+        assert_eq!(signer.address().0.to_vec(), vectors.signers[0].address);
+        assert!(signer.sign(&message).unwrap() == vector_signature(&vectors, 0));
+
+        let apdus = signer.transport().apdus.borrow();
+        let path = [
+            &[5u8][..],
+            &[0x80, 0, 0, 44],
+            &[0x80, 0, 0, 60],
+            &[0x80, 0, 0, 0],
+            &[0, 0, 0, 0],
+            &[0, 0, 0, 0],
+        ]
+        .concat();
+        assert_eq!(apdus[0], [&[0xe0, 0x02, 0, 0, 21][..], &path].concat());
+        assert_eq!(
+            apdus[1],
+            [&[0xe0, 0x0c, 0, 0, 85][..], &path, &DOMAIN_SEPARATOR, message.struct_hash.as_ref()]
+                .concat()
+        );
+    }
+
+    #[test]
+    fn test_ledger_signer_rejected() {
+        let vectors = onesig_test_vectors::load();
+        assert_eq!(
+            LedgerSigner::new(mock_ledger(&vectors, 0x6985), vec![])
+                .err()
+                .map(|e| e.to_string()),
+            Some(SdkError::LedgerStatus(0x6985).to_string())
+        );
+    }
+
+    #[test]
+    fn test_remote_signer_checks_signatures() {
+        let vectors = onesig_test_vectors::load();
+        let message = merkle_root_message(&vectors);
+        let local = LocalSigner::from_bytes(&vectors.signers[0].private_key).unwrap();
+        let remote = RemoteSigner::new(local.address(), |message| local.sign(message));
+        assert!(remote.sign(&message).unwrap() == vector_signature(&vectors, 0));
+
+        let other = RemoteSigner::new(Address([1; 20]), |message| local.sign(message));
+        assert_eq!(other.sign(&message).err(), Some(SdkError::SignerMismatch));
+
+        // Signing another message is caught as well
+        let forged = RemoteSigner::new(local.address(), |message: &Eip712Message| {
+            local.sign(&Eip712Message {
+                struct_hash: process_proof(&message.struct_hash, &[message.struct_hash]),
+                ..*message
+            })
+        });
+        assert_eq!(forged.sign(&message).err(), Some(SdkError::SignerMismatch));
+    }
+}
@@ -0,0 +1,42 @@
+use std::mem::size_of;
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use onesig::{OneSigState, ONE_SIG_STATE_VERSION};
+use onesig_core::{Address, ADDRESS_LEN};
+
+use crate::error::SdkError;
+
+/// A `OneSigState` account: its zero-copy header and the populated part of its signer list
+#[derive(Clone)]
+pub struct OneSigAccount {
+    pub address: Pubkey,
+    pub header: OneSigState,
+    pub signers: Vec<Address>,
+}
+
+impl OneSigAccount {
+    /// Decodes the data of the `OneSigState` account at `address`, which must have the layout
+    /// version of the program the SDK is built against.
+    pub fn from_account_data(address: Pubkey, data: &[u8]) -> Result<Self, SdkError> {
+        let invalid = || SdkError::InvalidOneSigState(address);
+        if data.len() < OneSigState::SIGNERS_OFFSET || !data.starts_with(OneSigState::DISCRIMINATOR)
+        {
+            return Err(invalid());
+        }
+        let header: OneSigState = bytemuck::pod_read_unaligned(
+            &data[OneSigState::HEADER_OFFSET..][..size_of::<OneSigState>()],
+        );
+        if header.version != ONE_SIG_STATE_VERSION ||
+            header.signers_len > header.signers_capacity ||
+            data.len() < OneSigState::space(header.signers_capacity)
+        {
+            return Err(invalid());
+        }
+        let signers = data[OneSigState::SIGNERS_OFFSET..]
+            .chunks_exact(ADDRESS_LEN)
+            .take(header.signers_len as usize)
+            .map(bytemuck::pod_read_unaligned::<Address>)
+            .collect();
+        Ok(Self { address, header, signers })
+    }
+}
@@ -0,0 +1,65 @@
+// Tests for decoding `OneSigState` accounts off-chain.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, Discriminator};
+    use bytemuck::Zeroable;
+    use onesig::{OneSigState, TargetPolicy, ONE_SIG_STATE_VERSION};
+    use onesig_core::{Address, Hash};
+
+    use crate::{error::SdkError, state::OneSigAccount};
+
+    const ADDRESS: Pubkey = Pubkey::new_from_array([0x5a; 32]);
+
+    fn account_data(signers: &[Address], signers_capacity: u16) -> Vec<u8> {
+        let mut header = OneSigState::zeroed();
+        header.one_sig_id = 7;
+        header.nonce = 3;
+        header.seed = Hash([0x11; 32]);
+        header.signers_len = signers.len() as u16;
+        header.signers_capacity = signers_capacity;
+        header.threshold = 2;
+        header.executor_required = 1;
+        header.version = ONE_SIG_STATE_VERSION;
+        header.target_policy = 1;
+        let mut data = OneSigState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&header));
+        for signer in signers {
+            data.extend_from_slice(&signer.0);
+        }
+        data.resize(OneSigState::space(signers_capacity), 0);
+        data
+    }
+
+    #[test]
+    fn test_from_account_data() {
+        let signers = [Address([1; 20]), Address([2; 20])];
+        let account =
+            OneSigAccount::from_account_data(ADDRESS, &account_data(&signers, 4)).unwrap();
+        assert_eq!(account.address, ADDRESS);
+        assert_eq!(account.header.one_sig_id, 7);
+        assert_eq!(account.header.nonce, 3);
+        assert!(account.header.seed == Hash([0x11; 32]));
+        assert_eq!(account.header.threshold, 2);
+        assert!(account.header.executor_required());
+        assert_eq!(account.header.target_policy(), TargetPolicy::Allow);
+        assert!(account.signers == signers);
+    }
+
+    #[test]
+    fn test_from_account_data_rejects_other_accounts() {
+        let error = |data: &[u8]| OneSigAccount::from_account_data(ADDRESS, data).err();
+        let invalid = Some(SdkError::InvalidOneSigState(ADDRESS));
+        let data = account_data(&[Address([1; 20])], 2);
+
+        let mut other_discriminator = data.clone();
+        other_discriminator[0] ^= 1;
+        assert_eq!(error(&other_discriminator), invalid);
+
+        let mut other_version = data.clone();
+        other_version[8 + std::mem::offset_of!(OneSigState, version)] += 1;
+        assert_eq!(error(&other_version), invalid);
+
+        // Shorter than its signer capacity
+        assert_eq!(error(&data[..data.len() - 1]), invalid);
+    }
+}
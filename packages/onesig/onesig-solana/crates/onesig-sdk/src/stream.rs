@@ -0,0 +1,103 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use onesig::{
+    ExecutorState, MerkleRootRegistry, MerkleRootState, ModuleState, OneSigState, SpendingLimits,
+    TargetPrograms,
+};
+
+use crate::{
+    error::SdkError,
+    events::{interleave_logged_events, is_event_cpi, OneSigEvent},
+    pda::EVENT_AUTHORITY_SEED,
+    state::OneSigAccount,
+};
+
+/// An account of the OneSig program as a Geyser plugin or a Yellowstone gRPC subscription streams
+/// it: every write to it, decoded.
+pub enum AccountUpdate {
+    OneSig(OneSigAccount),
+    MerkleRoot(MerkleRootState),
+    MerkleRootRegistry(MerkleRootRegistry),
+    Executor(ExecutorState),
+    Module(ModuleState),
+    SpendingLimits(SpendingLimits),
+    TargetPrograms(TargetPrograms),
+    /// The account was closed, its lamports drained
+    Closed,
+}
+
+impl AccountUpdate {
+    /// Decodes a write to `address`, `None` if the account is not one of the program at
+    /// `program_id`. An account left without lamports is [`Closed`](Self::Closed) whatever its
+    /// owner, as closing hands it back to the system program; streams filtered to the program's
+    /// accounts only carry the ones it closed.
+    pub fn from_account(
+        program_id: &Pubkey,
+        address: Pubkey,
+        owner: &Pubkey,
+        lamports: u64,
+        data: &[u8],
+    ) -> Option<Result<Self, SdkError>> {
+        if lamports == 0 {
+            return Some(Ok(Self::Closed));
+        }
+        if owner != program_id {
+            return None;
+        }
+        if data.starts_with(OneSigState::DISCRIMINATOR) {
+            return Some(OneSigAccount::from_account_data(address, data).map(Self::OneSig));
+        }
+        let discriminator = data.get(..8)?;
+        Some(if discriminator == MerkleRootState::DISCRIMINATOR {
+            anchor(address, data, "MerkleRootState").map(Self::MerkleRoot)
+        } else if discriminator == MerkleRootRegistry::DISCRIMINATOR {
+            anchor(address, data, "MerkleRootRegistry").map(Self::MerkleRootRegistry)
+        } else if discriminator == ExecutorState::DISCRIMINATOR {
+            anchor(address, data, "ExecutorState").map(Self::Executor)
+        } else if discriminator == ModuleState::DISCRIMINATOR {
+            anchor(address, data, "ModuleState").map(Self::Module)
+        } else if discriminator == SpendingLimits::DISCRIMINATOR {
+            anchor(address, data, "SpendingLimits").map(Self::SpendingLimits)
+        } else if discriminator == TargetPrograms::DISCRIMINATOR {
+            anchor(address, data, "TargetPrograms").map(Self::TargetPrograms)
+        } else {
+            return None;
+        })
+    }
+}
+
+fn anchor<T: AccountDeserialize>(
+    address: Pubkey,
+    mut data: &[u8],
+    name: &'static str,
+) -> Result<T, SdkError> {
+    T::try_deserialize(&mut data).map_err(|_| SdkError::InvalidAccount(address, name))
+}
+
+/// An inner instruction of a transaction as Geyser and Yellowstone stream it, compiled against
+/// the account keys of the transaction, the addresses it loads from lookup tables included
+pub struct InnerInstruction<'a> {
+    pub program_id_index: u8,
+    pub accounts: &'a [u8],
+    pub data: &'a [u8],
+}
+
+/// Decodes the events the OneSig program at `program_id` emitted in a streamed transaction, from
+/// its account keys, inner instructions and log messages, in the order they were emitted.
+pub fn events_from_inner_instructions<'a>(
+    program_id: &Pubkey,
+    account_keys: &[Pubkey],
+    inner_instructions: impl IntoIterator<Item = InnerInstruction<'a>>,
+    log_messages: &[String],
+) -> Result<Vec<OneSigEvent>, SdkError> {
+    let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0;
+    let key = |index: u8| account_keys.get(usize::from(index)).copied();
+    let event_cpis = inner_instructions.into_iter().map(|instruction| {
+        let program = key(instruction.program_id_index);
+        let first_account = instruction.accounts.first().copied().and_then(key);
+        if !is_event_cpi(program_id, &event_authority, program, first_account) {
+            return Ok(None);
+        }
+        OneSigEvent::from_event_cpi(instruction.data).transpose()
+    });
+    interleave_logged_events(program_id, log_messages, event_cpis)
+}
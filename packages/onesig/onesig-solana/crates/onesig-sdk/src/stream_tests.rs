@@ -0,0 +1,99 @@
+// Tests for decoding the raw accounts and transactions Geyser streams.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        event::EVENT_IX_TAG_LE, prelude::Pubkey, AccountSerialize, Discriminator, Event,
+    };
+    use onesig::{
+        ConfigSet, ExecutorState, MerkleRootState, PreviousConfig, SetConfigParams, EVENT_VERSION,
+        MERKLE_ROOT_STATE_VERSION,
+    };
+    use onesig_core::Hash;
+
+    use crate::{
+        error::SdkError,
+        events::OneSigEvent,
+        pda::EVENT_AUTHORITY_SEED,
+        stream::{events_from_inner_instructions, AccountUpdate, InnerInstruction},
+    };
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0e; 32]);
+
+    fn root_data() -> Vec<u8> {
+        let root = MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            seed_commitment: [2; 16],
+            expiry: 100,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions: Some(3),
+        };
+        let mut data = vec![];
+        root.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_account_update() {
+        let address = Pubkey::new_unique();
+        let update = |owner: &Pubkey, lamports: u64, data: &[u8]| {
+            AccountUpdate::from_account(&PROGRAM_ID, address, owner, lamports, data)
+        };
+
+        let Some(Ok(AccountUpdate::MerkleRoot(root))) = update(&PROGRAM_ID, 1, &root_data()) else {
+            panic!("expected a MerkleRootState");
+        };
+        assert_eq!(root.remaining_executions, Some(3));
+        assert!(matches!(update(&Pubkey::default(), 0, &[]), Some(Ok(AccountUpdate::Closed))));
+        // Accounts of other programs, and data of no account type of the program
+        assert!(update(&Pubkey::new_unique(), 1, &root_data()).is_none());
+        assert!(update(&PROGRAM_ID, 1, &[0; 8]).is_none());
+        assert!(update(&PROGRAM_ID, 1, &[]).is_none());
+        assert!(matches!(
+            update(&PROGRAM_ID, 1, ExecutorState::DISCRIMINATOR),
+            Some(Err(SdkError::InvalidAccount(_, "ExecutorState")))
+        ));
+    }
+
+    #[test]
+    fn test_events_from_inner_instructions() {
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID).0;
+        let keys = [Pubkey::new_unique(), PROGRAM_ID, Pubkey::new_unique(), event_authority];
+        let config_set = ConfigSet {
+            one_sig_account: Pubkey::new_unique(),
+            params: SetConfigParams::SetThreshold(2),
+            previous: PreviousConfig::Threshold(1),
+            event_version: EVENT_VERSION,
+        };
+        let data = [EVENT_IX_TAG_LE, &config_set.data()].concat();
+        let instructions = [
+            // Into another program, and into the program without its event authority
+            InnerInstruction { program_id_index: 2, accounts: &[3], data: &data },
+            InnerInstruction { program_id_index: 1, accounts: &[0], data: &data },
+            InnerInstruction { program_id_index: 1, accounts: &[3], data: &data },
+            // Not an event
+            InnerInstruction { program_id_index: 1, accounts: &[3], data: &[1, 2, 3] },
+            InnerInstruction { program_id_index: 9, accounts: &[], data: &[] },
+        ];
+
+        let events = events_from_inner_instructions(&PROGRAM_ID, &keys, instructions, &[]).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            OneSigEvent::ConfigSet(ConfigSet { params: SetConfigParams::SetThreshold(2), .. })
+        ));
+
+        // The variant of `params`, after the tags and the account, is not one
+        let mut invalid = data.clone();
+        invalid[8 + 8 + 32] = 0xff;
+        let instructions =
+            [InnerInstruction { program_id_index: 1, accounts: &[3], data: &invalid }];
+        assert!(matches!(
+            events_from_inner_instructions(&PROGRAM_ID, &keys, instructions, &[]),
+            Err(SdkError::InvalidEvent("ConfigSet"))
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    future::Future,
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use crate::{
+    error::SdkError,
+    rpc::{
+        AsyncRpcClient, AsyncRpcTransport, Commitment, RpcClient, RpcTransport, SignatureStatus,
+    },
+    transaction::transaction_signature,
+};
+
+// JSON-RPC error codes of Solana nodes
+const INVALID_REQUEST: i64 = -32600;
+const INVALID_PARAMS: i64 = -32602;
+const SEND_TRANSACTION_PREFLIGHT_FAILURE: i64 = -32002;
+const TRANSACTION_SIGNATURE_VERIFICATION_FAILURE: i64 = -32003;
+
+/// Whether trying again, maybe with another endpoint, may get past a failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The endpoint failed, is behind or unhealthy
+    Retryable,
+    /// The transaction itself is rejected, or failed on-chain
+    Terminal,
+}
+
+/// Classifies a failure to submit or track a transaction.
+///
+/// Preflights failing for an unknown blockhash are retryable, as the node may lag behind the one
+/// the blockhash came from, and so are the ones reporting the transaction already processed,
+/// which confirmation tracking then finds.
+pub fn classify(error: &SdkError) -> Failure {
+    match error {
+        SdkError::Transport(_) | SdkError::InvalidRpcResponse(_) => Failure::Retryable,
+        SdkError::Rpc { code: SEND_TRANSACTION_PREFLIGHT_FAILURE, data, .. } => {
+            let err = data.as_ref().and_then(|data| data.get("err"));
+            match err.and_then(Value::as_str) {
+                Some("BlockhashNotFound" | "AlreadyProcessed") => Failure::Retryable,
+                _ => Failure::Terminal,
+            }
+        },
+        SdkError::Rpc {
+            code: INVALID_REQUEST | INVALID_PARAMS | TRANSACTION_SIGNATURE_VERIFICATION_FAILURE,
+            ..
+        } => Failure::Terminal,
+        SdkError::Rpc { .. } => Failure::Retryable,
+        _ => Failure::Terminal,
+    }
+}
+
+/// How a [`Submitter`] broadcasts and tracks a transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmitConfig {
+    /// Commitment the transaction must reach
+    pub commitment: Commitment,
+    /// Time after which the submission gives up, from its start
+    pub timeout: Duration,
+    /// Time after which the transaction is broadcast again to every endpoint, until it lands
+    pub rebroadcast_interval: Duration,
+    /// Time between two polls of its status
+    pub poll_interval: Duration,
+}
+
+impl Default for SubmitConfig {
+    fn default() -> Self {
+        Self {
+            commitment: Commitment::Confirmed,
+            timeout: Duration::from_secs(90),
+            rebroadcast_interval: Duration::from_secs(2),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// A transaction that reached the commitment of its submission
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Confirmed {
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Where a submission stands after a poll
+fn settle(
+    signature: &str,
+    status: Option<SignatureStatus>,
+    commitment: Commitment,
+) -> Option<Result<Confirmed, SdkError>> {
+    let status = status?;
+    if let Some(err) = status.err {
+        return Some(Err(SdkError::TransactionFailed { signature: signature.into(), err }));
+    }
+    status
+        .commitment()
+        .is_some_and(|reached| reached >= commitment)
+        .then(|| Ok(Confirmed { signature: signature.into(), slot: status.slot }))
+}
+
+/// Submits transactions through several RPC endpoints, the first one preferred.
+///
+/// The transaction is preflighted by the first endpoint that answers, then broadcast to every
+/// other one without preflight, and again every [`SubmitConfig::rebroadcast_interval`] until an
+/// endpoint reports it at the commitment of the submission. Terminal failures end the submission
+/// at once; retryable ones fail over to the next endpoint.
+pub struct Submitter<T> {
+    endpoints: Vec<RpcClient<T>>,
+    config: SubmitConfig,
+}
+
+/// [`Submitter`] over async clients, sleeping between polls with `sleep`, e.g.
+/// `tokio::time::sleep`
+pub struct AsyncSubmitter<T, S> {
+    endpoints: Vec<AsyncRpcClient<T>>,
+    config: SubmitConfig,
+    sleep: S,
+}
+
+impl<T: RpcTransport> Submitter<T> {
+    pub fn new(endpoints: Vec<RpcClient<T>>, config: SubmitConfig) -> Self {
+        Self { endpoints, config }
+    }
+
+    pub fn endpoints(&self) -> &[RpcClient<T>] {
+        &self.endpoints
+    }
+
+    /// Submits a signed wire transaction, returning once it reached the commitment.
+    pub fn submit(&self, transaction: &[u8]) -> Result<Confirmed, SdkError> {
+        let mut preflighted = false;
+        self.submit_with(transaction, |transaction| {
+            preflighted |= self.broadcast(transaction, preflighted)?;
+            Ok(())
+        })
+    }
+
+    /// Submits a signed wire transaction with `send` rather than through the endpoints, e.g. in
+    /// a bundle of a [`JitoClient`], and tracks it through the endpoints. `send` runs again every
+    /// rebroadcast interval until the transaction lands; its retryable failures are left for the
+    /// next time.
+    ///
+    /// [`JitoClient`]: crate::jito::JitoClient
+    pub fn submit_via(
+        &self,
+        transaction: &[u8],
+        send: impl Fn(&[u8]) -> Result<(), SdkError>,
+    ) -> Result<Confirmed, SdkError> {
+        self.submit_with(transaction, |transaction| match send(transaction) {
+            Err(error) if classify(&error) == Failure::Terminal => Err(error),
+            _ => Ok(()),
+        })
+    }
+
+    fn submit_with(
+        &self,
+        transaction: &[u8],
+        mut broadcast: impl FnMut(&[u8]) -> Result<(), SdkError>,
+    ) -> Result<Confirmed, SdkError> {
+        let signature = transaction_signature(transaction)?;
+        let started = Instant::now();
+        let mut broadcast_at: Option<Instant> = None;
+        loop {
+            if broadcast_at.is_none_or(|at| at.elapsed() >= self.config.rebroadcast_interval) {
+                broadcast(transaction)?;
+                broadcast_at = Some(Instant::now());
+            }
+            let status = self
+                .endpoints
+                .iter()
+                .find_map(|endpoint| endpoint.signature_status(&signature).ok().flatten());
+            if let Some(settled) = settle(&signature, status, self.config.commitment) {
+                return settled;
+            }
+            if started.elapsed() >= self.config.timeout {
+                return Err(SdkError::SubmissionTimeout { signature });
+            }
+            thread::sleep(self.config.poll_interval);
+        }
+    }
+
+    /// Sends `transaction` to every endpoint, preflighting it until one accepts it unless
+    /// `preflighted`, and returns whether one did.
+    fn broadcast(&self, transaction: &[u8], preflighted: bool) -> Result<bool, SdkError> {
+        let mut accepted = false;
+        for endpoint in &self.endpoints {
+            let sent = if preflighted || accepted {
+                endpoint.rebroadcast_transaction(transaction)
+            } else {
+                endpoint.send_transaction(transaction)
+            };
+            match sent {
+                Ok(_) => accepted = true,
+                Err(error) if classify(&error) == Failure::Terminal => return Err(error),
+                Err(_) => {},
+            }
+        }
+        Ok(accepted)
+    }
+}
+
+impl<T, S, F> AsyncSubmitter<T, S>
+where
+    T: AsyncRpcTransport,
+    S: Fn(Duration) -> F,
+    F: Future<Output = ()>,
+{
+    pub fn new(endpoints: Vec<AsyncRpcClient<T>>, config: SubmitConfig, sleep: S) -> Self {
+        Self { endpoints, config, sleep }
+    }
+
+    pub fn endpoints(&self) -> &[AsyncRpcClient<T>] {
+        &self.endpoints
+    }
+
+    /// Submits a signed wire transaction, returning once it reached the commitment.
+    pub async fn submit(&self, transaction: &[u8]) -> Result<Confirmed, SdkError> {
+        let signature = transaction_signature(transaction)?;
+        let started = Instant::now();
+        let mut preflighted = false;
+        let mut broadcast_at: Option<Instant> = None;
+        loop {
+            if broadcast_at.is_none_or(|at| at.elapsed() >= self.config.rebroadcast_interval) {
+                preflighted |= self.broadcast(transaction, preflighted).await?;
+                broadcast_at = Some(Instant::now());
+            }
+            let mut status = None;
+            for endpoint in &self.endpoints {
+                status = endpoint.signature_status(&signature).await.ok().flatten();
+                if status.is_some() {
+                    break;
+                }
+            }
+            if let Some(settled) = settle(&signature, status, self.config.commitment) {
+                return settled;
+            }
+            if started.elapsed() >= self.config.timeout {
+                return Err(SdkError::SubmissionTimeout { signature });
+            }
+            (self.sleep)(self.config.poll_interval).await;
+        }
+    }
+
+    async fn broadcast(&self, transaction: &[u8], preflighted: bool) -> Result<bool, SdkError> {
+        let mut accepted = false;
+        for endpoint in &self.endpoints {
+            let sent = if preflighted || accepted {
+                endpoint.rebroadcast_transaction(transaction).await
+            } else {
+                endpoint.send_transaction(transaction).await
+            };
+            match sent {
+                Ok(_) => accepted = true,
+                Err(error) if classify(&error) == Failure::Terminal => return Err(error),
+                Err(_) => {},
+            }
+        }
+        Ok(accepted)
+    }
+}
@@ -0,0 +1,292 @@
+// Tests for submitting transactions through several endpoints and tracking their confirmation.
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        future::{ready, Future},
+        pin::pin,
+        sync::Mutex,
+        task::{Context, Poll, Waker},
+        time::Duration,
+    };
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        error::SdkError,
+        rpc::{AsyncRpcClient, AsyncRpcTransport, Commitment, RpcClient, RpcTransport},
+        submission::{classify, AsyncSubmitter, Confirmed, Failure, SubmitConfig, Submitter},
+        transaction::{transaction_signature, TRANSACTION_SIGNATURE_LEN},
+    };
+
+    /// Replays `responses` in order, recording the requests
+    struct MockTransport {
+        requests: RefCell<Vec<Value>>,
+        responses: RefCell<Vec<Value>>,
+    }
+
+    impl MockTransport {
+        fn new(responses: impl IntoIterator<Item = Value>) -> Self {
+            let mut responses: Vec<Value> = responses.into_iter().collect();
+            responses.reverse();
+            Self { requests: RefCell::default(), responses: RefCell::new(responses) }
+        }
+    }
+
+    impl RpcTransport for MockTransport {
+        fn post(&self, body: &str) -> Result<String, SdkError> {
+            self.requests.borrow_mut().push(serde_json::from_str(body).unwrap());
+            let response = self.responses.borrow_mut().pop();
+            response
+                .map(|response| response.to_string())
+                .ok_or(SdkError::Transport("closed".into()))
+        }
+    }
+
+    struct AsyncMockTransport(Mutex<MockTransport>);
+
+    impl AsyncRpcTransport for AsyncMockTransport {
+        fn post(&self, body: String) -> impl Future<Output = Result<String, SdkError>> + Send {
+            ready(self.0.lock().unwrap().post(&body))
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the mock transport does not suspend"),
+        }
+    }
+
+    fn result(result: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "result": result })
+    }
+
+    fn error(code: i64, data: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": code, "message": "", "data": data } })
+    }
+
+    fn status(status: Value) -> Value {
+        result(json!({ "context": { "slot": 1 }, "value": [status] }))
+    }
+
+    fn confirmed(slot: u64) -> Value {
+        status(json!({ "slot": slot, "err": null, "confirmationStatus": "confirmed" }))
+    }
+
+    const TRANSACTION: [u8; 1 + TRANSACTION_SIGNATURE_LEN + 3] = {
+        let mut transaction = [0x5a; 1 + TRANSACTION_SIGNATURE_LEN + 3];
+        transaction[0] = 1;
+        transaction
+    };
+
+    fn signature() -> String {
+        bs58::encode([0x5a; TRANSACTION_SIGNATURE_LEN]).into_string()
+    }
+
+    fn endpoint(responses: impl IntoIterator<Item = Value>) -> RpcClient<MockTransport> {
+        RpcClient::new(MockTransport::new(responses), Commitment::default())
+    }
+
+    fn requests(endpoint: &RpcClient<MockTransport>) -> Vec<Value> {
+        endpoint.transport().requests.borrow().clone()
+    }
+
+    fn config(timeout: Duration) -> SubmitConfig {
+        SubmitConfig {
+            commitment: Commitment::Confirmed,
+            timeout,
+            rebroadcast_interval: Duration::ZERO,
+            poll_interval: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_transaction_signature() {
+        assert_eq!(transaction_signature(&TRANSACTION), Ok(signature()));
+        assert_eq!(transaction_signature(&[0]), Err(SdkError::InvalidTransaction));
+        assert_eq!(transaction_signature(&TRANSACTION[..64]), Err(SdkError::InvalidTransaction));
+        assert_eq!(transaction_signature(&[]), Err(SdkError::InvalidTransaction));
+    }
+
+    #[test]
+    fn test_classify() {
+        let rpc = |code, data| SdkError::Rpc { code, message: String::new(), data };
+        let preflight = |err: Value| rpc(-32002, Some(json!({ "err": err, "logs": [] })));
+
+        for retryable in [
+            SdkError::Transport("reset".into()),
+            SdkError::InvalidRpcResponse("html".into()),
+            rpc(-32005, None),
+            rpc(-32603, None),
+            preflight(json!("BlockhashNotFound")),
+            preflight(json!("AlreadyProcessed")),
+        ] {
+            assert_eq!(classify(&retryable), Failure::Retryable, "{retryable}");
+        }
+        for terminal in [
+            preflight(json!({ "InstructionError": [0, { "Custom": 6009 }] })),
+            rpc(-32002, None),
+            rpc(-32003, None),
+            rpc(-32602, None),
+            SdkError::TransactionFailed { signature: signature(), err: json!("AccountInUse") },
+            SdkError::InvalidTransaction,
+        ] {
+            assert_eq!(classify(&terminal), Failure::Terminal, "{terminal}");
+        }
+    }
+
+    #[test]
+    fn test_failover() {
+        // The first endpoint is down; the second preflights, then confirms
+        let down = endpoint([]);
+        let up = endpoint([result(json!(signature())), confirmed(42)]);
+        let submitter = Submitter::new(vec![down, up], config(Duration::from_secs(5)));
+
+        assert_eq!(
+            submitter.submit(&TRANSACTION),
+            Ok(Confirmed { signature: signature(), slot: 42 })
+        );
+        let [down, up] = submitter.endpoints() else { unreachable!() };
+        assert_eq!(requests(down).len(), 2);
+        assert_eq!(requests(up)[0]["params"][1]["preflightCommitment"], "confirmed");
+        assert_eq!(requests(up)[1]["method"], "getSignatureStatuses");
+    }
+
+    #[test]
+    fn test_rebroadcast() {
+        // The primary does not know the transaction yet, the secondary has it processed
+        let processed =
+            status(json!({ "slot": 7, "err": null, "confirmationStatus": "processed" }));
+        let primary = endpoint([
+            result(json!(signature())),
+            status(Value::Null),
+            result(json!(signature())),
+            confirmed(8),
+        ]);
+        let secondary =
+            endpoint([result(json!(signature())), processed, result(json!(signature()))]);
+        let submitter = Submitter::new(vec![primary, secondary], config(Duration::from_secs(5)));
+
+        assert_eq!(
+            submitter.submit(&TRANSACTION),
+            Ok(Confirmed { signature: signature(), slot: 8 })
+        );
+        let [primary, secondary] = submitter.endpoints() else { unreachable!() };
+        // Preflighted once, by the first endpoint to accept it
+        let skip_preflight =
+            json!({ "encoding": "base64", "skipPreflight": true, "maxRetries": 0 });
+        assert_eq!(requests(primary)[0]["params"][1]["preflightCommitment"], "confirmed");
+        assert_eq!(requests(primary)[2]["params"][1], skip_preflight);
+        assert_eq!(requests(secondary)[0]["params"][1], skip_preflight);
+        assert_eq!(requests(secondary)[2]["params"][1], skip_preflight);
+    }
+
+    #[test]
+    fn test_submit_via() {
+        // Sent again while it has not landed; retryable failures of the sender wait for the next
+        // time, and the endpoints only track it
+        let tracker = endpoint([status(Value::Null), status(Value::Null), confirmed(9)]);
+        let submitter = Submitter::new(vec![tracker], config(Duration::from_secs(5)));
+        let sent = RefCell::new(0);
+        let send = |transaction: &[u8]| {
+            assert_eq!(transaction, TRANSACTION);
+            *sent.borrow_mut() += 1;
+            match *sent.borrow() {
+                1 => Err(SdkError::Transport("rate limited".into())),
+                _ => Ok(()),
+            }
+        };
+
+        assert_eq!(
+            submitter.submit_via(&TRANSACTION, send),
+            Ok(Confirmed { signature: signature(), slot: 9 })
+        );
+        assert_eq!(*sent.borrow(), 3);
+        let methods: Vec<Value> = requests(&submitter.endpoints()[0])
+            .iter()
+            .map(|request| request["method"].clone())
+            .collect();
+        assert_eq!(methods, ["getSignatureStatuses"; 3]);
+
+        let submitter = Submitter::new(vec![endpoint([])], config(Duration::from_secs(5)));
+        let rejected = |_: &[u8]| Err(SdkError::InvalidTransaction);
+        assert_eq!(submitter.submit_via(&TRANSACTION, rejected), Err(SdkError::InvalidTransaction));
+    }
+
+    #[test]
+    fn test_terminal_preflight() {
+        let rejected = error(
+            -32002,
+            json!({ "err": { "InstructionError": [0, { "Custom": 6010 }] }, "logs": [] }),
+        );
+        let submitter = Submitter::new(
+            vec![endpoint([rejected]), endpoint([])],
+            config(Duration::from_secs(5)),
+        );
+
+        assert!(matches!(submitter.submit(&TRANSACTION), Err(SdkError::Rpc { code: -32002, .. })));
+        assert!(requests(&submitter.endpoints()[1]).is_empty());
+    }
+
+    #[test]
+    fn test_failed_on_chain() {
+        let err = json!({ "InstructionError": [1, { "Custom": 6022 }] });
+        let failed = status(json!({ "slot": 9, "err": err, "confirmationStatus": "confirmed" }));
+        let submitter = Submitter::new(
+            vec![endpoint([result(json!(signature())), failed])],
+            config(Duration::from_secs(5)),
+        );
+
+        assert_eq!(
+            submitter.submit(&TRANSACTION),
+            Err(SdkError::TransactionFailed { signature: signature(), err })
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let submitter = Submitter::new(
+            vec![endpoint([result(json!(signature())), status(Value::Null)])],
+            config(Duration::ZERO),
+        );
+        assert_eq!(
+            submitter.submit(&TRANSACTION),
+            Err(SdkError::SubmissionTimeout { signature: signature() })
+        );
+    }
+
+    #[test]
+    fn test_async_submitter() {
+        let processed =
+            status(json!({ "slot": 7, "err": null, "confirmationStatus": "processed" }));
+        let transport = MockTransport::new([
+            result(json!(signature())),
+            processed,
+            result(json!(signature())),
+            status(json!({ "slot": 8, "err": null, "confirmationStatus": "finalized" })),
+        ]);
+        let slept = Mutex::new(vec![]);
+        let submitter = AsyncSubmitter::new(
+            vec![AsyncRpcClient::new(
+                AsyncMockTransport(Mutex::new(transport)),
+                Commitment::default(),
+            )],
+            SubmitConfig {
+                poll_interval: Duration::from_millis(3),
+                ..config(Duration::from_secs(5))
+            },
+            |duration| {
+                slept.lock().unwrap().push(duration);
+                ready(())
+            },
+        );
+
+        assert_eq!(
+            block_on(submitter.submit(&TRANSACTION)),
+            Ok(Confirmed { signature: signature(), slot: 8 })
+        );
+        assert_eq!(*slept.lock().unwrap(), [Duration::from_millis(3)]);
+    }
+}
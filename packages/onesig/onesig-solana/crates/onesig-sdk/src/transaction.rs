@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+
+use crate::{error::SdkError, lookup_table::LookupTable, nonce::NonceAccount};
+
+/// Length of an ed25519 signature
+pub const TRANSACTION_SIGNATURE_LEN: usize = 64;
+
+/// Most bytes a wire transaction, signatures included, can take, those of a network packet.
+///
+/// It bounds what a single `execute_transaction` carries. With a pre-verified root, one signer
+/// and vault 0, a leaf calling a program over `a` accounts of its own with a proof of depth `p`
+/// has room for `787 - 33a - 32p` bytes of instruction data: 467 bytes for a tree of 1024
+/// leaves, and at most 23 accounts or a depth of 24. Verifying the root inline costs another
+/// `48 + 65t` bytes for `t` signatures, less the 32 of the `MerkleRootState` address, so it
+/// takes at most 11 signatures; `verify_merkle_root` alone takes up to `MAX_THRESHOLD`, 13.
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// First byte of versioned messages, version 0; legacy messages start with their header, below
+/// 0x80.
+pub const MESSAGE_VERSION_PREFIX: u8 = 0x80;
+
+/// Signs transactions with a Solana key, wherever it is kept: a keypair, a hardware wallet or a
+/// remote signer.
+pub trait TransactionSigner {
+    fn pubkey(&self) -> Pubkey;
+
+    /// Signs the serialized message of a transaction.
+    fn sign_message(&self, message: &[u8]) -> Result<[u8; TRANSACTION_SIGNATURE_LEN], SdkError>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+/// An instruction with its accounts as indexes into the keys of its message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+/// A legacy transaction message, compiled as `solana_message::legacy::Message::new` compiles it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub header: MessageHeader,
+    /// Signers then non-signers, writable ones first in each, the payer first of all
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: [u8; 32],
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+/// A v0 transaction message, loading accounts from address lookup tables, compiled as
+/// `solana_message::v0::Message::try_compile` compiles it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageV0 {
+    pub header: MessageHeader,
+    /// Keys not loaded from a table, ordered as in a legacy message
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: [u8; 32],
+    /// Instructions indexing `account_keys`, then the writable accounts loaded from every table,
+    /// then the readonly ones
+    pub instructions: Vec<CompiledInstruction>,
+    pub address_table_lookups: Vec<MessageAddressTableLookup>,
+}
+
+/// Accounts a v0 message loads from a lookup table, by their index in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageAddressTableLookup {
+    pub account_key: Pubkey,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct KeyMeta {
+    is_signer: bool,
+    is_writable: bool,
+    is_invoked: bool,
+}
+
+/// Accounts of `instructions` paid by `payer`, ordered by key
+fn key_metas(payer: &Pubkey, instructions: &[Instruction]) -> BTreeMap<Pubkey, KeyMeta> {
+    let mut keys: BTreeMap<Pubkey, KeyMeta> = BTreeMap::new();
+    for instruction in instructions {
+        keys.entry(instruction.program_id).or_default().is_invoked = true;
+        for account in &instruction.accounts {
+            let meta = keys.entry(account.pubkey).or_default();
+            meta.is_signer |= account.is_signer;
+            meta.is_writable |= account.is_writable;
+        }
+    }
+    let meta = keys.entry(*payer).or_default();
+    meta.is_signer = true;
+    meta.is_writable = true;
+    keys
+}
+
+/// Orders `keys`: the payer, writable signers, readonly signers, writable then readonly others.
+fn static_keys(payer: &Pubkey, keys: &BTreeMap<Pubkey, KeyMeta>) -> (MessageHeader, Vec<Pubkey>) {
+    let group = |is_signer: bool, is_writable: bool| {
+        keys.iter()
+            .filter(move |(key, meta)| {
+                *key != payer && meta.is_signer == is_signer && meta.is_writable == is_writable
+            })
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>()
+    };
+    let (readonly_signers, writable, readonly) =
+        (group(true, false), group(false, true), group(false, false));
+    let mut account_keys = vec![*payer];
+    account_keys.extend(group(true, true));
+    let num_required_signatures = account_keys.len() + readonly_signers.len();
+    account_keys.extend(&readonly_signers);
+    account_keys.extend(writable);
+    account_keys.extend(&readonly);
+    let header = MessageHeader {
+        num_required_signatures: num_required_signatures as u8,
+        num_readonly_signed_accounts: readonly_signers.len() as u8,
+        num_readonly_unsigned_accounts: readonly.len() as u8,
+    };
+    (header, account_keys)
+}
+
+/// Compiles `instructions` against `keys`, the keys of the message in index order.
+fn compile_instructions(
+    instructions: &[Instruction],
+    keys: &[Pubkey],
+) -> Result<Vec<CompiledInstruction>, SdkError> {
+    if keys.len() > u8::MAX as usize + 1 {
+        return Err(SdkError::TooManyAccounts(keys.len()));
+    }
+    let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap() as u8;
+    Ok(instructions
+        .iter()
+        .map(|instruction| CompiledInstruction {
+            program_id_index: index(&instruction.program_id),
+            accounts: instruction.accounts.iter().map(|account| index(&account.pubkey)).collect(),
+            data: instruction.data.clone(),
+        })
+        .collect())
+}
+
+fn serialize_header(bytes: &mut Vec<u8>, header: &MessageHeader, account_keys: &[Pubkey]) {
+    bytes.extend([
+        header.num_required_signatures,
+        header.num_readonly_signed_accounts,
+        header.num_readonly_unsigned_accounts,
+    ]);
+    write_compact_u16(bytes, account_keys.len());
+    for key in account_keys {
+        bytes.extend_from_slice(key.as_ref());
+    }
+}
+
+fn serialize_instructions(bytes: &mut Vec<u8>, instructions: &[CompiledInstruction]) {
+    write_compact_u16(bytes, instructions.len());
+    for instruction in instructions {
+        bytes.push(instruction.program_id_index);
+        write_compact_u16(bytes, instruction.accounts.len());
+        bytes.extend_from_slice(&instruction.accounts);
+        write_compact_u16(bytes, instruction.data.len());
+        bytes.extend_from_slice(&instruction.data);
+    }
+}
+
+/// Length of the wire transaction of a `message_len` bytes message with `signatures` signatures
+fn transaction_size(message_len: usize, signatures: usize) -> usize {
+    let mut prefix = vec![];
+    write_compact_u16(&mut prefix, signatures);
+    prefix.len() + signatures * TRANSACTION_SIGNATURE_LEN + message_len
+}
+
+/// The wire transaction of `message`, signed by `signers`, in any order, for each of `keys`.
+/// Transactions over [`PACKET_DATA_SIZE`] are rejected before anything is signed.
+fn sign(
+    message: Vec<u8>,
+    keys: &[Pubkey],
+    signers: &[&dyn TransactionSigner],
+) -> Result<Vec<u8>, SdkError> {
+    let size = transaction_size(message.len(), keys.len());
+    if size > PACKET_DATA_SIZE {
+        return Err(SdkError::TransactionTooLarge(size));
+    }
+    let mut transaction = vec![];
+    write_compact_u16(&mut transaction, keys.len());
+    for key in keys {
+        let signer = signers
+            .iter()
+            .find(|signer| signer.pubkey() == *key)
+            .ok_or(SdkError::MissingTransactionSigner(*key))?;
+        transaction.extend_from_slice(&signer.sign_message(&message)?);
+    }
+    transaction.extend(message);
+    Ok(transaction)
+}
+
+/// The wire transaction of `message` with `count` blank signatures
+fn unsigned(message: Vec<u8>, count: usize) -> Vec<u8> {
+    let mut transaction = vec![];
+    write_compact_u16(&mut transaction, count);
+    transaction.resize(transaction.len() + count * TRANSACTION_SIGNATURE_LEN, 0);
+    transaction.extend(message);
+    transaction
+}
+
+impl Message {
+    /// Compiles `instructions` paid by `payer`.
+    pub fn new(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        recent_blockhash: [u8; 32],
+    ) -> Result<Self, SdkError> {
+        let (header, account_keys) = static_keys(payer, &key_metas(payer, instructions));
+        let instructions = compile_instructions(instructions, &account_keys)?;
+        Ok(Self { header, account_keys, recent_blockhash, instructions })
+    }
+
+    /// Compiles `instructions` paid by `payer` into a durable transaction: it advances `nonce`
+    /// first and takes its nonce as blockhash, so it stays valid until the nonce advances.
+    pub fn new_with_nonce(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        nonce: &NonceAccount,
+    ) -> Result<Self, SdkError> {
+        Self::new(payer, &nonce.with_advance(instructions), nonce.durable_nonce)
+    }
+
+    /// Keys that must sign, in signature order
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.account_keys[..self.header.num_required_signatures as usize]
+    }
+
+    /// Wire encoding of the message, the bytes signers sign
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        serialize_header(&mut bytes, &self.header, &self.account_keys);
+        bytes.extend_from_slice(&self.recent_blockhash);
+        serialize_instructions(&mut bytes, &self.instructions);
+        bytes
+    }
+
+    /// Length of the signed wire transaction
+    pub fn size(&self) -> usize {
+        transaction_size(self.serialize().len(), self.signers().len())
+    }
+
+    /// Signs the message with `signers`, in any order, returning the wire transaction.
+    pub fn sign(&self, signers: &[&dyn TransactionSigner]) -> Result<Vec<u8>, SdkError> {
+        sign(self.serialize(), self.signers(), signers)
+    }
+
+    /// The wire transaction with blank signatures, for simulations that skip signature checks
+    pub fn unsigned(&self) -> Vec<u8> {
+        unsigned(self.serialize(), self.signers().len())
+    }
+}
+
+impl MessageV0 {
+    /// Compiles `instructions` paid by `payer`, loading from `lookup_tables` the accounts they
+    /// hold that neither sign nor are invoked as programs; tables none is loaded from are left
+    /// out.
+    pub fn new(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        recent_blockhash: [u8; 32],
+    ) -> Result<Self, SdkError> {
+        Self::compile(payer, instructions, lookup_tables, recent_blockhash, None)
+    }
+
+    /// As [`Message::new_with_nonce`]; the nonce account is never loaded from a table, as the
+    /// runtime only finds it among the static keys.
+    pub fn new_with_nonce(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        nonce: &NonceAccount,
+    ) -> Result<Self, SdkError> {
+        let instructions = nonce.with_advance(instructions);
+        Self::compile(
+            payer,
+            &instructions,
+            lookup_tables,
+            nonce.durable_nonce,
+            Some(&nonce.address),
+        )
+    }
+
+    fn compile(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[LookupTable],
+        recent_blockhash: [u8; 32],
+        nonce: Option<&Pubkey>,
+    ) -> Result<Self, SdkError> {
+        let mut keys = key_metas(payer, instructions);
+        let mut address_table_lookups = vec![];
+        let (mut loaded_writable, mut loaded_readonly) = (vec![], vec![]);
+        for table in lookup_tables {
+            let mut drain = |is_writable: bool, loaded: &mut Vec<Pubkey>| {
+                let found: Vec<(Pubkey, u8)> = keys
+                    .iter()
+                    .filter(|(key, meta)| {
+                        !meta.is_signer &&
+                            !meta.is_invoked &&
+                            meta.is_writable == is_writable &&
+                            Some(*key) != nonce
+                    })
+                    .filter_map(|(key, _)| {
+                        let index = table.addresses.iter().position(|address| address == key)?;
+                        Some((*key, u8::try_from(index).ok()?))
+                    })
+                    .collect();
+                found
+                    .into_iter()
+                    .map(|(key, index)| {
+                        keys.remove(&key);
+                        loaded.push(key);
+                        index
+                    })
+                    .collect::<Vec<u8>>()
+            };
+            let writable_indexes = drain(true, &mut loaded_writable);
+            let readonly_indexes = drain(false, &mut loaded_readonly);
+            if !writable_indexes.is_empty() || !readonly_indexes.is_empty() {
+                address_table_lookups.push(MessageAddressTableLookup {
+                    account_key: table.address,
+                    writable_indexes,
+                    readonly_indexes,
+                });
+            }
+        }
+
+        let (header, account_keys) = static_keys(payer, &keys);
+        let all_keys = [&account_keys[..], &loaded_writable, &loaded_readonly].concat();
+        let instructions = compile_instructions(instructions, &all_keys)?;
+        Ok(Self { header, account_keys, recent_blockhash, instructions, address_table_lookups })
+    }
+
+    /// Keys that must sign, in signature order
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.account_keys[..self.header.num_required_signatures as usize]
+    }
+
+    /// Wire encoding of the message, its version prefix first, the bytes signers sign
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![MESSAGE_VERSION_PREFIX];
+        serialize_header(&mut bytes, &self.header, &self.account_keys);
+        bytes.extend_from_slice(&self.recent_blockhash);
+        serialize_instructions(&mut bytes, &self.instructions);
+        write_compact_u16(&mut bytes, self.address_table_lookups.len());
+        for lookup in &self.address_table_lookups {
+            bytes.extend_from_slice(lookup.account_key.as_ref());
+            write_compact_u16(&mut bytes, lookup.writable_indexes.len());
+            bytes.extend_from_slice(&lookup.writable_indexes);
+            write_compact_u16(&mut bytes, lookup.readonly_indexes.len());
+            bytes.extend_from_slice(&lookup.readonly_indexes);
+        }
+        bytes
+    }
+
+    /// Length of the signed wire transaction
+    pub fn size(&self) -> usize {
+        transaction_size(self.serialize().len(), self.signers().len())
+    }
+
+    /// Signs the message with `signers`, in any order, returning the wire transaction.
+    pub fn sign(&self, signers: &[&dyn TransactionSigner]) -> Result<Vec<u8>, SdkError> {
+        sign(self.serialize(), self.signers(), signers)
+    }
+
+    /// The wire transaction with blank signatures, for simulations that skip signature checks
+    pub fn unsigned(&self) -> Vec<u8> {
+        unsigned(self.serialize(), self.signers().len())
+    }
+}
+
+/// Signature of a signed wire transaction, its first, which identifies it
+pub fn transaction_signature(transaction: &[u8]) -> Result<String, SdkError> {
+    match read_compact_u16(transaction) {
+        Some((count, len)) if count > 0 && transaction.len() >= len + TRANSACTION_SIGNATURE_LEN =>
+            Ok(bs58::encode(&transaction[len..len + TRANSACTION_SIGNATURE_LEN]).into_string()),
+        _ => Err(SdkError::InvalidTransaction),
+    }
+}
+
+/// Reads a "shortvec" length prefix, returning it with its length in bytes.
+pub(crate) fn read_compact_u16(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Solana's "shortvec" length prefix: 7 bits per byte, least significant first.
+pub(crate) fn write_compact_u16(bytes: &mut Vec<u8>, len: usize) {
+    let mut len = len as u16;
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
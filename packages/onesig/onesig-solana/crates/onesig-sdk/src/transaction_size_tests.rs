@@ -0,0 +1,192 @@
+// Tests for the bounds the packet size puts on the transactions of the largest leaves and roots.
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+    };
+    use onesig::{VerifyMerkleRootParams, MAX_THRESHOLD};
+    use onesig_core::{Hash, SIGNATURE_BYTES_LEN};
+
+    use crate::{
+        client::{ExecuteTransactionOptions, OneSigClient},
+        error::SdkError,
+        leaf::SolanaLeaf,
+        lookup_table::LookupTable,
+        transaction::{
+            Message, MessageV0, TransactionSigner, PACKET_DATA_SIZE, TRANSACTION_SIGNATURE_LEN,
+        },
+    };
+
+    const PAYER: Pubkey = Pubkey::new_from_array([0x10; 32]);
+    const MERKLE_ROOT: Hash = Hash([0x4d; 32]);
+
+    /// The payer, counting what it signs
+    #[derive(Default)]
+    struct Payer(Cell<usize>);
+
+    impl TransactionSigner for Payer {
+        fn pubkey(&self) -> Pubkey {
+            PAYER
+        }
+
+        fn sign_message(
+            &self,
+            _message: &[u8],
+        ) -> Result<[u8; TRANSACTION_SIGNATURE_LEN], SdkError> {
+            self.0.set(self.0.get() + 1);
+            Ok([0x5a; TRANSACTION_SIGNATURE_LEN])
+        }
+    }
+
+    fn client() -> OneSigClient {
+        OneSigClient::new(Pubkey::new_from_array([0x51; 32]))
+    }
+
+    /// Leaf calling a program over `accounts` accounts of its own with `data_len` bytes of data
+    fn leaf(accounts: usize, data_len: usize) -> SolanaLeaf {
+        let client = client();
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: (0..accounts)
+                .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+                .collect(),
+            data: vec![0xab; data_len],
+        };
+        SolanaLeaf {
+            one_sig_state: client.state(),
+            one_sig_id: 1,
+            nonce: 0,
+            instruction: client.resolve_instruction(&instruction, 0, 0).instruction,
+            token_outflow_limits: vec![],
+            guarded_accounts: vec![],
+            vault_index: 0,
+            cluster_genesis_hash: None,
+            group: None,
+            not_before: None,
+            bounty: None,
+        }
+    }
+
+    fn verification(signatures: usize) -> VerifyMerkleRootParams {
+        VerifyMerkleRootParams {
+            merkle_root: MERKLE_ROOT,
+            expiry: i64::MAX,
+            max_executions: 0,
+            signatures: vec![0x1b; signatures * SIGNATURE_BYTES_LEN],
+        }
+    }
+
+    fn execute(leaf: &SolanaLeaf, depth: usize, inline: Option<usize>) -> Instruction {
+        client().execute_leaf(
+            &PAYER,
+            &MERKLE_ROOT,
+            leaf,
+            vec![Hash([0x9f; 32]); depth],
+            inline.map(verification),
+            ExecuteTransactionOptions::default(),
+        )
+    }
+
+    /// The signed legacy transaction of `instruction`, paid by the payer
+    fn send(instruction: Instruction) -> Result<Vec<u8>, SdkError> {
+        Message::new(&PAYER, &[instruction], [0xbb; 32])?.sign(&[&Payer::default()])
+    }
+
+    #[test]
+    fn test_size() {
+        let message = Message::new(&PAYER, &[execute(&leaf(3, 100), 5, None)], [0xbb; 32]).unwrap();
+        assert_eq!(message.size(), message.sign(&[&Payer::default()]).unwrap().len());
+
+        let message =
+            MessageV0::new(&PAYER, &[execute(&leaf(3, 100), 5, Some(2))], &[], [0; 32]).unwrap();
+        assert_eq!(message.size(), message.sign(&[&Payer::default()]).unwrap().len());
+    }
+
+    #[test]
+    fn test_oversized_transactions_are_not_signed() {
+        let payer = Payer::default();
+        let message = Message::new(&PAYER, &[execute(&leaf(0, 1_000), 0, None)], [0; 32]).unwrap();
+
+        assert_eq!(message.sign(&[&payer]), Err(SdkError::TransactionTooLarge(message.size())));
+        assert_eq!(payer.0.get(), 0);
+    }
+
+    #[test]
+    fn test_instruction_data_of_a_leaf() {
+        // Room for `787 - 33a - 32p` bytes with `a` accounts and a proof of depth `p`, see
+        // `PACKET_DATA_SIZE`
+        for (accounts, depth) in [(0, 0), (0, 10), (8, 10), (16, 5)] {
+            let max_data_len = 787 - 33 * accounts - 32 * depth;
+            let transaction = send(execute(&leaf(accounts, max_data_len), depth, None)).unwrap();
+            assert_eq!(transaction.len(), PACKET_DATA_SIZE);
+
+            assert_eq!(
+                send(execute(&leaf(accounts, max_data_len + 1), depth, None)),
+                Err(SdkError::TransactionTooLarge(PACKET_DATA_SIZE + 1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_accounts_of_a_leaf() {
+        assert!(send(execute(&leaf(23, 0), 0, None)).is_ok());
+        assert!(matches!(
+            send(execute(&leaf(24, 0), 0, None)),
+            Err(SdkError::TransactionTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_proof_depth() {
+        assert!(send(execute(&leaf(0, 0), 24, None)).is_ok());
+        assert!(matches!(
+            send(execute(&leaf(0, 0), 25, None)),
+            Err(SdkError::TransactionTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_signatures_verified_inline() {
+        assert_eq!(send(execute(&leaf(0, 0), 0, Some(11))).unwrap().len(), 1_176);
+        assert_eq!(
+            send(execute(&leaf(0, 0), 0, Some(12))),
+            Err(SdkError::TransactionTooLarge(1_241))
+        );
+    }
+
+    #[test]
+    fn test_signatures_of_verify_merkle_root() {
+        let verify =
+            |signatures| send(client().verify_merkle_root(&PAYER, verification(signatures)));
+
+        let transaction = verify(MAX_THRESHOLD as usize).unwrap();
+        assert_eq!(transaction.len(), 1_204);
+        assert_eq!(
+            verify(MAX_THRESHOLD as usize + 1),
+            Err(SdkError::TransactionTooLarge(1_204 + SIGNATURE_BYTES_LEN))
+        );
+    }
+
+    #[test]
+    fn test_lookup_tables_make_room_for_accounts() {
+        let client = client();
+        let leaf = leaf(64, 0);
+        let instruction = execute(&leaf, 10, None);
+        assert!(matches!(send(instruction.clone()), Err(SdkError::TransactionTooLarge(_))));
+
+        let targets: Vec<Pubkey> = std::iter::once(leaf.instruction.program_id)
+            .chain(leaf.instruction.accounts.iter().map(|account| account.pubkey))
+            .collect();
+        let table = LookupTable {
+            address: Pubkey::new_unique(),
+            authority: None,
+            addresses: client.lookup_table_addresses(0, &targets),
+        };
+        let message = MessageV0::new(&PAYER, &[instruction], &[table], [0; 32]).unwrap();
+        assert!(message.size() <= PACKET_DATA_SIZE);
+        assert!(message.sign(&[&Payer::default()]).is_ok());
+    }
+}
@@ -0,0 +1,260 @@
+// Tests for the compilation, encoding and signing of legacy and v0 transaction messages.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{
+        prelude::Pubkey,
+        solana_program::instruction::{AccountMeta, Instruction},
+    };
+
+    use crate::{
+        error::SdkError,
+        lookup_table::LookupTable,
+        transaction::{
+            write_compact_u16, Message, MessageAddressTableLookup, MessageHeader, MessageV0,
+            TransactionSigner, MESSAGE_VERSION_PREFIX, TRANSACTION_SIGNATURE_LEN,
+        },
+    };
+
+    struct FixedSigner(Pubkey);
+
+    impl TransactionSigner for FixedSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.0
+        }
+
+        fn sign_message(
+            &self,
+            _message: &[u8],
+        ) -> Result<[u8; TRANSACTION_SIGNATURE_LEN], SdkError> {
+            Ok([self.0.to_bytes()[0]; TRANSACTION_SIGNATURE_LEN])
+        }
+    }
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    // Paid by 0x10, two programs (0x90, 0x80) over accounts of every kind
+    fn message() -> Message {
+        let instructions = [
+            Instruction {
+                program_id: key(0x90),
+                accounts: vec![
+                    AccountMeta::new(key(0x30), false),
+                    AccountMeta::new_readonly(key(0x20), false),
+                    AccountMeta::new(key(0x40), true),
+                    AccountMeta::new_readonly(key(0x10), true),
+                ],
+                data: vec![1, 2],
+            },
+            Instruction {
+                program_id: key(0x80),
+                accounts: vec![AccountMeta::new_readonly(key(0x20), true)],
+                data: vec![],
+            },
+        ];
+        Message::new(&key(0x10), &instructions, [0xbb; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_write_compact_u16() {
+        for (len, expected) in [
+            (0, vec![0]),
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x01]),
+            (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x80, 0x80, 0x01]),
+        ] {
+            let mut bytes = vec![];
+            write_compact_u16(&mut bytes, len);
+            assert_eq!(bytes, expected);
+        }
+    }
+
+    #[test]
+    fn test_compile() {
+        let message = message();
+        // The payer, writable signers, readonly signers, writable then readonly accounts, each
+        // group by key
+        assert_eq!(
+            message.account_keys,
+            [key(0x10), key(0x40), key(0x20), key(0x30), key(0x80), key(0x90)]
+        );
+        assert_eq!(
+            message.header,
+            MessageHeader {
+                num_required_signatures: 3,
+                num_readonly_signed_accounts: 1,
+                num_readonly_unsigned_accounts: 2,
+            }
+        );
+        assert_eq!(message.signers(), [key(0x10), key(0x40), key(0x20)]);
+        assert_eq!(message.instructions[0].program_id_index, 5);
+        assert_eq!(message.instructions[0].accounts, [3, 2, 1, 0]);
+        assert_eq!(message.instructions[1].program_id_index, 4);
+        assert_eq!(message.instructions[1].accounts, [2]);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let message = message();
+        let bytes = message.serialize();
+
+        let mut expected = vec![3, 1, 2, 6];
+        for key in &message.account_keys {
+            expected.extend_from_slice(key.as_ref());
+        }
+        expected.extend_from_slice(&[0xbb; 32]);
+        expected.extend_from_slice(&[2, 5, 4, 3, 2, 1, 0, 2, 1, 2, 4, 1, 2, 0]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_sign() {
+        let message = message();
+        let signers = [FixedSigner(key(0x20)), FixedSigner(key(0x10)), FixedSigner(key(0x40))];
+        let signers: Vec<&dyn TransactionSigner> =
+            signers.iter().map(|signer| signer as &dyn TransactionSigner).collect();
+
+        let transaction = message.sign(&signers).unwrap();
+        assert_eq!(transaction[0], 3);
+        // Signatures follow the order of the signer keys
+        for (i, byte) in [0x10, 0x40, 0x20].into_iter().enumerate() {
+            let signature =
+                &transaction[1 + i * TRANSACTION_SIGNATURE_LEN..][..TRANSACTION_SIGNATURE_LEN];
+            assert_eq!(signature, [byte; TRANSACTION_SIGNATURE_LEN]);
+        }
+        assert_eq!(transaction[1 + 3 * TRANSACTION_SIGNATURE_LEN..], message.serialize());
+
+        assert_eq!(message.sign(&signers[..2]), Err(SdkError::MissingTransactionSigner(key(0x40))));
+    }
+
+    #[test]
+    fn test_too_many_accounts() {
+        let instruction = Instruction {
+            program_id: key(0),
+            accounts: (0..=u8::MAX as u16)
+                .map(|i| {
+                    let mut bytes = [1; 32];
+                    bytes[0] = i as u8;
+                    AccountMeta::new(Pubkey::new_from_array(bytes), false)
+                })
+                .collect(),
+            data: vec![],
+        };
+        assert_eq!(
+            Message::new(&key(0xff), &[instruction], [0; 32]),
+            Err(SdkError::TooManyAccounts(258))
+        );
+    }
+
+    // Paid by 0x10, loading what it can of 0x20 to 0x90 from a table, but neither its signer 0x40
+    // nor its program 0x80
+    fn message_v0(lookup_tables: &[LookupTable]) -> MessageV0 {
+        let instructions = [
+            Instruction {
+                program_id: key(0x90),
+                accounts: vec![
+                    AccountMeta::new(key(0x30), false),
+                    AccountMeta::new_readonly(key(0x20), false),
+                    AccountMeta::new(key(0x40), true),
+                    AccountMeta::new_readonly(key(0x50), false),
+                ],
+                data: vec![1, 2],
+            },
+            Instruction { program_id: key(0x80), accounts: vec![], data: vec![] },
+        ];
+        MessageV0::new(&key(0x10), &instructions, lookup_tables, [0xbb; 32]).unwrap()
+    }
+
+    fn lookup_table(address: u8, addresses: &[u8]) -> LookupTable {
+        LookupTable {
+            address: key(address),
+            authority: None,
+            addresses: addresses.iter().map(|address| key(*address)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_compile_v0() {
+        let unused = lookup_table(0xa1, &[0x60]);
+        let message =
+            message_v0(&[unused, lookup_table(0xa2, &[0x20, 0x80, 0x40, 0x30, 0x10, 0x90])]);
+
+        assert_eq!(message.account_keys, [key(0x10), key(0x40), key(0x50), key(0x80), key(0x90)]);
+        assert_eq!(
+            message.header,
+            MessageHeader {
+                num_required_signatures: 2,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 3,
+            }
+        );
+        assert_eq!(
+            message.address_table_lookups,
+            [MessageAddressTableLookup {
+                account_key: key(0xa2),
+                writable_indexes: vec![3],
+                readonly_indexes: vec![0],
+            }]
+        );
+        // Loaded accounts index after the static keys, writable ones first
+        assert_eq!(message.instructions[0].program_id_index, 4);
+        assert_eq!(message.instructions[0].accounts, [5, 6, 1, 2]);
+        assert_eq!(message.instructions[1].program_id_index, 3);
+
+        // Without tables, every key is static
+        let message = message_v0(&[]);
+        assert_eq!(message.account_keys.len(), 7);
+        assert!(message.address_table_lookups.is_empty());
+    }
+
+    #[test]
+    fn test_compile_v0_first_table_wins() {
+        let message = message_v0(&[lookup_table(0xa1, &[0x30]), lookup_table(0xa2, &[0x20, 0x30])]);
+        assert_eq!(
+            message.address_table_lookups,
+            [
+                MessageAddressTableLookup {
+                    account_key: key(0xa1),
+                    writable_indexes: vec![0],
+                    readonly_indexes: vec![],
+                },
+                MessageAddressTableLookup {
+                    account_key: key(0xa2),
+                    writable_indexes: vec![],
+                    readonly_indexes: vec![0],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_v0() {
+        let message = message_v0(&[lookup_table(0xa2, &[0x20, 0x30])]);
+        let bytes = message.serialize();
+
+        let mut expected = vec![MESSAGE_VERSION_PREFIX, 2, 0, 3, 5];
+        for key in &message.account_keys {
+            expected.extend_from_slice(key.as_ref());
+        }
+        expected.extend_from_slice(&[0xbb; 32]);
+        expected.extend_from_slice(&[2, 4, 4, 5, 6, 1, 2, 2, 1, 2, 3, 0, 0]);
+        expected.push(1);
+        expected.extend_from_slice(key(0xa2).as_ref());
+        expected.extend_from_slice(&[1, 1, 1, 0]);
+        assert_eq!(bytes, expected);
+
+        let signers = [FixedSigner(key(0x40)), FixedSigner(key(0x10))];
+        let signers: Vec<&dyn TransactionSigner> =
+            signers.iter().map(|signer| signer as &dyn TransactionSigner).collect();
+        let transaction = message.sign(&signers).unwrap();
+        assert_eq!(transaction[0], 2);
+        assert_eq!(
+            transaction[1..][..TRANSACTION_SIGNATURE_LEN],
+            [0x10; TRANSACTION_SIGNATURE_LEN]
+        );
+        assert_eq!(transaction[1 + 2 * TRANSACTION_SIGNATURE_LEN..], bytes);
+        assert_eq!(message.unsigned()[1 + 2 * TRANSACTION_SIGNATURE_LEN..], bytes);
+    }
+}
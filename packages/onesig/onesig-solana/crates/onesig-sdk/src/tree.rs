@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use onesig_core::{
+    leaf::{process_proof, verify_proof},
+    Hash,
+};
+
+use crate::{
+    digest::{merkle_root_message, Eip712Message},
+    error::SdkError,
+    leaf::Leaf,
+    proof::LeafProof,
+};
+
+/// Merkle tree of a batch of OneSig leaves, across chains.
+///
+/// Built like merkletreejs with `sort: true`, as the TypeScript tooling does: leaf hashes are
+/// sorted, each pair is hashed in sorted order (as the program folds proofs), and the last node
+/// of an odd level is carried up as is.
+pub struct OneSigTree {
+    /// Leaf hashes, in the order they were given
+    leaves: Vec<Hash>,
+    /// Levels from the sorted leaves up to the root
+    levels: Vec<Vec<Hash>>,
+}
+
+impl OneSigTree {
+    /// Builds the tree of `leaves`, which must not execute twice at the same OneSig id and nonce.
+    pub fn new(leaves: impl IntoIterator<Item = Leaf>) -> Result<Self, SdkError> {
+        let mut seen = HashSet::new();
+        let mut hashes = vec![];
+        for leaf in leaves {
+            let (one_sig_id, nonce) = (leaf.one_sig_id(), leaf.nonce());
+            if !seen.insert((one_sig_id, nonce)) {
+                return Err(SdkError::DuplicateNonce { one_sig_id, nonce });
+            }
+            hashes.push(leaf.hash());
+        }
+        Self::from_hashes(hashes)
+    }
+
+    /// Builds the tree of already hashed leaves, e.g. of chains this SDK does not encode.
+    pub fn from_hashes(leaves: Vec<Hash>) -> Result<Self, SdkError> {
+        if leaves.is_empty() {
+            return Err(SdkError::EmptyTree);
+        }
+        let mut level = leaves.clone();
+        level.sort();
+        let mut levels = vec![level];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => process_proof(a, &[*b]),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { leaves, levels })
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Leaf hashes, in the order they were given
+    pub fn leaves(&self) -> &[Hash] {
+        &self.leaves
+    }
+
+    /// Proof of the `index`th leaf given, as `execute_transaction` expects it.
+    pub fn proof(&self, index: usize) -> Result<Vec<Hash>, SdkError> {
+        let leaf = self.leaves.get(index).ok_or(SdkError::LeafNotFound(index))?;
+        let mut position = self.levels[0].iter().position(|hash| hash == leaf).unwrap();
+        let mut proof = vec![];
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        debug_assert!(verify_proof(&self.root(), &proof, leaf));
+        Ok(proof)
+    }
+
+    /// Proof of the `index`th leaf given, with the leaf and the root
+    pub fn leaf_proof(&self, index: usize) -> Result<LeafProof, SdkError> {
+        let proof = self.proof(index)?;
+        Ok(LeafProof { merkle_root: self.root(), leaf: self.leaves[index], proof })
+    }
+
+    /// EIP-712 message signers sign to approve the root; a non-zero `max_executions` caps how many
+    /// leaves the root executes on Solana.
+    pub fn message(&self, seed: &Hash, expiry: u64, max_executions: u32) -> Eip712Message {
+        merkle_root_message(seed, &self.root(), expiry, max_executions)
+    }
+
+    /// Digest of [`Self::message`]
+    pub fn digest(&self, seed: &Hash, expiry: u64, max_executions: u32) -> Hash {
+        self.message(seed, expiry, max_executions).digest()
+    }
+}
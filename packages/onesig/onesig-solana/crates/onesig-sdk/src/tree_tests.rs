@@ -0,0 +1,83 @@
+// Tests for the merkletreejs-compatible tree layout and the proofs it produces.
+#[cfg(test)]
+mod tests {
+    use onesig_core::{
+        digest::merkle_root_digest,
+        leaf::{process_proof, verify_proof},
+        Hash,
+    };
+
+    use crate::{
+        error::SdkError,
+        leaf::{EvmLeaf, Leaf},
+        tree::OneSigTree,
+    };
+
+    fn hashes(count: u8) -> Vec<Hash> {
+        (0..count).map(|i| Hash([i.wrapping_mul(97); 32])).collect()
+    }
+
+    fn evm_leaf(one_sig_id: u64, nonce: u64) -> Leaf {
+        EvmLeaf { one_sig_address: [0x33; 20], one_sig_id, nonce, calls: vec![] }.into()
+    }
+
+    #[test]
+    fn test_proofs_verify() {
+        for count in 1..=9 {
+            let tree = OneSigTree::from_hashes(hashes(count)).unwrap();
+            for (index, leaf) in tree.leaves().iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(verify_proof(&tree.root(), &proof, leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn test_root_ignores_leaf_order() {
+        let leaves = hashes(7);
+        let mut reversed = leaves.clone();
+        reversed.reverse();
+
+        // `Hash` is not `Debug`
+        assert!(
+            OneSigTree::from_hashes(leaves).unwrap().root() ==
+                OneSigTree::from_hashes(reversed).unwrap().root()
+        );
+    }
+
+    #[test]
+    fn test_odd_leaf_is_carried_up() {
+        // `Hash` is not `Debug`
+        let mut leaves = hashes(3);
+        leaves.sort();
+        let tree = OneSigTree::from_hashes(leaves.clone()).unwrap();
+
+        let expected = process_proof(&process_proof(&leaves[0], &[leaves[1]]), &[leaves[2]]);
+        assert!(tree.root() == expected);
+        assert!(tree.proof(2).unwrap() == vec![process_proof(&leaves[0], &[leaves[1]])]);
+
+        let single = OneSigTree::from_hashes(vec![leaves[0]]).unwrap();
+        assert!(single.root() == leaves[0]);
+        assert!(single.proof(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_nonces() {
+        assert!(OneSigTree::new([evm_leaf(101, 0), evm_leaf(102, 0), evm_leaf(101, 1)]).is_ok());
+        assert_eq!(
+            OneSigTree::new([evm_leaf(101, 0), evm_leaf(101, 0)]).err(),
+            Some(SdkError::DuplicateNonce { one_sig_id: 101, nonce: 0 })
+        );
+        assert_eq!(OneSigTree::new([]).err(), Some(SdkError::EmptyTree));
+    }
+
+    #[test]
+    fn test_digest() {
+        let tree = OneSigTree::new([evm_leaf(101, 0), evm_leaf(101, 1)]).unwrap();
+        let seed = Hash([5; 32]);
+
+        assert!(tree.digest(&seed, 100, 0) == merkle_root_digest(&seed, &tree.root(), 100, 0));
+        assert!(tree.digest(&seed, 100, 2) != tree.digest(&seed, 100, 0));
+        assert_eq!(tree.proof(2).err(), Some(SdkError::LeafNotFound(2)));
+    }
+}
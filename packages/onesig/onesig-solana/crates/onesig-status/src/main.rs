@@ -0,0 +1,108 @@
+//! `onesig-status`: an HTTP service serving the state of OneSig accounts as JSON.
+//!
+//! Frontends and monitoring read the nonce, signers, threshold, active roots and recent
+//! executions of each account from it, rather than each talking to a node and decoding accounts
+//! and events. The service reads the accounts every interval and walks their transaction history
+//! from where the previous walk stopped, serving the last statuses it read in between.
+
+mod server;
+mod status;
+
+#[cfg(test)]
+mod server_tests;
+#[cfg(test)]
+mod status_tests;
+
+use std::{
+    env,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Result};
+use onesig_cli::{args::Args, context::Context};
+
+use crate::status::Instance;
+
+const USAGE: &str = "\
+usage: onesig-status [--state <name|pubkey>...] [--listen <address>] [--interval <seconds>]
+       [--recent <n>]
+
+  --state <name|pubkey>    OneSig account to serve, by address or profile name
+                           [default: the accounts of the profile]
+  --listen <address>       [default: 127.0.0.1:8080]
+  --interval <seconds>     wait between two reads of the accounts [default: 30]
+  --recent <n>             executions served per account, newest first [default: 20]
+
+  --profile, --config, --url, --commitment and --program-id are those of onesig-cli.
+
+endpoints:
+  GET /instances                   every account
+  GET /instances/<name|address>    one account
+";
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(args: impl IntoIterator<Item = String>) -> Result<()> {
+    let mut args = Args::parse(args);
+    if args.flag("help")? {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    let context = Context::from_args(&mut args)?;
+    let states = args.values("state")?;
+    let mut instances = if states.is_empty() {
+        let instances = context.instances().iter();
+        instances
+            .map(|(name, state)| Instance::new(Some(name.clone()), *state))
+            .collect()
+    } else {
+        states
+            .into_iter()
+            .map(|state| {
+                let name = context.instances().contains_key(&state).then(|| state.clone());
+                Ok(Instance::new(name, context.state(&state)?))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    if instances.is_empty() {
+        bail!("give --state, or a profile naming OneSig accounts\n\n{USAGE}");
+    }
+    let listen = args.value("listen")?.unwrap_or_else(|| "127.0.0.1:8080".into());
+    let interval = Duration::from_secs(args.parsed("interval")?.unwrap_or(30));
+    let recent = args.parsed("recent")?.unwrap_or(20);
+    args.finish()?;
+
+    let statuses = Arc::new(Mutex::new(vec![]));
+    server::serve(&listen, statuses.clone())?;
+    println!("serving {} accounts on http://{listen}/instances", instances.len());
+    loop {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs().try_into()?;
+        let mut refreshed = vec![];
+        for instance in &mut instances {
+            match instance.refresh(&context, now, recent) {
+                Ok(status) => refreshed.push(status),
+                Err(error) => {
+                    eprintln!("{}: {error:#}", instance.address);
+                    // Keep serving what was last read rather than dropping the account
+                    let address = instance.address.to_string();
+                    let previous = statuses.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    refreshed
+                        .extend(previous.iter().find(|status| status.address == address).cloned());
+                },
+            }
+        }
+        *statuses.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = refreshed;
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+
+use crate::status::InstanceStatus;
+
+/// The statuses of the last refresh, in the order of the instances
+pub type Statuses = Arc<Mutex<Vec<InstanceStatus>>>;
+
+/// Serves `statuses` as JSON on `address`, from a thread of its own.
+pub fn serve(address: &str, statuses: Statuses) -> Result<()> {
+    let listener =
+        TcpListener::bind(address).with_context(|| format!("cannot listen on {address}"))?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(error) = respond(stream, &statuses) {
+                eprintln!("status: {error}");
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, statuses: &Statuses) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut parts = request_line.split(' ');
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let (status, body) = if method == "GET" {
+        let statuses = statuses.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        route(path, &statuses)
+    } else {
+        ("405 Method Not Allowed", error("only GET is served"))
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// The response to `GET <path>`: `/instances` lists every instance, `/instances/<name|address>`
+/// is one of them.
+pub fn route(path: &str, statuses: &[InstanceStatus]) -> (&'static str, String) {
+    let path = path.split('?').next().unwrap_or_default().trim_end_matches('/');
+    match path.strip_prefix("/instances") {
+        Some("") => ("200 OK", json(&statuses)),
+        Some(instance) if instance.starts_with('/') => {
+            let instance = &instance[1..];
+            match statuses.iter().find(|status| {
+                status.address == instance || status.name.as_deref() == Some(instance)
+            }) {
+                Some(status) => ("200 OK", json(status)),
+                None => ("404 Not Found", error(&format!("no instance {instance}"))),
+            }
+        },
+        _ => ("404 Not Found", error("not found, see /instances")),
+    }
+}
+
+fn json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string(value).expect("statuses serialize to JSON")
+}
+
+fn error(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
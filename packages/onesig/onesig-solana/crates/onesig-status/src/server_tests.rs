@@ -0,0 +1,44 @@
+// Tests for the routes of the status API.
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::{server::route, status::InstanceStatus};
+
+    fn status(name: Option<&str>, address: &str) -> InstanceStatus {
+        InstanceStatus {
+            name: name.map(String::from),
+            address: address.into(),
+            one_sig_id: 1,
+            nonce: 0,
+            threshold: 1,
+            signers: vec![],
+            executor_required: false,
+            seed: String::new(),
+            active_roots: vec![],
+            recent_executions: vec![],
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        let statuses = [status(Some("treasury"), "Addr1"), status(None, "Addr2")];
+        let get = |path: &str| {
+            let (code, body) = route(path, &statuses);
+            (code, serde_json::from_str::<Value>(&body).unwrap())
+        };
+
+        let (code, body) = get("/instances");
+        assert_eq!(code, "200 OK");
+        assert_eq!(body.as_array().unwrap().len(), 2);
+        assert_eq!(get("/instances/treasury").1["address"], "Addr1");
+        assert_eq!(get("/instances/Addr2/?pretty").1["address"], "Addr2");
+        assert_eq!(get("/instances/Addr1").1["name"], "treasury");
+
+        let (code, body) = get("/instances/unknown");
+        assert_eq!((code, body["error"].as_str()), ("404 Not Found", Some("no instance unknown")));
+        assert_eq!(get("/instancesAddr1").0, "404 Not Found");
+        assert_eq!(get("/").0, "404 Not Found");
+    }
+}
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use onesig::{MerkleRootRegistry, MerkleRootState};
+use onesig_cli::{
+    commands::{close_root::closeable, decode::utc},
+    context::Context,
+};
+use onesig_sdk::{checksum_address, Execution, History, OneSigAccount};
+use serde::Serialize;
+
+/// The state of a OneSig as the service serves it
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStatus {
+    /// Name of the account in the profile, if it has one
+    pub name: Option<String>,
+    pub address: String,
+    pub one_sig_id: u64,
+    pub nonce: u64,
+    pub threshold: u8,
+    /// EIP-55 checksummed
+    pub signers: Vec<String>,
+    pub executor_required: bool,
+    pub seed: String,
+    /// Verified roots that have not expired and were signed with the current seed
+    pub active_roots: Vec<RootStatus>,
+    /// Newest first
+    pub recent_executions: Vec<ExecutionStatus>,
+    /// Unix time the status was read at
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootStatus {
+    pub merkle_root: String,
+    pub expiry: i64,
+    /// `expiry` as a `YYYY-MM-DD hh:mm:ss UTC` time
+    pub expires_at: String,
+    /// `None` if the root executes any number of leaves
+    pub remaining_executions: Option<u32>,
+    /// Leaves executed under the root so far
+    pub executions: u32,
+    pub signed_by: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionStatus {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    #[serde(flatten)]
+    pub execution: ExecutionKind,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ExecutionKind {
+    Leaf { merkle_root: String, nonce: u64 },
+    Module { module: String, vault_index: u8 },
+}
+
+/// A OneSig the service watches, with the history of its transactions walked so far
+pub struct Instance {
+    pub name: Option<String>,
+    pub address: Pubkey,
+    history: History,
+}
+
+impl Instance {
+    pub fn new(name: Option<String>, address: Pubkey) -> Self {
+        Self { name, address, history: History::new(address) }
+    }
+
+    /// Reads the account and its roots, and walks the transactions since the last refresh.
+    pub fn refresh(
+        &mut self,
+        context: &Context,
+        now: i64,
+        recent: usize,
+    ) -> Result<InstanceStatus> {
+        let account = context.one_sig_account(&self.address)?;
+        let registry = context.client(self.address).pda.merkle_root_registry().0;
+        let mut roots = vec![];
+        if let Some(registry) = context.anchor_account::<MerkleRootRegistry>(&registry)? {
+            for entry in registry.entries {
+                if let Some(root) = context.anchor_account::<MerkleRootState>(&entry.address)? {
+                    roots.push(root);
+                }
+            }
+        }
+        context
+            .rpc()
+            .update_history(&context.program_id, &mut self.history)
+            .map_err(|error| anyhow!("cannot walk the history of {}: {error}", self.address))?;
+        Ok(describe(self.name.clone(), &account, &roots, &self.history, recent, now))
+    }
+}
+
+/// The status of `account` at `now`, with the `recent` newest executions of its history
+pub fn describe(
+    name: Option<String>,
+    account: &OneSigAccount,
+    roots: &[MerkleRootState],
+    history: &History,
+    recent: usize,
+    now: i64,
+) -> InstanceStatus {
+    let header = &account.header;
+    let active_roots = roots
+        .iter()
+        .filter(|root| !closeable(root, &header.seed, now))
+        .map(|root| RootStatus {
+            merkle_root: format!("0x{}", hex::encode(root.merkle_root.0)),
+            expiry: root.expiry,
+            expires_at: utc(root.expiry),
+            remaining_executions: root.remaining_executions,
+            executions: root.executions,
+            signed_by: root.signed_by.iter().map(checksum_address).collect(),
+        })
+        .collect();
+    let executions: Vec<_> = history.executions().collect();
+    let recent_executions = executions
+        .into_iter()
+        .rev()
+        .take(recent)
+        .map(|record| ExecutionStatus {
+            execution: match record.activity {
+                Execution::Leaf { merkle_root, nonce, .. } => ExecutionKind::Leaf {
+                    merkle_root: format!("0x{}", hex::encode(merkle_root.0)),
+                    nonce: *nonce,
+                },
+                Execution::Module { module, vault_index } =>
+                    ExecutionKind::Module { module: module.to_string(), vault_index: *vault_index },
+            },
+            signature: record.signature,
+            slot: record.slot,
+            block_time: record.block_time,
+        })
+        .collect();
+    InstanceStatus {
+        name,
+        address: account.address.to_string(),
+        one_sig_id: header.one_sig_id,
+        nonce: header.nonce,
+        threshold: header.threshold,
+        signers: account.signers.iter().map(checksum_address).collect(),
+        executor_required: header.executor_required(),
+        seed: format!("0x{}", hex::encode(header.seed.0)),
+        active_roots,
+        recent_executions,
+        updated_at: now,
+    }
+}
@@ -0,0 +1,112 @@
+// Tests for the status served for a OneSig account.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use bytemuck::Zeroable;
+    use onesig::{
+        Address, Hash, MerkleRootState, OneSigState, MERKLE_ROOT_STATE_VERSION,
+        ONE_SIG_STATE_VERSION,
+    };
+    use onesig_sdk::{checksum_address, Activity, Execution, History, OneSigAccount, Record};
+    use serde_json::json;
+
+    use crate::status::describe;
+
+    fn root(id: u8, seed: Hash, expiry: i64) -> MerkleRootState {
+        MerkleRootState {
+            merkle_root: Hash([id; 32]),
+            seed_commitment: MerkleRootState::seed_commitment(&seed),
+            expiry,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![Address([0xaa; 20])],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions: Some(2),
+        }
+    }
+
+    /// Execution of leaf `nonce` of root `0x0101..`
+    fn leaf(nonce: u64) -> Execution {
+        Execution::Leaf {
+            merkle_root: Hash([1; 32]),
+            nonce,
+            executor: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            ix_data_hash: Hash([2; 32]),
+            value: 0,
+            lamports_spent: 0,
+            root_executions: None,
+        }
+    }
+
+    fn record(slot: u64, execution: Execution) -> Record<Activity> {
+        Record {
+            signature: format!("sig{slot}"),
+            slot,
+            block_time: Some(1_700_000_000),
+            activity: Activity::Execution(execution),
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut header = OneSigState::zeroed();
+        header.one_sig_id = 9;
+        header.nonce = 4;
+        header.seed = Hash([0x11; 32]);
+        header.threshold = 2;
+        header.executor_required = 1;
+        header.version = ONE_SIG_STATE_VERSION;
+        let address = Pubkey::new_unique();
+        let account = OneSigAccount {
+            address,
+            header,
+            signers: vec![Address([0xaa; 20]), Address([0xbb; 20])],
+        };
+        let roots =
+            [root(1, header.seed, 1_000), root(2, header.seed, 10), root(3, Hash([0; 32]), 1_000)];
+        let module = Pubkey::new_unique();
+        let mut history = History::new(address);
+        history.records.extend([
+            record(1, leaf(2)),
+            record(2, leaf(3)),
+            record(3, Execution::Module { module, vault_index: 1 }),
+        ]);
+
+        let status = describe(Some("treasury".into()), &account, &roots, &history, 2, 100);
+        assert_eq!(status.nonce, 4);
+        assert_eq!(status.signers[1], checksum_address(&Address([0xbb; 20])));
+        // Expired roots and roots of a former seed are not active
+        assert_eq!(status.active_roots.len(), 1);
+        assert_eq!(status.active_roots[0].merkle_root, format!("0x{}", "01".repeat(32)));
+        assert_eq!(status.active_roots[0].expires_at, "1970-01-01 00:16:40 UTC");
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["name"], "treasury");
+        assert_eq!(json["executorRequired"], true);
+        assert_eq!(json["activeRoots"][0]["remainingExecutions"], 2);
+        // Newest first, up to the count asked for
+        assert_eq!(
+            json["recentExecutions"],
+            json!([
+                {
+                    "signature": "sig3",
+                    "slot": 3,
+                    "blockTime": 1_700_000_000,
+                    "kind": "module",
+                    "module": module.to_string(),
+                    "vaultIndex": 1,
+                },
+                {
+                    "signature": "sig2",
+                    "slot": 2,
+                    "blockTime": 1_700_000_000,
+                    "kind": "leaf",
+                    "merkleRoot": format!("0x{}", "01".repeat(32)),
+                    "nonce": 3,
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Prints freshly generated test vectors, to be committed as `vectors.json`.
+
+fn main() {
+    let vectors = onesig_test_vectors::generate();
+    println!("{}", serde_json::to_string_pretty(&vectors).unwrap());
+}
@@ -0,0 +1,56 @@
+//! Signers made up inside tests, for cases the committed vectors do not cover: more signers, other
+//! digests. Keys derive from labels, and k256 signs per RFC 6979, so fixtures are deterministic
+//! and need no round trip through the hardhat suite.
+
+use k256::ecdsa::SigningKey;
+use onesig_core::{Address, Hash, Secp256k1Pubkey, SIGNATURE_BYTES_LEN};
+
+use crate::label;
+
+pub struct TestSigner {
+    key: SigningKey,
+    /// Uncompressed public key, without its prefix byte
+    pub public_key: Vec<u8>,
+    pub address: Address,
+}
+
+impl TestSigner {
+    /// The signer whose private key derives from `name`
+    pub fn new(name: &str) -> Self {
+        let key = SigningKey::from_slice(&label(name).0).unwrap();
+        let point = key.verifying_key().to_encoded_point(false);
+        let public_key = point.as_bytes()[1..].to_vec();
+        let address = Address::from(Secp256k1Pubkey::new(&public_key));
+        Self { key, public_key, address }
+    }
+
+    pub fn private_key(&self) -> Vec<u8> {
+        self.key.to_bytes().to_vec()
+    }
+
+    /// Signs `digest` as Ethereum signers do: r‖s‖v with v = 27 + recovery id.
+    pub fn sign(&self, digest: &Hash) -> [u8; SIGNATURE_BYTES_LEN] {
+        let (signature, recovery_id) = self.key.sign_prehash_recoverable(&digest.0).unwrap();
+        let mut bytes = [0u8; SIGNATURE_BYTES_LEN];
+        bytes[..64].copy_from_slice(&signature.to_bytes());
+        bytes[64] = 27 + recovery_id.to_byte();
+        bytes
+    }
+}
+
+/// `count` signers sorted by address; the first `THRESHOLD` labels are the ones of the vectors.
+pub fn signers(count: usize) -> Vec<TestSigner> {
+    let mut signers: Vec<TestSigner> =
+        (0..count).map(|i| TestSigner::new(&format!("signer-{i}"))).collect();
+    signers.sort_by_key(|signer| signer.address);
+    signers
+}
+
+pub fn addresses(signers: &[TestSigner]) -> Vec<Address> {
+    signers.iter().map(|signer| signer.address).collect()
+}
+
+/// Signatures of `digest` by each of `signers`, concatenated in their order
+pub fn sign_all(signers: &[TestSigner], digest: &Hash) -> Vec<u8> {
+    signers.iter().flat_map(|signer| signer.sign(digest)).collect()
+}
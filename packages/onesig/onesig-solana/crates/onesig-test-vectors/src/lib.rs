@@ -0,0 +1,343 @@
+//! OneSig test vectors shared by the Solana unit tests and the EVM hardhat tests, so both check
+//! their encodings and signature verification against the same values rather than copies of each
+//! other's output.
+//!
+//! The vectors are committed in `vectors.json`, next to this crate's manifest, and regenerated
+//! with `cargo run -p onesig-test-vectors > crates/onesig-test-vectors/vectors.json`. Everything
+//! is derived deterministically (signer keys from fixed labels, RFC 6979 signatures), so a
+//! regeneration only changes the file when an encoding does.
+
+use onesig_core::{
+    digest::{merkle_root_digest, signer_execution_authorization_digest},
+    leaf::{
+        encode_instruction, encode_leaf, process_proof, LeafAccountMeta, LeafTokenOutflowLimit,
+    },
+    Hash,
+};
+use serde::{Deserialize, Serialize};
+use solana_keccak_hasher as keccak;
+
+use crate::fixtures::{sign_all, signers};
+
+pub mod fixtures;
+#[cfg(test)]
+mod vectors_tests;
+
+/// The committed vectors, see [`load`].
+pub const VECTORS_JSON: &str = include_str!("../vectors.json");
+
+/// Threshold of the multisig the vectors are signed for.
+pub const THRESHOLD: u8 = 2;
+
+/// Merkle root expiry of the vectors, far enough in the future for tests running against a live
+/// clock: 2100-01-01.
+pub const EXPIRY: u64 = 4_102_444_800;
+
+/// Execution cap the limited merkle root digest is signed with.
+pub const MAX_EXECUTIONS: u32 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestVectors {
+    /// Signers, sorted by address
+    pub signers: Vec<SignerVector>,
+    pub threshold: u8,
+    #[serde(with = "hex_bytes")]
+    pub seed: Vec<u8>,
+    pub expiry: u64,
+    pub solana_leaves: Vec<SolanaLeafVector>,
+    /// Leaves of the tree: the Solana leaves, then opaque leaves of other chains
+    #[serde(with = "hex_bytes_list")]
+    pub leaves: Vec<Vec<u8>>,
+    /// Proof of each leaf, in the same order
+    pub proofs: Vec<ProofVector>,
+    #[serde(with = "hex_bytes")]
+    pub merkle_root: Vec<u8>,
+    /// `SignMerkleRoot` digest of the root, and the signatures of every signer on it
+    pub merkle_root_signatures: SignedDigestVector,
+    /// `SignLimitedMerkleRoot` digest of the root, capped to `MAX_EXECUTIONS`, and its signatures
+    pub limited_merkle_root_signatures: SignedDigestVector,
+    pub signer_execution_authorization: SignerExecutionAuthorizationVector,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerVector {
+    #[serde(with = "hex_bytes")]
+    pub private_key: Vec<u8>,
+    /// Uncompressed public key, without its prefix byte
+    #[serde(with = "hex_bytes")]
+    pub public_key: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub address: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMetaVector {
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOutflowLimitVector {
+    #[serde(with = "hex_bytes")]
+    pub mint: Vec<u8>,
+    pub max_amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolanaLeafVector {
+    #[serde(with = "hex_bytes")]
+    pub one_sig_state: Vec<u8>,
+    pub one_sig_id: u64,
+    pub nonce: u64,
+    #[serde(with = "hex_bytes")]
+    pub program_id: Vec<u8>,
+    pub accounts: Vec<AccountMetaVector>,
+    #[serde(with = "hex_bytes")]
+    pub data: Vec<u8>,
+    pub value: u64,
+    pub token_outflow_limits: Vec<TokenOutflowLimitVector>,
+    #[serde(with = "hex_bytes_list")]
+    pub guarded_accounts: Vec<Vec<u8>>,
+    pub vault_index: u8,
+    #[serde(with = "hex_bytes")]
+    pub cluster_genesis_hash: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub encoded_instruction: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub leaf: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofVector {
+    #[serde(with = "hex_bytes_list")]
+    pub proof: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedDigestVector {
+    #[serde(with = "hex_bytes")]
+    pub digest: Vec<u8>,
+    /// r‖s‖v signatures of every signer, concatenated in signer order
+    #[serde(with = "hex_bytes")]
+    pub signatures: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerExecutionAuthorizationVector {
+    /// Index of the authorized leaf in `leaves`
+    pub leaf_index: usize,
+    #[serde(with = "hex_bytes")]
+    pub delegate: Vec<u8>,
+    pub expiry: u64,
+    #[serde(with = "hex_bytes")]
+    pub digest: Vec<u8>,
+    /// Signature of the first signer
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+/// Parses the committed vectors.
+pub fn load() -> TestVectors {
+    serde_json::from_str(VECTORS_JSON).expect("vectors.json is malformed")
+}
+
+/// Generates the vectors from scratch.
+pub fn generate() -> TestVectors {
+    let keys = signers(THRESHOLD as usize);
+
+    let seed = label("seed");
+    let solana_leaves = vec![solana_leaf(false), solana_leaf(true)];
+    let leaves: Vec<Hash> = solana_leaves
+        .iter()
+        .map(|leaf| Hash(leaf.leaf.clone().try_into().unwrap()))
+        .chain([label("evm-leaf-0"), label("evm-leaf-1")])
+        .collect();
+    let proofs: Vec<Vec<Hash>> = (0..leaves.len()).map(|i| merkle_proof(&leaves, i)).collect();
+    let merkle_root = process_proof(&leaves[0], &proofs[0]);
+
+    let signed = |digest: Hash| SignedDigestVector {
+        digest: digest.0.to_vec(),
+        signatures: sign_all(&keys, &digest),
+    };
+    let delegate = label("delegate");
+    let authorization_digest =
+        signer_execution_authorization_digest(&leaves[0], &merkle_root, &delegate.0, EXPIRY);
+
+    TestVectors {
+        threshold: THRESHOLD,
+        seed: seed.0.to_vec(),
+        expiry: EXPIRY,
+        solana_leaves,
+        leaves: leaves.iter().map(|leaf| leaf.0.to_vec()).collect(),
+        proofs: proofs
+            .iter()
+            .map(|proof| ProofVector { proof: proof.iter().map(|h| h.0.to_vec()).collect() })
+            .collect(),
+        merkle_root: merkle_root.0.to_vec(),
+        merkle_root_signatures: signed(merkle_root_digest(&seed, &merkle_root, EXPIRY, 0)),
+        limited_merkle_root_signatures: signed(merkle_root_digest(
+            &seed,
+            &merkle_root,
+            EXPIRY,
+            MAX_EXECUTIONS,
+        )),
+        signer_execution_authorization: SignerExecutionAuthorizationVector {
+            leaf_index: 0,
+            delegate: delegate.0.to_vec(),
+            expiry: EXPIRY,
+            digest: authorization_digest.0.to_vec(),
+            signature: keys[0].sign(&authorization_digest).to_vec(),
+        },
+        signers: keys
+            .iter()
+            .map(|key| SignerVector {
+                private_key: key.private_key(),
+                public_key: key.public_key.clone(),
+                address: key.address.0.to_vec(),
+            })
+            .collect(),
+    }
+}
+
+// A Solana leaf with two accounts, either plain or with every leaf extension up to the cluster
+// genesis hash set
+fn solana_leaf(extended: bool) -> SolanaLeafVector {
+    let one_sig_state = label("one-sig-state");
+    let program_id = label("program");
+    let accounts = [(label("account-0"), true, true), (label("account-1"), false, true)];
+    let data = vec![1, 2, 3, 4];
+    let value = 1_000_000;
+    let (limits, guarded_accounts, vault_index, genesis_hash) = if extended {
+        (vec![(label("mint"), 500)], vec![label("guarded")], 1, Some(label("genesis")))
+    } else {
+        (vec![], vec![], 0, None)
+    };
+
+    let encoded_instruction = encode_instruction(
+        &program_id.0,
+        accounts.iter().map(|(pubkey, is_signer, is_writable)| LeafAccountMeta {
+            pubkey: &pubkey.0,
+            is_signer: *is_signer,
+            is_writable: *is_writable,
+        }),
+        &data,
+        value,
+    );
+    let leaf = encode_leaf(
+        &one_sig_state.0,
+        1,
+        7,
+        &encoded_instruction,
+        limits.iter().map(|(mint, max_amount)| LeafTokenOutflowLimit {
+            mint: &mint.0,
+            max_amount: *max_amount,
+        }),
+        guarded_accounts.iter().map(|account| &account.0),
+        vault_index,
+        genesis_hash.as_ref(),
+        None,
+        None,
+        None,
+    );
+
+    SolanaLeafVector {
+        one_sig_state: one_sig_state.0.to_vec(),
+        one_sig_id: 1,
+        nonce: 7,
+        program_id: program_id.0.to_vec(),
+        accounts: accounts
+            .iter()
+            .map(|(pubkey, is_signer, is_writable)| AccountMetaVector {
+                pubkey: pubkey.0.to_vec(),
+                is_signer: *is_signer,
+                is_writable: *is_writable,
+            })
+            .collect(),
+        data,
+        value,
+        token_outflow_limits: limits
+            .iter()
+            .map(|(mint, max_amount)| TokenOutflowLimitVector {
+                mint: mint.0.to_vec(),
+                max_amount: *max_amount,
+            })
+            .collect(),
+        guarded_accounts: guarded_accounts.iter().map(|account| account.0.to_vec()).collect(),
+        vault_index,
+        cluster_genesis_hash: genesis_hash.map_or(vec![], |hash| hash.0.to_vec()),
+        encoded_instruction,
+        leaf: leaf.0.to_vec(),
+    }
+}
+
+// Proof of `leaves[index]` in a tree hashing each pair in sorted order, an odd node being carried
+// up as is; the tree `process_proof` folds
+fn merkle_proof(leaves: &[Hash], mut index: usize) -> Vec<Hash> {
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => process_proof(a, &[*b]),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        index /= 2;
+    }
+    proof
+}
+
+// Deterministic 32-byte value of a label
+fn label(name: &str) -> Hash {
+    keccak::hash(format!("onesig-test-vectors:{name}").as_bytes()).into()
+}
+
+// 0x-prefixed hex, as read by ethers
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex::decode(hex.trim_start_matches("0x")).map_err(D::Error::custom)
+    }
+}
+
+mod hex_bytes_list {
+    use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(list: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(list.len()))?;
+        for bytes in list {
+            seq.serialize_element(&format!("0x{}", hex::encode(bytes)))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex| hex::decode(hex.trim_start_matches("0x")).map_err(D::Error::custom))
+            .collect()
+    }
+}
@@ -0,0 +1,40 @@
+// Tests that the committed vectors are up to date and verify with the shared encodings.
+#[cfg(test)]
+mod tests {
+    use onesig_core::{
+        digest::merkle_root_digest, leaf::verify_proof, signature::recover_address, Address, Hash,
+        Signature, SIGNATURE_BYTES_LEN,
+    };
+
+    use crate::{generate, load, EXPIRY};
+
+    fn hash(bytes: &[u8]) -> Hash {
+        Hash(bytes.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_committed_vectors_are_up_to_date() {
+        assert!(
+            load() == generate(),
+            "vectors.json is stale, regenerate it with `cargo run -p onesig-test-vectors`"
+        );
+    }
+
+    #[test]
+    fn test_vectors_verify() {
+        let vectors = load();
+        let merkle_root = hash(&vectors.merkle_root);
+        for (leaf, proof) in vectors.leaves.iter().zip(&vectors.proofs) {
+            let proof: Vec<Hash> = proof.proof.iter().map(|p| hash(p)).collect();
+            assert!(verify_proof(&merkle_root, &proof, &hash(leaf)));
+        }
+
+        let digest = merkle_root_digest(&hash(&vectors.seed), &merkle_root, EXPIRY, 0);
+        assert_eq!(digest.0.to_vec(), vectors.merkle_root_signatures.digest);
+        let signatures = vectors.merkle_root_signatures.signatures.chunks(SIGNATURE_BYTES_LEN);
+        for (signature, signer) in signatures.zip(&vectors.signers) {
+            let recovered = recover_address(&digest, &Signature(signature.try_into().unwrap()));
+            assert_eq!(recovered, Some(Address(signer.address.clone().try_into().unwrap())));
+        }
+    }
+}
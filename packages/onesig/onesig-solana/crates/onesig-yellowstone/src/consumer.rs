@@ -0,0 +1,87 @@
+use std::pin::Pin;
+
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use solana_pubkey::Pubkey;
+use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest, SubscribeRequestPing,
+    SubscribeUpdate,
+};
+
+use crate::decode::{decode_update, subscribe_request, StreamError, StreamUpdate};
+
+/// A Yellowstone gRPC endpoint, e.g. of an RPC provider
+#[derive(Clone, Debug)]
+pub struct Endpoint {
+    pub url: String,
+    /// Authentication token, sent as the `x-token` header
+    pub x_token: Option<String>,
+    pub commitment: CommitmentLevel,
+}
+
+/// A subscription to the OneSig program at a Yellowstone gRPC endpoint, yielding its decoded
+/// updates as they stream.
+pub struct Consumer {
+    program_id: Pubkey,
+    requests: Pin<Box<dyn Sink<SubscribeRequest, Error = StreamError> + Send>>,
+    updates: Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, StreamError>> + Send>>,
+}
+
+impl Consumer {
+    /// Connects to `endpoint` and subscribes to the program at `program_id`.
+    pub async fn connect(endpoint: &Endpoint, program_id: Pubkey) -> Result<Self, StreamError> {
+        let mut client = GeyserGrpcClient::build_from_shared(endpoint.url.clone())
+            .map_err(connect_error)?
+            .x_token(endpoint.x_token.clone())
+            .map_err(connect_error)?
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(connect_error)?
+            .connect()
+            .await
+            .map_err(connect_error)?;
+        let request = subscribe_request(&program_id, endpoint.commitment);
+        let (requests, updates) =
+            client.subscribe_with_request(Some(request)).await.map_err(connect_error)?;
+        Ok(Self {
+            program_id,
+            requests: Box::pin(
+                requests.sink_map_err(|error| StreamError::Closed(error.to_string())),
+            ),
+            updates: Box::pin(
+                updates
+                    .map(|update| update.map_err(|status| StreamError::Grpc(status.to_string()))),
+            ),
+        })
+    }
+
+    /// The next update of the program, `None` once the endpoint closed the stream. The stream is
+    /// not resumed after an error: consumers reconnect, and catch up on what they missed from the
+    /// accounts' current state and the transaction history, e.g. with `onesig_sdk::History`.
+    pub async fn next(&mut self) -> Option<Result<StreamUpdate, StreamError>> {
+        loop {
+            let update = match self.updates.next().await? {
+                Ok(SubscribeUpdate { update_oneof: Some(update), .. }) => update,
+                Ok(_) => continue,
+                Err(error) => return Some(Err(error)),
+            };
+            if let UpdateOneof::Ping(_) = update {
+                // Answered so that load balancers keep the idle stream open
+                let ping = SubscribeRequest {
+                    ping: Some(SubscribeRequestPing { id: 1 }),
+                    ..Default::default()
+                };
+                if let Err(error) = self.requests.send(ping).await {
+                    return Some(Err(error));
+                }
+                continue;
+            }
+            if let Some(update) = decode_update(&self.program_id, update).transpose() {
+                return Some(update);
+            }
+        }
+    }
+}
+
+fn connect_error(error: impl std::fmt::Display) -> StreamError {
+    StreamError::Connect(error.to_string())
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use onesig_sdk::{events_from_inner_instructions, AccountUpdate, InnerInstruction, OneSigEvent};
+use solana_pubkey::Pubkey;
+use thiserror::Error;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions, SubscribeUpdateAccount,
+    SubscribeUpdateTransaction,
+};
+
+/// Name of the filters of the subscription, which the updates they match are tagged with
+pub const FILTER: &str = "onesig";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StreamError {
+    #[error("cannot connect to the gRPC endpoint: {0}")]
+    Connect(String),
+    #[error("the gRPC stream failed: {0}")]
+    Grpc(String),
+    #[error("the gRPC stream is closed: {0}")]
+    Closed(String),
+    #[error("{0} bytes is not an address")]
+    InvalidPubkey(usize),
+    #[error("inner instruction of program index {0}, past the account keys of a transaction")]
+    InvalidProgramIndex(u32),
+    #[error(transparent)]
+    Sdk(#[from] onesig_sdk::SdkError),
+}
+
+/// An update of the OneSig program the stream carries
+pub enum StreamUpdate {
+    /// A write to the account at `address`
+    Account { slot: u64, address: Pubkey, update: AccountUpdate },
+    /// The events a transaction calling the program emitted, in order
+    Events { slot: u64, signature: String, events: Vec<OneSigEvent> },
+}
+
+/// Subscribes to the accounts the program at `program_id` owns and to the transactions calling it
+/// that succeeded, at `commitment`. Accounts the program closes are handed back to the system
+/// program, so their closing is not carried; the events of the transactions closing them are.
+pub fn subscribe_request(program_id: &Pubkey, commitment: CommitmentLevel) -> SubscribeRequest {
+    let accounts = SubscribeRequestFilterAccounts {
+        owner: vec![program_id.to_string()],
+        ..Default::default()
+    };
+    let transactions = SubscribeRequestFilterTransactions {
+        vote: Some(false),
+        failed: Some(false),
+        account_include: vec![program_id.to_string()],
+        ..Default::default()
+    };
+    SubscribeRequest {
+        accounts: HashMap::from([(FILTER.to_owned(), accounts)]),
+        transactions: HashMap::from([(FILTER.to_owned(), transactions)]),
+        commitment: Some(commitment as i32),
+        ..Default::default()
+    }
+}
+
+/// Decodes an update of the stream, `None` for the ones not of the program at `program_id`, e.g.
+/// pings, and for transactions emitting no event.
+pub fn decode_update(
+    program_id: &Pubkey,
+    update: UpdateOneof,
+) -> Result<Option<StreamUpdate>, StreamError> {
+    match update {
+        UpdateOneof::Account(update) => decode_account(program_id, update),
+        UpdateOneof::Transaction(update) => decode_transaction(program_id, update),
+        _ => Ok(None),
+    }
+}
+
+fn decode_account(
+    program_id: &Pubkey,
+    update: SubscribeUpdateAccount,
+) -> Result<Option<StreamUpdate>, StreamError> {
+    let SubscribeUpdateAccount { account: Some(account), slot, .. } = update else {
+        return Ok(None);
+    };
+    let address = pubkey(&account.pubkey)?;
+    let owner = pubkey(&account.owner)?;
+    let Some(update) =
+        AccountUpdate::from_account(program_id, address, &owner, account.lamports, &account.data)
+    else {
+        return Ok(None);
+    };
+    Ok(Some(StreamUpdate::Account { slot, address, update: update? }))
+}
+
+fn decode_transaction(
+    program_id: &Pubkey,
+    update: SubscribeUpdateTransaction,
+) -> Result<Option<StreamUpdate>, StreamError> {
+    let SubscribeUpdateTransaction { transaction: Some(info), slot, .. } = update else {
+        return Ok(None);
+    };
+    let (Some(transaction), Some(meta)) = (info.transaction, info.meta) else {
+        return Ok(None);
+    };
+    // A failed transaction emitted nothing, its events rolled back with it
+    if meta.err.is_some() {
+        return Ok(None);
+    }
+    // Inner instructions index the static keys, then the ones loaded from lookup tables
+    let static_keys = transaction.message.map(|message| message.account_keys).unwrap_or_default();
+    let account_keys = static_keys
+        .iter()
+        .chain(&meta.loaded_writable_addresses)
+        .chain(&meta.loaded_readonly_addresses)
+        .map(|key| pubkey(key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let inner_instructions = meta
+        .inner_instructions
+        .iter()
+        .flat_map(|inner| &inner.instructions)
+        .map(|instruction| {
+            let program_id_index = u8::try_from(instruction.program_id_index)
+                .map_err(|_| StreamError::InvalidProgramIndex(instruction.program_id_index))?;
+            Ok(InnerInstruction {
+                program_id_index,
+                accounts: &instruction.accounts,
+                data: &instruction.data,
+            })
+        })
+        .collect::<Result<Vec<_>, StreamError>>()?;
+    let events = events_from_inner_instructions(
+        program_id,
+        &account_keys,
+        inner_instructions,
+        &meta.log_messages,
+    )?;
+    if events.is_empty() {
+        return Ok(None);
+    }
+    let signature = bs58::encode(&info.signature).into_string();
+    Ok(Some(StreamUpdate::Events { slot, signature, events }))
+}
+
+fn pubkey(bytes: &[u8]) -> Result<Pubkey, StreamError> {
+    Pubkey::try_from(bytes).map_err(|_| StreamError::InvalidPubkey(bytes.len()))
+}
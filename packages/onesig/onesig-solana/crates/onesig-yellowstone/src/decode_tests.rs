@@ -0,0 +1,155 @@
+// Tests for the subscription and the decoding of the updates Yellowstone streams.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{event::EVENT_IX_TAG_LE, AccountSerialize, Event};
+    use onesig::{
+        ConfigSet, MerkleRootState, PreviousConfig, SetConfigParams, EVENT_VERSION,
+        MERKLE_ROOT_STATE_VERSION,
+    };
+    use onesig_core::Hash;
+    use onesig_sdk::{AccountUpdate, OneSigEvent, EVENT_AUTHORITY_SEED};
+    use solana_pubkey::Pubkey;
+    use yellowstone_grpc_proto::prelude::{
+        subscribe_update::UpdateOneof, CommitmentLevel, InnerInstruction, InnerInstructions,
+        Message, SubscribeUpdateAccount, SubscribeUpdateAccountInfo, SubscribeUpdatePing,
+        SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo, Transaction, TransactionError,
+        TransactionStatusMeta,
+    };
+
+    use crate::decode::{decode_update, subscribe_request, StreamError, StreamUpdate, FILTER};
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0e; 32]);
+
+    fn account(owner: &Pubkey, lamports: u64, data: Vec<u8>) -> UpdateOneof {
+        UpdateOneof::Account(SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: vec![7; 32],
+                lamports,
+                owner: owner.to_bytes().to_vec(),
+                data,
+                ..Default::default()
+            }),
+            slot: 5,
+            ..Default::default()
+        })
+    }
+
+    fn root_data() -> Vec<u8> {
+        let root = MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            seed_commitment: [2; 16],
+            expiry: 100,
+            rent_payer: Pubkey::new_unique(),
+            signed_by: vec![],
+            bump: 255,
+            version: MERKLE_ROOT_STATE_VERSION,
+            executions: 0,
+            remaining_executions: Some(3),
+        };
+        let mut data = vec![];
+        root.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    /// A transaction calling the program, whose first inner instruction is an event CPI with
+    /// `data`, the program and its event authority loaded from a lookup table
+    fn transaction(data: Vec<u8>, err: Option<TransactionError>) -> UpdateOneof {
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID).0;
+        let meta = TransactionStatusMeta {
+            err,
+            inner_instructions: vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    program_id_index: 1,
+                    accounts: vec![2],
+                    data,
+                    stack_height: Some(2),
+                }],
+            }],
+            loaded_readonly_addresses: vec![event_authority.to_bytes().to_vec()],
+            ..Default::default()
+        };
+        let message = Message {
+            account_keys: vec![vec![9; 32], PROGRAM_ID.to_bytes().to_vec()],
+            ..Default::default()
+        };
+        UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: vec![1; 64],
+                transaction: Some(Transaction { message: Some(message), ..Default::default() }),
+                meta: Some(meta),
+                ..Default::default()
+            }),
+            slot: 6,
+        })
+    }
+
+    fn config_set() -> Vec<u8> {
+        let event = ConfigSet {
+            one_sig_account: Pubkey::new_unique(),
+            params: SetConfigParams::SetThreshold(2),
+            previous: PreviousConfig::Threshold(1),
+            event_version: EVENT_VERSION,
+        };
+        [EVENT_IX_TAG_LE, &event.data()].concat()
+    }
+
+    #[test]
+    fn test_subscribe_request() {
+        let request = subscribe_request(&PROGRAM_ID, CommitmentLevel::Confirmed);
+        assert_eq!(request.accounts[FILTER].owner, [PROGRAM_ID.to_string()]);
+        let transactions = &request.transactions[FILTER];
+        assert_eq!(transactions.account_include, [PROGRAM_ID.to_string()]);
+        assert_eq!((transactions.vote, transactions.failed), (Some(false), Some(false)));
+        assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+    }
+
+    #[test]
+    fn test_decode_account() {
+        let Ok(Some(StreamUpdate::Account { slot, address, update })) =
+            decode_update(&PROGRAM_ID, account(&PROGRAM_ID, 1, root_data()))
+        else {
+            panic!("expected an account update");
+        };
+        assert_eq!((slot, address), (5, Pubkey::new_from_array([7; 32])));
+        let AccountUpdate::MerkleRoot(root) = update else {
+            panic!("expected a MerkleRootState");
+        };
+        assert_eq!(root.remaining_executions, Some(3));
+
+        // Accounts of other programs
+        let update = decode_update(&PROGRAM_ID, account(&Pubkey::new_unique(), 1, root_data()));
+        assert!(matches!(update, Ok(None)));
+
+        let UpdateOneof::Account(mut update) = account(&PROGRAM_ID, 1, root_data()) else {
+            unreachable!()
+        };
+        update.account.as_mut().unwrap().pubkey = vec![7; 31];
+        assert!(matches!(
+            decode_update(&PROGRAM_ID, UpdateOneof::Account(update)),
+            Err(StreamError::InvalidPubkey(31))
+        ));
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let Ok(Some(StreamUpdate::Events { slot, signature, events })) =
+            decode_update(&PROGRAM_ID, transaction(config_set(), None))
+        else {
+            panic!("expected the events of a transaction");
+        };
+        assert_eq!((slot, signature), (6, bs58::encode([1; 64]).into_string()));
+        assert!(matches!(
+            &events[..],
+            [OneSigEvent::ConfigSet(ConfigSet { params: SetConfigParams::SetThreshold(2), .. })]
+        ));
+
+        // Failed transactions, transactions emitting no event, and updates of no account or
+        // transaction
+        let failed = transaction(config_set(), Some(TransactionError { err: vec![1] }));
+        assert!(matches!(decode_update(&PROGRAM_ID, failed), Ok(None)));
+        assert!(matches!(decode_update(&PROGRAM_ID, transaction(vec![1, 2, 3], None)), Ok(None)));
+        let ping = UpdateOneof::Ping(SubscribeUpdatePing {});
+        assert!(matches!(decode_update(&PROGRAM_ID, ping), Ok(None)));
+    }
+}
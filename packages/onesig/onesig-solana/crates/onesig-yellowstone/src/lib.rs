@@ -0,0 +1,16 @@
+//! A Yellowstone gRPC consumer of the OneSig program, for indexers and monitors that follow it
+//! from a Geyser stream rather than by polling RPC nodes: a [`Consumer`] subscribes to the
+//! accounts the program owns and the transactions calling it, and yields each write to an account
+//! as an `AccountUpdate` and each transaction as the events it emitted, decoded by `onesig-sdk`.
+//!
+//! The subscription is built by [`subscribe_request`] and the messages decoded by
+//! [`decode_update`], for consumers managing the gRPC connection themselves.
+
+pub mod consumer;
+pub mod decode;
+
+#[cfg(test)]
+mod decode_tests;
+
+pub use consumer::*;
+pub use decode::*;
@@ -0,0 +1,21 @@
+//! Arbitrary instruction data decoded as the `ExecuteTransactionParams` of `execute_transaction`,
+//! the way Anchor decodes it: from the front of the data, ignoring what follows.
+//!
+//! `cargo +nightly fuzz run execute_transaction_params` from `programs/onesig`.
+
+#![no_main]
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use libfuzzer_sys::fuzz_target;
+use onesig::{ExecuteTransactionParams, MAX_PROOF_DEPTH};
+
+fuzz_target!(|data: &[u8]| {
+    let mut rest = data;
+    if let Ok(params) = ExecuteTransactionParams::deserialize(&mut rest) {
+        // Borsh is canonical: what was read serializes back to the same bytes
+        let mut bytes = Vec::new();
+        params.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes, &data[..data.len() - rest.len()]);
+        assert!(params.transaction.proof.len() <= MAX_PROOF_DEPTH);
+    }
+});
@@ -0,0 +1,20 @@
+//! Arbitrary instruction data decoded as the `SetConfigParams` of `set_config`, the way Anchor
+//! decodes it: from the front of the data, ignoring what follows.
+//!
+//! `cargo +nightly fuzz run set_config_params` from `programs/onesig`.
+
+#![no_main]
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use libfuzzer_sys::fuzz_target;
+use onesig::SetConfigParams;
+
+fuzz_target!(|data: &[u8]| {
+    let mut rest = data;
+    if let Ok(params) = SetConfigParams::deserialize(&mut rest) {
+        // Borsh is canonical: what was read serializes back to the same bytes
+        let mut bytes = Vec::new();
+        params.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes, &data[..data.len() - rest.len()]);
+    }
+});
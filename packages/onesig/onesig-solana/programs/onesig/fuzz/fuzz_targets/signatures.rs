@@ -0,0 +1,33 @@
+//! Arbitrary signature blobs checked by `SignatureValidator::verify_signatures`, which chunks them
+//! into r‖s‖v signatures and recovers their signers.
+//!
+//! The input is a threshold byte, a signer count byte, that many 20-byte addresses, a 32-byte
+//! digest, then the signatures. `cargo +nightly fuzz run signatures` from `programs/onesig`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use onesig::validation::signature::SignatureValidator;
+use onesig_core::{Address, Hash, ADDRESS_LEN, HASH_BYTES, SIGNATURE_BYTES_LEN};
+
+fuzz_target!(|data: &[u8]| {
+    let [threshold, signers_len, data @ ..] = data else { return };
+    let Some((signers, data)) = data.split_at_checked(*signers_len as usize * ADDRESS_LEN) else {
+        return;
+    };
+    let Some((digest, signatures)) = data.split_at_checked(HASH_BYTES) else { return };
+    let signers: Vec<Address> = signers
+        .chunks_exact(ADDRESS_LEN)
+        .map(|signer| Address(signer.try_into().unwrap()))
+        .collect();
+    let digest = Hash(digest.try_into().unwrap());
+
+    if let Ok(recovered) =
+        SignatureValidator::verify_signatures(*threshold, &signers, &digest, signatures)
+    {
+        // Exactly the threshold of distinct registered signers, one per leading signature
+        assert_eq!(recovered.len(), *threshold as usize);
+        assert!(recovered.len() * SIGNATURE_BYTES_LEN <= signatures.len());
+        assert!(recovered.iter().all(|signer| signers.contains(signer)));
+    }
+});
@@ -0,0 +1,20 @@
+//! Arbitrary instruction data decoded as the `VerifyMerkleRootParams` of `verify_merkle_root`, the
+//! way Anchor decodes it: from the front of the data, ignoring what follows.
+//!
+//! `cargo +nightly fuzz run verify_merkle_root_params` from `programs/onesig`.
+
+#![no_main]
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use libfuzzer_sys::fuzz_target;
+use onesig::VerifyMerkleRootParams;
+
+fuzz_target!(|data: &[u8]| {
+    let mut rest = data;
+    if let Ok(params) = VerifyMerkleRootParams::deserialize(&mut rest) {
+        // Borsh is canonical: what was read serializes back to the same bytes
+        let mut bytes = Vec::new();
+        params.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes, &data[..data.len() - rest.len()]);
+    }
+});
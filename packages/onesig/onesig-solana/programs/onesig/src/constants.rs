@@ -1,54 +1,115 @@
-use hex_literal::hex;
-use solana_keccak_hasher::HASH_BYTES;
+use anchor_lang::prelude::{pubkey, Pubkey};
+pub use onesig_core::constants::{
+    DOMAIN_SEPARATOR, EIP191_PREFIX_FOR_EIP712, HASH_BYTES, MERKLE_LEAF_ENCODING_VERSION,
+    SIGNATURE_BYTES_LEN, SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH,
+    SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH, SIGN_MERKLE_ROOT_TYPE_HASH,
+};
+
+use crate::types::Hash;
 
 /// Current program version
 pub const VERSION: &str = "0.0.1";
 
+/// Layout version of the `OneSigState` accounts written by this program. The accounts of the first
+/// release, which predate it, are converted in place by `migrate_state`.
+pub const ONE_SIG_STATE_VERSION: u8 = 4;
+
+/// Layout version of the `MerkleRootState` accounts written by this program. The accounts of the
+/// first release, which predate it, are converted in place by `migrate_state`.
+pub const MERKLE_ROOT_STATE_VERSION: u8 = 4;
+
+/// Version of the layouts of the events emitted by this program, which each carry it as their
+/// `event_version`. Bumped whenever a field is added to an event, after the fields of the version
+/// before; events emitted before events were versioned lack it, and decode as version 0.
+pub const EVENT_VERSION: u8 = 3;
+
+/// SPL Token program, whose token accounts are covered by leaf token outflow limits
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// SPL Token-2022 program, whose token accounts are covered by leaf token outflow limits
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Associated Token Account program, which `create_pda_ata` creates token accounts through
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
 /// PDA seed for OneSig account derivation
 pub const ONE_SIG_SEED: &[u8] = b"OneSig";
 
 /// PDA seed for Merkle tree account derivation
 pub const MERKLE_ROOT_SEED: &[u8] = b"MerkleRoot";
 
-/// EIP-191 prefix for EIP-712 style digests
-pub const EIP191_PREFIX_FOR_EIP712: [u8; 2] = [0x19, 0x01];
+/// PDA seed for the spending limits of a OneSig account
+pub const SPENDING_LIMITS_SEED: &[u8] = b"SpendingLimits";
+
+/// PDA seed for the programs listed by the target policy of a OneSig account
+pub const TARGET_PROGRAMS_SEED: &[u8] = b"TargetPrograms";
 
-/// keccak256("SignerExecutionAuthorization(bytes32 leafHash,bytes32 merkleRoot,bytes
-/// delegate,uint256 expiry)")
-pub const SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH: [u8; HASH_BYTES] =
-    hex!("2ed09255a17ecc5d8fd5128a11480e0f01d23b22eaeb02f4f4acae355e5c428a");
+/// PDA seed for the registry of verified Merkle roots
+pub const MERKLE_ROOT_REGISTRY_SEED: &[u8] = b"MerkleRootRegistry";
 
-/// Maximum number of signers (20) for a OneSig account.
-/// Solana limits account size to 10KB, which could theoretically support up to 159 signers,
-/// but we limit to 20 for 2 reasons:
-/// 1. Aligns with practical governance needs for most DAOs and multisig wallets
-/// 2. The threshold is limited to 13 by Solana's transaction size constraints when including
-///    multiple signatures, so additional signers beyond 20 provide minimal security benefits
-pub const SIGNERS_MAX_LEN: usize = 20;
+/// PDA seed for the escrow the bounties of executed leaves are paid from, a system account the
+/// program signs for
+pub const BOUNTY_ESCROW_SEED: &[u8] = b"BountyEscrow";
 
-/// Solana account size limit is 10KB, so we limit the number of executors to 277
-/// to avoid hitting the limit
-pub const EXECUTORS_MAX_LEN: usize = 277;
+/// PDA seed for the marker `record_leaf_failure` leaves for a leaf whose call fails
+pub const LEAF_FAILURE_SEED: &[u8] = b"LeafFailure";
+
+/// PDA seed for executor account derivation
+pub const EXECUTOR_SEED: &[u8] = b"Executor";
+
+/// PDA seed for module account derivation
+pub const MODULE_SEED: &[u8] = b"Module";
+
+/// PDA seed, under a module program, of the authority it signs `execute_module_transaction` with
+pub const MODULE_AUTHORITY_SEED: &[u8] = b"OneSigModule";
+
+/// Maximum signer capacity of a OneSig account.
+/// The state account is zero-copy and sized per instance by `signers_capacity`, so the signer
+/// list is no longer bounded by heap/stack limits of deserialization. Note that the threshold is
+/// still limited to 13 by Solana's transaction size constraints when including multiple
+/// signatures.
+pub const SIGNERS_MAX_LEN: usize = 64;
+
+/// Maximum number of programs a target policy, or an executor, can list
+pub const TARGET_PROGRAMS_MAX_LEN: usize = 16;
 
 /// The maximum number of threshold is 13 for the Solana transaction size limit
 pub const MAX_THRESHOLD: u8 = 13;
 
-/// Size of raw signature (64 bytes + 1 recovery byte)
-pub const SIGNATURE_BYTES_LEN: usize = 65;
-
-/// The version of the Merkle tree leaf encoding
-pub const MERKLE_LEAF_ENCODING_VERSION: [u8; 1] = [1];
-
-/// keccak::hash(b"SignMerkleRoot(bytes32 seed,bytes32 merkleRoot,uint256 expiry)").as_ref()
-pub const SIGN_MERKLE_ROOT_TYPE_HASH: [u8; HASH_BYTES] =
-    hex!("642ed5d2b77bc7ccb98e10da4c02d7cd8231228da4222a9f88a80c15545074ed");
-
-/// Pre-calculated domain separator for EIP-712 signatures, hashed by following data:
-/// - EIP-191 prefix for EIP-712 style digests
-/// - EIP-712 domain separator type-hash
-/// - Contract name: "OneSig"
-/// - Contract version: "0.0.1"
-/// - Chain ID: 1 (Ethereum Mainnet)
-/// - Verifying contract address: 0xdEaD
-pub const DOMAIN_SEPARATOR: [u8; HASH_BYTES] =
-    hex!("94c28989170eb4dc31359174b9115c116a8fafa67b5adacc570ca583eb96d657");
+/// Maximum number of hashes in the merkle proof of a leaf, enough for roots of 2^32 leaves. Longer
+/// proofs are rejected before they are read, see `OneSigTransaction`; one deeper than 24 does not
+/// fit a transaction anyway.
+pub const MAX_PROOF_DEPTH: usize = 32;
+
+/// Genesis hash of the cluster this build is deployed to, selected by the `mainnet`, `testnet` or
+/// `devnet` feature. Bound into every leaf, so the same deployment on another cluster cannot
+/// replay them; `localnet` builds, and builds without a cluster feature, leave leaves unbound.
+#[cfg(feature = "mainnet")]
+pub const CLUSTER_GENESIS_HASH: Option<Hash> =
+    Some(Hash(pubkey!("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d").to_bytes()));
+#[cfg(feature = "testnet")]
+pub const CLUSTER_GENESIS_HASH: Option<Hash> =
+    Some(Hash(pubkey!("4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY").to_bytes()));
+#[cfg(feature = "devnet")]
+pub const CLUSTER_GENESIS_HASH: Option<Hash> =
+    Some(Hash(pubkey!("EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG").to_bytes()));
+#[cfg(not(any(feature = "mainnet", feature = "testnet", feature = "devnet")))]
+pub const CLUSTER_GENESIS_HASH: Option<Hash> = None;
+
+/// CAIP-2 namespace of Solana chains, whose reference is the base58 genesis hash truncated to
+/// `CAIP2_REFERENCE_LEN` characters, e.g. `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp` for mainnet
+pub const CAIP2_NAMESPACE: &str = "solana";
+pub const CAIP2_REFERENCE_LEN: usize = 32;
+
+#[cfg(any(
+    all(feature = "mainnet", feature = "testnet"),
+    all(feature = "mainnet", feature = "devnet"),
+    all(feature = "mainnet", feature = "localnet"),
+    all(feature = "testnet", feature = "devnet"),
+    all(feature = "testnet", feature = "localnet"),
+    all(feature = "devnet", feature = "localnet"),
+))]
+compile_error!(
+    "at most one of the `mainnet`, `testnet`, `devnet` and `localnet` features can be enabled"
+);
@@ -31,4 +31,46 @@ pub enum OneSigError {
     SignerExecutionProofUnauthorized,
     MissingProgramId,
     NonceMutatedDuringExecution,
+    InvalidStateSize,
+    TooManyMerkleRoots,
+    MerkleRootsRemaining,
+    ExecutorsRemaining,
+    NonZeroSignerBalance,
+    StateVersionMismatch,
+    InvalidMigrationAccount,
+    MerkleRootExhausted,
+    ExecutionLimitNotTracked,
+    ExpiryNotExtended,
+    ExcessiveTokenOutflow,
+    SpendingLimitExceeded,
+    InvalidSpendingLimit,
+    SpendingLimitNotFound,
+    MissingTargetPrograms,
+    TooManyTargetPrograms,
+    TargetProgramNotAllowed,
+    ExecutorTargetNotAllowed,
+    InvalidVaultSigner,
+    InvalidTokenProgram,
+    MissingSpendingLimits,
+    InvalidModule,
+    ModulesRemaining,
+    ModuleCannotCallOneSig,
+    NonceOverflow,
+    NonceNamespaceUnchanged,
+    ProofTooDeep,
+    MissingPayer,
+    InvalidTransactionGroup,
+    MissingInstructionsSysvar,
+    IncompleteTransactionGroup,
+    ExecutionTooEarly,
+    MissingBountyEscrow,
+    InvalidBountyEscrow,
+    InsufficientBountyEscrow,
+    InvalidExecutionRateLimit,
+    ExecutionRateLimited,
+    ExecutorConfigMoved,
+    InvalidMerkleRootsLen,
+    VaultSignersMissing,
+    LeafMismatch,
+    LeafFailureNotCloseable,
 }
@@ -1,6 +1,11 @@
+//! Events of the program. Each carries its `event_version`, the
+//! [`EVENT_VERSION`](crate::EVENT_VERSION) it was emitted at, after the fields of its first
+//! version: fields are only ever added after it, so decoders keep reading the fields they know of
+//! events emitted by later versions.
+
 use anchor_lang::prelude::*;
 
-use crate::types::{Address, Hash, SetConfigParams};
+use crate::types::{Address, Hash, PreviousConfig, SetConfigParams};
 
 #[event]
 pub struct OneSigInitialized {
@@ -11,12 +16,188 @@ pub struct OneSigInitialized {
     pub signers: Vec<Address>,
     pub executors: Vec<Pubkey>,
     pub executor_required: bool,
+    /// CAIP-2 identifier of the chain leaves are bound to, if any
+    pub chain_id: Option<String>,
+    pub event_version: u8,
 }
 
 #[event]
 pub struct ConfigSet {
     pub one_sig_account: Pubkey,
     pub params: SetConfigParams,
+    /// What `params` replaced
+    pub previous: PreviousConfig,
+    pub event_version: u8,
+}
+
+/// `executor` can execute leaves from `effective_at`, the unix timestamp of the block it was
+/// registered in. Not emitted for the executors `OneSigInitialized` lists.
+#[event]
+pub struct ExecutorAdded {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub event_version: u8,
+    pub effective_at: i64,
+}
+
+/// `executor` can no longer execute leaves from `effective_at`, the unix timestamp of the block it
+/// was deregistered in
+#[event]
+pub struct ExecutorRemoved {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub event_version: u8,
+    pub effective_at: i64,
+}
+
+/// `SetExecutorRequired` changed whether leaves can only be executed by registered executors, from
+/// `effective_at`, the unix timestamp of the block it was executed in. Emitted after the
+/// `ConfigSet`, only when the setting changed.
+#[event]
+pub struct ExecutorRequiredSet {
+    pub one_sig_account: Pubkey,
+    pub executor_required: bool,
+    pub effective_at: i64,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct ModuleAdded {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct ModuleRemoved {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct ModuleTransactionExecuted {
+    pub one_sig_account: Pubkey,
+    pub module: Pubkey,
+    pub vault_index: u8,
+    pub chain_id: Option<String>,
+    pub one_sig_id: u64,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct ExecutorTargetsSet {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub programs: Vec<Pubkey>,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct SpendingLimitSet {
+    pub one_sig_account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub window: i64,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct SpendingLimitRemoved {
+    pub one_sig_account: Pubkey,
+    pub mint: Pubkey,
+    pub event_version: u8,
+}
+
+/// `lamports` were paid back for the execution of a leaf by `executor`: to the `fee_payer` that
+/// sponsored it, if any, or else to `executor`
+#[event]
+pub struct ExecutorReimbursed {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub lamports: u64,
+    pub event_version: u8,
+    pub fee_payer: Option<Pubkey>,
+}
+
+/// `lamports` were paid from the bounty escrow for the execution of the leaf at `nonce` by
+/// `executor`, the bounty the leaf committed to: to `recipient`, the fee payer that sponsored the
+/// execution, if any, or else `executor`
+#[event]
+pub struct BountyPaid {
+    pub one_sig_account: Pubkey,
+    pub executor: Pubkey,
+    pub recipient: Pubkey,
+    pub nonce: u64,
+    pub lamports: u64,
+    pub event_version: u8,
+}
+
+/// `lamports` were withdrawn from the bounty escrow to `recipient` by the multisig, or swept to it
+/// when the OneSig account was closed
+#[event]
+pub struct BountyEscrowWithdrawn {
+    pub one_sig_account: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    pub event_version: u8,
+}
+
+/// The leaf `leaf`, at `nonce`, passes every check of `execute_transaction`, but the instruction it
+/// calls failed when `executor` simulated it, with `reported_error_code` according to the executor
+/// (unverified). See `LeafFailure`.
+#[event]
+pub struct LeafFailureRecorded {
+    pub one_sig_account: Pubkey,
+    pub leaf: Hash,
+    pub nonce: u64,
+    pub reported_error_code: u32,
+    pub executor: Pubkey,
+    pub event_version: u8,
+}
+
+#[event]
+pub struct OneSigClosed {
+    pub one_sig_account: Pubkey,
+    pub recipient: Pubkey,
+    pub event_version: u8,
+}
+
+/// The nonce moved other than by the execution of the leaf at it, which `TransactionExecuted`
+/// reports: no leaf past `previous_nonce` under `previous_seed` will execute, the next one is
+/// `nonce` under `seed`. Emitted by `ResetNonce`, `previous_nonce` being the one of the leaf that
+/// made the reset.
+#[event]
+pub struct NonceSkipped {
+    pub one_sig_account: Pubkey,
+    pub previous_seed: Hash,
+    pub previous_nonce: u64,
+    pub seed: Hash,
+    pub nonce: u64,
+    pub event_version: u8,
+}
+
+/// Logged with `emit!` rather than emitted through an event CPI: `verify_merkle_root` carries up to
+/// `MAX_THRESHOLD` signatures, which leave no room in its transaction for the event authority.
+#[event]
+pub struct MerkleRootVerified {
+    pub one_sig_account: Pubkey,
+    pub merkle_root: Hash,
+    pub expiry: i64,
+    /// Paid the rent of the `MerkleRootState`, and is refunded it on close
+    pub rent_payer: Pubkey,
+    pub event_version: u8,
+}
+
+/// `merkle_root` was re-verified by `extend_merkle_root`, moving its expiry from `previous_expiry`
+/// to `expiry`. Logged with `emit!` like `MerkleRootVerified`, for the same reason.
+#[event]
+pub struct MerkleRootExtended {
+    pub one_sig_account: Pubkey,
+    pub merkle_root: Hash,
+    pub previous_expiry: i64,
+    pub expiry: i64,
+    pub event_version: u8,
 }
 
 #[event]
@@ -24,4 +205,23 @@ pub struct TransactionExecuted {
     pub one_sig_account: Pubkey,
     pub merkle_root: Hash,
     pub nonce: u64,
+    /// CAIP-2 identifier of the chain leaves are bound to, if any
+    pub chain_id: Option<String>,
+    /// Instance identifier bound into the leaves, the `ONE_SIG_ID` of EVM deployments
+    pub one_sig_id: u64,
+    /// Who landed the leaf: the `executor` of `execute_transaction`, or the `delegate` of
+    /// `signer_execute_transaction`
+    pub executor: Pubkey,
+    /// Program the leaf called
+    pub program_id: Pubkey,
+    /// keccak256 of the instruction data the leaf called it with
+    pub ix_data_hash: Hash,
+    /// Lamports the leaf declared it may spend
+    pub value: u64,
+    /// Lamports the vault's signer actually lost to the call, before any executor reimbursement
+    pub lamports_spent: u64,
+    /// Leaves executed under a pre-verified root so far, this one included; `None` for a root
+    /// verified inline, of which nothing is recorded
+    pub root_executions: Option<u32>,
+    pub event_version: u8,
 }
@@ -1,14 +1,27 @@
 use anchor_lang::{
-    prelude::*, solana_program::program::invoke_signed, system_program::ID as SYSTEM_PROGRAM_ID,
+    prelude::*,
+    solana_program::{
+        instruction::Instruction,
+        program::invoke_signed,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+    system_program::{self, Transfer, ID as SYSTEM_PROGRAM_ID},
     Discriminator,
 };
 
 use crate::{
-    constants::ONE_SIG_SEED,
+    constants::{
+        BOUNTY_ESCROW_SEED, MERKLE_ROOT_STATE_VERSION, ONE_SIG_SEED, TOKEN_2022_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+    },
     errors::OneSigError,
-    state::{MerkleRootState, OneSigState},
+    state::{
+        MerkleRootState, OneSigState, OneSigStateRef, OneSigStateRefMut, SpendingLimits,
+        TargetPrograms,
+    },
     types::{
-        Hash, OneSigAccountMeta, OneSigInstruction, OneSigTransaction, VerifyMerkleRootParams,
+        Hash, OneSigAccountMeta, OneSigInstruction, OneSigTransaction, SetConfigParams,
+        TargetPolicy, TransactionGroup, VerifyMerkleRootParams,
     },
     validation::merkle::MerkleValidator,
     ID,
@@ -18,42 +31,86 @@ use crate::{
 /// caller-supplied `VerifyMerkleRootParams`, or by reading a pre-verified `MerkleRootState`
 /// PDA. This mirrors the two-path behavior of `execute_transaction` and is shared by
 /// both `execute_transaction` and `signer_execute_transaction`.
+///
+/// A pre-verified root counts the execution, whose count it returns along with the root, and one
+/// signed with `max_executions` is charged one execution per call.
 pub fn resolve_merkle_root(
-    one_sig_state: &Account<OneSigState>,
-    merkle_root_state: Option<&Account<MerkleRootState>>,
+    one_sig_state: &OneSigStateRef,
+    merkle_root_state: Option<&mut Account<MerkleRootState>>,
     merkle_root_verification: Option<&VerifyMerkleRootParams>,
-) -> Result<Hash> {
-    let root = if let Some(VerifyMerkleRootParams { merkle_root, expiry, signatures }) =
+) -> Result<(Hash, Option<u32>)> {
+    // Nothing records how often a root is used when verified inline, so a root capped by
+    // `max_executions` must go through `verify_merkle_root`
+    if let Some(params) = merkle_root_verification {
+        require!(params.max_executions == 0, OneSigError::ExecutionLimitNotTracked);
+    }
+
+    let root = if let Some(VerifyMerkleRootParams { merkle_root, expiry, signatures, .. }) =
         merkle_root_verification
     {
         // Case 1: Direct verification with merkle root parameters
         MerkleValidator::verify_merkle_root(
-            one_sig_state,
+            &one_sig_state.seed,
+            &one_sig_state.multisig(),
             merkle_root,
             *expiry,
+            0,
             signatures.as_ref(),
             Clock::get()?.unix_timestamp,
         )?;
-        *merkle_root
+        (*merkle_root, None)
     } else {
         // Case 2: Two-step verification, using pre-verified merkle root state
         require!(merkle_root_state.is_some(), OneSigError::MissingMerkleRootState);
         let merkle_root_state = merkle_root_state.unwrap();
-        one_sig_state.multisig.verify_proved_signers(&merkle_root_state.signed_by)?;
-        merkle_root_state.merkle_root
+        require!(
+            merkle_root_state.version == MERKLE_ROOT_STATE_VERSION,
+            OneSigError::StateVersionMismatch
+        );
+        one_sig_state.multisig().verify_proved_signers(&merkle_root_state.signed_by)?;
+        if let Some(remaining) = merkle_root_state.remaining_executions.as_mut() {
+            *remaining = remaining.checked_sub(1).ok_or(OneSigError::MerkleRootExhausted)?;
+        }
+        merkle_root_state.executions = merkle_root_state.executions.saturating_add(1);
+        (merkle_root_state.merkle_root, Some(merkle_root_state.executions))
     };
     Ok(root)
 }
 
+/// Checks that `one_sig_signer` is the signer PDA of vault `vault_index` and returns its bump.
+/// Vault 0 is the original `one_sig_signer`, derived without an index and whose bump is stored in
+/// the state; the others are derived from `[ONE_SIG_SEED, one_sig_state, vault_index]`.
+pub fn verify_vault_signer(
+    one_sig_signer: &Pubkey,
+    one_sig_state: &Pubkey,
+    bump: u8,
+    vault_index: u8,
+) -> Result<u8> {
+    let (expected, bump) = if vault_index == 0 {
+        let address =
+            Pubkey::create_program_address(&[ONE_SIG_SEED, one_sig_state.as_ref(), &[bump]], &ID)
+                .map_err(|_| OneSigError::InvalidVaultSigner)?;
+        (address, bump)
+    } else {
+        Pubkey::find_program_address(&[ONE_SIG_SEED, one_sig_state.as_ref(), &[vault_index]], &ID)
+    };
+    require_keys_eq!(*one_sig_signer, expected, OneSigError::InvalidVaultSigner);
+    Ok(bump)
+}
+
 /// Builds the OneSigInstruction from the transaction using the remaining accounts
 /// 1. Calculates the start and end indices for accounts
 /// 2. Extracts the relevant accounts
 /// 3. Creates an instruction with the program ID from the first account
 /// 4. Adds the remaining accounts as instruction accounts
-/// 5. Returns the OneSigInstruction
+/// 5. Returns the OneSigInstruction, spending up to `value`
+///
+/// `ix_data` moves into the instruction, and from it into the invoked one, so the data of a leaf,
+/// which can run to kilobytes, is never copied on the heap.
 pub fn build_instruction(
     one_sig_signer: &UncheckedAccount,
-    transaction: &OneSigTransaction,
+    ix_data: Vec<u8>,
+    value: u64,
     remaining_accounts: &[AccountInfo],
 ) -> Result<OneSigInstruction> {
     require!(!remaining_accounts.is_empty(), OneSigError::MissingProgramId);
@@ -72,47 +129,185 @@ pub fn build_instruction(
                 }
             })
             .collect(),
-        data: transaction.ix_data.clone(),
-        value: transaction.value,
+        data: ix_data,
+        value,
     })
 }
 
+/// Checks the program an instruction calls against the target policy of the OneSig account. Calls
+/// into this program are checked against its self-CPI policy instead, which always allows
+/// `set_config`, so both policies can be changed, and never the execute paths nor the merkle root
+/// and initialization instructions: this is the re-entrancy guard of `execute_instruction`.
+pub fn check_target_program(
+    one_sig_state: &OneSigStateRef,
+    target_programs: Option<&Account<TargetPrograms>>,
+    instruction: &OneSigInstruction,
+) -> Result<()> {
+    if instruction.program_id == ID {
+        require!(one_sig_state.allows_self_cpi(&instruction.data), OneSigError::Reentrancy);
+        return Ok(());
+    }
+    let policy = one_sig_state.target_policy();
+    if policy == TargetPolicy::None {
+        return Ok(());
+    }
+    let target_programs = target_programs.ok_or(OneSigError::MissingTargetPrograms)?;
+    let listed = target_programs.programs.contains(&instruction.program_id);
+    require!(listed == (policy == TargetPolicy::Allow), OneSigError::TargetProgramNotAllowed);
+    Ok(())
+}
+
+/// Checks a leaf signed to take effect from `not_before` is executed no earlier, at `now`.
+pub fn check_not_before(not_before: Option<i64>, now: i64) -> Result<()> {
+    if let Some(not_before) = not_before {
+        require!(now >= not_before, OneSigError::ExecutionTooEarly);
+    }
+    Ok(())
+}
+
+/// Checks the other leaves of the `group` of a leaf executed on `one_sig_state` are executed in
+/// the same transaction: by the instructions around the current one, each the next leaf's, which
+/// `instructions_sysvar` lists. Every leaf of the group makes the check, so whichever lands first
+/// only does with the others, and as each executes at the nonce following the one before, none is
+/// executed twice. A group is only executed by top-level instructions, which the sysvar lists.
+pub fn verify_transaction_group(
+    instructions_sysvar: Option<&UncheckedAccount>,
+    one_sig_state: &Pubkey,
+    group: &TransactionGroup,
+) -> Result<()> {
+    require!(group.index < group.len, OneSigError::InvalidTransactionGroup);
+    let instructions_sysvar = instructions_sysvar.ok_or(OneSigError::MissingInstructionsSysvar)?;
+    let current = load_current_index_checked(instructions_sysvar)? as usize;
+    let first = current
+        .checked_sub(group.index as usize)
+        .ok_or(OneSigError::IncompleteTransactionGroup)?;
+    for index in (0..group.len).filter(|index| *index != group.index) {
+        let instruction = load_instruction_at_checked(first + index as usize, instructions_sysvar)
+            .map_err(|_| OneSigError::IncompleteTransactionGroup)?;
+        require!(
+            executed_group(&instruction, one_sig_state) ==
+                Some(TransactionGroup { index, len: group.len }),
+            OneSigError::IncompleteTransactionGroup
+        );
+    }
+    Ok(())
+}
+
+/// The group of the leaf `instruction` executes on `one_sig_state`, `None` if it does not
+/// execute a grouped leaf of it. Both execute instructions take the transaction first, and the
+/// OneSig account third.
+fn executed_group(instruction: &Instruction, one_sig_state: &Pubkey) -> Option<TransactionGroup> {
+    if instruction.program_id != ID || instruction.accounts.get(2)?.pubkey != *one_sig_state {
+        return None;
+    }
+    let params = [
+        crate::instruction::ExecuteTransaction::DISCRIMINATOR,
+        crate::instruction::SignerExecuteTransaction::DISCRIMINATOR,
+    ]
+    .into_iter()
+    .find_map(|discriminator| instruction.data.strip_prefix(discriminator))?;
+    OneSigTransaction::deserialize(&mut &params[..]).ok()?.group
+}
+
 /// Executes the instruction with PDA authorization and balance checks:
-/// 1. Records the balance of the one_sig_signer before execution
-/// 2. Invokes the instruction with the PDA's signature
-/// 3. Verifies the balance change is within allowed limits
-/// 4. Ensures the one_sig_signer account isn't initialized
+/// 1. Checks the program called with `check_target_program`, which refuses re-entrant calls
+/// 2. Records the balance of the one_sig_signer, and of the accounts the leaf guards, before
+///    execution
+/// 3. Invokes the instruction with the PDA's signature
+/// 4. Verifies the balance change is within allowed limits, for SOL (over the one_sig_signer and
+///    the guarded accounts combined) and for every token mint the leaf declared a limit for
+/// 5. Charges what was spent to the spending limits of the OneSig account
+/// 6. Ensures the one_sig_signer account isn't initialized
+///
+/// `one_sig_signer` is the signer PDA of the transaction's vault, with bump `signer_bump` (see
+/// `verify_vault_signer`). Returns the lamports the one_sig_signer lost to the call.
 pub fn execute_instruction(
     one_sig_signer: &UncheckedAccount,
-    one_sig_state: &Account<OneSigState>,
+    one_sig_state: &AccountLoader<OneSigState>,
+    signer_bump: u8,
+    spending_limits: &mut Account<SpendingLimits>,
+    target_programs: Option<&Account<TargetPrograms>>,
     remaining_accounts: &[AccountInfo],
     instruction: OneSigInstruction,
-) -> Result<()> {
+    transaction: &OneSigTransaction,
+) -> Result<u64> {
+    check_target_program(&OneSigStateRef::load(one_sig_state)?, target_programs, &instruction)?;
+    let OneSigTransaction { token_outflow_limits, guarded_accounts, vault_index, .. } = transaction;
+    // Token balances are tracked for the mints limited by the leaf, then for those with a
+    // spending limit
+    let mints: Vec<Pubkey> = token_outflow_limits
+        .iter()
+        .map(|limit| limit.mint)
+        .chain(
+            spending_limits
+                .entries
+                .iter()
+                .map(|limit| limit.mint)
+                .filter(|mint| *mint != Pubkey::default()),
+        )
+        .collect();
+    let sol_limited = spending_limits.entries.iter().any(|limit| limit.mint == Pubkey::default());
     let balance_before = one_sig_signer.lamports();
+    let guarded_before =
+        guarded_lamports(&one_sig_signer.key(), &remaining_accounts[1..], guarded_accounts);
+    let token_balances_before =
+        token_balances(&one_sig_signer.key(), &remaining_accounts[1..], &mints)?;
 
     let (solana_ix, value) = instruction.into();
+    // Recorded before the call, which may close the account
+    OneSigStateRefMut::load(one_sig_state)?.record_vault(*vault_index);
 
-    // Re-entrancy guard: an executed instruction must not call back into the nonce-advancing
-    // execute paths, or it could replay itself and defeat replay protection.
-    if solana_ix.program_id == ID {
-        let exec_disc = crate::instruction::ExecuteTransaction::DISCRIMINATOR;
-        let signer_exec_disc = crate::instruction::SignerExecuteTransaction::DISCRIMINATOR;
-        require!(
-            !solana_ix.data.starts_with(exec_disc) && !solana_ix.data.starts_with(signer_exec_disc),
-            OneSigError::Reentrancy
-        );
-    }
-
-    // Execute the instruction with the PDA's signature
-    invoke_signed(
-        &solana_ix,
-        &remaining_accounts[1..], // Skip program_id
-        &[&[ONE_SIG_SEED, one_sig_state.key().as_ref(), &[one_sig_state.bump]]],
-    )?;
+    // Execute the instruction with the signature of the vault's PDA. A failing call aborts the
+    // whole transaction rather than returning its error here: executors that simulated the
+    // failure record it with `record_leaf_failure`, and the leaf stays executable.
+    with_signer_seeds(&one_sig_state.key(), *vault_index, signer_bump, |signer_seeds| {
+        invoke_signed(
+            &solana_ix,
+            &remaining_accounts[1..], // Skip program_id
+            &[signer_seeds],
+        )
+    })?;
 
-    // Verify balance change is within limits
+    // Verify balance change is within limits, summed in u128 so that no sum overflows, whatever
+    // `value` the leaf declares
     let balance_after = one_sig_signer.lamports();
-    require!(balance_before <= balance_after + value, OneSigError::ExcessiveBalanceDeduction);
+    let guarded_after =
+        guarded_lamports(&one_sig_signer.key(), &remaining_accounts[1..], guarded_accounts);
+    require!(
+        u128::from(balance_before) + u128::from(guarded_before) <=
+            u128::from(balance_after) + u128::from(guarded_after) + u128::from(value),
+        OneSigError::ExcessiveBalanceDeduction
+    );
+    let token_balances_after =
+        token_balances(&one_sig_signer.key(), &remaining_accounts[1..], &mints)?;
+    let token_outflows: Vec<u64> = token_balances_before
+        .iter()
+        .zip(&token_balances_after)
+        .map(|(before, after)| before.saturating_sub(*after))
+        .collect();
+    let (leaf_outflows, limited_outflows) = token_outflows.split_at(token_outflow_limits.len());
+    for (limit, outflow) in token_outflow_limits.iter().zip(leaf_outflows) {
+        require!(*outflow <= limit.max_amount, OneSigError::ExcessiveTokenOutflow);
+    }
+
+    // The instruction may have changed the spending limits (or closed them together with the
+    // OneSig account), so the spend is charged to what is stored now. Only limits that were in
+    // place during the execution are charged.
+    let spending_limits_info = spending_limits.to_account_info();
+    if !(spending_limits_info.owner == &SYSTEM_PROGRAM_ID && spending_limits_info.data_is_empty()) {
+        spending_limits.reload()?;
+        let now = Clock::get()?.unix_timestamp;
+        if sol_limited {
+            spending_limits.record_spend(
+                &Pubkey::default(),
+                balance_before.saturating_sub(balance_after),
+                now,
+            )?;
+        }
+        for (mint, outflow) in mints[token_outflow_limits.len()..].iter().zip(limited_outflows) {
+            spending_limits.record_spend(mint, *outflow, now)?;
+        }
+    }
 
     // Verify account after execution to ensure:
     // 1. The one_sig_signer account is still owned by the system program
@@ -120,5 +315,256 @@ pub fn execute_instruction(
     require!(one_sig_signer.owner.key() == SYSTEM_PROGRAM_ID, OneSigError::InvalidSignerOwner);
     require!(one_sig_signer.data_is_empty(), OneSigError::NonEmptySignerData);
 
+    Ok(balance_before.saturating_sub(balance_after))
+}
+
+/// Pays the executor reimbursement stored in `spending_limits` once the leaf ran, from the
+/// vault's signer PDA to `recipient`, the executor or the fee payer sponsoring it, and charges it
+/// to the SOL spending limit. Returns the lamports paid: none when no reimbursement is set, or
+/// when the leaf closed the OneSig account.
+pub fn reimburse_executor<'info>(
+    one_sig_signer: &UncheckedAccount<'info>,
+    one_sig_state: &Pubkey,
+    signer_bump: u8,
+    vault_index: u8,
+    spending_limits: &mut Account<SpendingLimits>,
+    recipient: &AccountInfo<'info>,
+) -> Result<u64> {
+    let spending_limits_info = spending_limits.to_account_info();
+    if spending_limits_info.owner == &SYSTEM_PROGRAM_ID && spending_limits_info.data_is_empty() {
+        return Ok(0);
+    }
+    let lamports = spending_limits.executor_reimbursement;
+    if lamports == 0 {
+        return Ok(0);
+    }
+    spending_limits.record_spend(&Pubkey::default(), lamports, Clock::get()?.unix_timestamp)?;
+
+    with_signer_seeds(one_sig_state, vault_index, signer_bump, |signer_seeds| {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                system_program::ID,
+                Transfer { from: one_sig_signer.to_account_info(), to: recipient.clone() },
+                &[signer_seeds],
+            ),
+            lamports,
+        )
+    })?;
+    Ok(lamports)
+}
+
+/// Pays the `bounty` a leaf executed on `one_sig_state` committed to once it ran, from the bounty
+/// escrow of the OneSig account to `recipient`, the executor or the fee payer sponsoring it. The
+/// escrow only holds what the multisig funded it with for bounties, so it is not charged to the
+/// spending limits; it must hold the bounty, and stay rent-exempt unless emptied.
+pub fn pay_bounty<'info>(
+    bounty_escrow: Option<&UncheckedAccount<'info>>,
+    one_sig_state: &Pubkey,
+    recipient: &AccountInfo<'info>,
+    bounty: u64,
+) -> Result<()> {
+    let bounty_escrow = bounty_escrow.ok_or(OneSigError::MissingBountyEscrow)?;
+    let (address, bump) =
+        Pubkey::find_program_address(&[BOUNTY_ESCROW_SEED, one_sig_state.as_ref()], &ID);
+    require_keys_eq!(bounty_escrow.key(), address, OneSigError::InvalidBountyEscrow);
+    withdraw_from_bounty_escrow(bounty_escrow, one_sig_state, bump, recipient, bounty)
+}
+
+/// Moves `lamports` from the bounty escrow of `one_sig_state`, whose bump is `bump`, to
+/// `recipient`. The system program refuses to leave the escrow neither empty nor rent-exempt, so
+/// what it would keep is checked first, to fail with `InsufficientBountyEscrow` instead.
+pub(crate) fn withdraw_from_bounty_escrow<'info>(
+    bounty_escrow: &AccountInfo<'info>,
+    one_sig_state: &Pubkey,
+    bump: u8,
+    recipient: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    let remaining = bounty_escrow
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(OneSigError::InsufficientBountyEscrow)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        OneSigError::InsufficientBountyEscrow
+    );
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program::ID,
+            Transfer { from: bounty_escrow.clone(), to: recipient.clone() },
+            &[&[BOUNTY_ESCROW_SEED, one_sig_state.as_ref(), &[bump]]],
+        ),
+        lamports,
+    )
+}
+
+// Calls `f` with the seeds of the signer PDA of vault `vault_index`, whose bump is `bump`
+fn with_signer_seeds<T>(
+    one_sig_state: &Pubkey,
+    vault_index: u8,
+    bump: u8,
+    f: impl FnOnce(&[&[u8]]) -> T,
+) -> T {
+    let vault_index = [vault_index];
+    let bump = [bump];
+    let mut signer_seeds: Vec<&[u8]> = vec![ONE_SIG_SEED, one_sig_state.as_ref()];
+    if vault_index != [0] {
+        signer_seeds.push(&vault_index);
+    }
+    signer_seeds.push(&bump);
+    f(&signer_seeds)
+}
+
+/// Sums the lamports of the `guarded` accounts among `accounts`, other than the one_sig_signer
+/// whose balance is checked on its own. Only the accounts passed to the instruction can be debited
+/// by it, so the others are left out.
+pub(crate) fn guarded_lamports(
+    one_sig_signer: &Pubkey,
+    accounts: &[AccountInfo],
+    guarded: &[Pubkey],
+) -> u64 {
+    let mut seen: Vec<&Pubkey> = Vec::with_capacity(guarded.len());
+    let mut total = 0u64;
+    for account in accounts {
+        if account.key == one_sig_signer || seen.contains(&account.key) {
+            continue;
+        }
+        if guarded.contains(account.key) {
+            seen.push(account.key);
+            total = total.saturating_add(account.lamports());
+        }
+    }
+    total
+}
+
+/// Sums, per mint, the balances of the token accounts among `accounts` that `owner` owns. Only
+/// the accounts passed to the instruction can be debited by it, so these cover every outflow; an
+/// account that is closed or handed over to another owner counts as emptied.
+fn token_balances(owner: &Pubkey, accounts: &[AccountInfo], mints: &[Pubkey]) -> Result<Vec<u64>> {
+    let mut balances = vec![0u64; mints.len()];
+    if mints.is_empty() {
+        return Ok(balances);
+    }
+    let mut seen: Vec<&Pubkey> = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        // An account may be passed more than once
+        if seen.contains(&account.key) {
+            continue;
+        }
+        seen.push(account.key);
+
+        let data = account.try_borrow_data()?;
+        let Some((mint, account_owner, amount)) = parse_token_account(account.owner, &data) else {
+            continue;
+        };
+        if account_owner != *owner {
+            continue;
+        }
+        for (limited_mint, balance) in mints.iter().zip(balances.iter_mut()) {
+            if *limited_mint == mint {
+                *balance = balance.saturating_add(amount);
+            }
+        }
+    }
+    Ok(balances)
+}
+
+/// Reads `(mint, owner, amount)` from the data of an initialized SPL Token or Token-2022 token
+/// account, or `None` if the account is not one.
+///
+/// For Token-2022, `amount` includes the transfer fees withheld in the account: they stay in it
+/// until harvested, so a transfer between two accounts of the same owner does not register the
+/// fee as an outflow, while harvesting them out of the account does. Transfer hooks run within
+/// the transfer and need no accounting of their own.
+pub(crate) fn parse_token_account(
+    program_owner: &Pubkey,
+    data: &[u8],
+) -> Option<(Pubkey, Pubkey, u64)> {
+    // Base `Account` layout: mint (32), owner (32), amount (8), delegate (36), state (1), ...
+    const ACCOUNT_LEN: usize = 165;
+    const STATE_OFFSET: usize = 108;
+    // Token-2022 accounts with extensions are tagged with their type after the base layout,
+    // which tells them apart from mints of the same length. Neither program lets an extended
+    // account reach the length of a multisig account.
+    const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+    const MULTISIG_LEN: usize = 355;
+
+    if *program_owner != TOKEN_PROGRAM_ID && *program_owner != TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    if data.len() < ACCOUNT_LEN ||
+        data.len() == MULTISIG_LEN ||
+        (data.len() > ACCOUNT_LEN && data[ACCOUNT_LEN] != ACCOUNT_TYPE_ACCOUNT) ||
+        data[STATE_OFFSET] == 0
+    {
+        return None;
+    }
+    let mint = Pubkey::try_from(&data[0..32]).ok()?;
+    let owner = Pubkey::try_from(&data[32..64]).ok()?;
+    let amount = u64::from_le_bytes(data[64..72].try_into().ok()?);
+    let withheld = withheld_transfer_fees(data.get(ACCOUNT_LEN + 1..).unwrap_or_default());
+    Some((mint, owner, amount.saturating_add(withheld)))
+}
+
+/// Reads the `withheld_amount` of the `TransferFeeAmount` extension from the TLV entries of a
+/// Token-2022 account, or 0 without one.
+fn withheld_transfer_fees(mut extensions: &[u8]) -> u64 {
+    // Entries are a type (u16), a length (u16) and the value; an uninitialized type ends them
+    const EXTENSION_TRANSFER_FEE_AMOUNT: u16 = 2;
+    while extensions.len() >= 4 {
+        let extension_type = u16::from_le_bytes([extensions[0], extensions[1]]);
+        let len = u16::from_le_bytes([extensions[2], extensions[3]]) as usize;
+        let Some(value) = extensions.get(4..4 + len) else {
+            break;
+        };
+        match extension_type {
+            0 => break,
+            EXTENSION_TRANSFER_FEE_AMOUNT if len == 8 => {
+                return u64::from_le_bytes(value.try_into().unwrap_or_default());
+            },
+            _ => extensions = &extensions[4 + len..],
+        }
+    }
+    0
+}
+
+/// Whether the leaf `instruction` starts a new nonce namespace: a `set_config` of this program
+/// with `ResetNonce`. Decided before it executes, as the state it leaves is the one of a `SetSeed`
+/// leaf at nonce 0.
+pub fn resets_nonce(instruction: &OneSigInstruction) -> bool {
+    instruction.program_id == ID &&
+        instruction
+            .data
+            .strip_prefix(crate::instruction::SetConfig::DISCRIMINATOR)
+            .and_then(|params| SetConfigParams::deserialize(&mut &params[..]).ok())
+            .is_some_and(|params| matches!(params, SetConfigParams::ResetNonce(_)))
+}
+
+/// Advances the nonce once a leaf at `nonce` under `seed` has been executed. The zero-copy state
+/// reflects anything the executed instruction mutated, so a mutated nonce is rejected, unless the
+/// leaf started a new namespace (`resets_nonce`): the seed changed and the nonce restarted at 0,
+/// where the first leaf of the new seed executes. A leaf that decommissioned the account through
+/// `close_one_sig` leaves no state to protect.
+pub fn advance_nonce(
+    one_sig_state: &AccountLoader<OneSigState>,
+    nonce: u64,
+    seed: &Hash,
+    resets_nonce: bool,
+) -> Result<()> {
+    let account_info = one_sig_state.as_ref();
+    if account_info.owner == &SYSTEM_PROGRAM_ID && account_info.data_is_empty() {
+        return Ok(());
+    }
+    let mut one_sig_state = one_sig_state.load_mut()?;
+    if resets_nonce {
+        require!(
+            one_sig_state.seed != *seed && one_sig_state.nonce == 0,
+            OneSigError::NonceMutatedDuringExecution
+        );
+        return Ok(());
+    }
+    require!(one_sig_state.nonce == nonce, OneSigError::NonceMutatedDuringExecution);
+    one_sig_state.nonce = nonce.checked_add(1).ok_or(OneSigError::NonceOverflow)?;
     Ok(())
 }
@@ -0,0 +1,116 @@
+// Tests for recognizing the SPL token accounts whose balances are bounded by leaf token outflow
+// limits, for summing the lamports of the accounts a leaf guards, and for deriving vault signers.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+    use crate::{
+        execution::{guarded_lamports, parse_token_account, verify_vault_signer},
+        ID, ONE_SIG_SEED, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    };
+
+    const MINT: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const OWNER: Pubkey = Pubkey::new_from_array([2u8; 32]);
+
+    // An initialized base-layout token account holding `amount`
+    fn token_account_data(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[0..32].copy_from_slice(MINT.as_ref());
+        data[32..64].copy_from_slice(OWNER.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1; // AccountState::Initialized
+        data
+    }
+
+    #[test]
+    fn test_guarded_lamports() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let [signer, guarded, other] = &keys;
+        let mut lamports = [1u64, 10, 100];
+        let mut data: [[u8; 0]; 3] = [[], [], []];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, true, lamports, data, &OWNER, false)
+            })
+            .collect();
+        assert_eq!(guarded_lamports(signer, &accounts, &[*guarded, *other]), 110);
+
+        // The signer is not counted twice, nor an account passed more than once
+        let repeated = [accounts[0].clone(), accounts[1].clone(), accounts[1].clone()];
+        assert_eq!(guarded_lamports(signer, &repeated, &[*signer, *guarded]), 10);
+        assert_eq!(guarded_lamports(signer, &repeated, &[]), 0);
+    }
+
+    #[test]
+    fn test_verify_vault_signer() {
+        let state = Pubkey::new_unique();
+        let (signer, bump) = Pubkey::find_program_address(&[ONE_SIG_SEED, state.as_ref()], &ID);
+        let (vault, vault_bump) =
+            Pubkey::find_program_address(&[ONE_SIG_SEED, state.as_ref(), &[1]], &ID);
+
+        assert_eq!(verify_vault_signer(&signer, &state, bump, 0).unwrap(), bump);
+        assert_eq!(verify_vault_signer(&vault, &state, bump, 1).unwrap(), vault_bump);
+        // A vault's signer cannot stand in for another vault's
+        assert!(verify_vault_signer(&vault, &state, bump, 0).is_err());
+        assert!(verify_vault_signer(&signer, &state, bump, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_token_account() {
+        let data = token_account_data(42);
+        for program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            assert_eq!(parse_token_account(&program, &data), Some((MINT, OWNER, 42)));
+        }
+    }
+
+    #[test]
+    fn test_parse_token_account_with_extensions() {
+        let mut data = token_account_data(7);
+        data.resize(170, 0);
+        data[165] = 2; // AccountType::Account
+        assert_eq!(parse_token_account(&TOKEN_2022_PROGRAM_ID, &data), Some((MINT, OWNER, 7)));
+
+        data[165] = 1; // AccountType::Mint
+        assert_eq!(parse_token_account(&TOKEN_2022_PROGRAM_ID, &data), None);
+    }
+
+    #[test]
+    fn test_parse_token_account_counts_withheld_transfer_fees() {
+        let mut data = token_account_data(7);
+        data.push(2); // AccountType::Account
+                      // An ImmutableOwner extension (no value), then a TransferFeeAmount withholding 3
+        data.extend_from_slice(&7u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&8u16.to_le_bytes());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        assert_eq!(parse_token_account(&TOKEN_2022_PROGRAM_ID, &data), Some((MINT, OWNER, 10)));
+
+        // A truncated entry is ignored
+        data.truncate(data.len() - 1);
+        assert_eq!(parse_token_account(&TOKEN_2022_PROGRAM_ID, &data), Some((MINT, OWNER, 7)));
+    }
+
+    #[test]
+    fn test_parse_token_account_rejects_other_accounts() {
+        let data = token_account_data(42);
+        // Owned by another program
+        assert_eq!(parse_token_account(&Pubkey::new_unique(), &data), None);
+
+        // Uninitialized
+        let mut uninitialized = data.clone();
+        uninitialized[108] = 0;
+        assert_eq!(parse_token_account(&TOKEN_PROGRAM_ID, &uninitialized), None);
+
+        // Mint
+        assert_eq!(parse_token_account(&TOKEN_PROGRAM_ID, &data[..82]), None);
+
+        // Multisig, whose signer list may hold any byte where the account type would be
+        let mut multisig = vec![2u8; 355];
+        multisig[108] = 1;
+        assert_eq!(parse_token_account(&TOKEN_PROGRAM_ID, &multisig), None);
+    }
+}
@@ -0,0 +1,138 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Allocate, Assign, CreateAccount, Transfer},
+};
+
+use crate::{
+    constants::{EVENT_VERSION, EXECUTOR_SEED, ONE_SIG_SEED},
+    errors::OneSigError,
+    events::ExecutorAdded,
+    state::{ExecutorState, OneSigState},
+    ID,
+};
+
+/// Registers `executor` by creating its `ExecutorState` PDA. Authorized by the multisig (executed
+/// as a leaf, like `set_config`); the rent of the PDA is paid by `payer`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(executor: Pubkey)]
+pub struct AddExecutor<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// Funds the rent of the executor PDA. When executed as a leaf this is usually the
+    /// `one_sig_signer` PDA itself, in which case the leaf's `value` must cover the rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub state: AccountLoader<'info, OneSigState>,
+    /// CHECK: Created in the handler, which reports an existing executor as `DuplicateExecutor`.
+    #[account(mut, seeds = [EXECUTOR_SEED, state.key().as_ref(), executor.as_ref()], bump)]
+    pub executor_state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AddExecutor<'_> {
+    pub fn apply(ctx: &mut Context<AddExecutor>, executor: &Pubkey) -> Result<()> {
+        create_executor_state(
+            &ctx.accounts.payer,
+            &ctx.accounts.executor_state,
+            &ctx.accounts.state.key(),
+            executor,
+            ctx.bumps.executor_state,
+        )?;
+        {
+            let mut state = ctx.accounts.state.load_mut()?;
+            state.executors_len =
+                state.executors_len.checked_add(1).ok_or(OneSigError::InvalidExecutorsLen)?;
+        }
+        emit_cpi!(ExecutorAdded {
+            one_sig_account: ctx.accounts.state.key(),
+            executor: *executor,
+            event_version: EVENT_VERSION,
+            effective_at: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+/// Creates and initializes the `ExecutorState` PDA of `executor` at `executor_state`, whose
+/// address the caller must have checked. Shared by `add_executor` and `init_one_sig`.
+pub(crate) fn create_executor_state<'info>(
+    payer: &AccountInfo<'info>,
+    executor_state: &AccountInfo<'info>,
+    one_sig_state: &Pubkey,
+    executor: &Pubkey,
+    bump: u8,
+) -> Result<()> {
+    require!(*executor != Pubkey::default(), OneSigError::InvalidExecutor);
+    require!(*executor_state.owner != ID, OneSigError::DuplicateExecutor);
+
+    create_pda(
+        payer,
+        executor_state,
+        ExecutorState::space(0),
+        &[EXECUTOR_SEED, one_sig_state.as_ref(), executor.as_ref(), &[bump]],
+    )?;
+    let record = ExecutorState {
+        executor: *executor,
+        rent_payer: payer.key(),
+        bump,
+        target_programs: vec![],
+    };
+    record.try_serialize(&mut &mut executor_state.try_borrow_mut_data()?[..])
+}
+
+/// Creates the PDA of `seeds` at `account`, owned by this program with `space` zeroed bytes, its
+/// rent paid by `payer`. Shared with `migrate_state`, which creates the PDAs `init_one_sig` does.
+pub(crate) fn create_pda<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds = &[seeds];
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program::ID,
+                CreateAccount { from: payer.clone(), to: account.clone() },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &ID,
+        )?;
+    } else {
+        // The address was pre-funded (e.g. to grief the creation): top it up, then allocate and
+        // assign it instead of `create_account`, which rejects accounts holding lamports.
+        let top_up = rent.saturating_sub(current_lamports);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program::ID,
+                    Transfer { from: payer.clone(), to: account.clone() },
+                ),
+                top_up,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program::ID,
+                Allocate { account_to_allocate: account.clone() },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program::ID,
+                Assign { account_to_assign: account.clone() },
+                signer_seeds,
+            ),
+            &ID,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, MODULE_SEED, ONE_SIG_SEED},
+    errors::OneSigError,
+    events::ModuleAdded,
+    state::{ModuleState, OneSigState},
+    ID,
+};
+
+/// Registers the `module` program by creating its `ModuleState` PDA. Authorized by the multisig
+/// (executed as a leaf, like `set_config`); the rent of the PDA is paid by `payer`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(module: Pubkey)]
+pub struct AddModule<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// Funds the rent of the module PDA. When executed as a leaf this is usually the
+    /// `one_sig_signer` PDA itself, in which case the leaf's `value` must cover the rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ModuleState::INIT_SPACE,
+        seeds = [MODULE_SEED, state.key().as_ref(), module.as_ref()],
+        bump,
+    )]
+    pub module_state: Account<'info, ModuleState>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AddModule<'_> {
+    pub fn apply(ctx: &mut Context<AddModule>, module: &Pubkey) -> Result<()> {
+        // A module must be a program other than this one, which would sign for itself
+        require!(*module != Pubkey::default() && *module != ID, OneSigError::InvalidModule);
+        ctx.accounts.module_state.set_inner(ModuleState {
+            module: *module,
+            rent_payer: ctx.accounts.payer.key(),
+            bump: ctx.bumps.module_state,
+        });
+        {
+            let mut state = ctx.accounts.state.load_mut()?;
+            state.modules_len =
+                state.modules_len.checked_add(1).ok_or(OneSigError::InvalidModule)?;
+        }
+        emit_cpi!(ModuleAdded {
+            one_sig_account: ctx.accounts.state.key(),
+            module: *module,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EXECUTOR_SEED, MERKLE_ROOT_SEED, MERKLE_ROOT_STATE_VERSION, TARGET_PROGRAMS_SEED},
+    errors::OneSigError,
+    execution::{build_instruction, check_not_before, check_target_program, verify_vault_signer},
+    state::{ExecutorState, MerkleRootState, OneSigState, OneSigStateRef, TargetPrograms},
+    types::{
+        ExecuteTransactionParams, Hash, TransactionCheck, TransactionCheckResult,
+        VerifyMerkleRootParams,
+    },
+    validation::merkle::MerkleValidator,
+};
+
+/// The accounts of `execute_transaction` the checks read, none of them written.
+#[derive(Accounts)]
+pub struct CheckTransaction<'info> {
+    /// CHECK: Only its key is read, as the executor the leaf would be executed by.
+    pub executor: UncheckedAccount<'info>,
+    /// CHECK: The vault signer the leaf would be executed with, checked by
+    /// `verify_vault_signer` as one of the checks.
+    pub one_sig_signer: UncheckedAccount<'info>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// Only bound to its root here: its expiry, seed and executions left are checks.
+    #[account(
+        seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), merkle_root_state.merkle_root.as_ref()],
+        bump = merkle_root_state.bump,
+    )]
+    pub merkle_root_state: Option<Account<'info, MerkleRootState>>,
+    #[account(
+        seeds = [TARGET_PROGRAMS_SEED, one_sig_state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+    #[account(
+        seeds = [EXECUTOR_SEED, one_sig_state.key().as_ref(), executor.key().as_ref()],
+        bump = executor_state.bump,
+    )]
+    pub executor_state: Option<Account<'info, ExecutorState>>,
+}
+
+/// A failed check, and the error `execute_transaction` would fail it with
+pub(crate) type Failure = (TransactionCheck, Error);
+
+/// The accounts the checks of a leaf read, as `check_transaction` and `record_leaf_failure` take
+/// them
+pub(crate) struct LeafCheckAccounts<'a, 'info> {
+    pub one_sig_signer: &'a UncheckedAccount<'info>,
+    pub one_sig_state: &'a AccountLoader<'info, OneSigState>,
+    pub merkle_root_state: Option<&'a Account<'info, MerkleRootState>>,
+    pub target_programs: Option<&'a Account<'info, TargetPrograms>>,
+    pub executor_state: Option<&'a Account<'info, ExecutorState>>,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+fn ensure(
+    condition: bool,
+    check: TransactionCheck,
+    error: OneSigError,
+) -> std::result::Result<(), Failure> {
+    if condition {
+        Ok(())
+    } else {
+        Err((check, error.into()))
+    }
+}
+
+impl CheckTransaction<'_> {
+    /// Makes the checks `execute_transaction` makes before executing a leaf, with the same
+    /// accounts and parameters, and returns the first the leaf fails, for preflight tooling to
+    /// tell without parsing simulation logs. Nothing is executed or written: neither a capped root
+    /// nor the execution rate limit is charged an execution.
+    ///
+    /// What a leaf's execution spends, checked against its `value`, outflow limits and guarded
+    /// accounts, is only known by executing it, so simulating `execute_transaction` remains the
+    /// check of those, and of the other leaves of a grouped leaf being executed alongside it.
+    pub fn apply(
+        ctx: &Context<CheckTransaction>,
+        params: ExecuteTransactionParams,
+    ) -> Result<TransactionCheckResult> {
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
+        let now = Clock::get()?.unix_timestamp;
+        let accounts = LeafCheckAccounts {
+            one_sig_signer: &ctx.accounts.one_sig_signer,
+            one_sig_state: &ctx.accounts.one_sig_state,
+            merkle_root_state: ctx.accounts.merkle_root_state.as_ref(),
+            target_programs: ctx.accounts.target_programs.as_ref(),
+            executor_state: ctx.accounts.executor_state.as_ref(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+        let (failed, error_code) = match Self::first_failure(&accounts, &one_sig_state, params, now)
+        {
+            Ok(_) => (None, 0),
+            Err((check, error)) => {
+                let error_code = match ProgramError::from(error) {
+                    ProgramError::Custom(code) => code,
+                    _ => 0,
+                };
+                (Some(check), error_code)
+            },
+        };
+        Ok(TransactionCheckResult { failed, error_code, nonce: one_sig_state.nonce })
+    }
+
+    /// The first check the leaf `params` execute fails, or the hash of the leaf if it passes them
+    /// all.
+    pub(crate) fn first_failure(
+        accounts: &LeafCheckAccounts,
+        one_sig_state: &OneSigStateRef,
+        params: ExecuteTransactionParams,
+        now: i64,
+    ) -> std::result::Result<Hash, Failure> {
+        use TransactionCheck::*;
+        let fail = |check: TransactionCheck| move |error: Error| (check, error);
+        let ExecuteTransactionParams { transaction, merkle_root_verification } = params;
+
+        if one_sig_state.executor_required() {
+            ensure(accounts.executor_state.is_some(), Executor, OneSigError::ExecutorRequired)?;
+        }
+
+        let merkle_root: Hash = match (merkle_root_verification, accounts.merkle_root_state) {
+            (
+                Some(VerifyMerkleRootParams { merkle_root, expiry, max_executions, signatures }),
+                _,
+            ) => {
+                ensure(max_executions == 0, MerkleRoot, OneSigError::ExecutionLimitNotTracked)?;
+                ensure(expiry >= now, Expiry, OneSigError::ExpiredMerkleRoot)?;
+                MerkleValidator::verify_merkle_root(
+                    &one_sig_state.seed,
+                    &one_sig_state.multisig(),
+                    &merkle_root,
+                    expiry,
+                    0,
+                    &signatures,
+                    now,
+                )
+                .map_err(fail(Signatures))?;
+                merkle_root
+            },
+            (None, Some(root)) => {
+                ensure(
+                    root.version == MERKLE_ROOT_STATE_VERSION,
+                    MerkleRoot,
+                    OneSigError::StateVersionMismatch,
+                )?;
+                ensure(
+                    root.verified_under(&one_sig_state.seed),
+                    MerkleRoot,
+                    OneSigError::SeedMismatch,
+                )?;
+                ensure(
+                    root.remaining_executions != Some(0),
+                    MerkleRoot,
+                    OneSigError::MerkleRootExhausted,
+                )?;
+                ensure(root.expiry >= now, Expiry, OneSigError::ExpiredMerkleRoot)?;
+                one_sig_state
+                    .multisig()
+                    .verify_proved_signers(&root.signed_by)
+                    .map_err(fail(Signatures))?;
+                root.merkle_root
+            },
+            (None, None) => return Err((MerkleRoot, OneSigError::MissingMerkleRootState.into())),
+        };
+
+        verify_vault_signer(
+            &accounts.one_sig_signer.key(),
+            &accounts.one_sig_state.key(),
+            one_sig_state.bump,
+            transaction.vault_index,
+        )
+        .map_err(fail(VaultSigner))?;
+
+        let instruction = build_instruction(
+            accounts.one_sig_signer,
+            transaction.ix_data,
+            transaction.value,
+            accounts.remaining_accounts,
+        )
+        .map_err(fail(Proof))?;
+        let leaf = MerkleValidator::encode_leaf(
+            &accounts.one_sig_state.key(),
+            one_sig_state.one_sig_id,
+            one_sig_state.nonce,
+            &instruction,
+            &transaction.token_outflow_limits,
+            &transaction.guarded_accounts,
+            transaction.vault_index,
+            one_sig_state.cluster_genesis_hash().as_ref(),
+            transaction.group.as_ref(),
+            transaction.not_before,
+            transaction.bounty,
+        )
+        .map_err(fail(Proof))?;
+        MerkleValidator::verify_merkle_proof(&merkle_root, &transaction.proof, &leaf)
+            .map_err(fail(Proof))?;
+
+        check_target_program(one_sig_state, accounts.target_programs, &instruction)
+            .map_err(fail(TargetProgram))?;
+        if one_sig_state.executor_required() {
+            if let Some(executor_state) = accounts.executor_state {
+                ensure(
+                    executor_state.allows_target(&instruction.program_id),
+                    ExecutorTarget,
+                    OneSigError::ExecutorTargetNotAllowed,
+                )?;
+            }
+        }
+        check_not_before(transaction.not_before, now).map_err(fail(NotBefore))?;
+        ensure(
+            one_sig_state.allows_execution(now),
+            ExecutionRate,
+            OneSigError::ExecutionRateLimited,
+        )?;
+        Ok(leaf)
+    }
+}
@@ -0,0 +1,42 @@
+use anchor_lang::{prelude::*, system_program::ID as SYSTEM_PROGRAM_ID};
+
+use crate::{
+    constants::LEAF_FAILURE_SEED,
+    errors::OneSigError,
+    state::{LeafFailure, OneSigState},
+};
+
+/// Permissionless close of an outdated `LeafFailure`: anyone can reclaim the PDA slot once its leaf
+/// can no longer execute, with rent refunded to the executor that recorded it.
+#[derive(Accounts)]
+pub struct CloseLeafFailure<'info> {
+    /// CHECK: Refund target only. Constrained to equal the recorded `rent_payer`, so the
+    /// reclaimed rent cannot be redirected.
+    #[account(mut, address = leaf_failure.rent_payer @OneSigError::InvalidRentPayer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [LEAF_FAILURE_SEED, one_sig_state.key().as_ref(), leaf_failure.leaf.as_ref()],
+        bump = leaf_failure.bump,
+        close = rent_payer,
+    )]
+    pub leaf_failure: Account<'info, LeafFailure>,
+    /// CHECK: The OneSigState the marker was recorded for, bound by the marker seeds. Loaded in
+    /// the handler, unless `close_one_sig` closed it: no leaf of a closed account executes again.
+    pub one_sig_state: UncheckedAccount<'info>,
+}
+
+impl<'info> CloseLeafFailure<'info> {
+    pub fn apply(ctx: &mut Context<'info, CloseLeafFailure<'info>>) -> Result<()> {
+        let account_info = ctx.accounts.one_sig_state.as_ref();
+        if account_info.owner == &SYSTEM_PROGRAM_ID && account_info.data_is_empty() {
+            return Ok(());
+        }
+        let one_sig_state = AccountLoader::<OneSigState>::try_from(account_info)?;
+        require!(
+            ctx.accounts.leaf_failure.outdated(&*one_sig_state.load()?),
+            OneSigError::LeafFailureNotCloseable
+        );
+        Ok(())
+    }
+}
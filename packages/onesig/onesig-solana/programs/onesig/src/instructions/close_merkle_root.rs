@@ -1,13 +1,13 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::MERKLE_ROOT_SEED,
+    constants::{MERKLE_ROOT_REGISTRY_SEED, MERKLE_ROOT_SEED},
     errors::OneSigError,
-    state::{MerkleRootState, OneSigState},
+    state::{MerkleRootRegistry, MerkleRootState, OneSigState},
 };
 
 /// Permissionless close of a dead `MerkleRootState`: anyone can reclaim the PDA slot, with rent
-/// refunded to the recorded `rent_payer`.
+/// refunded to the recorded `rent_payer`, together with the rent of its registry entry.
 #[derive(Accounts)]
 pub struct CloseMerkleRoot<'info> {
     /// CHECK: Refund target only. Constrained to equal the recorded `rent_payer`, so the
@@ -20,18 +20,46 @@ pub struct CloseMerkleRoot<'info> {
         seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), merkle_root_state.merkle_root.as_ref()],
         bump = merkle_root_state.bump,
         close = rent_payer,
-        // Dead when expired, or when the stored seed no longer matches state (so it can never
-        // pass the `execute_transaction` seed gate).
+        // Dead when expired, or when it was verified under another seed than the one of state (so
+        // it can never pass the `execute_transaction` seed gate).
         constraint = (merkle_root_state.expiry < Clock::get()?.unix_timestamp
-            || merkle_root_state.seed != one_sig_state.seed) @OneSigError::MerkleRootNotCloseable,
+            || !merkle_root_state.verified_under(&one_sig_state.load()?.seed)) @OneSigError::MerkleRootNotCloseable,
     )]
     pub merkle_root_state: Account<'info, MerkleRootState>,
-    pub one_sig_state: Account<'info, OneSigState>,
+    #[account(mut)]
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// Shrunk in the handler, and only when it held an entry for the root.
+    #[account(
+        mut,
+        seeds = [MERKLE_ROOT_REGISTRY_SEED, one_sig_state.key().as_ref()],
+        bump = merkle_root_registry.bump,
+    )]
+    pub merkle_root_registry: Account<'info, MerkleRootRegistry>,
+    pub system_program: Program<'info, System>,
 }
 
 impl CloseMerkleRoot<'_> {
-    pub fn apply(_ctx: &mut Context<CloseMerkleRoot>) -> Result<()> {
-        // No additional logic needed for closing the account
+    pub fn apply(ctx: &mut Context<CloseMerkleRoot>) -> Result<()> {
+        let address = ctx.accounts.merkle_root_state.key();
+        let registry = &mut ctx.accounts.merkle_root_registry;
+        let len = registry.entries.len();
+        registry.entries.retain(|entry| entry.address != address);
+        // Roots are counted as they are registered, so one without an entry was never counted
+        if registry.entries.len() < len {
+            // Refund the rent of the entry along with the one of the root
+            let space = MerkleRootRegistry::space(registry.entries.len());
+            let registry = registry.to_account_info();
+            let refund = registry.lamports().saturating_sub(Rent::get()?.minimum_balance(space));
+            registry.resize(space)?;
+            registry.sub_lamports(refund)?;
+            ctx.accounts.rent_payer.add_lamports(refund)?;
+
+            let mut one_sig_state = ctx.accounts.one_sig_state.load_mut()?;
+            one_sig_state.merkle_roots_len = one_sig_state
+                .merkle_roots_len
+                .checked_sub(1)
+                .ok_or(OneSigError::InvalidMerkleRootsLen)?;
+        }
         Ok(())
     }
 }
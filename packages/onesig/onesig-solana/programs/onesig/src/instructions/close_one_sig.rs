@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        BOUNTY_ESCROW_SEED, EVENT_VERSION, MERKLE_ROOT_REGISTRY_SEED, ONE_SIG_SEED,
+        SPENDING_LIMITS_SEED, TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    events::{BountyEscrowWithdrawn, OneSigClosed},
+    execution::{verify_vault_signer, withdraw_from_bounty_escrow},
+    state::{MerkleRootRegistry, OneSigState, SpendingLimits, TargetPrograms},
+};
+
+/// Decommissions a OneSig account by closing its `OneSigState`, `MerkleRootRegistry`,
+/// `SpendingLimits` and `TargetPrograms`, with rent sent to `recipient`.
+/// Authorized by the multisig (executed as a leaf, like `set_config`).
+///
+/// Once the state is gone, nothing can sign for the `one_sig_signer` PDA or close the PDAs derived
+/// from the state, so the close is refused while any of them still holds lamports: the signer PDA
+/// must be drained, and every executor, module and merkle root account closed beforehand.
+///
+/// The same goes for the signer PDAs of the other vaults that have signed for the account (see
+/// `OneSigState::used_vaults`), passed in ascending vault order as remaining accounts. What is left
+/// in the bounty escrow is swept to `recipient`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseOneSig<'info> {
+    #[account(
+        seeds = [ONE_SIG_SEED, state.key().as_ref()],
+        bump = state.load()?.bump,
+        constraint = one_sig_signer.lamports() == 0 @OneSigError::NonZeroSignerBalance,
+    )]
+    pub one_sig_signer: Signer<'info>,
+    /// CHECK: Refund target only, chosen by the multisig through the signed leaf.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = recipient,
+        constraint = state.load()?.executors_len == 0 @OneSigError::ExecutorsRemaining,
+        constraint = state.load()?.merkle_roots_len == 0 @OneSigError::MerkleRootsRemaining,
+        constraint = state.load()?.modules_len == 0 @OneSigError::ModulesRemaining,
+    )]
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [MERKLE_ROOT_REGISTRY_SEED, state.key().as_ref()],
+        bump = merkle_root_registry.bump,
+        close = recipient,
+    )]
+    pub merkle_root_registry: Account<'info, MerkleRootRegistry>,
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()],
+        bump = spending_limits.bump,
+        close = recipient,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    #[account(
+        mut,
+        seeds = [TARGET_PROGRAMS_SEED, state.key().as_ref()],
+        bump = target_programs.bump,
+        close = recipient,
+    )]
+    pub target_programs: Account<'info, TargetPrograms>,
+    /// CHECK: The system account the bounties of the OneSig account are paid from.
+    #[account(mut, seeds = [BOUNTY_ESCROW_SEED, state.key().as_ref()], bump)]
+    pub bounty_escrow: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CloseOneSig<'_> {
+    pub fn apply(ctx: &mut Context<CloseOneSig>) -> Result<()> {
+        let state = ctx.accounts.state.load()?;
+        let mut vault_signers = ctx.remaining_accounts.iter();
+        for vault_index in state.used_vaults() {
+            let vault_signer = vault_signers.next().ok_or(OneSigError::VaultSignersMissing)?;
+            verify_vault_signer(
+                &vault_signer.key(),
+                &ctx.accounts.state.key(),
+                state.bump,
+                vault_index,
+            )?;
+            require!(vault_signer.lamports() == 0, OneSigError::NonZeroSignerBalance);
+        }
+        drop(state);
+
+        let bounties = ctx.accounts.bounty_escrow.lamports();
+        if bounties > 0 {
+            withdraw_from_bounty_escrow(
+                &ctx.accounts.bounty_escrow,
+                &ctx.accounts.state.key(),
+                ctx.bumps.bounty_escrow,
+                &ctx.accounts.recipient,
+                bounties,
+            )?;
+            emit_cpi!(BountyEscrowWithdrawn {
+                one_sig_account: ctx.accounts.state.key(),
+                recipient: ctx.accounts.recipient.key(),
+                lamports: bounties,
+                event_version: EVENT_VERSION,
+            });
+        }
+        emit_cpi!(OneSigClosed {
+            one_sig_account: ctx.accounts.state.key(),
+            recipient: ctx.accounts.recipient.key(),
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
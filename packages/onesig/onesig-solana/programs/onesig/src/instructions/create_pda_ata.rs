@@ -0,0 +1,83 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+
+use crate::{
+    constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    errors::OneSigError,
+    execution::verify_vault_signer,
+    state::OneSigState,
+};
+
+/// Creates the associated token account of a vault signer PDA (the `one_sig_signer` for vault 0)
+/// for `mint`, so tokens can be sent to the OneSig account without a leaf to set it up first.
+///
+/// Permissionless: the account is owned by the PDA, and `payer` funds its rent. Creating an
+/// account that already exists is a no-op.
+#[derive(Accounts)]
+pub struct CreatePdaAta<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// CHECK: Checked in the handler to be the signer PDA of the requested vault.
+    pub one_sig_signer: UncheckedAccount<'info>,
+    /// CHECK: Derived from the wallet, mint and token program by the Associated Token Account
+    /// program, which rejects any other address.
+    #[account(mut)]
+    pub associated_token_account: UncheckedAccount<'info>,
+    /// CHECK: Validated as a mint of `token_program` by the Associated Token Account program.
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: Constrained to the SPL Token or Token-2022 program.
+    #[account(
+        constraint = token_program.key() == TOKEN_PROGRAM_ID ||
+            token_program.key() == TOKEN_2022_PROGRAM_ID @OneSigError::InvalidTokenProgram,
+    )]
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: Constrained to the Associated Token Account program.
+    #[account(address = ASSOCIATED_TOKEN_PROGRAM_ID)]
+    pub associated_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreatePdaAta<'_> {
+    pub fn apply(ctx: &mut Context<CreatePdaAta>, vault_index: u8) -> Result<()> {
+        let bump = ctx.accounts.one_sig_state.load()?.bump;
+        verify_vault_signer(
+            &ctx.accounts.one_sig_signer.key(),
+            &ctx.accounts.one_sig_state.key(),
+            bump,
+            vault_index,
+        )?;
+
+        // `CreateIdempotent` of the Associated Token Account program
+        let accounts = &ctx.accounts;
+        let instruction = Instruction {
+            program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(accounts.payer.key(), true),
+                AccountMeta::new(accounts.associated_token_account.key(), false),
+                AccountMeta::new_readonly(accounts.one_sig_signer.key(), false),
+                AccountMeta::new_readonly(accounts.mint.key(), false),
+                AccountMeta::new_readonly(accounts.system_program.key(), false),
+                AccountMeta::new_readonly(accounts.token_program.key(), false),
+            ],
+            data: vec![1],
+        };
+        invoke(
+            &instruction,
+            &[
+                accounts.payer.to_account_info(),
+                accounts.associated_token_account.to_account_info(),
+                accounts.one_sig_signer.to_account_info(),
+                accounts.mint.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.token_program.to_account_info(),
+            ],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        EVENT_VERSION, MODULE_AUTHORITY_SEED, MODULE_SEED, SPENDING_LIMITS_SEED,
+        TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    events::ModuleTransactionExecuted,
+    execution::{build_instruction, execute_instruction, verify_vault_signer},
+    state::{ModuleState, OneSigState, OneSigStateRef, SpendingLimits, TargetPrograms},
+    types::{ExecuteModuleTransactionParams, OneSigTransaction},
+    ID,
+};
+
+/// Executes an instruction on behalf of a registered module program, without a merkle proof.
+///
+/// The module calls in through CPI, signing with its `[MODULE_AUTHORITY_SEED, one_sig_state]` PDA,
+/// and is trusted to enforce its own constraints on what it executes. The instruction is still
+/// bound by the checks every executed leaf goes through (target policy, balance guard with
+/// `value`, spending limits), and cannot call into this program, so a module can never change the
+/// configuration of the OneSig account. The nonce is left untouched.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteModuleTransaction<'info> {
+    #[account(
+        seeds = [MODULE_AUTHORITY_SEED, one_sig_state.key().as_ref()],
+        seeds::program = module_state.module,
+        bump,
+    )]
+    pub module_authority: Signer<'info>,
+    /// CHECK: This is the same PDA used in invoke_signed when executing transactions: the
+    /// `one_sig_signer` PDA, or the signer PDA of the vault the transaction names, as checked by
+    /// `verify_vault_signer`.
+    pub one_sig_signer: UncheckedAccount<'info>,
+    /// Records the vault signing for the instruction.
+    #[account(mut)]
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    #[account(
+        seeds = [MODULE_SEED, one_sig_state.key().as_ref(), module_state.module.as_ref()],
+        bump = module_state.bump,
+    )]
+    pub module_state: Account<'info, ModuleState>,
+    /// Charged with what the executed instruction spends.
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, one_sig_state.key().as_ref()],
+        bump = spending_limits.bump,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    /// The programs listed by the target policy; only needed when one is set.
+    #[account(
+        seeds = [TARGET_PROGRAMS_SEED, one_sig_state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+}
+
+impl ExecuteModuleTransaction<'_> {
+    pub fn apply(
+        ctx: &mut Context<ExecuteModuleTransaction>,
+        params: ExecuteModuleTransactionParams,
+    ) -> Result<()> {
+        let ExecuteModuleTransactionParams { ix_data, value, vault_index } = params;
+        let transaction = OneSigTransaction {
+            // The data moves into the instruction built below
+            ix_data: vec![],
+            value,
+            token_outflow_limits: vec![],
+            guarded_accounts: vec![],
+            vault_index,
+            group: None,
+            not_before: None,
+            bounty: None,
+            proof: vec![],
+        };
+
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
+        let signer_bump = verify_vault_signer(
+            &ctx.accounts.one_sig_signer.key(),
+            &ctx.accounts.one_sig_state.key(),
+            one_sig_state.bump,
+            vault_index,
+        )?;
+        let instruction = build_instruction(
+            &ctx.accounts.one_sig_signer,
+            ix_data,
+            value,
+            ctx.remaining_accounts,
+        )?;
+        require!(instruction.program_id != ID, OneSigError::ModuleCannotCallOneSig);
+        let (chain_id, one_sig_id) = (one_sig_state.caip2_chain_id(), one_sig_state.one_sig_id);
+        drop(one_sig_state);
+
+        execute_instruction(
+            &ctx.accounts.one_sig_signer,
+            &ctx.accounts.one_sig_state,
+            signer_bump,
+            &mut ctx.accounts.spending_limits,
+            ctx.accounts.target_programs.as_ref(),
+            ctx.remaining_accounts,
+            instruction,
+            &transaction,
+        )?;
+
+        emit_cpi!(ModuleTransactionExecuted {
+            one_sig_account: ctx.accounts.one_sig_state.key(),
+            module: ctx.accounts.module_state.module,
+            vault_index,
+            chain_id,
+            one_sig_id,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
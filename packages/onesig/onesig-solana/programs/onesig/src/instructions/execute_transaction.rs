@@ -1,11 +1,23 @@
-use anchor_lang::prelude::*;
+use std::mem;
+
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID};
+use onesig_core::digest;
 
 use crate::{
-    constants::{MERKLE_ROOT_SEED, ONE_SIG_SEED},
+    constants::{
+        EVENT_VERSION, EXECUTOR_SEED, MERKLE_ROOT_SEED, SPENDING_LIMITS_SEED, TARGET_PROGRAMS_SEED,
+    },
     errors::OneSigError,
-    events::TransactionExecuted,
-    execution::{build_instruction, execute_instruction, resolve_merkle_root},
-    state::{MerkleRootState, OneSigState},
+    events::{BountyPaid, ExecutorReimbursed, TransactionExecuted},
+    execution::{
+        advance_nonce, build_instruction, check_not_before, execute_instruction, pay_bounty,
+        reimburse_executor, resets_nonce, resolve_merkle_root, verify_transaction_group,
+        verify_vault_signer,
+    },
+    state::{
+        ExecutorState, MerkleRootState, OneSigState, OneSigStateRef, OneSigStateRefMut,
+        SpendingLimits, TargetPrograms,
+    },
     types::ExecuteTransactionParams,
     validation::merkle::MerkleValidator,
 };
@@ -13,20 +25,61 @@ use crate::{
 #[event_cpi]
 #[derive(Accounts)]
 pub struct ExecuteTransaction<'info> {
+    /// Receives the executor reimbursement, if one is set, unless a `fee_payer` sponsors the
+    /// execution.
+    #[account(mut)]
     pub executor: Signer<'info>,
     /// CHECK: This is the same PDA used in invoke_signed when executing transactions.
-    /// It signs on behalf of the program in execute_transaction.
-    #[account(seeds = [ONE_SIG_SEED, one_sig_state.key().as_ref()], bump = one_sig_state.bump)]
+    /// It signs on behalf of the program in execute_transaction: the `one_sig_signer` PDA, or the
+    /// signer PDA of the vault the transaction names, as checked by `verify_vault_signer`.
+    /// Pays the executor reimbursement.
+    #[account(mut)]
     pub one_sig_signer: UncheckedAccount<'info>,
     #[account(mut)]
-    pub one_sig_state: Account<'info, OneSigState>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// Charged with what the executed instruction spends.
     #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, one_sig_state.key().as_ref()],
+        bump = spending_limits.bump,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    #[account(
+        mut,
         seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), merkle_root_state.merkle_root.as_ref()],
         bump = merkle_root_state.bump,
         constraint = merkle_root_state.expiry >= Clock::get()?.unix_timestamp @OneSigError::ExpiredMerkleRoot,
-        constraint = merkle_root_state.seed == one_sig_state.seed @OneSigError::SeedMismatch,
+        constraint = merkle_root_state.verified_under(&one_sig_state.load()?.seed) @OneSigError::SeedMismatch,
     )]
     pub merkle_root_state: Option<Account<'info, MerkleRootState>>,
+    /// The programs listed by the target policy; only needed when one is set.
+    #[account(
+        seeds = [TARGET_PROGRAMS_SEED, one_sig_state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+    /// The `executor`'s registration, with the programs it may target; only needed when
+    /// `executor_required` is set.
+    #[account(
+        seeds = [EXECUTOR_SEED, one_sig_state.key().as_ref(), executor.key().as_ref()],
+        bump = executor_state.bump,
+    )]
+    pub executor_state: Option<Account<'info, ExecutorState>>,
+    /// CHECK: The instructions sysvar, which lists the instructions executing the other leaves of
+    /// the transaction's group; only needed for a grouped leaf.
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    /// Sponsors the execution, paying the fees of the transaction in place of `executor`, and
+    /// receives the executor reimbursement instead. `executor` still signs, and is the one
+    /// `executor_required` applies to.
+    #[account(mut)]
+    pub fee_payer: Option<Signer<'info>>,
+    /// CHECK: The escrow the bounty of the leaf is paid from, checked against its seeds by
+    /// `pay_bounty`; only needed for a leaf with a bounty, executed while `executor_required` is
+    /// not set.
+    #[account(mut)]
+    pub bounty_escrow: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
 }
 
 impl ExecuteTransaction<'_> {
@@ -34,10 +87,14 @@ impl ExecuteTransaction<'_> {
     ///
     /// The root is resolved one of two ways: inline (signatures verified in this call) or from a
     /// pre-verified `MerkleRootState` PDA. Execution is permissionless unless `executor_required`
-    /// is set, in which case `executor` must be an approved executor.
+    /// is set, in which case `executor` must be an approved executor. Once the leaf ran,
+    /// `executor`, or the `fee_payer` sponsoring it, is paid the executor reimbursement of the
+    /// OneSig account, if any, by the vault's signer, and the bounty the leaf commits to, if any,
+    /// from the bounty escrow when the execution was permissionless. Every execution is charged to
+    /// the execution rate limit of the OneSig account.
     pub fn apply(
         ctx: &mut Context<ExecuteTransaction>,
-        params: &ExecuteTransactionParams,
+        params: ExecuteTransactionParams,
     ) -> Result<()> {
         // NOTE: Executor validation - Key difference from EVM implementation
         //
@@ -48,61 +105,159 @@ impl ExecuteTransaction<'_> {
         //
         // In Solana, this instruction supports option 1 and 3 only. A signer wishing to
         // self-submit uses `signer_execute_transaction` (see signer_execute_transaction.rs).
-        if ctx.accounts.one_sig_state.executors.executor_required {
-            let executor = ctx.accounts.executor.key();
-            require!(
-                ctx.accounts.one_sig_state.executors.executors.contains(&executor),
-                OneSigError::ExecutorRequired
-            );
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
+        if one_sig_state.executor_required() {
+            // The seeds constraint binds the executor PDA to `executor`, so its presence suffices
+            require!(ctx.accounts.executor_state.is_some(), OneSigError::ExecutorRequired);
         }
-        let ExecuteTransactionParams { transaction, merkle_root_verification } = params;
+        let ExecuteTransactionParams { mut transaction, merkle_root_verification } = params;
 
         // Verify merkle root and get the root hash
-        let merkle_root = resolve_merkle_root(
-            &ctx.accounts.one_sig_state,
-            ctx.accounts.merkle_root_state.as_ref(),
+        let (merkle_root, root_executions) = resolve_merkle_root(
+            &one_sig_state,
+            ctx.accounts.merkle_root_state.as_mut(),
             merkle_root_verification.as_ref(),
         )?;
 
-        // Get current nonce (needed for leaf encoding)
-        let nonce = ctx.accounts.one_sig_state.nonce;
+        // Get current nonce (needed for leaf encoding), and the seed namespacing it
+        let (nonce, seed) = (one_sig_state.nonce, one_sig_state.seed);
+        let signer_bump = verify_vault_signer(
+            &ctx.accounts.one_sig_signer.key(),
+            &ctx.accounts.one_sig_state.key(),
+            one_sig_state.bump,
+            transaction.vault_index,
+        )?;
 
-        // Build the OneSigInstruction from the transaction
-        let instruction =
-            build_instruction(&ctx.accounts.one_sig_signer, transaction, ctx.remaining_accounts)?;
+        // Build the OneSigInstruction from the transaction, taking its data
+        let instruction = build_instruction(
+            &ctx.accounts.one_sig_signer,
+            mem::take(&mut transaction.ix_data),
+            transaction.value,
+            ctx.remaining_accounts,
+        )?;
 
         // Encode the transaction leaf and verify against the Merkle proof
         let leaf = MerkleValidator::encode_leaf(
             &ctx.accounts.one_sig_state.key(),
-            ctx.accounts.one_sig_state.one_sig_id,
+            one_sig_state.one_sig_id,
             nonce,
             &instruction,
+            &transaction.token_outflow_limits,
+            &transaction.guarded_accounts,
+            transaction.vault_index,
+            one_sig_state.cluster_genesis_hash().as_ref(),
+            transaction.group.as_ref(),
+            transaction.not_before,
+            transaction.bounty,
         )?;
         MerkleValidator::verify_merkle_proof(&merkle_root, &transaction.proof, &leaf)?;
+        if let Some(group) = transaction.group.as_ref() {
+            verify_transaction_group(
+                ctx.accounts.instructions_sysvar.as_ref(),
+                &ctx.accounts.one_sig_state.key(),
+                group,
+            )?;
+        }
+        // An executor restricted to some programs only lands leaves calling them, and only
+        // while executors are gated at all
+        if one_sig_state.executor_required() {
+            if let Some(executor_state) = ctx.accounts.executor_state.as_ref() {
+                require!(
+                    executor_state.allows_target(&instruction.program_id),
+                    OneSigError::ExecutorTargetNotAllowed
+                );
+            }
+        }
+        // A leaf signed in advance waits for its not-before timestamp
+        let now = Clock::get()?.unix_timestamp;
+        check_not_before(transaction.not_before, now)?;
+        // Identify the chain and instance for the event before releasing the state
+        let (chain_id, one_sig_id) = (one_sig_state.caip2_chain_id(), one_sig_state.one_sig_id);
+        let permissionless = !one_sig_state.executor_required();
+        drop(one_sig_state);
+        // Charged before the leaf runs, which may change the limit or close the account
+        OneSigStateRefMut::load(&ctx.accounts.one_sig_state)?.record_execution(now)?;
+        // Identify what the leaf calls for the event, and whether it resets the nonce, before the
+        // instruction is consumed
+        let (program_id, ix_data_hash) =
+            (instruction.program_id, digest::ix_data_hash(&instruction.data));
+        let resets_nonce = resets_nonce(&instruction);
 
         // Execute the verified OneSigInstruction
-        execute_instruction(
+        let lamports_spent = execute_instruction(
             &ctx.accounts.one_sig_signer,
             &ctx.accounts.one_sig_state,
+            signer_bump,
+            &mut ctx.accounts.spending_limits,
+            ctx.accounts.target_programs.as_ref(),
             ctx.remaining_accounts,
             instruction,
+            &transaction,
+        )?;
+        // A sponsor paying the fees in place of the executor is reimbursed in its place
+        let recipient = match ctx.accounts.fee_payer.as_ref() {
+            Some(fee_payer) => fee_payer.to_account_info(),
+            None => ctx.accounts.executor.to_account_info(),
+        };
+        let reimbursed = reimburse_executor(
+            &ctx.accounts.one_sig_signer,
+            &ctx.accounts.one_sig_state.key(),
+            signer_bump,
+            transaction.vault_index,
+            &mut ctx.accounts.spending_limits,
+            &recipient,
         )?;
+        // Bounties reward whoever lands the leaf, so only executions open to anyone earn them
+        let bounty = match transaction.bounty {
+            Some(bounty) if permissionless && bounty > 0 => {
+                pay_bounty(
+                    ctx.accounts.bounty_escrow.as_ref(),
+                    &ctx.accounts.one_sig_state.key(),
+                    &recipient,
+                    bounty,
+                )?;
+                bounty
+            },
+            _ => 0,
+        };
 
-        // Bump the nonce for replay protection: reload to see any state the executed instruction
-        // mutated, reject a mutated nonce, then increment.
-        ctx.accounts.one_sig_state.reload()?;
-        require!(
-            ctx.accounts.one_sig_state.nonce == nonce,
-            OneSigError::NonceMutatedDuringExecution
-        );
-        ctx.accounts.one_sig_state.nonce = nonce + 1;
+        // Bump the nonce for replay protection
+        advance_nonce(&ctx.accounts.one_sig_state, nonce, &seed, resets_nonce)?;
 
         // Emit successful transaction event
         emit_cpi!(TransactionExecuted {
             one_sig_account: ctx.accounts.one_sig_state.key(),
             merkle_root,
             nonce,
+            chain_id,
+            one_sig_id,
+            executor: ctx.accounts.executor.key(),
+            program_id,
+            ix_data_hash,
+            value: transaction.value,
+            lamports_spent,
+            root_executions,
+            event_version: EVENT_VERSION,
         });
+        if reimbursed > 0 {
+            emit_cpi!(ExecutorReimbursed {
+                one_sig_account: ctx.accounts.one_sig_state.key(),
+                executor: ctx.accounts.executor.key(),
+                lamports: reimbursed,
+                event_version: EVENT_VERSION,
+                fee_payer: ctx.accounts.fee_payer.as_ref().map(|fee_payer| fee_payer.key()),
+            });
+        }
+        if bounty > 0 {
+            emit_cpi!(BountyPaid {
+                one_sig_account: ctx.accounts.one_sig_state.key(),
+                executor: ctx.accounts.executor.key(),
+                recipient: *recipient.key,
+                nonce,
+                lamports: bounty,
+                event_version: EVENT_VERSION,
+            });
+        }
         Ok(())
     }
 }
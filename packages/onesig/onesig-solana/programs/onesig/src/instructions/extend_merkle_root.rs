@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        EVENT_VERSION, MERKLE_ROOT_REGISTRY_SEED, MERKLE_ROOT_SEED, MERKLE_ROOT_STATE_VERSION,
+    },
+    errors::OneSigError,
+    events::MerkleRootExtended,
+    instructions::migrate_state::grow_zeroed,
+    state::{MerkleRootRegistry, MerkleRootState, OneSigState, OneSigStateRef},
+    types::VerifyMerkleRootParams,
+    validation::merkle::MerkleValidator,
+};
+
+/// Re-verifies an already persisted merkle root with a later expiry, so an expiring root can be
+/// kept alive without closing and re-creating its `MerkleRootState`.
+///
+/// The fresh signatures replace everything the original verification recorded: the seed, the
+/// signers and the execution cap are taken from this call, as `verify_merkle_root` would. When the
+/// threshold rose since, the account grows to record the extra signers, at the expense of `payer`.
+#[derive(Accounts)]
+#[instruction(params: VerifyMerkleRootParams)]
+pub struct ExtendMerkleRoot<'info> {
+    #[account(
+        mut,
+        seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), params.merkle_root.as_ref()],
+        bump = merkle_root_state.bump,
+        constraint = merkle_root_state.version == MERKLE_ROOT_STATE_VERSION @OneSigError::StateVersionMismatch,
+        constraint = params.expiry > merkle_root_state.expiry @OneSigError::ExpiryNotExtended,
+    )]
+    pub merkle_root_state: Account<'info, MerkleRootState>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [MERKLE_ROOT_REGISTRY_SEED, one_sig_state.key().as_ref()],
+        bump = merkle_root_registry.bump,
+    )]
+    pub merkle_root_registry: Account<'info, MerkleRootRegistry>,
+    /// Only needed for the account to grow.
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+impl ExtendMerkleRoot<'_> {
+    pub fn apply(
+        ctx: &mut Context<ExtendMerkleRoot>,
+        params: &VerifyMerkleRootParams,
+    ) -> Result<()> {
+        let VerifyMerkleRootParams { merkle_root, expiry, max_executions, signatures } = params;
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
+
+        let signed_by = MerkleValidator::verify_merkle_root_signers(
+            &one_sig_state.seed,
+            &one_sig_state.multisig(),
+            merkle_root,
+            *expiry,
+            *max_executions,
+            signatures,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let space = MerkleRootState::space(signed_by.len());
+        if ctx.accounts.merkle_root_state.to_account_info().data_len() < space {
+            let (Some(payer), Some(_)) = (&ctx.accounts.payer, &ctx.accounts.system_program) else {
+                return err!(OneSigError::MissingPayer);
+            };
+            grow_zeroed(
+                &payer.to_account_info(),
+                &ctx.accounts.merkle_root_state.to_account_info(),
+                space,
+            )?;
+        }
+
+        let merkle_root_state = &mut ctx.accounts.merkle_root_state;
+        let previous_expiry = merkle_root_state.expiry;
+        merkle_root_state.seed_commitment = MerkleRootState::seed_commitment(&one_sig_state.seed);
+        merkle_root_state.expiry = *expiry;
+        merkle_root_state.signed_by = signed_by;
+        merkle_root_state.remaining_executions = (*max_executions > 0).then_some(*max_executions);
+
+        // Keep the listed expiry in sync
+        let address = merkle_root_state.key();
+        if let Some(entry) = ctx
+            .accounts
+            .merkle_root_registry
+            .entries
+            .iter_mut()
+            .find(|e| e.address == address)
+        {
+            entry.expiry = *expiry;
+        }
+
+        emit!(MerkleRootExtended {
+            one_sig_account: ctx.accounts.one_sig_state.key(),
+            merkle_root: *merkle_root,
+            previous_expiry,
+            expiry: *expiry,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -1,50 +1,121 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::ONE_SIG_SEED, events::OneSigInitialized, state::OneSigState,
-    types::InitOneSigParams, ID,
+    constants::{
+        EVENT_VERSION, EXECUTOR_SEED, MERKLE_ROOT_REGISTRY_SEED, ONE_SIG_SEED,
+        ONE_SIG_STATE_VERSION, SIGNERS_MAX_LEN, SPENDING_LIMITS_SEED, TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    events::OneSigInitialized,
+    instructions::add_executor::create_executor_state,
+    state::{MerkleRootRegistry, OneSigState, OneSigStateRefMut, SpendingLimits, TargetPrograms},
+    types::{InitOneSigParams, SelfCpiTarget},
+    ID,
 };
 
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(params: InitOneSigParams)]
 pub struct InitOneSig<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
         init,
         payer = payer,
-        space = 8 + OneSigState::INIT_SPACE,
+        space = OneSigState::space(params.signers_capacity),
     )]
-    pub state: Account<'info, OneSigState>,
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        init,
+        payer = payer,
+        space = MerkleRootRegistry::space(0),
+        seeds = [MERKLE_ROOT_REGISTRY_SEED, state.key().as_ref()],
+        bump,
+    )]
+    pub merkle_root_registry: Account<'info, MerkleRootRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = SpendingLimits::space(0),
+        seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()],
+        bump,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TargetPrograms::INIT_SPACE,
+        seeds = [TARGET_PROGRAMS_SEED, state.key().as_ref()],
+        bump,
+    )]
+    pub target_programs: Account<'info, TargetPrograms>,
     pub system_program: Program<'info, System>,
 }
 
-impl InitOneSig<'_> {
-    pub fn apply(ctx: &mut Context<InitOneSig>, params: &InitOneSigParams) -> Result<()> {
-        let InitOneSigParams { one_sig_id, seed, signers, threshold, executors, executor_required } =
-            params;
-        ctx.accounts.state.seed = *seed;
-        ctx.accounts.state.nonce = 0;
-        ctx.accounts.state.one_sig_id = *one_sig_id;
+impl<'info> InitOneSig<'info> {
+    pub fn apply(
+        ctx: &mut Context<'info, InitOneSig<'info>>,
+        params: &InitOneSigParams,
+    ) -> Result<()> {
+        let InitOneSigParams {
+            one_sig_id,
+            seed,
+            signers,
+            threshold,
+            executors,
+            executor_required,
+            signers_capacity,
+        } = params;
+        require!(*signers_capacity as usize <= SIGNERS_MAX_LEN, OneSigError::InvalidSignersLen);
+        require!(
+            ctx.remaining_accounts.len() == executors.len() && executors.len() <= u16::MAX as usize,
+            OneSigError::InvalidExecutorsLen
+        );
+
+        ctx.accounts.merkle_root_registry.bump = ctx.bumps.merkle_root_registry;
+        ctx.accounts.spending_limits.bump = ctx.bumps.spending_limits;
+        ctx.accounts.target_programs.bump = ctx.bumps.target_programs;
 
         // Find the one_sig_signer PDA and bump
         let (_, bump) = Pubkey::find_program_address(
             &[ONE_SIG_SEED, &ctx.accounts.state.key().to_bytes()],
             &ID,
         );
-        ctx.accounts.state.bump = bump;
+
+        // Record the header first: the signer capacity determines the layout of the account data
+        {
+            let mut state = ctx.accounts.state.load_init()?;
+            state.seed = *seed;
+            state.nonce = 0;
+            state.one_sig_id = *one_sig_id;
+            state.bump = bump;
+            state.version = ONE_SIG_STATE_VERSION;
+            state.signers_capacity = *signers_capacity;
+            state.executors_len = executors.len() as u16;
+            state.self_cpi_targets = SelfCpiTarget::mask(&SelfCpiTarget::DEFAULT);
+        }
+        let mut state = OneSigStateRefMut::load(&ctx.accounts.state)?;
 
         // Add signers and set threshold into the multisig
         for signer in signers {
-            ctx.accounts.state.multisig.add_signer(*signer)?;
+            state.add_signer(*signer)?;
         }
-        ctx.accounts.state.multisig.set_threshold(*threshold)?;
+        state.set_threshold(*threshold)?;
+
+        state.set_executor_required(*executor_required)?;
+        let chain_id = state.caip2_chain_id();
+        drop(state);
 
-        // Add executors and set executor required
-        for executor in executors {
-            ctx.accounts.state.executors.add_executor(*executor)?;
+        // Create an executor PDA for each executor, passed in the same order as remaining accounts
+        let state_key = ctx.accounts.state.key();
+        for (executor, executor_state) in executors.iter().zip(ctx.remaining_accounts) {
+            let (expected, bump) = Pubkey::find_program_address(
+                &[EXECUTOR_SEED, state_key.as_ref(), executor.as_ref()],
+                &ID,
+            );
+            require_keys_eq!(executor_state.key(), expected, OneSigError::InvalidExecutor);
+            create_executor_state(&ctx.accounts.payer, executor_state, &state_key, executor, bump)?;
         }
-        ctx.accounts.state.executors.set_executor_required(*executor_required)?;
 
         // Emit the event
         emit_cpi!(OneSigInitialized {
@@ -55,6 +126,8 @@ impl InitOneSig<'_> {
             signers: signers.clone(),
             executors: executors.clone(),
             executor_required: *executor_required,
+            chain_id,
+            event_version: EVENT_VERSION,
         });
         Ok(())
     }
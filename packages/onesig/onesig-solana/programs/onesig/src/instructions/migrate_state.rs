@@ -0,0 +1,312 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+use bytemuck::Zeroable;
+
+use crate::{
+    constants::{
+        EXECUTOR_SEED, MERKLE_ROOT_REGISTRY_SEED, MERKLE_ROOT_SEED, MERKLE_ROOT_STATE_VERSION,
+        ONE_SIG_SEED, ONE_SIG_STATE_VERSION, SPENDING_LIMITS_SEED, TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    instructions::add_executor::{create_executor_state, create_pda},
+    state::{
+        MerkleRootEntry, MerkleRootRegistry, MerkleRootState, OneSigState, OneSigStateRef,
+        OneSigStateRefMut, SpendingLimits, TargetPrograms,
+    },
+    types::{Address, Hash, SelfCpiTarget},
+    ID,
+};
+
+/// Discriminator of the Borsh-encoded `OneSigState` of the first release, the first 8 bytes of
+/// `sha256("account:OneSigState")`
+pub const BASELINE_ONE_SIG_STATE_DISCRIMINATOR: [u8; 8] = [19, 121, 0, 170, 35, 130, 183, 15];
+
+const BASELINE_SIGNERS_MAX_LEN: usize = 20;
+const BASELINE_EXECUTORS_MAX_LEN: usize = 277;
+
+/// Converts the accounts of the first release of the program, the only layouts that were deployed
+/// before the current ones, in place.
+///
+/// Permissionless, as the layouts are recognised exactly (see `migrate_baseline_one_sig_state`)
+/// and anything else is rejected: a conversion only re-encodes what the account held, so it cannot
+/// change anything the multisig authorized. Converting a `OneSigState` creates the PDAs
+/// `init_one_sig` creates, and an `ExecutorState` for each of its executors, passed first among the
+/// remaining accounts in the order of its executor list. `MerkleRootState` accounts of this OneSig
+/// to convert follow; any rent is paid by `payer`.
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: A `OneSigState` in the layout of the first release, converted by the handler, or in
+    /// the current one.
+    #[account(mut, owner = ID @OneSigError::InvalidMigrationAccount)]
+    pub state: UncheckedAccount<'info>,
+    /// CHECK: Created when converting an account of the first release, which had none.
+    #[account(mut, seeds = [MERKLE_ROOT_REGISTRY_SEED, state.key().as_ref()], bump)]
+    pub merkle_root_registry: UncheckedAccount<'info>,
+    /// CHECK: Created when converting an account of the first release, which had none.
+    #[account(mut, seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()], bump)]
+    pub spending_limits: UncheckedAccount<'info>,
+    /// CHECK: Created when converting an account of the first release, which had none.
+    #[account(mut, seeds = [TARGET_PROGRAMS_SEED, state.key().as_ref()], bump)]
+    pub target_programs: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateState<'info> {
+    pub fn apply(ctx: &mut Context<'info, MigrateState<'info>>) -> Result<()> {
+        let is_baseline = ctx
+            .accounts
+            .state
+            .try_borrow_data()?
+            .starts_with(&BASELINE_ONE_SIG_STATE_DISCRIMINATOR);
+        let mut remaining_accounts = ctx.remaining_accounts;
+        if is_baseline {
+            let executors =
+                migrate_baseline_one_sig_state(ctx.accounts, &ctx.bumps, remaining_accounts)?;
+            remaining_accounts = &remaining_accounts[executors..];
+        } else {
+            // Already converted: only its roots are left to convert
+            let state = AccountLoader::<OneSigState>::try_from(ctx.accounts.state.as_ref())
+                .map_err(|_| OneSigError::InvalidMigrationAccount)?;
+            OneSigStateRef::load(&state)?;
+        }
+
+        for account in remaining_accounts {
+            migrate_merkle_root_state(ctx.accounts, account)?;
+        }
+        Ok(())
+    }
+}
+
+// `OneSigState` of the first release: its `Multisig` and `Executors` fields inlined, which Borsh
+// encodes alike, in an account of exactly `8 + INIT_SPACE` bytes, which it never resized
+#[derive(AnchorDeserialize, InitSpace)]
+struct BaselineOneSigState {
+    one_sig_id: u64,
+    seed: Hash,
+    bump: u8,
+    nonce: u64,
+    #[max_len(BASELINE_SIGNERS_MAX_LEN)]
+    signers: Vec<Address>,
+    threshold: u8,
+    #[max_len(BASELINE_EXECUTORS_MAX_LEN)]
+    executors: Vec<Pubkey>,
+    executor_required: bool,
+}
+
+// The account is recognised by its discriminator and size, a Borsh decode within the bounds of the
+// first release, and the bump of its `one_sig_signer` PDA; its signers and threshold are checked
+// again as they are re-added. Bytes past the encoding are ignored, as Anchor left stale ones there
+// when a list shrank.
+//
+// The signers move to the zero-copy layout, with room for as many as the first release allowed,
+// and the executors to `ExecutorState` PDAs. The registry, spending limits and target programs
+// start empty, and leaves get the `SelfCpiTarget::DEFAULT` of new accounts. The account shrinks
+// in place, the rent it no longer needs staying in it until it is closed. Returns the number of
+// `executor_states` used.
+fn migrate_baseline_one_sig_state<'info>(
+    accounts: &MigrateState<'info>,
+    bumps: &MigrateStateBumps,
+    executor_states: &[AccountInfo<'info>],
+) -> Result<usize> {
+    let payer = accounts.payer.as_ref();
+    let state = accounts.state.as_ref();
+    let state_key = state.key();
+    require!(
+        state.data_len() == 8 + BaselineOneSigState::INIT_SPACE,
+        OneSigError::InvalidMigrationAccount
+    );
+    let baseline = BaselineOneSigState::deserialize(&mut &state.try_borrow_data()?[8..])
+        .map_err(|_| OneSigError::InvalidMigrationAccount)?;
+    let (_, bump) = Pubkey::find_program_address(&[ONE_SIG_SEED, state_key.as_ref()], &ID);
+    require!(
+        baseline.signers.len() <= BASELINE_SIGNERS_MAX_LEN &&
+            baseline.executors.len() <= BASELINE_EXECUTORS_MAX_LEN &&
+            baseline.bump == bump,
+        OneSigError::InvalidMigrationAccount
+    );
+    require!(executor_states.len() >= baseline.executors.len(), OneSigError::InvalidExecutorsLen);
+
+    let mut header = OneSigState::zeroed();
+    header.one_sig_id = baseline.one_sig_id;
+    header.nonce = baseline.nonce;
+    header.seed = baseline.seed;
+    header.signers_capacity = BASELINE_SIGNERS_MAX_LEN as u16;
+    header.executors_len = baseline.executors.len() as u16;
+    header.bump = baseline.bump;
+    header.version = ONE_SIG_STATE_VERSION;
+    header.self_cpi_targets = SelfCpiTarget::mask(&SelfCpiTarget::DEFAULT);
+    let space = OneSigState::space(BASELINE_SIGNERS_MAX_LEN as u16);
+    state.resize(space)?;
+    {
+        let mut data = state.try_borrow_mut_data()?;
+        data.fill(0);
+        data[..OneSigState::HEADER_OFFSET].copy_from_slice(OneSigState::DISCRIMINATOR);
+        data[OneSigState::HEADER_OFFSET..OneSigState::SIGNERS_OFFSET]
+            .copy_from_slice(bytemuck::bytes_of(&header));
+    }
+    // Re-add the signers the way `init_one_sig` does, checking them again
+    let loader = AccountLoader::<OneSigState>::try_from(state)?;
+    let mut one_sig_state = OneSigStateRefMut::load(&loader)?;
+    for signer in baseline.signers {
+        one_sig_state.add_signer(signer)?;
+    }
+    one_sig_state.set_threshold(baseline.threshold)?;
+    one_sig_state.set_executor_required(baseline.executor_required)?;
+    drop(one_sig_state);
+
+    let registry = accounts.merkle_root_registry.as_ref();
+    let seeds = [MERKLE_ROOT_REGISTRY_SEED, state_key.as_ref(), &[bumps.merkle_root_registry]];
+    create_pda(payer, registry, MerkleRootRegistry::space(0), &seeds)?;
+    MerkleRootRegistry { entries: vec![], bump: bumps.merkle_root_registry }
+        .try_serialize(&mut &mut registry.try_borrow_mut_data()?[..])?;
+
+    let spending_limits = accounts.spending_limits.as_ref();
+    let seeds = [SPENDING_LIMITS_SEED, state_key.as_ref(), &[bumps.spending_limits]];
+    create_pda(payer, spending_limits, SpendingLimits::space(0), &seeds)?;
+    SpendingLimits { entries: vec![], bump: bumps.spending_limits, executor_reimbursement: 0 }
+        .try_serialize(&mut &mut spending_limits.try_borrow_mut_data()?[..])?;
+
+    let target_programs = accounts.target_programs.as_ref();
+    let seeds = [TARGET_PROGRAMS_SEED, state_key.as_ref(), &[bumps.target_programs]];
+    create_pda(payer, target_programs, 8 + TargetPrograms::INIT_SPACE, &seeds)?;
+    TargetPrograms { programs: vec![], bump: bumps.target_programs }
+        .try_serialize(&mut &mut target_programs.try_borrow_mut_data()?[..])?;
+
+    for (executor, executor_state) in baseline.executors.iter().zip(executor_states) {
+        let (expected, bump) = Pubkey::find_program_address(
+            &[EXECUTOR_SEED, state_key.as_ref(), executor.as_ref()],
+            &ID,
+        );
+        require_keys_eq!(executor_state.key(), expected, OneSigError::InvalidExecutor);
+        create_executor_state(payer, executor_state, &state_key, executor, bump)?;
+    }
+    Ok(baseline.executors.len())
+}
+
+// `MerkleRootState` of the first release, in an account of exactly `8 + INIT_SPACE` bytes. No
+// account of the current layout has that size (see `MerkleRootState::space`).
+#[derive(AnchorDeserialize, InitSpace)]
+struct BaselineMerkleRootState {
+    merkle_root: Hash,
+    seed: Hash,
+    expiry: i64,
+    rent_payer: Pubkey,
+    #[max_len(BASELINE_SIGNERS_MAX_LEN)]
+    signed_by: Vec<Address>,
+    bump: u8,
+}
+
+// A root of the first release is recognised by its size and a Borsh decode within its bounds, and
+// re-encoded keeping a commitment to its seed, unlimited in executions as it was, counting them
+// from 0. The account shrinks to its signers, the rent freed staying in it until it is closed. It
+// is then registered and counted like a root verified now, so the OneSig account cannot be closed
+// before it. A root already in the current layout must have its version and the size of its
+// signers.
+fn migrate_merkle_root_state<'info>(
+    accounts: &MigrateState<'info>,
+    merkle_root_state: &AccountInfo<'info>,
+) -> Result<()> {
+    let one_sig_state = &accounts.state.key();
+    require!(
+        *merkle_root_state.owner == ID &&
+            merkle_root_state.is_writable &&
+            merkle_root_state.try_borrow_data()?.starts_with(MerkleRootState::DISCRIMINATOR),
+        OneSigError::InvalidMigrationAccount
+    );
+
+    let is_baseline = merkle_root_state.data_len() == 8 + BaselineMerkleRootState::INIT_SPACE;
+    let root = if is_baseline {
+        let baseline =
+            BaselineMerkleRootState::deserialize(&mut &merkle_root_state.try_borrow_data()?[8..])
+                .map_err(|_| OneSigError::InvalidMigrationAccount)?;
+        require!(
+            baseline.signed_by.len() <= BASELINE_SIGNERS_MAX_LEN,
+            OneSigError::InvalidMigrationAccount
+        );
+        MerkleRootState {
+            merkle_root: baseline.merkle_root,
+            version: MERKLE_ROOT_STATE_VERSION,
+            bump: baseline.bump,
+            expiry: baseline.expiry,
+            seed_commitment: MerkleRootState::seed_commitment(&baseline.seed),
+            rent_payer: baseline.rent_payer,
+            executions: 0,
+            remaining_executions: None,
+            signed_by: baseline.signed_by,
+        }
+    } else {
+        let root = MerkleRootState::try_deserialize(&mut &merkle_root_state.try_borrow_data()?[..])
+            .map_err(|_| OneSigError::InvalidMigrationAccount)?;
+        require!(root.version == MERKLE_ROOT_STATE_VERSION, OneSigError::StateVersionMismatch);
+        require!(
+            merkle_root_state.data_len() == MerkleRootState::space(root.signed_by.len()),
+            OneSigError::InvalidMigrationAccount
+        );
+        root
+    };
+    // Only roots verified for this OneSig account can be migrated alongside it
+    let expected = Pubkey::create_program_address(
+        &[MERKLE_ROOT_SEED, one_sig_state.as_ref(), root.merkle_root.as_ref(), &[root.bump]],
+        &ID,
+    )
+    .map_err(|_| OneSigError::InvalidMigrationAccount)?;
+    require_keys_eq!(merkle_root_state.key(), expected, OneSigError::InvalidMigrationAccount);
+    if !is_baseline {
+        return Ok(());
+    }
+
+    merkle_root_state.resize(MerkleRootState::space(root.signed_by.len()))?;
+    {
+        let mut data = merkle_root_state.try_borrow_mut_data()?;
+        data.fill(0);
+        root.try_serialize(&mut &mut data[..])?;
+    }
+
+    let registry_info = accounts.merkle_root_registry.as_ref();
+    let mut registry =
+        MerkleRootRegistry::try_deserialize(&mut &registry_info.try_borrow_data()?[..])?;
+    registry
+        .entries
+        .push(MerkleRootEntry { address: merkle_root_state.key(), expiry: root.expiry });
+    let space = MerkleRootRegistry::space(registry.entries.len());
+    grow_zeroed(accounts.payer.as_ref(), registry_info, space)?;
+    registry.try_serialize(&mut &mut registry_info.try_borrow_mut_data()?[..])?;
+
+    let loader = AccountLoader::<OneSigState>::try_from(accounts.state.as_ref())?;
+    let mut state = loader.load_mut()?;
+    state.merkle_roots_len =
+        state.merkle_roots_len.checked_add(1).ok_or(OneSigError::TooManyMerkleRoots)?;
+    Ok(())
+}
+
+// Grows `account` to `space` bytes if it is smaller, zeroing the added tail, with `payer` topping
+// up its rent.
+pub(crate) fn grow_zeroed<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let rent = Rent::get()?.minimum_balance(space);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program::ID,
+                Transfer { from: payer.clone(), to: account.clone() },
+            ),
+            top_up,
+        )?;
+    }
+    let len = account.data_len();
+    account.resize(space)?;
+    account.try_borrow_mut_data()?[len..].fill(0);
+    Ok(())
+}
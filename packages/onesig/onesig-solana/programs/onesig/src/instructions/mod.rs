@@ -1,13 +1,47 @@
+pub mod add_executor;
+pub mod add_module;
+pub mod check_transaction;
+pub mod close_leaf_failure;
 pub mod close_merkle_root;
+pub mod close_one_sig;
+pub mod create_pda_ata;
+pub mod execute_module_transaction;
 pub mod execute_transaction;
+pub mod extend_merkle_root;
 pub mod init_one_sig;
+pub mod migrate_state;
+pub mod record_leaf_failure;
+pub mod remove_executor;
+pub mod remove_module;
+pub mod remove_spending_limit;
+pub mod resize_state;
 pub mod set_config;
+pub mod set_executor_targets;
+pub mod set_spending_limit;
 pub mod signer_execute_transaction;
 pub mod verify_merkle_root;
+pub mod withdraw_bounty_escrow;
 
+pub use add_executor::*;
+pub use add_module::*;
+pub use check_transaction::*;
+pub use close_leaf_failure::*;
 pub use close_merkle_root::*;
+pub use close_one_sig::*;
+pub use create_pda_ata::*;
+pub use execute_module_transaction::*;
 pub use execute_transaction::*;
+pub use extend_merkle_root::*;
 pub use init_one_sig::*;
+pub use migrate_state::*;
+pub use record_leaf_failure::*;
+pub use remove_executor::*;
+pub use remove_module::*;
+pub use remove_spending_limit::*;
+pub use resize_state::*;
 pub use set_config::*;
+pub use set_executor_targets::*;
+pub use set_spending_limit::*;
 pub use signer_execute_transaction::*;
 pub use verify_merkle_root::*;
+pub use withdraw_bounty_escrow::*;
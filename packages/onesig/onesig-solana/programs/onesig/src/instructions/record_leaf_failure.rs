@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        EVENT_VERSION, EXECUTOR_SEED, LEAF_FAILURE_SEED, MERKLE_ROOT_SEED, TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    events::LeafFailureRecorded,
+    instructions::check_transaction::{CheckTransaction, LeafCheckAccounts},
+    state::{
+        ExecutorState, LeafFailure, MerkleRootState, OneSigState, OneSigStateRef, TargetPrograms,
+    },
+    types::RecordLeafFailureParams,
+};
+
+/// The accounts of `execute_transaction` the checks read, as `check_transaction` takes them, with
+/// the `LeafFailure` PDA to create.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: RecordLeafFailureParams)]
+pub struct RecordLeafFailure<'info> {
+    /// The executor the leaf is checked for, which pays the rent of the marker.
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    /// CHECK: The vault signer the leaf would be executed with, checked by
+    /// `verify_vault_signer` as one of the checks.
+    pub one_sig_signer: UncheckedAccount<'info>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// Only bound to its root here: its expiry, seed and executions left are checks.
+    #[account(
+        seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), merkle_root_state.merkle_root.as_ref()],
+        bump = merkle_root_state.bump,
+    )]
+    pub merkle_root_state: Option<Account<'info, MerkleRootState>>,
+    #[account(
+        seeds = [TARGET_PROGRAMS_SEED, one_sig_state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+    #[account(
+        seeds = [EXECUTOR_SEED, one_sig_state.key().as_ref(), executor.key().as_ref()],
+        bump = executor_state.bump,
+    )]
+    pub executor_state: Option<Account<'info, ExecutorState>>,
+    #[account(
+        init,
+        payer = executor,
+        space = 8 + LeafFailure::INIT_SPACE,
+        seeds = [LEAF_FAILURE_SEED, one_sig_state.key().as_ref(), params.leaf.as_ref()],
+        bump,
+    )]
+    pub leaf_failure: Account<'info, LeafFailure>,
+    pub system_program: Program<'info, System>,
+}
+
+impl RecordLeafFailure<'_> {
+    /// Records a leaf as stuck, without executing it: a failing call aborts the transaction that
+    /// makes it, so the failure of a leaf is recorded apart from its execution, which the executor
+    /// simulated. The program vouches for the leaf itself, which must pass every check
+    /// `check_transaction` makes at the current nonce, so that only the call it makes can fail;
+    /// the error it failed with is the one the executor observed.
+    ///
+    /// The leaf stays executable: the marker only lets monitors see the stuck leaf on-chain, and
+    /// executors retry it. Once the nonce moves past it, `close_leaf_failure` reclaims the rent.
+    pub fn apply(
+        ctx: &mut Context<RecordLeafFailure>,
+        params: RecordLeafFailureParams,
+    ) -> Result<()> {
+        let RecordLeafFailureParams { execution, leaf, reported_error_code } = params;
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
+        let now = Clock::get()?.unix_timestamp;
+        let accounts = LeafCheckAccounts {
+            one_sig_signer: &ctx.accounts.one_sig_signer,
+            one_sig_state: &ctx.accounts.one_sig_state,
+            merkle_root_state: ctx.accounts.merkle_root_state.as_ref(),
+            target_programs: ctx.accounts.target_programs.as_ref(),
+            executor_state: ctx.accounts.executor_state.as_ref(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+        let checked = CheckTransaction::first_failure(&accounts, &one_sig_state, execution, now)
+            .map_err(|(_, error)| error)?;
+        require!(checked == leaf, OneSigError::LeafMismatch);
+
+        let nonce = one_sig_state.nonce;
+        ctx.accounts.leaf_failure.set_inner(LeafFailure {
+            one_sig_state: ctx.accounts.one_sig_state.key(),
+            leaf,
+            bump: ctx.bumps.leaf_failure,
+            nonce,
+            seed_commitment: MerkleRootState::seed_commitment(&one_sig_state.seed),
+            reported_error_code,
+            recorded_at: now,
+            rent_payer: ctx.accounts.executor.key(),
+        });
+        drop(one_sig_state);
+
+        emit_cpi!(LeafFailureRecorded {
+            one_sig_account: ctx.accounts.one_sig_state.key(),
+            leaf,
+            nonce,
+            reported_error_code,
+            executor: ctx.accounts.executor.key(),
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, EXECUTOR_SEED, ONE_SIG_SEED},
+    errors::OneSigError,
+    events::ExecutorRemoved,
+    state::{ExecutorState, OneSigState},
+};
+
+/// Deregisters an executor by closing its `ExecutorState` PDA. Authorized by the multisig (executed
+/// as a leaf, like `set_config`); rent is refunded to the recorded `rent_payer`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveExecutor<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// CHECK: Refund target only. Constrained to equal the recorded `rent_payer`.
+    #[account(mut, address = executor_state.rent_payer @OneSigError::InvalidRentPayer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, state.key().as_ref(), executor_state.executor.as_ref()],
+        bump = executor_state.bump,
+        close = rent_payer,
+    )]
+    pub executor_state: Account<'info, ExecutorState>,
+}
+
+impl RemoveExecutor<'_> {
+    pub fn apply(ctx: &mut Context<RemoveExecutor>) -> Result<()> {
+        {
+            let mut state = ctx.accounts.state.load_mut()?;
+            state.executors_len =
+                state.executors_len.checked_sub(1).ok_or(OneSigError::InvalidExecutorsLen)?;
+        }
+        emit_cpi!(ExecutorRemoved {
+            one_sig_account: ctx.accounts.state.key(),
+            executor: ctx.accounts.executor_state.executor,
+            event_version: EVENT_VERSION,
+            effective_at: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
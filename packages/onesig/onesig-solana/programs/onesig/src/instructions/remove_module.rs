@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, MODULE_SEED, ONE_SIG_SEED},
+    errors::OneSigError,
+    events::ModuleRemoved,
+    state::{ModuleState, OneSigState},
+};
+
+/// Deregisters a module by closing its `ModuleState` PDA. Authorized by the multisig (executed as
+/// a leaf, like `set_config`); rent is refunded to the recorded `rent_payer`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveModule<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// CHECK: Refund target only. Constrained to equal the recorded `rent_payer`.
+    #[account(mut, address = module_state.rent_payer @OneSigError::InvalidRentPayer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [MODULE_SEED, state.key().as_ref(), module_state.module.as_ref()],
+        bump = module_state.bump,
+        close = rent_payer,
+    )]
+    pub module_state: Account<'info, ModuleState>,
+}
+
+impl RemoveModule<'_> {
+    pub fn apply(ctx: &mut Context<RemoveModule>) -> Result<()> {
+        {
+            let mut state = ctx.accounts.state.load_mut()?;
+            state.modules_len =
+                state.modules_len.checked_sub(1).ok_or(OneSigError::InvalidModule)?;
+        }
+        emit_cpi!(ModuleRemoved {
+            one_sig_account: ctx.accounts.state.key(),
+            module: ctx.accounts.module_state.module,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, ONE_SIG_SEED, SPENDING_LIMITS_SEED},
+    errors::OneSigError,
+    events::SpendingLimitRemoved,
+    state::{OneSigState, SpendingLimits},
+};
+
+/// Removes the spending limit of a mint (or SOL). Authorized by the multisig (executed as a leaf,
+/// like `set_config`); the rent of the entry is refunded to `recipient`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RemoveSpendingLimit<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// CHECK: Refund target only, chosen by the multisig through the signed leaf.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()],
+        bump = spending_limits.bump,
+        constraint = spending_limits.entries.iter().any(|limit| limit.mint == mint)
+            @OneSigError::SpendingLimitNotFound,
+        realloc = SpendingLimits::space(spending_limits.entries.len().saturating_sub(1)),
+        realloc::payer = recipient,
+        realloc::zero = false,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    pub system_program: Program<'info, System>,
+}
+
+impl RemoveSpendingLimit<'_> {
+    pub fn apply(ctx: &mut Context<RemoveSpendingLimit>, mint: &Pubkey) -> Result<()> {
+        ctx.accounts.spending_limits.entries.retain(|limit| limit.mint != *mint);
+        emit_cpi!(SpendingLimitRemoved {
+            one_sig_account: ctx.accounts.state.key(),
+            mint: *mint,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::ONE_SIG_SEED, state::OneSigState, types::ResizeStateParams};
+
+/// Grows the signer capacity of a `OneSigState` account after initialization.
+/// Authorized by the multisig (executed as a leaf, like `set_config`); the rent for the added
+/// space is paid by `payer`.
+#[derive(Accounts)]
+#[instruction(params: ResizeStateParams)]
+pub struct ResizeState<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// Funds the rent of the added space. When executed as a leaf this is usually the
+    /// `one_sig_signer` PDA itself, in which case the leaf's `value` must cover the rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        realloc = OneSigState::space(params.signers_capacity),
+        realloc::payer = payer,
+        // The added space is zeroed by `OneSigState::grow_signers`
+        realloc::zero = false,
+    )]
+    pub state: AccountLoader<'info, OneSigState>,
+    pub system_program: Program<'info, System>,
+}
+
+impl ResizeState<'_> {
+    pub fn apply(ctx: &mut Context<ResizeState>, params: &ResizeStateParams) -> Result<()> {
+        let state_info = ctx.accounts.state.to_account_info();
+        let mut data = state_info.try_borrow_mut_data()?;
+        OneSigState::grow_signers(&mut data, params.signers_capacity)
+    }
+}
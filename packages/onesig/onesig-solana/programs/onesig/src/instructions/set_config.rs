@@ -1,44 +1,157 @@
+use std::mem;
+
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::ONE_SIG_SEED, events::ConfigSet, state::OneSigState, types::SetConfigParams,
+    constants::{
+        EVENT_VERSION, ONE_SIG_SEED, SPENDING_LIMITS_SEED, TARGET_PROGRAMS_MAX_LEN,
+        TARGET_PROGRAMS_SEED,
+    },
+    errors::OneSigError,
+    events::{ConfigSet, ExecutorRequiredSet, NonceSkipped},
+    state::{OneSigState, OneSigStateRefMut, SpendingLimits, TargetPrograms},
+    types::{PreviousConfig, SetConfigParams, SetTargetProgramsParams},
 };
 
 #[event_cpi]
 #[derive(Accounts)]
 pub struct SetConfig<'info> {
-    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.bump)]
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
     pub one_sig_signer: Signer<'info>,
     #[account(mut)]
-    pub state: Account<'info, OneSigState>,
+    pub state: AccountLoader<'info, OneSigState>,
+    /// Only needed for `SetTargetPrograms`.
+    #[account(
+        mut,
+        seeds = [TARGET_PROGRAMS_SEED, state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+    /// Only needed for `SetExecutorReimbursement`.
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()],
+        bump = spending_limits.bump,
+    )]
+    pub spending_limits: Option<Account<'info, SpendingLimits>>,
 }
 
 impl SetConfig<'_> {
     pub fn apply(ctx: &mut Context<SetConfig>, params: &SetConfigParams) -> Result<()> {
-        match params {
+        let mut state = OneSigStateRefMut::load(&ctx.accounts.state)?;
+        let previous = match params {
             SetConfigParams::AddSigner(signer) => {
-                ctx.accounts.state.multisig.add_signer(*signer)?;
-            },
-            SetConfigParams::RemoveSigner(signer) => {
-                ctx.accounts.state.multisig.remove_signer(*signer)?;
+                state.add_signer(*signer)?;
+                PreviousConfig::None
             },
+            SetConfigParams::RemoveSigner(signer) =>
+                PreviousConfig::SignerIndex(state.remove_signer(*signer)?),
             SetConfigParams::SetThreshold(threshold) => {
-                ctx.accounts.state.multisig.set_threshold(*threshold)?;
+                let previous = state.threshold;
+                state.set_threshold(*threshold)?;
+                PreviousConfig::Threshold(previous)
             },
-            SetConfigParams::SetSeed(seed) => {
-                ctx.accounts.state.seed = *seed;
+            SetConfigParams::SetSeed(seed) =>
+                PreviousConfig::Seed(mem::replace(&mut state.seed, *seed)),
+            SetConfigParams::AddExecutor(_) | SetConfigParams::RemoveExecutor(_) =>
+                return err!(OneSigError::ExecutorConfigMoved),
+            SetConfigParams::SetExecutorRequired(executor_required) => {
+                let previous = state.executor_required();
+                state.set_executor_required(*executor_required)?;
+                PreviousConfig::ExecutorRequired(previous)
             },
-            SetConfigParams::AddExecutor(executor) => {
-                ctx.accounts.state.executors.add_executor(*executor)?;
+            SetConfigParams::SetTargetPrograms(SetTargetProgramsParams { policy, programs }) => {
+                let target_programs = ctx
+                    .accounts
+                    .target_programs
+                    .as_mut()
+                    .ok_or(OneSigError::MissingTargetPrograms)?;
+                require!(
+                    programs.len() <= TARGET_PROGRAMS_MAX_LEN,
+                    OneSigError::TooManyTargetPrograms
+                );
+                let previous = SetTargetProgramsParams {
+                    policy: state.target_policy(),
+                    programs: mem::replace(&mut target_programs.programs, programs.clone()),
+                };
+                state.set_target_policy(*policy);
+                PreviousConfig::TargetPrograms(previous)
             },
-            SetConfigParams::RemoveExecutor(executor) => {
-                ctx.accounts.state.executors.remove_executor(*executor)?;
+            SetConfigParams::SetExecutorReimbursement(lamports) => {
+                let spending_limits = ctx
+                    .accounts
+                    .spending_limits
+                    .as_mut()
+                    .ok_or(OneSigError::MissingSpendingLimits)?;
+                PreviousConfig::ExecutorReimbursement(mem::replace(
+                    &mut spending_limits.executor_reimbursement,
+                    *lamports,
+                ))
             },
-            SetConfigParams::SetExecutorRequired(executor_required) => {
-                ctx.accounts.state.executors.set_executor_required(*executor_required)?;
+            SetConfigParams::SetClusterGenesisHash(genesis_hash) =>
+                PreviousConfig::ClusterGenesisHash(mem::replace(
+                    &mut state.cluster_genesis_hash,
+                    *genesis_hash,
+                )),
+            SetConfigParams::ResetNonce(seed) => {
+                require!(*seed != state.seed, OneSigError::NonceNamespaceUnchanged);
+                PreviousConfig::Nonce {
+                    seed: mem::replace(&mut state.seed, *seed),
+                    nonce: mem::replace(&mut state.nonce, 0),
+                }
             },
+            SetConfigParams::SetSelfCpiTargets(targets) => {
+                let previous = state.self_cpi_targets();
+                state.set_self_cpi_targets(targets);
+                PreviousConfig::SelfCpiTargets(previous)
+            },
+            SetConfigParams::SetExecutionRateLimit(limit) => {
+                let previous = state.execution_rate_limit();
+                state.set_execution_rate_limit(*limit)?;
+                PreviousConfig::ExecutionRateLimit(previous)
+            },
+        };
+        drop(state);
+        // Nonce trackers learn of the reset without decoding the change
+        let skipped = match (params, &previous) {
+            (
+                SetConfigParams::ResetNonce(seed),
+                PreviousConfig::Nonce { seed: previous_seed, nonce: previous_nonce },
+            ) => Some(NonceSkipped {
+                one_sig_account: ctx.accounts.state.key(),
+                previous_seed: *previous_seed,
+                previous_nonce: *previous_nonce,
+                seed: *seed,
+                nonce: 0,
+                event_version: EVENT_VERSION,
+            }),
+            _ => None,
+        };
+        // Relayers learn whether they must be registered without decoding the change
+        let executor_required_set = match (params, &previous) {
+            (
+                SetConfigParams::SetExecutorRequired(executor_required),
+                PreviousConfig::ExecutorRequired(previous),
+            ) if executor_required != previous => Some(ExecutorRequiredSet {
+                one_sig_account: ctx.accounts.state.key(),
+                executor_required: *executor_required,
+                effective_at: Clock::get()?.unix_timestamp,
+                event_version: EVENT_VERSION,
+            }),
+            _ => None,
+        };
+        emit_cpi!(ConfigSet {
+            one_sig_account: ctx.accounts.state.key(),
+            params: params.clone(),
+            previous,
+            event_version: EVENT_VERSION,
+        });
+        if let Some(skipped) = skipped {
+            emit_cpi!(skipped);
+        }
+        if let Some(executor_required_set) = executor_required_set {
+            emit_cpi!(executor_required_set);
         }
-        emit_cpi!(ConfigSet { one_sig_account: ctx.accounts.state.key(), params: params.clone() });
         Ok(())
     }
 }
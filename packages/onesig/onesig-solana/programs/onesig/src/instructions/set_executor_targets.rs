@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, EXECUTOR_SEED, ONE_SIG_SEED, TARGET_PROGRAMS_MAX_LEN},
+    errors::OneSigError,
+    events::ExecutorTargetsSet,
+    state::{ExecutorState, OneSigState},
+    types::SetExecutorTargetsParams,
+};
+
+/// Restricts the programs an executor may have executed leaves call while `executor_required` is
+/// set, e.g. to keep a keeper bot to oracle updates. Authorized by the multisig (executed as a
+/// leaf, like `set_config`); the executor PDA is resized at the expense of `payer`, which is
+/// refunded when it shrinks.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: SetExecutorTargetsParams)]
+pub struct SetExecutorTargets<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// Funds added space. When executed as a leaf this is usually the `one_sig_signer` PDA
+    /// itself, in which case the leaf's `value` must cover the rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, state.key().as_ref(), params.executor.as_ref()],
+        bump = executor_state.bump,
+        realloc = ExecutorState::space(params.programs.len()),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub executor_state: Account<'info, ExecutorState>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetExecutorTargets<'_> {
+    pub fn apply(
+        ctx: &mut Context<SetExecutorTargets>,
+        params: &SetExecutorTargetsParams,
+    ) -> Result<()> {
+        let SetExecutorTargetsParams { executor, programs } = params;
+        require!(programs.len() <= TARGET_PROGRAMS_MAX_LEN, OneSigError::TooManyTargetPrograms);
+        ctx.accounts.executor_state.target_programs = programs.clone();
+
+        emit_cpi!(ExecutorTargetsSet {
+            one_sig_account: ctx.accounts.state.key(),
+            executor: *executor,
+            programs: programs.clone(),
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, ONE_SIG_SEED, SPENDING_LIMITS_SEED},
+    errors::OneSigError,
+    events::SpendingLimitSet,
+    state::{OneSigState, SpendingLimit, SpendingLimits},
+    types::SetSpendingLimitParams,
+};
+
+/// Sets the spending limit of a mint (or SOL), adding it if there is none yet. Authorized by the
+/// multisig (executed as a leaf, like `set_config`); the rent of a new entry is paid by `payer`.
+///
+/// Changing an existing limit keeps what was spent in the current window, so raising a limit does
+/// not also clear its counter.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: SetSpendingLimitParams)]
+pub struct SetSpendingLimit<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// Funds the rent of a new entry. When executed as a leaf this is usually the
+    /// `one_sig_signer` PDA itself, in which case the leaf's `value` must cover the rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, state.key().as_ref()],
+        bump = spending_limits.bump,
+        realloc = SpendingLimits::space(spending_limits.len_after_set(&params.mint)),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetSpendingLimit<'_> {
+    pub fn apply(
+        ctx: &mut Context<SetSpendingLimit>,
+        params: &SetSpendingLimitParams,
+    ) -> Result<()> {
+        let SetSpendingLimitParams { mint, amount, window } = *params;
+        require!(window > 0, OneSigError::InvalidSpendingLimit);
+
+        let entries = &mut ctx.accounts.spending_limits.entries;
+        if let Some(limit) = entries.iter_mut().find(|limit| limit.mint == mint) {
+            limit.amount = amount;
+            limit.window = window;
+        } else {
+            entries.push(SpendingLimit {
+                mint,
+                amount,
+                window,
+                window_start: Clock::get()?.unix_timestamp,
+                spent: 0,
+            });
+        }
+
+        emit_cpi!(SpendingLimitSet {
+            one_sig_account: ctx.accounts.state.key(),
+            mint,
+            amount,
+            window
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
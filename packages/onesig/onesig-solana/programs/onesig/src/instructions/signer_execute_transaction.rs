@@ -1,11 +1,20 @@
-use anchor_lang::prelude::*;
+use std::mem;
+
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID};
+use onesig_core::digest;
 
 use crate::{
-    constants::{MERKLE_ROOT_SEED, ONE_SIG_SEED},
+    constants::{EVENT_VERSION, MERKLE_ROOT_SEED, SPENDING_LIMITS_SEED, TARGET_PROGRAMS_SEED},
     errors::OneSigError,
     events::TransactionExecuted,
-    execution::{build_instruction, execute_instruction, resolve_merkle_root},
-    state::{MerkleRootState, OneSigState},
+    execution::{
+        advance_nonce, build_instruction, check_not_before, execute_instruction, resets_nonce,
+        resolve_merkle_root, verify_transaction_group, verify_vault_signer,
+    },
+    state::{
+        MerkleRootState, OneSigState, OneSigStateRef, OneSigStateRefMut, SpendingLimits,
+        TargetPrograms,
+    },
     types::SignerExecuteTransactionParams,
     validation::{merkle::MerkleValidator, signature::SignatureValidator},
 };
@@ -19,18 +28,37 @@ pub struct SignerExecuteTransaction<'info> {
     /// `submitter == delegate`.
     pub delegate: Signer<'info>,
     /// CHECK: This is the same PDA used in invoke_signed when executing transactions.
-    /// It signs on behalf of the program in signer_execute_transaction.
-    #[account(seeds = [ONE_SIG_SEED, one_sig_state.key().as_ref()], bump = one_sig_state.bump)]
+    /// It signs on behalf of the program in signer_execute_transaction: the `one_sig_signer` PDA,
+    /// or the signer PDA of the vault the transaction names, as checked by
+    /// `verify_vault_signer`.
     pub one_sig_signer: UncheckedAccount<'info>,
     #[account(mut)]
-    pub one_sig_state: Account<'info, OneSigState>,
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    /// Charged with what the executed instruction spends.
+    #[account(
+        mut,
+        seeds = [SPENDING_LIMITS_SEED, one_sig_state.key().as_ref()],
+        bump = spending_limits.bump,
+    )]
+    pub spending_limits: Account<'info, SpendingLimits>,
     #[account(
+        mut,
         seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), merkle_root_state.merkle_root.as_ref()],
         bump = merkle_root_state.bump,
         constraint = merkle_root_state.expiry >= Clock::get()?.unix_timestamp @OneSigError::ExpiredMerkleRoot,
-        constraint = merkle_root_state.seed == one_sig_state.seed @OneSigError::SeedMismatch,
+        constraint = merkle_root_state.verified_under(&one_sig_state.load()?.seed) @OneSigError::SeedMismatch,
     )]
     pub merkle_root_state: Option<Account<'info, MerkleRootState>>,
+    /// The programs listed by the target policy; only needed when one is set.
+    #[account(
+        seeds = [TARGET_PROGRAMS_SEED, one_sig_state.key().as_ref()],
+        bump = target_programs.bump,
+    )]
+    pub target_programs: Option<Account<'info, TargetPrograms>>,
+    /// CHECK: The instructions sysvar, which lists the instructions executing the other leaves of
+    /// the transaction's group; only needed for a grouped leaf.
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 impl SignerExecuteTransaction<'_> {
@@ -42,70 +70,104 @@ impl SignerExecuteTransaction<'_> {
     /// 2. Encode the leaf and verify the merkle proof.
     /// 3. If `executor_required`: run `verify_signer_execution_proof` with digest bound to
     ///    `delegate.key()`. Skipped in permissionless mode.
-    /// 4. Execute, increment nonce, emit event.
+    /// 4. Charge the execution rate limit, execute, increment nonce, emit event.
     pub fn apply(
         ctx: &mut Context<SignerExecuteTransaction>,
-        params: &SignerExecuteTransactionParams,
+        params: SignerExecuteTransactionParams,
     ) -> Result<()> {
         let SignerExecuteTransactionParams {
-            transaction,
+            mut transaction,
             merkle_root_verification,
             signature,
             expiry,
         } = params;
+        let one_sig_state = OneSigStateRef::load(&ctx.accounts.one_sig_state)?;
 
         // Verify merkle root and get the root hash
-        let merkle_root = resolve_merkle_root(
-            &ctx.accounts.one_sig_state,
-            ctx.accounts.merkle_root_state.as_ref(),
+        let (merkle_root, root_executions) = resolve_merkle_root(
+            &one_sig_state,
+            ctx.accounts.merkle_root_state.as_mut(),
             merkle_root_verification.as_ref(),
         )?;
 
-        // Get current nonce (needed for leaf encoding)
-        let nonce = ctx.accounts.one_sig_state.nonce;
+        // Get current nonce (needed for leaf encoding), and the seed namespacing it
+        let (nonce, seed) = (one_sig_state.nonce, one_sig_state.seed);
+        let signer_bump = verify_vault_signer(
+            &ctx.accounts.one_sig_signer.key(),
+            &ctx.accounts.one_sig_state.key(),
+            one_sig_state.bump,
+            transaction.vault_index,
+        )?;
 
-        // Build the OneSigInstruction from the transaction
-        let instruction =
-            build_instruction(&ctx.accounts.one_sig_signer, transaction, ctx.remaining_accounts)?;
+        // Build the OneSigInstruction from the transaction, taking its data
+        let instruction = build_instruction(
+            &ctx.accounts.one_sig_signer,
+            mem::take(&mut transaction.ix_data),
+            transaction.value,
+            ctx.remaining_accounts,
+        )?;
 
         // Encode the transaction leaf and verify against the Merkle proof
         let leaf = MerkleValidator::encode_leaf(
             &ctx.accounts.one_sig_state.key(),
-            ctx.accounts.one_sig_state.one_sig_id,
+            one_sig_state.one_sig_id,
             nonce,
             &instruction,
+            &transaction.token_outflow_limits,
+            &transaction.guarded_accounts,
+            transaction.vault_index,
+            one_sig_state.cluster_genesis_hash().as_ref(),
+            transaction.group.as_ref(),
+            transaction.not_before,
+            transaction.bounty,
         )?;
         MerkleValidator::verify_merkle_proof(&merkle_root, &transaction.proof, &leaf)?;
+        if let Some(group) = transaction.group.as_ref() {
+            verify_transaction_group(
+                ctx.accounts.instructions_sysvar.as_ref(),
+                &ctx.accounts.one_sig_state.key(),
+                group,
+            )?;
+        }
 
         // Signer-execution-proof gate: only when executor_required. In permissionless mode
         // both the signature and expiry fields are accepted but not verified.
-        if ctx.accounts.one_sig_state.executors.executor_required {
+        if one_sig_state.executor_required() {
             SignatureValidator::verify_signer_execution_proof(
                 &leaf,
                 &merkle_root,
                 ctx.accounts.delegate.key(),
-                *expiry,
-                &ctx.accounts.one_sig_state.multisig.signers,
-                signature,
+                expiry,
+                one_sig_state.signers(),
+                &signature,
             )?;
         }
+        let now = Clock::get()?.unix_timestamp;
+        check_not_before(transaction.not_before, now)?;
+        let (chain_id, one_sig_id) = (one_sig_state.caip2_chain_id(), one_sig_state.one_sig_id);
+        drop(one_sig_state);
+        // Charged before the leaf runs, which may change the limit or close the account
+        OneSigStateRefMut::load(&ctx.accounts.one_sig_state)?.record_execution(now)?;
+        // Identify what the leaf calls for the event, and whether it resets the nonce, before the
+        // instruction is consumed
+        let (program_id, ix_data_hash) =
+            (instruction.program_id, digest::ix_data_hash(&instruction.data));
+        let resets_nonce = resets_nonce(&instruction);
 
         // Execute the verified OneSigInstruction
-        execute_instruction(
+        let lamports_spent = execute_instruction(
             &ctx.accounts.one_sig_signer,
             &ctx.accounts.one_sig_state,
+            signer_bump,
+            &mut ctx.accounts.spending_limits,
+            ctx.accounts.target_programs.as_ref(),
             ctx.remaining_accounts,
             instruction,
+            &transaction,
         )?;
 
-        // Bump the nonce for replay protection: reload to see any state the executed instruction
-        // mutated, reject a mutated nonce, then increment.
-        ctx.accounts.one_sig_state.reload()?;
-        require!(
-            ctx.accounts.one_sig_state.nonce == nonce,
-            OneSigError::NonceMutatedDuringExecution
-        );
-        ctx.accounts.one_sig_state.nonce = nonce + 1;
+        // Bump the nonce for replay protection
+        advance_nonce(&ctx.accounts.one_sig_state, nonce, &seed, resets_nonce)?;
 
         // Emit successful transaction event (shared with execute_transaction; observers
         // disambiguate via the top-level instruction discriminator).
@@ -113,6 +175,15 @@ impl SignerExecuteTransaction<'_> {
             one_sig_account: ctx.accounts.one_sig_state.key(),
             merkle_root,
             nonce,
+            chain_id,
+            one_sig_id,
+            executor: ctx.accounts.delegate.key(),
+            program_id,
+            ix_data_hash,
+            value: transaction.value,
+            lamports_spent,
+            root_executions,
+            event_version: EVENT_VERSION,
         });
         Ok(())
     }
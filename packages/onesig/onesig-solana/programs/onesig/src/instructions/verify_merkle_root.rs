@@ -1,8 +1,13 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::MERKLE_ROOT_SEED,
-    state::{MerkleRootState, OneSigState},
+    constants::{
+        EVENT_VERSION, MERKLE_ROOT_REGISTRY_SEED, MERKLE_ROOT_SEED, MERKLE_ROOT_STATE_VERSION,
+        SIGNATURE_BYTES_LEN,
+    },
+    errors::OneSigError,
+    events::MerkleRootVerified,
+    state::{MerkleRootEntry, MerkleRootRegistry, MerkleRootState, OneSigState, OneSigStateRefMut},
     types::VerifyMerkleRootParams,
     validation::merkle::MerkleValidator,
 };
@@ -16,12 +21,23 @@ pub struct VerifyMerkleRoot<'info> {
     #[account(
         init,
         payer = payer,
-        space = 8 + MerkleRootState::INIT_SPACE,
+        // Verification records the signer of every signature, all of which must be valid
+        space = MerkleRootState::space(params.signatures.len() / SIGNATURE_BYTES_LEN),
         seeds = [MERKLE_ROOT_SEED, one_sig_state.key().as_ref(), params.merkle_root.as_ref()],
         bump,
     )]
     pub merkle_root_state: Account<'info, MerkleRootState>,
-    pub one_sig_state: Account<'info, OneSigState>,
+    #[account(mut)]
+    pub one_sig_state: AccountLoader<'info, OneSigState>,
+    #[account(
+        mut,
+        seeds = [MERKLE_ROOT_REGISTRY_SEED, one_sig_state.key().as_ref()],
+        bump = merkle_root_registry.bump,
+        realloc = MerkleRootRegistry::space(merkle_root_registry.entries.len() + 1),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub merkle_root_registry: Account<'info, MerkleRootRegistry>,
     pub system_program: Program<'info, System>,
 }
 
@@ -30,28 +46,53 @@ impl VerifyMerkleRoot<'_> {
         ctx: &mut Context<VerifyMerkleRoot>,
         params: &VerifyMerkleRootParams,
     ) -> Result<()> {
-        let VerifyMerkleRootParams { merkle_root, expiry, signatures } = params;
+        let VerifyMerkleRootParams { merkle_root, expiry, max_executions, signatures } = params;
+        let mut one_sig_state = OneSigStateRefMut::load(&ctx.accounts.one_sig_state)?;
 
         // Verify Merkle root and signatures, capturing the signers that signed.
-        let signed_by = MerkleValidator::verify_merkle_root(
-            &ctx.accounts.one_sig_state,
+        let signed_by = MerkleValidator::verify_merkle_root_signers(
+            &one_sig_state.seed,
+            &one_sig_state.multisig(),
             merkle_root,
             *expiry,
+            *max_executions,
             signatures,
             Clock::get()?.unix_timestamp,
         )?;
 
-        // Store the expiry and seed in the Merkle root state account
+        // Store the expiry and seed commitment in the Merkle root state account
         // This allows execute_transaction to ensure the verified merkle root
         // is not expired and the seed is the same as the OneSigState account
-        ctx.accounts.merkle_root_state.seed = ctx.accounts.one_sig_state.seed;
+        ctx.accounts.merkle_root_state.seed_commitment =
+            MerkleRootState::seed_commitment(&one_sig_state.seed);
         ctx.accounts.merkle_root_state.expiry = *expiry;
 
         ctx.accounts.merkle_root_state.merkle_root = *merkle_root;
         ctx.accounts.merkle_root_state.rent_payer = ctx.accounts.payer.key();
         ctx.accounts.merkle_root_state.signed_by = signed_by;
         ctx.accounts.merkle_root_state.bump = ctx.bumps.merkle_root_state;
+        ctx.accounts.merkle_root_state.version = MERKLE_ROOT_STATE_VERSION;
+        ctx.accounts.merkle_root_state.remaining_executions =
+            (*max_executions > 0).then_some(*max_executions);
+
+        // Track the open root so it can be enumerated, and so the OneSig account cannot be closed
+        // while it exists
+        ctx.accounts.merkle_root_registry.entries.push(MerkleRootEntry {
+            address: ctx.accounts.merkle_root_state.key(),
+            expiry: *expiry,
+        });
+        one_sig_state.merkle_roots_len = one_sig_state
+            .merkle_roots_len
+            .checked_add(1)
+            .ok_or(OneSigError::TooManyMerkleRoots)?;
 
+        emit!(MerkleRootVerified {
+            one_sig_account: ctx.accounts.one_sig_state.key(),
+            merkle_root: *merkle_root,
+            expiry: *expiry,
+            rent_payer: ctx.accounts.payer.key(),
+            event_version: EVENT_VERSION,
+        });
         Ok(())
     }
 }
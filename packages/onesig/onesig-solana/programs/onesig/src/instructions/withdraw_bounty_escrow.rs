@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BOUNTY_ESCROW_SEED, EVENT_VERSION, ONE_SIG_SEED},
+    events::BountyEscrowWithdrawn,
+    execution::withdraw_from_bounty_escrow,
+    state::OneSigState,
+};
+
+/// Withdraws `lamports` from the bounty escrow of a OneSig account to `recipient`. Authorized by
+/// the multisig (executed as a leaf, like `set_config`). The escrow is left rent-exempt, or
+/// emptied.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawBountyEscrow<'info> {
+    #[account(seeds = [ONE_SIG_SEED, state.key().as_ref()], bump = state.load()?.bump)]
+    pub one_sig_signer: Signer<'info>,
+    /// CHECK: Withdrawal target only, chosen by the multisig through the signed leaf.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    pub state: AccountLoader<'info, OneSigState>,
+    /// CHECK: The system account the bounties of the OneSig account are paid from.
+    #[account(mut, seeds = [BOUNTY_ESCROW_SEED, state.key().as_ref()], bump)]
+    pub bounty_escrow: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl WithdrawBountyEscrow<'_> {
+    pub fn apply(ctx: &mut Context<WithdrawBountyEscrow>, lamports: u64) -> Result<()> {
+        withdraw_from_bounty_escrow(
+            &ctx.accounts.bounty_escrow,
+            &ctx.accounts.state.key(),
+            ctx.bumps.bounty_escrow,
+            &ctx.accounts.recipient,
+            lamports,
+        )?;
+        emit_cpi!(BountyEscrowWithdrawn {
+            one_sig_account: ctx.accounts.state.key(),
+            recipient: ctx.accounts.recipient.key(),
+            lamports,
+            event_version: EVENT_VERSION,
+        });
+        Ok(())
+    }
+}
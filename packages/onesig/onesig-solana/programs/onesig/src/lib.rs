@@ -1,3 +1,25 @@
+//! OneSig: a multisig whose signers approve batches of transactions at once, by signing the
+//! merkle root of their leaves, for any party to then execute leaf by leaf.
+//!
+//! Other programs can CPI into OneSig by depending on this crate with the `cpi` feature, which
+//! provides `cpi::verify_merkle_root`, `cpi::execute_transaction` and the other instructions with
+//! their typed account structs (`cpi::accounts`). The accounts of the instruction executed by a
+//! leaf are passed as remaining accounts (`CpiContext::with_remaining_accounts`), program id
+//! first. A calling program acts like any other executor: when `executor_required` is set, the
+//! `executor` it signs for must be registered. A module program registered with `add_module`
+//! instead calls `cpi::execute_module_transaction`, signing with its
+//! `[MODULE_AUTHORITY_SEED, one_sig_state]` PDA, and needs no merkle proof.
+//!
+//! With the `library` feature, the crate builds without the program entrypoint, for programs and
+//! off-chain tools reusing the account and parameter types, the leaf encoding and the signature
+//! checks of [`validation`]: e.g. `MerkleValidator::encode_leaf` to build leaves, and
+//! `MerkleValidator::merkle_root_digest` for what signers sign. Tooling that does not need the
+//! Anchor types can depend on the `onesig-core` crate these are built on instead.
+//!
+//! The `mainnet`, `testnet`, `devnet` and `localnet` features select the cluster a build is
+//! deployed to, and with it the genesis hash bound into leaves (see
+//! [`constants::CLUSTER_GENESIS_HASH`]); the program id is the same on every cluster. Without one,
+//! the build behaves as `localnet`.
 #![allow(unexpected_cfgs)]
 
 pub mod constants;
@@ -9,6 +31,11 @@ pub mod state;
 pub mod types;
 pub mod validation;
 
+#[cfg(test)]
+mod execution_tests;
+#[cfg(test)]
+mod state_tests;
+
 use anchor_lang::prelude::*;
 pub use constants::*;
 pub use errors::*;
@@ -18,6 +45,8 @@ pub use state::*;
 pub use types::*;
 pub use validation::*;
 
+// Every cluster is deployed from the same program keypair, so the address is shared: the cluster
+// features only select `CLUSTER_GENESIS_HASH`.
 declare_id!("5XDrnPsfpZ29v7DRrUtUBJ3yr5n1mhSUDyEzPuAvakHv");
 
 #[program]
@@ -28,7 +57,10 @@ pub mod onesig {
         Ok(VERSION.to_string())
     }
 
-    pub fn init_one_sig(mut ctx: Context<InitOneSig>, params: InitOneSigParams) -> Result<()> {
+    pub fn init_one_sig<'info>(
+        mut ctx: Context<'info, InitOneSig<'info>>,
+        params: InitOneSigParams,
+    ) -> Result<()> {
         InitOneSig::apply(&mut ctx, &params)
     }
 
@@ -36,6 +68,55 @@ pub mod onesig {
         SetConfig::apply(&mut ctx, &params)
     }
 
+    pub fn resize_state(mut ctx: Context<ResizeState>, params: ResizeStateParams) -> Result<()> {
+        ResizeState::apply(&mut ctx, &params)
+    }
+
+    pub fn add_executor(mut ctx: Context<AddExecutor>, executor: Pubkey) -> Result<()> {
+        AddExecutor::apply(&mut ctx, &executor)
+    }
+
+    pub fn remove_executor(mut ctx: Context<RemoveExecutor>) -> Result<()> {
+        RemoveExecutor::apply(&mut ctx)
+    }
+
+    pub fn add_module(mut ctx: Context<AddModule>, module: Pubkey) -> Result<()> {
+        AddModule::apply(&mut ctx, &module)
+    }
+
+    pub fn remove_module(mut ctx: Context<RemoveModule>) -> Result<()> {
+        RemoveModule::apply(&mut ctx)
+    }
+
+    pub fn set_executor_targets(
+        mut ctx: Context<SetExecutorTargets>,
+        params: SetExecutorTargetsParams,
+    ) -> Result<()> {
+        SetExecutorTargets::apply(&mut ctx, &params)
+    }
+
+    pub fn set_spending_limit(
+        mut ctx: Context<SetSpendingLimit>,
+        params: SetSpendingLimitParams,
+    ) -> Result<()> {
+        SetSpendingLimit::apply(&mut ctx, &params)
+    }
+
+    pub fn remove_spending_limit(
+        mut ctx: Context<RemoveSpendingLimit>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        RemoveSpendingLimit::apply(&mut ctx, &mint)
+    }
+
+    pub fn create_pda_ata(mut ctx: Context<CreatePdaAta>, vault_index: u8) -> Result<()> {
+        CreatePdaAta::apply(&mut ctx, vault_index)
+    }
+
+    pub fn migrate_state<'info>(mut ctx: Context<'info, MigrateState<'info>>) -> Result<()> {
+        MigrateState::apply(&mut ctx)
+    }
+
     pub fn verify_merkle_root(
         mut ctx: Context<VerifyMerkleRoot>,
         params: VerifyMerkleRootParams,
@@ -43,24 +124,73 @@ pub mod onesig {
         VerifyMerkleRoot::apply(&mut ctx, &params)
     }
 
+    pub fn extend_merkle_root(
+        mut ctx: Context<ExtendMerkleRoot>,
+        params: VerifyMerkleRootParams,
+    ) -> Result<()> {
+        ExtendMerkleRoot::apply(&mut ctx, &params)
+    }
+
     pub fn execute_transaction(
         mut ctx: Context<ExecuteTransaction>,
         params: ExecuteTransactionParams,
     ) -> Result<()> {
-        ExecuteTransaction::apply(&mut ctx, &params)
+        ExecuteTransaction::apply(&mut ctx, params)
     }
 
     pub fn signer_execute_transaction(
         mut ctx: Context<SignerExecuteTransaction>,
         params: SignerExecuteTransactionParams,
     ) -> Result<()> {
-        SignerExecuteTransaction::apply(&mut ctx, &params)
+        SignerExecuteTransaction::apply(&mut ctx, params)
+    }
+
+    pub fn execute_module_transaction(
+        mut ctx: Context<ExecuteModuleTransaction>,
+        params: ExecuteModuleTransactionParams,
+    ) -> Result<()> {
+        ExecuteModuleTransaction::apply(&mut ctx, params)
+    }
+
+    pub fn check_transaction(
+        ctx: Context<CheckTransaction>,
+        params: ExecuteTransactionParams,
+    ) -> Result<TransactionCheckResult> {
+        CheckTransaction::apply(&ctx, params)
+    }
+
+    pub fn record_leaf_failure(
+        mut ctx: Context<RecordLeafFailure>,
+        params: RecordLeafFailureParams,
+    ) -> Result<()> {
+        RecordLeafFailure::apply(&mut ctx, params)
+    }
+
+    pub fn close_leaf_failure<'info>(
+        mut ctx: Context<'info, CloseLeafFailure<'info>>,
+    ) -> Result<()> {
+        CloseLeafFailure::apply(&mut ctx)
     }
 
     pub fn close_merkle_root(mut ctx: Context<CloseMerkleRoot>) -> Result<()> {
         CloseMerkleRoot::apply(&mut ctx)
     }
+
+    pub fn close_one_sig(mut ctx: Context<CloseOneSig>) -> Result<()> {
+        CloseOneSig::apply(&mut ctx)
+    }
+
+    pub fn withdraw_bounty_escrow(
+        mut ctx: Context<WithdrawBountyEscrow>,
+        lamports: u64,
+    ) -> Result<()> {
+        WithdrawBountyEscrow::apply(&mut ctx, lamports)
+    }
 }
 
 #[derive(Accounts)]
-pub struct GetVersion {}
+pub struct GetVersion<'info> {
+    // The generated CPI client needs accounts with a lifetime. The account is optional and may be
+    // left out entirely (`allow-missing-optionals`), so `version` is still called without accounts
+    pub program: Option<Program<'info, program::Onesig>>,
+}
@@ -1,118 +1,567 @@
-use anchor_lang::prelude::*;
+use std::{
+    cell::{Ref, RefMut},
+    mem::size_of,
+    ops::{Deref, DerefMut},
+};
+
+use anchor_lang::{prelude::*, Discriminator};
+use bytemuck::Zeroable;
+use onesig_core::digest;
 
 use crate::{
-    constants::SIGNERS_MAX_LEN,
+    constants::{
+        CAIP2_NAMESPACE, CAIP2_REFERENCE_LEN, CLUSTER_GENESIS_HASH, ONE_SIG_STATE_VERSION,
+        SIGNERS_MAX_LEN, TARGET_PROGRAMS_MAX_LEN,
+    },
     errors::OneSigError,
-    types::{Address, Hash},
-    EXECUTORS_MAX_LEN, MAX_THRESHOLD,
+    types::{Address, ExecutionRateLimit, Hash, SelfCpiTarget, TargetPolicy, ADDRESS_LEN},
+    MAX_THRESHOLD,
 };
 
-/// OneSig state account holding configuration and multisig details
-#[account]
-#[derive(InitSpace)]
+/// OneSig state account holding configuration and multisig details.
+///
+/// The account is zero-copy: this fixed-size header is followed in the account data by the signer
+/// list (`signers_capacity` addresses). Executors are not stored here but in one [`ExecutorState`]
+/// PDA each, keeping this account small for `execute_transaction`.
+/// Use [`OneSigStateRef`] / [`OneSigStateRefMut`] to access the header together with the signers.
+///
+/// Its discriminator, the first 8 bytes of `sha256("account:ZeroCopyOneSigState")`, differs from
+/// the one of the Borsh-encoded `OneSigState` of the first release, so those accounts are refused
+/// until `migrate_state` has converted them.
+#[account(zero_copy, discriminator = [141, 165, 49, 160, 65, 66, 13, 197])]
 pub struct OneSigState {
     pub one_sig_id: u64,
+    // Transaction replay protection counter
+    pub nonce: u64,
     pub seed: Hash,
+    // Number of populated entries in the signer list
+    pub signers_len: u16,
+    // Number of entries the account data has room for in the signer list
+    pub signers_capacity: u16,
+    // Number of open `ExecutorState` / `MerkleRootState` PDAs; both must be closed before the
+    // account can be closed with `close_one_sig`
+    pub executors_len: u16,
+    pub merkle_roots_len: u16,
+    pub threshold: u8,
+    // Boolean flag, stored as u8 to keep the header `Pod`
+    pub executor_required: u8,
     // The bump for the one_sig_signer PDA
     pub bump: u8,
-    // Transaction replay protection counter
-    pub nonce: u64,
-    pub multisig: Multisig,
-    pub executors: Executors,
+    // Layout version, see `ONE_SIG_STATE_VERSION`
+    pub version: u8,
+    // `TargetPolicy` restricting the programs executed leaves may call, stored as u8 to keep the
+    // header `Pod`
+    pub target_policy: u8,
+    // `SelfCpiTarget`s executed leaves may call, as the bits of `SelfCpiTarget::mask`
+    pub self_cpi_targets: u8,
+    // Number of open `ModuleState` PDAs, which must be closed before the account can be closed
+    pub modules_len: u16,
+    // Genesis hash of the chain this account is bound to, all zero when unset, see
+    // `cluster_genesis_hash`
+    pub cluster_genesis_hash: Hash,
+    // `ExecutionRateLimit` on the leaves executed, see `allows_execution`
+    pub max_executions: u32,
+    // Leaves executed in the window started at `execution_window_start`
+    pub window_executions: u32,
+    pub execution_window: i64,
+    pub execution_window_start: i64,
+    // Vaults whose signer PDA has signed for executed instructions, as a bit set indexed by vault;
+    // the ones other than vault 0 must be passed to `close_one_sig`, see `used_vaults`
+    pub vaults: [u8; 32],
+}
+
+impl OneSigState {
+    /// Offset of the zero-copy header in the account data (after the discriminator).
+    pub const HEADER_OFFSET: usize = OneSigState::DISCRIMINATOR.len();
+    /// Offset of the signer list in the account data.
+    pub const SIGNERS_OFFSET: usize = Self::HEADER_OFFSET + size_of::<OneSigState>();
+
+    /// Account size (including discriminator) for the given signer capacity.
+    pub fn space(signers_capacity: u16) -> usize {
+        Self::SIGNERS_OFFSET + signers_capacity as usize * ADDRESS_LEN
+    }
+
+    /// Grows the signer list of a `OneSigState` account whose `data` has already been resized to
+    /// `space(signers_capacity)`, zeroing every unused slot. The capacity can only grow.
+    pub fn grow_signers(data: &mut [u8], signers_capacity: u16) -> Result<()> {
+        require!(data.len() >= Self::space(signers_capacity), OneSigError::InvalidStateSize);
+        let (header, signers) = data[Self::HEADER_OFFSET..].split_at_mut(size_of::<OneSigState>());
+        let header = bytemuck::from_bytes_mut::<OneSigState>(header);
+        require!(
+            signers_capacity >= header.signers_capacity &&
+                signers_capacity as usize <= SIGNERS_MAX_LEN,
+            OneSigError::InvalidSignersLen
+        );
+
+        signers[header.signers_len as usize * ADDRESS_LEN..].fill(0);
+        header.signers_capacity = signers_capacity;
+        Ok(())
+    }
+
+    pub fn executor_required(&self) -> bool {
+        self.executor_required != 0
+    }
+
+    /// Genesis hash bound into the leaves executed for this account: the one set with
+    /// `SetClusterGenesisHash`, for deployments of the same binary on other SVM chains, or else
+    /// the one of the cluster the program was built for.
+    pub fn cluster_genesis_hash(&self) -> Option<Hash> {
+        if self.cluster_genesis_hash == Hash::zeroed() {
+            CLUSTER_GENESIS_HASH
+        } else {
+            Some(self.cluster_genesis_hash)
+        }
+    }
+
+    /// CAIP-2 identifier of the chain of `cluster_genesis_hash`, emitted with executions so
+    /// indexers can tell the chains of Solana and EVM deployments apart the same way. `None` while
+    /// leaves are not bound to a chain.
+    pub fn caip2_chain_id(&self) -> Option<String> {
+        self.cluster_genesis_hash().map(|genesis_hash| {
+            let mut reference = Pubkey::new_from_array(genesis_hash.0).to_string();
+            reference.truncate(CAIP2_REFERENCE_LEN);
+            format!("{CAIP2_NAMESPACE}:{reference}")
+        })
+    }
+
+    pub fn target_policy(&self) -> TargetPolicy {
+        match self.target_policy {
+            1 => TargetPolicy::Allow,
+            2 => TargetPolicy::Deny,
+            _ => TargetPolicy::None,
+        }
+    }
+
+    pub fn self_cpi_targets(&self) -> Vec<SelfCpiTarget> {
+        SelfCpiTarget::ALL
+            .into_iter()
+            .filter(|target| self.self_cpi_targets & target.bit() != 0)
+            .collect()
+    }
+
+    /// Whether executed leaves may call the instruction of this program with data `data`:
+    /// `set_config` always, the instructions of the allowed `SelfCpiTarget`s, and no other.
+    pub fn allows_self_cpi(&self, data: &[u8]) -> bool {
+        data.starts_with(crate::instruction::SetConfig::DISCRIMINATOR) ||
+            SelfCpiTarget::of(data)
+                .is_some_and(|target| self.self_cpi_targets & target.bit() != 0)
+    }
+
+    pub fn execution_rate_limit(&self) -> ExecutionRateLimit {
+        ExecutionRateLimit { max_executions: self.max_executions, window: self.execution_window }
+    }
+
+    /// Whether the execution rate limit leaves room for a leaf executed at `now`: once the current
+    /// window has elapsed, the next execution starts a new one.
+    pub fn allows_execution(&self, now: i64) -> bool {
+        self.max_executions == 0 ||
+            self.execution_window_elapsed(now) ||
+            self.window_executions < self.max_executions
+    }
+
+    /// Indices of the vaults other than vault 0 whose signer PDA has signed for the account, in
+    /// ascending order. Their signer PDAs must be drained before the account is closed.
+    pub fn used_vaults(&self) -> impl Iterator<Item = u8> + '_ {
+        (1..=u8::MAX).filter(|index| self.vaults[*index as usize / 8] & (1 << (index % 8)) != 0)
+    }
+
+    fn execution_window_elapsed(&self, now: i64) -> bool {
+        now >= self.execution_window_start.saturating_add(self.execution_window)
+    }
+
+    // Checks that the header has the current layout and that the signer list it describes fits in
+    // `signers_len` bytes of data.
+    fn validate_signers(&self, signers_len: usize) -> Result<()> {
+        require!(self.version == ONE_SIG_STATE_VERSION, OneSigError::StateVersionMismatch);
+        require!(
+            self.signers_len <= self.signers_capacity &&
+                self.signers_capacity as usize * ADDRESS_LEN <= signers_len,
+            OneSigError::InvalidStateSize
+        );
+        Ok(())
+    }
 }
 
-#[derive(InitSpace, Clone, AnchorSerialize, AnchorDeserialize)]
-pub struct Multisig {
-    #[max_len(SIGNERS_MAX_LEN)]
-    pub signers: Vec<Address>,
+/// Multisig configuration borrowed from a `OneSigState` account.
+#[derive(Clone, Copy)]
+pub struct Multisig<'a> {
+    pub signers: &'a [Address],
     pub threshold: u8,
 }
 
-impl Multisig {
+impl Multisig<'_> {
+    pub fn verify_proved_signers(&self, proved: &[Address]) -> Result<()> {
+        let still_active = proved.iter().filter(|signer| self.signers.contains(signer)).count();
+        require!(still_active >= self.threshold as usize, OneSigError::InsufficientSignatures);
+        Ok(())
+    }
+}
+
+/// Read access to a `OneSigState` account: the header plus the populated part of the signer list.
+pub struct OneSigStateRef<'a> {
+    header: Ref<'a, OneSigState>,
+    signers: Ref<'a, [Address]>,
+}
+
+impl<'a> OneSigStateRef<'a> {
+    pub fn load(loader: &'a AccountLoader<'_, OneSigState>) -> Result<Self> {
+        let data = loader.as_ref().try_borrow_data()?;
+        require!(data.len() >= OneSigState::SIGNERS_OFFSET, OneSigError::InvalidStateSize);
+        let (header, signers) = Ref::map_split(data, |data| {
+            data[OneSigState::HEADER_OFFSET..].split_at(size_of::<OneSigState>())
+        });
+        let header = Ref::map(header, bytemuck::from_bytes::<OneSigState>);
+        header.validate_signers(signers.len())?;
+
+        let signers_len = header.signers_len as usize * ADDRESS_LEN;
+        let signers = Ref::map(signers, |signers| bytemuck::cast_slice(&signers[..signers_len]));
+        Ok(Self { header, signers })
+    }
+
+    pub fn signers(&self) -> &[Address] {
+        &self.signers
+    }
+
+    pub fn multisig(&self) -> Multisig<'_> {
+        Multisig { signers: &self.signers, threshold: self.header.threshold }
+    }
+}
+
+impl Deref for OneSigStateRef<'_> {
+    type Target = OneSigState;
+
+    fn deref(&self) -> &OneSigState {
+        &self.header
+    }
+}
+
+/// Write access to a `OneSigState` account: the header plus the full capacity of the signer list.
+pub struct OneSigStateRefMut<'a> {
+    header: RefMut<'a, OneSigState>,
+    signers: RefMut<'a, [Address]>,
+}
+
+impl<'a> OneSigStateRefMut<'a> {
+    pub fn load(loader: &'a AccountLoader<'_, OneSigState>) -> Result<Self> {
+        let account_info = loader.as_ref();
+        require!(account_info.is_writable, ErrorCode::AccountNotMutable);
+        let data = account_info.try_borrow_mut_data()?;
+        require!(data.len() >= OneSigState::SIGNERS_OFFSET, OneSigError::InvalidStateSize);
+        let (header, signers) = RefMut::map_split(data, |data| {
+            data[OneSigState::HEADER_OFFSET..].split_at_mut(size_of::<OneSigState>())
+        });
+        let header = RefMut::map(header, bytemuck::from_bytes_mut::<OneSigState>);
+        header.validate_signers(signers.len())?;
+
+        let signers_end = header.signers_capacity as usize * ADDRESS_LEN;
+        let signers =
+            RefMut::map(signers, |signers| bytemuck::cast_slice_mut(&mut signers[..signers_end]));
+        Ok(Self { header, signers })
+    }
+
+    pub fn signers(&self) -> &[Address] {
+        &self.signers[..self.header.signers_len as usize]
+    }
+
+    pub fn multisig(&self) -> Multisig<'_> {
+        Multisig { signers: self.signers(), threshold: self.header.threshold }
+    }
+
     pub fn add_signer(&mut self, signer: Address) -> Result<()> {
         require!(signer != Address::default(), OneSigError::InvalidSigner);
-        require!(self.signers.len() < SIGNERS_MAX_LEN, OneSigError::InvalidSignersLen);
-        require!(!self.signers.contains(&signer), OneSigError::DuplicateSigners);
-        self.signers.push(signer);
+        let len = self.header.signers_len as usize;
+        require!(len < self.signers.len() && len < SIGNERS_MAX_LEN, OneSigError::InvalidSignersLen);
+        require!(!self.signers().contains(&signer), OneSigError::DuplicateSigners);
+        self.signers[len] = signer;
+        self.header.signers_len += 1;
         Ok(())
     }
 
-    pub fn remove_signer(&mut self, signer: Address) -> Result<()> {
+    /// Removes `signer`, returning the index it had in the signer list.
+    pub fn remove_signer(&mut self, signer: Address) -> Result<u16> {
         // Find the index of the signer to remove
+        let len = self.header.signers_len as usize;
         let index = self
-            .signers
+            .signers()
             .iter()
             .position(|signer_to_remove| *signer_to_remove == signer)
             .ok_or(OneSigError::MissingSigner)?;
-        self.signers.remove(index);
-        let total_signers = self.signers.len();
-        require!(total_signers >= self.threshold as usize, OneSigError::ThresholdExceedsSigners);
-        Ok(())
+        // Shift the tail down to keep the list ordered, then clear the vacated slot
+        self.signers.copy_within(index + 1..len, index);
+        self.signers[len - 1] = Address::default();
+        self.header.signers_len -= 1;
+        let total_signers = self.header.signers_len as usize;
+        require!(
+            total_signers >= self.header.threshold as usize,
+            OneSigError::ThresholdExceedsSigners
+        );
+        Ok(index as u16)
     }
 
     pub fn set_threshold(&mut self, threshold: u8) -> Result<()> {
         require!(threshold > 0 && threshold <= MAX_THRESHOLD, OneSigError::InvalidThreshold);
-        require!(threshold as usize <= self.signers.len(), OneSigError::ThresholdExceedsSigners);
-        self.threshold = threshold;
+        require!(
+            threshold as usize <= self.header.signers_len as usize,
+            OneSigError::ThresholdExceedsSigners
+        );
+        self.header.threshold = threshold;
         Ok(())
     }
 
-    pub fn verify_proved_signers(&self, proved: &[Address]) -> Result<()> {
-        let still_active = proved.iter().filter(|signer| self.signers.contains(signer)).count();
-        require!(still_active >= self.threshold as usize, OneSigError::InsufficientSignatures);
+    pub fn set_executor_required(&mut self, required: bool) -> Result<()> {
+        self.header.executor_required = required as u8;
         Ok(())
     }
+
+    pub fn set_target_policy(&mut self, policy: TargetPolicy) {
+        self.header.target_policy = policy as u8;
+    }
+
+    pub fn set_self_cpi_targets(&mut self, targets: &[SelfCpiTarget]) {
+        self.header.self_cpi_targets = SelfCpiTarget::mask(targets);
+    }
+
+    /// Replaces the execution rate limit, the next execution starting a new window.
+    pub fn set_execution_rate_limit(&mut self, limit: ExecutionRateLimit) -> Result<()> {
+        let ExecutionRateLimit { max_executions, window } = limit;
+        require!(
+            window >= 0 && (max_executions == 0 || window > 0),
+            OneSigError::InvalidExecutionRateLimit
+        );
+        self.header.max_executions = max_executions;
+        self.header.execution_window = window;
+        self.header.execution_window_start = 0;
+        self.header.window_executions = 0;
+        Ok(())
+    }
+
+    /// Records that the signer PDA of vault `vault_index` signs for the account, so that
+    /// `close_one_sig` requires it to be drained.
+    pub fn record_vault(&mut self, vault_index: u8) {
+        self.header.vaults[vault_index as usize / 8] |= 1 << (vault_index % 8);
+    }
+
+    /// Charges a leaf executed at `now` to the execution rate limit, starting a new window once the
+    /// current one has elapsed.
+    pub fn record_execution(&mut self, now: i64) -> Result<()> {
+        require!(self.header.allows_execution(now), OneSigError::ExecutionRateLimited);
+        if self.header.execution_window_elapsed(now) {
+            self.header.execution_window_start = now;
+            self.header.window_executions = 0;
+        }
+        self.header.window_executions = self.header.window_executions.saturating_add(1);
+        Ok(())
+    }
+}
+
+impl Deref for OneSigStateRefMut<'_> {
+    type Target = OneSigState;
+
+    fn deref(&self) -> &OneSigState {
+        &self.header
+    }
+}
+
+impl DerefMut for OneSigStateRefMut<'_> {
+    fn deref_mut(&mut self) -> &mut OneSigState {
+        &mut self.header
+    }
+}
+
+/// Marks `executor` as an approved executor of a OneSig account. The PDA is derived from
+/// `[EXECUTOR_SEED, one_sig_state, executor]`, so its existence is the membership check.
+#[account]
+pub struct ExecutorState {
+    pub executor: Pubkey,
+    // Rent is refunded to this account when the executor is removed.
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+    // Programs the executor may have executed leaves call while `executor_required` is set; any
+    // program when empty
+    pub target_programs: Vec<Pubkey>,
+}
+
+impl ExecutorState {
+    /// Account size (including discriminator) for `len` target programs.
+    pub fn space(len: usize) -> usize {
+        8 + 32 + 32 + 1 + 4 + len * 32
+    }
+
+    pub fn allows_target(&self, program_id: &Pubkey) -> bool {
+        self.target_programs.is_empty() || self.target_programs.contains(program_id)
+    }
+}
+
+/// Marks `module` as a module program of a OneSig account, which can have the signer PDAs execute
+/// instructions without a merkle proof through `execute_module_transaction`. The PDA is derived
+/// from `[MODULE_SEED, one_sig_state, module]`, so its existence is the membership check.
+#[account]
+#[derive(InitSpace)]
+pub struct ModuleState {
+    pub module: Pubkey,
+    // Rent is refunded to this account when the module is removed.
+    pub rent_payer: Pubkey,
+    pub bump: u8,
 }
 
+/// Marks a leaf as stuck, recorded by `record_leaf_failure`: it passes every check of
+/// `execute_transaction`, but the instruction it calls failed, with `reported_error_code`, when the
+/// executor simulated it. The PDA is derived from `[LEAF_FAILURE_SEED, one_sig_state, leaf]`, so
+/// monitors find the stuck leaves of a OneSig account among the program accounts, and executors
+/// retry them.
 #[account]
 #[derive(InitSpace)]
+pub struct LeafFailure {
+    // First, for the accounts of a OneSig account to be filtered on
+    pub one_sig_state: Pubkey,
+    pub leaf: Hash,
+    pub bump: u8,
+    // Nonce the leaf executes at, and `seed_commitment` of the seed the nonce is namespaced by
+    pub nonce: u64,
+    pub seed_commitment: [u8; 16],
+    // Code of the error the call failed with, as reported by the executor. Informational only: the
+    // program cannot observe the failure, so the code is unverified.
+    pub reported_error_code: u32,
+    pub recorded_at: i64,
+    // The executor that recorded the failure. Rent is refunded to it when the account is closed.
+    pub rent_payer: Pubkey,
+}
+
+impl LeafFailure {
+    /// Whether the leaf can no longer execute on a OneSig account in `state`: its nonce moved past
+    /// the leaf (executing it, or the leaves before it), or its seed changed.
+    pub fn outdated(&self, state: &OneSigState) -> bool {
+        state.nonce > self.nonce || self.seed_commitment != digest::seed_commitment(&state.seed)
+    }
+}
+
+/// Lists the `MerkleRootState` accounts currently open for a OneSig account, so they can be
+/// enumerated without scanning program accounts. The account grows by one entry in
+/// `verify_merkle_root` and shrinks back in `close_merkle_root`.
+#[account]
+pub struct MerkleRootRegistry {
+    pub entries: Vec<MerkleRootEntry>,
+    pub bump: u8,
+}
+
+impl MerkleRootRegistry {
+    /// Account size (including discriminator) for `len` entries.
+    pub fn space(len: usize) -> usize {
+        8 + 4 + len * MerkleRootEntry::INIT_SPACE + 1
+    }
+}
+
+/// Spending limits of a OneSig account, which every executed leaf is charged against. Changing
+/// them takes a leaf of its own (`set_spending_limit` / `remove_spending_limit`).
+#[account]
+pub struct SpendingLimits {
+    pub entries: Vec<SpendingLimit>,
+    pub bump: u8,
+    // Lamports paid by the vault's signer PDA to the executor of every leaf landed through
+    // `execute_transaction`, charged like any other SOL spend; none when 0
+    pub executor_reimbursement: u64,
+}
+
+impl SpendingLimits {
+    /// Account size (including discriminator) for `len` entries.
+    pub fn space(len: usize) -> usize {
+        8 + 4 + len * SpendingLimit::INIT_SPACE + 1 + 8
+    }
+
+    /// Number of entries once a limit for `mint` is set.
+    pub fn len_after_set(&self, mint: &Pubkey) -> usize {
+        self.entries.len() + usize::from(!self.entries.iter().any(|limit| limit.mint == *mint))
+    }
+
+    /// Charges `amount` of `mint` spent at `now` to its limit, if any, starting a new window once
+    /// the current one has elapsed.
+    pub fn record_spend(&mut self, mint: &Pubkey, amount: u64, now: i64) -> Result<()> {
+        let Some(limit) = self.entries.iter_mut().find(|limit| limit.mint == *mint) else {
+            return Ok(());
+        };
+        if now >= limit.window_start.saturating_add(limit.window) {
+            limit.window_start = now;
+            limit.spent = 0;
+        }
+        limit.spent = limit.spent.saturating_add(amount);
+        require!(limit.spent <= limit.amount, OneSigError::SpendingLimitExceeded);
+        Ok(())
+    }
+}
+
+/// Programs the `target_policy` of a OneSig account allows or denies executed leaves to call.
+/// Allocated for `TARGET_PROGRAMS_MAX_LEN` programs up front, so `set_config` never resizes it.
+#[account]
+#[derive(InitSpace)]
+pub struct TargetPrograms {
+    #[max_len(TARGET_PROGRAMS_MAX_LEN)]
+    pub programs: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[derive(Clone, Copy, InitSpace, AnchorSerialize, AnchorDeserialize)]
+pub struct SpendingLimit {
+    // Token mint, or the default pubkey for SOL (lamports of the one_sig_signer PDA)
+    pub mint: Pubkey,
+    // Maximum amount that can be spent per window
+    pub amount: u64,
+    // Window length, in seconds
+    pub window: i64,
+    pub window_start: i64,
+    // Amount spent since `window_start`
+    pub spent: u64,
+}
+
+#[derive(Clone, Copy, InitSpace, AnchorSerialize, AnchorDeserialize)]
+pub struct MerkleRootEntry {
+    // Address of the `MerkleRootState` account
+    pub address: Pubkey,
+    pub expiry: i64,
+}
+
+/// A merkle root verified by `verify_merkle_root`, whose leaves then execute without signatures.
+///
+/// The account is sized to the signers it records (see [`MerkleRootState::space`]), its
+/// fixed-size fields first, and keeps a commitment to the seed rather than the seed itself: one
+/// verified with 3 signatures spans 171 bytes, where the ones of the first release all spanned 517.
+#[account]
 pub struct MerkleRootState {
     // Store root here so that we can execute tx without providing the root
     // and save instruction data size
     pub merkle_root: Hash,
-    // The copied seed from the OneSigState account.
-    // If this seed is not the same as the OneSigState account,
-    // the merkle root is invalid
-    pub seed: Hash,
+    // Layout version, see `MERKLE_ROOT_STATE_VERSION`
+    pub version: u8,
+    pub bump: u8,
     // The same type as UnixTimestamp
     pub expiry: i64,
+    // `seed_commitment` of the seed of the OneSigState account at verification time. Once the
+    // seed changes, the merkle root is invalid, see `verified_under`.
+    pub seed_commitment: [u8; 16],
     // Rent is refunded to this account when the account is closed.
     pub rent_payer: Pubkey,
-    // The signers that signed this root at verification time.
-    #[max_len(SIGNERS_MAX_LEN)]
+    // Leaves executed under this root, for dashboards to show the progress of its batch
+    pub executions: u32,
+    // Executions left for a root signed with `max_executions`; `None` if unlimited
+    pub remaining_executions: Option<u32>,
+    // Every signer whose signature verified this root, sorted.
     pub signed_by: Vec<Address>,
-    pub bump: u8,
-}
-
-#[derive(InitSpace, Clone, AnchorSerialize, AnchorDeserialize)]
-pub struct Executors {
-    #[max_len(EXECUTORS_MAX_LEN)]
-    pub executors: Vec<Pubkey>,
-    pub executor_required: bool,
 }
 
-impl Executors {
-    pub fn add_executor(&mut self, executor: Pubkey) -> Result<()> {
-        require!(executor != Pubkey::default(), OneSigError::InvalidExecutor);
-        require!(self.executors.len() < EXECUTORS_MAX_LEN, OneSigError::InvalidExecutorsLen);
-        require!(!self.executors.contains(&executor), OneSigError::DuplicateExecutor);
-        self.executors.push(executor);
-        Ok(())
+impl MerkleRootState {
+    /// Account size (including discriminator) recording `signers` signers.
+    pub fn space(signers: usize) -> usize {
+        8 + 32 + 1 + 1 + 8 + 16 + 32 + 4 + 5 + 4 + signers * ADDRESS_LEN
     }
 
-    pub fn remove_executor(&mut self, executor: Pubkey) -> Result<()> {
-        // Find the index of the executor to remove
-        let index = self
-            .executors
-            .iter()
-            .position(|executor_to_remove| *executor_to_remove == executor)
-            .ok_or(OneSigError::ExecutorNotFound)?;
-        self.executors.remove(index);
-        Ok(())
+    /// The `seed_commitment` of a root verified under `seed`, see
+    /// [`onesig_core::digest::seed_commitment`].
+    pub fn seed_commitment(seed: &Hash) -> [u8; 16] {
+        digest::seed_commitment(seed)
     }
 
-    pub fn set_executor_required(&mut self, required: bool) -> Result<()> {
-        self.executor_required = required;
-        Ok(())
+    /// Whether the root was verified under `seed`, i.e. the seed of its OneSig account did not
+    /// change since.
+    pub fn verified_under(&self, seed: &Hash) -> bool {
+        self.seed_commitment == Self::seed_commitment(seed)
     }
 }
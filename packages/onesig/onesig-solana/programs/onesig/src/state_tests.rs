@@ -0,0 +1,382 @@
+// Tests for the zero-copy `OneSigState` layout: the header plus the signer list stored after it
+// in the account data.
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::*, Discriminator};
+
+    use crate::{
+        state::{
+            ExecutorState, MerkleRootEntry, MerkleRootRegistry, MerkleRootState, OneSigState,
+            OneSigStateRef, OneSigStateRefMut, SpendingLimit, SpendingLimits, TargetPrograms,
+        },
+        types::{ExecutionRateLimit, Hash, SelfCpiTarget, TargetPolicy},
+        Address, OneSigError, CLUSTER_GENESIS_HASH, ID, MERKLE_ROOT_STATE_VERSION,
+        ONE_SIG_STATE_VERSION, SIGNERS_MAX_LEN,
+    };
+
+    const SIGNERS_CAPACITY: u16 = 3;
+
+    // Account data backed by u64 words so the zero-copy header is correctly aligned.
+    fn new_account_data(signers_capacity: u16) -> Vec<u64> {
+        let space = OneSigState::space(signers_capacity);
+        let mut words = vec![0u64; space.div_ceil(8)];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        data[..8].copy_from_slice(OneSigState::DISCRIMINATOR);
+        let header: &mut OneSigState =
+            bytemuck::from_bytes_mut(&mut data[8..OneSigState::SIGNERS_OFFSET]);
+        header.signers_capacity = signers_capacity;
+        header.version = ONE_SIG_STATE_VERSION;
+        words
+    }
+
+    fn with_state<R>(words: &mut [u64], f: impl FnOnce(&AccountLoader<OneSigState>) -> R) -> R {
+        with_state_sized(words, OneSigState::space(SIGNERS_CAPACITY), f)
+    }
+
+    fn with_state_sized<R>(
+        words: &mut [u64],
+        space: usize,
+        f: impl FnOnce(&AccountLoader<OneSigState>) -> R,
+    ) -> R {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let data: &mut [u8] = &mut bytemuck::cast_slice_mut(words)[..space];
+        let account_info = AccountInfo::new(&key, false, true, &mut lamports, data, &ID, false);
+        let loader = AccountLoader::<OneSigState>::try_from(&account_info).unwrap();
+        f(&loader)
+    }
+
+    fn address(byte: u8) -> Address {
+        Address([byte; 20])
+    }
+
+    #[test]
+    fn test_space() {
+        assert_eq!(OneSigState::SIGNERS_OFFSET, 8 + 152);
+        assert_eq!(OneSigState::space(3), 8 + 152 + 3 * 20);
+    }
+
+    #[test]
+    fn test_merkle_root_registry_space() {
+        let entry = MerkleRootEntry { address: Pubkey::new_unique(), expiry: 1 };
+        let registry = MerkleRootRegistry { entries: vec![entry; 2], bump: 255 };
+        let mut data = Vec::new();
+        registry.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), MerkleRootRegistry::space(2));
+    }
+
+    #[test]
+    fn test_merkle_root_state_space_and_seed() {
+        let seed = Hash([2; 32]);
+        let root = MerkleRootState {
+            merkle_root: Hash([1; 32]),
+            version: MERKLE_ROOT_STATE_VERSION,
+            bump: 255,
+            expiry: 1,
+            seed_commitment: MerkleRootState::seed_commitment(&seed),
+            rent_payer: Pubkey::new_unique(),
+            executions: 0,
+            remaining_executions: Some(3),
+            signed_by: vec![Address([0xaa; 20]); 3],
+        };
+        let mut data = Vec::new();
+        root.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), MerkleRootState::space(3));
+        assert_eq!(MerkleRootState::space(3), 171);
+        // Never the size of the accounts of the first release, which `migrate_state` relies on
+        assert!((0..=SIGNERS_MAX_LEN).all(|signers| MerkleRootState::space(signers) != 517));
+
+        assert!(root.verified_under(&seed));
+        assert!(!root.verified_under(&Hash([3; 32])));
+    }
+
+    #[test]
+    fn test_executor_state_space_and_targets() {
+        let oracle = Pubkey::new_unique();
+        let mut executor = ExecutorState {
+            executor: Pubkey::new_unique(),
+            rent_payer: Pubkey::new_unique(),
+            bump: 255,
+            target_programs: vec![],
+        };
+        let mut data = Vec::new();
+        executor.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ExecutorState::space(0));
+        assert!(executor.allows_target(&Pubkey::new_unique()));
+
+        executor.target_programs = vec![oracle];
+        let mut data = Vec::new();
+        executor.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ExecutorState::space(1));
+        assert!(executor.allows_target(&oracle));
+        assert!(!executor.allows_target(&Pubkey::new_unique()));
+    }
+
+    fn spending_limits(mint: Pubkey) -> SpendingLimits {
+        let limit = SpendingLimit { mint, amount: 100, window: 60, window_start: 1_000, spent: 0 };
+        SpendingLimits { entries: vec![limit], bump: 255, executor_reimbursement: 5_000 }
+    }
+
+    #[test]
+    fn test_spending_limits_space() {
+        let limits = spending_limits(Pubkey::default());
+        let mut data = Vec::new();
+        limits.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), SpendingLimits::space(1));
+        assert_eq!(limits.len_after_set(&Pubkey::default()), 1);
+        assert_eq!(limits.len_after_set(&Pubkey::new_unique()), 2);
+    }
+
+    #[test]
+    fn test_record_spend_within_window() {
+        let mint = Pubkey::new_unique();
+        let mut limits = spending_limits(mint);
+
+        limits.record_spend(&mint, 60, 1_010).unwrap();
+        limits.record_spend(&mint, 40, 1_059).unwrap();
+        assert_eq!(
+            limits.record_spend(&mint, 1, 1_059).unwrap_err(),
+            OneSigError::SpendingLimitExceeded.into()
+        );
+        // Unlimited mints are not tracked
+        limits.record_spend(&Pubkey::new_unique(), u64::MAX, 1_059).unwrap();
+    }
+
+    #[test]
+    fn test_record_spend_starts_new_window() {
+        let mint = Pubkey::new_unique();
+        let mut limits = spending_limits(mint);
+
+        limits.record_spend(&mint, 100, 1_000).unwrap();
+        limits.record_spend(&mint, 100, 1_060).unwrap();
+        assert_eq!(limits.entries[0].window_start, 1_060);
+        assert_eq!(limits.entries[0].spent, 100);
+    }
+
+    #[test]
+    fn test_signers_round_trip() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            state.add_signer(address(1)).unwrap();
+            state.add_signer(address(2)).unwrap();
+            state.set_threshold(2).unwrap();
+            state.set_executor_required(true).unwrap();
+        });
+        with_state(&mut words, |loader| {
+            let state = OneSigStateRef::load(loader).unwrap();
+            assert_eq!(state.signers(), &[address(1), address(2)]);
+            assert_eq!(state.multisig().threshold, 2);
+            assert!(state.executor_required());
+        });
+    }
+
+    #[test]
+    fn test_target_policy_round_trip() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            assert_eq!(OneSigStateRef::load(loader).unwrap().target_policy(), TargetPolicy::None);
+            OneSigStateRefMut::load(loader).unwrap().set_target_policy(TargetPolicy::Deny);
+            assert_eq!(OneSigStateRef::load(loader).unwrap().target_policy(), TargetPolicy::Deny);
+        });
+    }
+
+    #[test]
+    fn test_execution_rate_limit() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            // Unlimited until set
+            for _ in 0..100 {
+                state.record_execution(1_000).unwrap();
+            }
+            let limit = ExecutionRateLimit { max_executions: 2, window: 60 };
+            state.set_execution_rate_limit(limit).unwrap();
+            assert_eq!(state.execution_rate_limit(), limit);
+
+            state.record_execution(1_000).unwrap();
+            state.record_execution(1_059).unwrap();
+            assert!(!state.allows_execution(1_059));
+            assert_eq!(
+                state.record_execution(1_059).unwrap_err(),
+                OneSigError::ExecutionRateLimited.into()
+            );
+            // The next window starts with the first execution after the current one elapsed
+            state.record_execution(1_060).unwrap();
+            assert_eq!((state.execution_window_start, state.window_executions), (1_060, 1));
+
+            for limit in [(1, 0), (1, -1), (0, -1)] {
+                let limit = ExecutionRateLimit { max_executions: limit.0, window: limit.1 };
+                assert_eq!(
+                    state.set_execution_rate_limit(limit).unwrap_err(),
+                    OneSigError::InvalidExecutionRateLimit.into()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_used_vaults() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            for vault_index in [255, 0, 9, 8, 9, 1] {
+                state.record_vault(vault_index);
+            }
+            // Vault 0 is the `one_sig_signer`, checked on its own
+            assert_eq!(state.used_vaults().collect::<Vec<_>>(), [1, 8, 9, 255]);
+        });
+    }
+
+    #[test]
+    fn test_self_cpi_policy() {
+        use crate::instruction::{
+            AddModule, CloseOneSig, ExecuteTransaction, SetConfig, VerifyMerkleRoot,
+            WithdrawBountyEscrow,
+        };
+
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            state.set_self_cpi_targets(&[SelfCpiTarget::Modules]);
+            assert_eq!(state.self_cpi_targets(), vec![SelfCpiTarget::Modules]);
+            assert!(state.allows_self_cpi(AddModule::DISCRIMINATOR));
+            assert!(!state.allows_self_cpi(CloseOneSig::DISCRIMINATOR));
+
+            // `set_config` is always allowed, the instructions of no target never are
+            state.set_self_cpi_targets(&[]);
+            assert!(state.allows_self_cpi(SetConfig::DISCRIMINATOR));
+            state.set_self_cpi_targets(&SelfCpiTarget::ALL);
+            assert!(state.allows_self_cpi(WithdrawBountyEscrow::DISCRIMINATOR));
+            assert!(!state.allows_self_cpi(ExecuteTransaction::DISCRIMINATOR));
+            assert!(!state.allows_self_cpi(VerifyMerkleRoot::DISCRIMINATOR));
+            assert!(!state.allows_self_cpi(&[]));
+        });
+    }
+
+    #[test]
+    fn test_cluster_genesis_hash_overrides_build_cluster() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            // `Hash` is not `Debug`
+            assert!(
+                OneSigStateRef::load(loader).unwrap().cluster_genesis_hash() ==
+                    CLUSTER_GENESIS_HASH
+            );
+            OneSigStateRefMut::load(loader).unwrap().cluster_genesis_hash = Hash([7; 32]);
+            assert!(
+                OneSigStateRef::load(loader).unwrap().cluster_genesis_hash() == Some(Hash([7; 32]))
+            );
+        });
+    }
+
+    #[test]
+    fn test_caip2_chain_id() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            OneSigStateRefMut::load(loader).unwrap().cluster_genesis_hash =
+                Hash(pubkey!("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d").to_bytes());
+            assert_eq!(
+                OneSigStateRef::load(loader).unwrap().caip2_chain_id().as_deref(),
+                Some("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp")
+            );
+        });
+    }
+
+    #[test]
+    fn test_target_programs_space() {
+        assert_eq!(TargetPrograms::INIT_SPACE, 4 + 16 * 32 + 1);
+    }
+
+    #[test]
+    fn test_remove_signer_keeps_order() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            for byte in 1..=3 {
+                state.add_signer(address(byte)).unwrap();
+            }
+            state.set_threshold(1).unwrap();
+            assert_eq!(state.remove_signer(address(2)).unwrap(), 1);
+            assert_eq!(state.signers(), &[address(1), address(3)]);
+        });
+    }
+
+    #[test]
+    fn test_add_signer_beyond_capacity() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            for byte in 1..=3 {
+                state.add_signer(address(byte)).unwrap();
+            }
+            assert_eq!(
+                state.add_signer(address(4)).unwrap_err(),
+                OneSigError::InvalidSignersLen.into()
+            );
+        });
+    }
+
+    #[test]
+    fn test_load_rejects_capacity_beyond_account_data() {
+        // Header claims more room than the account data has
+        let mut words = new_account_data(SIGNERS_CAPACITY + 1);
+        with_state(&mut words, |loader| {
+            assert_eq!(
+                OneSigStateRef::load(loader).err().unwrap(),
+                OneSigError::InvalidStateSize.into()
+            );
+        });
+    }
+
+    #[test]
+    fn test_load_rejects_unmigrated_state() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        bytemuck::from_bytes_mut::<OneSigState>(&mut data[8..OneSigState::SIGNERS_OFFSET])
+            .version = 0;
+        with_state(&mut words, |loader| {
+            assert_eq!(
+                OneSigStateRef::load(loader).err().unwrap(),
+                OneSigError::StateVersionMismatch.into()
+            );
+        });
+    }
+
+    #[test]
+    fn test_grow_signers() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        with_state(&mut words, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            state.add_signer(address(1)).unwrap();
+            state.add_signer(address(2)).unwrap();
+            state.set_threshold(2).unwrap();
+        });
+
+        // Emulate the realloc: the account data grows and the new bytes are left dirty
+        let space = OneSigState::space(SIGNERS_CAPACITY + 4);
+        words.resize(space.div_ceil(8), u64::MAX);
+        let data: &mut [u8] = &mut bytemuck::cast_slice_mut(&mut words)[..space];
+        OneSigState::grow_signers(data, SIGNERS_CAPACITY + 4).unwrap();
+
+        with_state_sized(&mut words, space, |loader| {
+            let mut state = OneSigStateRefMut::load(loader).unwrap();
+            assert_eq!(state.signers_capacity, SIGNERS_CAPACITY + 4);
+            assert_eq!(state.signers(), &[address(1), address(2)]);
+            // The freed-up slots are usable
+            for byte in 3..=6 {
+                state.add_signer(address(byte)).unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn test_grow_signers_rejects_shrink() {
+        let mut words = new_account_data(SIGNERS_CAPACITY);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        assert_eq!(
+            OneSigState::grow_signers(data, SIGNERS_CAPACITY - 1).unwrap_err(),
+            OneSigError::InvalidSignersLen.into()
+        );
+    }
+}
@@ -1,173 +1,188 @@
+use std::io::{self, Read};
+
 use anchor_lang::{
     prelude::*,
     solana_program::instruction::{AccountMeta, Instruction},
+    Discriminator,
 };
-use solana_keccak_hasher::{self as keccak, Hash as SolanaHash, HASH_BYTES};
-use solana_secp256k1_recover::{
-    Secp256k1Pubkey as SolanaSecp256k1Pubkey, SECP256K1_PUBLIC_KEY_LENGTH,
+pub use onesig_core::{
+    constants::ADDRESS_LEN,
+    types::{Address, Hash, Secp256k1Pubkey, Signature},
 };
 
-use crate::constants::SIGNATURE_BYTES_LEN;
-
-pub const ADDRESS_LEN: usize = 20;
-
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    Eq,
-    PartialEq,
-    Ord,
-    PartialOrd,
-    Hash,
-    InitSpace,
-    AnchorSerialize,
-    AnchorDeserialize,
-    Default,
-)]
-pub struct Address(pub [u8; ADDRESS_LEN]);
-
-impl Address {
-    pub fn to_bytes(self) -> [u8; ADDRESS_LEN] {
-        self.0
-    }
-}
-
-impl From<Secp256k1Pubkey> for Address {
-    fn from(pubkey: Secp256k1Pubkey) -> Self {
-        let pubkey_bytes = pubkey.to_bytes();
-        let hash = keccak::hash(&pubkey_bytes);
-        let mut address_bytes = [0u8; ADDRESS_LEN];
-        address_bytes.copy_from_slice(&hash.to_bytes()[12..]);
-        Self(address_bytes)
-    }
-}
-
-impl TryFrom<Vec<u8>> for Address {
-    type Error = std::array::TryFromSliceError;
-
-    fn try_from(slice: Vec<u8>) -> std::result::Result<Self, Self::Error> {
-        let array: &[u8; ADDRESS_LEN] = slice.as_slice().try_into()?;
-        Ok(Address(*array))
-    }
-}
-
-#[derive(
-    Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, InitSpace, AnchorSerialize, AnchorDeserialize,
-)]
-pub struct Secp256k1Pubkey(pub [u8; SECP256K1_PUBLIC_KEY_LENGTH]);
-
-impl Secp256k1Pubkey {
-    pub fn new(pubkey_vec: &[u8]) -> Self {
-        Self(
-            <[u8; SECP256K1_PUBLIC_KEY_LENGTH]>::try_from(<&[u8]>::clone(&pubkey_vec))
-                .expect("Slice must be the same length as a Pubkey"),
-        )
-    }
-
-    pub fn to_bytes(self) -> [u8; SECP256K1_PUBLIC_KEY_LENGTH] {
-        self.0
-    }
-}
+use crate::constants::MAX_PROOF_DEPTH;
 
-impl Default for Secp256k1Pubkey {
-    fn default() -> Self {
-        Self([0; SECP256K1_PUBLIC_KEY_LENGTH])
-    }
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct InitOneSigParams {
+    pub one_sig_id: u64,
+    pub seed: Hash,
+    pub threshold: u8,
+    pub signers: Vec<Address>,
+    // An `ExecutorState` PDA is created for each executor, passed in order as remaining accounts
+    pub executors: Vec<Pubkey>,
+    pub executor_required: bool,
+    // Room reserved in the state account for signers added later via `set_config`
+    pub signers_capacity: u16,
 }
 
-impl From<SolanaSecp256k1Pubkey> for Secp256k1Pubkey {
-    fn from(pubkey: SolanaSecp256k1Pubkey) -> Self {
-        Self(pubkey.0)
-    }
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub enum SetConfigParams {
+    AddSigner(Address),
+    RemoveSigner(Address),
+    SetThreshold(u8),
+    SetSeed(Hash),
+    // Deprecated and rejected with `ExecutorConfigMoved`: executors are added and removed by the
+    // `add_executor` and `remove_executor` instructions. Kept so the later variants keep their
+    // Borsh tags
+    AddExecutor(Pubkey),
+    RemoveExecutor(Pubkey),
+    SetExecutorRequired(bool),
+    SetTargetPrograms(SetTargetProgramsParams),
+    // Lamports reimbursed to the executor of each leaf, see `SpendingLimits`
+    SetExecutorReimbursement(u64),
+    // Genesis hash of the chain leaves are bound to, overriding the cluster of the build; all
+    // zero to clear
+    SetClusterGenesisHash(Hash),
+    // Restarts the nonce at 0 under a new seed, which must differ from the current one: the seed
+    // namespaces the nonce, so no leaf or root of the previous namespace can be replayed
+    ResetNonce(Hash),
+    // Replaces the configuration instructions executed leaves may call, see `SelfCpiTarget`
+    SetSelfCpiTargets(Vec<SelfCpiTarget>),
+    // Replaces the execution rate limit, restarting its window
+    SetExecutionRateLimit(ExecutionRateLimit),
 }
 
-impl TryFrom<Vec<u8>> for Secp256k1Pubkey {
-    type Error = std::array::TryFromSliceError;
-
-    fn try_from(slice: Vec<u8>) -> std::result::Result<Self, Self::Error> {
-        let array: &[u8; SECP256K1_PUBLIC_KEY_LENGTH] = slice.as_slice().try_into()?;
-        Ok(Secp256k1Pubkey(*array))
-    }
+/// What a `SetConfigParams` replaced, reported by `ConfigSet` so the change record is complete from
+/// the events alone.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub enum PreviousConfig {
+    // `AddSigner` replaces nothing
+    None,
+    // Index the removed signer had in the signer list
+    SignerIndex(u16),
+    Threshold(u8),
+    Seed(Hash),
+    ExecutorRequired(bool),
+    TargetPrograms(SetTargetProgramsParams),
+    ExecutorReimbursement(u64),
+    // All zero when none was set
+    ClusterGenesisHash(Hash),
+    // Seed and nonce `ResetNonce` restarted from
+    Nonce { seed: Hash, nonce: u64 },
+    SelfCpiTargets(Vec<SelfCpiTarget>),
+    ExecutionRateLimit(ExecutionRateLimit),
 }
 
-#[derive(
-    Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, InitSpace, AnchorSerialize, AnchorDeserialize,
-)]
-pub struct Signature(pub [u8; SIGNATURE_BYTES_LEN]);
-
-impl Default for Signature {
-    fn default() -> Self {
-        Self([0u8; SIGNATURE_BYTES_LEN])
-    }
+/// Number of leaves `execute_transaction` and `signer_execute_transaction` execute per window of
+/// `window` seconds, however many a signed root holds, so a compromised or mistaken root cannot be
+/// drained faster than people watching it can pause execution. Unlimited when `max_executions` is
+/// 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct ExecutionRateLimit {
+    pub max_executions: u32,
+    pub window: i64,
 }
 
-impl Signature {
-    pub fn to_bytes(self) -> [u8; SIGNATURE_BYTES_LEN] {
-        self.0
-    }
-
-    pub fn split_recovery_id(&self) -> (&u8, &[u8]) {
-        self.0.split_last().unwrap()
-    }
+/// Restriction on the programs that executed leaves may call. Calls into this program are
+/// restricted by its self-CPI policy instead (see `SelfCpiTarget`), so the policy can always be
+/// changed back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum TargetPolicy {
+    None,
+    // Only the listed programs can be called
+    Allow,
+    // Every program but the listed ones can be called
+    Deny,
 }
 
-impl TryFrom<&[u8]> for Signature {
-    type Error = anchor_lang::error::Error;
-
-    fn try_from(slice: &[u8]) -> std::result::Result<Self, Self::Error> {
-        let array: &[u8; SIGNATURE_BYTES_LEN] = slice
-            .try_into()
-            .map_err(|_| crate::errors::OneSigError::InvalidSignatureFormat)?;
-        Ok(Signature(*array))
-    }
+/// Configuration instructions of this program, besides `set_config`, that executed leaves may call
+/// when the OneSig account allows them. `set_config` can always be called, so the allowed set can
+/// always be changed back; every other instruction of this program never can, as calls into the
+/// execute paths or the merkle roots could defeat the checks made around the executed leaf.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum SelfCpiTarget {
+    // `add_executor`, `remove_executor` and `set_executor_targets`
+    Executors,
+    // `add_module` and `remove_module`
+    Modules,
+    // `set_spending_limit` and `remove_spending_limit`
+    SpendingLimits,
+    ResizeState,
+    CloseOneSig,
+    // `withdraw_bounty_escrow`
+    BountyEscrow,
 }
 
-#[derive(
-    Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, InitSpace, AnchorSerialize, AnchorDeserialize,
-)]
-pub struct Hash(pub [u8; HASH_BYTES]);
-
-impl AsRef<[u8]> for Hash {
-    fn as_ref(&self) -> &[u8] {
-        &self.0[..]
+impl SelfCpiTarget {
+    /// The targets a new or migrated account allows: none, leaving `set_config` as the only
+    /// instruction of this program its leaves can call. Each target lets a leaf move funds or
+    /// accounts outside the configuration signers review in `set_config`, and none is needed to
+    /// operate the account, so the multisig opts in with `SetSelfCpiTargets` when it needs one.
+    pub const DEFAULT: [SelfCpiTarget; 0] = [];
+
+    /// Every target
+    pub const ALL: [SelfCpiTarget; 6] = [
+        SelfCpiTarget::Executors,
+        SelfCpiTarget::Modules,
+        SelfCpiTarget::SpendingLimits,
+        SelfCpiTarget::ResizeState,
+        SelfCpiTarget::CloseOneSig,
+        SelfCpiTarget::BountyEscrow,
+    ];
+
+    /// The target a call into this program with instruction data `data` falls under, `None` for
+    /// `set_config` and for the instructions that are never allowed.
+    pub fn of(data: &[u8]) -> Option<Self> {
+        use crate::instruction::{
+            AddExecutor, AddModule, CloseOneSig, RemoveExecutor, RemoveModule, RemoveSpendingLimit,
+            ResizeState, SetExecutorTargets, SetSpendingLimit, WithdrawBountyEscrow,
+        };
+        let calls = |discriminators: &[&[u8]]| {
+            discriminators.iter().any(|discriminator| data.starts_with(discriminator))
+        };
+        if calls(&[
+            AddExecutor::DISCRIMINATOR,
+            RemoveExecutor::DISCRIMINATOR,
+            SetExecutorTargets::DISCRIMINATOR,
+        ]) {
+            Some(Self::Executors)
+        } else if calls(&[AddModule::DISCRIMINATOR, RemoveModule::DISCRIMINATOR]) {
+            Some(Self::Modules)
+        } else if calls(&[SetSpendingLimit::DISCRIMINATOR, RemoveSpendingLimit::DISCRIMINATOR]) {
+            Some(Self::SpendingLimits)
+        } else if calls(&[ResizeState::DISCRIMINATOR]) {
+            Some(Self::ResizeState)
+        } else if calls(&[CloseOneSig::DISCRIMINATOR]) {
+            Some(Self::CloseOneSig)
+        } else if calls(&[WithdrawBountyEscrow::DISCRIMINATOR]) {
+            Some(Self::BountyEscrow)
+        } else {
+            None
+        }
     }
-}
 
-impl From<SolanaHash> for Hash {
-    fn from(hash: SolanaHash) -> Self {
-        Self(hash.to_bytes())
+    /// The bit of the target in the `self_cpi_targets` byte of `OneSigState`
+    pub fn bit(self) -> u8 {
+        1 << self as u8
     }
-}
-
-impl TryFrom<Vec<u8>> for Hash {
-    type Error = std::array::TryFromSliceError;
 
-    fn try_from(slice: Vec<u8>) -> std::result::Result<Self, Self::Error> {
-        let array: &[u8; HASH_BYTES] = slice.as_slice().try_into()?;
-        Ok(Hash(*array))
+    /// `targets` as the `self_cpi_targets` byte of `OneSigState`
+    pub fn mask(targets: &[SelfCpiTarget]) -> u8 {
+        targets.iter().fold(0, |mask, target| mask | target.bit())
     }
 }
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
-pub struct InitOneSigParams {
-    pub one_sig_id: u64,
-    pub seed: Hash,
-    pub threshold: u8,
-    pub signers: Vec<Address>,
-    pub executors: Vec<Pubkey>,
-    pub executor_required: bool,
+pub struct SetTargetProgramsParams {
+    pub policy: TargetPolicy,
+    // Replaces the listed programs
+    pub programs: Vec<Pubkey>,
 }
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
-pub enum SetConfigParams {
-    AddSigner(Address),
-    RemoveSigner(Address),
-    SetThreshold(u8),
-    SetSeed(Hash),
-    AddExecutor(Pubkey),
-    RemoveExecutor(Pubkey),
-    SetExecutorRequired(bool),
+pub struct ResizeStateParams {
+    // New room for signers; must not be lower than the current capacity
+    pub signers_capacity: u16,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -179,6 +194,54 @@ pub struct ExecuteTransactionParams {
     pub merkle_root_verification: Option<VerifyMerkleRootParams>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RecordLeafFailureParams {
+    // The `execute_transaction` of the leaf, as it was simulated
+    pub execution: ExecuteTransactionParams,
+    // Hash of the leaf, which its `LeafFailure` PDA is derived from
+    pub leaf: Hash,
+    // Code of the error the simulated call failed with, recorded as is: the program cannot verify
+    // it
+    pub reported_error_code: u32,
+}
+
+/// A check `execute_transaction` makes before executing a leaf, in the order it makes them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum TransactionCheck {
+    // The executor is registered, when `executor_required` is set
+    Executor,
+    // The root is verified: inline, or by a `MerkleRootState` of the current seed and layout that
+    // has executions left
+    MerkleRoot,
+    Expiry,
+    // The signatures over the root, or the signers its `MerkleRootState` recorded, still make the
+    // threshold
+    Signatures,
+    VaultSigner,
+    // The leaf, at the current nonce, proves against the root
+    Proof,
+    // The target policy of the OneSig account allows the program the leaf calls, or its self-CPI
+    // policy the instruction of this program it calls
+    TargetProgram,
+    // The executor may call that program, when `executor_required` is set
+    ExecutorTarget,
+    // The leaf's not-before timestamp, if any, has passed
+    NotBefore,
+    // The execution rate limit of the OneSig account leaves room for another execution
+    ExecutionRate,
+}
+
+/// What `check_transaction` returns as return data
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct TransactionCheckResult {
+    // The first check the leaf fails, `None` if it passes them all
+    pub failed: Option<TransactionCheck>,
+    // Code of the error `execute_transaction` would fail that check with, 0 if none
+    pub error_code: u32,
+    // Nonce the leaf was checked at
+    pub nonce: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct SignerExecuteTransactionParams {
     // Transaction with calls and Merkle proof
@@ -194,24 +257,113 @@ pub struct SignerExecuteTransactionParams {
     pub expiry: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetExecutorTargetsParams {
+    pub executor: Pubkey,
+    // Replaces the programs the executor may target; empty lifts the restriction
+    pub programs: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetSpendingLimitParams {
+    // Token mint, or the default pubkey for SOL
+    pub mint: Pubkey,
+    // Maximum amount that can be spent per window
+    pub amount: u64,
+    // Window length, in seconds
+    pub window: i64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct VerifyMerkleRootParams {
     // Expected Merkle root
     pub merkle_root: Hash,
     // Root validity timestamp
     pub expiry: i64,
+    // Number of leaves the root may execute once verified into a `MerkleRootState`, or 0 for no
+    // limit. Part of the signed digest whenever non-zero.
+    pub max_executions: u32,
     // Concatenated signatures
     pub signatures: Vec<u8>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+// Deserialized by hand to bound `proof`, see `MAX_PROOF_DEPTH`
+#[derive(AnchorSerialize, Clone)]
 pub struct OneSigTransaction {
     pub ix_data: Vec<u8>,
     // The maximum amount of SOL that can be spent by the subsequent instruction
     pub value: u64,
+    // The maximum amounts of SPL tokens the instruction may move out of the token accounts owned
+    // by the one_sig_signer PDA. Part of the leaf when non-empty; mints not listed are unbounded.
+    pub token_outflow_limits: Vec<TokenOutflowLimit>,
+    // Further accounts whose lamports count against `value` together with the one_sig_signer
+    // PDA's, e.g. program-owned accounts the instruction can debit. Part of the leaf when
+    // non-empty.
+    pub guarded_accounts: Vec<Pubkey>,
+    // Vault whose signer PDA executes the instruction; 0 is the `one_sig_signer` PDA. Part of the
+    // leaf when non-zero.
+    pub vault_index: u8,
+    // The group of leaves executed together the leaf belongs to, if any. Part of the leaf when
+    // set.
+    pub group: Option<TransactionGroup>,
+    // Unix timestamp before which the leaf cannot be executed, if any. Part of the leaf when set.
+    pub not_before: Option<i64>,
+    // Lamports `execute_transaction` pays from the bounty escrow to whoever executes the leaf
+    // while `executor_required` is not set, if any. Part of the leaf when set.
+    pub bounty: Option<u64>,
     pub proof: Vec<Hash>,
 }
 
+impl AnchorDeserialize for OneSigTransaction {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            ix_data: AnchorDeserialize::deserialize_reader(reader)?,
+            value: AnchorDeserialize::deserialize_reader(reader)?,
+            token_outflow_limits: AnchorDeserialize::deserialize_reader(reader)?,
+            guarded_accounts: AnchorDeserialize::deserialize_reader(reader)?,
+            vault_index: AnchorDeserialize::deserialize_reader(reader)?,
+            group: AnchorDeserialize::deserialize_reader(reader)?,
+            not_before: AnchorDeserialize::deserialize_reader(reader)?,
+            bounty: AnchorDeserialize::deserialize_reader(reader)?,
+            proof: deserialize_proof(reader)?,
+        })
+    }
+}
+
+// A proof of at most `MAX_PROOF_DEPTH` hashes, its length checked before any of them is read
+fn deserialize_proof<R: Read>(reader: &mut R) -> io::Result<Vec<Hash>> {
+    let len = u32::deserialize_reader(reader)? as usize;
+    if len > MAX_PROOF_DEPTH {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "proof deeper than MAX_PROOF_DEPTH"));
+    }
+    (0..len).map(|_| Hash::deserialize_reader(reader)).collect()
+}
+
+/// Position of a leaf among the leaves at `len` consecutive nonces that only execute together:
+/// each is executed by the instruction of the transaction following the one of the leaf before,
+/// so a change made of several leaves is never left half applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct TransactionGroup {
+    // Of the leaf in the group, in nonce order
+    pub index: u8,
+    pub len: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExecuteModuleTransactionParams {
+    pub ix_data: Vec<u8>,
+    // The maximum amount of SOL that can be spent by the instruction
+    pub value: u64,
+    // Vault whose signer PDA executes the instruction; 0 is the `one_sig_signer` PDA
+    pub vault_index: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenOutflowLimit {
+    pub mint: Pubkey,
+    pub max_amount: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct OneSigAccountMeta {
     pub pubkey: Pubkey,
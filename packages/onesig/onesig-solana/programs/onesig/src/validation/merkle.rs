@@ -1,114 +1,140 @@
-use anchor_lang::prelude::{borsh::BorshSerialize, *};
-use solana_keccak_hasher as keccak;
+use anchor_lang::prelude::*;
+use onesig_core::{
+    digest,
+    leaf::{self, LeafAccountMeta, LeafGroup, LeafInstruction, LeafTokenOutflowLimit},
+};
 
 use super::signature::SignatureValidator;
 use crate::{
-    constants::*,
+    constants::MAX_PROOF_DEPTH,
     errors::OneSigError,
-    state::OneSigState,
-    types::{Address, Hash, OneSigInstruction},
+    state::Multisig,
+    types::{
+        Address, Hash, OneSigAccountMeta, OneSigInstruction, TokenOutflowLimit, TransactionGroup,
+    },
 };
 
 pub struct MerkleValidator;
 
 impl MerkleValidator {
-    /// Verifies Merkle root expiry and signatures, returning the recovered signers.
+    /// Verifies Merkle root expiry and signatures, returning the first `threshold` signers
+    /// recovered.
     pub fn verify_merkle_root(
-        one_sig_state: &OneSigState,
+        seed: &Hash,
+        multisig: &Multisig,
         merkle_root: &Hash,
         expiry: i64,
+        max_executions: u32,
         signatures: &[u8],
         current_timestamp: i64,
     ) -> Result<Vec<Address>> {
         require!(expiry >= current_timestamp, OneSigError::ExpiredMerkleRoot);
 
-        let expiry_u128: u128 = expiry.try_into().unwrap();
-        // Build EIP-712 style digest
-        let digest = keccak::hashv(&[
-            &EIP191_PREFIX_FOR_EIP712,
-            &DOMAIN_SEPARATOR,
-            keccak::hashv(&[
-                SIGN_MERKLE_ROOT_TYPE_HASH.as_ref(),
-                one_sig_state.seed.as_ref(),
-                merkle_root.as_ref(),
-                &0u128.to_be_bytes(),       // high bytes of uint256
-                &expiry_u128.to_be_bytes(), // low bytes of uint256
-            ])
-            .as_ref(),
-        ]);
+        let digest = Self::merkle_root_digest(seed, merkle_root, expiry, max_executions)?;
 
         // Verify multisig signatures on digest
         SignatureValidator::verify_signatures(
-            one_sig_state.multisig.threshold,
-            &one_sig_state.multisig.signers,
-            &digest.into(),
+            multisig.threshold,
+            multisig.signers,
+            &digest,
             signatures,
         )
     }
 
-    pub fn verify_merkle_proof(merkle_root: &Hash, proof: &[Hash], leaf: &Hash) -> Result<()> {
-        let mut computed_hash = *leaf;
+    /// Like [`Self::verify_merkle_root`], but recovers every signature, returning all the
+    /// signers, for a `MerkleRootState` to record.
+    pub fn verify_merkle_root_signers(
+        seed: &Hash,
+        multisig: &Multisig,
+        merkle_root: &Hash,
+        expiry: i64,
+        max_executions: u32,
+        signatures: &[u8],
+        current_timestamp: i64,
+    ) -> Result<Vec<Address>> {
+        require!(expiry >= current_timestamp, OneSigError::ExpiredMerkleRoot);
+
+        let digest = Self::merkle_root_digest(seed, merkle_root, expiry, max_executions)?;
 
-        // Apply proof elements in order
-        for p in proof.iter() {
-            computed_hash = if computed_hash < *p {
-                keccak::hashv(&[computed_hash.as_ref(), p.as_ref()]).into()
-            } else {
-                keccak::hashv(&[p.as_ref(), computed_hash.as_ref()]).into()
-            };
-        }
+        SignatureValidator::verify_every_signature(
+            multisig.threshold,
+            multisig.signers,
+            &digest,
+            signatures,
+        )
+    }
 
-        // Verify computed root matches expected
-        require!(computed_hash == *merkle_root, OneSigError::InvalidProof);
+    /// The EIP-712 style digest signers sign to approve `merkle_root`, see
+    /// [`onesig_core::digest::merkle_root_digest`]. A negative `expiry` has already expired.
+    pub fn merkle_root_digest(
+        seed: &Hash,
+        merkle_root: &Hash,
+        expiry: i64,
+        max_executions: u32,
+    ) -> Result<Hash> {
+        let expiry: u64 = expiry.try_into().map_err(|_| error!(OneSigError::ExpiredMerkleRoot))?;
+        Ok(digest::merkle_root_digest(seed, merkle_root, expiry, max_executions))
+    }
+
+    pub fn verify_merkle_proof(merkle_root: &Hash, proof: &[Hash], leaf: &Hash) -> Result<()> {
+        require!(proof.len() <= MAX_PROOF_DEPTH, OneSigError::ProofTooDeep);
+        require!(leaf::verify_proof(merkle_root, proof, leaf), OneSigError::InvalidProof);
         Ok(())
     }
 
-    // Encodes transaction leaf hash from state and instruction
+    // Encodes transaction leaf hash from state and instruction, see
+    // `onesig_core::leaf::encode_leaf`. The instruction is hashed in place, its data not copied.
+    #[allow(clippy::too_many_arguments)]
     pub fn encode_leaf(
         one_sig_state: &Pubkey,
         one_sig_id: u64,
         nonce: u64,
         instruction: &OneSigInstruction,
+        token_outflow_limits: &[TokenOutflowLimit],
+        guarded_accounts: &[Pubkey],
+        vault_index: u8,
+        cluster_genesis_hash: Option<&Hash>,
+        group: Option<&TransactionGroup>,
+        not_before: Option<i64>,
+        bounty: Option<u64>,
     ) -> Result<Hash> {
-        let encoded_instruction = MerkleValidator::encode_instruction(instruction)?;
-        let nonce_bytes = nonce.to_be_bytes();
-        let one_sig_id_bytes = one_sig_id.to_be_bytes();
-
-        let leaf_data = vec![
-            MERKLE_LEAF_ENCODING_VERSION.as_ref(),
-            one_sig_id_bytes.as_ref(),
-            one_sig_state.as_ref(),
-            nonce_bytes.as_ref(),
-            encoded_instruction.as_ref(),
-        ];
-
-        Ok(keccak::hash(keccak::hashv(&leaf_data).as_ref()).into())
+        Ok(leaf::hash_leaf(
+            one_sig_state.as_array(),
+            one_sig_id,
+            nonce,
+            LeafInstruction {
+                program_id: instruction.program_id.as_array(),
+                accounts: instruction.accounts.iter().map(leaf_account_meta),
+                data: &instruction.data,
+                value: instruction.value,
+            },
+            token_outflow_limits.iter().map(|limit| LeafTokenOutflowLimit {
+                mint: limit.mint.as_array(),
+                max_amount: limit.max_amount,
+            }),
+            guarded_accounts.iter().map(Pubkey::as_array),
+            vault_index,
+            cluster_genesis_hash,
+            group.map(|group| LeafGroup { index: group.index, len: group.len }),
+            not_before,
+            bounty,
+        ))
     }
 
     pub fn encode_instruction(instruction: &OneSigInstruction) -> Result<Vec<u8>> {
-        // Capacity calculation breakdown:
-        //    48 + instruction.accounts.len() * 34 + instruction.data.len()
-        //
-        // 1. 48 bytes of fixed overhead:
-        //    - program_id: Pubkey - 32 bytes (from Instruction struct)
-        //    - Vec serialization overhead - 4 bytes (for accounts vector length prefix)
-        //    - Vec serialization overhead - 4 bytes (for data vector length prefix)
-        //    - u64 value - 8 bytes (from the OneSigInstruction tuple)
-        //
-        // 2. ix.accounts.len() * 34:
-        //    - Each AccountMeta in Borsh serialization takes 34 bytes:
-        //      * pubkey: Pubkey - 32 bytes (standard size of Solana public key)
-        //      * is_signer: bool - 1 byte
-        //      * is_writable: bool - 1 byte
-        //
-        // 3. ix.data.len():
-        //    - The actual instruction data bytes
-        //
-        // This pre-allocation ensures the Vec has sufficient capacity for all serialized data,
-        // avoiding multiple reallocations during serialization, thus improving performance.
-        let mut encoded_data: Vec<u8> =
-            Vec::with_capacity(48 + instruction.accounts.len() * 34 + instruction.data.len());
-        instruction.serialize(&mut encoded_data)?;
-        Ok(encoded_data)
+        Ok(leaf::encode_instruction(
+            instruction.program_id.as_array(),
+            instruction.accounts.iter().map(leaf_account_meta),
+            &instruction.data,
+            instruction.value,
+        ))
+    }
+}
+
+fn leaf_account_meta(account: &OneSigAccountMeta) -> LeafAccountMeta<'_> {
+    LeafAccountMeta {
+        pubkey: account.pubkey.as_array(),
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
     }
 }
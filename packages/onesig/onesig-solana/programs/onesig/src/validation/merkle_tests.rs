@@ -5,36 +5,36 @@
 // 2. Transaction Merkle proofs are verified consistently across chains
 // 3. Merkle root signature verification works the same way as in EVM
 //
-// The test uses hardcoded values from EVM tests
-// (packages/onesig/onesig-evm/test/hardhat/one-sig.test.ts) to verify cross-chain compatibility. To
-// obtain these values, add these console.log statements in EVM tests:
-// ```typescript
-// console.log('signers:', sortedSigners.map((s) => s.publicKey))
-// console.log('seed', SEED)
-// console.log('expiry', expiry)
-// console.log('signatures:', signatures)
-// console.log('leaf', leafEncode(leaves[0]))
-// console.log('proof', proof)
-// console.log('merkleRoot', merkleRoot.toString('hex'))
-// ```
+// Roots are signed in the tests by the k256 signers of `onesig_test_vectors::fixtures`; the
+// committed vectors, which the EVM hardhat tests
+// (packages/onesig/onesig-evm/test/hardhat/test-vectors.test.ts) verify as well, are checked too.
 #[cfg(test)]
 mod tests {
 
-    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::{prelude::Pubkey, AnchorDeserialize, AnchorSerialize};
+    use onesig_test_vectors::fixtures::{self, TestSigner};
+    use solana_keccak_hasher as keccak;
 
     use crate::{
-        state::{Multisig, OneSigState},
-        types::{Hash, OneSigAccountMeta, OneSigInstruction},
-        validation::merkle::MerkleValidator,
-        Address, Executors, OneSigError, Secp256k1Pubkey,
+        constants::{MAX_PROOF_DEPTH, SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH},
+        state::Multisig,
+        types::{
+            Hash, OneSigAccountMeta, OneSigInstruction, OneSigTransaction, TokenOutflowLimit,
+            TransactionGroup,
+        },
+        validation::{merkle::MerkleValidator, signature::SignatureValidator},
+        Address, OneSigError, Secp256k1Pubkey,
     };
 
     // Test fixture struct for MerkleRoot verification tests
     struct MerkleRootTestFixture {
+        keys: Vec<TestSigner>,
         expiry: i64,
         signatures: Vec<u8>,
         merkle_root: Hash,
-        state: OneSigState,
+        seed: Hash,
+        signers: Vec<Address>,
+        threshold: u8,
     }
 
     // Test fixture struct for MerkleProof verification tests
@@ -44,60 +44,54 @@ mod tests {
         leaf: Hash,
     }
 
-    // Helper function to create a default MerkleRootTestFixture
+    // Helper function to create a default MerkleRootTestFixture: three signers at a threshold of
+    // 2, the first two of which signed the root
     fn create_merkle_root_fixture() -> MerkleRootTestFixture {
-        let signers: Vec<Address> = vec![
-            Secp256k1Pubkey::try_from(hex::decode("4c0346927a164fe5d37c5ff73ab31f2ed2416a5679b172477fd1aefb331fa72bc08a10c9a84d21289f8b63244c7b3d73cfb2ef18ad22455937341adfddbd305d")
-                .unwrap()).expect("slice with incorrect length").into(),
-            Secp256k1Pubkey::try_from(hex::decode("fea2a7c50de763bbaaeaf5452eb2412fde3f0610f4be4b1cdb160460f51d9b6c869e5fb895cb15485224977c7e737fb25d4ca1ebb88268d67fb8a9ad79814ed7")
-                .unwrap()).expect("slice with incorrect length").into(),
-        ];
-        let seed = hex::decode("76cc1c7d586cee3a5ae8ff4bab4299354dbba2c59176d79b744ba0a1ce5336fa")
-            .unwrap();
-        let expiry = 1741622488;
-        let signatures = hex::decode("31537dcfe1ec4d7e89f5972ccd3065e34e178e302c1b9e8e175d3843a2964f2863f2b60c7ef74363b687f822db6c313e8762b38d3097b6fec9b7b76165630aaa1b042674fc630e32f9000bc95271ec54283c8ad350016bb66a18464958963a51f3146e8b3fbb3a5bd0b89d3b6c1e01dd614e86bae01351dc3d1ffe3522c83ed4501c")
-            .unwrap();
-        let merkle_root: Hash =
-            hex::decode("9c8e2403d4f1269e83ce128f2265631b22be6327cca2ede7776da9f8db2a9afc")
-                .unwrap()
-                .try_into()
-                .expect("slice with incorrect length");
-
-        let state = OneSigState {
-            seed: seed.clone().try_into().unwrap(),
-            one_sig_id: 900,
-            bump: 0,
-            nonce: 1,
-            multisig: Multisig { signers: signers.clone(), threshold: 2 },
-            executors: Executors { executors: vec![], executor_required: false },
+        let keys = fixtures::signers(3);
+        let mut fixture = MerkleRootTestFixture {
+            expiry: onesig_test_vectors::EXPIRY as i64,
+            signatures: vec![],
+            merkle_root: Hash(keccak::hash(b"merkle root").to_bytes()),
+            seed: Hash(keccak::hash(b"seed").to_bytes()),
+            signers: fixtures::addresses(&keys),
+            threshold: 2,
+            keys,
         };
+        fixture.signatures = fixture.sign(0);
+        fixture
+    }
 
-        MerkleRootTestFixture { expiry, signatures, merkle_root, state }
+    impl MerkleRootTestFixture {
+        fn multisig(&self) -> Multisig<'_> {
+            Multisig { signers: &self.signers, threshold: self.threshold }
+        }
+
+        // Signatures of the first `threshold` signers on the root, capped to `max_executions`
+        fn sign(&self, max_executions: u32) -> Vec<u8> {
+            let digest = onesig_core::digest::merkle_root_digest(
+                &self.seed,
+                &self.merkle_root,
+                self.expiry as u64,
+                max_executions,
+            );
+            fixtures::sign_all(&self.keys[..self.threshold as usize], &digest)
+        }
     }
 
     // Helper function to create a default MerkleProofTestFixture
     fn create_merkle_proof_fixture() -> MerkleProofTestFixture {
-        let proof: Vec<Hash> = vec![
-            hex::decode("c83f6f23f70097d27d2c5f3881632259151032aceddf5467ffc004e9bfc1b74e")
-                .unwrap()
-                .try_into()
-                .expect("slice with incorrect length"),
-            hex::decode("63e335f61023ed89b955c0ee077806ffbdace5c6930e57967d183e10c225e7eb")
-                .unwrap()
-                .try_into()
-                .expect("slice with incorrect length"),
-        ];
-        let merkle_root =
-            hex::decode("9c8e2403d4f1269e83ce128f2265631b22be6327cca2ede7776da9f8db2a9afc")
-                .unwrap()
-                .try_into()
-                .unwrap();
-        let leaf = hex::decode("fdcc354b818ad1de1e3be454bac1ca27100f245ac7ce99ef26d4d7746b05f4f7")
-            .unwrap()
-            .try_into()
-            .unwrap();
-
-        MerkleProofTestFixture { proof, merkle_root, leaf }
+        let mut vectors = onesig_test_vectors::load();
+        MerkleProofTestFixture {
+            proof: vectors
+                .proofs
+                .swap_remove(0)
+                .proof
+                .into_iter()
+                .map(|hash| hash.try_into().unwrap())
+                .collect(),
+            merkle_root: vectors.merkle_root.try_into().unwrap(),
+            leaf: vectors.leaves.swap_remove(0).try_into().unwrap(),
+        }
     }
 
     #[test]
@@ -106,15 +100,172 @@ mod tests {
         let current_timestamp = 0;
 
         assert!(MerkleValidator::verify_merkle_root(
-            &fixture.state,
+            &fixture.seed,
+            &fixture.multisig(),
             &fixture.merkle_root,
             fixture.expiry,
+            0,
             &fixture.signatures,
             current_timestamp,
         )
         .is_ok());
     }
 
+    #[test]
+    fn test_merkle_root_digest_recovers_signers() {
+        let fixture = create_merkle_root_fixture();
+        let digest = MerkleValidator::merkle_root_digest(
+            &fixture.seed,
+            &fixture.merkle_root,
+            fixture.expiry,
+            0,
+        )
+        .unwrap();
+
+        let signed_by = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &digest,
+            &fixture.signatures,
+        )
+        .unwrap();
+        assert_eq!(signed_by.len(), 2);
+        assert!(MerkleValidator::merkle_root_digest(&fixture.seed, &fixture.merkle_root, -1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sign_limited_merkle_root_type_hash_matches_source_string() {
+        let computed = keccak::hash(
+            b"SignLimitedMerkleRoot(bytes32 seed,bytes32 merkleRoot,uint256 expiry,uint256 maxExecutions)",
+        );
+        assert_eq!(computed.to_bytes(), SIGN_LIMITED_MERKLE_ROOT_TYPE_HASH);
+    }
+
+    #[test]
+    fn test_verify_merkle_root_rejects_unsigned_max_executions() {
+        // The fixture signs `SignMerkleRoot`, so its signatures must not authorize a capped root
+        let fixture = create_merkle_root_fixture();
+
+        let result = MerkleValidator::verify_merkle_root(
+            &fixture.seed,
+            &fixture.multisig(),
+            &fixture.merkle_root,
+            fixture.expiry,
+            1,
+            &fixture.signatures,
+            0,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_limited_merkle_root() {
+        let fixture = create_merkle_root_fixture();
+
+        assert!(MerkleValidator::verify_merkle_root(
+            &fixture.seed,
+            &fixture.multisig(),
+            &fixture.merkle_root,
+            fixture.expiry,
+            onesig_test_vectors::MAX_EXECUTIONS,
+            &fixture.sign(onesig_test_vectors::MAX_EXECUTIONS),
+            0,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_verify_merkle_root_matches_vectors() {
+        let vectors = onesig_test_vectors::load();
+        let signers: Vec<Address> = vectors
+            .signers
+            .iter()
+            .map(|signer| Secp256k1Pubkey::try_from(signer.public_key.clone()).unwrap().into())
+            .collect();
+        let multisig = Multisig { signers: &signers, threshold: vectors.threshold };
+        let seed: Hash = vectors.seed.try_into().unwrap();
+        let merkle_root: Hash = vectors.merkle_root.try_into().unwrap();
+
+        for (max_executions, signatures) in [
+            (0, &vectors.merkle_root_signatures),
+            (onesig_test_vectors::MAX_EXECUTIONS, &vectors.limited_merkle_root_signatures),
+        ] {
+            let digest = MerkleValidator::merkle_root_digest(
+                &seed,
+                &merkle_root,
+                vectors.expiry as i64,
+                max_executions,
+            )
+            .unwrap();
+            assert_eq!(digest.0.to_vec(), signatures.digest);
+            assert!(MerkleValidator::verify_merkle_root(
+                &seed,
+                &multisig,
+                &merkle_root,
+                vectors.expiry as i64,
+                max_executions,
+                &signatures.signatures,
+                0,
+            )
+            .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_encode_leaf_matches_vectors() {
+        let pubkey = |bytes: &[u8]| Pubkey::try_from(bytes).unwrap();
+        for vector in onesig_test_vectors::load().solana_leaves {
+            let instruction = OneSigInstruction {
+                program_id: pubkey(&vector.program_id),
+                accounts: vector
+                    .accounts
+                    .iter()
+                    .map(|account| OneSigAccountMeta {
+                        pubkey: pubkey(&account.pubkey),
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                    .collect(),
+                data: vector.data,
+                value: vector.value,
+            };
+            let limits: Vec<TokenOutflowLimit> = vector
+                .token_outflow_limits
+                .iter()
+                .map(|limit| TokenOutflowLimit {
+                    mint: pubkey(&limit.mint),
+                    max_amount: limit.max_amount,
+                })
+                .collect();
+            let guarded: Vec<Pubkey> =
+                vector.guarded_accounts.iter().map(|account| pubkey(account)).collect();
+            let cluster_genesis_hash: Option<Hash> = (!vector.cluster_genesis_hash.is_empty())
+                .then(|| vector.cluster_genesis_hash.clone().try_into().unwrap());
+
+            assert_eq!(
+                MerkleValidator::encode_instruction(&instruction).unwrap(),
+                vector.encoded_instruction
+            );
+            let leaf = MerkleValidator::encode_leaf(
+                &pubkey(&vector.one_sig_state),
+                vector.one_sig_id,
+                vector.nonce,
+                &instruction,
+                &limits,
+                &guarded,
+                vector.vault_index,
+                cluster_genesis_hash.as_ref(),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            assert_eq!(leaf.0.to_vec(), vector.leaf);
+        }
+    }
+
     #[test]
     fn test_verify_merkle_proof() {
         let fixture = create_merkle_proof_fixture();
@@ -134,9 +285,11 @@ mod tests {
         // Set current_timestamp to be greater than expiry to trigger expiration error
         let current_timestamp = fixture.expiry + 1;
         let result = MerkleValidator::verify_merkle_root(
-            &fixture.state,
+            &fixture.seed,
+            &fixture.multisig(),
             &fixture.merkle_root,
             fixture.expiry,
+            0,
             &fixture.signatures,
             current_timestamp,
         );
@@ -148,15 +301,18 @@ mod tests {
     fn test_verify_merkle_root_invalid_signatures() {
         let fixture = create_merkle_root_fixture();
 
-        // Corrupt the signature by changing some bytes
+        // Corrupt the first signature so no public key can be recovered from it: a zero r is
+        // rejected, whereas a tweaked byte would usually just recover an unknown signer
         let mut invalid_signatures = fixture.signatures.clone();
-        invalid_signatures[10] = invalid_signatures[10].wrapping_add(1); // Modify a byte in the first signature
+        invalid_signatures[..32].fill(0);
 
         let current_timestamp = 0;
         let result = MerkleValidator::verify_merkle_root(
-            &fixture.state,
+            &fixture.seed,
+            &fixture.multisig(),
             &fixture.merkle_root,
             fixture.expiry,
+            0,
             &invalid_signatures,
             current_timestamp,
         );
@@ -169,11 +325,8 @@ mod tests {
         let fixture = create_merkle_proof_fixture();
 
         // Use an incorrect leaf hash that won't produce the expected Merkle root
-        let invalid_leaf =
-            hex::decode("0000354b818ad1de1e3be454bac1ca27100f245ac7ce99ef26d4d7746b05f4f7")
-                .unwrap()
-                .try_into()
-                .unwrap();
+        let mut invalid_leaf = fixture.leaf;
+        invalid_leaf.0[..2].fill(0);
 
         let result = MerkleValidator::verify_merkle_proof(
             &fixture.merkle_root,
@@ -184,6 +337,48 @@ mod tests {
         assert_eq!(result.unwrap_err(), OneSigError::InvalidProof.into());
     }
 
+    #[test]
+    fn test_verify_merkle_proof_too_deep() {
+        let fixture = create_merkle_proof_fixture();
+        let proof = vec![Hash([0x9f; 32]); MAX_PROOF_DEPTH + 1];
+
+        let result =
+            MerkleValidator::verify_merkle_proof(&fixture.merkle_root, &proof, &fixture.leaf);
+
+        assert_eq!(result.unwrap_err(), OneSigError::ProofTooDeep.into());
+    }
+
+    #[test]
+    fn test_transaction_proof_depth_bounded_when_deserialized() {
+        let encode = |depth| {
+            let transaction = OneSigTransaction {
+                ix_data: vec![1, 2, 3],
+                value: 4,
+                token_outflow_limits: vec![],
+                guarded_accounts: vec![Pubkey::new_unique()],
+                vault_index: 5,
+                group: None,
+                not_before: None,
+                bounty: Some(6),
+                proof: vec![Hash([0x9f; 32]); depth],
+            };
+            let mut bytes = vec![];
+            transaction.serialize(&mut bytes).unwrap();
+            bytes
+        };
+
+        let transaction = OneSigTransaction::deserialize(&mut encode(MAX_PROOF_DEPTH).as_slice());
+        assert_eq!(transaction.unwrap().proof.len(), MAX_PROOF_DEPTH);
+        let deeper = encode(MAX_PROOF_DEPTH + 1);
+        assert!(OneSigTransaction::deserialize(&mut deeper.as_slice()).is_err());
+
+        // The length is rejected before anything is read for it
+        let mut bytes = encode(0);
+        let len = bytes.len() - 4;
+        bytes[len..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(OneSigTransaction::deserialize(&mut bytes.as_slice()).is_err());
+    }
+
     #[test]
     fn test_encode_instruction_capacity() {
         let num_accounts = 10;
@@ -224,4 +419,257 @@ mod tests {
             data_size
         );
     }
+
+    #[test]
+    fn test_encode_leaf_appends_declared_guards_only_when_present() {
+        let one_sig_state = Pubkey::new_unique();
+        let instruction = OneSigInstruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![1, 2, 3],
+            value: 0,
+        };
+
+        // Without limits, the leaf is the one every other chain encodes
+        let encoded_instruction = MerkleValidator::encode_instruction(&instruction).unwrap();
+        let expected: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+            ])
+            .as_ref(),
+        )
+        .into();
+        let leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(leaf == expected);
+
+        let limits = [TokenOutflowLimit { mint: Pubkey::new_unique(), max_amount: 10 }];
+        let limited_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &limits,
+            &[],
+            0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(limited_leaf != leaf);
+
+        // Guarded accounts follow the limits, which are encoded even when empty
+        let guarded = [Pubkey::new_unique()];
+        let guarded_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &guarded,
+            0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let expected_guarded: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &1u32.to_le_bytes(),
+                guarded[0].as_ref(),
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(guarded_leaf == expected_guarded);
+
+        // A vault index is preceded by the other extensions, encoded even when empty
+        let vault_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            2,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let expected_vault: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[2u8],
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(vault_leaf == expected_vault);
+
+        // A cluster genesis hash is preceded by all the other extensions
+        let genesis_hash = Hash([9u8; 32]);
+        let cluster_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            0,
+            Some(&genesis_hash),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let expected_cluster: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[0u8],
+                genesis_hash.as_ref(),
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(cluster_leaf == expected_cluster);
+
+        // A group follows them all, an unset genesis hash encoded empty
+        let group = TransactionGroup { index: 1, len: 2 };
+        let grouped_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            0,
+            None,
+            Some(&group),
+            None,
+            None,
+        )
+        .unwrap();
+        let expected_grouped: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[0u8],
+                &[1u8, 2],
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(grouped_leaf == expected_grouped);
+
+        // A not-before timestamp follows, as a little-endian i64
+        let delayed_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            0,
+            None,
+            None,
+            Some(1_700_000_000),
+            None,
+        )
+        .unwrap();
+        let expected_delayed: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[0u8],
+                &[0u8, 0],
+                &1_700_000_000i64.to_le_bytes(),
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(delayed_leaf == expected_delayed);
+
+        // A bounty comes last, as a little-endian u64, an unset not-before timestamp as zeroes
+        let rewarded_leaf = MerkleValidator::encode_leaf(
+            &one_sig_state,
+            7,
+            3,
+            &instruction,
+            &[],
+            &[],
+            0,
+            None,
+            None,
+            None,
+            Some(5_000),
+        )
+        .unwrap();
+        let expected_rewarded: Hash = keccak::hash(
+            keccak::hashv(&[
+                &[1u8],
+                &7u64.to_be_bytes(),
+                one_sig_state.as_ref(),
+                &3u64.to_be_bytes(),
+                &encoded_instruction,
+                &0u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[0u8],
+                &[0u8, 0],
+                &0i64.to_le_bytes(),
+                &5_000u64.to_le_bytes(),
+            ])
+            .as_ref(),
+        )
+        .into();
+        assert!(rewarded_leaf == expected_rewarded);
+    }
 }
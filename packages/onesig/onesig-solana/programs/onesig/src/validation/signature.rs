@@ -1,13 +1,13 @@
-use std::collections::HashSet;
-
 use anchor_lang::prelude::*;
-use solana_keccak_hasher as keccak;
-use solana_secp256k1_recover::secp256k1_recover;
+use onesig_core::{
+    digest,
+    signature::{recover_address, recover_address_from_bytes},
+};
 
 use crate::{
     constants::*,
     errors::*,
-    types::{Address, Hash, Secp256k1Pubkey, Signature},
+    types::{Address, Hash, Signature},
 };
 
 pub struct SignatureValidator;
@@ -30,20 +30,48 @@ impl SignatureValidator {
         let authorization_digest =
             build_signer_execution_authorization_digest(leaf, merkle_root, &delegate, expiry);
 
-        let recovered: Address = Self::recover_signer(&authorization_digest, signature)?.into();
+        let recovered = Self::recover_signer(&authorization_digest, signature)?;
         require!(signers.contains(&recovered), OneSigError::SignerExecutionProofUnauthorized);
         Ok(())
     }
 
     // Verifies multiple signatures against the signer list and threshold, returning the recovered
-    // signer addresses (deduplicated, one per valid signature).
+    // signer addresses (deduplicated, one per valid signature), sorted. Recovery stops once
+    // `threshold` distinct signers are confirmed: the signatures past them are neither recovered
+    // nor returned.
     pub fn verify_signatures(
         threshold: u8,
         signers: &[Address],
         digest: &Hash,
         signatures: &[u8],
     ) -> Result<Vec<Address>> {
-        require!(threshold > 0, OneSigError::InvalidThreshold);
+        Self::recover_signers(threshold, signers, digest, signatures, threshold as usize)
+    }
+
+    // Like `verify_signatures`, but every signature is recovered, and must be of a distinct
+    // signer, so a root records all the signers that signed it, not just the first `threshold`:
+    // it then stays executable while enough of them remain signers.
+    pub fn verify_every_signature(
+        threshold: u8,
+        signers: &[Address],
+        digest: &Hash,
+        signatures: &[u8],
+    ) -> Result<Vec<Address>> {
+        Self::recover_signers(threshold, signers, digest, signatures, SIGNERS_MAX_LEN)
+    }
+
+    // Recovers the signers of `signatures` until `enough` of them are confirmed.
+    //
+    // The signatures are recovered in place from `signatures`, and the signers seen so far are
+    // kept sorted on the stack, so the returned list is the only allocation.
+    fn recover_signers(
+        threshold: u8,
+        signers: &[Address],
+        digest: &Hash,
+        signatures: &[u8],
+        enough: usize,
+    ) -> Result<Vec<Address>> {
+        require!(threshold > 0 && threshold <= MAX_THRESHOLD, OneSigError::InvalidThreshold);
 
         require!(
             signatures.len() % SIGNATURE_BYTES_LEN == 0,
@@ -56,71 +84,62 @@ impl SignatureValidator {
             OneSigError::InsufficientSignatures
         );
 
-        // Track which signers have already provided a signature
-        let mut seen_signers = HashSet::new();
-        for chunk_signature in signatures.chunks(SIGNATURE_BYTES_LEN) {
-            // Extract signature for this signer
-            let signature: &Signature = &chunk_signature.try_into()?;
-            // Recover signer public key
-            let recovered_signer = SignatureValidator::recover_signer(digest, signature)?;
-            let recovered_address: Address = recovered_signer.into();
+        // Track which signers have already provided a signature, sorted. Each is a distinct
+        // member of `signers`, so there are at most `SIGNERS_MAX_LEN` of them
+        let mut seen_signers = [Address::default(); SIGNERS_MAX_LEN];
+        let mut seen = 0;
+        for chunk_signature in signatures.chunks_exact(SIGNATURE_BYTES_LEN) {
+            // Borrow the signature of this signer
+            let signature: &[u8; SIGNATURE_BYTES_LEN] =
+                chunk_signature.try_into().map_err(|_| OneSigError::InvalidSignatureFormat)?;
+            // Recover signer address
+            let recovered_address = recover_address_from_bytes(digest, signature)
+                .ok_or(OneSigError::FailedSignatureRecovery)?;
 
             // Verify the recovered signer is in the authorized signers list
             require!(signers.contains(&recovered_address), OneSigError::MissingSigner);
 
             // Mark this signer as seen and check if we've already processed this signer
-            let is_new = seen_signers.insert(recovered_address);
-            require!(is_new, OneSigError::DuplicateSigners);
+            let Err(index) = seen_signers[..seen].binary_search(&recovered_address) else {
+                return err!(OneSigError::DuplicateSigners);
+            };
+            seen_signers.copy_within(index..seen, index + 1);
+            seen_signers[index] = recovered_address;
+            seen += 1;
+
+            if seen == enough {
+                break;
+            }
         }
-        Ok(seen_signers.into_iter().collect())
+        Ok(seen_signers[..seen].to_vec())
     }
 
-    // Recovers the signer public key from a signature
-    fn recover_signer(digest: &Hash, signature: &Signature) -> Result<Secp256k1Pubkey> {
-        let (recovery_id, signature_r_s) = signature.split_recovery_id();
-        // Ethereum's `ecrecover` encodes the recovery id as v = recovery_id + 27, so accept v in
-        // 27..=30 and normalize back to the secp256k1 recovery id 0..=3.
-        let recovery_id =
-            if (27..=30).contains(recovery_id) { recovery_id - 27 } else { *recovery_id };
-
-        // Recover public key
-        let signer: Secp256k1Pubkey =
-            secp256k1_recover(digest.as_ref(), recovery_id, signature_r_s)
-                .map_err(|_| OneSigError::FailedSignatureRecovery)?
-                .into();
-
-        Ok(signer)
+    // Recovers the address that signed a digest
+    fn recover_signer(digest: &Hash, signature: &Signature) -> Result<Address> {
+        Ok(recover_address(digest, signature).ok_or(OneSigError::FailedSignatureRecovery)?)
     }
 }
 
-// EIP-712 signer-as-executor authorization digest:
-//   structHash            = keccak256(
-//       SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH || leafHash || merkleRoot ||
-//       keccak256(delegate) || expiry_padded
-//   )
-//   authorization_digest  = keccak256(0x1901 || DOMAIN_SEPARATOR || structHash)
-//
-// Uses the canonical OneSig domain (the same `DOMAIN_SEPARATOR` as merkle-root
-// signatures);
-//
-// `merkleRoot` pins the authorization to one operator-approved batch so the delegate
-// cannot pick a different root that happens to contain the same leaf. `expiry` is
-// ABI-encoded as uint256 (32 bytes, left zero-padded).
-pub(crate) fn build_signer_execution_authorization_digest(
+/// EIP-712 signer-as-executor authorization digest:
+/// ```text
+/// structHash            = keccak256(
+///     SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH || leafHash || merkleRoot ||
+///     keccak256(delegate) || expiry_padded
+/// )
+/// authorization_digest  = keccak256(0x1901 || DOMAIN_SEPARATOR || structHash)
+/// ```
+///
+/// Uses the canonical OneSig domain (the same `DOMAIN_SEPARATOR` as merkle-root
+/// signatures);
+///
+/// `merkleRoot` pins the authorization to one operator-approved batch so the delegate
+/// cannot pick a different root that happens to contain the same leaf. `expiry` is
+/// ABI-encoded as uint256 (32 bytes, left zero-padded).
+pub fn build_signer_execution_authorization_digest(
     leaf: &Hash,
     merkle_root: &Hash,
     delegate: &Pubkey,
     expiry: u64,
 ) -> Hash {
-    let delegate_hash = keccak::hash(delegate.as_ref());
-    let mut expiry_padded = [0u8; 32];
-    expiry_padded[24..].copy_from_slice(&expiry.to_be_bytes());
-    let struct_hash = keccak::hashv(&[
-        &SIGNER_EXECUTION_AUTHORIZATION_TYPE_HASH,
-        leaf.as_ref(),
-        merkle_root.as_ref(),
-        delegate_hash.as_ref(),
-        &expiry_padded,
-    ]);
-    keccak::hashv(&[&EIP191_PREFIX_FOR_EIP712, &DOMAIN_SEPARATOR, struct_hash.as_ref()]).into()
+    digest::signer_execution_authorization_digest(leaf, merkle_root, delegate.as_ref(), expiry)
 }
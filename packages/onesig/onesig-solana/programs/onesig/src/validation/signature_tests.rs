@@ -6,15 +6,12 @@
 // 3. The threshold validation logic works as expected
 // 4. Signatures are properly sorted and validated
 //
-// The test uses hardcoded values from EVM tests
-// (packages/onesig/onesig-evm/test/hardhat/one-sig.test.ts) to verify cross-chain compatibility. To
-// obtain these values, add these console.log statements in EVM tests:
-// ```typescript
-// console.log('signers:', sortedSigners.map((s) => s.publicKey))
-// console.log('signatures:', signatures)
-// ```
+// Signatures are made in the tests by the k256 signers of `onesig_test_vectors::fixtures`; the
+// committed vectors, which the EVM hardhat tests
+// (packages/onesig/onesig-evm/test/hardhat/test-vectors.test.ts) verify as well, are checked too.
 #[cfg(test)]
 mod tests {
+    use onesig_test_vectors::fixtures::{self, TestSigner};
     use solana_keccak_hasher as keccak;
     use solana_secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
 
@@ -29,28 +26,25 @@ mod tests {
 
     // Test fixture struct to avoid duplicating test data
     struct TestFixture {
+        keys: Vec<TestSigner>,
         signers: Vec<Address>,
         threshold: u8,
         digest: Hash,
         signatures: Vec<u8>,
     }
 
-    // Helper function to create a default test fixture
+    // Helper function to create a default test fixture: three signers at a threshold of 2, the
+    // first two of which signed
     fn create_test_fixture() -> TestFixture {
-        let signers = vec![
-            Secp256k1Pubkey::try_from(hex::decode("4c0346927a164fe5d37c5ff73ab31f2ed2416a5679b172477fd1aefb331fa72bc08a10c9a84d21289f8b63244c7b3d73cfb2ef18ad22455937341adfddbd305d")
-                .unwrap()).expect("slice with incorrect length").into(),
-            Secp256k1Pubkey::try_from(hex::decode("fea2a7c50de763bbaaeaf5452eb2412fde3f0610f4be4b1cdb160460f51d9b6c869e5fb895cb15485224977c7e737fb25d4ca1ebb88268d67fb8a9ad79814ed7")
-                .unwrap()).expect("slice with incorrect length").into(),
-        ];
-        let threshold = 2;
-        let digest =
-            hex::decode("7d84fd508a27ac81dee4bfa97f29bedb885c1bd7fc4650f491e64fbdaa05cdac")
-                .unwrap();
-        let signatures = hex::decode("31537dcfe1ec4d7e89f5972ccd3065e34e178e302c1b9e8e175d3843a2964f2863f2b60c7ef74363b687f822db6c313e8762b38d3097b6fec9b7b76165630aaa1b042674fc630e32f9000bc95271ec54283c8ad350016bb66a18464958963a51f3146e8b3fbb3a5bd0b89d3b6c1e01dd614e86bae01351dc3d1ffe3522c83ed4501c")
-            .unwrap();
-
-        TestFixture { signers, threshold, digest: digest.try_into().unwrap(), signatures }
+        let keys = fixtures::signers(3);
+        let digest = Hash(keccak::hash(b"digest").to_bytes());
+        TestFixture {
+            signers: fixtures::addresses(&keys),
+            threshold: 2,
+            signatures: fixtures::sign_all(&keys[..2], &digest),
+            digest,
+            keys,
+        }
     }
 
     #[test]
@@ -122,6 +116,123 @@ mod tests {
         assert_eq!(result.unwrap_err(), OneSigError::InsufficientSignatures.into());
     }
 
+    #[test]
+    fn test_verify_signatures_of_every_signer() {
+        let mut fixture = create_test_fixture();
+        fixture.threshold = 3;
+        let signatures = fixtures::sign_all(&fixture.keys, &fixture.digest);
+
+        let signed_by = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        )
+        .unwrap();
+
+        assert_eq!(signed_by.len(), 3);
+    }
+
+    #[test]
+    fn test_verify_signatures_returns_sorted_signers() {
+        let mut fixture = create_test_fixture();
+        fixture.threshold = 3;
+        // Signed in reverse order
+        let signatures: Vec<u8> = fixtures::sign_all(&fixture.keys, &fixture.digest)
+            .chunks(SIGNATURE_BYTES_LEN)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        let signed_by = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        )
+        .unwrap();
+
+        let mut expected = fixture.signers.clone();
+        expected.sort();
+        assert_eq!(signed_by, expected);
+    }
+
+    #[test]
+    fn test_verify_signatures_stops_at_threshold() {
+        let fixture = create_test_fixture();
+        // Past the threshold, neither an unrecoverable signature nor a duplicate is looked at
+        let mut signatures = fixture.signatures.clone();
+        signatures.extend([0xff; SIGNATURE_BYTES_LEN]);
+        signatures.extend_from_slice(&fixture.signatures[..SIGNATURE_BYTES_LEN]);
+
+        let signed_by = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        )
+        .unwrap();
+
+        assert_eq!(signed_by.len(), fixture.threshold as usize);
+        assert!(signed_by.iter().all(|signer| fixture.signers[..2].contains(signer)));
+
+        // Nor is the signature of the third signer
+        let signatures = fixtures::sign_all(&fixture.keys, &fixture.digest);
+        let signed_by = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        )
+        .unwrap();
+        assert!(!signed_by.contains(&fixture.signers[2]));
+    }
+
+    #[test]
+    fn test_verify_every_signature_records_signers_past_threshold() {
+        let fixture = create_test_fixture();
+        let signatures = fixtures::sign_all(&fixture.keys, &fixture.digest);
+
+        let signed_by = SignatureValidator::verify_every_signature(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        )
+        .unwrap();
+        let mut expected = fixture.signers.clone();
+        expected.sort();
+        assert_eq!(signed_by, expected);
+
+        // Past the threshold, an unrecoverable signature or a duplicate is still refused
+        for extra in [vec![0xff; SIGNATURE_BYTES_LEN], signatures[..SIGNATURE_BYTES_LEN].to_vec()] {
+            let result = SignatureValidator::verify_every_signature(
+                fixture.threshold,
+                &fixture.signers,
+                &fixture.digest,
+                &[fixture.signatures.clone(), extra].concat(),
+            );
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_signatures_of_unknown_key() {
+        let fixture = create_test_fixture();
+        let mut signatures = fixture.signatures[..SIGNATURE_BYTES_LEN].to_vec();
+        signatures.extend(TestSigner::new("outsider").sign(&fixture.digest));
+
+        let result = SignatureValidator::verify_signatures(
+            fixture.threshold,
+            &fixture.signers,
+            &fixture.digest,
+            &signatures,
+        );
+
+        assert_eq!(result.unwrap_err(), OneSigError::MissingSigner.into());
+    }
+
     #[test]
     fn test_verify_signatures_signer_not_found() {
         // Test case where a signature is from a signer not in the authorized list
@@ -241,14 +352,39 @@ mod tests {
 
         // authorization_digest = keccak256(0x1901 || DOMAIN_SEPARATOR || structHash)
         // (the canonical OneSig domain, shared with merkle-root signatures)
-        let expected = keccak::hashv(&[
-            &EIP191_PREFIX_FOR_EIP712,
-            &DOMAIN_SEPARATOR,
-            struct_hash.as_ref(),
-        ]);
-
-        let computed =
-            build_signer_execution_authorization_digest(&leaf_hash, &merkle_root, &delegate, expiry);
+        let expected =
+            keccak::hashv(&[&EIP191_PREFIX_FOR_EIP712, &DOMAIN_SEPARATOR, struct_hash.as_ref()]);
+
+        let computed = build_signer_execution_authorization_digest(
+            &leaf_hash,
+            &merkle_root,
+            &delegate,
+            expiry,
+        );
         assert_eq!(computed.0, expected.to_bytes());
     }
+
+    #[test]
+    fn test_signer_execution_authorization_matches_vectors() {
+        let vectors = onesig_test_vectors::load();
+        let signers: Vec<Address> = vectors
+            .signers
+            .iter()
+            .map(|signer| Secp256k1Pubkey::try_from(signer.public_key.clone()).unwrap().into())
+            .collect();
+        let authorization = vectors.signer_execution_authorization;
+
+        let digest = build_signer_execution_authorization_digest(
+            &vectors.leaves[authorization.leaf_index].clone().try_into().unwrap(),
+            &vectors.merkle_root.try_into().unwrap(),
+            &anchor_lang::prelude::Pubkey::try_from(authorization.delegate.as_slice()).unwrap(),
+            authorization.expiry,
+        );
+        assert_eq!(digest.0.to_vec(), authorization.digest);
+
+        let signed_by =
+            SignatureValidator::verify_signatures(1, &signers, &digest, &authorization.signature)
+                .unwrap();
+        assert!(signed_by == signers[..1]);
+    }
 }
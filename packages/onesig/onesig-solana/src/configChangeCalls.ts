@@ -4,7 +4,7 @@ import { createNoopSigner, publicKey as toPublicKey } from '@metaplex-foundation
 import type { ConfigChangeOperation, OneSigConfigChangeCall } from '@layerzerolabs/onesig-model';
 
 import { ONESIG_PROGRAM_ID } from './index';
-import { OneSig } from './onesig';
+import { EXECUTOR_STATE_RENT, OneSig } from './onesig';
 
 /**
  * Serialize a Solana `Instruction` into the normalized `OneSigConfigChangeCall`
@@ -22,6 +22,7 @@ function instructionToConfigChangeCall(
     chainName: string,
     contractAddress: string,
     ix: Instruction,
+    value: bigint = 0n,
 ): OneSigConfigChangeCall {
     const hexData = '0x' + Buffer.from(ix.data).toString('hex');
 
@@ -31,7 +32,7 @@ function instructionToConfigChangeCall(
     return {
         chainName,
         to: contractAddress,
-        value: '0',
+        value: value.toString(),
         data: hexData,
         metadata: {
             programId: ix.programId.toString(),
@@ -68,6 +69,7 @@ export function generateSolanaConfigChangeCall(
     const oneSig = new OneSig(programId, createNoopSigner(statePublicKey));
 
     let ix: Instruction;
+    let value = 0n;
 
     switch (operation) {
         case 'setSigner': {
@@ -90,6 +92,8 @@ export function generateSolanaConfigChangeCall(
         }
         case 'setExecutor':
             ix = oneSig.addExecutor(toPublicKey(params.address as string));
+            // The executor PDA is funded by the OneSig signer out of the leaf's value
+            value = EXECUTOR_STATE_RENT;
             break;
         case 'removeExecutor':
             ix = oneSig.removeExecutor(toPublicKey(params.address as string));
@@ -98,7 +102,7 @@ export function generateSolanaConfigChangeCall(
             throw new Error(`Unsupported Solana config change operation: ${operation}`);
     }
 
-    return instructionToConfigChangeCall(chainName, contractAddress, ix);
+    return instructionToConfigChangeCall(chainName, contractAddress, ix, value);
 }
 
 /** Convert a hex string (with or without 0x prefix) to Uint8Array. */
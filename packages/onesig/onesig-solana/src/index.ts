@@ -1,13 +1,20 @@
 import { arrayify } from '@ethersproject/bytes';
 import type { Instruction, PublicKey } from '@metaplex-foundation/umi';
 import { createNoopSigner, publicKeyBytes } from '@metaplex-foundation/umi';
-import { u64 } from '@metaplex-foundation/umi/serializers';
+import {
+    array,
+    i64,
+    publicKey as publicKeySerializer,
+    u8,
+    u64,
+} from '@metaplex-foundation/umi/serializers';
 import { fromWeb3JsPublicKey, toWeb3JsPublicKey } from '@metaplex-foundation/umi-web3js-adapters';
 import type { Connection } from '@solana/web3.js';
 
 import { encodeLeaf, makeOneSigTree } from '@layerzerolabs/onesig-core';
 
-import { getOneSigStateAccountDataSerializer } from './generated';
+import type { TokenOutflowLimitArgs, TransactionGroupArgs } from './generated';
+import { getOneSigStateAccountDataSerializer, getTokenOutflowLimitSerializer } from './generated';
 import { getInstructionSerializer, OneSig } from './onesig';
 import { simulateLamportAllowances } from './simulate';
 
@@ -27,8 +34,32 @@ export interface GenerateLeafsResult<Leaf extends BaseLeafData = BaseLeafData<an
 export * from './configChangeCalls';
 export * from './generated';
 export * from './onesig';
+export * from './wrappedSol';
 
-export type SolanaCallData = Instruction & { value: bigint };
+export type SolanaCallData = Instruction & {
+    value: bigint;
+    /** Per-mint caps on the SPL tokens the call may move out of `oneSigSigner`'s token accounts. */
+    tokenOutflowLimits?: TokenOutflowLimitArgs[];
+    /**
+     * Further accounts whose lamports count against `value` together with `oneSigSigner`'s, so
+     * the call cannot drain them either.
+     */
+    guardedAccounts?: PublicKey[];
+    /** Vault whose signer PDA executes the call; defaults to 0, the `oneSigSigner` PDA. */
+    vaultIndex?: number;
+    /**
+     * Position of the leaf among the `len` leaves at consecutive nonces that only execute
+     * together, in one transaction.
+     */
+    group?: TransactionGroupArgs;
+    /** Unix time before which the program refuses to execute the call. */
+    notBefore?: bigint;
+    /**
+     * Lamports paid from the bounty escrow to whoever executes the call while executors are not
+     * required.
+     */
+    bounty?: bigint;
+};
 export type SolanaLeafData = BaseLeafData<PublicKey, SolanaCallData>;
 
 export interface BuildOneSigSolanaLeavesArgs {
@@ -36,6 +67,12 @@ export interface BuildOneSigSolanaLeavesArgs {
     dummyFeePayer: PublicKey;
     oneSigState: PublicKey;
     instructions: Instruction[];
+    /**
+     * Genesis hash of the cluster, for a program built with a cluster feature (`mainnet`,
+     * `testnet` or `devnet`), which binds it into every leaf, or the one set on the OneSig with
+     * `setClusterGenesisHash`.
+     */
+    clusterGenesisHash?: Uint8Array;
 }
 
 export interface OneSigSolanaLeafProof {
@@ -51,8 +88,8 @@ export interface BuildOneSigSolanaLeavesResult {
 /**
  * Prepares a Call Data specifically for Merkle leaf calculation in the Solana OneSig.
  * This formats the instruction data to be consistent with how the OneSig program will execute it:
- * - Configures the `oneSigSigner` PDA with `isSigner = true` to match
- *   the Rust program's behavior during actual execution.
+ * - Configures the signer PDA of the call's vault (`oneSigSigner` by default) with
+ *   `isSigner = true` to match the Rust program's behavior during actual execution.
  * - Sets all other keys' `isSigner` to false.
  *
  * Note: This function is used for generating leaf hashes for the Merkle tree.
@@ -62,7 +99,7 @@ export function prepareSolanaCallDataForMerkleLeaf(
     oneSig: OneSig,
     call: SolanaCallData,
 ): SolanaCallData {
-    const [oneSigSigner] = oneSig.pda.oneSigSigner();
+    const [oneSigSigner] = oneSig.pda.vaultSigner(call.vaultIndex ?? 0);
     return {
         ...call,
         keys: call.keys.map((key) => {
@@ -75,16 +112,21 @@ export function prepareSolanaCallDataForMerkleLeaf(
     };
 }
 
+/**
+ * Leaf generator of a Solana OneSig. Pass `clusterGenesisHash` when the program is built with a
+ * cluster feature, which binds the genesis hash of its cluster into every leaf, or when one is set
+ * on the OneSig with `setClusterGenesisHash`.
+ */
 export function solanaLeafGenerator(
     programId: PublicKey,
     leafs: SolanaLeafData[],
+    clusterGenesisHash?: Uint8Array,
 ): GenerateLeafsResult<SolanaLeafData> {
     if (leafs.length === 0) {
         throw new Error(`Cannot generate Solana leaf with empty leaves`);
     }
     const oneSigState = leafs[0].targetOneSigAddress;
     const oneSig = new OneSig(programId, createNoopSigner(oneSigState));
-    const [oneSigSigner] = oneSig.pda.oneSigSigner();
     return {
         leafs,
         encodeAddress(address: PublicKey) {
@@ -96,6 +138,8 @@ export function solanaLeafGenerator(
             }
             return Buffer.concat(
                 calls.map((call) => {
+                    const vaultIndex = call.vaultIndex ?? 0;
+                    const [oneSigSigner] = oneSig.pda.vaultSigner(vaultIndex);
                     for (const key of call.keys) {
                         if (key.pubkey === oneSigSigner) {
                             if (!key.isSigner) {
@@ -110,9 +154,36 @@ export function solanaLeafGenerator(
                         keys: call.keys.slice(1),
                         data: call.data,
                     };
+                    // The extensions (token outflow limits, guarded accounts, vault index, cluster
+                    // genesis hash, group, not-before timestamp, bounty) are appended in that order
+                    // up to the last one that is set, which keeps the encoding of other leaves
+                    // unchanged
+                    const limits = call.tokenOutflowLimits ?? [];
+                    const guardedAccounts = call.guardedAccounts ?? [];
+                    const extensions: [Uint8Array, boolean][] = [
+                        [
+                            array(getTokenOutflowLimitSerializer()).serialize(limits),
+                            limits.length > 0,
+                        ],
+                        [
+                            array(publicKeySerializer()).serialize(guardedAccounts),
+                            guardedAccounts.length > 0,
+                        ],
+                        [u8().serialize(vaultIndex), vaultIndex !== 0],
+                        [clusterGenesisHash ?? new Uint8Array(), clusterGenesisHash !== undefined],
+                        [
+                            Uint8Array.of(call.group?.index ?? 0, call.group?.len ?? 0),
+                            call.group !== undefined,
+                        ],
+                        [i64().serialize(call.notBefore ?? 0n), call.notBefore !== undefined],
+                        [u64().serialize(call.bounty ?? 0n), call.bounty !== undefined],
+                    ];
+                    const extensionsLength =
+                        extensions.map(([, set]) => set).lastIndexOf(true) + 1;
                     return Buffer.concat([
                         getInstructionSerializer().serialize(ix),
                         u64().serialize(call.value),
+                        ...extensions.slice(0, extensionsLength).map(([encoded]) => encoded),
                     ]);
                 }),
             );
@@ -156,7 +227,7 @@ export async function buildOneSigSolanaLeaves(
         targetOneSigAddress: args.oneSigState,
         calls: [prepareSolanaCallDataForMerkleLeaf(oneSig, call)],
     }));
-    const generator = solanaLeafGenerator(programId, leafs);
+    const generator = solanaLeafGenerator(programId, leafs, args.clusterGenesisHash);
     const tree = makeOneSigTree([generator]);
 
     return {
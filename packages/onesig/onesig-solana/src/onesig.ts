@@ -11,6 +11,7 @@ import type {
     ProgramError,
     ProgramRepositoryInterface,
     PublicKey,
+    RpcAccount,
     RpcInterface,
     Signer,
     Some,
@@ -21,6 +22,7 @@ import {
     createNullRpc,
     isOption,
     isSome,
+    publicKey as toPublicKey,
     publicKeyBytes,
     some,
 } from '@metaplex-foundation/umi';
@@ -32,33 +34,123 @@ import { createDefaultProgramRepository } from '@metaplex-foundation/umi-program
 import type {
     ExecuteTransactionInstructionDataArgs,
     InitOneSigInstructionDataArgs,
+    MerkleRootEntry,
     OneSigState,
     OneSigTransactionArgs,
+    SelfCpiTarget,
     SetConfigParamsArgs,
     SignatureArgs,
     SignerExecuteTransactionInstructionDataArgs,
+    SpendingLimit,
+    TargetPolicy,
     VerifyMerkleRootParamsArgs,
 } from './generated';
 import {
+    addExecutor as addExecutorInstruction,
+    addModule as addModuleInstruction,
     closeMerkleRoot,
+    closeOneSig as closeOneSigInstruction,
+    createPdaAta as createPdaAtaInstruction,
+    deserializeMerkleRootRegistry,
+    deserializeOneSigState,
+    deserializeSpendingLimits,
+    deserializeTargetPrograms,
     executeTransaction,
-    fetchOneSigState,
+    extendMerkleRoot as extendMerkleRootInstruction,
     getOnesigErrorFromCode,
     getOnesigErrorFromName,
     initOneSig,
+    migrateState as migrateStateInstruction,
     ONESIG_PROGRAM_ID,
+    removeExecutor as removeExecutorInstruction,
+    removeModule as removeModuleInstruction,
+    removeSpendingLimit as removeSpendingLimitInstruction,
+    resizeState as resizeStateInstruction,
     setConfig as setConfigInstruction,
     setConfigParams,
+    setExecutorTargets as setExecutorTargetsInstruction,
+    setSpendingLimit as setSpendingLimitInstruction,
     signerExecuteTransaction,
     verifyMerkleRoot as verifyMerkleRootInstruction,
+    withdrawBountyEscrow as withdrawBountyEscrowInstruction,
 } from './generated';
 import type { SolanaCallData } from './index';
 
 export const EDDSA = createWeb3JsEddsa();
 
+export const SPL_TOKEN_PROGRAM_ID = toPublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA');
+export const ASSOCIATED_TOKEN_PROGRAM_ID = toPublicKey(
+    'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL',
+);
+export const INSTRUCTIONS_SYSVAR_ID = toPublicKey('Sysvar1nstructions1111111111111111111111111');
+
+// Layout of the zero-copy `OneSigState` account: discriminator, fixed-size header, then the signer
+// list (`signersCapacity` addresses).
+const ONE_SIG_STATE_SIGNERS_OFFSET = 8 + 152;
+const ADDRESS_LEN = 20;
+
+// Size of an `ExecutorState` account: discriminator, executor, rent payer, bump and an empty list
+// of target programs.
+const EXECUTOR_STATE_SPACE = 8 + 32 + 32 + 1 + 4;
+
+/**
+ * Rent-exempt balance of an `ExecutorState` account under the default rent parameters. Adding an
+ * executor through a leaf funds the PDA from the OneSig signer, so the leaf's `value` must cover it.
+ */
+export const EXECUTOR_STATE_RENT = BigInt((EXECUTOR_STATE_SPACE + 128) * 3480 * 2);
+
+// Size of a `ModuleState` account: discriminator, module, rent payer and bump.
+const MODULE_STATE_SPACE = 8 + 32 + 32 + 1;
+
+/**
+ * Rent-exempt balance of a `ModuleState` account under the default rent parameters. Adding a
+ * module through a leaf funds the PDA from the OneSig signer, so the leaf's `value` must cover it.
+ */
+export const MODULE_STATE_RENT = BigInt((MODULE_STATE_SPACE + 128) * 3480 * 2);
+
+// Size of a `SpendingLimit` entry: mint, amount, window, window start and spent amount.
+const SPENDING_LIMIT_SPACE = 32 + 8 + 8 + 8 + 8;
+
+/**
+ * Rent of the space a new entry adds to the `SpendingLimits` account. Setting a limit for a new
+ * mint through a leaf funds it from the OneSig signer, so the leaf's `value` must cover it.
+ */
+export const SPENDING_LIMIT_RENT = BigInt(SPENDING_LIMIT_SPACE * 3480 * 2);
+
+/**
+ * `OneSigState` header together with the populated part of the signer list.
+ */
+export type OneSigStateWithSigners = Omit<OneSigState, 'executorRequired'> & {
+    multisig: { signers: [Uint8Array][]; threshold: number };
+    executorRequired: boolean;
+};
+
+/**
+ * Decodes a `OneSigState` account, including the signer list stored after the zero-copy header.
+ */
+export function decodeOneSigState(account: RpcAccount): OneSigStateWithSigners {
+    const state = deserializeOneSigState(account);
+    const signers = Array.from({ length: state.signersLen }, (_, i): [Uint8Array] => {
+        const start = ONE_SIG_STATE_SIGNERS_OFFSET + i * ADDRESS_LEN;
+        return [account.data.slice(start, start + ADDRESS_LEN)];
+    });
+    return {
+        ...state,
+        multisig: { signers, threshold: state.threshold },
+        executorRequired: state.executorRequired !== 0,
+    };
+}
+
 export class OneSigPDA {
     static readonly ONESIG_SEED = Buffer.from('OneSig', 'utf8');
     static readonly MERKLE_ROOT_SEED = Buffer.from('MerkleRoot', 'utf8');
+    static readonly MERKLE_ROOT_REGISTRY_SEED = Buffer.from('MerkleRootRegistry', 'utf8');
+    static readonly EXECUTOR_SEED = Buffer.from('Executor', 'utf8');
+    static readonly SPENDING_LIMITS_SEED = Buffer.from('SpendingLimits', 'utf8');
+    static readonly TARGET_PROGRAMS_SEED = Buffer.from('TargetPrograms', 'utf8');
+    static readonly BOUNTY_ESCROW_SEED = Buffer.from('BountyEscrow', 'utf8');
+    static readonly MODULE_SEED = Buffer.from('Module', 'utf8');
+    static readonly MODULE_AUTHORITY_SEED = Buffer.from('OneSigModule', 'utf8');
     constructor(
         public readonly program: PublicKey = ONESIG_PROGRAM_ID,
         public readonly state: PublicKey,
@@ -68,6 +160,20 @@ export class OneSigPDA {
         return EDDSA.findPda(this.program, [OneSigPDA.ONESIG_SEED, publicKeyBytes(this.state)]);
     }
 
+    /**
+     * Signer PDA of the vault `vaultIndex`; vault 0 is `oneSigSigner`.
+     */
+    vaultSigner(vaultIndex: number): Pda {
+        if (vaultIndex === 0) {
+            return this.oneSigSigner();
+        }
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.ONESIG_SEED,
+            publicKeyBytes(this.state),
+            Uint8Array.of(vaultIndex),
+        ]);
+    }
+
     merkleRootState(merkleRoot: Uint8Array): Pda {
         return EDDSA.findPda(this.program, [
             OneSigPDA.MERKLE_ROOT_SEED,
@@ -75,6 +181,75 @@ export class OneSigPDA {
             merkleRoot,
         ]);
     }
+
+    merkleRootRegistry(): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.MERKLE_ROOT_REGISTRY_SEED,
+            publicKeyBytes(this.state),
+        ]);
+    }
+
+    spendingLimits(): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.SPENDING_LIMITS_SEED,
+            publicKeyBytes(this.state),
+        ]);
+    }
+
+    targetPrograms(): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.TARGET_PROGRAMS_SEED,
+            publicKeyBytes(this.state),
+        ]);
+    }
+
+    /**
+     * System account the bounties of executed leaves are paid from, funded by transfers to it.
+     */
+    bountyEscrow(): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.BOUNTY_ESCROW_SEED,
+            publicKeyBytes(this.state),
+        ]);
+    }
+
+    /**
+     * Associated token account of the signer PDA of vault `vaultIndex` for `mint`.
+     */
+    associatedTokenAccount(
+        mint: PublicKey,
+        tokenProgram: PublicKey = SPL_TOKEN_PROGRAM_ID,
+        vaultIndex = 0,
+    ): Pda {
+        return EDDSA.findPda(ASSOCIATED_TOKEN_PROGRAM_ID, [
+            publicKeyBytes(this.vaultSigner(vaultIndex)[0]),
+            publicKeyBytes(tokenProgram),
+            publicKeyBytes(mint),
+        ]);
+    }
+
+    executorState(executor: PublicKey): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.EXECUTOR_SEED,
+            publicKeyBytes(this.state),
+            publicKeyBytes(executor),
+        ]);
+    }
+
+    moduleState(module: PublicKey): Pda {
+        return EDDSA.findPda(this.program, [
+            OneSigPDA.MODULE_SEED,
+            publicKeyBytes(this.state),
+            publicKeyBytes(module),
+        ]);
+    }
+
+    /**
+     * PDA of the `module` program it signs `execute_module_transaction` with.
+     */
+    moduleAuthority(module: PublicKey): Pda {
+        return EDDSA.findPda(module, [OneSigPDA.MODULE_AUTHORITY_SEED, publicKeyBytes(this.state)]);
+    }
 }
 
 export class EventPDA {
@@ -147,10 +322,11 @@ export class OneSig {
     }
 
     /**
-     * Initialize OneSig multisig configuration
+     * Initialize OneSig multisig configuration. The `ExecutorState` PDA of every initial executor
+     * is passed as a remaining account and created (at `payer`'s expense) in the same instruction.
      */
     initialize(payer: Signer, params: InitOneSigInstructionDataArgs): WrappedInstruction {
-        return initOneSig(
+        const ix = initOneSig(
             {
                 programs: this.programRepo,
                 payer,
@@ -159,10 +335,21 @@ export class OneSig {
                 ...params,
                 state: this.state,
                 payer: payer,
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
+                spendingLimits: this.pda.spendingLimits(),
+                targetPrograms: this.pda.targetPrograms(),
                 program: this.programId,
                 eventAuthority: this.eventPda.eventAuthority(),
             },
         ).items[0];
+        params.executors.forEach((executor) => {
+            ix.instruction.keys.push({
+                pubkey: this.pda.executorState(executor)[0],
+                isSigner: false,
+                isWritable: true,
+            });
+        });
+        return ix;
     }
 
     /**
@@ -174,6 +361,16 @@ export class OneSig {
      *
      * The two-step approach avoids Solana's 1232-byte transaction size limit when
      * signatures + merkle proofs + tx data would exceed it.
+     *
+     * Set `asExecutor` to pass the `ExecutorState` PDA of `signer`, which is required when
+     * `executor_required` is set, and `withTargetPrograms` to pass the `TargetPrograms` PDA, which
+     * is required when a target policy is set.
+     *
+     * A `feePayer` sponsors the transaction in place of `signer`, which still executes the leaf
+     * as its executor, and is paid back the executor reimbursement.
+     *
+     * A grouped call (`call.group`) only executes in a transaction executing the other leaves of
+     * its group, in nonce order, right before and after it.
      */
     executeTransaction(
         signer: Signer,
@@ -184,6 +381,9 @@ export class OneSig {
             merkleRootVerification: OptionOrNullable<
                 Pick<VerifyMerkleRootParamsArgs, 'expiry' | 'signatures'>
             >;
+            asExecutor?: boolean;
+            withTargetPrograms?: boolean;
+            feePayer?: Signer;
         },
     ): WrappedInstruction {
         const hasMerkleRootVerification =
@@ -192,6 +392,12 @@ export class OneSig {
         const oneSigTransactionArgs: OneSigTransactionArgs = {
             ixData: params.call.data,
             value: params.call.value,
+            tokenOutflowLimits: params.call.tokenOutflowLimits ?? [],
+            guardedAccounts: params.call.guardedAccounts ?? [],
+            vaultIndex: params.call.vaultIndex ?? 0,
+            group: params.call.group ?? null,
+            notBefore: params.call.notBefore ?? null,
+            bounty: params.call.bounty ?? null,
             proof: params.proof.map((p) => [arrayify(p)]),
         };
         const args: ExecuteTransactionInstructionDataArgs = {
@@ -200,11 +406,13 @@ export class OneSig {
                 ? some({
                       ...(params.merkleRootVerification as Some<VerifyMerkleRootParamsArgs>).value,
                       merkleRoot: [merkleRoot],
+                      // Inline verification only accepts roots without an execution cap
+                      maxExecutions: 0,
                   })
                 : null,
         };
 
-        const [oneSigSigner] = this.pda.oneSigSigner();
+        const [oneSigSigner] = this.pda.vaultSigner(params.call.vaultIndex ?? 0);
         const [ix] = executeTransaction(
             {
                 programs: this.programRepo,
@@ -217,9 +425,18 @@ export class OneSig {
                 program: this.programId,
                 eventAuthority: this.eventPda.eventAuthority(),
                 oneSigSigner: oneSigSigner,
+                spendingLimits: this.pda.spendingLimits(),
                 merkleRootState: hasMerkleRootVerification
                     ? undefined
                     : this.pda.merkleRootState(merkleRoot),
+                executorState: params.asExecutor
+                    ? this.pda.executorState(signer.publicKey)
+                    : undefined,
+                targetPrograms: params.withTargetPrograms ? this.pda.targetPrograms() : undefined,
+                instructionsSysvar: params.call.group ? INSTRUCTIONS_SYSVAR_ID : undefined,
+                feePayer: params.feePayer,
+                bountyEscrow:
+                    params.call.bounty !== undefined ? this.pda.bountyEscrow() : undefined,
             },
         ).items;
 
@@ -240,6 +457,8 @@ export class OneSig {
      *
      * In permissionless mode (`executor_required = false`), neither the signature nor
      * its expiry is enforced.
+     *
+     * Set `withTargetPrograms` when a target policy is set, as for `executeTransaction`.
      */
     signerExecuteTransaction(
         delegate: Signer,
@@ -252,6 +471,7 @@ export class OneSig {
             >;
             signature: SignatureArgs;
             expiry: number | bigint;
+            withTargetPrograms?: boolean;
         },
     ): WrappedInstruction {
         const hasMerkleRootVerification =
@@ -260,6 +480,12 @@ export class OneSig {
         const oneSigTransactionArgs: OneSigTransactionArgs = {
             ixData: params.call.data,
             value: params.call.value,
+            tokenOutflowLimits: params.call.tokenOutflowLimits ?? [],
+            guardedAccounts: params.call.guardedAccounts ?? [],
+            vaultIndex: params.call.vaultIndex ?? 0,
+            group: params.call.group ?? null,
+            notBefore: params.call.notBefore ?? null,
+            bounty: params.call.bounty ?? null,
             proof: params.proof.map((p) => [arrayify(p)]),
         };
         const args: SignerExecuteTransactionInstructionDataArgs = {
@@ -268,13 +494,15 @@ export class OneSig {
                 ? some({
                       ...(params.merkleRootVerification as Some<VerifyMerkleRootParamsArgs>).value,
                       merkleRoot: [merkleRoot],
+                      // Inline verification only accepts roots without an execution cap
+                      maxExecutions: 0,
                   })
                 : null,
             signature: params.signature,
             expiry: params.expiry,
         };
 
-        const [oneSigSigner] = this.pda.oneSigSigner();
+        const [oneSigSigner] = this.pda.vaultSigner(params.call.vaultIndex ?? 0);
         const [ix] = signerExecuteTransaction(
             {
                 programs: this.programRepo,
@@ -287,9 +515,12 @@ export class OneSig {
                 program: this.programId,
                 eventAuthority: this.eventPda.eventAuthority(),
                 oneSigSigner: oneSigSigner,
+                spendingLimits: this.pda.spendingLimits(),
                 merkleRootState: hasMerkleRootVerification
                     ? undefined
                     : this.pda.merkleRootState(merkleRoot),
+                targetPrograms: params.withTargetPrograms ? this.pda.targetPrograms() : undefined,
+                instructionsSysvar: params.call.group ? INSTRUCTIONS_SYSVAR_ID : undefined,
             },
         ).items;
 
@@ -300,7 +531,88 @@ export class OneSig {
         return ix;
     }
 
-    verifyMerkleRoot(payer: Signer, params: VerifyMerkleRootParamsArgs): WrappedInstruction {
+    /**
+     * Create the associated token account of the signer PDA of vault `vaultIndex` (the OneSig
+     * signer by default) for `mint`, so it can receive tokens. Permissionless; `payer` covers the
+     * rent, and an existing account is left as is.
+     */
+    createPdaAta(
+        payer: Signer,
+        mint: PublicKey,
+        tokenProgram: PublicKey = SPL_TOKEN_PROGRAM_ID,
+        vaultIndex = 0,
+    ): WrappedInstruction {
+        return createPdaAtaInstruction(
+            {
+                programs: this.programRepo,
+                payer,
+            },
+            {
+                payer,
+                oneSigState: this.state.publicKey,
+                oneSigSigner: this.pda.vaultSigner(vaultIndex)[0],
+                associatedTokenAccount: this.pda.associatedTokenAccount(
+                    mint,
+                    tokenProgram,
+                    vaultIndex,
+                ),
+                mint,
+                tokenProgram,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                vaultIndex,
+            },
+        ).items[0];
+    }
+
+    /**
+     * Upgrade the state account from the layout of the first release, creating the PDAs it lacked
+     * and an `ExecutorState` for each of its `executors`, given in the order it lists them, then
+     * the `MerkleRootState` accounts of the given roots. `executors` is empty for a state account
+     * already in the current layout. Permissionless; `payer` covers the rent of the new accounts.
+     */
+    migrateState(
+        payer: Signer,
+        merkleRoots: Uint8Array[] = [],
+        executors: PublicKey[] = [],
+    ): WrappedInstruction {
+        const ix = migrateStateInstruction(
+            {
+                programs: this.programRepo,
+                payer,
+            },
+            {
+                payer,
+                state: this.state.publicKey,
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
+                spendingLimits: this.pda.spendingLimits(),
+                targetPrograms: this.pda.targetPrograms(),
+            },
+        ).items[0];
+        executors.forEach((executor) => {
+            ix.instruction.keys.push({
+                pubkey: this.pda.executorState(executor)[0],
+                isSigner: false,
+                isWritable: true,
+            });
+        });
+        merkleRoots.forEach((merkleRoot) => {
+            ix.instruction.keys.push({
+                pubkey: this.pda.merkleRootState(merkleRoot)[0],
+                isSigner: false,
+                isWritable: true,
+            });
+        });
+        return ix;
+    }
+
+    /**
+     * Verify a merkle root into its MerkleRootState PDA. Pass the `maxExecutions` the root was
+     * signed with to cap it to that many executions; it defaults to 0 (unlimited).
+     */
+    verifyMerkleRoot(
+        payer: Signer,
+        params: Omit<VerifyMerkleRootParamsArgs, 'maxExecutions'> & { maxExecutions?: number },
+    ): WrappedInstruction {
         return verifyMerkleRootInstruction(
             {
                 programs: this.programRepo,
@@ -309,15 +621,40 @@ export class OneSig {
             {
                 oneSigState: this.state.publicKey,
                 merkleRootState: this.pda.merkleRootState(params.merkleRoot[0]),
-                params,
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
+                params: { ...params, maxExecutions: params.maxExecutions ?? 0 },
+            },
+        ).items[0];
+    }
+
+    /**
+     * Move the expiry of a verified MerkleRootState to the later `expiry` the signers signed for
+     * the same root. The root is re-verified in place, so `maxExecutions` resets its execution
+     * cap the same way as in `verifyMerkleRoot`. `payer` is only needed when the threshold rose
+     * since the root was verified: it pays for the room its extra signers take.
+     */
+    extendMerkleRoot(
+        params: Omit<VerifyMerkleRootParamsArgs, 'maxExecutions'> & { maxExecutions?: number },
+        payer?: Signer,
+    ): WrappedInstruction {
+        return extendMerkleRootInstruction(
+            {
+                programs: this.programRepo,
+                payer,
+            },
+            {
+                oneSigState: this.state.publicKey,
+                merkleRootState: this.pda.merkleRootState(params.merkleRoot[0]),
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
+                params: { ...params, maxExecutions: params.maxExecutions ?? 0 },
             },
         ).items[0];
     }
 
     /**
      * Close a (dead) MerkleRootState. The close is permissionless — only the transaction fee payer
-     * needs to sign. Rent is always refunded to `rentPayer`, which must equal the recorded
-     * `rent_payer` of the account.
+     * needs to sign. Rent, including that of the root's registry entry, is always refunded to
+     * `rentPayer`, which must equal the recorded `rent_payer` of the account.
      */
     closeMerkleRootState(merkleRoot: Uint8Array, rentPayer: PublicKey): WrappedInstruction {
         return closeMerkleRoot(
@@ -328,6 +665,7 @@ export class OneSig {
                 rentPayer,
                 merkleRootState: this.pda.merkleRootState(merkleRoot),
                 oneSigState: this.state.publicKey,
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
             },
         ).items[0];
     }
@@ -342,20 +680,277 @@ export class OneSig {
                 state: this.state.publicKey,
                 eventAuthority: this.eventPda.eventAuthority(),
                 oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                targetPrograms:
+                    config.__kind === 'SetTargetPrograms' ? this.pda.targetPrograms() : undefined,
+                spendingLimits:
+                    config.__kind === 'SetExecutorReimbursement'
+                        ? this.pda.spendingLimits()
+                        : undefined,
                 program: this.programId,
                 params: config,
             },
         );
-        const instruction = txBuilder.getInstructions()[0];
-        instruction.keys = [
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Grow the signer capacity of the state account. Like `setConfig`, this is executed as a leaf;
+     * the rent for the added space is paid by the OneSig signer PDA, so the leaf's `value` must
+     * cover it.
+     */
+    resizeState(signersCapacity: number): Instruction {
+        const oneSigSigner = createNoopSigner(this.pda.oneSigSigner()[0]);
+        const txBuilder = resizeStateInstruction(
             {
-                pubkey: this.programId,
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                state: this.state.publicKey,
+                oneSigSigner,
+                payer: oneSigSigner,
+                signersCapacity,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Register an executor, executed as a leaf. The `ExecutorState` PDA is funded by the OneSig
+     * signer PDA, so the leaf's `value` must cover `EXECUTOR_STATE_RENT`.
+     */
+    addExecutor(executor: PublicKey): Instruction {
+        const oneSigSigner = createNoopSigner(this.pda.oneSigSigner()[0]);
+        const txBuilder = addExecutorInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner,
+                payer: oneSigSigner,
+                state: this.state.publicKey,
+                executorState: this.pda.executorState(executor),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                executor,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Restrict the programs `executor` may have executed leaves call while `executor_required` is
+     * set, replacing the listed ones; an empty list lifts the restriction. Executed as a leaf: the
+     * `ExecutorState` PDA grows at the OneSig signer's expense, so the leaf's `value` must cover
+     * the rent of 32 bytes per added program.
+     */
+    setExecutorTargets(executor: PublicKey, programs: PublicKey[]): Instruction {
+        const oneSigSigner = createNoopSigner(this.pda.oneSigSigner()[0]);
+        const txBuilder = setExecutorTargetsInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner,
+                payer: oneSigSigner,
+                state: this.state.publicKey,
+                executorState: this.pda.executorState(executor),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                params: { executor, programs },
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Deregister an executor, executed as a leaf. Rent is refunded to `rentPayer`, which must equal
+     * the recorded `rent_payer`: the OneSig signer PDA for executors added through `addExecutor`,
+     * the init payer for executors created by `initialize`.
+     */
+    removeExecutor(
+        executor: PublicKey,
+        rentPayer: PublicKey = this.pda.oneSigSigner()[0],
+    ): Instruction {
+        const txBuilder = removeExecutorInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                rentPayer,
+                state: this.state.publicKey,
+                executorState: this.pda.executorState(executor),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Register the `module` program, executed as a leaf. The module can then have the signer PDAs
+     * execute instructions without a merkle proof, by CPI signed with its `moduleAuthority`. The
+     * `ModuleState` PDA is funded by the OneSig signer PDA, so the leaf's `value` must cover
+     * `MODULE_STATE_RENT`.
+     */
+    addModule(module: PublicKey): Instruction {
+        const oneSigSigner = createNoopSigner(this.pda.oneSigSigner()[0]);
+        const txBuilder = addModuleInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner,
+                payer: oneSigSigner,
+                state: this.state.publicKey,
+                moduleState: this.pda.moduleState(module),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                module,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Deregister a module, executed as a leaf. Rent is refunded to `rentPayer`, which must equal
+     * the recorded `rent_payer`: the OneSig signer PDA for modules added through `addModule`.
+     */
+    removeModule(
+        module: PublicKey,
+        rentPayer: PublicKey = this.pda.oneSigSigner()[0],
+    ): Instruction {
+        const txBuilder = removeModuleInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                rentPayer,
+                state: this.state.publicKey,
+                moduleState: this.pda.moduleState(module),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Cap what executed leaves can spend of `mint` (the default pubkey for SOL) to `amount` per
+     * `window` seconds, executed as a leaf. A limit for a new mint grows the `SpendingLimits`
+     * account at the OneSig signer's expense, so the leaf's `value` must cover
+     * `SPENDING_LIMIT_RENT`.
+     */
+    setSpendingLimit(mint: PublicKey, amount: bigint, window: bigint): Instruction {
+        const oneSigSigner = createNoopSigner(this.pda.oneSigSigner()[0]);
+        const txBuilder = setSpendingLimitInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner,
+                payer: oneSigSigner,
+                state: this.state.publicKey,
+                spendingLimits: this.pda.spendingLimits(),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                params: { mint, amount, window },
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Lift the spending limit of `mint`, executed as a leaf. The rent of its entry is refunded to
+     * `recipient`.
+     */
+    removeSpendingLimit(
+        mint: PublicKey,
+        recipient: PublicKey = this.pda.oneSigSigner()[0],
+    ): Instruction {
+        const txBuilder = removeSpendingLimitInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                recipient,
+                state: this.state.publicKey,
+                spendingLimits: this.pda.spendingLimits(),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                mint,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
+    }
+
+    /**
+     * Close the state account, sending its rent to `recipient`. Executed as a one-step leaf: the
+     * program refuses while any merkle root (including a pre-verified one carrying this leaf) or
+     * executor account is still open, or while the OneSig signer PDA holds lamports. The signer
+     * PDAs of the other vaults that have signed (`usedVaults`, in ascending order) must be passed
+     * and hold no lamports either. What is left in the bounty escrow goes to `recipient`.
+     */
+    closeOneSig(recipient: PublicKey, usedVaults: number[] = []): Instruction {
+        const txBuilder = closeOneSigInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
+            },
+            {
+                oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                recipient,
+                state: this.state.publicKey,
+                merkleRootRegistry: this.pda.merkleRootRegistry(),
+                spendingLimits: this.pda.spendingLimits(),
+                targetPrograms: this.pda.targetPrograms(),
+                bountyEscrow: this.pda.bountyEscrow(),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+            },
+        );
+        const [ix] = txBuilder.getInstructions();
+        usedVaults.forEach((vaultIndex) => {
+            ix.keys.push({
+                pubkey: this.pda.vaultSigner(vaultIndex)[0],
                 isSigner: false,
                 isWritable: false,
+            });
+        });
+        return this.toLeafInstruction(ix);
+    }
+
+    /**
+     * Withdraw `lamports` from the bounty escrow to `recipient`, executed as a leaf. The escrow
+     * must be left rent-exempt, or emptied.
+     */
+    withdrawBountyEscrow(recipient: PublicKey, lamports: bigint): Instruction {
+        const txBuilder = withdrawBountyEscrowInstruction(
+            {
+                programs: this.programRepo,
+                eddsa: EDDSA,
             },
-            ...instruction.keys,
-        ];
-        return instruction;
+            {
+                oneSigSigner: createNoopSigner(this.pda.oneSigSigner()[0]),
+                recipient,
+                state: this.state.publicKey,
+                bountyEscrow: this.pda.bountyEscrow(),
+                eventAuthority: this.eventPda.eventAuthority(),
+                program: this.programId,
+                lamports,
+            },
+        );
+        return this.toLeafInstruction(txBuilder.getInstructions()[0]);
     }
 
     addSigner(signer: Uint8Array): Instruction {
@@ -374,19 +969,147 @@ export class OneSig {
         return this.setConfig(setConfigParams('SetSeed', [[seed]]));
     }
 
-    addExecutor(executor: PublicKey): Instruction {
-        return this.setConfig(setConfigParams('AddExecutor', [executor]));
-    }
-
-    removeExecutor(executor: PublicKey): Instruction {
-        return this.setConfig(setConfigParams('RemoveExecutor', [executor]));
+    /**
+     * Restart the nonce at 0 under `seed`, which must differ from the current seed: every leaf and
+     * root of the previous seed stops executing, so none of them can be replayed.
+     */
+    resetNonce(seed: Uint8Array): Instruction {
+        return this.setConfig(setConfigParams('ResetNonce', [[seed]]));
     }
 
     setExecutorRequired(required: boolean): Instruction {
         return this.setConfig(setConfigParams('SetExecutorRequired', [required]));
     }
 
-    async getState(rpc: RpcInterface, commitment: Commitment = 'confirmed'): Promise<OneSigState> {
-        return fetchOneSigState({ rpc }, this.state.publicKey, { commitment });
+    /**
+     * Restrict the programs executed leaves may call to (`Allow`) or away from (`Deny`) `programs`,
+     * replacing the listed ones. Calls into OneSig itself are never restricted.
+     */
+    setTargetPrograms(policy: TargetPolicy, programs: PublicKey[]): Instruction {
+        return this.setConfig(setConfigParams('SetTargetPrograms', [{ policy, programs }]));
+    }
+
+    /**
+     * Pay the executor of every leaf `lamports` out of the signer PDA of the leaf's vault, once the
+     * leaf ran; 0 stops reimbursing. Reimbursements count against the SOL spending limit.
+     */
+    setExecutorReimbursement(lamports: bigint): Instruction {
+        return this.setConfig(setConfigParams('SetExecutorReimbursement', [lamports]));
+    }
+
+    /**
+     * Bind every leaf executed from now on to the chain with `genesisHash`, overriding the cluster
+     * the program was built for; for deployments of the same program on other SVM chains. An
+     * all-zero hash clears it.
+     */
+    setClusterGenesisHash(genesisHash: Uint8Array): Instruction {
+        return this.setConfig(setConfigParams('SetClusterGenesisHash', [[genesisHash]]));
+    }
+
+    /**
+     * Allow executed leaves to call the configuration instructions of `targets` back into OneSig,
+     * replacing the allowed ones. `setConfig` can always be called back into, and the other
+     * instructions of OneSig never can.
+     */
+    setSelfCpiTargets(targets: SelfCpiTarget[]): Instruction {
+        return this.setConfig(setConfigParams('SetSelfCpiTargets', [targets]));
+    }
+
+    /**
+     * Execute at most `maxExecutions` leaves per `window` seconds, however many a signed root
+     * holds, leaving time to pause execution should a root turn out compromised; 0 lifts the
+     * limit. The new limit starts a new window.
+     */
+    setExecutionRateLimit(maxExecutions: number, window: bigint): Instruction {
+        return this.setConfig(
+            setConfigParams('SetExecutionRateLimit', [{ maxExecutions, window }]),
+        );
+    }
+
+    async getState(
+        rpc: RpcInterface,
+        commitment: Commitment = 'confirmed',
+    ): Promise<OneSigStateWithSigners> {
+        const account = await rpc.getAccount(this.state.publicKey, { commitment });
+        if (!account.exists) {
+            throw new Error(`OneSig state account not found: ${this.state.publicKey}`);
+        }
+        return decodeOneSigState(account);
+    }
+
+    /**
+     * List the verified merkle roots that are still open, as `MerkleRootState` addresses with their
+     * expiries. Expired entries can be reclaimed with `closeMerkleRootState`.
+     */
+    async getMerkleRoots(
+        rpc: RpcInterface,
+        commitment: Commitment = 'confirmed',
+    ): Promise<MerkleRootEntry[]> {
+        const [registry] = this.pda.merkleRootRegistry();
+        const account = await rpc.getAccount(registry, { commitment });
+        if (!account.exists) {
+            throw new Error(`OneSig merkle root registry not found: ${registry}`);
+        }
+        return deserializeMerkleRootRegistry(account).entries;
+    }
+
+    /**
+     * List the spending limits of the OneSig account, with what was spent in their current window.
+     */
+    async getSpendingLimits(
+        rpc: RpcInterface,
+        commitment: Commitment = 'confirmed',
+    ): Promise<SpendingLimit[]> {
+        const [spendingLimits] = this.pda.spendingLimits();
+        const account = await rpc.getAccount(spendingLimits, { commitment });
+        if (!account.exists) {
+            throw new Error(`OneSig spending limits not found: ${spendingLimits}`);
+        }
+        return deserializeSpendingLimits(account).entries;
+    }
+
+    /**
+     * List the programs named by the target policy of the OneSig account.
+     */
+    async getTargetPrograms(
+        rpc: RpcInterface,
+        commitment: Commitment = 'confirmed',
+    ): Promise<PublicKey[]> {
+        const [targetPrograms] = this.pda.targetPrograms();
+        const account = await rpc.getAccount(targetPrograms, { commitment });
+        if (!account.exists) {
+            throw new Error(`OneSig target programs not found: ${targetPrograms}`);
+        }
+        return deserializeTargetPrograms(account).programs;
+    }
+
+    async isExecutor(
+        rpc: RpcInterface,
+        executor: PublicKey,
+        commitment: Commitment = 'confirmed',
+    ): Promise<boolean> {
+        return rpc.accountExists(this.pda.executorState(executor)[0], { commitment });
+    }
+
+    async isModule(
+        rpc: RpcInterface,
+        module: PublicKey,
+        commitment: Commitment = 'confirmed',
+    ): Promise<boolean> {
+        return rpc.accountExists(this.pda.moduleState(module)[0], { commitment });
+    }
+
+    // Leaf instructions are executed through `execute_transaction`, which expects the target
+    // program ID as the first account.
+    private toLeafInstruction(instruction: Instruction): Instruction {
+        instruction.keys = [
+            {
+                pubkey: this.programId,
+                isSigner: false,
+                isWritable: false,
+            },
+            ...instruction.keys,
+        ];
+        return instruction;
     }
 }
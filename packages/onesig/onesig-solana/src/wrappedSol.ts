@@ -0,0 +1,94 @@
+import type { AccountMeta, PublicKey } from '@metaplex-foundation/umi';
+import { publicKey as toPublicKey } from '@metaplex-foundation/umi';
+import { u32, u64, u8 } from '@metaplex-foundation/umi/serializers';
+
+import type { SolanaCallData } from './index';
+import type { OneSig } from './onesig';
+import { SPL_TOKEN_PROGRAM_ID } from './onesig';
+
+/** Mint of wrapped SOL. */
+export const NATIVE_MINT = toPublicKey('So11111111111111111111111111111111111111112');
+
+const SYSTEM_PROGRAM_ID = toPublicKey('11111111111111111111111111111111');
+
+// Instruction indices of the System and SPL Token programs
+const SYSTEM_TRANSFER = 2;
+const TOKEN_CLOSE_ACCOUNT = 9;
+const TOKEN_SYNC_NATIVE = 17;
+
+/**
+ * Leaf calls wrapping `amount` lamports of the signer PDA of vault `vaultIndex` into its wrapped
+ * SOL associated token account: a transfer, whose `value` covers the lamports leaving the PDA,
+ * then a `SyncNative` to credit them as tokens. Each call is executed as its own leaf, in order.
+ *
+ * The token account must exist; `OneSig.createPdaAta` with `NATIVE_MINT` creates it.
+ */
+export function wrapSolCalls(oneSig: OneSig, amount: bigint, vaultIndex = 0): SolanaCallData[] {
+    const [signer] = oneSig.pda.vaultSigner(vaultIndex);
+    const [tokenAccount] = oneSig.pda.associatedTokenAccount(
+        NATIVE_MINT,
+        SPL_TOKEN_PROGRAM_ID,
+        vaultIndex,
+    );
+    return [
+        toCall(
+            SYSTEM_PROGRAM_ID,
+            [
+                { pubkey: signer, isSigner: true, isWritable: true },
+                { pubkey: tokenAccount, isSigner: false, isWritable: true },
+            ],
+            Buffer.concat([u32().serialize(SYSTEM_TRANSFER), u64().serialize(amount)]),
+            amount,
+            vaultIndex,
+        ),
+        toCall(
+            SPL_TOKEN_PROGRAM_ID,
+            [{ pubkey: tokenAccount, isSigner: false, isWritable: true }],
+            u8().serialize(TOKEN_SYNC_NATIVE),
+            0n,
+            vaultIndex,
+        ),
+    ];
+}
+
+/**
+ * Leaf call unwrapping all the wrapped SOL of vault `vaultIndex` by closing its associated token
+ * account, which returns the tokens and the rent of the account to the vault's signer PDA as
+ * lamports. Nothing leaves the PDA, so the call needs no `value`.
+ */
+export function unwrapSolCall(oneSig: OneSig, vaultIndex = 0): SolanaCallData {
+    const [signer] = oneSig.pda.vaultSigner(vaultIndex);
+    const [tokenAccount] = oneSig.pda.associatedTokenAccount(
+        NATIVE_MINT,
+        SPL_TOKEN_PROGRAM_ID,
+        vaultIndex,
+    );
+    return toCall(
+        SPL_TOKEN_PROGRAM_ID,
+        [
+            { pubkey: tokenAccount, isSigner: false, isWritable: true },
+            { pubkey: signer, isSigner: false, isWritable: true },
+            { pubkey: signer, isSigner: true, isWritable: false },
+        ],
+        u8().serialize(TOKEN_CLOSE_ACCOUNT),
+        0n,
+        vaultIndex,
+    );
+}
+
+// Leaf calls carry the target program as their first key
+function toCall(
+    programId: PublicKey,
+    keys: AccountMeta[],
+    data: Uint8Array,
+    value: bigint,
+    vaultIndex: number,
+): SolanaCallData {
+    return {
+        programId,
+        keys: [{ pubkey: programId, isSigner: false, isWritable: false }, ...keys],
+        data,
+        value,
+        vaultIndex,
+    };
+}
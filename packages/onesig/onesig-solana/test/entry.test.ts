@@ -6,9 +6,12 @@ import { Wallet } from 'ethers';
 import { beforeAll, describe } from 'vitest';
 
 import { OneSig, ONESIG_PROGRAM_ID } from '../src';
+import { closeOneSigTests } from './features/close-one-sig';
 import { oneStepExecutionTests } from './features/one-step-exec';
 import { setConfigTests } from './features/set-config';
 import { signerAsExecutorTests } from './features/signer-as-exec';
+import { spendingLimitsTests } from './features/spending-limits';
+import { targetProgramsTests } from './features/target-programs';
 import { twoStepExecutionTests } from './features/two-step-exec';
 import { DEFAULT_CONFIG, LOCAL_RPC_URL, setupOneSig, TransactionContext } from './helpers';
 
@@ -59,5 +62,8 @@ describe('OneSig Solana', () => {
     describe('Two-Step Transaction Execution', twoStepExecutionTests.bind(this, ctx));
     describe('Signer-as-Executor', signerAsExecutorTests);
     describe('SetConfig Operations', setConfigTests.bind(this, ctx));
+    describe('Close OneSig', closeOneSigTests);
+    describe('Spending Limits', spendingLimitsTests);
+    describe('Target Programs', targetProgramsTests);
     // describe('Transaction Size', txSizeTests.bind(this, ctx))
 });
@@ -0,0 +1,106 @@
+import { arrayify } from '@ethersproject/bytes';
+import { generateSigner, signerIdentity, sol } from '@metaplex-foundation/umi';
+import { createUmi } from '@metaplex-foundation/umi-bundle-defaults';
+import { randomBytes } from 'crypto';
+import { Wallet } from 'ethers';
+import { beforeAll, expect, it } from 'vitest';
+
+import {
+    MerkleRootsRemainingError,
+    NonZeroSignerBalanceError,
+    OneSig,
+    ONESIG_PROGRAM_ID,
+} from '../../src';
+import {
+    DEFAULT_CONFIG,
+    executeWithVerifiedMerkleRoot,
+    LOCAL_RPC_URL,
+    performOneStepExecution,
+    prepareAndVerifyMerkleRoot,
+    setupOneSig,
+    TransactionContext,
+} from '../helpers';
+import { sendAndConfirm, shouldBeRejected, sleep } from '../utils';
+
+/**
+ * Integration tests for `close_one_sig`.
+ *
+ * Uses an ISOLATED OneSig instance since the suite ends by closing it.
+ */
+export function closeOneSigTests() {
+    const umi = createUmi(LOCAL_RPC_URL, 'confirmed');
+    const payer = generateSigner(umi);
+    const recipient = generateSigner(umi);
+    const oneSigState = generateSigner(umi);
+    const oneSig = new OneSig(ONESIG_PROGRAM_ID, oneSigState);
+    const oneSigSeed = arrayify(randomBytes(32));
+    const sortedSigners = Array(DEFAULT_CONFIG.threshold)
+        .fill(0)
+        .map(() => Wallet.createRandom())
+        .sort((a, b) => a.address.localeCompare(b.address));
+
+    const ctx: TransactionContext = {
+        umi,
+        oneSig,
+        payer,
+        recipient,
+        oneSigState,
+        oneSigSeed,
+        sortedSigners,
+    };
+
+    beforeAll(async () => {
+        umi.use(signerIdentity(payer));
+        await Promise.all([
+            umi.rpc.airdrop(payer.publicKey, sol(100), { commitment: 'confirmed' }),
+            umi.rpc.airdrop(recipient.publicKey, sol(1), { commitment: 'confirmed' }),
+        ]);
+        await setupOneSig(umi, oneSig, oneSigSeed, payer, oneSigState, sortedSigners);
+    });
+
+    it('refuses to close while a verified merkle root is open', async () => {
+        // The root carrying the close leaf is itself open, so the close cannot go two-step
+        const call = { ...oneSig.closeOneSig(recipient.publicKey), value: 0n };
+        const { merkleRoot, proof } = await prepareAndVerifyMerkleRoot(ctx, call, 10);
+        await shouldBeRejected(
+            executeWithVerifiedMerkleRoot(ctx, merkleRoot, call, proof),
+            new MerkleRootsRemainingError(oneSig.getProgram()),
+        );
+
+        // Reclaim the root once it has expired
+        await sleep(11000);
+        const closeRootIx = oneSig.closeMerkleRootState(merkleRoot, payer.publicKey);
+        await sendAndConfirm(umi, [closeRootIx], [payer]);
+        const state = await oneSig.getState(umi.rpc);
+        expect(state.merkleRootsLen).toBe(0);
+    });
+
+    it('refuses to close while the signer PDA holds lamports', async () => {
+        // A non-zero leaf value is transferred to the signer PDA ahead of the execution
+        const { nonce } = await oneSig.getState(umi.rpc);
+        await shouldBeRejected(
+            performOneStepExecution(ctx, nonce, {
+                ...oneSig.closeOneSig(recipient.publicKey),
+                value: sol(1).basisPoints,
+            }),
+            new NonZeroSignerBalanceError(oneSig.getProgram()),
+        );
+    });
+
+    it('closes the state and refunds its rent to the recipient', async () => {
+        const stateAccount = await umi.rpc.getAccount(oneSigState.publicKey);
+        expect(stateAccount.exists).toBe(true);
+        const rent = stateAccount.lamports.basisPoints;
+        const balanceBefore = await umi.rpc.getBalance(recipient.publicKey);
+
+        const { nonce } = await oneSig.getState(umi.rpc);
+        await performOneStepExecution(ctx, nonce, {
+            ...oneSig.closeOneSig(recipient.publicKey),
+            value: 0n,
+        });
+
+        expect(await umi.rpc.accountExists(oneSigState.publicKey)).toBe(false);
+        const balanceAfter = await umi.rpc.getBalance(recipient.publicKey);
+        expect(balanceAfter.basisPoints - balanceBefore.basisPoints).toEqual(rent);
+    });
+}
@@ -1,6 +1,16 @@
 import { arrayify } from '@ethersproject/bytes';
-import { transferSol } from '@metaplex-foundation/mpl-toolbox';
-import { createNoopSigner, lamports, sol, some } from '@metaplex-foundation/umi';
+import {
+    closeToken,
+    createAssociatedToken,
+    createMint,
+    fetchToken,
+    findAssociatedTokenPda,
+    mintTokensTo,
+    SPL_TOKEN_PROGRAM_ID,
+    transferSol,
+    transferTokens,
+} from '@metaplex-foundation/mpl-toolbox';
+import { createNoopSigner, generateSigner, lamports, sol, some } from '@metaplex-foundation/umi';
 import { Connection } from '@solana/web3.js';
 import { Wallet } from 'ethers';
 import { expect, it } from 'vitest';
@@ -8,11 +18,18 @@ import { expect, it } from 'vitest';
 import {
     buildOneSigSolanaLeaves,
     DuplicateSignersError,
+    ExcessiveBalanceDeductionError,
+    ExcessiveTokenOutflowError,
     ExpiredMerkleRootError,
     FailedSignatureRecoveryError,
     InsufficientSignaturesError,
     InvalidProofError,
     MissingSignerError,
+    NATIVE_MINT,
+    SolanaCallData,
+    TokenOutflowLimitArgs,
+    unwrapSolCall,
+    wrapSolCalls,
 } from '../../src';
 import {
     buildOneSigMerkleData,
@@ -251,4 +268,157 @@ export function oneStepExecutionTests(ctx: TransactionContext) {
             new InvalidProofError(ctx.oneSig.getProgram()),
         );
     });
+
+    it('should bound the SPL token outflow declared by the leaf', async () => {
+        const [oneSigSigner] = ctx.oneSig.pda.oneSigSigner();
+        const mint = generateSigner(ctx.umi);
+        const [source] = findAssociatedTokenPda(ctx.umi, {
+            mint: mint.publicKey,
+            owner: oneSigSigner,
+        });
+        const [destination] = findAssociatedTokenPda(ctx.umi, {
+            mint: mint.publicKey,
+            owner: ctx.recipient.publicKey,
+        });
+        await createMint(ctx.umi, { mint, decimals: 0 })
+            .add(createAssociatedToken(ctx.umi, { mint: mint.publicKey, owner: oneSigSigner }))
+            .add(
+                createAssociatedToken(ctx.umi, {
+                    mint: mint.publicKey,
+                    owner: ctx.recipient.publicKey,
+                }),
+            )
+            .add(mintTokensTo(ctx.umi, { mint: mint.publicKey, token: source, amount: 100 }))
+            .sendAndConfirm(ctx.umi);
+
+        const transferIx = transferTokens(ctx.umi, {
+            source,
+            destination,
+            authority: createNoopSigner(oneSigSigner),
+            amount: 60,
+        }).getInstructions()[0];
+        const transferCall = (tokenOutflowLimits: TokenOutflowLimitArgs[]): SolanaCallData => ({
+            ...transferIx,
+            keys: [
+                { pubkey: SPL_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+                ...transferIx.keys,
+            ],
+            value: 0n,
+            tokenOutflowLimits,
+        });
+
+        const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await shouldBeRejected(
+            performOneStepExecution(
+                ctx,
+                nonce,
+                transferCall([{ mint: mint.publicKey, maxAmount: 50n }]),
+            ),
+            new ExcessiveTokenOutflowError(ctx.oneSig.getProgram()),
+        );
+
+        await performOneStepExecution(
+            ctx,
+            nonce,
+            transferCall([{ mint: mint.publicKey, maxAmount: 60n }]),
+        );
+        expect((await fetchToken(ctx.umi, destination)).amount).toEqual(60n);
+    });
+
+    it('should bound the lamports drained from guarded accounts', async () => {
+        // Closing a token account of the OneSig signer moves its rent elsewhere without touching
+        // the signer's own balance
+        const [oneSigSigner] = ctx.oneSig.pda.oneSigSigner();
+        const mint = generateSigner(ctx.umi);
+        const [tokenAccount] = findAssociatedTokenPda(ctx.umi, {
+            mint: mint.publicKey,
+            owner: oneSigSigner,
+        });
+        await createMint(ctx.umi, { mint, decimals: 0 })
+            .add(createAssociatedToken(ctx.umi, { mint: mint.publicKey, owner: oneSigSigner }))
+            .sendAndConfirm(ctx.umi);
+        const rent = (await ctx.umi.rpc.getBalance(tokenAccount)).basisPoints;
+
+        const closeIx = closeToken(ctx.umi, {
+            account: tokenAccount,
+            destination: ctx.recipient.publicKey,
+            owner: createNoopSigner(oneSigSigner),
+        }).getInstructions()[0];
+        const closeCall = (value: bigint): SolanaCallData => ({
+            ...closeIx,
+            keys: [
+                { pubkey: SPL_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+                ...closeIx.keys,
+            ],
+            value,
+            guardedAccounts: [tokenAccount],
+        });
+
+        const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await shouldBeRejected(
+            performOneStepExecution(ctx, nonce, closeCall(0n)),
+            new ExcessiveBalanceDeductionError(ctx.oneSig.getProgram()),
+        );
+        await performOneStepExecution(ctx, nonce, closeCall(rent));
+        expect(await ctx.umi.rpc.accountExists(tokenAccount)).toBe(false);
+    });
+
+    it('should execute from an indexed vault', async () => {
+        const [vault] = ctx.oneSig.pda.vaultSigner(1);
+        await ctx.umi.rpc.airdrop(vault, sol(1), { commitment: 'confirmed' });
+        const call: SolanaCallData = {
+            ...createTransferInstruction(ctx.umi, vault, ctx.recipient.publicKey, 1000n),
+            vaultIndex: 1,
+        };
+
+        const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await verifyBalanceChange(
+            ctx.umi,
+            ctx.oneSig.pda.oneSigSigner()[0],
+            async () => {
+                await performOneStepExecution(ctx, nonce, call);
+            },
+            0n,
+        );
+    });
+
+    it('should create the associated token account of the OneSig signer', async () => {
+        const mint = generateSigner(ctx.umi);
+        await createMint(ctx.umi, { mint, decimals: 0 }).sendAndConfirm(ctx.umi);
+
+        const ix = ctx.oneSig.createPdaAta(ctx.payer, mint.publicKey);
+        await sendAndConfirm(ctx.umi, [ix], [ctx.payer]);
+        // Creating it again is a no-op (the compute limit only keeps the transaction distinct)
+        await sendAndConfirm(ctx.umi, [ix], [ctx.payer], 200_000);
+
+        const [ata] = ctx.oneSig.pda.associatedTokenAccount(mint.publicKey);
+        const token = await fetchToken(ctx.umi, ata);
+        expect(token.owner).toEqual(ctx.oneSig.pda.oneSigSigner()[0]);
+        expect(token.amount).toEqual(0n);
+    });
+
+    it('should wrap and unwrap SOL held by the OneSig signer', async () => {
+        const createAta = ctx.oneSig.createPdaAta(ctx.payer, NATIVE_MINT);
+        await sendAndConfirm(ctx.umi, [createAta], [ctx.payer]);
+        const [ata] = ctx.oneSig.pda.associatedTokenAccount(NATIVE_MINT);
+        const rent = (await ctx.umi.rpc.getBalance(ata)).basisPoints;
+
+        for (const call of wrapSolCalls(ctx.oneSig, 5000n)) {
+            const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+            await performOneStepExecution(ctx, nonce, call);
+        }
+        expect((await fetchToken(ctx.umi, ata)).amount).toEqual(5000n);
+
+        // Closing the account returns both the wrapped lamports and its rent
+        const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await verifyBalanceChange(
+            ctx.umi,
+            ctx.oneSig.pda.oneSigSigner()[0],
+            async () => {
+                await performOneStepExecution(ctx, nonce, unwrapSolCall(ctx.oneSig));
+            },
+            5000n + rent,
+        );
+        expect(await ctx.umi.rpc.accountExists(ata)).toBe(false);
+    });
 }
@@ -7,6 +7,7 @@ import {
     publicKey,
     Signer,
     sol,
+    some,
     TRANSACTION_SIZE_LIMIT,
     Umi,
 } from '@metaplex-foundation/umi';
@@ -17,17 +18,24 @@ import { expect, it } from 'vitest';
 import {
     DuplicateExecutorError,
     DuplicateSignersError,
-    ExecutorNotFoundError,
+    EXECUTOR_STATE_RENT,
     ExecutorRequiredError,
+    ExecutorTargetNotAllowedError,
     InvalidSignersLenError,
     InvalidThresholdError,
     MissingSignerError,
+    MODULE_STATE_RENT,
+    InvalidProofError,
+    ONESIG_PROGRAM_ID,
+    TargetPolicy,
     ThresholdExceedsSignersError,
 } from '../../src';
 import {
+    buildOneSigMerkleData,
     createTransferInstruction,
     DEFAULT_CONFIG,
     executeWithVerifiedMerkleRoot,
+    isGatedExecutor,
     MAX_SIGNERS,
     MAX_THRESHOLD,
     performOneStepExecution,
@@ -36,7 +44,7 @@ import {
     TransactionContext,
     verifyBalanceChange,
 } from '../helpers';
-import { shouldBeRejected, sleep } from '../utils';
+import { sendAndConfirm, shouldBeRejected, sleep } from '../utils';
 
 /**
  * Tests for SetConfig operations
@@ -164,30 +172,21 @@ export function setConfigTests(ctx: TransactionContext) {
     });
 
     it('should add an executor', async () => {
-        // Get current executors for comparison
         const state = await ctx.oneSig.getState(ctx.umi.rpc);
-        const executorsBefore = state.executors.executors;
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(false);
 
-        // Add the executor
+        // Add the executor; the leaf value funds its PDA
         const ix = ctx.oneSig.addExecutor(newExecutor.publicKey);
-        await performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n });
+        await performOneStepExecution(ctx, state.nonce, { ...ix, value: EXECUTOR_STATE_RENT });
 
-        // Verify the executor was added
-        const stateAfter = await ctx.oneSig.getState(ctx.umi.rpc);
-        const executorsAfter = stateAfter.executors.executors;
-
-        expect(executorsAfter.length).toEqual(executorsBefore.length + 1);
-
-        // The new executor should exist in the array
-        expect(executorsAfter.some((e) => e.toString() === newExecutor.publicKey.toString())).toBe(
-            true,
-        );
+        // Verify the executor PDA was created
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(true);
     });
 
     it('should only allow executors to execute when executorRequired is true', async () => {
         // Get current state
         const stateBefore = await ctx.oneSig.getState(ctx.umi.rpc);
-        const initialRequiredState = stateBefore.executors.executorRequired;
+        const initialRequiredState = stateBefore.executorRequired;
         expect(initialRequiredState).toBe(false);
 
         const ix = ctx.oneSig.setExecutorRequired(true);
@@ -195,7 +194,7 @@ export function setConfigTests(ctx: TransactionContext) {
 
         // Verify the flag was updated
         const stateAfter = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(stateAfter.executors.executorRequired).toBe(true);
+        expect(stateAfter.executorRequired).toBe(true);
 
         // T
         const instruction = createTransferInstruction(
@@ -222,6 +221,48 @@ export function setConfigTests(ctx: TransactionContext) {
         ctx.umi.payer = ctx.payer;
     });
 
+    it('should restrict an executor to its target programs', async () => {
+        // Keep the executor to OneSig itself; the leaf value funds the added program entry
+        let state = await ctx.oneSig.getState(ctx.umi.rpc);
+        await performOneStepExecution(ctx, state.nonce, {
+            ...ctx.oneSig.setExecutorTargets(newExecutor.publicKey, [ONESIG_PROGRAM_ID]),
+            value: BigInt(32 * 3480 * 2),
+        });
+        state = await ctx.oneSig.getState(ctx.umi.rpc);
+        await performOneStepExecution(ctx, state.nonce, {
+            ...ctx.oneSig.setExecutorRequired(true),
+            value: 0n,
+        });
+
+        ctx.umi.payer = newExecutor;
+        state = await ctx.oneSig.getState(ctx.umi.rpc);
+        await shouldBeRejected(
+            performOneStepExecution(
+                ctx,
+                state.nonce,
+                createTransferInstruction(
+                    ctx.umi,
+                    ctx.oneSig.pda.oneSigSigner()[0],
+                    ctx.recipient.publicKey,
+                    50n,
+                ),
+            ),
+            new ExecutorTargetNotAllowedError(ctx.oneSig.getProgram()),
+        );
+
+        // Leaves calling OneSig are still within reach of the executor
+        await performOneStepExecution(ctx, state.nonce, {
+            ...ctx.oneSig.setExecutorRequired(false),
+            value: 0n,
+        });
+        ctx.umi.payer = ctx.payer;
+        state = await ctx.oneSig.getState(ctx.umi.rpc);
+        await performOneStepExecution(ctx, state.nonce, {
+            ...ctx.oneSig.setExecutorTargets(newExecutor.publicKey, []),
+            value: 0n,
+        });
+    });
+
     it('should allow removing the last executor when executor_required is true (EVM/Stellar parity)', async () => {
         // Enable executor_required while the single executor is still present.
         let state = await ctx.oneSig.getState(ctx.umi.rpc);
@@ -231,12 +272,12 @@ export function setConfigTests(ctx: TransactionContext) {
         });
 
         state = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(state.executors.executorRequired).toBe(true);
-        expect(state.executors.executors.length).toBe(1);
+        expect(state.executorRequired).toBe(true);
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(true);
 
         // Removing the only executor now SUCCEEDS: executor_required=true with an empty executor
         // set is a valid state, matching onesig-evm and onesig-stellar. The executor authorizes
-        // its own removal (the check uses the still-populated set at the start of the tx).
+        // its own removal (its PDA is checked at the start of the tx, before the leaf closes it).
         ctx.umi.payer = newExecutor;
         state = await ctx.oneSig.getState(ctx.umi.rpc);
         await performOneStepExecution(ctx, state.nonce, {
@@ -245,8 +286,8 @@ export function setConfigTests(ctx: TransactionContext) {
         });
 
         state = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(state.executors.executorRequired).toBe(true);
-        expect(state.executors.executors.length).toBe(0);
+        expect(state.executorRequired).toBe(true);
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(false);
 
         // With an empty executor set + executor_required, execute_transaction is now unusable
         // (the executor check can never pass). Recover via the signer-as-executor path, which a
@@ -259,31 +300,145 @@ export function setConfigTests(ctx: TransactionContext) {
         });
 
         state = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(state.executors.executorRequired).toBe(false);
+        expect(state.executorRequired).toBe(false);
 
         // Restore the executor so downstream tests observe the original state.
         await performOneStepExecution(ctx, state.nonce, {
             ...ctx.oneSig.addExecutor(newExecutor.publicKey),
-            value: 0n,
+            value: EXECUTOR_STATE_RENT,
         });
-        state = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(
-            state.executors.executors.some(
-                (e) => e.toString() === newExecutor.publicKey.toString(),
-            ),
-        ).toBe(true);
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(true);
     });
 
     it('should remove an executor', async () => {
         const ix = ctx.oneSig.removeExecutor(newExecutor.publicKey);
         const state = await ctx.oneSig.getState(ctx.umi.rpc);
-        await performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n });
+
+        // The rent of the executor PDA is refunded to the OneSig signer that funded it
+        await verifyBalanceChange(
+            ctx.umi,
+            ctx.oneSig.pda.oneSigSigner()[0],
+            async () => {
+                await performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n });
+            },
+            EXECUTOR_STATE_RENT,
+        );
 
         // Verify it was removed
-        const stateAfter = await ctx.oneSig.getState(ctx.umi.rpc);
-        expect(
-            stateAfter.executors.executors.some((e) => e.toString() === newExecutor.toString()),
-        ).toBe(false);
+        expect(await ctx.oneSig.isExecutor(ctx.umi.rpc, newExecutor.publicKey)).toBe(false);
+    });
+
+    it('should add and remove a module', async () => {
+        const module = generateSigner(ctx.umi).publicKey;
+        const { nonce, modulesLen } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await performOneStepExecution(ctx, nonce, {
+            ...ctx.oneSig.addModule(module),
+            value: MODULE_STATE_RENT,
+        });
+        expect(await ctx.oneSig.isModule(ctx.umi.rpc, module)).toBe(true);
+        expect((await ctx.oneSig.getState(ctx.umi.rpc)).modulesLen).toEqual(modulesLen + 1);
+
+        // The rent of the module PDA is refunded to the OneSig signer that funded it
+        await verifyBalanceChange(
+            ctx.umi,
+            ctx.oneSig.pda.oneSigSigner()[0],
+            async () => {
+                await performOneStepExecution(ctx, nonce + 1n, {
+                    ...ctx.oneSig.removeModule(module),
+                    value: 0n,
+                });
+            },
+            MODULE_STATE_RENT,
+        );
+        expect(await ctx.oneSig.isModule(ctx.umi.rpc, module)).toBe(false);
+    });
+
+    it('should reimburse the executor out of the OneSig signer', async () => {
+        const reimbursement = 10_000n;
+        const [oneSigSigner] = ctx.oneSig.pda.oneSigSigner();
+        const { nonce } = await ctx.oneSig.getState(ctx.umi.rpc);
+        // The new reimbursement already applies to the leaf setting it
+        await verifyBalanceChange(
+            ctx.umi,
+            oneSigSigner,
+            async () => {
+                await performOneStepExecution(ctx, nonce, {
+                    ...ctx.oneSig.setExecutorReimbursement(reimbursement),
+                    value: 0n,
+                });
+            },
+            -reimbursement,
+        );
+        await verifyBalanceChange(
+            ctx.umi,
+            oneSigSigner,
+            async () => {
+                await performOneStepExecution(ctx, nonce + 1n, {
+                    ...createTransferInstruction(
+                        ctx.umi,
+                        oneSigSigner,
+                        ctx.recipient.publicKey,
+                        100n,
+                    ),
+                    value: 100n,
+                });
+            },
+            -(100n + reimbursement),
+        );
+
+        await verifyBalanceChange(
+            ctx.umi,
+            oneSigSigner,
+            async () => {
+                await performOneStepExecution(ctx, nonce + 2n, {
+                    ...ctx.oneSig.setExecutorReimbursement(0n),
+                    value: 0n,
+                });
+            },
+            0n,
+        );
+    });
+
+    it('should bind leaves to the configured cluster genesis hash', async () => {
+        const genesisHash = randomBytes(32);
+        const { nonce, multisig, targetPolicy } = await ctx.oneSig.getState(ctx.umi.rpc);
+        await performOneStepExecution(ctx, nonce, {
+            ...ctx.oneSig.setClusterGenesisHash(genesisHash),
+            value: 0n,
+        });
+        expect((await ctx.oneSig.getState(ctx.umi.rpc)).clusterGenesisHash[0]).toEqual(
+            new Uint8Array(genesisHash),
+        );
+
+        // Leaves that do not bind the hash no longer verify
+        const call = { ...ctx.oneSig.setClusterGenesisHash(new Uint8Array(32)), value: 0n };
+        const { merkleRoot, expiry, signatures, proof } = await buildOneSigMerkleData(
+            ctx.oneSig,
+            ctx.oneSigSeed,
+            ctx.sortedSigners,
+            nonce + 1n,
+            call,
+        );
+        const unbound = ctx.oneSig.executeTransaction(ctx.umi.payer, merkleRoot, {
+            call,
+            proof,
+            asExecutor: await isGatedExecutor(ctx.umi, ctx.oneSig),
+            withTargetPrograms: targetPolicy !== TargetPolicy.None,
+            merkleRootVerification: some({
+                expiry,
+                signatures: arrayify(signatures).slice(0, multisig.threshold * 65),
+            }),
+        });
+        await shouldBeRejected(
+            sendAndConfirm(ctx.umi, [unbound], [ctx.umi.payer]),
+            new InvalidProofError(ctx.oneSig.getProgram()),
+        );
+
+        // The helpers bind the configured hash, and clearing it unbinds leaves again
+        await performOneStepExecution(ctx, nonce + 1n, call);
+        expect((await ctx.oneSig.getState(ctx.umi.rpc)).clusterGenesisHash[0]).toEqual(
+            new Uint8Array(32),
+        );
     });
 
     // ===================== FAILURE TEST CASES =====================
@@ -319,10 +474,10 @@ export function setConfigTests(ctx: TransactionContext) {
     it('should fail to add a duplicate executor', async () => {
         const state = await ctx.oneSig.getState(ctx.umi.rpc);
         const ix = ctx.oneSig.addExecutor(newExecutor.publicKey);
-        await performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n });
+        await performOneStepExecution(ctx, state.nonce, { ...ix, value: EXECUTOR_STATE_RENT });
 
         await shouldBeRejected(
-            performOneStepExecution(ctx, state.nonce + 1n, { ...ix, value: 0n }),
+            performOneStepExecution(ctx, state.nonce + 1n, { ...ix, value: EXECUTOR_STATE_RENT }),
             new DuplicateExecutorError(ctx.oneSig.getProgram()),
         );
     });
@@ -331,10 +486,11 @@ export function setConfigTests(ctx: TransactionContext) {
         const newExecutor = generateSigner(ctx.umi);
         const state = await ctx.oneSig.getState(ctx.umi.rpc);
         const ix = ctx.oneSig.removeExecutor(newExecutor.publicKey);
-        await shouldBeRejected(
-            performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n }),
-            new ExecutorNotFoundError(ctx.oneSig.getProgram()),
-        );
+        // The executor PDA does not exist, so Anchor rejects it as uninitialized
+        await shouldBeRejected(performOneStepExecution(ctx, state.nonce, { ...ix, value: 0n }), {
+            code: 3012,
+            name: 'AccountNotInitialized',
+        });
     });
 
     it('should fail to set threshold to zero', async () => {
@@ -82,6 +82,7 @@ export function signerAsExecutorTests() {
             oneSigId: DEFAULT_CONFIG.oneSigId,
             executors: [placeholderExecutor.publicKey],
             executorRequired: true,
+            signersCapacity: DEFAULT_CONFIG.signersCapacity,
         });
         await sendAndConfirm(umi, [initIx], [payer, oneSigState]);
     });
@@ -400,7 +401,7 @@ export function signerAsExecutorTests() {
             await performSignerExecution(ctx, delegate, proofSigner, nonce, call);
 
             const state = await oneSig.getState(umi.rpc);
-            expect(state.executors.executorRequired).toBe(false);
+            expect(state.executorRequired).toBe(false);
         });
 
         it('executes with arbitrary signer execution authorization bytes (gate skipped)', async () => {
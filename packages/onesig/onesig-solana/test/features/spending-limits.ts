@@ -0,0 +1,107 @@
+import { arrayify } from '@ethersproject/bytes';
+import { generateSigner, publicKey, signerIdentity, sol } from '@metaplex-foundation/umi';
+import { createUmi } from '@metaplex-foundation/umi-bundle-defaults';
+import { randomBytes } from 'crypto';
+import { Wallet } from 'ethers';
+import { beforeAll, expect, it } from 'vitest';
+
+import {
+    OneSig,
+    ONESIG_PROGRAM_ID,
+    SolanaCallData,
+    SPENDING_LIMIT_RENT,
+    SpendingLimitExceededError,
+    SpendingLimitNotFoundError,
+} from '../../src';
+import {
+    createTransferInstruction,
+    DEFAULT_CONFIG,
+    LOCAL_RPC_URL,
+    performOneStepExecution,
+    setupOneSig,
+    TransactionContext,
+} from '../helpers';
+import { shouldBeRejected } from '../utils';
+
+// The default pubkey stands for SOL in spending limits
+const SOL = publicKey('11111111111111111111111111111111');
+
+/**
+ * Integration tests for the spending limits enforced on executed leaves.
+ *
+ * Uses an ISOLATED OneSig instance so the limits do not affect the other suites.
+ */
+export function spendingLimitsTests() {
+    const umi = createUmi(LOCAL_RPC_URL, 'confirmed');
+    const payer = generateSigner(umi);
+    const recipient = generateSigner(umi);
+    const oneSigState = generateSigner(umi);
+    const oneSig = new OneSig(ONESIG_PROGRAM_ID, oneSigState);
+    const oneSigSeed = arrayify(randomBytes(32));
+    const sortedSigners = Array(DEFAULT_CONFIG.threshold)
+        .fill(0)
+        .map(() => Wallet.createRandom())
+        .sort((a, b) => a.address.localeCompare(b.address));
+
+    const ctx: TransactionContext = {
+        umi,
+        oneSig,
+        payer,
+        recipient,
+        oneSigState,
+        oneSigSeed,
+        sortedSigners,
+    };
+
+    const execute = async (call: SolanaCallData) => {
+        const { nonce } = await oneSig.getState(umi.rpc);
+        return performOneStepExecution(ctx, nonce, call);
+    };
+    const transfer = (amount: bigint) =>
+        createTransferInstruction(umi, oneSig.pda.oneSigSigner()[0], recipient.publicKey, amount);
+
+    beforeAll(async () => {
+        umi.use(signerIdentity(payer));
+        await Promise.all([
+            umi.rpc.airdrop(payer.publicKey, sol(100), { commitment: 'confirmed' }),
+            umi.rpc.airdrop(recipient.publicKey, sol(1), { commitment: 'confirmed' }),
+        ]);
+        await setupOneSig(umi, oneSig, oneSigSeed, payer, oneSigState, sortedSigners);
+    });
+
+    it('caps the SOL spent by executed leaves within a window', async () => {
+        // Setting the limit is not charged to it, although the leaf pays for the new entry
+        await execute({
+            ...oneSig.setSpendingLimit(SOL, 1000n, 3600n),
+            value: SPENDING_LIMIT_RENT,
+        });
+        const [limit] = await oneSig.getSpendingLimits(umi.rpc);
+        expect(limit.amount).toEqual(1000n);
+        expect(limit.spent).toEqual(0n);
+
+        await execute(transfer(600n));
+        await shouldBeRejected(
+            execute(transfer(600n)),
+            new SpendingLimitExceededError(oneSig.getProgram()),
+        );
+    });
+
+    it('keeps the spent amount when the limit is raised', async () => {
+        await execute({ ...oneSig.setSpendingLimit(SOL, 2000n, 3600n), value: 0n });
+        await execute(transfer(600n));
+
+        const [limit] = await oneSig.getSpendingLimits(umi.rpc);
+        expect(limit.spent).toEqual(1200n);
+    });
+
+    it('stops enforcing a removed limit', async () => {
+        await execute({ ...oneSig.removeSpendingLimit(SOL), value: 0n });
+        expect(await oneSig.getSpendingLimits(umi.rpc)).toEqual([]);
+        await execute(transfer(5000n));
+
+        await shouldBeRejected(
+            execute({ ...oneSig.removeSpendingLimit(SOL), value: 0n }),
+            new SpendingLimitNotFoundError(oneSig.getProgram()),
+        );
+    });
+}
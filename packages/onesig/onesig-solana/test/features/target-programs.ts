@@ -0,0 +1,100 @@
+import { arrayify } from '@ethersproject/bytes';
+import { generateSigner, publicKey, signerIdentity, sol } from '@metaplex-foundation/umi';
+import { createUmi } from '@metaplex-foundation/umi-bundle-defaults';
+import { randomBytes } from 'crypto';
+import { Wallet } from 'ethers';
+import { beforeAll, expect, it } from 'vitest';
+
+import {
+    OneSig,
+    ONESIG_PROGRAM_ID,
+    SolanaCallData,
+    TargetPolicy,
+    TargetProgramNotAllowedError,
+} from '../../src';
+import {
+    createTransferInstruction,
+    DEFAULT_CONFIG,
+    LOCAL_RPC_URL,
+    performOneStepExecution,
+    setupOneSig,
+    TransactionContext,
+} from '../helpers';
+import { shouldBeRejected } from '../utils';
+
+const SYSTEM_PROGRAM = publicKey('11111111111111111111111111111111');
+
+/**
+ * Integration tests for the target policy restricting the programs executed leaves may call.
+ *
+ * Uses an ISOLATED OneSig instance so the policy does not affect the other suites.
+ */
+export function targetProgramsTests() {
+    const umi = createUmi(LOCAL_RPC_URL, 'confirmed');
+    const payer = generateSigner(umi);
+    const recipient = generateSigner(umi);
+    const oneSigState = generateSigner(umi);
+    const oneSig = new OneSig(ONESIG_PROGRAM_ID, oneSigState);
+    const oneSigSeed = arrayify(randomBytes(32));
+    const sortedSigners = Array(DEFAULT_CONFIG.threshold)
+        .fill(0)
+        .map(() => Wallet.createRandom())
+        .sort((a, b) => a.address.localeCompare(b.address));
+
+    const ctx: TransactionContext = {
+        umi,
+        oneSig,
+        payer,
+        recipient,
+        oneSigState,
+        oneSigSeed,
+        sortedSigners,
+    };
+
+    const execute = async (call: SolanaCallData) => {
+        const { nonce } = await oneSig.getState(umi.rpc);
+        return performOneStepExecution(ctx, nonce, call);
+    };
+    const transfer = () =>
+        createTransferInstruction(umi, oneSig.pda.oneSigSigner()[0], recipient.publicKey, 1000n);
+
+    beforeAll(async () => {
+        umi.use(signerIdentity(payer));
+        await Promise.all([
+            umi.rpc.airdrop(payer.publicKey, sol(100), { commitment: 'confirmed' }),
+            umi.rpc.airdrop(recipient.publicKey, sol(1), { commitment: 'confirmed' }),
+        ]);
+        await setupOneSig(umi, oneSig, oneSigSeed, payer, oneSigState, sortedSigners);
+    });
+
+    it('only calls the allowed programs', async () => {
+        await execute({
+            ...oneSig.setTargetPrograms(TargetPolicy.Allow, [SYSTEM_PROGRAM]),
+            value: 0n,
+        });
+        expect((await oneSig.getState(umi.rpc)).targetPolicy).toEqual(TargetPolicy.Allow);
+        expect(await oneSig.getTargetPrograms(umi.rpc)).toEqual([SYSTEM_PROGRAM]);
+        await execute(transfer());
+
+        // Config changes stay possible whatever the policy lists
+        await execute({ ...oneSig.setTargetPrograms(TargetPolicy.Allow, []), value: 0n });
+        await shouldBeRejected(
+            execute(transfer()),
+            new TargetProgramNotAllowedError(oneSig.getProgram()),
+        );
+    });
+
+    it('refuses to call the denied programs', async () => {
+        await execute({
+            ...oneSig.setTargetPrograms(TargetPolicy.Deny, [SYSTEM_PROGRAM]),
+            value: 0n,
+        });
+        await shouldBeRejected(
+            execute(transfer()),
+            new TargetProgramNotAllowedError(oneSig.getProgram()),
+        );
+
+        await execute({ ...oneSig.setTargetPrograms(TargetPolicy.None, []), value: 0n });
+        await execute(transfer());
+    });
+}
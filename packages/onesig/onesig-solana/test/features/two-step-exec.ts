@@ -4,16 +4,23 @@ import { randomBytes } from 'crypto';
 import { Wallet } from 'ethers';
 import { expect, it } from 'vitest';
 
+import { encodeLeaf, makeOneSigTree, signOneSigTree } from '@layerzerolabs/onesig-core';
+
 import {
     InsufficientSignaturesError,
+    ExpiryNotExtendedError,
+    MerkleRootExhaustedError,
     MerkleRootNotCloseableError,
     OneSig,
     ONESIG_PROGRAM_ID,
+    prepareSolanaCallDataForMerkleLeaf,
     SeedMismatchError,
+    solanaLeafGenerator,
 } from '../../src';
 import {
     buildOneSigMerkleData,
     createTransferInstruction,
+    DEFAULT_CONFIG,
     executeWithVerifiedMerkleRoot,
     performOneStepExecution,
     performTwoStepExecution,
@@ -101,6 +108,13 @@ export function twoStepExecutionTests(ctx: TransactionContext) {
         const merkleRootState = ctx.oneSig.pda.merkleRootState(merkleRoot)[0];
         const balanceBefore = await ctx.umi.rpc.getBalance(oneSigSigner);
 
+        // The verified root is listed in the registry until it is closed
+        const isListed = async () =>
+            (await ctx.oneSig.getMerkleRoots(ctx.umi.rpc)).some(
+                (entry) => entry.address === merkleRootState,
+            );
+        expect(await isListed()).toEqual(true);
+
         // Step 3: Close the merkle root account
         const closeMerkleRootIx = ctx.oneSig.closeMerkleRootState(merkleRoot, ctx.payer.publicKey);
         // Wait for the merkle tree to be expired
@@ -111,9 +125,10 @@ export function twoStepExecutionTests(ctx: TransactionContext) {
         const balanceAfter = await ctx.umi.rpc.getBalance(oneSigSigner);
         expect(balanceAfter.basisPoints >= balanceBefore.basisPoints).toEqual(true);
 
-        // Double check that the account is closed
+        // Double check that the account is closed and delisted
         const accountInfo = await ctx.umi.rpc.getAccount(merkleRootState);
         expect(accountInfo.exists).toEqual(false);
+        expect(await isListed()).toEqual(false);
     });
 
     it('should fail to close merkle root account before expiry', async () => {
@@ -375,4 +390,113 @@ export function twoStepExecutionTests(ctx: TransactionContext) {
         const after = await local.oneSig.getState(local.umi.rpc);
         expect(after.nonce).toEqual(before.nonce + 1n);
     });
+
+    it('stops executing a pre-verified root once its signed max executions are used', async () => {
+        const local = await setupIsolatedOneSig(ctx, 2, 2);
+        const [oneSigSigner] = local.oneSig.pda.oneSigSigner();
+        const calls = [1n, 2n].map((amount) =>
+            createTransferInstruction(local.umi, oneSigSigner, local.recipient.publicKey, amount),
+        );
+
+        // One root covering two consecutive leaves, but signed for a single execution
+        const { nonce } = await local.oneSig.getState(local.umi.rpc);
+        const solanaGen = solanaLeafGenerator(
+            local.oneSig.programId,
+            calls.map((call, i) => ({
+                nonce: nonce + BigInt(i),
+                oneSigId: DEFAULT_CONFIG.oneSigId,
+                targetOneSigAddress: local.oneSig.state.publicKey,
+                calls: [prepareSolanaCallDataForMerkleLeaf(local.oneSig, call)],
+            })),
+        );
+        const merkleTree = makeOneSigTree([solanaGen]);
+        const merkleRoot = arrayify(merkleTree.getRoot());
+        const expiry = Math.floor(Date.now() / 1000) + DEFAULT_CONFIG.expiryOffset;
+        const signatures = await signOneSigTree(merkleTree, local.sortedSigners, {
+            seed: local.oneSigSeed,
+            expiry,
+            maxExecutions: 1,
+        });
+        const proofs = calls.map((_, i) => merkleTree.getHexProof(encodeLeaf(solanaGen, i)));
+
+        await sendAndConfirm(
+            local.umi,
+            [
+                local.oneSig.verifyMerkleRoot(local.payer, {
+                    merkleRoot: [merkleRoot],
+                    expiry,
+                    maxExecutions: 1,
+                    signatures: arrayify(signatures),
+                }),
+            ],
+            [local.payer],
+        );
+
+        await executeWithVerifiedMerkleRoot(local, merkleRoot, calls[0], proofs[0]);
+        await shouldBeRejected(
+            executeWithVerifiedMerkleRoot(local, merkleRoot, calls[1], proofs[1]),
+            new MerkleRootExhaustedError(local.oneSig.getProgram()),
+        );
+    });
+
+    it('extends the expiry of a verified root in place', async () => {
+        const local = await setupIsolatedOneSig(ctx, 2, 2);
+        const call = createTransferInstruction(
+            local.umi,
+            local.oneSig.pda.oneSigSigner()[0],
+            local.recipient.publicKey,
+            555n,
+        );
+        const { nonce } = await local.oneSig.getState(local.umi.rpc);
+
+        // Verify with a short expiry and let it lapse
+        const { merkleRoot, proof } = await prepareAndVerifyMerkleRoot(local, call, 1);
+        await sleep(3000);
+
+        // The signers sign the same root again, with a later expiry
+        const { expiry, signatures } = await buildOneSigMerkleData(
+            local.oneSig,
+            local.oneSigSeed,
+            local.sortedSigners,
+            nonce,
+            call,
+        );
+        const extendIx = local.oneSig.extendMerkleRoot({
+            merkleRoot: [merkleRoot],
+            expiry,
+            signatures: arrayify(signatures),
+        });
+        await sendAndConfirm(local.umi, [extendIx], [local.payer]);
+
+        const merkleRootState = local.oneSig.pda.merkleRootState(merkleRoot)[0];
+        const [entry] = await local.oneSig.getMerkleRoots(local.umi.rpc);
+        expect(entry.address).toEqual(merkleRootState);
+        expect(entry.expiry).toEqual(BigInt(expiry));
+
+        // The expiry can only move forward
+        const earlier = await buildOneSigMerkleData(
+            local.oneSig,
+            local.oneSigSeed,
+            local.sortedSigners,
+            nonce,
+            call,
+            DEFAULT_CONFIG.expiryOffset / 2,
+        );
+        await shouldBeRejected(
+            sendAndConfirm(
+                local.umi,
+                [
+                    local.oneSig.extendMerkleRoot({
+                        merkleRoot: [merkleRoot],
+                        expiry: earlier.expiry,
+                        signatures: arrayify(earlier.signatures),
+                    }),
+                ],
+                [local.payer],
+            ),
+            new ExpiryNotExtendedError(local.oneSig.getProgram()),
+        );
+
+        await executeWithVerifiedMerkleRoot(local, merkleRoot, call, proof);
+    });
 }
@@ -25,9 +25,11 @@ import {
 
 import {
     OneSig,
+    OneSigStateWithSigners,
     prepareSolanaCallDataForMerkleLeaf,
     SolanaCallData,
     solanaLeafGenerator,
+    TargetPolicy,
 } from '../src';
 import { sendAndConfirm, TransactionSendResult } from './utils';
 
@@ -41,6 +43,7 @@ export const DEFAULT_CONFIG = {
     oneSigId: 900n,
     threshold: 2,
     expiryOffset: 1000,
+    signersCapacity: MAX_SIGNERS,
 };
 
 export interface TransactionContext {
@@ -117,6 +120,7 @@ export async function setupOneSig(
         oneSigId: DEFAULT_CONFIG.oneSigId,
         executors: [],
         executorRequired: false,
+        signersCapacity: DEFAULT_CONFIG.signersCapacity,
     });
     await sendAndConfirm(umi, [ix], [payer, oneSigState]);
 }
@@ -132,20 +136,25 @@ export async function buildOneSigMerkleData(
     nonce: bigint,
     call: SolanaCallData,
     expiryOffset = DEFAULT_CONFIG.expiryOffset,
+    clusterGenesisHash?: Uint8Array,
 ): Promise<{
     merkleRoot: Uint8Array;
     expiry: number;
     signatures: string;
     proof: string[];
 }> {
-    const solanaGen = solanaLeafGenerator(oneSig.programId, [
-        {
-            nonce,
-            oneSigId: DEFAULT_CONFIG.oneSigId,
-            targetOneSigAddress: oneSig.state.publicKey,
-            calls: [prepareSolanaCallDataForMerkleLeaf(oneSig, call)],
-        },
-    ]);
+    const solanaGen = solanaLeafGenerator(
+        oneSig.programId,
+        [
+            {
+                nonce,
+                oneSigId: DEFAULT_CONFIG.oneSigId,
+                targetOneSigAddress: oneSig.state.publicKey,
+                calls: [prepareSolanaCallDataForMerkleLeaf(oneSig, call)],
+            },
+        ],
+        clusterGenesisHash,
+    );
 
     // Create merkle tree and sign
     const merkleTree = makeOneSigTree([solanaGen]);
@@ -180,6 +189,7 @@ export async function createOneSigTransaction(
     call: SolanaCallData,
     expiryOffset = DEFAULT_CONFIG.expiryOffset,
 ) {
+    const oneSigState = await oneSig.getState(umi.rpc);
     // Use shared function to build merkle data
     const { merkleRoot, expiry, signatures, proof } = await buildOneSigMerkleData(
         oneSig,
@@ -188,15 +198,17 @@ export async function createOneSigTransaction(
         nonce,
         call,
         expiryOffset,
+        boundClusterGenesisHash(oneSigState),
     );
 
-    const oneSigState = await oneSig.getState(umi.rpc);
     const threshold = oneSigState.multisig.threshold;
 
     // Create execution instruction
     const ix = oneSig.executeTransaction(umi.payer, merkleRoot, {
         call,
         proof,
+        asExecutor: await isGatedExecutor(umi, oneSig),
+        withTargetPrograms: oneSigState.targetPolicy !== TargetPolicy.None,
         merkleRootVerification: some({
             expiry,
             signatures:
@@ -209,6 +221,23 @@ export async function createOneSigTransaction(
     return { ix, expiry, merkleRoot };
 }
 
+/**
+ * The genesis hash set on the OneSig with `setClusterGenesisHash`, which leaves must bind.
+ */
+export function boundClusterGenesisHash(state: OneSigStateWithSigners): Uint8Array | undefined {
+    const [genesisHash] = state.clusterGenesisHash;
+    return genesisHash.some((byte) => byte !== 0) ? genesisHash : undefined;
+}
+
+/**
+ * Whether the payer should pass its executor PDA: only when the executor gate applies and the
+ * payer is registered, so that unregistered payers still hit `ExecutorRequired`.
+ */
+export async function isGatedExecutor(umi: Umi, oneSig: OneSig): Promise<boolean> {
+    const { executorRequired } = await oneSig.getState(umi.rpc);
+    return executorRequired && (await oneSig.isExecutor(umi.rpc, umi.payer.publicKey));
+}
+
 /**
  * Performs a one-step execution flow, creating and executing
  * the transaction in a single operation
@@ -224,7 +253,7 @@ export async function performOneStepExecution(
     if (call.value > 0) {
         const transferInstruction = transferSol(ctx.umi, {
             source: createNoopSigner(ctx.umi.payer.publicKey),
-            destination: ctx.oneSig.pda.oneSigSigner()[0],
+            destination: ctx.oneSig.pda.vaultSigner(call.vaultIndex ?? 0)[0],
             amount: lamports(call.value),
         }).items[0];
         instructions.push(transferInstruction);
@@ -330,7 +359,7 @@ export async function executeWithVerifiedMerkleRoot(
     if (call.value > 0) {
         const transferInstruction = transferSol(ctx.umi, {
             source: createNoopSigner(ctx.payer.publicKey),
-            destination: ctx.oneSig.pda.oneSigSigner()[0],
+            destination: ctx.oneSig.pda.vaultSigner(call.vaultIndex ?? 0)[0],
             amount: lamports(call.value),
         }).items[0];
         instructions.push(transferInstruction);
@@ -340,6 +369,7 @@ export async function executeWithVerifiedMerkleRoot(
         call,
         proof,
         merkleRootVerification: null,
+        asExecutor: await isGatedExecutor(ctx.umi, ctx.oneSig),
     });
     instructions.push(executeInstruction);
 
@@ -489,7 +519,7 @@ export async function performSignerExecution(
     if (call.value > 0) {
         const transferInstruction = transferSol(ctx.umi, {
             source: createNoopSigner(delegate.publicKey),
-            destination: ctx.oneSig.pda.oneSigSigner()[0],
+            destination: ctx.oneSig.pda.vaultSigner(call.vaultIndex ?? 0)[0],
             amount: lamports(call.value),
         }).items[0];
         instructions.push(transferInstruction);
@@ -561,7 +591,7 @@ export async function performSignerExecutionTwoStep(
     if (call.value > 0) {
         const transferInstruction = transferSol(ctx.umi, {
             source: createNoopSigner(delegate.publicKey),
-            destination: ctx.oneSig.pda.oneSigSigner()[0],
+            destination: ctx.oneSig.pda.vaultSigner(call.vaultIndex ?? 0)[0],
             amount: lamports(call.value),
         }).items[0];
         instructions.push(transferInstruction);